@@ -0,0 +1,33 @@
+require('../../bootstrap').invokeWith(({ getInput, getBooleanInput, getMultilineInput }) => {
+    const args = [
+        'bump-dependency',
+
+        '--key',
+        getInput('key', { required: true }),
+
+        '--value',
+        getInput('value', { required: true }),
+    ]
+
+    if (getBooleanInput('require_clean_worktree')) {
+        args.push('--require-clean-worktree')
+    }
+
+    if (getInput('expected_branch')) {
+        args.push('--expected-branch', getInput('expected_branch'))
+    }
+
+    if (getBooleanInput('no_ignore')) {
+        args.push('--no-ignore')
+    }
+
+    if (getBooleanInput('allow_duplicates')) {
+        args.push('--allow-duplicates')
+    }
+
+    for (const changelogName of getMultilineInput('changelog_name')) {
+        args.push('--changelog-name', changelogName)
+    }
+
+    return args
+})
\ No newline at end of file
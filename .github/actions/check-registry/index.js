@@ -0,0 +1,25 @@
+require('../../bootstrap').invokeWith(({ getInput, getBooleanInput }) => {
+    const args = [
+        'check-registry',
+
+        '--bump',
+        getInput('bump', { required: true }),
+
+        '--scheme',
+        getInput('scheme'),
+    ]
+
+    if (getBooleanInput('require_previous')) {
+        args.push('--require-previous')
+    }
+
+    if (getBooleanInput('no_ignore')) {
+        args.push('--no-ignore')
+    }
+
+    if (getBooleanInput('allow_duplicates')) {
+        args.push('--allow-duplicates')
+    }
+
+    return args
+})
\ No newline at end of file
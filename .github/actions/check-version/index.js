@@ -0,0 +1,16 @@
+require('../../bootstrap').invokeWith(({ getInput }) => {
+    const args = [
+        'self',
+        'check-version',
+    ];
+
+    if (getInput('repository')) {
+        args.push('--repository', getInput('repository'))
+    }
+
+    if (getInput('github_token')) {
+        args.push('--github-token', getInput('github_token'))
+    }
+
+    return args
+})
\ No newline at end of file
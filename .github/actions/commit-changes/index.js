@@ -0,0 +1,43 @@
+require('../../bootstrap').invokeWith(({ getInput, getBooleanInput, getMultilineInput }) => {
+    const args = [
+        'commit-changes',
+
+        '--branch',
+        getInput('branch', { required: true }),
+
+        '--message',
+        getInput('message', { required: true }),
+
+        '--files',
+        getMultilineInput('files', { required: true }).join(','),
+    ]
+
+    // when unset, the repository and parent sha default to GITHUB_REPOSITORY and GITHUB_SHA
+    if (getInput('repository')) {
+        args.push('--repository', getInput('repository'))
+    }
+
+    if (getInput('parent_sha')) {
+        args.push('--parent-sha', getInput('parent_sha'))
+    }
+
+    if (getBooleanInput('create_branch')) {
+        args.push('--create-branch')
+    }
+
+    if (getBooleanInput('force')) {
+        args.push('--force')
+    }
+
+    if (getInput('token')) {
+        args.push('--github-token')
+        args.push(getInput('token'))
+    } else {
+        args.push('--app-id')
+        args.push(getInput('app_id'))
+        args.push('--app-private-key')
+        args.push(getInput('app_private_key'))
+    }
+
+    return args
+})
\ No newline at end of file
@@ -0,0 +1,26 @@
+require('../../bootstrap').invokeWith(({ getInput }) => {
+    const args = [
+        'create-buildpackage',
+
+        '--path',
+        getInput('path', { required: true }),
+
+        '--format',
+        getInput('format'),
+    ]
+
+    if (getInput('output')) {
+        args.push('--output', getInput('output'))
+    }
+
+    if (getInput('push')) {
+        args.push('--push', getInput('push'))
+    }
+
+    if (getInput('registry_username')) {
+        args.push('--registry-username', getInput('registry_username'))
+        args.push('--registry-password', getInput('registry_password', { required: true }))
+    }
+
+    return args
+})
\ No newline at end of file
@@ -0,0 +1,39 @@
+require('../../bootstrap').invokeWith(({ getInput, getBooleanInput, getMultilineInput }) => {
+    const args = [
+        'deprecate-buildpack',
+        '--id', getInput('id', { required: true }),
+        '--message', getInput('message', { required: true }),
+        '--eol-date', getInput('eol_date', { required: true }),
+        '--builder-pattern', getInput('builder_pattern'),
+    ];
+
+    if (getBooleanInput('remove_from_builders')) {
+        args.push('--remove-from-builders')
+    }
+
+    if (getBooleanInput('require_clean_worktree')) {
+        args.push('--require-clean-worktree')
+    }
+
+    if (getInput('expected_branch')) {
+        args.push('--expected-branch', getInput('expected_branch'))
+    }
+
+    if (getBooleanInput('no_ignore')) {
+        args.push('--no-ignore')
+    }
+
+    if (getBooleanInput('allow_duplicates')) {
+        args.push('--allow-duplicates')
+    }
+
+    for (const projectDir of getMultilineInput('project_dir')) {
+        args.push('--project-dir', projectDir)
+    }
+
+    for (const changelogName of getMultilineInput('changelog_name')) {
+        args.push('--changelog-name', changelogName)
+    }
+
+    return args
+})
\ No newline at end of file
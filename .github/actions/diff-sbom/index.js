@@ -0,0 +1,9 @@
+require('../../bootstrap').invokeWith(({ getInput }) => {
+    return [
+        'diff-sbom',
+        '--base',
+        getInput('base', { required: true }),
+        '--head',
+        getInput('head', { required: true }),
+    ]
+})
\ No newline at end of file
@@ -0,0 +1,31 @@
+require('../../bootstrap').invokeWith(({ getInput, getBooleanInput, getMultilineInput }) => {
+    const args = ['extract-changelog', '--version', getInput('version', { required: true })];
+
+    if (getInput('buildpack_id')) {
+        args.push('--buildpack-id', getInput('buildpack_id'))
+    }
+
+    args.push('--output-encoding', getInput('output_encoding'))
+
+    if (getInput('output_file')) {
+        args.push('--output-file', getInput('output_file'))
+    }
+
+    if (getBooleanInput('no_ignore')) {
+        args.push('--no-ignore')
+    }
+
+    if (getBooleanInput('allow_duplicates')) {
+        args.push('--allow-duplicates')
+    }
+
+    for (const projectDir of getMultilineInput('project_dir')) {
+        args.push('--project-dir', projectDir)
+    }
+
+    for (const changelogName of getMultilineInput('changelog_name')) {
+        args.push('--changelog-name', changelogName)
+    }
+
+    return args
+})
\ No newline at end of file
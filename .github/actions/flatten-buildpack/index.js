@@ -0,0 +1,26 @@
+require('../../bootstrap').invokeWith(({ getInput, getBooleanInput, getMultilineInput }) => {
+    const args = [
+        'flatten-buildpack',
+
+        '--buildpack-id',
+        getInput('buildpack_id', { required: true }),
+    ]
+
+    if (getBooleanInput('no_remote')) {
+        args.push('--no-remote')
+    }
+
+    if (getBooleanInput('no_ignore')) {
+        args.push('--no-ignore')
+    }
+
+    if (getBooleanInput('allow_duplicates')) {
+        args.push('--allow-duplicates')
+    }
+
+    for (const projectDir of getMultilineInput('project_dir')) {
+        args.push('--project-dir', projectDir)
+    }
+
+    return args
+})
\ No newline at end of file
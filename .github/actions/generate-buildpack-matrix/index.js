@@ -1,3 +1,46 @@
-require('../../bootstrap').invokeWith(() => {
-    return ['generate-buildpack-matrix']
+const { writeFileSync } = require('node:fs')
+const { join } = require('node:path')
+
+require('../../bootstrap').invokeWith(({ getInput, getBooleanInput, getMultilineInput }) => {
+    const args = [
+        'generate-buildpack-matrix',
+
+        '--output-encoding',
+        getInput('output_encoding'),
+    ]
+
+    if (getInput('output_file')) {
+        args.push('--output-file', getInput('output_file'))
+    }
+
+    if (getBooleanInput('pretty')) {
+        args.push('--pretty')
+    }
+
+    if (getInput('changed_since')) {
+        args.push('--changed-since', getInput('changed_since'), '--shared-paths-file', getInput('shared_paths_file'))
+    }
+
+    // inline plans are written to a file so they aren't limited by the command line length
+    if (getInput('publish_plan_file')) {
+        args.push('--publish-plan', getInput('publish_plan_file'))
+    } else if (getInput('publish_plan')) {
+        const publishPlanFile = join(process.env.RUNNER_TEMP, 'publish-plan.json')
+        writeFileSync(publishPlanFile, getInput('publish_plan'))
+        args.push('--publish-plan', publishPlanFile)
+    }
+
+    if (getBooleanInput('no_ignore')) {
+        args.push('--no-ignore')
+    }
+
+    if (getBooleanInput('allow_duplicates')) {
+        args.push('--allow-duplicates')
+    }
+
+    for (const projectDir of getMultilineInput('project_dir')) {
+        args.push('--project-dir', projectDir)
+    }
+
+    return args
 })
\ No newline at end of file
@@ -1,12 +1,53 @@
-require('../../bootstrap').invokeWith(({ getInput }) => {
+require('../../bootstrap').invokeWith(({ getInput, getBooleanInput, getMultilineInput }) => {
     const args = ['generate-changelog'];
 
-    if (getInput('unreleased')) {
+    if (getBooleanInput('list_versions')) {
+        args.push('--list-versions')
+    } else if (getInput('unreleased')) {
         args.push('--unreleased')
     } else if (getInput('version')) {
         args.push('--version')
         args.push(getInput('version'))
     }
 
+    if (!getBooleanInput('list_versions')) {
+        args.push('--format', getInput('format'))
+    }
+
+    if (getBooleanInput('lenient')) {
+        args.push('--lenient')
+    }
+
+    if (getInput('previous_sbom_dir') && getInput('current_sbom_dir')) {
+        args.push('--previous-sbom-dir', getInput('previous_sbom_dir'))
+        args.push('--current-sbom-dir', getInput('current_sbom_dir'))
+    }
+
+    if (getInput('locales_dir')) {
+        args.push('--locales-dir', getInput('locales_dir'))
+    }
+
+    args.push('--output-encoding', getInput('output_encoding'))
+
+    if (getInput('output_file')) {
+        args.push('--output-file', getInput('output_file'))
+    }
+
+    if (getBooleanInput('no_ignore')) {
+        args.push('--no-ignore')
+    }
+
+    if (getBooleanInput('allow_duplicates')) {
+        args.push('--allow-duplicates')
+    }
+
+    for (const projectDir of getMultilineInput('project_dir')) {
+        args.push('--project-dir', projectDir)
+    }
+
+    for (const changelogName of getMultilineInput('changelog_name')) {
+        args.push('--changelog-name', changelogName)
+    }
+
     return args
 })
\ No newline at end of file
@@ -0,0 +1,47 @@
+require('../../bootstrap').invokeWith(({ getInput, getBooleanInput, getMultilineInput }) => {
+    const args = [
+        'generate-release-checklist',
+
+        '--tag-template',
+        getInput('tag_template'),
+
+        '--remote',
+        getInput('remote'),
+    ]
+
+    if (getInput('version')) {
+        args.push('--version', getInput('version'))
+    }
+
+    if (getInput('builder_repository')) {
+        args.push(
+            '--builder-repository',
+            getInput('builder_repository'),
+            '--builders',
+            getMultilineInput('builders', { required: true }).join(','),
+            '--github-token',
+            getInput('token', { required: true }),
+        )
+        if (getInput('builder_ref')) {
+            args.push('--builder-ref', getInput('builder_ref'))
+        }
+    }
+
+    if (getBooleanInput('no_ignore')) {
+        args.push('--no-ignore')
+    }
+
+    if (getBooleanInput('allow_duplicates')) {
+        args.push('--allow-duplicates')
+    }
+
+    for (const projectDir of getMultilineInput('project_dir')) {
+        args.push('--project-dir', projectDir)
+    }
+
+    for (const changelogName of getMultilineInput('changelog_name')) {
+        args.push('--changelog-name', changelogName)
+    }
+
+    return args
+})
\ No newline at end of file
@@ -0,0 +1,14 @@
+require('../../bootstrap').invokeWith(({ getInput }) => {
+    return [
+        'generate-token',
+
+        '--app-id',
+        getInput('app_id', { required: true }),
+
+        '--private-key',
+        getInput('private_key', { required: true }),
+
+        '--repository',
+        getInput('repository', { required: true }),
+    ]
+})
\ No newline at end of file
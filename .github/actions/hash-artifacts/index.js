@@ -0,0 +1,13 @@
+require('../../bootstrap').invokeWith(({ getInput, getMultilineInput }) => {
+    const args = ['hash-artifacts']
+
+    for (const artifact of getMultilineInput('artifacts', { required: true })) {
+        args.push('--artifact', artifact)
+    }
+
+    if (getInput('sums_file')) {
+        args.push('--sums-file', getInput('sums_file'))
+    }
+
+    return args
+})
\ No newline at end of file
@@ -0,0 +1,29 @@
+require('../../bootstrap').invokeWith(({ getInput, getBooleanInput, getMultilineInput }) => {
+    const args = ['lint-changelogs']
+
+    if (getBooleanInput('require_entries')) {
+        args.push('--require-entries')
+    }
+
+    if (getInput('config')) {
+        args.push('--config', getInput('config'))
+    }
+
+    if (getBooleanInput('fix')) {
+        args.push('--fix')
+    }
+
+    if (getBooleanInput('no_ignore')) {
+        args.push('--no-ignore')
+    }
+
+    if (getBooleanInput('allow_duplicates')) {
+        args.push('--allow-duplicates')
+    }
+
+    for (const changelogName of getMultilineInput('changelog_name')) {
+        args.push('--changelog-name', changelogName)
+    }
+
+    return args
+})
\ No newline at end of file
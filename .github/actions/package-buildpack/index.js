@@ -0,0 +1,17 @@
+require('../../bootstrap').invokeWith(({ getInput, getBooleanInput }) => {
+    const args = [
+        'package-buildpack',
+
+        '--path',
+        getInput('path'),
+
+        '--target',
+        getInput('target'),
+    ]
+
+    if (getBooleanInput('release')) {
+        args.push('--release')
+    }
+
+    return args
+})
\ No newline at end of file
@@ -0,0 +1,30 @@
+require('../../bootstrap').invokeWith(({ getInput, getBooleanInput, getMultilineInput }) => {
+    const args = [
+        'plan-release',
+
+        '--output-encoding',
+        getInput('output_encoding'),
+    ]
+
+    if (getInput('output_file')) {
+        args.push('--output-file', getInput('output_file'))
+    }
+
+    if (getBooleanInput('pretty')) {
+        args.push('--pretty')
+    }
+
+    if (getBooleanInput('no_ignore')) {
+        args.push('--no-ignore')
+    }
+
+    if (getBooleanInput('allow_duplicates')) {
+        args.push('--allow-duplicates')
+    }
+
+    for (const projectDir of getMultilineInput('project_dir')) {
+        args.push('--project-dir', projectDir)
+    }
+
+    return args
+})
\ No newline at end of file
@@ -0,0 +1,41 @@
+const { writeFileSync } = require('node:fs')
+const { join } = require('node:path')
+
+require('../../bootstrap').invokeWith(({ getInput }) => {
+    const args = [
+        'post-pr-comment',
+
+        '--marker',
+        getInput('marker'),
+    ]
+
+    // inline bodies are written to a file so they aren't limited by the command line length
+    if (getInput('body_file')) {
+        args.push('--body-file', getInput('body_file'))
+    } else {
+        const bodyFile = join(process.env.RUNNER_TEMP, 'pr-comment-body.md')
+        writeFileSync(bodyFile, getInput('body', { required: true }))
+        args.push('--body-file', bodyFile)
+    }
+
+    // when unset, the repository and pull request default to GITHUB_REPOSITORY and the triggering event
+    if (getInput('repository')) {
+        args.push('--repository', getInput('repository'))
+    }
+
+    if (getInput('pull_request')) {
+        args.push('--pull-request', getInput('pull_request'))
+    }
+
+    if (getInput('token')) {
+        args.push('--github-token')
+        args.push(getInput('token'))
+    } else {
+        args.push('--app-id')
+        args.push(getInput('app_id'))
+        args.push('--app-private-key')
+        args.push(getInput('app_private_key'))
+    }
+
+    return args
+})
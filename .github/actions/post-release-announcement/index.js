@@ -0,0 +1,56 @@
+const { writeFileSync } = require('node:fs')
+const { join } = require('node:path')
+
+require('../../bootstrap').invokeWith(({ getInput, getBooleanInput }) => {
+    const args = [
+        'post-release-announcement',
+
+        '--version',
+        getInput('version', { required: true }),
+
+        '--title-template',
+        getInput('title_template'),
+
+        '--tag-template',
+        getInput('tag_template'),
+    ]
+
+    // inline notes are written to a file so they aren't limited by the command line length
+    if (getInput('notes_file')) {
+        args.push('--notes-file', getInput('notes_file'))
+    } else {
+        const notesFile = join(process.env.RUNNER_TEMP, 'release-announcement-notes.md')
+        writeFileSync(notesFile, getInput('notes', { required: true }))
+        args.push('--notes-file', notesFile)
+    }
+
+    if (getInput('previous_version')) {
+        args.push('--previous-version', getInput('previous_version'))
+    }
+
+    if (getInput('date')) {
+        args.push('--date', getInput('date'))
+    }
+
+    if (getBooleanInput('issue')) {
+        args.push('--issue')
+    } else {
+        args.push('--category', getInput('category'))
+    }
+
+    if (getInput('repository')) {
+        args.push('--repository', getInput('repository'))
+    }
+
+    if (getInput('token')) {
+        args.push('--github-token')
+        args.push(getInput('token'))
+    } else {
+        args.push('--app-id')
+        args.push(getInput('app_id'))
+        args.push('--app-private-key')
+        args.push(getInput('app_private_key'))
+    }
+
+    return args
+})
\ No newline at end of file
@@ -1,11 +1,84 @@
-require('../../bootstrap').invokeWith(({ getInput }) => {
-    return [
+require('../../bootstrap').invokeWith(({ getInput, getBooleanInput, getMultilineInput }) => {
+    const args = [
         'prepare-release',
         
         '--bump',
         getInput('bump', { required: true }),
 
         '--repository-url',
-        getInput('repository_url')
+        getInput('repository_url'),
+
+        '--scheme',
+        getInput('scheme'),
+
+        '--tag-template',
+        getInput('tag_template'),
+
+        '--date-format',
+        getInput('date_format'),
+
+        '--commit-message-template',
+        getInput('commit_message_template'),
+
+        '--pr-title-template',
+        getInput('pr_title_template'),
     ]
+
+    for (const buildpackId of getMultilineInput('only')) {
+        args.push('--only', buildpackId)
+    }
+
+    for (const buildpackId of getMultilineInput('exclude')) {
+        args.push('--exclude', buildpackId)
+    }
+
+    if (getBooleanInput('normalize_entries')) {
+        args.push('--normalize-entries')
+    }
+
+    if (getInput('heading_anchor_template')) {
+        args.push('--heading-anchor-template', getInput('heading_anchor_template'))
+    }
+
+    if (getInput('release_date')) {
+        args.push('--release-date', getInput('release_date'))
+    }
+
+    if (getInput('root_changelog')) {
+        args.push('--root-changelog', getInput('root_changelog'))
+    }
+
+    if (getInput('version_file')) {
+        args.push('--version-file', getInput('version_file'))
+    }
+
+    if (getBooleanInput('force')) {
+        args.push('--force')
+    }
+
+    if (getBooleanInput('require_clean_worktree')) {
+        args.push('--require-clean-worktree')
+    }
+
+    if (getInput('expected_branch')) {
+        args.push('--expected-branch', getInput('expected_branch'))
+    }
+
+    if (getBooleanInput('no_ignore')) {
+        args.push('--no-ignore')
+    }
+
+    if (getBooleanInput('allow_duplicates')) {
+        args.push('--allow-duplicates')
+    }
+
+    for (const projectDir of getMultilineInput('project_dir')) {
+        args.push('--project-dir', projectDir)
+    }
+
+    for (const changelogName of getMultilineInput('changelog_name')) {
+        args.push('--changelog-name', changelogName)
+    }
+
+    return args
 })
\ No newline at end of file
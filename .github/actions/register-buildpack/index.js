@@ -0,0 +1,36 @@
+require('../../bootstrap').invokeWith(({ getInput, getBooleanInput }) => {
+    const args = [
+        'register-buildpack',
+
+        '--buildpack-id',
+        getInput('buildpack_id', { required: true }),
+
+        '--version',
+        getInput('version', { required: true }),
+
+        '--address',
+        getInput('address', { required: true }),
+
+        '--registry-index',
+        getInput('registry_index'),
+    ]
+
+    if (getBooleanInput('wait')) {
+        args.push('--wait-timeout', getInput('wait_timeout'))
+        args.push('--poll-interval', getInput('poll_interval'))
+    } else {
+        args.push('--no-wait')
+    }
+
+    if (getInput('token')) {
+        args.push('--github-token')
+        args.push(getInput('token'))
+    } else {
+        args.push('--app-id')
+        args.push(getInput('app_id'))
+        args.push('--app-private-key')
+        args.push(getInput('app_private_key'))
+    }
+
+    return args
+})
\ No newline at end of file
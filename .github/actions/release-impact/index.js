@@ -0,0 +1,47 @@
+require('../../bootstrap').invokeWith(({ getInput, getBooleanInput, getMultilineInput }) => {
+    const args = [
+        'release-impact',
+
+        '--bump',
+        getInput('bump', { required: true }),
+
+        '--scheme',
+        getInput('scheme'),
+
+        '--builder-pattern',
+        getInput('builder_pattern'),
+
+        '--shared-paths-file',
+        getInput('shared_paths_file'),
+    ]
+
+    for (const buildpackId of getMultilineInput('only')) {
+        args.push('--only', buildpackId)
+    }
+
+    for (const buildpackId of getMultilineInput('exclude')) {
+        args.push('--exclude', buildpackId)
+    }
+
+    if (getInput('release_date')) {
+        args.push('--release-date', getInput('release_date'))
+    }
+
+    if (getInput('builder_path')) {
+        args.push('--builder-path', getInput('builder_path'))
+    }
+
+    if (getBooleanInput('no_ignore')) {
+        args.push('--no-ignore')
+    }
+
+    if (getBooleanInput('allow_duplicates')) {
+        args.push('--allow-duplicates')
+    }
+
+    for (const changelogName of getMultilineInput('changelog_name')) {
+        args.push('--changelog-name', changelogName)
+    }
+
+    return args
+})
@@ -0,0 +1,43 @@
+require('../../bootstrap').invokeWith(({ getInput, getBooleanInput, getMultilineInput }) => {
+    const args = [
+        'set-buildpack-targets',
+    ];
+
+    for (const target of getMultilineInput('targets', { required: true })) {
+        args.push('--target', target)
+    }
+
+    for (const distro of getMultilineInput('distros')) {
+        args.push('--distro', distro)
+    }
+
+    for (const buildpackId of getMultilineInput('only')) {
+        args.push('--only', buildpackId)
+    }
+
+    for (const buildpackId of getMultilineInput('exclude')) {
+        args.push('--exclude', buildpackId)
+    }
+
+    if (getBooleanInput('require_clean_worktree')) {
+        args.push('--require-clean-worktree')
+    }
+
+    if (getInput('expected_branch')) {
+        args.push('--expected-branch', getInput('expected_branch'))
+    }
+
+    if (getBooleanInput('no_ignore')) {
+        args.push('--no-ignore')
+    }
+
+    if (getBooleanInput('allow_duplicates')) {
+        args.push('--allow-duplicates')
+    }
+
+    for (const projectDir of getMultilineInput('project_dir')) {
+        args.push('--project-dir', projectDir)
+    }
+
+    return args
+})
\ No newline at end of file
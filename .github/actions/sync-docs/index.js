@@ -0,0 +1,19 @@
+require('../../bootstrap').invokeWith(({ getBooleanInput, getMultilineInput }) => {
+    const args = ['sync-docs'];
+
+    const files = getMultilineInput('files')
+    if (files.length > 0) {
+        args.push('--files')
+        args.push(files.join(','))
+    }
+
+    if (getBooleanInput('no_ignore')) {
+        args.push('--no-ignore')
+    }
+
+    if (getBooleanInput('allow_duplicates')) {
+        args.push('--allow-duplicates')
+    }
+
+    return args
+})
\ No newline at end of file
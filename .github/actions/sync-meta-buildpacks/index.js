@@ -0,0 +1,25 @@
+require('../../bootstrap').invokeWith(({ getInput, getBooleanInput }) => {
+    const args = ['experimental', 'sync-meta-buildpacks'];
+
+    if (getBooleanInput('check')) {
+        args.push('--check')
+    }
+
+    if (getBooleanInput('require_clean_worktree')) {
+        args.push('--require-clean-worktree')
+    }
+
+    if (getInput('expected_branch')) {
+        args.push('--expected-branch', getInput('expected_branch'))
+    }
+
+    if (getBooleanInput('no_ignore')) {
+        args.push('--no-ignore')
+    }
+
+    if (getBooleanInput('allow_duplicates')) {
+        args.push('--allow-duplicates')
+    }
+
+    return args
+})
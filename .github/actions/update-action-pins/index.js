@@ -0,0 +1,29 @@
+require('../../bootstrap').invokeWith(({ getInput, getBooleanInput, getMultilineInput }) => {
+    const args = [
+        'update-action-pins',
+
+        '--action-version',
+        getInput('version', { required: true }),
+
+        '--action-repository',
+        getInput('action_repository'),
+    ]
+
+    if (getInput('sha')) {
+        args.push('--sha', getInput('sha'))
+    }
+
+    for (const workflowPattern of getMultilineInput('workflow_pattern')) {
+        args.push('--workflow-pattern', workflowPattern)
+    }
+
+    if (getBooleanInput('require_clean_worktree')) {
+        args.push('--require-clean-worktree')
+    }
+
+    if (getInput('expected_branch')) {
+        args.push('--expected-branch', getInput('expected_branch'))
+    }
+
+    return args
+})
\ No newline at end of file
@@ -0,0 +1,62 @@
+require('../../bootstrap').invokeWith(({ getInput, getBooleanInput, getMultilineInput }) => {
+    const args = [
+        'update-builder-remote',
+
+        '--repository',
+        getInput('repository', { required: true }),
+
+        '--buildpack-id',
+        getInput('buildpack_id', { required: true }),
+
+        '--buildpack-version',
+        getInput('buildpack_version', { required: true }),
+
+        '--builders',
+        getMultilineInput('builders', { required: true }).join(','),
+    ]
+
+    if (getInput('buildpack_uri_template')) {
+        args.push('--buildpack-uri-template', getInput('buildpack_uri_template'))
+        if (getInput('buildpack_digest')) {
+            args.push('--buildpack-digest', getInput('buildpack_digest'))
+        }
+    } else {
+        args.push('--buildpack-uri', getInput('buildpack_uri', { required: true }))
+    }
+
+    if (getInput('base_branch')) {
+        args.push('--base-branch', getInput('base_branch'))
+    }
+
+    if (getInput('branch')) {
+        args.push('--branch', getInput('branch'))
+    }
+
+    if (getInput('title')) {
+        args.push('--title', getInput('title'))
+    }
+
+    if (getBooleanInput('fail_on_no_match')) {
+        args.push('--fail-on-no-match')
+    }
+
+    if (getBooleanInput('allow_downgrade')) {
+        args.push('--allow-downgrade')
+    }
+
+    if (getInput('optional')) {
+        args.push('--optional', String(getBooleanInput('optional')))
+    }
+
+    if (getInput('token')) {
+        args.push('--github-token')
+        args.push(getInput('token'))
+    } else {
+        args.push('--app-id')
+        args.push(getInput('app_id'))
+        args.push('--app-private-key')
+        args.push(getInput('app_private_key'))
+    }
+
+    return args
+})
\ No newline at end of file
@@ -1,23 +1,95 @@
-require('../../bootstrap').invokeWith(({ getInput }) => {
-    return [
+require('../../bootstrap').invokeWith(({ getInput, getBooleanInput, getMultilineInput }) => {
+    const args = [
         'update-builder',
 
         '--path',
         getInput('path', { required: true }),
+    ]
 
-        '--buildpack-id',
-        getInput('buildpack_id', { required: true }),
+    if (getInput('manifest')) {
+        args.push('--manifest', getInput('manifest'))
+    } else {
+        args.push(
+            '--buildpack-id',
+            getInput('buildpack_id', { required: true }),
+            '--buildpack-version',
+            getInput('buildpack_version', { required: true }),
+        )
+    }
 
-        '--buildpack-version',
-        getInput('buildpack_version', { required: true }),
+    if (getInput('manifest') || getInput('buildpack_uri_template')) {
+        args.push('--buildpack-uri-template', getInput('buildpack_uri_template', { required: true }))
+        if (getInput('buildpack_digest')) {
+            args.push('--buildpack-digest', getInput('buildpack_digest'))
+        }
+    } else {
+        args.push('--buildpack-uri', getInput('buildpack_uri', { required: true }))
+    }
 
-        '--buildpack-uri',
-        getInput('buildpack_uri', { required: true }),
+    if (getInput('channel')) {
+        args.push('--channel', getInput('channel'), '--channels-file', getInput('channels_file'))
+    }
 
-        '--builders',
-        getInput('builders', { required: true })
-            .split('\n')
-            .map(v => v.trim())
-            .join(','),
-    ]
+    if (getBooleanInput('all_builders')) {
+        args.push('--all-builders', '--builder-pattern', getInput('builder_pattern'))
+    } else if (getInput('builders') || !getInput('channel')) {
+        args.push(
+            '--builders',
+            getInput('builders', { required: true })
+                .split('\n')
+                .map(v => v.trim())
+                .join(','),
+        )
+    }
+
+    if (getBooleanInput('verify_image')) {
+        args.push('--verify-image')
+    }
+
+    if (getBooleanInput('pin_digests')) {
+        args.push('--pin-digests', '--registry-concurrency', getInput('registry_concurrency'))
+        if (getInput('digest_cache')) {
+            args.push('--digest-cache', getInput('digest_cache'))
+        }
+    }
+
+    if (getBooleanInput('fail_on_no_match')) {
+        args.push('--fail-on-no-match')
+    }
+
+    if (getBooleanInput('allow_downgrade')) {
+        args.push('--allow-downgrade')
+    }
+
+    if (getInput('optional')) {
+        args.push('--optional', String(getBooleanInput('optional')))
+    }
+
+    if (getBooleanInput('create_entry')) {
+        args.push('--create-entry')
+        if (getInput('order_index')) {
+            args.push('--order-index', getInput('order_index'))
+        }
+    }
+
+    if (getBooleanInput('update_timestamp')) {
+        args.push('--update-timestamp')
+    }
+
+    if (getBooleanInput('update_changelog')) {
+        args.push('--update-changelog')
+        for (const changelogName of getMultilineInput('changelog_name')) {
+            args.push('--changelog-name', changelogName)
+        }
+    }
+
+    if (getBooleanInput('require_clean_worktree')) {
+        args.push('--require-clean-worktree')
+    }
+
+    if (getInput('expected_branch')) {
+        args.push('--expected-branch', getInput('expected_branch'))
+    }
+
+    return args
 })
\ No newline at end of file
@@ -0,0 +1,17 @@
+require('../../bootstrap').invokeWith(({ getInput, getBooleanInput }) => {
+    const args = ['validate-builders']
+
+    if (getInput('config')) {
+        args.push('--config', getInput('config'))
+    }
+
+    if (getInput('builder_pattern')) {
+        args.push('--builder-pattern', getInput('builder_pattern'))
+    }
+
+    if (getBooleanInput('fix')) {
+        args.push('--fix', '--registry-concurrency', getInput('registry_concurrency'))
+    }
+
+    return args
+})
\ No newline at end of file
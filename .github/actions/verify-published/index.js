@@ -0,0 +1,14 @@
+require('../../bootstrap').invokeWith(({ getInput, getMultilineInput }) => {
+    return [
+        'verify-published',
+
+        '--buildpack-id',
+        getInput('buildpack_id', { required: true }),
+
+        '--version',
+        getInput('version', { required: true }),
+
+        '--registries',
+        getMultilineInput('registries', { required: true }).join(','),
+    ]
+})
\ No newline at end of file
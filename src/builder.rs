@@ -0,0 +1,1978 @@
+use crate::cancellation::{check_cancelled, CancellationError};
+use crate::debug_artifacts::capture_toml_failure;
+use crate::log;
+use crate::oci::{resolve_digests, DigestCache, ImageReference, OciError};
+use crate::patch::{apply_patches, quote_like, scan_toml_tables, Patch, PatchError};
+use crate::progress::{track, ProgressStep};
+use clap::ValueEnum;
+use lazy_static::lazy_static;
+use libcnb_data::buildpack::{BuildpackId, BuildpackVersion};
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use toml_edit::{value, ArrayOfTables, Decor, Document, Item, RawString, Table, Value};
+use uriparse::URIReference;
+
+type Result<T> = std::result::Result<T, BuilderFileError>;
+
+pub struct BuilderFile {
+    pub path: PathBuf,
+    pub document: Document,
+}
+
+pub fn read_builder_file(path: PathBuf) -> Result<BuilderFile> {
+    let contents =
+        std::fs::read_to_string(&path).map_err(|e| BuilderFileError::Reading(path.clone(), e))?;
+    parse_builder_file(path, &contents)
+}
+
+// for builder files that don't come from disk, e.g.; contents fetched from another repository
+pub fn parse_builder_file(path: PathBuf, contents: &str) -> Result<BuilderFile> {
+    track(ProgressStep::Parse, path.display(), || {
+        Document::from_str(contents).map_err(|e| {
+            capture_toml_failure(&path, contents, &e);
+            BuilderFileError::Parsing(path.clone(), e)
+        })
+    })
+    .map(|document| BuilderFile { path, document })
+}
+
+pub fn builder_references_buildpack(
+    builder_file: &BuilderFile,
+    buildpack_id: &BuildpackId,
+) -> bool {
+    let matches_id = |table: &Table| matches_buildpack_id(table, buildpack_id);
+
+    let in_buildpacks = builder_file
+        .document
+        .get("buildpacks")
+        .and_then(|value| value.as_array_of_tables())
+        .map(|buildpacks| buildpacks.iter().any(matches_id))
+        .unwrap_or(false);
+
+    let in_order = builder_file
+        .document
+        .get("order")
+        .and_then(|value| value.as_array_of_tables())
+        .map(|orders| {
+            orders.iter().any(|order| {
+                order
+                    .get("group")
+                    .and_then(|value| value.as_array_of_tables())
+                    .map(|groups| groups.iter().any(matches_id))
+                    .unwrap_or(false)
+            })
+        })
+        .unwrap_or(false);
+
+    in_buildpacks || in_order
+}
+
+// every `uri` and `version` edit is collected as a patch against the serialized builder and the
+// patches are applied together, so no edit is made against offsets an earlier edit has shifted and
+// the quoting and trailing comments of each value are kept
+pub fn update_builder_contents_with_buildpack(
+    builder_file: &mut BuilderFile,
+    buildpack_id: &BuildpackId,
+    buildpack_version: &BuildpackVersion,
+    buildpack_uri: &URIReference,
+) -> Result<Option<String>> {
+    let references = count_buildpack_references(builder_file, buildpack_id)?;
+    if references == 0 {
+        return Ok(None);
+    }
+
+    let contents = builder_file.document.to_string();
+    let patches = buildpack_value_patches(
+        &contents,
+        buildpack_id,
+        &buildpack_version.to_string(),
+        &buildpack_uri.to_string(),
+    );
+
+    // entries the scan can't locate (e.g.; inline tables or a missing `uri` key) are edited through
+    // the document instead
+    let updated = match patches {
+        Some(patches) if patches.len() == references => apply_patches(&contents, patches)
+            .map_err(|e| BuilderFileError::Patching(builder_file.path.clone(), e))?,
+        _ => {
+            update_builder_document_with_buildpack(
+                builder_file,
+                buildpack_id,
+                buildpack_version,
+                buildpack_uri,
+            );
+            builder_file.document.to_string()
+        }
+    };
+
+    builder_file.document = Document::from_str(&updated)
+        .map_err(|e| BuilderFileError::Parsing(builder_file.path.clone(), e))?;
+
+    Ok(Some(updated))
+}
+
+fn matches_buildpack_id(table: &Table, buildpack_id: &BuildpackId) -> bool {
+    table
+        .get("id")
+        .and_then(|item| item.as_str())
+        .filter(|value| value == &buildpack_id.as_str())
+        .is_some()
+}
+
+// the number of `[[buildpacks]]` and `[[order.group]]` entries for the buildpack, which is the number
+// of values an update has to edit
+fn count_buildpack_references(
+    builder_file: &BuilderFile,
+    buildpack_id: &BuildpackId,
+) -> Result<usize> {
+    let mut references = builder_file
+        .document
+        .get("buildpacks")
+        .and_then(|value| value.as_array_of_tables())
+        .map(|buildpacks| {
+            buildpacks
+                .iter()
+                .filter(|buildpack| matches_buildpack_id(buildpack, buildpack_id))
+                .count()
+        })
+        .unwrap_or_default();
+
+    let order_list = builder_file
+        .document
+        .get("order")
+        .and_then(|value| value.as_array_of_tables())
+        .ok_or(BuilderFileError::MissingRequiredKey(
+            builder_file.path.clone(),
+            "order".to_string(),
+        ))?;
+
+    for order in order_list.iter() {
+        let group_list = order
+            .get("group")
+            .and_then(|value| value.as_array_of_tables())
+            .ok_or(BuilderFileError::MissingRequiredKey(
+                builder_file.path.clone(),
+                "group".to_string(),
+            ))?;
+        references += group_list
+            .iter()
+            .filter(|group| matches_buildpack_id(group, buildpack_id))
+            .count();
+    }
+
+    Ok(references)
+}
+
+// `None` if an entry for the buildpack is missing the key to edit
+fn buildpack_value_patches(
+    contents: &str,
+    buildpack_id: &BuildpackId,
+    buildpack_version: &str,
+    buildpack_uri: &str,
+) -> Option<Vec<Patch>> {
+    scan_toml_tables(contents)
+        .into_iter()
+        .filter(|table| {
+            table.is_array && (table.name == "buildpacks" || table.name == "order.group")
+        })
+        .filter(|table| table.get_str(contents, "id").as_deref() == Some(buildpack_id.as_str()))
+        .map(|table| {
+            let (key, new_value) = if table.name == "buildpacks" {
+                ("uri", buildpack_uri)
+            } else {
+                ("version", buildpack_version)
+            };
+            table.values.get(key).map(|span| {
+                Patch::replace(span.clone(), quote_like(&contents[span.clone()], new_value))
+            })
+        })
+        .collect()
+}
+
+fn update_builder_document_with_buildpack(
+    builder_file: &mut BuilderFile,
+    buildpack_id: &BuildpackId,
+    buildpack_version: &BuildpackVersion,
+    buildpack_uri: &URIReference,
+) {
+    builder_file
+        .document
+        .get_mut("buildpacks")
+        .and_then(|value| value.as_array_of_tables_mut())
+        .unwrap_or(&mut ArrayOfTables::default())
+        .iter_mut()
+        .filter(|buildpack| matches_buildpack_id(buildpack, buildpack_id))
+        .for_each(|buildpack| buildpack["uri"] = value(buildpack_uri.to_string()));
+
+    builder_file
+        .document
+        .get_mut("order")
+        .and_then(|value| value.as_array_of_tables_mut())
+        .unwrap_or(&mut ArrayOfTables::default())
+        .iter_mut()
+        .filter_map(|order| {
+            order
+                .get_mut("group")
+                .and_then(|value| value.as_array_of_tables_mut())
+        })
+        .flat_map(|groups| groups.iter_mut())
+        .filter(|group| matches_buildpack_id(group, buildpack_id))
+        .for_each(|group| group["version"] = value(buildpack_version.to_string()));
+}
+
+// the versions currently pinned for the buildpack in each `[[order.group]]` that references it
+pub fn get_builder_buildpack_versions(
+    builder_file: &BuilderFile,
+    buildpack_id: &BuildpackId,
+) -> Vec<String> {
+    builder_file
+        .document
+        .get("order")
+        .and_then(|value| value.as_array_of_tables())
+        .map(|orders| {
+            orders
+                .iter()
+                .filter_map(|order| {
+                    order
+                        .get("group")
+                        .and_then(|value| value.as_array_of_tables())
+                })
+                .flat_map(|groups| groups.iter())
+                .filter(|group| matches_buildpack_id(group, buildpack_id))
+                .filter_map(|group| group.get("version").and_then(|item| item.as_str()))
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn get_builder_buildpack_uris(builder_file: &BuilderFile) -> Vec<String> {
+    builder_file
+        .document
+        .get("buildpacks")
+        .and_then(|value| value.as_array_of_tables())
+        .map(|buildpacks| {
+            buildpacks
+                .iter()
+                .filter_map(|buildpack| buildpack.get("uri").and_then(|item| item.as_str()))
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// replaces each `[[buildpacks]]` uri found in `pinned_uris` with its pinned value
+pub fn pin_builder_buildpack_uris(
+    builder_file: &mut BuilderFile,
+    pinned_uris: &HashMap<String, String>,
+) -> bool {
+    let mut pinned = false;
+
+    builder_file
+        .document
+        .get_mut("buildpacks")
+        .and_then(|value| value.as_array_of_tables_mut())
+        .unwrap_or(&mut toml_edit::ArrayOfTables::default())
+        .iter_mut()
+        .for_each(|buildpack| {
+            let pinned_uri = buildpack
+                .get("uri")
+                .and_then(|item| item.as_str())
+                .and_then(|uri| pinned_uris.get(uri))
+                .cloned();
+            if let Some(pinned_uri) = pinned_uri {
+                buildpack["uri"] = value(pinned_uri);
+                pinned = true;
+            }
+        });
+
+    pinned
+}
+
+// resolves every tag-based `docker://` uri across the builders once, concurrently, and pins each
+// builder that uses it to the digest the tag currently points at
+pub fn pin_builder_buildpack_digests(
+    builder_files: &mut [BuilderFile],
+    uris: Vec<String>,
+    concurrency: usize,
+    cache: &DigestCache,
+) -> std::result::Result<(), PinDigestsError> {
+    let mut uris = uris
+        .into_iter()
+        .filter(|uri| uri.starts_with("docker://"))
+        .collect::<Vec<_>>();
+    uris.sort();
+    uris.dedup();
+
+    let images = uris
+        .iter()
+        .map(|uri| {
+            ImageReference::try_from(uri.as_str())
+                .map_err(|e| PinDigestsError::ResolvingDigest(uri.clone(), e))
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?
+        .into_iter()
+        .zip(uris)
+        .filter(|(image, _)| !image.is_digest())
+        .collect::<Vec<_>>();
+
+    if images.is_empty() {
+        return Ok(());
+    }
+
+    let references = images
+        .iter()
+        .map(|(image, _)| image.clone())
+        .collect::<Vec<_>>();
+
+    let digests = resolve_digests(&references, concurrency, cache);
+
+    // lookups skipped after a cancellation would otherwise be reported as failed digests
+    check_cancelled().map_err(PinDigestsError::Cancelled)?;
+
+    let mut pinned_uris = HashMap::new();
+    for ((image, uri), digest) in images.into_iter().zip(digests) {
+        let digest = digest.map_err(|e| PinDigestsError::ResolvingDigest(uri.clone(), e))?;
+        pinned_uris.insert(uri, format!("docker://{}", image.with_digest(&digest)));
+    }
+
+    for builder_file in builder_files {
+        if pin_builder_buildpack_uris(builder_file, &pinned_uris) {
+            log!(
+                path = builder_file.path;
+                "📌 Pinned image digests for builder: {}",
+                builder_file.path.display()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+// sets `optional` on each `[[order.group]]` entry for the buildpack, an existing key keeps its
+// surrounding whitespace and comments, and a new key is indented like the entry's other keys
+pub fn set_builder_buildpack_optional(
+    builder_file: &mut BuilderFile,
+    buildpack_id: &BuildpackId,
+    optional: bool,
+) -> bool {
+    let mut changed = false;
+
+    builder_file
+        .document
+        .get_mut("order")
+        .and_then(|value| value.as_array_of_tables_mut())
+        .unwrap_or(&mut ArrayOfTables::default())
+        .iter_mut()
+        .filter_map(|order| {
+            order
+                .get_mut("group")
+                .and_then(|value| value.as_array_of_tables_mut())
+        })
+        .flat_map(|groups| groups.iter_mut())
+        .filter(|group| matches_buildpack_id(group, buildpack_id))
+        .for_each(|group| {
+            match group
+                .get_mut("optional")
+                .and_then(|item| item.as_value_mut())
+            {
+                Some(existing) if existing.as_bool() == Some(optional) => {}
+                Some(existing) => {
+                    let decor = existing.decor().clone();
+                    *existing = Value::from(optional);
+                    *existing.decor_mut() = decor;
+                    changed = true;
+                }
+                None => {
+                    let key_decor = group
+                        .iter()
+                        .next()
+                        .and_then(|(key, _)| group.key_decor(key))
+                        .cloned();
+                    group["optional"] = value(optional);
+                    if let (Some(decor), Some(key_decor)) =
+                        (key_decor, group.key_decor_mut("optional"))
+                    {
+                        *key_decor = decor;
+                    }
+                    changed = true;
+                }
+            }
+        });
+
+    changed
+}
+
+// records when the builder last changed as `last_updated` under `[metadata]`, an existing value keeps
+// its surrounding whitespace and comments
+pub fn set_builder_last_updated(builder_file: &mut BuilderFile, timestamp: &str) -> Result<()> {
+    let metadata = builder_file
+        .document
+        .entry("metadata")
+        .or_insert_with(|| Item::Table(Table::new()))
+        .as_table_mut()
+        .ok_or_else(|| {
+            BuilderFileError::InvalidTable(builder_file.path.clone(), "metadata".to_string())
+        })?;
+
+    match metadata
+        .get_mut("last_updated")
+        .and_then(|item| item.as_value_mut())
+    {
+        Some(existing) => {
+            let decor = existing.decor().clone();
+            *existing = Value::from(timestamp);
+            *existing.decor_mut() = decor;
+        }
+        None => {
+            let key_decor = metadata
+                .iter()
+                .next()
+                .and_then(|(key, _)| metadata.key_decor(key))
+                .cloned();
+            metadata["last_updated"] = value(timestamp);
+            if let (Some(decor), Some(key_decor)) =
+                (key_decor, metadata.key_decor_mut("last_updated"))
+            {
+                *key_decor = decor;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// removes the buildpack's `[[buildpacks]]` and `[[order.group]]` entries, an `[[order]]` left without
+// any groups is removed as well
+pub fn remove_buildpack_from_builder(
+    builder_file: &mut BuilderFile,
+    buildpack_id: &BuildpackId,
+) -> bool {
+    let matches_id = |table: &Table| matches_buildpack_id(table, buildpack_id);
+
+    let mut removed = false;
+
+    if let Some(buildpacks) = builder_file
+        .document
+        .get_mut("buildpacks")
+        .and_then(|value| value.as_array_of_tables_mut())
+    {
+        removed |= remove_tables(buildpacks, matches_id);
+    }
+
+    if let Some(orders) = builder_file
+        .document
+        .get_mut("order")
+        .and_then(|value| value.as_array_of_tables_mut())
+    {
+        for order in orders.iter_mut() {
+            if let Some(groups) = order
+                .get_mut("group")
+                .and_then(|value| value.as_array_of_tables_mut())
+            {
+                removed |= remove_tables(groups, matches_id);
+            }
+        }
+        remove_tables(orders, |order| {
+            order
+                .get("group")
+                .and_then(|value| value.as_array_of_tables())
+                .map(|groups| groups.is_empty())
+                .unwrap_or(false)
+        });
+    }
+
+    removed
+}
+
+// removes `[[buildpacks]]` entries that repeat an earlier entry's id and uri, entries that share an
+// id but point at different uris are left alone since there's no telling which one is intended
+pub fn remove_duplicate_builder_buildpacks(builder_file: &mut BuilderFile) -> bool {
+    if let Some(buildpacks) = builder_file
+        .document
+        .get_mut("buildpacks")
+        .and_then(|value| value.as_array_of_tables_mut())
+    {
+        let mut seen = vec![];
+        let indexes = buildpacks
+            .iter()
+            .enumerate()
+            .filter_map(|(index, buildpack)| {
+                let entry = (
+                    buildpack.get("id").and_then(|item| item.as_str())?,
+                    buildpack.get("uri").and_then(|item| item.as_str()),
+                );
+                if seen.contains(&entry) {
+                    Some(index)
+                } else {
+                    seen.push(entry);
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        remove_table_indexes(buildpacks, &indexes);
+        !indexes.is_empty()
+    } else {
+        false
+    }
+}
+
+fn remove_tables(tables: &mut ArrayOfTables, predicate: impl Fn(&Table) -> bool) -> bool {
+    let indexes = tables
+        .iter()
+        .enumerate()
+        .filter(|(_, table)| predicate(table))
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    remove_table_indexes(tables, &indexes);
+    !indexes.is_empty()
+}
+
+fn remove_table_indexes(tables: &mut ArrayOfTables, indexes: &[usize]) {
+    // the next table takes the removed table's whitespace so the file keeps its layout
+    for index in indexes.iter().rev() {
+        let prefix = tables
+            .get(*index)
+            .and_then(|table| table.decor().prefix().cloned());
+        tables.remove(*index);
+        if let (Some(prefix), Some(next)) = (prefix, tables.get_mut(*index)) {
+            next.decor_mut().set_prefix(prefix);
+        }
+    }
+}
+
+// adds a `[[buildpacks]]` entry and/or a new `[[order]]` group for a buildpack the builder doesn't
+// reference yet, copying the formatting of existing entries so the file keeps a consistent style
+pub fn add_buildpack_to_builder(
+    builder_file: &mut BuilderFile,
+    buildpack_id: &BuildpackId,
+    buildpack_version: &BuildpackVersion,
+    buildpack_uri: &URIReference,
+    order_index: Option<usize>,
+) -> Result<bool> {
+    let matches_id = |table: &Table| matches_buildpack_id(table, buildpack_id);
+
+    let buildpacks = builder_file
+        .document
+        .entry("buildpacks")
+        .or_insert(Item::ArrayOfTables(ArrayOfTables::new()))
+        .as_array_of_tables_mut()
+        .ok_or(BuilderFileError::MissingRequiredKey(
+            builder_file.path.clone(),
+            "buildpacks".to_string(),
+        ))?;
+
+    let mut added = false;
+
+    if !buildpacks.iter().any(matches_id) {
+        let mut buildpack = Table::new();
+        buildpack["id"] = value(buildpack_id.to_string());
+        buildpack["uri"] = value(buildpack_uri.to_string());
+        let index = sorted_index(
+            &buildpacks
+                .iter()
+                .map(|buildpack| buildpack.get("id").and_then(Item::as_str))
+                .collect::<Vec<_>>(),
+            buildpack_id.as_str(),
+        );
+        insert_table(buildpacks, index, buildpack);
+        added = true;
+    }
+
+    let orders = builder_file
+        .document
+        .get_mut("order")
+        .and_then(|value| value.as_array_of_tables_mut())
+        .ok_or(BuilderFileError::MissingRequiredKey(
+            builder_file.path.clone(),
+            "order".to_string(),
+        ))?;
+
+    let in_order = orders.iter().any(|order| {
+        order
+            .get("group")
+            .and_then(|value| value.as_array_of_tables())
+            .map(|groups| groups.iter().any(matches_id))
+            .unwrap_or(false)
+    });
+
+    if !in_order {
+        let order_index = order_index.unwrap_or_else(|| {
+            sorted_index(
+                &orders
+                    .iter()
+                    .map(|order| {
+                        order
+                            .get("group")
+                            .and_then(|value| value.as_array_of_tables())
+                            .and_then(|groups| groups.get(0))
+                            .and_then(|group| group.get("id"))
+                            .and_then(Item::as_str)
+                    })
+                    .collect::<Vec<_>>(),
+                buildpack_id.as_str(),
+            )
+        });
+        if order_index > orders.len() {
+            Err(BuilderFileError::InvalidOrderIndex(
+                builder_file.path.clone(),
+                order_index,
+                orders.len(),
+            ))?;
+        }
+
+        let mut group = Table::new();
+        group["id"] = value(buildpack_id.to_string());
+        group["version"] = value(buildpack_version.to_string());
+
+        if let Some(neighbor_group) = neighboring_table(orders, order_index)
+            .and_then(|order| order.get("group"))
+            .and_then(|value| value.as_array_of_tables())
+            .and_then(|groups| groups.iter().last())
+        {
+            copy_formatting(neighbor_group, &mut group);
+        }
+        let mut groups = ArrayOfTables::new();
+        groups.push(group);
+        let mut order = Table::new();
+        order.insert("group", Item::ArrayOfTables(groups));
+
+        insert_table(orders, order_index, order);
+        added = true;
+    }
+
+    Ok(added)
+}
+
+// the index that keeps the entries in alphabetical order by id, new entries go at the end when the
+// entries aren't sorted to begin with
+fn sorted_index(ids: &[Option<&str>], id: &str) -> usize {
+    let is_sorted = ids
+        .windows(2)
+        .all(|pair| matches!(pair, [Some(a), Some(b)] if a <= b))
+        && ids.iter().all(Option::is_some);
+    if is_sorted {
+        ids.partition_point(|existing| existing.map_or(false, |existing| existing < id))
+    } else {
+        ids.len()
+    }
+}
+
+// the entry whose formatting a new entry at `index` copies, the first entry is only used when there
+// is no other since its prefix holds whatever comes before the entries (e.g.; a heading comment)
+fn neighboring_table(tables: &ArrayOfTables, index: usize) -> Option<&Table> {
+    [index.checked_sub(1), Some(index), Some(index + 1)]
+        .into_iter()
+        .flatten()
+        .filter(|index| *index > 0)
+        .find_map(|index| tables.get(index))
+        .or_else(|| tables.get(0))
+}
+
+fn insert_table(tables: &mut ArrayOfTables, index: usize, mut table: Table) {
+    if let Some(neighbor) = neighboring_table(tables, index) {
+        copy_formatting(neighbor, &mut table);
+    }
+
+    // with a single entry there is no separator between entries to copy, only what comes before the
+    // first one (e.g. nothing at the start of the file), so the new entry gets a blank line instead
+    if tables.len() == 1 {
+        let prefix = table
+            .decor()
+            .prefix()
+            .and_then(RawString::as_str)
+            .unwrap_or_default()
+            .to_string();
+        if !prefix.contains('\n') {
+            table.decor_mut().set_prefix(format!("\n{prefix}"));
+        }
+    }
+
+    // a new first entry takes over the prefix of the current one so a heading comment stays on top
+    if index == 0 {
+        if let Some(first) = tables.get_mut(0) {
+            let heading = first.decor().prefix().cloned();
+            let mut prefix = table
+                .decor()
+                .prefix()
+                .and_then(RawString::as_str)
+                .unwrap_or_default()
+                .to_string();
+            if !prefix.starts_with('\n') {
+                prefix.insert(0, '\n');
+            }
+            first.decor_mut().set_prefix(prefix);
+            if let Some(heading) = heading {
+                table.decor_mut().set_prefix(heading);
+            }
+        }
+    }
+
+    // tables without a document position are rendered after the table preceding them, one inserted
+    // before an existing table takes its position so it's rendered first
+    if let Some(position) = tables.get(index).and_then(Table::position) {
+        table.set_position(position);
+    }
+
+    let mut existing = tables.iter().cloned().collect::<Vec<_>>();
+    existing.insert(index, table);
+    tables.clear();
+    for table in existing {
+        tables.push(table);
+    }
+}
+
+const BUILDPACK_URI_PLACEHOLDERS: [&str; 4] = ["{buildpack_id}", "{name}", "{version}", "{digest}"];
+
+// `{name}` is the buildpack id without its namespace (e.g.; `nodejs-engine` for `heroku/nodejs-engine`)
+pub fn render_buildpack_uri(
+    template: &str,
+    buildpack_id: &BuildpackId,
+    buildpack_version: &BuildpackVersion,
+    digest: Option<&str>,
+) -> std::result::Result<String, BuildpackUriTemplateError> {
+    lazy_static! {
+        static ref PLACEHOLDER: Regex = Regex::new(r"\{[^}]*}").expect("Should be a valid regex");
+        static ref DIGEST: Regex =
+            Regex::new(r"^sha256:[a-f0-9]{64}$").expect("Should be a valid regex");
+    }
+
+    if let Some(placeholder) = PLACEHOLDER
+        .find_iter(template)
+        .find(|placeholder| !BUILDPACK_URI_PLACEHOLDERS.contains(&placeholder.as_str()))
+    {
+        Err(BuildpackUriTemplateError::UnknownPlaceholder(
+            placeholder.as_str().to_string(),
+        ))?;
+    }
+
+    let digest = match digest {
+        Some(digest) if DIGEST.is_match(digest) => digest,
+        Some(digest) => Err(BuildpackUriTemplateError::InvalidDigest(digest.to_string()))?,
+        None if template.contains("{digest}") => Err(BuildpackUriTemplateError::MissingDigest)?,
+        None => "",
+    };
+
+    let name = buildpack_id
+        .as_str()
+        .rsplit_once('/')
+        .map_or(buildpack_id.as_str(), |(_, name)| name);
+
+    Ok(template
+        .replace("{buildpack_id}", buildpack_id.as_str())
+        .replace("{name}", name)
+        .replace("{version}", &buildpack_version.to_string())
+        .replace("{digest}", digest))
+}
+
+#[derive(ValueEnum, Debug, Default, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum BuilderChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+impl Display for BuilderChannel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuilderChannel::Stable => write!(f, "stable"),
+            BuilderChannel::Beta => write!(f, "beta"),
+        }
+    }
+}
+
+// maps builder directories (relative to the builder repository) to the channel they receive updates
+// from, e.g.;
+//
+// [channels]
+// stable = ["builder-20", "builder-22"]
+// beta = ["builder-24"]
+//
+// builders that aren't listed are in the `stable` channel
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct BuilderChannels {
+    channels: BTreeMap<PathBuf, BuilderChannel>,
+}
+
+impl BuilderChannels {
+    pub fn channel_of(&self, builder_dir: &Path) -> BuilderChannel {
+        self.channels.get(builder_dir).copied().unwrap_or_default()
+    }
+
+    pub fn builders_in(&self, channel: BuilderChannel) -> Vec<PathBuf> {
+        self.channels
+            .iter()
+            .filter(|(_, builder_channel)| **builder_channel == channel)
+            .map(|(builder_dir, _)| builder_dir.clone())
+            .collect()
+    }
+}
+
+pub fn read_builder_channels(
+    path: PathBuf,
+) -> std::result::Result<BuilderChannels, BuilderChannelsError> {
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| BuilderChannelsError::Reading(path.clone(), e))?;
+    let document = Document::from_str(&contents).map_err(|e| {
+        capture_toml_failure(&path, &contents, &e);
+        BuilderChannelsError::Parsing(path.clone(), e)
+    })?;
+
+    let mut channels = BTreeMap::new();
+
+    if let Some(table) = document.get("channels").and_then(Item::as_table_like) {
+        for (name, builders) in table.iter() {
+            let channel = BuilderChannel::from_str(name, true).map_err(|_| {
+                BuilderChannelsError::UnknownChannel(path.clone(), name.to_string())
+            })?;
+            let builders = builders.as_array().ok_or_else(|| {
+                BuilderChannelsError::InvalidBuilders(path.clone(), name.to_string())
+            })?;
+            for builder in builders {
+                let builder = builder.as_str().ok_or_else(|| {
+                    BuilderChannelsError::InvalidBuilders(path.clone(), name.to_string())
+                })?;
+                if let Some(previous) = channels.insert(PathBuf::from(builder), channel) {
+                    if previous != channel {
+                        Err(BuilderChannelsError::ConflictingChannels(
+                            path.clone(),
+                            builder.to_string(),
+                        ))?;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(BuilderChannels { channels })
+}
+
+// only the blank lines and indentation are copied, a comment belongs to the entry it's written for
+fn copy_formatting(from: &Table, to: &mut Table) {
+    *to.decor_mut() = without_comments(from.decor());
+
+    let default_key_decor = from.iter().next().and_then(|(key, _)| from.key_decor(key));
+    let keys = to
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .collect::<Vec<_>>();
+    for key in keys {
+        if let Some(decor) = from.key_decor(&key).or(default_key_decor) {
+            if let Some(key_decor) = to.key_decor_mut(&key) {
+                *key_decor = without_comments(decor);
+            }
+        }
+    }
+}
+
+fn without_comments(decor: &Decor) -> Decor {
+    let strip = |raw: Option<&RawString>| {
+        raw.and_then(RawString::as_str)
+            .unwrap_or_default()
+            .split('\n')
+            .filter(|line| !line.trim_start().starts_with('#'))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    Decor::new(strip(decor.prefix()), strip(decor.suffix()))
+}
+
+#[derive(Debug)]
+pub enum BuilderFileError {
+    Reading(PathBuf, io::Error),
+    Parsing(PathBuf, toml_edit::TomlError),
+    MissingRequiredKey(PathBuf, String),
+    InvalidOrderIndex(PathBuf, usize, usize),
+    InvalidTable(PathBuf, String),
+    Patching(PathBuf, PatchError),
+}
+
+impl Display for BuilderFileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuilderFileError::Reading(path, error) => {
+                write!(
+                    f,
+                    "Could not read builder\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            BuilderFileError::Parsing(path, error) => {
+                write!(
+                    f,
+                    "Could not parse builder\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            BuilderFileError::MissingRequiredKey(path, key) => {
+                write!(
+                    f,
+                    "Missing required key `{key}` in builder\nPath: {}",
+                    path.display()
+                )
+            }
+
+            BuilderFileError::InvalidOrderIndex(path, index, length) => {
+                write!(
+                    f,
+                    "Order index {index} is out of range, builder has {length} order entries\nPath: {}",
+                    path.display()
+                )
+            }
+
+            BuilderFileError::InvalidTable(path, field) => {
+                write!(
+                    f,
+                    "Field `{field}` in builder is not a table\nPath: {}",
+                    path.display()
+                )
+            }
+
+            BuilderFileError::Patching(path, error) => {
+                write!(
+                    f,
+                    "Could not update builder\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum PinDigestsError {
+    ResolvingDigest(String, OciError),
+    Cancelled(CancellationError),
+}
+
+impl Display for PinDigestsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PinDigestsError::ResolvingDigest(uri, error) => {
+                write!(
+                    f,
+                    "Could not resolve the image digest\nValue: {uri}\nError: {error}"
+                )
+            }
+
+            PinDigestsError::Cancelled(error) => {
+                write!(f, "{error}")
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum BuilderChannelsError {
+    Reading(PathBuf, io::Error),
+    Parsing(PathBuf, toml_edit::TomlError),
+    UnknownChannel(PathBuf, String),
+    InvalidBuilders(PathBuf, String),
+    ConflictingChannels(PathBuf, String),
+}
+
+impl Display for BuilderChannelsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuilderChannelsError::Reading(path, error) => {
+                write!(
+                    f,
+                    "Could not read builder channels\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            BuilderChannelsError::Parsing(path, error) => {
+                write!(
+                    f,
+                    "Could not parse builder channels\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            BuilderChannelsError::UnknownChannel(path, name) => {
+                write!(
+                    f,
+                    "Unknown channel `{name}` in builder channels, expected `stable` or `beta`\nPath: {}",
+                    path.display()
+                )
+            }
+
+            BuilderChannelsError::InvalidBuilders(path, name) => {
+                write!(
+                    f,
+                    "Channel `{name}` must be a list of builder directories\nPath: {}",
+                    path.display()
+                )
+            }
+
+            BuilderChannelsError::ConflictingChannels(path, builder) => {
+                write!(
+                    f,
+                    "Builder `{builder}` is listed in more than one channel\nPath: {}",
+                    path.display()
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum BuildpackUriTemplateError {
+    UnknownPlaceholder(String),
+    MissingDigest,
+    InvalidDigest(String),
+}
+
+impl Display for BuildpackUriTemplateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildpackUriTemplateError::UnknownPlaceholder(placeholder) => {
+                write!(
+                    f,
+                    "Unknown placeholder `{placeholder}` in buildpack URI template, expected one of {}",
+                    BUILDPACK_URI_PLACEHOLDERS
+                        .map(|placeholder| format!("`{placeholder}`"))
+                        .join(", ")
+                )
+            }
+
+            BuildpackUriTemplateError::MissingDigest => {
+                write!(
+                    f,
+                    "The buildpack URI template uses `{{digest}}` but no digest was given"
+                )
+            }
+
+            BuildpackUriTemplateError::InvalidDigest(digest) => {
+                write!(
+                    f,
+                    "Invalid digest `{digest}`, expected the format `sha256:<64 hex characters>`"
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::builder::{
+        add_buildpack_to_builder, builder_references_buildpack, get_builder_buildpack_uris,
+        get_builder_buildpack_versions, pin_builder_buildpack_digests, pin_builder_buildpack_uris,
+        read_builder_channels, remove_buildpack_from_builder, remove_duplicate_builder_buildpacks,
+        render_buildpack_uri, set_builder_buildpack_optional, set_builder_last_updated,
+        update_builder_contents_with_buildpack, BuilderChannel, BuilderFile, PinDigestsError,
+    };
+    use crate::oci::{DigestCache, OciError};
+    use libcnb_data::buildpack::{BuildpackId, BuildpackVersion};
+    use libcnb_data::buildpack_id;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+    use toml_edit::Document;
+    use uriparse::URIReference;
+
+    #[test]
+    fn test_update_builder_contents_with_buildpack() {
+        let toml = r#"
+[[buildpacks]]
+  id = "heroku/java"
+  uri = "docker://docker.io/heroku/buildpack-java@sha256:21990393c93927b16f76c303ae007ea7e95502d52b0317ca773d4cd51e7a5682"
+
+[[buildpacks]]
+  id = "heroku/nodejs"
+  uri = "docker://docker.io/heroku/buildpack-nodejs@sha256:22ec91eebee2271b99368844f193c4bb3c6084201062f89b3e45179b938c3241"
+
+[[order]]
+  [[order.group]]
+    id = "heroku/nodejs"
+    version = "0.6.5"  
+
+[[order]]
+  [[order.group]]
+    id = "heroku/java"
+    version = "0.6.9"
+
+  [[order.group]]
+    id = "heroku/procfile"
+    version = "2.0.0"
+    optional = true
+"#;
+        let mut builder_file = BuilderFile {
+            path: PathBuf::from("/path/to/builder.toml"),
+            document: Document::from_str(toml).unwrap(),
+        };
+        assert_eq!(
+            update_builder_contents_with_buildpack(
+                &mut builder_file,
+                &buildpack_id!("heroku/java"),
+                &BuildpackVersion::try_from("0.6.10".to_string()).unwrap(),
+                &URIReference::try_from("docker://docker.io/heroku/buildpack-java@sha256:c6dd500be06a2a1e764c30359c5dd4f4955a98b572ef3095b2f6115cd8a87c99").unwrap()
+            ).unwrap(),
+            Some(r#"
+[[buildpacks]]
+  id = "heroku/java"
+  uri = "docker://docker.io/heroku/buildpack-java@sha256:c6dd500be06a2a1e764c30359c5dd4f4955a98b572ef3095b2f6115cd8a87c99"
+
+[[buildpacks]]
+  id = "heroku/nodejs"
+  uri = "docker://docker.io/heroku/buildpack-nodejs@sha256:22ec91eebee2271b99368844f193c4bb3c6084201062f89b3e45179b938c3241"
+
+[[order]]
+  [[order.group]]
+    id = "heroku/nodejs"
+    version = "0.6.5"  
+
+[[order]]
+  [[order.group]]
+    id = "heroku/java"
+    version = "0.6.10"
+
+  [[order.group]]
+    id = "heroku/procfile"
+    version = "2.0.0"
+    optional = true
+"#.to_string())
+        );
+        assert_eq!(
+            update_builder_contents_with_buildpack(
+                &mut builder_file,
+                &buildpack_id!("heroku/go"),
+                &BuildpackVersion::try_from("0.1.0".to_string()).unwrap(),
+                &URIReference::try_from("docker://docker.io/heroku/buildpack-go:latest").unwrap()
+            )
+            .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_update_builder_contents_with_buildpack_with_multiple_edits() {
+        let toml = r#"# builder for heroku/nodejs
+[[buildpacks]]
+  id = "heroku/nodejs"
+  uri = 'docker://docker.io/heroku/buildpack-nodejs:1.0.0' # released
+
+[[buildpacks]]
+  id = "heroku/procfile"
+  uri = "docker://docker.io/heroku/procfile-cnb:2.0.0"
+
+[[order]]
+  [[order.group]]
+    id = "heroku/nodejs"
+    version = "1.0.0" # pinned
+    optional = true
+
+  [[order.group]]
+    id = "heroku/procfile"
+    version = "2.0.0"
+
+[[order]]
+  [[order.group]]
+    id = "heroku/nodejs"
+    version = "1.0.0"
+"#;
+        let mut builder_file = BuilderFile {
+            path: PathBuf::from("/path/to/builder.toml"),
+            document: Document::from_str(toml).unwrap(),
+        };
+        let expected = r#"# builder for heroku/nodejs
+[[buildpacks]]
+  id = "heroku/nodejs"
+  uri = 'docker://docker.io/heroku/buildpack-nodejs:1.10.100' # released
+
+[[buildpacks]]
+  id = "heroku/procfile"
+  uri = "docker://docker.io/heroku/procfile-cnb:2.0.0"
+
+[[order]]
+  [[order.group]]
+    id = "heroku/nodejs"
+    version = "1.10.100" # pinned
+    optional = true
+
+  [[order.group]]
+    id = "heroku/procfile"
+    version = "2.0.0"
+
+[[order]]
+  [[order.group]]
+    id = "heroku/nodejs"
+    version = "1.10.100"
+"#;
+        assert_eq!(
+            update_builder_contents_with_buildpack(
+                &mut builder_file,
+                &buildpack_id!("heroku/nodejs"),
+                &BuildpackVersion::try_from("1.10.100".to_string()).unwrap(),
+                &URIReference::try_from("docker://docker.io/heroku/buildpack-nodejs:1.10.100")
+                    .unwrap()
+            )
+            .unwrap(),
+            Some(expected.to_string())
+        );
+
+        // the document is replaced by the patched contents so later edits build on them
+        assert!(set_builder_buildpack_optional(
+            &mut builder_file,
+            &buildpack_id!("heroku/nodejs"),
+            true
+        ));
+        assert_eq!(
+            builder_file.document.to_string(),
+            format!("{expected}    optional = true\n")
+        );
+
+        // an entry without a `uri` key can't be patched so the document is edited instead
+        let mut builder_file = BuilderFile {
+            path: PathBuf::from("/path/to/builder.toml"),
+            document: Document::from_str(
+                "[[buildpacks]]\nid = \"heroku/nodejs\"\n\n[[order]]\n[[order.group]]\nid = \"heroku/nodejs\"\nversion = \"1.0.0\"\n",
+            )
+            .unwrap(),
+        };
+        assert_eq!(
+            update_builder_contents_with_buildpack(
+                &mut builder_file,
+                &buildpack_id!("heroku/nodejs"),
+                &BuildpackVersion::try_from("1.1.0".to_string()).unwrap(),
+                &URIReference::try_from("docker://docker.io/heroku/buildpack-nodejs:1.1.0")
+                    .unwrap()
+            )
+            .unwrap(),
+            Some("[[buildpacks]]\nid = \"heroku/nodejs\"\nuri = \"docker://docker.io/heroku/buildpack-nodejs:1.1.0\"\n\n[[order]]\n[[order.group]]\nid = \"heroku/nodejs\"\nversion = \"1.1.0\"\n".to_string())
+        );
+    }
+
+    #[test]
+    fn test_get_builder_buildpack_versions() {
+        let builder_file = BuilderFile {
+            path: PathBuf::from("/path/to/builder.toml"),
+            document: Document::from_str(
+                r#"
+[[order]]
+  [[order.group]]
+    id = "heroku/java"
+    version = "0.6.9"
+
+[[order]]
+  [[order.group]]
+    id = "heroku/java"
+    version = "0.6.10"
+
+  [[order.group]]
+    id = "heroku/procfile"
+    version = "2.0.0"
+"#,
+            )
+            .unwrap(),
+        };
+        assert_eq!(
+            get_builder_buildpack_versions(&builder_file, &buildpack_id!("heroku/java")),
+            vec!["0.6.9", "0.6.10"]
+        );
+        assert!(
+            get_builder_buildpack_versions(&builder_file, &buildpack_id!("heroku/go")).is_empty()
+        );
+    }
+
+    #[test]
+    fn test_builder_references_buildpack() {
+        let builder_file = BuilderFile {
+            path: PathBuf::from("/path/to/builder.toml"),
+            document: Document::from_str(
+                r#"
+[[buildpacks]]
+  id = "heroku/java"
+  uri = "docker://docker.io/heroku/buildpack-java:latest"
+
+[[order]]
+  [[order.group]]
+    id = "heroku/procfile"
+    version = "2.0.0"
+"#,
+            )
+            .unwrap(),
+        };
+        assert!(builder_references_buildpack(
+            &builder_file,
+            &buildpack_id!("heroku/java")
+        ));
+        assert!(builder_references_buildpack(
+            &builder_file,
+            &buildpack_id!("heroku/procfile")
+        ));
+        assert!(!builder_references_buildpack(
+            &builder_file,
+            &buildpack_id!("heroku/nodejs")
+        ));
+    }
+
+    #[test]
+    fn test_add_buildpack_to_builder() {
+        let mut builder_file = BuilderFile {
+            path: PathBuf::from("/path/to/builder.toml"),
+            document: Document::from_str(
+                r#"
+[[buildpacks]]
+  id = "heroku/java"
+  uri = "docker://docker.io/heroku/buildpack-java:latest"
+
+[[order]]
+  [[order.group]]
+    id = "heroku/java"
+    version = "0.6.9"
+
+[[order]]
+  [[order.group]]
+    id = "heroku/procfile"
+    version = "2.0.0"
+"#,
+            )
+            .unwrap(),
+        };
+
+        assert!(add_buildpack_to_builder(
+            &mut builder_file,
+            &buildpack_id!("heroku/go"),
+            &BuildpackVersion::try_from("0.1.0".to_string()).unwrap(),
+            &URIReference::try_from("docker://docker.io/heroku/buildpack-go:latest").unwrap(),
+            Some(1),
+        )
+        .unwrap());
+
+        assert_eq!(
+            builder_file.document.to_string(),
+            r#"
+[[buildpacks]]
+  id = "heroku/go"
+  uri = "docker://docker.io/heroku/buildpack-go:latest"
+
+[[buildpacks]]
+  id = "heroku/java"
+  uri = "docker://docker.io/heroku/buildpack-java:latest"
+
+[[order]]
+  [[order.group]]
+    id = "heroku/java"
+    version = "0.6.9"
+
+[[order]]
+  [[order.group]]
+    id = "heroku/go"
+    version = "0.1.0"
+
+[[order]]
+  [[order.group]]
+    id = "heroku/procfile"
+    version = "2.0.0"
+"#
+        );
+
+        assert!(!add_buildpack_to_builder(
+            &mut builder_file,
+            &buildpack_id!("heroku/go"),
+            &BuildpackVersion::try_from("0.1.0".to_string()).unwrap(),
+            &URIReference::try_from("docker://docker.io/heroku/buildpack-go:latest").unwrap(),
+            None,
+        )
+        .unwrap());
+
+        assert!(add_buildpack_to_builder(
+            &mut builder_file,
+            &buildpack_id!("heroku/php"),
+            &BuildpackVersion::try_from("0.1.0".to_string()).unwrap(),
+            &URIReference::try_from("docker://docker.io/heroku/buildpack-php:latest").unwrap(),
+            Some(4),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_add_buildpack_to_builder_formatting() {
+        let version = BuildpackVersion::try_from("0.1.0".to_string()).unwrap();
+        let add = |contents: &str, buildpack_id: &BuildpackId| {
+            let mut builder_file = BuilderFile {
+                path: PathBuf::from("/path/to/builder.toml"),
+                document: Document::from_str(contents).unwrap(),
+            };
+            let uri = format!(
+                "docker://docker.io/{}:latest",
+                buildpack_id.as_str().replace('/', "/buildpack-")
+            );
+            add_buildpack_to_builder(
+                &mut builder_file,
+                buildpack_id,
+                &version,
+                &URIReference::try_from(uri.as_str()).unwrap(),
+                None,
+            )
+            .unwrap();
+            builder_file.document.to_string()
+        };
+
+        let sorted = r#"# Buildpacks included in the builder
+[[buildpacks]]
+id = "heroku/go"
+uri = "docker://docker.io/heroku/buildpack-go:latest"
+
+# pinned until the next release
+[[buildpacks]]
+id = "heroku/ruby" # the default
+uri = "docker://docker.io/heroku/buildpack-ruby:latest"
+
+# Detection order
+[[order]]
+[[order.group]]
+id = "heroku/go"
+version = "0.6.9"
+
+[[order]]
+[[order.group]]
+id = "heroku/ruby"
+version = "2.0.0"
+"#;
+        assert_eq!(
+            add(sorted, &buildpack_id!("heroku/java")),
+            r#"# Buildpacks included in the builder
+[[buildpacks]]
+id = "heroku/go"
+uri = "docker://docker.io/heroku/buildpack-go:latest"
+
+[[buildpacks]]
+id = "heroku/java"
+uri = "docker://docker.io/heroku/buildpack-java:latest"
+
+# pinned until the next release
+[[buildpacks]]
+id = "heroku/ruby" # the default
+uri = "docker://docker.io/heroku/buildpack-ruby:latest"
+
+# Detection order
+[[order]]
+[[order.group]]
+id = "heroku/go"
+version = "0.6.9"
+
+[[order]]
+[[order.group]]
+id = "heroku/java"
+version = "0.1.0"
+
+[[order]]
+[[order.group]]
+id = "heroku/ruby"
+version = "2.0.0"
+"#
+        );
+        assert_eq!(
+            add(sorted, &buildpack_id!("heroku/clojure")),
+            r#"# Buildpacks included in the builder
+[[buildpacks]]
+id = "heroku/clojure"
+uri = "docker://docker.io/heroku/buildpack-clojure:latest"
+
+[[buildpacks]]
+id = "heroku/go"
+uri = "docker://docker.io/heroku/buildpack-go:latest"
+
+# pinned until the next release
+[[buildpacks]]
+id = "heroku/ruby" # the default
+uri = "docker://docker.io/heroku/buildpack-ruby:latest"
+
+# Detection order
+[[order]]
+[[order.group]]
+id = "heroku/clojure"
+version = "0.1.0"
+
+[[order]]
+[[order.group]]
+id = "heroku/go"
+version = "0.6.9"
+
+[[order]]
+[[order.group]]
+id = "heroku/ruby"
+version = "2.0.0"
+"#
+        );
+
+        assert_eq!(
+            add(
+                r#"[[buildpacks]]
+    id = "heroku/ruby"
+    uri = "docker://docker.io/heroku/buildpack-ruby:latest"
+[[buildpacks]]
+    id = "heroku/go"
+    uri = "docker://docker.io/heroku/buildpack-go:latest"
+
+[[order]]
+    [[order.group]]
+        id = "heroku/ruby"
+        version = "2.0.0"
+
+[[order]]
+    [[order.group]]
+        id = "heroku/go"
+        version = "0.6.9"
+"#,
+                &buildpack_id!("heroku/java")
+            ),
+            r#"[[buildpacks]]
+    id = "heroku/ruby"
+    uri = "docker://docker.io/heroku/buildpack-ruby:latest"
+[[buildpacks]]
+    id = "heroku/go"
+    uri = "docker://docker.io/heroku/buildpack-go:latest"
+[[buildpacks]]
+    id = "heroku/java"
+    uri = "docker://docker.io/heroku/buildpack-java:latest"
+
+[[order]]
+    [[order.group]]
+        id = "heroku/ruby"
+        version = "2.0.0"
+
+[[order]]
+    [[order.group]]
+        id = "heroku/go"
+        version = "0.6.9"
+
+[[order]]
+    [[order.group]]
+        id = "heroku/java"
+        version = "0.1.0"
+"#
+        );
+    }
+
+    #[test]
+    fn test_add_buildpack_to_builder_with_a_single_entry() {
+        let mut builder_file = BuilderFile {
+            path: PathBuf::from("/path/to/builder.toml"),
+            document: Document::from_str(
+                r#"[[buildpacks]]
+  id = "heroku/go"
+  uri = "docker://docker.io/heroku/buildpack-go:latest"
+
+[[order]]
+  [[order.group]]
+    id = "heroku/go"
+    version = "0.6.9"
+"#,
+            )
+            .unwrap(),
+        };
+        add_buildpack_to_builder(
+            &mut builder_file,
+            &buildpack_id!("heroku/java"),
+            &BuildpackVersion::try_from("0.1.0".to_string()).unwrap(),
+            &URIReference::try_from("docker://docker.io/heroku/buildpack-java:latest").unwrap(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            builder_file.document.to_string(),
+            r#"[[buildpacks]]
+  id = "heroku/go"
+  uri = "docker://docker.io/heroku/buildpack-go:latest"
+
+[[buildpacks]]
+  id = "heroku/java"
+  uri = "docker://docker.io/heroku/buildpack-java:latest"
+
+[[order]]
+  [[order.group]]
+    id = "heroku/go"
+    version = "0.6.9"
+
+[[order]]
+  [[order.group]]
+    id = "heroku/java"
+    version = "0.1.0"
+"#
+        );
+    }
+
+    #[test]
+    fn test_pin_builder_buildpack_uris() {
+        let mut builder_file = BuilderFile {
+            path: PathBuf::from("/path/to/builder.toml"),
+            document: Document::from_str(
+                r#"
+[[buildpacks]]
+  id = "heroku/java"
+  uri = "docker://docker.io/heroku/buildpack-java:1.2.3"
+
+[[buildpacks]]
+  id = "heroku/go"
+  uri = "docker://docker.io/heroku/buildpack-go@sha256:22ec91eebee2271b99368844f193c4bb3c6084201062f89b3e45179b938c3241"
+"#,
+            )
+            .unwrap(),
+        };
+
+        assert_eq!(
+            get_builder_buildpack_uris(&builder_file),
+            vec![
+                "docker://docker.io/heroku/buildpack-java:1.2.3",
+                "docker://docker.io/heroku/buildpack-go@sha256:22ec91eebee2271b99368844f193c4bb3c6084201062f89b3e45179b938c3241"
+            ]
+        );
+
+        assert!(pin_builder_buildpack_uris(
+            &mut builder_file,
+            &HashMap::from([(
+                "docker://docker.io/heroku/buildpack-java:1.2.3".to_string(),
+                "docker://docker.io/heroku/buildpack-java@sha256:c6dd500be06a2a1e764c30359c5dd4f4955a98b572ef3095b2f6115cd8a87c99".to_string()
+            )])
+        ));
+
+        assert_eq!(
+            builder_file.document.to_string(),
+            r#"
+[[buildpacks]]
+  id = "heroku/java"
+  uri = "docker://docker.io/heroku/buildpack-java@sha256:c6dd500be06a2a1e764c30359c5dd4f4955a98b572ef3095b2f6115cd8a87c99"
+
+[[buildpacks]]
+  id = "heroku/go"
+  uri = "docker://docker.io/heroku/buildpack-go@sha256:22ec91eebee2271b99368844f193c4bb3c6084201062f89b3e45179b938c3241"
+"#
+        );
+
+        assert!(!pin_builder_buildpack_uris(
+            &mut builder_file,
+            &HashMap::new()
+        ));
+    }
+
+    #[test]
+    fn test_pin_builder_buildpack_digests_skips_pinned_and_non_image_uris() {
+        let contents = r#"
+[[buildpacks]]
+  id = "heroku/go"
+  uri = "docker://docker.io/heroku/buildpack-go@sha256:22ec91eebee2271b99368844f193c4bb3c6084201062f89b3e45179b938c3241"
+
+[[buildpacks]]
+  id = "heroku/procfile"
+  uri = "urn:cnb:registry:heroku/procfile@2.0.0"
+"#;
+        let mut builder_files = vec![BuilderFile {
+            path: PathBuf::from("/path/to/builder.toml"),
+            document: Document::from_str(contents).unwrap(),
+        }];
+        let uris = get_builder_buildpack_uris(&builder_files[0]);
+
+        pin_builder_buildpack_digests(&mut builder_files, uris, 8, &DigestCache::default())
+            .unwrap();
+        assert_eq!(builder_files[0].document.to_string(), contents);
+
+        match pin_builder_buildpack_digests(
+            &mut builder_files,
+            vec!["docker://docker.io/heroku/buildpack-java:".to_string()],
+            8,
+            &DigestCache::default(),
+        ) {
+            Err(PinDigestsError::ResolvingDigest(uri, OciError::InvalidImageReference(_))) => {
+                assert_eq!(uri, "docker://docker.io/heroku/buildpack-java:");
+            }
+            result => panic!("Expected an invalid image reference, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn test_remove_buildpack_from_builder() {
+        let mut builder_file = BuilderFile {
+            path: PathBuf::from("/path/to/builder.toml"),
+            document: Document::from_str(
+                r#"[[buildpacks]]
+  id = "heroku/nodejs-yarn"
+  uri = "docker://docker.io/heroku/buildpack-nodejs-yarn@sha256:abc"
+
+[[buildpacks]]
+  id = "heroku/procfile"
+  uri = "docker://docker.io/heroku/buildpack-procfile@sha256:def"
+
+[[order]]
+  [[order.group]]
+    id = "heroku/nodejs-yarn"
+    version = "1.0.0"
+
+[[order]]
+  [[order.group]]
+    id = "heroku/nodejs-yarn"
+    version = "1.0.0"
+
+  [[order.group]]
+    id = "heroku/procfile"
+    version = "2.0.0"
+    optional = true
+"#,
+            )
+            .unwrap(),
+        };
+
+        assert!(remove_buildpack_from_builder(
+            &mut builder_file,
+            &buildpack_id!("heroku/nodejs-yarn")
+        ));
+        assert!(!remove_buildpack_from_builder(
+            &mut builder_file,
+            &buildpack_id!("heroku/nodejs-yarn")
+        ));
+
+        assert_eq!(
+            builder_file.document.to_string(),
+            r#"[[buildpacks]]
+  id = "heroku/procfile"
+  uri = "docker://docker.io/heroku/buildpack-procfile@sha256:def"
+
+[[order]]
+  [[order.group]]
+    id = "heroku/procfile"
+    version = "2.0.0"
+    optional = true
+"#
+        );
+    }
+
+    #[test]
+    fn test_remove_duplicate_builder_buildpacks() {
+        let mut builder_file = BuilderFile {
+            path: PathBuf::from("/path/to/builder.toml"),
+            document: Document::from_str(
+                r#"[[buildpacks]]
+  id = "heroku/nodejs"
+  uri = "docker://docker.io/heroku/buildpack-nodejs:1.0.0"
+
+[[buildpacks]]
+  id = "heroku/procfile"
+  uri = "docker://docker.io/heroku/buildpack-procfile:2.0.0"
+
+[[buildpacks]]
+  id = "heroku/nodejs"
+  uri = "docker://docker.io/heroku/buildpack-nodejs:1.0.0"
+
+[[buildpacks]]
+  id = "heroku/procfile"
+  uri = "docker://docker.io/heroku/buildpack-procfile:3.0.0"
+"#,
+            )
+            .unwrap(),
+        };
+
+        assert!(remove_duplicate_builder_buildpacks(&mut builder_file));
+        assert!(!remove_duplicate_builder_buildpacks(&mut builder_file));
+
+        assert_eq!(
+            builder_file.document.to_string(),
+            r#"[[buildpacks]]
+  id = "heroku/nodejs"
+  uri = "docker://docker.io/heroku/buildpack-nodejs:1.0.0"
+
+[[buildpacks]]
+  id = "heroku/procfile"
+  uri = "docker://docker.io/heroku/buildpack-procfile:2.0.0"
+
+[[buildpacks]]
+  id = "heroku/procfile"
+  uri = "docker://docker.io/heroku/buildpack-procfile:3.0.0"
+"#
+        );
+    }
+
+    #[test]
+    fn test_set_builder_buildpack_optional() {
+        let mut builder_file = BuilderFile {
+            path: PathBuf::from("/path/to/builder.toml"),
+            document: Document::from_str(
+                r#"
+[[order]]
+  [[order.group]]
+    id = "heroku/nodejs"
+    version = "1.0.0"
+
+  [[order.group]]
+    id = "heroku/procfile"
+    version = "2.0.0"
+    optional = true
+
+[[order]]
+  [[order.group]]
+    id = "heroku/nodejs"
+    version = "1.0.0"
+    optional   =   false # required until the migration is done
+"#,
+            )
+            .unwrap(),
+        };
+
+        assert!(set_builder_buildpack_optional(
+            &mut builder_file,
+            &buildpack_id!("heroku/nodejs"),
+            true
+        ));
+        assert!(!set_builder_buildpack_optional(
+            &mut builder_file,
+            &buildpack_id!("heroku/procfile"),
+            true
+        ));
+        assert!(!set_builder_buildpack_optional(
+            &mut builder_file,
+            &buildpack_id!("heroku/go"),
+            true
+        ));
+
+        assert_eq!(
+            builder_file.document.to_string(),
+            r#"
+[[order]]
+  [[order.group]]
+    id = "heroku/nodejs"
+    version = "1.0.0"
+    optional = true
+
+  [[order.group]]
+    id = "heroku/procfile"
+    version = "2.0.0"
+    optional = true
+
+[[order]]
+  [[order.group]]
+    id = "heroku/nodejs"
+    version = "1.0.0"
+    optional   =   true # required until the migration is done
+"#
+        );
+    }
+
+    #[test]
+    fn test_set_builder_last_updated() {
+        let mut builder_file = BuilderFile {
+            path: PathBuf::from("/path/to/builder.toml"),
+            document: Document::from_str("[[buildpacks]]\n  id = \"heroku/nodejs\"\n").unwrap(),
+        };
+        set_builder_last_updated(&mut builder_file, "2023-06-01T12:00:00Z").unwrap();
+        assert_eq!(
+            builder_file.document.to_string(),
+            "[[buildpacks]]\n  id = \"heroku/nodejs\"\n\n[metadata]\nlast_updated = \"2023-06-01T12:00:00Z\"\n"
+        );
+
+        let mut builder_file = BuilderFile {
+            path: PathBuf::from("/path/to/builder.toml"),
+            document: Document::from_str(
+                "[metadata]\n  maintainer = \"heroku\"\n  last_updated = \"2023-05-01T00:00:00Z\" # set by update-builder\n",
+            )
+            .unwrap(),
+        };
+        set_builder_last_updated(&mut builder_file, "2023-06-01T12:00:00Z").unwrap();
+        assert_eq!(
+            builder_file.document.to_string(),
+            "[metadata]\n  maintainer = \"heroku\"\n  last_updated = \"2023-06-01T12:00:00Z\" # set by update-builder\n"
+        );
+
+        let mut builder_file = BuilderFile {
+            path: PathBuf::from("/path/to/builder.toml"),
+            document: Document::from_str("metadata = \"none\"\n").unwrap(),
+        };
+        assert!(set_builder_last_updated(&mut builder_file, "2023-06-01T12:00:00Z").is_err());
+    }
+
+    #[test]
+    fn test_read_builder_channels() {
+        let path = std::env::temp_dir().join(format!("channels-{}.toml", std::process::id()));
+        std::fs::write(
+            &path,
+            "[channels]\nstable = [\"builder-20\", \"builder-22\"]\nbeta = [\"builder-24\"]\n",
+        )
+        .unwrap();
+        let channels = read_builder_channels(path.clone()).unwrap();
+
+        assert_eq!(
+            channels.channel_of(&PathBuf::from("builder-24")),
+            BuilderChannel::Beta
+        );
+        assert_eq!(
+            channels.channel_of(&PathBuf::from("builder-22")),
+            BuilderChannel::Stable
+        );
+        assert_eq!(
+            channels.channel_of(&PathBuf::from("unlisted")),
+            BuilderChannel::Stable
+        );
+        assert_eq!(
+            channels.builders_in(BuilderChannel::Stable),
+            vec![PathBuf::from("builder-20"), PathBuf::from("builder-22")]
+        );
+
+        std::fs::write(
+            &path,
+            "[channels]\nstable = [\"builder-22\"]\nbeta = [\"builder-22\"]\n",
+        )
+        .unwrap();
+        assert!(read_builder_channels(path.clone()).is_err());
+
+        std::fs::write(&path, "[channels]\nalpha = [\"builder-22\"]\n").unwrap();
+        assert!(read_builder_channels(path.clone()).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_render_buildpack_uri() {
+        let buildpack_id = buildpack_id!("heroku/nodejs-engine");
+        let buildpack_version = BuildpackVersion::new(1, 2, 3);
+        let digest = "sha256:21990393c93927b16f76c303ae007ea7e95502d52b0317ca773d4cd51e7a5682";
+
+        assert_eq!(
+            render_buildpack_uri(
+                "docker://docker.io/heroku/buildpack-{name}@{digest}",
+                &buildpack_id,
+                &buildpack_version,
+                Some(digest)
+            )
+            .unwrap(),
+            format!("docker://docker.io/heroku/buildpack-nodejs-engine@{digest}")
+        );
+        assert_eq!(
+            render_buildpack_uri(
+                "docker://docker.io/{buildpack_id}:{version}",
+                &buildpack_id,
+                &buildpack_version,
+                None
+            )
+            .unwrap(),
+            "docker://docker.io/heroku/nodejs-engine:1.2.3"
+        );
+        assert!(render_buildpack_uri(
+            "docker://docker.io/heroku/buildpack-{name}@{digest}",
+            &buildpack_id,
+            &buildpack_version,
+            None
+        )
+        .is_err());
+        assert!(render_buildpack_uri(
+            "docker://docker.io/heroku/buildpack-{name}@{digest}",
+            &buildpack_id,
+            &buildpack_version,
+            Some("sha256:abc")
+        )
+        .is_err());
+        assert!(render_buildpack_uri(
+            "docker://docker.io/heroku/buildpack-{id}",
+            &buildpack_id,
+            &buildpack_version,
+            None
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,1296 @@
+use crate::debug_artifacts::capture_toml_failure;
+use crate::log;
+use crate::progress::{track, ProgressStep};
+use clap::Args;
+use ignore::{Walk, WalkBuilder};
+use libcnb_data::buildpack::{BuildpackId, BuildpackVersion};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
+use toml_edit::{value, ArrayOfTables, Document, Formatted, Item, Table, Value};
+
+type Result<T> = std::result::Result<T, BuildpackFileError>;
+
+pub struct BuildpackFile {
+    pub path: PathBuf,
+    pub document: Document,
+}
+
+pub fn read_buildpack_file(path: PathBuf) -> Result<BuildpackFile> {
+    track(ProgressStep::Parse, path.display(), || {
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|e| BuildpackFileError::Reading(path.clone(), e))?;
+        Document::from_str(&contents).map_err(|e| {
+            capture_toml_failure(&path, &contents, &e);
+            BuildpackFileError::Parsing(path.clone(), e)
+        })
+    })
+    .map(|document| BuildpackFile { path, document })
+}
+
+pub const DISCOVERY_CACHE_DIR_ENV: &str = "BUILDPACK_DISCOVERY_CACHE_DIR";
+
+#[derive(Args, Debug)]
+pub struct BuildpackDiscoveryArgs {
+    #[arg(long)]
+    pub no_ignore: bool,
+    #[arg(long)]
+    pub discovery_cache_dir: Option<PathBuf>,
+    #[arg(long = "project-dir")]
+    pub project_dirs: Vec<PathBuf>,
+    #[arg(long)]
+    pub allow_duplicates: bool,
+}
+
+impl BuildpackDiscoveryArgs {
+    // with `--project-dir`, only those directories (relative to `start_dir`) are searched and their
+    // results merged, paths stay relative to `start_dir` so outputs still point at the right root
+    pub fn find_buildpack_dirs(
+        &self,
+        start_dir: &Path,
+        ignore: &[PathBuf],
+    ) -> std::result::Result<Vec<PathBuf>, BuildpackDiscoveryError> {
+        let dirs = track(ProgressStep::Discovery, start_dir.display(), || {
+            self.find_project_buildpack_dirs(start_dir, ignore)
+        })
+        .map_err(BuildpackDiscoveryError::Walking)?;
+
+        if !self.allow_duplicates {
+            let duplicates = find_duplicate_buildpack_ids(&dirs);
+            if !duplicates.is_empty() {
+                Err(BuildpackDiscoveryError::DuplicateBuildpackIds(duplicates))?;
+            }
+        }
+
+        Ok(dirs)
+    }
+
+    fn find_project_buildpack_dirs(
+        &self,
+        start_dir: &Path,
+        ignore: &[PathBuf],
+    ) -> std::result::Result<Vec<PathBuf>, ignore::Error> {
+        if self.project_dirs.is_empty() {
+            return self.find_buildpack_dirs_in(start_dir, ignore);
+        }
+
+        let mut dirs = vec![];
+        for project_dir in &self.project_dirs {
+            let project_dir = project_dir
+                .components()
+                .filter(|component| component != &Component::CurDir)
+                .collect::<PathBuf>();
+            // `--project-dir .` searches the whole project, joining an empty path would add a trailing
+            // separator and the root buildpack would no longer dedup against other results
+            let search_dir = if project_dir.as_os_str().is_empty() {
+                start_dir.to_path_buf()
+            } else {
+                start_dir.join(project_dir)
+            };
+            dirs.extend(self.find_buildpack_dirs_in(&search_dir, ignore)?);
+        }
+        dirs.sort();
+        dirs.dedup();
+        Ok(dirs)
+    }
+
+    // the cache is opt-in, setting the environment variable once enables it for every command in a job
+    fn find_buildpack_dirs_in(
+        &self,
+        start_dir: &Path,
+        ignore: &[PathBuf],
+    ) -> std::result::Result<Vec<PathBuf>, ignore::Error> {
+        let cache_dir = self.discovery_cache_dir.clone().or_else(|| {
+            std::env::var_os(DISCOVERY_CACHE_DIR_ENV)
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from)
+        });
+        match cache_dir {
+            Some(cache_dir) => {
+                find_buildpack_dirs_cached(start_dir, ignore, !self.no_ignore, &cache_dir)
+            }
+            None => find_buildpack_dirs(start_dir, ignore, !self.no_ignore),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+struct CachedBuildpackDir {
+    dir: PathBuf,
+    sha256: String,
+}
+
+// a cached result is reused only while listing the buildpack.toml files finds the same ones (e.g.;
+// no buildpack was added and no `.gitignore` change hides one) and each still has the same contents,
+// failing to read or write the cache falls back to walking the tree
+pub fn find_buildpack_dirs_cached(
+    start_dir: &Path,
+    ignore: &[PathBuf],
+    respect_ignore_files: bool,
+    cache_dir: &Path,
+) -> std::result::Result<Vec<PathBuf>, ignore::Error> {
+    let mut hasher = Sha256::new();
+    hasher.update(start_dir.to_string_lossy().as_bytes());
+    for path in ignore {
+        hasher.update(b"\0");
+        hasher.update(path.to_string_lossy().as_bytes());
+    }
+    hasher.update([u8::from(respect_ignore_files)]);
+    let cache_file = cache_dir.join(format!("buildpack-discovery-{:x}.json", hasher.finalize()));
+
+    let cached = std::fs::read_to_string(&cache_file)
+        .ok()
+        .and_then(|contents| serde_json::from_str::<Vec<CachedBuildpackDir>>(&contents).ok());
+    if let Some(entries) = cached {
+        let mut cached_files = entries
+            .iter()
+            .map(|entry| entry.dir.join("buildpack.toml"))
+            .collect::<Vec<_>>();
+        cached_files.sort();
+        let mut buildpack_files = list_buildpack_files(start_dir, ignore, respect_ignore_files)?;
+        buildpack_files.sort();
+
+        let unchanged = cached_files == buildpack_files
+            && entries.iter().all(|entry| {
+                hash_buildpack_file(&entry.dir).as_deref() == Some(entry.sha256.as_str())
+            });
+        if unchanged {
+            return Ok(entries.into_iter().map(|entry| entry.dir).collect());
+        }
+    }
+
+    let dirs = find_buildpack_dirs(start_dir, ignore, respect_ignore_files)?;
+
+    let entries = dirs
+        .iter()
+        .map(|dir| {
+            hash_buildpack_file(dir).map(|sha256| CachedBuildpackDir {
+                dir: dir.clone(),
+                sha256,
+            })
+        })
+        .collect::<Option<Vec<_>>>();
+    if let Some(entries) = entries {
+        let written = std::fs::create_dir_all(cache_dir).and_then(|_| {
+            std::fs::write(
+                &cache_file,
+                serde_json::to_string(&entries).expect("Cache entries should serialize to JSON"),
+            )
+        });
+        if let Err(error) = written {
+            log!(
+                "⚠️ Could not write buildpack discovery cache: {}\nError: {error}",
+                cache_file.display()
+            );
+        }
+    }
+
+    Ok(dirs)
+}
+
+fn hash_buildpack_file(dir: &Path) -> Option<String> {
+    std::fs::read(dir.join("buildpack.toml"))
+        .ok()
+        .map(|contents| format!("{:x}", Sha256::digest(contents)))
+}
+
+// a `buildpack.toml` in `start_dir` itself makes it a buildpack (e.g.; a repository with a single
+// buildpack at its root), its `target` directory holds build output with packaged copies of the
+// buildpack so it is never searched
+pub fn find_buildpack_dirs(
+    start_dir: &Path,
+    ignore: &[PathBuf],
+    respect_ignore_files: bool,
+) -> std::result::Result<Vec<PathBuf>, ignore::Error> {
+    let start_dir = start_dir.components().collect::<PathBuf>();
+    let is_root_buildpack = start_dir.join("buildpack.toml").is_file();
+
+    let nested_dirs = walk_project(&start_dir, ignore, respect_ignore_files)
+        .filter_map(|entry| match entry {
+            Ok(entry) => {
+                let is_buildpack_dir = entry.depth() > 0
+                    && entry
+                        .file_type()
+                        .filter(|file_type| file_type.is_dir())
+                        .is_some()
+                    && entry.path().join("buildpack.toml").is_file();
+                is_buildpack_dir.then(|| Ok(entry.into_path()))
+            }
+            Err(error) => Some(Err(error)),
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok(is_root_buildpack
+        .then_some(start_dir)
+        .into_iter()
+        .chain(nested_dirs)
+        .collect())
+}
+
+// the `buildpack.toml` files of the buildpacks `find_buildpack_dirs` finds, listed from the walked
+// entries without checking every directory for one
+fn list_buildpack_files(
+    start_dir: &Path,
+    ignore: &[PathBuf],
+    respect_ignore_files: bool,
+) -> std::result::Result<Vec<PathBuf>, ignore::Error> {
+    let start_dir = start_dir.components().collect::<PathBuf>();
+    walk_project(&start_dir, ignore, respect_ignore_files)
+        .filter_map(|entry| match entry {
+            Ok(entry) => (entry.file_name() == "buildpack.toml" && entry.path().is_file())
+                .then(|| Ok(entry.into_path())),
+            Err(error) => Some(Err(error)),
+        })
+        .collect()
+}
+
+fn walk_project(start_dir: &Path, ignore: &[PathBuf], respect_ignore_files: bool) -> Walk {
+    let mut ignore = ignore.to_vec();
+    if start_dir.join("buildpack.toml").is_file() {
+        ignore.push(start_dir.join("target"));
+    }
+
+    WalkBuilder::new(start_dir)
+        .standard_filters(respect_ignore_files)
+        .hidden(false)
+        .require_git(false)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .filter_entry(move |entry| {
+            entry.file_name() != ".git" && !ignore.iter().any(|path| path == entry.path())
+        })
+        .build()
+}
+
+// buildpacks that declare the same id (e.g.; a copied directory that was never renamed) mapped to
+// their directories, a `buildpack.toml` that can't be read is left for the command to report
+pub fn find_duplicate_buildpack_ids(dirs: &[PathBuf]) -> BTreeMap<String, Vec<PathBuf>> {
+    let mut dirs_by_id: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    for dir in dirs {
+        let buildpack_id = std::fs::read_to_string(dir.join("buildpack.toml"))
+            .ok()
+            .and_then(|contents| Document::from_str(&contents).ok())
+            .and_then(|document| {
+                document
+                    .get("buildpack")
+                    .and_then(|buildpack| buildpack.get("id"))
+                    .and_then(Item::as_str)
+                    .map(String::from)
+            });
+        if let Some(buildpack_id) = buildpack_id {
+            dirs_by_id
+                .entry(buildpack_id)
+                .or_default()
+                .push(dir.clone());
+        }
+    }
+    dirs_by_id.retain(|_, dirs| dirs.len() > 1);
+    dirs_by_id
+}
+
+pub fn get_buildpack_id(buildpack_file: &BuildpackFile) -> Result<BuildpackId> {
+    let buildpack_id = buildpack_file
+        .document
+        .get("buildpack")
+        .and_then(|value| value.as_table_like())
+        .and_then(|buildpack| buildpack.get("id"))
+        .and_then(|id| id.as_str().map(|v| v.to_string()))
+        .ok_or(BuildpackFileError::MissingRequiredField(
+            buildpack_file.path.clone(),
+            "buildpack.id".to_string(),
+        ))?;
+    buildpack_id.parse().map_err(|_| {
+        BuildpackFileError::InvalidBuildpackId(buildpack_file.path.clone(), buildpack_id.clone())
+    })
+}
+
+pub fn get_buildpack_version(buildpack_file: &BuildpackFile) -> Result<BuildpackVersion> {
+    let version = buildpack_file
+        .document
+        .get("buildpack")
+        .and_then(|value| value.as_table_like())
+        .and_then(|buildpack| buildpack.get("version"))
+        .and_then(|version| version.as_str().map(|v| v.to_string()))
+        .ok_or(BuildpackFileError::MissingRequiredField(
+            buildpack_file.path.clone(),
+            "buildpack.version".to_string(),
+        ))?;
+    BuildpackVersion::try_from(version.clone()).map_err(|_| {
+        BuildpackFileError::InvalidBuildpackVersion(buildpack_file.path.clone(), version)
+    })
+}
+
+pub fn get_buildpack_docker_repository(buildpack_file: &BuildpackFile) -> Option<String> {
+    buildpack_file
+        .document
+        .get("metadata")
+        .and_then(|value| value.as_table_like())
+        .and_then(|metadata| metadata.get("release"))
+        .and_then(|value| value.as_table_like())
+        .and_then(|release| release.get("docker"))
+        .and_then(|value| value.as_table_like())
+        .and_then(|docker| docker.get("repository"))
+        .and_then(|repository| repository.as_str().map(|v| v.to_string()))
+}
+
+pub fn is_buildpack_release_disabled(buildpack_file: &BuildpackFile) -> bool {
+    buildpack_file
+        .document
+        .get("metadata")
+        .and_then(|value| value.as_table_like())
+        .and_then(|metadata| metadata.get("release"))
+        .and_then(|value| value.as_table_like())
+        .and_then(|release| release.get("disabled"))
+        .and_then(|disabled| disabled.as_bool())
+        .unwrap_or(false)
+}
+
+// recorded under `[metadata.release.deprecation]` so tooling can show the message and end-of-life date,
+// an existing deprecation is replaced
+pub fn set_buildpack_deprecation(
+    buildpack_file: &mut BuildpackFile,
+    message: &str,
+    eol_date: &str,
+) -> Result<()> {
+    let mut item = buildpack_file.document.as_item_mut();
+    let mut keys = vec![];
+    for key in ["metadata", "release", "deprecation"] {
+        keys.push(key);
+        item = &mut item[key];
+        if item.is_none() {
+            let mut table = Table::new();
+            table.set_implicit(true);
+            *item = Item::Table(table);
+        }
+        if !item.is_table() {
+            Err(BuildpackFileError::InvalidTable(
+                buildpack_file.path.clone(),
+                keys.join("."),
+            ))?;
+        }
+    }
+    item["message"] = value(message);
+    item["eol_date"] = value(eol_date);
+    Ok(())
+}
+
+pub fn get_buildpack_dependency_ids(buildpack_file: &BuildpackFile) -> Result<Vec<BuildpackId>> {
+    buildpack_file
+        .document
+        .get("order")
+        .and_then(|value| value.as_array_of_tables())
+        .unwrap_or(&ArrayOfTables::default())
+        .iter()
+        .flat_map(|order| {
+            order
+                .get("group")
+                .and_then(|value| value.as_array_of_tables())
+                .unwrap_or(&ArrayOfTables::default())
+                .iter()
+                .map(|group| get_group_buildpack_id(group, &buildpack_file.path))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Result<Vec<_>>>()
+}
+
+pub fn get_group_buildpack_id(group: &Table, path: &Path) -> Result<BuildpackId> {
+    group
+        .get("id")
+        .and_then(|id| id.as_str())
+        .ok_or(BuildpackFileError::MissingRequiredField(
+            path.to_path_buf(),
+            "order[].group[].id".to_string(),
+        ))
+        .and_then(|id| {
+            id.parse::<BuildpackId>().map_err(|_| {
+                BuildpackFileError::InvalidBuildpackId(path.to_path_buf(), id.to_string())
+            })
+        })
+}
+
+pub fn update_buildpack_contents_with_new_version(
+    buildpack_file: &mut BuildpackFile,
+    next_version: &BuildpackVersion,
+    updated_dependencies: &[BuildpackId],
+) -> Result<String> {
+    let buildpack = buildpack_file
+        .document
+        .get_mut("buildpack")
+        .and_then(|value| value.as_table_like_mut())
+        .ok_or(BuildpackFileError::MissingRequiredField(
+            buildpack_file.path.clone(),
+            "buildpack".to_string(),
+        ))?;
+
+    buildpack.insert("version", value(next_version.to_string()));
+
+    let mut empty_orders = ArrayOfTables::default();
+    let mut empty_groups = ArrayOfTables::default();
+
+    let orders = buildpack_file
+        .document
+        .get_mut("order")
+        .and_then(|value| value.as_array_of_tables_mut())
+        .unwrap_or(&mut empty_orders);
+    for order in orders.iter_mut() {
+        let groups = order
+            .get_mut("group")
+            .and_then(|value| value.as_array_of_tables_mut())
+            .unwrap_or(&mut empty_groups);
+        for group in groups.iter_mut() {
+            let buildpack_id = get_group_buildpack_id(group, &buildpack_file.path)?;
+            if updated_dependencies.contains(&buildpack_id) {
+                group.insert("version", value(next_version.to_string()));
+            }
+        }
+    }
+
+    Ok(buildpack_file.document.to_string())
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct OrderVersionDrift {
+    pub buildpack_id: BuildpackId,
+    pub pinned: String,
+    pub version: String,
+}
+
+// rewrites `[[order.group]]` pins for the given buildpacks to their current versions, returning
+// each pin that drifted along with the version it was pinned to
+pub fn sync_buildpack_order_versions(
+    buildpack_file: &mut BuildpackFile,
+    versions: &HashMap<BuildpackId, BuildpackVersion>,
+) -> Result<Vec<OrderVersionDrift>> {
+    let mut drifted = vec![];
+    let mut empty_orders = ArrayOfTables::default();
+    let mut empty_groups = ArrayOfTables::default();
+
+    let orders = buildpack_file
+        .document
+        .get_mut("order")
+        .and_then(|value| value.as_array_of_tables_mut())
+        .unwrap_or(&mut empty_orders);
+    for order in orders.iter_mut() {
+        let groups = order
+            .get_mut("group")
+            .and_then(|value| value.as_array_of_tables_mut())
+            .unwrap_or(&mut empty_groups);
+        for group in groups.iter_mut() {
+            let buildpack_id = get_group_buildpack_id(group, &buildpack_file.path)?;
+            if let Some(version) = versions.get(&buildpack_id) {
+                let version = version.to_string();
+                let pinned = group
+                    .get("version")
+                    .and_then(|item| item.as_str())
+                    .unwrap_or_default()
+                    .to_string();
+                if pinned != version {
+                    group.insert("version", value(&version));
+                    drifted.push(OrderVersionDrift {
+                        buildpack_id,
+                        pinned,
+                        version,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(drifted)
+}
+
+// orders buildpacks so each one comes after the buildpacks it includes, returning their indexes,
+// ties keep their original order so the result is stable and dependencies outside the list are
+// ignored, when buildpacks include each other the ids left in the cycle are returned instead
+pub fn sort_buildpacks_by_dependencies(
+    buildpacks: &[(BuildpackId, Vec<BuildpackId>)],
+) -> std::result::Result<Vec<usize>, Vec<BuildpackId>> {
+    let mut sorted: Vec<usize> = vec![];
+    while sorted.len() < buildpacks.len() {
+        let ready = buildpacks
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !sorted.contains(index))
+            .filter(|(_, (_, dependencies))| {
+                dependencies.iter().all(|dependency| {
+                    buildpacks
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, (buildpack_id, _))| buildpack_id == dependency)
+                        .all(|(index, _)| sorted.contains(&index))
+                })
+            })
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+
+        if ready.is_empty() {
+            return Err(buildpacks
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| !sorted.contains(index))
+                .map(|(_, (buildpack_id, _))| buildpack_id.clone())
+                .collect());
+        }
+        sorted.extend(ready);
+    }
+    Ok(sorted)
+}
+
+// rewrites `[[dependencies]]` uris in a package.toml that reference one of the given buildpacks
+// through the CNB registry (`urn:cnb:registry:<id>@<version>`) or its docker repository
+// (`docker://<repository>:<version>`), local paths are resolved when packaging so they're left alone
+pub fn update_package_dependency_uris(
+    document: &mut Document,
+    buildpacks: &[(BuildpackId, Option<String>)],
+    next_version: &BuildpackVersion,
+) {
+    let mut empty_dependencies = ArrayOfTables::default();
+    let dependencies = document
+        .get_mut("dependencies")
+        .and_then(|value| value.as_array_of_tables_mut())
+        .unwrap_or(&mut empty_dependencies);
+    for dependency in dependencies.iter_mut() {
+        if let Some(Value::String(uri)) = dependency.get_mut("uri").and_then(Item::as_value_mut) {
+            if let Some(updated) =
+                update_package_dependency_uri(uri.value(), buildpacks, next_version)
+            {
+                let decor = uri.decor().clone();
+                let mut replacement = Formatted::new(updated);
+                *replacement.decor_mut() = decor;
+                *uri = replacement;
+            }
+        }
+    }
+}
+
+fn update_package_dependency_uri(
+    uri: &str,
+    buildpacks: &[(BuildpackId, Option<String>)],
+    next_version: &BuildpackVersion,
+) -> Option<String> {
+    if let Some((buildpack_id, _)) = uri
+        .strip_prefix("urn:cnb:registry:")
+        .and_then(|reference| reference.split_once('@'))
+    {
+        return buildpacks
+            .iter()
+            .any(|(id, _)| id.as_str() == buildpack_id)
+            .then(|| format!("urn:cnb:registry:{buildpack_id}@{next_version}"));
+    }
+
+    let (repository, tag) = uri.strip_prefix("docker://")?.rsplit_once(':')?;
+    if tag.contains('/') {
+        return None;
+    }
+    buildpacks
+        .iter()
+        .any(|(_, docker_repository)| docker_repository.as_deref() == Some(repository))
+        .then(|| format!("docker://{repository}:{next_version}"))
+}
+
+pub fn update_buildpack_contents_with_value(
+    buildpack_file: &mut BuildpackFile,
+    key: &str,
+    new_value: &str,
+) -> Result<Option<String>> {
+    let mut item = buildpack_file.document.as_item_mut();
+    for segment in key.split('.') {
+        match item
+            .as_table_like_mut()
+            .and_then(|table| table.get_mut(segment))
+        {
+            Some(child) => item = child,
+            None => return Ok(None),
+        }
+    }
+
+    match item.as_value_mut() {
+        Some(Value::String(current_value)) => {
+            let previous_value = current_value.value().clone();
+            let decor = current_value.decor().clone();
+            let mut replacement = Formatted::new(new_value.to_string());
+            *replacement.decor_mut() = decor;
+            *current_value = replacement;
+            Ok(Some(previous_value))
+        }
+        _ => Err(BuildpackFileError::InvalidFieldValue(
+            buildpack_file.path.clone(),
+            key.to_string(),
+        )),
+    }
+}
+
+#[derive(Debug)]
+pub enum BuildpackFileError {
+    Reading(PathBuf, io::Error),
+    Parsing(PathBuf, toml_edit::TomlError),
+    MissingRequiredField(PathBuf, String),
+    InvalidBuildpackId(PathBuf, String),
+    InvalidBuildpackVersion(PathBuf, String),
+    InvalidFieldValue(PathBuf, String),
+    InvalidTable(PathBuf, String),
+}
+
+impl Display for BuildpackFileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildpackFileError::Reading(path, error) => {
+                write!(
+                    f,
+                    "Could not read buildpack\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            BuildpackFileError::Parsing(path, error) => {
+                write!(
+                    f,
+                    "Could not parse buildpack\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            BuildpackFileError::MissingRequiredField(path, field) => {
+                write!(
+                    f,
+                    "Missing required field `{field}` in buildpack.toml\nPath: {}",
+                    path.display()
+                )
+            }
+
+            BuildpackFileError::InvalidBuildpackId(path, id) => {
+                write!(
+                    f,
+                    "Invalid buildpack id `{id}` in buildpack.toml\nPath: {}",
+                    path.display()
+                )
+            }
+
+            BuildpackFileError::InvalidBuildpackVersion(path, version) => {
+                write!(
+                    f,
+                    "Invalid buildpack version `{version}` in buildpack.toml\nPath: {}",
+                    path.display()
+                )
+            }
+
+            BuildpackFileError::InvalidFieldValue(path, field) => {
+                write!(
+                    f,
+                    "Field `{field}` in buildpack.toml is not a string\nPath: {}",
+                    path.display()
+                )
+            }
+
+            BuildpackFileError::InvalidTable(path, field) => {
+                write!(
+                    f,
+                    "Field `{field}` in buildpack.toml is not a table\nPath: {}",
+                    path.display()
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum BuildpackDiscoveryError {
+    Walking(ignore::Error),
+    DuplicateBuildpackIds(BTreeMap<String, Vec<PathBuf>>),
+}
+
+impl Display for BuildpackDiscoveryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildpackDiscoveryError::Walking(error) => write!(f, "{error}"),
+
+            BuildpackDiscoveryError::DuplicateBuildpackIds(duplicates) => {
+                write!(
+                    f,
+                    "Multiple buildpacks declare the same id, rename one or pass `--allow-duplicates` if this is intentional"
+                )?;
+                for (buildpack_id, dirs) in duplicates {
+                    write!(f, "\n{buildpack_id}:")?;
+                    for dir in dirs {
+                        write!(f, "\n  - {}", dir.display())?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::buildpack::{
+        find_buildpack_dirs, find_buildpack_dirs_cached, find_duplicate_buildpack_ids,
+        is_buildpack_release_disabled, set_buildpack_deprecation, sort_buildpacks_by_dependencies,
+        sync_buildpack_order_versions, update_buildpack_contents_with_new_version,
+        update_buildpack_contents_with_value, update_package_dependency_uris,
+        BuildpackDiscoveryArgs, BuildpackFile, OrderVersionDrift,
+    };
+    use libcnb_data::buildpack::BuildpackVersion;
+    use libcnb_data::buildpack_id;
+    use sha2::{Digest, Sha256};
+    use std::collections::{BTreeMap, HashMap};
+    use std::fs;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+    use toml_edit::Document;
+
+    #[test]
+    fn test_find_buildpack_dirs() {
+        let project_dir =
+            std::env::temp_dir().join(format!("find-buildpack-dirs-{}", std::process::id()));
+        for dir in ["buildpacks/a", "buildpacks/b", "fixtures/c", "target/d"] {
+            fs::create_dir_all(project_dir.join(dir)).unwrap();
+            fs::write(project_dir.join(dir).join("buildpack.toml"), "").unwrap();
+        }
+        fs::write(project_dir.join(".gitignore"), "fixtures/\n").unwrap();
+
+        let ignore = [project_dir.join("target")];
+        assert_eq!(
+            find_buildpack_dirs(&project_dir, &ignore, true).unwrap(),
+            vec![
+                project_dir.join("buildpacks/a"),
+                project_dir.join("buildpacks/b")
+            ]
+        );
+        assert_eq!(
+            find_buildpack_dirs(&project_dir, &ignore, false).unwrap(),
+            vec![
+                project_dir.join("buildpacks/a"),
+                project_dir.join("buildpacks/b"),
+                project_dir.join("fixtures/c")
+            ]
+        );
+
+        fs::remove_dir_all(project_dir).unwrap();
+    }
+
+    #[test]
+    fn test_find_buildpack_dirs_with_root_buildpack() {
+        let project_dir = std::env::temp_dir().join(format!(
+            "find-buildpack-dirs-with-root-buildpack-{}",
+            std::process::id()
+        ));
+        for dir in [
+            "",
+            "target/buildpack/release/heroku_procfile",
+            "tests/fixtures",
+        ] {
+            fs::create_dir_all(project_dir.join(dir)).unwrap();
+        }
+        for dir in ["", "target/buildpack/release/heroku_procfile"] {
+            fs::write(project_dir.join(dir).join("buildpack.toml"), "").unwrap();
+        }
+
+        assert_eq!(
+            find_buildpack_dirs(&project_dir.join("."), &[], false).unwrap(),
+            vec![project_dir.clone()]
+        );
+
+        let discovery = BuildpackDiscoveryArgs {
+            no_ignore: true,
+            discovery_cache_dir: None,
+            project_dirs: vec![PathBuf::from("."), PathBuf::from("tests")],
+            allow_duplicates: false,
+        };
+        assert_eq!(
+            discovery.find_buildpack_dirs(&project_dir, &[]).unwrap(),
+            vec![project_dir.clone()]
+        );
+
+        fs::remove_dir_all(project_dir).unwrap();
+    }
+
+    #[test]
+    fn test_find_buildpack_dirs_in_project_dirs() {
+        let project_dir = std::env::temp_dir().join(format!(
+            "find-buildpack-dirs-in-project-dirs-{}",
+            std::process::id()
+        ));
+        for dir in ["buildpacks/a", "meta-buildpacks/b", "fixtures/c"] {
+            fs::create_dir_all(project_dir.join(dir)).unwrap();
+            fs::write(project_dir.join(dir).join("buildpack.toml"), "").unwrap();
+        }
+
+        let discovery = BuildpackDiscoveryArgs {
+            no_ignore: false,
+            discovery_cache_dir: None,
+            project_dirs: vec![
+                PathBuf::from("./meta-buildpacks"),
+                PathBuf::from("buildpacks/"),
+                PathBuf::from("buildpacks/a"),
+            ],
+            allow_duplicates: false,
+        };
+        assert_eq!(
+            discovery.find_buildpack_dirs(&project_dir, &[]).unwrap(),
+            vec![
+                project_dir.join("buildpacks/a"),
+                project_dir.join("meta-buildpacks/b")
+            ]
+        );
+
+        fs::remove_dir_all(project_dir).unwrap();
+    }
+
+    #[test]
+    fn test_find_duplicate_buildpack_ids() {
+        let project_dir = std::env::temp_dir().join(format!(
+            "find-duplicate-buildpack-ids-{}",
+            std::process::id()
+        ));
+        for (dir, contents) in [
+            ("buildpacks/nodejs", "[buildpack]\nid = \"heroku/nodejs\"\n"),
+            (
+                "buildpacks/nodejs-copy",
+                "[buildpack]\nid = \"heroku/nodejs\"\n",
+            ),
+            (
+                "buildpacks/procfile",
+                "[buildpack]\nid = \"heroku/procfile\"\n",
+            ),
+            ("buildpacks/invalid", "[buildpack"),
+        ] {
+            fs::create_dir_all(project_dir.join(dir)).unwrap();
+            fs::write(project_dir.join(dir).join("buildpack.toml"), contents).unwrap();
+        }
+
+        let discovery = BuildpackDiscoveryArgs {
+            no_ignore: false,
+            discovery_cache_dir: None,
+            project_dirs: vec![],
+            allow_duplicates: false,
+        };
+        let dirs = find_buildpack_dirs(&project_dir, &[], false).unwrap();
+        assert_eq!(
+            find_duplicate_buildpack_ids(&dirs),
+            BTreeMap::from([(
+                "heroku/nodejs".to_string(),
+                vec![
+                    project_dir.join("buildpacks/nodejs"),
+                    project_dir.join("buildpacks/nodejs-copy")
+                ]
+            )])
+        );
+        assert!(discovery.find_buildpack_dirs(&project_dir, &[]).is_err());
+        assert_eq!(
+            BuildpackDiscoveryArgs {
+                allow_duplicates: true,
+                ..discovery
+            }
+            .find_buildpack_dirs(&project_dir, &[])
+            .unwrap(),
+            dirs
+        );
+
+        fs::remove_dir_all(project_dir).unwrap();
+    }
+
+    #[test]
+    fn test_find_buildpack_dirs_cached() {
+        let project_dir =
+            std::env::temp_dir().join(format!("find-buildpack-dirs-cached-{}", std::process::id()));
+        let cache_dir = project_dir.join("cache");
+        for dir in ["buildpacks/a", "buildpacks/b"] {
+            fs::create_dir_all(project_dir.join(dir)).unwrap();
+            fs::write(project_dir.join(dir).join("buildpack.toml"), dir).unwrap();
+        }
+
+        let ignore = [cache_dir.clone()];
+        let expected = vec![
+            project_dir.join("buildpacks/a"),
+            project_dir.join("buildpacks/b"),
+        ];
+        assert_eq!(
+            find_buildpack_dirs_cached(&project_dir, &ignore, true, &cache_dir).unwrap(),
+            expected
+        );
+        assert_eq!(fs::read_dir(&cache_dir).unwrap().count(), 1);
+
+        // a buildpack added after the cache was written invalidates the cached result
+        fs::create_dir_all(project_dir.join("buildpacks/c")).unwrap();
+        fs::write(project_dir.join("buildpacks/c/buildpack.toml"), "c").unwrap();
+        assert_eq!(
+            find_buildpack_dirs_cached(&project_dir, &ignore, true, &cache_dir).unwrap(),
+            vec![
+                project_dir.join("buildpacks/a"),
+                project_dir.join("buildpacks/b"),
+                project_dir.join("buildpacks/c"),
+            ]
+        );
+
+        // so does a buildpack hidden by a new `.gitignore`
+        fs::write(project_dir.join(".gitignore"), "buildpacks/b\n").unwrap();
+        assert_eq!(
+            find_buildpack_dirs_cached(&project_dir, &ignore, true, &cache_dir).unwrap(),
+            vec![
+                project_dir.join("buildpacks/a"),
+                project_dir.join("buildpacks/c"),
+            ]
+        );
+
+        // a modified buildpack file is rehashed without changing the result
+        fs::write(project_dir.join("buildpacks/a/buildpack.toml"), "changed").unwrap();
+        assert_eq!(
+            find_buildpack_dirs_cached(&project_dir, &ignore, true, &cache_dir).unwrap(),
+            vec![
+                project_dir.join("buildpacks/a"),
+                project_dir.join("buildpacks/c"),
+            ]
+        );
+        let cache_file = fs::read_dir(&cache_dir)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        assert!(fs::read_to_string(cache_file)
+            .unwrap()
+            .contains(&format!("{:x}", Sha256::digest("changed"))));
+
+        fs::remove_dir_all(project_dir).unwrap();
+    }
+
+    #[test]
+    fn test_update_buildpack_contents_with_new_version() {
+        let toml = r#"[buildpack]
+id = "test"
+version = "0.0.0"
+            "#;
+
+        let mut buildpack_file = create_buildpack_file(toml);
+        let next_version = BuildpackVersion {
+            major: 1,
+            minor: 0,
+            patch: 0,
+        };
+        assert_eq!(
+            update_buildpack_contents_with_new_version(&mut buildpack_file, &next_version, &[])
+                .unwrap(),
+            r#"[buildpack]
+id = "test"
+version = "1.0.0"
+            "#
+        );
+    }
+
+    #[test]
+    fn test_is_buildpack_release_disabled() {
+        assert!(!is_buildpack_release_disabled(&create_buildpack_file(
+            "[buildpack]\nid = \"test\"\nversion = \"0.0.0\"\n"
+        )));
+        assert!(!is_buildpack_release_disabled(&create_buildpack_file(
+            "[buildpack]\nid = \"test\"\nversion = \"0.0.0\"\n\n[metadata.release]\ndisabled = false\n"
+        )));
+        assert!(is_buildpack_release_disabled(&create_buildpack_file(
+            "[buildpack]\nid = \"test\"\nversion = \"0.0.0\"\n\n[metadata.release]\ndisabled = true\n"
+        )));
+    }
+
+    #[test]
+    fn test_set_buildpack_deprecation() {
+        let mut buildpack_file = create_buildpack_file(
+            "[buildpack]\nid = \"test\"\nversion = \"0.0.0\"\n\n[metadata.release]\ndisabled = false\n",
+        );
+        set_buildpack_deprecation(&mut buildpack_file, "Use heroku/nodejs.", "2024-06-30").unwrap();
+        set_buildpack_deprecation(
+            &mut buildpack_file,
+            "Use heroku/nodejs instead.",
+            "2024-06-30",
+        )
+        .unwrap();
+        assert_eq!(
+            buildpack_file.document.to_string(),
+            "[buildpack]\nid = \"test\"\nversion = \"0.0.0\"\n\n[metadata.release]\ndisabled = false\n\n[metadata.release.deprecation]\nmessage = \"Use heroku/nodejs instead.\"\neol_date = \"2024-06-30\"\n"
+        );
+
+        let mut buildpack_file = create_buildpack_file(
+            "[buildpack]\nid = \"test\"\nversion = \"0.0.0\"\n\n[metadata]\nrelease = 1\n",
+        );
+        assert!(
+            set_buildpack_deprecation(&mut buildpack_file, "Deprecated.", "2024-06-30").is_err()
+        );
+    }
+
+    #[test]
+    fn test_update_buildpack_contents_with_value() {
+        let toml = r#"[buildpack]
+id = "test"
+version = "0.0.0"
+
+[metadata.node]
+default_version = "18.16.0" # LTS
+other = 1
+"#;
+
+        let mut buildpack_file = create_buildpack_file(toml);
+        assert_eq!(
+            update_buildpack_contents_with_value(
+                &mut buildpack_file,
+                "metadata.node.default_version",
+                "20.1.0"
+            )
+            .unwrap(),
+            Some("18.16.0".to_string())
+        );
+        assert_eq!(
+            buildpack_file.document.to_string(),
+            r#"[buildpack]
+id = "test"
+version = "0.0.0"
+
+[metadata.node]
+default_version = "20.1.0" # LTS
+other = 1
+"#
+        );
+
+        assert_eq!(
+            update_buildpack_contents_with_value(&mut buildpack_file, "metadata.jvm.url", "x")
+                .unwrap(),
+            None
+        );
+        assert!(update_buildpack_contents_with_value(
+            &mut buildpack_file,
+            "metadata.node.other",
+            "2"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_update_buildpack_contents_with_new_version_and_order_groups_are_present() {
+        let toml = r#"[buildpack]
+id = "test"
+version = "0.0.9"
+
+[[order]]
+[[order.group]]
+id = "dep-a"
+version = "0.0.9"
+
+[[order.group]]
+id = "dep-b"
+version = "0.0.9"
+
+[[order.group]]
+id = "heroku/procfile"
+version = "2.0.0"
+optional = true
+            "#;
+
+        let mut buildpack_file = create_buildpack_file(toml);
+        let next_version = BuildpackVersion {
+            major: 0,
+            minor: 0,
+            patch: 10,
+        };
+        assert_eq!(
+            update_buildpack_contents_with_new_version(
+                &mut buildpack_file,
+                &next_version,
+                &[buildpack_id!("dep-a"), buildpack_id!("dep-b")]
+            )
+            .unwrap(),
+            r#"[buildpack]
+id = "test"
+version = "0.0.10"
+
+[[order]]
+[[order.group]]
+id = "dep-a"
+version = "0.0.10"
+
+[[order.group]]
+id = "dep-b"
+version = "0.0.10"
+
+[[order.group]]
+id = "heroku/procfile"
+version = "2.0.0"
+optional = true
+            "#
+        );
+    }
+
+    fn create_buildpack_file(contents: &str) -> BuildpackFile {
+        BuildpackFile {
+            path: PathBuf::from("/path/to/test/buildpack.toml"),
+            document: Document::from_str(contents).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_sync_buildpack_order_versions() {
+        let mut buildpack_file = BuildpackFile {
+            path: PathBuf::from("/path/to/buildpack.toml"),
+            document: Document::from_str(
+                r#"[buildpack]
+id = "heroku/nodejs"
+version = "1.0.1"
+
+[[order]]
+[[order.group]]
+id = "heroku/nodejs-engine"
+version = "1.0.0"
+
+[[order.group]]
+id = "heroku/nodejs-npm"
+version = "1.0.1"
+
+[[order.group]]
+id = "heroku/procfile"
+version = "2.0.0"
+optional = true
+"#,
+            )
+            .unwrap(),
+        };
+        let version = BuildpackVersion {
+            major: 1,
+            minor: 0,
+            patch: 1,
+        };
+        let versions = HashMap::from([
+            (
+                buildpack_id!("heroku/nodejs-engine"),
+                BuildpackVersion::try_from(version.to_string()).unwrap(),
+            ),
+            (buildpack_id!("heroku/nodejs-npm"), version),
+        ]);
+
+        assert_eq!(
+            sync_buildpack_order_versions(&mut buildpack_file, &versions).unwrap(),
+            vec![OrderVersionDrift {
+                buildpack_id: buildpack_id!("heroku/nodejs-engine"),
+                pinned: "1.0.0".to_string(),
+                version: "1.0.1".to_string()
+            }]
+        );
+        assert_eq!(
+            buildpack_file.document.to_string(),
+            r#"[buildpack]
+id = "heroku/nodejs"
+version = "1.0.1"
+
+[[order]]
+[[order.group]]
+id = "heroku/nodejs-engine"
+version = "1.0.1"
+
+[[order.group]]
+id = "heroku/nodejs-npm"
+version = "1.0.1"
+
+[[order.group]]
+id = "heroku/procfile"
+version = "2.0.0"
+optional = true
+"#
+        );
+        assert!(
+            sync_buildpack_order_versions(&mut buildpack_file, &versions)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_sort_buildpacks_by_dependencies() {
+        let buildpacks = vec![
+            (
+                buildpack_id!("heroku/nodejs"),
+                vec![
+                    buildpack_id!("heroku/nodejs-engine"),
+                    buildpack_id!("heroku/nodejs-yarn"),
+                ],
+            ),
+            (
+                buildpack_id!("heroku/nodejs-yarn"),
+                vec![
+                    buildpack_id!("heroku/nodejs-engine"),
+                    buildpack_id!("heroku/procfile"),
+                ],
+            ),
+            (buildpack_id!("heroku/nodejs-engine"), vec![]),
+            (buildpack_id!("heroku/nodejs-npm"), vec![]),
+        ];
+        assert_eq!(
+            sort_buildpacks_by_dependencies(&buildpacks).unwrap(),
+            vec![2, 3, 1, 0]
+        );
+
+        let cycle = vec![
+            (buildpack_id!("heroku/a"), vec![buildpack_id!("heroku/b")]),
+            (buildpack_id!("heroku/b"), vec![buildpack_id!("heroku/a")]),
+            (buildpack_id!("heroku/c"), vec![]),
+        ];
+        assert_eq!(
+            sort_buildpacks_by_dependencies(&cycle).unwrap_err(),
+            vec![buildpack_id!("heroku/a"), buildpack_id!("heroku/b")]
+        );
+    }
+
+    #[test]
+    fn test_update_package_dependency_uris() {
+        let mut document = Document::from_str(
+            r#"[buildpack]
+uri = "."
+
+[[dependencies]]
+uri = "urn:cnb:registry:heroku/nodejs-engine@1.0.0" # engine
+
+[[dependencies]]
+uri = "docker://docker.io/heroku/buildpack-nodejs-npm:1.0.0"
+
+[[dependencies]]
+uri = "urn:cnb:registry:heroku/procfile@2.0.0"
+
+[[dependencies]]
+uri = "../nodejs-yarn"
+"#,
+        )
+        .unwrap();
+        update_package_dependency_uris(
+            &mut document,
+            &[
+                (buildpack_id!("heroku/nodejs-engine"), None),
+                (
+                    buildpack_id!("heroku/nodejs-npm"),
+                    Some("docker.io/heroku/buildpack-nodejs-npm".to_string()),
+                ),
+                (buildpack_id!("heroku/nodejs-yarn"), None),
+            ],
+            &BuildpackVersion {
+                major: 1,
+                minor: 0,
+                patch: 1,
+            },
+        );
+        assert_eq!(
+            document.to_string(),
+            r#"[buildpack]
+uri = "."
+
+[[dependencies]]
+uri = "urn:cnb:registry:heroku/nodejs-engine@1.0.1" # engine
+
+[[dependencies]]
+uri = "docker://docker.io/heroku/buildpack-nodejs-npm:1.0.1"
+
+[[dependencies]]
+uri = "urn:cnb:registry:heroku/procfile@2.0.0"
+
+[[dependencies]]
+uri = "../nodejs-yarn"
+"#
+        );
+    }
+}
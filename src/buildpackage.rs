@@ -0,0 +1,350 @@
+use crate::buildpack::{
+    get_buildpack_dependency_ids, get_buildpack_id, get_buildpack_version, read_buildpack_file,
+    BuildpackFile, BuildpackFileError,
+};
+use crate::oci::{Blob, Image, OCI_LAYER_MEDIA_TYPE};
+use libcnb_data::buildpack::{BuildpackId, BuildpackVersion};
+use serde_json::{json, Map, Value};
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
+
+// matches the timestamp pack normalizes buildpackage layers to so digests are reproducible
+const NORMALIZED_MTIME: u64 = 315_532_801;
+
+pub const BUILDPACKAGE_METADATA_LABEL: &str = "io.buildpacks.buildpackage.metadata";
+pub const BUILDPACK_LAYERS_LABEL: &str = "io.buildpacks.buildpack.layers";
+
+type Result<T> = std::result::Result<T, BuildpackageError>;
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct BuildpackageMetadata {
+    pub id: BuildpackId,
+    pub version: BuildpackVersion,
+    pub api: String,
+    pub homepage: Option<String>,
+    pub stacks: Vec<String>,
+}
+
+pub fn read_buildpackage_metadata(buildpack_file: &BuildpackFile) -> Result<BuildpackageMetadata> {
+    let id = get_buildpack_id(buildpack_file).map_err(BuildpackageError::BuildpackFile)?;
+    let version =
+        get_buildpack_version(buildpack_file).map_err(BuildpackageError::BuildpackFile)?;
+
+    if !get_buildpack_dependency_ids(buildpack_file)
+        .map_err(BuildpackageError::BuildpackFile)?
+        .is_empty()
+    {
+        Err(BuildpackageError::CompositeBuildpack(
+            buildpack_file.path.clone(),
+        ))?;
+    }
+
+    let api = buildpack_file
+        .document
+        .get("api")
+        .and_then(|api| api.as_str())
+        .ok_or(BuildpackageError::BuildpackFile(
+            BuildpackFileError::MissingRequiredField(
+                buildpack_file.path.clone(),
+                "api".to_string(),
+            ),
+        ))?
+        .to_string();
+
+    let homepage = buildpack_file
+        .document
+        .get("buildpack")
+        .and_then(|value| value.as_table_like())
+        .and_then(|buildpack| buildpack.get("homepage"))
+        .and_then(|homepage| homepage.as_str().map(|v| v.to_string()));
+
+    let stacks = buildpack_file
+        .document
+        .get("stacks")
+        .and_then(|value| value.as_array_of_tables())
+        .map(|stacks| {
+            stacks
+                .iter()
+                .filter_map(|stack| stack.get("id").and_then(|id| id.as_str()))
+                .map(|id| id.to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(BuildpackageMetadata {
+        id,
+        version,
+        api,
+        homepage,
+        stacks,
+    })
+}
+
+pub fn create_buildpackage(package_dir: &Path) -> Result<(BuildpackageMetadata, Image)> {
+    let buildpack_file = read_buildpack_file(package_dir.join("buildpack.toml"))
+        .map_err(BuildpackageError::BuildpackFile)?;
+    let metadata = read_buildpackage_metadata(&buildpack_file)?;
+
+    let layer = create_buildpack_layer(package_dir, &metadata)
+        .map_err(|e| BuildpackageError::CreatingLayer(package_dir.to_path_buf(), e))?;
+    let layer = Blob::new(OCI_LAYER_MEDIA_TYPE, layer);
+
+    let image = Image::new(&buildpackage_config(&metadata, &layer.digest), vec![layer]);
+
+    Ok((metadata, image))
+}
+
+fn create_buildpack_layer(
+    package_dir: &Path,
+    metadata: &BuildpackageMetadata,
+) -> io::Result<Vec<u8>> {
+    let buildpack_dir = PathBuf::from("cnb")
+        .join("buildpacks")
+        .join(metadata.id.replace('/', "_"));
+
+    let mut layer = tar::Builder::new(vec![]);
+    append_directory(&mut layer, Path::new("cnb"))?;
+    append_directory(&mut layer, &PathBuf::from("cnb").join("buildpacks"))?;
+    append_directory(&mut layer, &buildpack_dir)?;
+    append_directory_contents(
+        &mut layer,
+        package_dir,
+        &buildpack_dir.join(metadata.version.to_string()),
+    )?;
+    layer.into_inner()
+}
+
+fn append_directory(layer: &mut tar::Builder<Vec<u8>>, path: &Path) -> io::Result<()> {
+    let mut header = normalized_header(tar::EntryType::Directory, 0o755);
+    layer.append_data(&mut header, path, io::empty())
+}
+
+fn append_directory_contents(
+    layer: &mut tar::Builder<Vec<u8>>,
+    source_dir: &Path,
+    layer_dir: &Path,
+) -> io::Result<()> {
+    append_directory(layer, layer_dir)?;
+
+    let mut entries = std::fs::read_dir(source_dir)?.collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let source_path = entry.path();
+        let layer_path = layer_dir.join(entry.file_name());
+        let file_metadata = std::fs::metadata(&source_path)?;
+        if file_metadata.is_dir() {
+            append_directory_contents(layer, &source_path, &layer_path)?;
+        } else {
+            let mut header = normalized_header(tar::EntryType::Regular, file_mode(&file_metadata));
+            header.set_size(file_metadata.len());
+            layer.append_data(&mut header, &layer_path, std::fs::File::open(&source_path)?)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn normalized_header(entry_type: tar::EntryType, mode: u32) -> tar::Header {
+    let mut header = tar::Header::new_ustar();
+    header.set_entry_type(entry_type);
+    header.set_mode(mode);
+    header.set_mtime(NORMALIZED_MTIME);
+    header.set_uid(0);
+    header.set_gid(0);
+    header.set_size(0);
+    header
+}
+
+#[cfg(unix)]
+fn file_mode(metadata: &std::fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o777
+}
+
+#[cfg(not(unix))]
+fn file_mode(_: &std::fs::Metadata) -> u32 {
+    0o755
+}
+
+fn buildpackage_config(metadata: &BuildpackageMetadata, layer_diff_id: &str) -> Value {
+    let stacks = metadata
+        .stacks
+        .iter()
+        .map(|stack| json!({ "id": stack }))
+        .collect::<Vec<_>>();
+
+    let mut buildpackage_metadata = Map::new();
+    buildpackage_metadata.insert("id".to_string(), json!(metadata.id.as_str()));
+    buildpackage_metadata.insert("version".to_string(), json!(metadata.version.to_string()));
+    buildpackage_metadata.insert("stacks".to_string(), json!(stacks));
+
+    let mut layer_metadata = Map::new();
+    layer_metadata.insert("api".to_string(), json!(metadata.api));
+    layer_metadata.insert("stacks".to_string(), json!(stacks));
+    layer_metadata.insert("layerDiffID".to_string(), json!(layer_diff_id));
+
+    if let Some(homepage) = &metadata.homepage {
+        buildpackage_metadata.insert("homepage".to_string(), json!(homepage));
+        layer_metadata.insert("homepage".to_string(), json!(homepage));
+    }
+
+    let buildpack_layers = json!({
+        metadata.id.as_str(): {
+            metadata.version.to_string(): layer_metadata,
+        }
+    });
+
+    json!({
+        "architecture": "amd64",
+        "os": "linux",
+        "created": "1980-01-01T00:00:01Z",
+        "config": {
+            "Labels": {
+                BUILDPACKAGE_METADATA_LABEL: Value::Object(buildpackage_metadata).to_string(),
+                BUILDPACK_LAYERS_LABEL: buildpack_layers.to_string(),
+            }
+        },
+        "rootfs": {
+            "type": "layers",
+            "diff_ids": [layer_diff_id],
+        },
+    })
+}
+
+#[derive(Debug)]
+pub enum BuildpackageError {
+    BuildpackFile(BuildpackFileError),
+    CompositeBuildpack(PathBuf),
+    CreatingLayer(PathBuf, io::Error),
+}
+
+impl Display for BuildpackageError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildpackageError::BuildpackFile(error) => {
+                write!(f, "{error}")
+            }
+            BuildpackageError::CompositeBuildpack(path) => {
+                write!(
+                    f,
+                    "Composite buildpacks cannot be exported as a buildpackage\nPath: {}",
+                    path.display()
+                )
+            }
+            BuildpackageError::CreatingLayer(path, error) => {
+                write!(
+                    f,
+                    "Could not create buildpackage layer\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::buildpack::BuildpackFile;
+    use crate::buildpackage::{buildpackage_config, read_buildpackage_metadata};
+    use libcnb_data::buildpack::BuildpackVersion;
+    use libcnb_data::buildpack_id;
+    use serde_json::{json, Value};
+    use std::path::PathBuf;
+    use std::str::FromStr;
+    use toml_edit::Document;
+
+    #[test]
+    fn test_buildpackage_config() {
+        let buildpack_file = BuildpackFile {
+            path: PathBuf::from("/path/to/buildpack.toml"),
+            document: Document::from_str(
+                r#"
+api = "0.9"
+
+[buildpack]
+id = "heroku/nodejs-engine"
+version = "1.2.3"
+homepage = "https://github.com/heroku/buildpacks-nodejs"
+
+[[stacks]]
+id = "heroku-22"
+"#,
+            )
+            .unwrap(),
+        };
+
+        let metadata = read_buildpackage_metadata(&buildpack_file).unwrap();
+        assert_eq!(metadata.id, buildpack_id!("heroku/nodejs-engine"));
+        assert_eq!(
+            metadata.version,
+            BuildpackVersion {
+                major: 1,
+                minor: 2,
+                patch: 3
+            }
+        );
+        assert_eq!(metadata.stacks, vec!["heroku-22"]);
+
+        let config = buildpackage_config(&metadata, "sha256:abc");
+        let labels = &config["config"]["Labels"];
+        let buildpackage_metadata: Value = serde_json::from_str(
+            labels["io.buildpacks.buildpackage.metadata"]
+                .as_str()
+                .unwrap(),
+        )
+        .unwrap();
+        let buildpack_layers: Value =
+            serde_json::from_str(labels["io.buildpacks.buildpack.layers"].as_str().unwrap())
+                .unwrap();
+
+        assert_eq!(
+            buildpackage_metadata,
+            json!({
+                "id": "heroku/nodejs-engine",
+                "version": "1.2.3",
+                "homepage": "https://github.com/heroku/buildpacks-nodejs",
+                "stacks": [{ "id": "heroku-22" }],
+            })
+        );
+        assert_eq!(
+            buildpack_layers,
+            json!({
+                "heroku/nodejs-engine": {
+                    "1.2.3": {
+                        "api": "0.9",
+                        "homepage": "https://github.com/heroku/buildpacks-nodejs",
+                        "layerDiffID": "sha256:abc",
+                        "stacks": [{ "id": "heroku-22" }],
+                    }
+                }
+            })
+        );
+        assert_eq!(config["rootfs"]["diff_ids"], json!(["sha256:abc"]));
+    }
+
+    #[test]
+    fn test_composite_buildpacks_are_not_supported() {
+        let buildpack_file = BuildpackFile {
+            path: PathBuf::from("/path/to/buildpack.toml"),
+            document: Document::from_str(
+                r#"
+api = "0.9"
+
+[buildpack]
+id = "heroku/nodejs"
+version = "1.2.3"
+
+[[order]]
+[[order.group]]
+id = "heroku/nodejs-engine"
+version = "1.2.3"
+"#,
+            )
+            .unwrap(),
+        };
+
+        assert!(read_buildpackage_metadata(&buildpack_file).is_err());
+    }
+}
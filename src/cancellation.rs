@@ -0,0 +1,149 @@
+use lazy_static::lazy_static;
+use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+static RECEIVED_SIGNAL: AtomicI32 = AtomicI32::new(0);
+
+lazy_static! {
+    static ref DEADLINE: Mutex<Option<(Instant, Duration)>> = Mutex::new(None);
+}
+
+#[cfg(unix)]
+const HANDLED_SIGNALS: [i32; 2] = [libc::SIGINT, libc::SIGTERM];
+
+// the runner sends SIGINT and then SIGTERM when a job is cancelled, the handlers only record the
+// signal so commands can stop at the next check instead of dying halfway through writing files
+#[cfg(unix)]
+pub fn install_signal_handlers() {
+    extern "C" fn handle_signal(signal: libc::c_int) {
+        RECEIVED_SIGNAL.store(signal, Ordering::SeqCst);
+        // both signals go back to their default so a second one, whichever it is, terminates the
+        // process right away
+        for signal in HANDLED_SIGNALS {
+            unsafe {
+                libc::signal(signal, libc::SIG_DFL);
+            }
+        }
+    }
+
+    let handler = handle_signal as extern "C" fn(libc::c_int) as *const () as libc::sighandler_t;
+    for signal in HANDLED_SIGNALS {
+        unsafe {
+            libc::signal(signal, handler);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub fn install_signal_handlers() {}
+
+pub fn set_timeout(timeout: Duration) {
+    *DEADLINE
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner) =
+        Some((Instant::now() + timeout, timeout));
+}
+
+// called between long-running steps, anything already written stays consistent because nothing
+// else is written once this returns an error
+pub fn check_cancelled() -> Result<(), CancellationError> {
+    match RECEIVED_SIGNAL.load(Ordering::SeqCst) {
+        0 => {}
+        signal => Err(CancellationError::Signal(signal_name(signal)))?,
+    }
+
+    match *DEADLINE
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+    {
+        Some((deadline, timeout)) if Instant::now() >= deadline => {
+            Err(CancellationError::TimedOut(timeout))
+        }
+        _ => Ok(()),
+    }
+}
+
+// the time left before `--timeout` runs out, network requests use it as their own timeout so one
+// that hangs can't keep the command running past the deadline
+pub fn remaining_time() -> Option<Duration> {
+    let deadline = *DEADLINE
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    deadline.map(|(deadline, _)| deadline.saturating_duration_since(Instant::now()))
+}
+
+#[cfg(unix)]
+fn signal_name(signal: i32) -> String {
+    match signal {
+        libc::SIGINT => "SIGINT".to_string(),
+        libc::SIGTERM => "SIGTERM".to_string(),
+        _ => format!("signal {signal}"),
+    }
+}
+
+#[cfg(not(unix))]
+fn signal_name(signal: i32) -> String {
+    format!("signal {signal}")
+}
+
+// accepts a number of seconds or a number with an `s`, `m`, or `h` suffix (e.g.; `90`, `15m`)
+pub fn parse_timeout(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let (amount, multiplier) = match value.chars().last() {
+        Some('s') => (&value[..value.len() - 1], 1),
+        Some('m') => (&value[..value.len() - 1], 60),
+        Some('h') => (&value[..value.len() - 1], 60 * 60),
+        _ => (value, 1),
+    };
+    match amount.parse::<u64>() {
+        Ok(amount) if amount > 0 => Ok(Duration::from_secs(amount * multiplier)),
+        _ => Err(format!(
+            "`{value}` is not a valid timeout, use a number of seconds or a duration like `90s`, `15m`, or `1h`"
+        )),
+    }
+}
+
+#[derive(Debug)]
+pub enum CancellationError {
+    Signal(String),
+    TimedOut(Duration),
+}
+
+impl Display for CancellationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CancellationError::Signal(signal) => {
+                write!(
+                    f,
+                    "Cancelled after receiving {signal}, no further changes were written"
+                )
+            }
+            CancellationError::TimedOut(timeout) => {
+                write!(
+                    f,
+                    "Cancelled after exceeding the {}s timeout, no further changes were written",
+                    timeout.as_secs()
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cancellation::parse_timeout;
+    use std::time::Duration;
+
+    #[test]
+    fn test_parse_timeout() {
+        assert_eq!(parse_timeout("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_timeout("45s"), Ok(Duration::from_secs(45)));
+        assert_eq!(parse_timeout("15m"), Ok(Duration::from_secs(900)));
+        assert_eq!(parse_timeout("1h"), Ok(Duration::from_secs(3600)));
+        assert!(parse_timeout("0").is_err());
+        assert!(parse_timeout("ten").is_err());
+        assert!(parse_timeout("").is_err());
+    }
+}
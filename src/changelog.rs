@@ -1,6 +1,13 @@
-use chrono::{DateTime, LocalResult, TimeZone, Utc};
+use crate::clock::Clock;
+use crate::debug_artifacts::capture_markdown_failure;
+use crate::patch::{apply_patches, Patch, PatchError};
+use crate::progress::{track, ProgressStep};
+use crate::versioning::VersionScheme;
+use chrono::{DateTime, LocalResult, NaiveDate, NaiveTime, TimeZone, Utc};
+use clap::{Args, ValueEnum};
 use indexmap::IndexMap;
 use lazy_static::lazy_static;
+use libcnb_data::buildpack::{BuildpackId, BuildpackVersion};
 use markdown::mdast::Node;
 use markdown::{to_mdast, ParseOptions};
 use regex::Regex;
@@ -8,126 +15,342 @@ use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::num::ParseIntError;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+lazy_static! {
+    static ref UNRELEASED_HEADER: Regex =
+        Regex::new(r"(?i)^\[?unreleased]?$").expect("Should be a valid regex");
+    static ref VERSION_HEADER: Regex =
+        Regex::new(r"^\[?(\d+(?:\.\d+\.\d+)?)]?.*(\d{4})[-/](\d{2})[-/](\d{2})")
+            .expect("Should be a valid regex");
+    static ref HEADING_ANCHOR: Regex =
+        Regex::new(r#"\s(\{#[^}\s]+}|<a\s[^>]*>\s*</a>)\s*$"#).expect("Should be a valid regex");
+    static ref CVE_ANNOTATION: Regex =
+        Regex::new(r"(?i)\[(?P<cve>CVE-\d{4}-\d{4,})](?P<link>\([^)]*\))?")
+            .expect("Should be a valid regex");
+}
+
+pub struct ChangelogFile {
+    pub path: PathBuf,
+    pub changelog: Changelog,
+}
+
+pub const DEFAULT_CHANGELOG_NAMES: [&str; 2] = ["CHANGELOG.md", "CHANGES.md"];
+
+pub const RELEASE_DATE_ENV: &str = "RELEASE_DATE";
+
+#[derive(Args, Debug)]
+pub struct ChangelogLocationArgs {
+    #[arg(
+        long = "changelog-name",
+        value_delimiter = ',',
+        default_values_t = DEFAULT_CHANGELOG_NAMES.map(String::from)
+    )]
+    pub changelog_names: Vec<String>,
+}
+
+// the separator used when writing release dates, dates with either separator are always accepted
+// when reading a changelog
+#[derive(ValueEnum, Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum ReleaseDateFormat {
+    #[default]
+    #[value(name = "yyyy-mm-dd")]
+    Dashes,
+    #[value(name = "yyyy/mm/dd")]
+    Slashes,
+}
+
+impl ReleaseDateFormat {
+    pub fn format(&self, date: &DateTime<Utc>) -> String {
+        match self {
+            ReleaseDateFormat::Dashes => date.format("%Y-%m-%d").to_string(),
+            ReleaseDateFormat::Slashes => date.format("%Y/%m/%d").to_string(),
+        }
+    }
+}
+
+impl Display for ReleaseDateFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReleaseDateFormat::Dashes => write!(f, "YYYY-MM-DD"),
+            ReleaseDateFormat::Slashes => write!(f, "YYYY/MM/DD"),
+        }
+    }
+}
+
+// the date written to new release headers, pinning it (e.g.; to the date a pipeline started) keeps a
+// re-run on another day from producing a different diff
+#[derive(Args, Debug)]
+pub struct ReleaseDateArgs {
+    #[arg(long)]
+    pub release_date: Option<NaiveDate>,
+}
+
+impl ReleaseDateArgs {
+    pub fn release_date(&self, clock: &dyn Clock) -> Result<DateTime<Utc>, ChangelogError> {
+        resolve_release_date(self.release_date, clock)
+            .map(|date| Utc.from_utc_datetime(&date.and_time(NaiveTime::default())))
+    }
+}
+
+// an explicit date wins over the environment variable, which wins over today's date in UTC
+pub fn resolve_release_date(
+    release_date: Option<NaiveDate>,
+    clock: &dyn Clock,
+) -> Result<NaiveDate, ChangelogError> {
+    choose_release_date(
+        release_date,
+        std::env::var(RELEASE_DATE_ENV).ok(),
+        clock.now().date_naive(),
+    )
+}
+
+fn choose_release_date(
+    release_date: Option<NaiveDate>,
+    env_value: Option<String>,
+    today: NaiveDate,
+) -> Result<NaiveDate, ChangelogError> {
+    match (
+        release_date,
+        env_value.filter(|value| !value.trim().is_empty()),
+    ) {
+        (Some(release_date), _) => Ok(release_date),
+        (None, Some(value)) => NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+            .map_err(|_| ChangelogError::InvalidReleaseDateOverride(value)),
+        (None, None) => Ok(today),
+    }
+}
+
+impl ChangelogLocationArgs {
+    pub fn find_changelog(
+        &self,
+        buildpack_dir: &Path,
+        root_dir: &Path,
+    ) -> Result<PathBuf, ChangelogFileError> {
+        find_changelog(buildpack_dir, root_dir, &self.changelog_names)
+    }
+
+    pub fn read_changelog_file_from_dir(
+        &self,
+        buildpack_dir: &Path,
+        root_dir: &Path,
+    ) -> Result<ChangelogFile, ChangelogFileError> {
+        read_changelog_file_from_dir(buildpack_dir, root_dir, &self.changelog_names)
+    }
+}
+
+// each name is tried in the buildpack directory before falling back to the repository root
+pub fn find_changelog(
+    buildpack_dir: &Path,
+    root_dir: &Path,
+    changelog_names: &[String],
+) -> Result<PathBuf, ChangelogFileError> {
+    let mut search_dirs = vec![buildpack_dir];
+    if root_dir != buildpack_dir {
+        search_dirs.push(root_dir);
+    }
+
+    let candidates = search_dirs
+        .into_iter()
+        .flat_map(|dir| changelog_names.iter().map(|name| dir.join(name)))
+        .collect::<Vec<_>>();
+
+    match candidates.iter().find(|path| path.is_file()) {
+        Some(path) => Ok(path.clone()),
+        None => Err(ChangelogFileError::NotFound(candidates)),
+    }
+}
+
+pub fn read_changelog_file_from_dir(
+    buildpack_dir: &Path,
+    root_dir: &Path,
+    changelog_names: &[String],
+) -> Result<ChangelogFile, ChangelogFileError> {
+    read_changelog_file(find_changelog(buildpack_dir, root_dir, changelog_names)?)
+}
+
+pub fn read_changelog_file(path: PathBuf) -> Result<ChangelogFile, ChangelogFileError> {
+    track(ProgressStep::Parse, path.display(), || {
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|e| ChangelogFileError::Reading(path.clone(), e))?;
+        Changelog::try_from(contents.as_str()).map_err(|e| {
+            capture_markdown_failure(&path, &contents, &e);
+            ChangelogFileError::Parsing(path.clone(), e)
+        })
+    })
+    .map(|changelog| ChangelogFile { path, changelog })
+}
 
 #[derive(Debug, Eq, PartialEq)]
-pub(crate) struct Changelog {
-    pub(crate) unreleased: Option<String>,
-    pub(crate) releases: IndexMap<String, ReleaseEntry>,
+pub struct Changelog {
+    pub unreleased: Option<String>,
+    pub releases: IndexMap<String, ReleaseEntry>,
 }
 
 impl TryFrom<&str> for Changelog {
     type Error = ChangelogError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        lazy_static! {
-            static ref UNRELEASED_HEADER: Regex =
-                Regex::new(r"(?i)^\[?unreleased]?$").expect("Should be a valid regex");
-            static ref VERSION_HEADER: Regex =
-                Regex::new(r"^\[?(\d+\.\d+\.\d+)]?.*(\d{4})[-/](\d{2})[-/](\d{2})")
-                    .expect("Should be a valid regex");
-        }
-
-        let changelog_ast =
-            to_mdast(value, &ParseOptions::default()).map_err(ChangelogError::Parse)?;
-
-        let mut current_header: Option<String> = None;
-        let mut headers: Vec<String> = vec![];
-        let mut body_nodes_by_header: HashMap<String, Vec<&Node>> = HashMap::new();
-
-        if let Node::Root(root) = changelog_ast {
-            for child in &root.children {
-                if let Node::Heading(heading) = child {
-                    match heading.depth.cmp(&2) {
-                        Ordering::Equal => {
-                            headers.push(child.to_string());
-                            current_header = Some(child.to_string());
-                        }
-                        Ordering::Less => {
-                            current_header = None;
+        parse_changelog(value, false)
+    }
+}
+
+impl Changelog {
+    // the same as `try_from` except release sections whose date isn't a real calendar date (e.g.;
+    // `2023-02-30`) are left out instead of failing the whole changelog
+    pub fn parse_lenient(value: &str) -> Result<Changelog, ChangelogError> {
+        parse_changelog(value, true)
+    }
+
+    pub fn latest_release(&self) -> Option<&ReleaseEntry> {
+        self.releases.values().reduce(|latest, entry| {
+            if entry.date > latest.date {
+                entry
+            } else {
+                latest
+            }
+        })
+    }
+}
+
+fn parse_changelog(value: &str, lenient: bool) -> Result<Changelog, ChangelogError> {
+    let changelog_ast = to_mdast(value, &ParseOptions::default()).map_err(ChangelogError::Parse)?;
+
+    let mut current_header: Option<String> = None;
+    let mut headers: Vec<String> = vec![];
+    let mut body_nodes_by_header: HashMap<String, Vec<&Node>> = HashMap::new();
+    let mut anchors_by_header: HashMap<String, String> = HashMap::new();
+
+    if let Node::Root(root) = changelog_ast {
+        for child in &root.children {
+            if let Node::Heading(heading) = child {
+                match heading.depth.cmp(&2) {
+                    Ordering::Equal => {
+                        if let Some(anchor) = child
+                            .position()
+                            .and_then(|position| {
+                                value.get(position.start.offset..position.end.offset)
+                            })
+                            .and_then(|line| HEADING_ANCHOR.captures(line))
+                        {
+                            anchors_by_header.insert(child.to_string(), anchor[1].to_string());
                         }
-                        _ => {
-                            if let Some(header) = &current_header {
-                                let body_nodes = body_nodes_by_header
-                                    .entry(header.clone())
-                                    .or_insert_with(Vec::new);
-                                body_nodes.push(child);
-                            }
+                        headers.push(child.to_string());
+                        current_header = Some(child.to_string());
+                    }
+                    Ordering::Less => {
+                        current_header = None;
+                    }
+                    _ => {
+                        if let Some(header) = &current_header {
+                            let body_nodes =
+                                body_nodes_by_header.entry(header.clone()).or_default();
+                            body_nodes.push(child);
                         }
                     }
-                } else if let Node::Definition(_) = child {
-                    // ignore any defined links, these will be regenerated at display time
-                } else if let Some(header) = &current_header {
-                    let body_nodes = body_nodes_by_header
-                        .entry(header.clone())
-                        .or_insert_with(Vec::new);
-                    body_nodes.push(child);
                 }
+            } else if let Node::Definition(_) = child {
+                // ignore any defined links, these will be regenerated at display time
+            } else if let Some(header) = &current_header {
+                let body_nodes = body_nodes_by_header.entry(header.clone()).or_default();
+                body_nodes.push(child);
             }
+        }
 
-            let mut unreleased = None;
-            let mut releases = IndexMap::new();
-
-            for header in headers {
-                let empty_nodes = vec![];
-                let body_nodes = body_nodes_by_header.get(&header).unwrap_or(&empty_nodes);
-
-                let start = body_nodes
-                    .iter()
-                    .next()
-                    .map(|node| node.position().map(|position| position.start.offset))
-                    .unwrap_or_default();
-                let end = body_nodes
-                    .iter()
-                    .last()
-                    .map(|node| node.position().map(|position| position.end.offset))
-                    .unwrap_or_default();
-
-                let body = if let (Some(start), Some(end)) = (start, end) {
-                    &value[start..end]
-                } else {
-                    ""
+        let mut unreleased = None;
+        let mut releases = IndexMap::new();
+
+        for header in headers {
+            let empty_nodes = vec![];
+            let body_nodes = body_nodes_by_header.get(&header).unwrap_or(&empty_nodes);
+
+            let start = body_nodes
+                .iter()
+                .next()
+                .map(|node| node.position().map(|position| position.start.offset))
+                .unwrap_or_default();
+            let end = body_nodes
+                .iter()
+                .last()
+                .map(|node| node.position().map(|position| position.end.offset))
+                .unwrap_or_default();
+
+            let body = if let (Some(start), Some(end)) = (start, end) {
+                &value[start..end]
+            } else {
+                ""
+            };
+
+            let body = body.trim().to_string();
+
+            if UNRELEASED_HEADER.is_match(&header) && !body.is_empty() {
+                unreleased = Some(body);
+            } else if let Some(captures) = VERSION_HEADER.captures(&header) {
+                let version = captures[1].to_string();
+                let date = match parse_release_date(&captures[2], &captures[3], &captures[4]) {
+                    Ok(date) => date,
+                    Err(_) if lenient => continue,
+                    Err(error) => return Err(error),
                 };
-
-                let body = body.trim().to_string();
-
-                if UNRELEASED_HEADER.is_match(&header) && !body.is_empty() {
-                    unreleased = Some(body);
-                } else if let Some(captures) = VERSION_HEADER.captures(&header) {
-                    let version = captures[1].to_string();
-                    let year = captures[2]
-                        .parse::<i32>()
-                        .map_err(ChangelogError::ParseReleaseEntryYear)?;
-                    let month = captures[3]
-                        .parse::<u32>()
-                        .map_err(ChangelogError::ParseReleaseEntryMonth)?;
-                    let day = captures[4]
-                        .parse::<u32>()
-                        .map_err(ChangelogError::ParseReleaseEntryDay)?;
-                    let date = match Utc.with_ymd_and_hms(year, month, day, 0, 0, 0) {
-                        LocalResult::None => Err(ChangelogError::InvalidReleaseDate),
-                        LocalResult::Single(value) => Ok(value),
-                        LocalResult::Ambiguous(_, _) => Err(ChangelogError::AmbiguousReleaseDate),
-                    }?;
-                    let release_entry = ReleaseEntry {
-                        version: version.clone(),
-                        body,
-                        date,
-                    };
-                    releases.insert(version, release_entry);
-                }
+                let release_entry = ReleaseEntry {
+                    version: version.clone(),
+                    body,
+                    date,
+                    anchor: anchors_by_header.get(&header).cloned(),
+                };
+                releases.insert(version, release_entry);
             }
+        }
 
-            Ok(Changelog {
-                unreleased,
-                releases,
-            })
-        } else {
-            Err(ChangelogError::NoRootNode)
+        Ok(Changelog {
+            unreleased,
+            releases,
+        })
+    } else {
+        Err(ChangelogError::NoRootNode)
+    }
+}
+
+fn parse_release_date(year: &str, month: &str, day: &str) -> Result<DateTime<Utc>, ChangelogError> {
+    let year = year
+        .parse::<i32>()
+        .map_err(ChangelogError::ParseReleaseEntryYear)?;
+    let month = month
+        .parse::<u32>()
+        .map_err(ChangelogError::ParseReleaseEntryMonth)?;
+    let day = day
+        .parse::<u32>()
+        .map_err(ChangelogError::ParseReleaseEntryDay)?;
+    match Utc.with_ymd_and_hms(year, month, day, 0, 0, 0) {
+        LocalResult::None => Err(ChangelogError::InvalidReleaseDate),
+        LocalResult::Single(value) => Ok(value),
+        LocalResult::Ambiguous(_, _) => Err(ChangelogError::AmbiguousReleaseDate),
+    }
+}
+
+impl Changelog {
+    pub fn display_with(&self, date_format: ReleaseDateFormat) -> ChangelogDisplay<'_> {
+        ChangelogDisplay {
+            changelog: self,
+            date_format,
         }
     }
 }
 
 impl Display for Changelog {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.display_with(ReleaseDateFormat::default()).fmt(f)
+    }
+}
+
+pub struct ChangelogDisplay<'a> {
+    changelog: &'a Changelog,
+    date_format: ReleaseDateFormat,
+}
+
+impl Display for ChangelogDisplay<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
@@ -143,18 +366,23 @@ and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0
             .trim()
         )?;
 
-        if let Some(unreleased) = &self.unreleased {
+        if let Some(unreleased) = &self.changelog.unreleased {
             write!(f, "\n\n## [Unreleased]\n\n{}", unreleased.trim())?;
         } else {
             write!(f, "\n\n## [Unreleased]")?;
         }
 
-        for entry in self.releases.values() {
+        for entry in self.changelog.releases.values() {
             write!(
                 f,
-                "\n\n## [{}] - {}\n\n{}",
+                "\n\n## [{}] - {}{}\n\n{}",
                 entry.version,
-                entry.date.format("%Y-%m-%d"),
+                self.date_format.format(&entry.date),
+                entry
+                    .anchor
+                    .as_ref()
+                    .map(|anchor| format!(" {anchor}"))
+                    .unwrap_or_default(),
                 entry.body.trim()
             )?;
         }
@@ -163,15 +391,122 @@ and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0
     }
 }
 
+impl Changelog {
+    pub fn normalized(&self) -> Changelog {
+        let mut releases = self.releases.values().cloned().collect::<Vec<_>>();
+        releases.sort_by(|a, b| {
+            version_coordinates(&b.version)
+                .cmp(&version_coordinates(&a.version))
+                .then(b.date.cmp(&a.date))
+        });
+
+        Changelog {
+            unreleased: self
+                .unreleased
+                .as_ref()
+                .map(|body| normalize_body(body))
+                .filter(|body| !body.is_empty()),
+            releases: releases
+                .into_iter()
+                .map(|entry| {
+                    (
+                        entry.version.clone(),
+                        ReleaseEntry {
+                            body: normalize_body(&entry.body),
+                            ..entry
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+fn version_coordinates(version: &str) -> Vec<u64> {
+    version
+        .split('.')
+        .map(|coordinate| coordinate.parse().unwrap_or_default())
+        .collect()
+}
+
+fn normalize_body(body: &str) -> String {
+    lazy_static! {
+        static ref BULLET: Regex = Regex::new(r"^(\s*)[*+](\s+)").expect("Should be a valid regex");
+        static ref HEADING: Regex =
+            Regex::new(r"^(#+)\s+(.*?)\s*$").expect("Should be a valid regex");
+    }
+
+    let mut lines: Vec<String> = vec![];
+    let mut in_code_block = false;
+
+    for line in body.lines() {
+        let line = line.trim_end();
+
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            lines.push(line.to_string());
+            continue;
+        }
+
+        if in_code_block {
+            lines.push(line.to_string());
+            continue;
+        }
+
+        if line.is_empty() {
+            if lines.last().filter(|last| last.is_empty()).is_none() {
+                lines.push(String::new());
+            }
+            continue;
+        }
+
+        if let Some(captures) = HEADING.captures(line) {
+            if lines.last().filter(|last| !last.is_empty()).is_some() {
+                lines.push(String::new());
+            }
+            lines.push(format!(
+                "{} {}",
+                &captures[1],
+                normalize_heading_text(&captures[2])
+            ));
+            lines.push(String::new());
+            continue;
+        }
+
+        lines.push(BULLET.replace(line, "$1-$2").to_string());
+    }
+
+    lines.join("\n").trim().to_string()
+}
+
+const CHANGE_TYPES: [&str; 6] = [
+    "Added",
+    "Changed",
+    "Deprecated",
+    "Removed",
+    "Fixed",
+    "Security",
+];
+
+fn normalize_heading_text(text: &str) -> String {
+    CHANGE_TYPES
+        .iter()
+        .find(|change_type| change_type.eq_ignore_ascii_case(text))
+        .map(|change_type| change_type.to_string())
+        .unwrap_or_else(|| text.to_string())
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
-pub(crate) struct ReleaseEntry {
-    pub(crate) version: String,
-    pub(crate) date: DateTime<Utc>,
-    pub(crate) body: String,
+pub struct ReleaseEntry {
+    pub version: String,
+    pub date: DateTime<Utc>,
+    pub body: String,
+    // explicit heading anchor markup kept after the date (e.g.; `{#v1-2-3}`)
+    pub anchor: Option<String>,
 }
 
 #[derive(Debug)]
-pub(crate) enum ChangelogError {
+pub enum ChangelogError {
     NoRootNode,
     Parse(String),
     ParseReleaseEntryYear(ParseIntError),
@@ -179,6 +514,11 @@ pub(crate) enum ChangelogError {
     ParseReleaseEntryDay(ParseIntError),
     InvalidReleaseDate,
     AmbiguousReleaseDate,
+    NoUnreleasedSection,
+    Patch(PatchError),
+    ReleaseNotFound(String),
+    InvalidVersionRange(String),
+    InvalidReleaseDateOverride(String),
 }
 
 impl Display for ChangelogError {
@@ -205,11 +545,803 @@ impl Display for ChangelogError {
             ChangelogError::AmbiguousReleaseDate => {
                 write!(f, "Ambiguous date in release entry")
             }
+            ChangelogError::NoUnreleasedSection => {
+                write!(f, "No Unreleased section in changelog")
+            }
+            ChangelogError::Patch(error) => {
+                write!(f, "Could not update changelog - {error}")
+            }
+            ChangelogError::ReleaseNotFound(version) => {
+                write!(f, "No release {version} in changelog")
+            }
+            ChangelogError::InvalidVersionRange(value) => {
+                write!(
+                    f,
+                    "Invalid version range `{value}`, expected `<version>` or `<from>..<to>`"
+                )
+            }
+            ChangelogError::InvalidReleaseDateOverride(value) => {
+                write!(
+                    f,
+                    "Invalid {RELEASE_DATE_ENV} `{value}`, expected a date in the format yyyy-mm-dd"
+                )
+            }
         }
     }
 }
 
-pub(crate) fn generate_release_declarations<S: Into<String>>(
+#[derive(Debug)]
+pub enum ChangelogFileError {
+    NotFound(Vec<PathBuf>),
+    Reading(PathBuf, std::io::Error),
+    Parsing(PathBuf, ChangelogError),
+}
+
+impl Display for ChangelogFileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangelogFileError::NotFound(candidates) => {
+                write!(
+                    f,
+                    "Could not find a changelog, looked for:\n{}",
+                    candidates
+                        .iter()
+                        .map(|path| format!("• {}", path.display()))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            }
+            ChangelogFileError::Reading(path, error) => {
+                write!(
+                    f,
+                    "Could not read changelog\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+            ChangelogFileError::Parsing(path, error) => {
+                write!(
+                    f,
+                    "Could not parse changelog\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+        }
+    }
+}
+
+// a version that already has a section is merged into it, with the unreleased changes after the
+// released ones, so releasing the same version twice never leaves two headings for it
+pub fn promote_changelog_unreleased_to_version(
+    changelog: &Changelog,
+    version: &BuildpackVersion,
+    date: &DateTime<Utc>,
+    updated_dependencies: &[BuildpackId],
+    version_scheme: &dyn VersionScheme,
+    normalize_entries: bool,
+    heading_anchor_template: Option<&str>,
+) -> Changelog {
+    let version = version_scheme.format_version(version);
+
+    let updated_dependencies_text = if updated_dependencies.is_empty() {
+        None
+    } else {
+        Some(
+            updated_dependencies
+                .iter()
+                .map(|id| format!("- Updated `{id}` to `{version}`"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+    };
+
+    let changes_with_dependencies = (&changelog.unreleased, &updated_dependencies_text);
+
+    let body = if let (Some(changes), Some(dependencies)) = changes_with_dependencies {
+        format!("{}\n{}", changes.trim_end(), dependencies)
+    } else if let (Some(changes), None) = changes_with_dependencies {
+        changes.clone()
+    } else if let (None, Some(dependencies)) = changes_with_dependencies {
+        dependencies.clone()
+    } else {
+        "- No changes".to_string()
+    };
+
+    let existing_release_entry = changelog.releases.get(&version);
+
+    let body = match existing_release_entry {
+        Some(existing) if body == "- No changes" => existing.body.clone(),
+        Some(existing) => format!("{}\n{body}", existing.body.trim_end()),
+        None => body,
+    };
+
+    let body = if normalize_entries {
+        normalize_changelog_entries(&body)
+    } else {
+        body
+    };
+
+    let new_release_entry = ReleaseEntry {
+        anchor: heading_anchor_template
+            .map(|template| render_heading_anchor(template, &version))
+            .or_else(|| existing_release_entry.and_then(|existing| existing.anchor.clone())),
+        version: version.clone(),
+        date: *date,
+        body,
+    };
+
+    let mut releases = IndexMap::from([(version.clone(), new_release_entry)]);
+    for (id, entry) in &changelog.releases {
+        if id != &version {
+            releases.insert(id.clone(), entry.clone());
+        }
+    }
+    Changelog {
+        unreleased: None,
+        releases,
+    }
+}
+
+// docs sites derive heading ids from the heading text, which includes the release date, so explicit
+// anchors are needed for links that survive re-rendering, `{slug}` is the version with `.` as `-`
+// (e.g.; `{#v{slug}}` or `<a id="v{slug}"></a>` for `1.2.3` give `{#v1-2-3}` or `<a id="v1-2-3"></a>`)
+pub fn render_heading_anchor(template: &str, version: &str) -> String {
+    let slug = version
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect::<String>();
+    template
+        .replace("{version}", version)
+        .replace("{slug}", &slug)
+}
+
+// removes repeated bullets (e.g. the same dependency bump merged twice) and groups entries under
+// their keep-a-changelog category, sorting bullets by their leading word so similar changes sit together
+pub fn normalize_changelog_entries(body: &str) -> String {
+    let mut sections: Vec<(Option<String>, Vec<String>, bool)> = vec![(None, vec![], true)];
+    let mut current = 0;
+    let mut seen_entries = vec![];
+
+    for line in body.lines() {
+        if let Some(heading) = line.strip_prefix("### ") {
+            let heading = Some(normalize_heading_text(heading.trim()));
+            current = match sections
+                .iter()
+                .position(|(existing, _, _)| existing == &heading)
+            {
+                Some(index) => index,
+                None => {
+                    sections.push((heading, vec![], true));
+                    sections.len() - 1
+                }
+            };
+            continue;
+        }
+
+        let (_, lines, only_entries) = &mut sections[current];
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if let Some(entry) = get_list_entries(line).first() {
+            if seen_entries.contains(entry) {
+                continue;
+            }
+            seen_entries.push(entry.clone());
+            lines.push(line.trim_end().to_string());
+        } else if line.starts_with(char::is_whitespace) && !lines.is_empty() {
+            let last = lines.last_mut().expect("Should have a line");
+            last.push('\n');
+            last.push_str(line.trim_end());
+        } else {
+            *only_entries = false;
+            lines.push(line.trim_end().to_string());
+        }
+    }
+
+    let category_rank = |heading: &Option<String>| match heading {
+        None => 0,
+        Some(heading) => CHANGE_TYPES
+            .iter()
+            .position(|change_type| change_type == heading)
+            .map(|position| position + 1)
+            .unwrap_or(CHANGE_TYPES.len() + 1),
+    };
+    sections.sort_by_key(|(heading, _, _)| category_rank(heading));
+
+    sections
+        .into_iter()
+        .filter(|(_, lines, _)| !lines.is_empty())
+        .map(|(heading, mut lines, only_entries)| {
+            if only_entries {
+                lines.sort_by_key(|line| entry_prefix(line));
+            }
+            match heading {
+                Some(heading) => format!("### {heading}\n\n{}", lines.join("\n")),
+                None => lines.join("\n"),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn entry_prefix(line: &str) -> String {
+    get_list_entries(line)
+        .first()
+        .and_then(|entry| entry.split_whitespace().next())
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+pub fn find_duplicated_unreleased_entries(changelog: &Changelog) -> Vec<(String, String)> {
+    let unreleased_entries = changelog
+        .unreleased
+        .as_deref()
+        .map(get_list_entries)
+        .unwrap_or_default();
+
+    unreleased_entries
+        .into_iter()
+        .filter_map(|entry| {
+            changelog
+                .releases
+                .values()
+                .find(|release| get_list_entries(&release.body).contains(&entry))
+                .map(|release| (entry, release.version.clone()))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ReleaseDateProblem {
+    InconsistentFormat(String, String, ReleaseDateFormat),
+    OutOfOrder(String, String, String, String),
+}
+
+impl Display for ReleaseDateProblem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReleaseDateProblem::InconsistentFormat(version, date, date_format) => {
+                write!(
+                    f,
+                    "Release {version} has date `{date}` which is not in {date_format} format"
+                )
+            }
+            ReleaseDateProblem::OutOfOrder(version, date, previous_version, previous_date) => {
+                write!(
+                    f,
+                    "Release {version} ({date}) is dated after {previous_version} ({previous_date}) which is listed above it"
+                )
+            }
+        }
+    }
+}
+
+struct ReleaseDate {
+    version: String,
+    written: String,
+    span: Range<usize>,
+    date: DateTime<Utc>,
+}
+
+impl ReleaseDate {
+    fn formatted(&self, date_format: ReleaseDateFormat) -> String {
+        date_format.format(&self.date)
+    }
+}
+
+fn find_release_dates(contents: &str) -> Result<Vec<ReleaseDate>, ChangelogError> {
+    lazy_static! {
+        static ref VERSION_HEADING: Regex =
+            Regex::new(r"^##\s+\[?(\d+(?:\.\d+\.\d+)?)]?.*((\d{4})[-/](\d{2})[-/](\d{2}))")
+                .expect("Should be a valid regex");
+    }
+
+    let root = match to_mdast(contents, &ParseOptions::default()).map_err(ChangelogError::Parse)? {
+        Node::Root(root) => root,
+        _ => return Err(ChangelogError::NoRootNode),
+    };
+
+    let mut release_dates = vec![];
+
+    for child in &root.children {
+        let position = match child {
+            Node::Heading(heading) if heading.depth == 2 => child.position(),
+            _ => None,
+        };
+        if let Some(position) = position {
+            let start = position.start.offset;
+            let heading = &contents[start..position.end.offset];
+            if let Some(captures) = VERSION_HEADING.captures(heading) {
+                let written = &captures[2];
+                let offset = captures.get(2).map(|m| m.start()).unwrap_or_default() + start;
+                release_dates.push(ReleaseDate {
+                    version: captures[1].to_string(),
+                    written: written.to_string(),
+                    span: offset..offset + written.len(),
+                    date: parse_release_date(&captures[3], &captures[4], &captures[5])?,
+                });
+            }
+        }
+    }
+
+    Ok(release_dates)
+}
+
+// release dates should all be written in the given format and never increase going down the file
+pub fn find_release_date_problems(
+    contents: &str,
+    date_format: ReleaseDateFormat,
+) -> Result<Vec<ReleaseDateProblem>, ChangelogError> {
+    let release_dates = find_release_dates(contents)?;
+    let mut problems = vec![];
+
+    for (index, release_date) in release_dates.iter().enumerate() {
+        if release_date.written != release_date.formatted(date_format) {
+            problems.push(ReleaseDateProblem::InconsistentFormat(
+                release_date.version.clone(),
+                release_date.written.clone(),
+                date_format,
+            ));
+        }
+        if let Some(previous) = index.checked_sub(1).map(|index| &release_dates[index]) {
+            if release_date.date > previous.date {
+                problems.push(ReleaseDateProblem::OutOfOrder(
+                    release_date.version.clone(),
+                    release_date.formatted(date_format),
+                    previous.version.clone(),
+                    previous.formatted(date_format),
+                ));
+            }
+        }
+    }
+
+    Ok(problems)
+}
+
+// only the date formats are rewritten, the order of releases needs a person to decide which date is wrong
+pub fn fix_release_date_formats(
+    contents: &str,
+    date_format: ReleaseDateFormat,
+) -> Result<String, ChangelogError> {
+    let patches = find_release_dates(contents)?
+        .into_iter()
+        .filter(|release_date| release_date.written != release_date.formatted(date_format))
+        .map(|release_date| {
+            Patch::replace(
+                release_date.span.clone(),
+                release_date.formatted(date_format),
+            )
+        })
+        .collect();
+    apply_patches(contents, patches).map_err(ChangelogError::Patch)
+}
+
+// rewrites bare `#123` references and GitHub pull request or issue URLs as markdown links so entries
+// still point at the right repository when the notes are published somewhere else, existing links,
+// code spans, and fenced code blocks are left alone
+pub fn link_pull_request_references(body: &str, repository_url: &str) -> String {
+    lazy_static! {
+        static ref REFERENCE: Regex = Regex::new(
+            r"(?P<skip>`[^`]*`|\[[^\]]*]\([^)]*\)|<[^>]*>)|(?P<url>https?://github\.com/(?P<url_repository>[\w.-]+/[\w.-]+)/(?:pull|issues)/(?P<url_number>\d+))|(?P<prefix>^|[^\w&/\[])#(?P<number>\d+)\b"
+        )
+        .expect("Should be a valid regex");
+    }
+
+    let repository_url = repository_url.trim_end_matches('/');
+    let repository = repository_url
+        .splitn(4, '/')
+        .nth(3)
+        .unwrap_or_default()
+        .trim_end_matches(".git");
+
+    let mut in_code_block = false;
+
+    body.split('\n')
+        .map(|line| {
+            if line.trim_start().starts_with("```") {
+                in_code_block = !in_code_block;
+                return line.to_string();
+            }
+            if in_code_block {
+                return line.to_string();
+            }
+            REFERENCE
+                .replace_all(line, |captures: &regex::Captures| {
+                    if let Some(skip) = captures.name("skip") {
+                        skip.as_str().to_string()
+                    } else if let Some(url) = captures.name("url") {
+                        let url_repository = &captures["url_repository"];
+                        let number = &captures["url_number"];
+                        if url_repository.eq_ignore_ascii_case(repository) {
+                            format!("[#{number}]({})", url.as_str())
+                        } else {
+                            format!("[{url_repository}#{number}]({})", url.as_str())
+                        }
+                    } else {
+                        let number = &captures["number"];
+                        format!(
+                            "{}[#{number}]({repository_url}/pull/{number})",
+                            &captures["prefix"]
+                        )
+                    }
+                })
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn get_list_entries(body: &str) -> Vec<String> {
+    body.lines()
+        .filter_map(|line| {
+            line.strip_prefix("- ")
+                .or_else(|| line.strip_prefix("* "))
+                .or_else(|| line.strip_prefix("+ "))
+        })
+        .map(|entry| entry.trim().to_string())
+        .filter(|entry| !entry.is_empty())
+        .collect()
+}
+
+// an entry from a `### Security` subsection, `cves` holds the ids of its `[CVE-2024-1234]` annotations
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SecurityEntry {
+    pub entry: String,
+    pub cves: Vec<String>,
+}
+
+pub fn get_security_entries(body: &str) -> Vec<SecurityEntry> {
+    let mut in_security_section = false;
+    let mut security_lines = vec![];
+
+    for line in body.lines() {
+        if let Some(heading) = line.strip_prefix("### ") {
+            in_security_section = normalize_heading_text(heading.trim()) == "Security";
+        } else if line.starts_with('#') {
+            in_security_section = false;
+        } else if in_security_section {
+            security_lines.push(line);
+        }
+    }
+
+    get_list_entries(&security_lines.join("\n"))
+        .into_iter()
+        .map(|entry| SecurityEntry {
+            cves: get_cve_ids(&entry),
+            entry,
+        })
+        .collect()
+}
+
+pub fn get_cve_ids(entry: &str) -> Vec<String> {
+    let mut cves = vec![];
+    for captures in CVE_ANNOTATION.captures_iter(entry) {
+        let cve = captures["cve"].to_uppercase();
+        if !cves.contains(&cve) {
+            cves.push(cve);
+        }
+    }
+    cves
+}
+
+// `[CVE-2024-1234]` annotations become links to the CVE record, annotations that are already links
+// are left alone
+pub fn link_cve_references(entry: &str) -> String {
+    CVE_ANNOTATION
+        .replace_all(entry, |captures: &regex::Captures| {
+            if captures.name("link").is_some() {
+                captures[0].to_string()
+            } else {
+                let cve = captures["cve"].to_uppercase();
+                format!("[{cve}](https://www.cve.org/CVERecord?id={cve})")
+            }
+        })
+        .to_string()
+}
+
+pub fn insert_unreleased_change(contents: &str, change: &str) -> Result<String, ChangelogError> {
+    let root = match to_mdast(contents, &ParseOptions::default()).map_err(ChangelogError::Parse)? {
+        Node::Root(root) => root,
+        _ => return Err(ChangelogError::NoRootNode),
+    };
+
+    let is_section_heading =
+        |node: &Node| matches!(node, Node::Heading(heading) if heading.depth <= 2);
+
+    let mut nodes = root.children.iter().skip_while(|node| {
+        !(matches!(node, Node::Heading(heading) if heading.depth == 2)
+            && UNRELEASED_HEADER.is_match(&node.to_string()))
+    });
+
+    let unreleased_heading = nodes.next().ok_or(ChangelogError::NoUnreleasedSection)?;
+
+    let last_node = nodes
+        .take_while(|node| !is_section_heading(node))
+        .filter(|node| !matches!(node, Node::Definition(_)))
+        .last()
+        .unwrap_or(unreleased_heading);
+
+    let position = last_node
+        .position()
+        .ok_or(ChangelogError::NoUnreleasedSection)?;
+
+    let insertion = if let Node::List(_) = last_node {
+        let marker = contents[position.start.offset..]
+            .chars()
+            .next()
+            .unwrap_or('-');
+        format!("\n{marker} {change}")
+    } else {
+        format!("\n\n- {change}")
+    };
+
+    apply_patches(
+        contents,
+        vec![Patch::insert(
+            contents[..position.end.offset].trim_end().len(),
+            insertion,
+        )],
+    )
+    .map_err(ChangelogError::Patch)
+}
+
+const ROOT_CHANGELOG_HEADER: &str = "# Changelog
+
+All notable changes to the buildpacks in this repository are summarized in this file. Each release lists the changes
+from the changelog of every buildpack included in it.";
+
+// a release of the repository lists each buildpack's changes under a heading for the buildpack, so the
+// headings from the buildpack changelog (e.g.; `### Added`) are shifted down a level to sit below it
+pub fn render_root_changelog_release(
+    version: &str,
+    date: &DateTime<Utc>,
+    entries: &[(BuildpackId, String)],
+    date_format: ReleaseDateFormat,
+) -> String {
+    lazy_static! {
+        static ref BODY_HEADING: Regex =
+            Regex::new(r"(?m)^(#{1,5})\s").expect("Should be a valid regex");
+    }
+
+    let mut entries = entries
+        .iter()
+        .filter(|(_, body)| !body.trim().is_empty() && body.trim() != "- No changes")
+        .collect::<Vec<_>>();
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let body = if entries.is_empty() {
+        "- No changes".to_string()
+    } else {
+        entries
+            .iter()
+            .map(|(buildpack_id, body)| {
+                format!(
+                    "### {buildpack_id}\n\n{}",
+                    BODY_HEADING.replace_all(body.trim(), "#$1 ")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    };
+
+    format!("## [{version}] - {}\n\n{body}", date_format.format(date))
+}
+
+// rebuilds the whole root changelog from the release entries of each buildpack changelog, releases
+// are grouped by version and dated by the latest buildpack release with that version
+pub fn compile_root_changelog(
+    changelogs: &[(BuildpackId, Changelog)],
+    date_format: ReleaseDateFormat,
+) -> String {
+    let mut releases = IndexMap::new();
+    for (buildpack_id, changelog) in changelogs {
+        for entry in changelog.releases.values() {
+            let (date, entries) = releases
+                .entry(entry.version.clone())
+                .or_insert_with(|| (entry.date, vec![]));
+            *date = (*date).max(entry.date);
+            entries.push((buildpack_id.clone(), entry.body.clone()));
+        }
+    }
+
+    releases.sort_by(|version_a, (date_a, _), version_b, (date_b, _)| {
+        date_b
+            .cmp(date_a)
+            .then(version_coordinates(version_b).cmp(&version_coordinates(version_a)))
+    });
+
+    let releases = releases
+        .iter()
+        .map(|(version, (date, entries))| {
+            render_root_changelog_release(version, date, entries, date_format)
+        })
+        .collect::<Vec<_>>();
+
+    if releases.is_empty() {
+        format!("{ROOT_CHANGELOG_HEADER}\n")
+    } else {
+        format!("{ROOT_CHANGELOG_HEADER}\n\n{}\n", releases.join("\n\n"))
+    }
+}
+
+// adds a release rendered by `render_root_changelog_release` above the newest release, a release with
+// the same version is replaced so running a release again doesn't duplicate it
+pub fn insert_root_changelog_release(
+    contents: &str,
+    version: &str,
+    release: &str,
+) -> Result<String, ChangelogError> {
+    if contents.trim().is_empty() {
+        return Ok(format!("{ROOT_CHANGELOG_HEADER}\n\n{release}\n"));
+    }
+
+    let root = match to_mdast(contents, &ParseOptions::default()).map_err(ChangelogError::Parse)? {
+        Node::Root(root) => root,
+        _ => return Err(ChangelogError::NoRootNode),
+    };
+
+    let release_headings = root
+        .children
+        .iter()
+        .filter(|node| matches!(node, Node::Heading(heading) if heading.depth <= 2))
+        .filter_map(|node| {
+            node.position()
+                .map(|position| (node, position.start.offset))
+        })
+        .skip_while(|(node, _)| matches!(node, Node::Heading(heading) if heading.depth == 1))
+        .collect::<Vec<_>>();
+
+    let existing = release_headings.iter().enumerate().find(|(_, (node, _))| {
+        let heading = node.to_string();
+        heading.trim_start_matches('[').split([']', ' ']).next() == Some(version)
+    });
+
+    let patch = match existing {
+        Some((index, (_, start))) => {
+            let end = release_headings
+                .get(index + 1)
+                .map(|(_, offset)| *offset)
+                .unwrap_or(contents.len());
+            let separator = if end == contents.len() { "\n" } else { "\n\n" };
+            Patch::replace(*start..end, format!("{release}{separator}"))
+        }
+        None => match release_headings.first() {
+            Some((_, start)) => Patch::insert(*start, format!("{release}\n\n")),
+            None => Patch::insert(contents.trim_end().len(), format!("\n\n{release}")),
+        },
+    };
+
+    apply_patches(contents, vec![patch]).map_err(ChangelogError::Patch)
+}
+
+pub fn get_link_definitions(contents: &str) -> Result<Vec<String>, String> {
+    Ok(match to_mdast(contents, &ParseOptions::default())? {
+        Node::Root(root) => root
+            .children
+            .iter()
+            .filter(|child| matches!(child, Node::Definition(_)))
+            .filter_map(|child| child.position())
+            .map(|position| contents[position.start.offset..position.end.offset].to_string())
+            .collect(),
+        _ => vec![],
+    })
+}
+
+// a version or a range of versions like git's `<from>..<to>`, the releases after `from` up to and
+// including `to`, either end can be left out
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum VersionRange {
+    Version(String),
+    Range(Option<String>, Option<String>),
+}
+
+impl FromStr for VersionRange {
+    type Err = ChangelogError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let is_version = |version: &str| {
+            version
+                .split('.')
+                .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+        };
+        let bound = |version: &str| match version {
+            "" => Ok(None),
+            version if is_version(version) => Ok(Some(version.to_string())),
+            _ => Err(ChangelogError::InvalidVersionRange(value.to_string())),
+        };
+
+        match value.split_once("..") {
+            Some((from, to)) => Ok(VersionRange::Range(bound(from)?, bound(to)?)),
+            None if is_version(value) => Ok(VersionRange::Version(value.to_string())),
+            None => Err(ChangelogError::InvalidVersionRange(value.to_string())),
+        }
+    }
+}
+
+// a release section as it's written in the changelog, `heading` is the `## ...` line and `body` is
+// everything up to the next heading, link definitions after the last release are left out
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ChangelogSection {
+    pub version: String,
+    pub heading: String,
+    pub body: String,
+}
+
+// the original text is sliced out of the changelog (rather than rendered from `Changelog`) so the
+// sections keep their exact formatting, sections are returned newest first like in the changelog
+pub fn extract_changelog_sections(
+    contents: &str,
+    range: &VersionRange,
+) -> Result<Vec<ChangelogSection>, ChangelogError> {
+    let root = match to_mdast(contents, &ParseOptions::default()).map_err(ChangelogError::Parse)? {
+        Node::Root(root) => root,
+        _ => Err(ChangelogError::NoRootNode)?,
+    };
+
+    let mut sections: Vec<(String, Range<usize>, Option<usize>)> = vec![];
+    let mut in_release = false;
+    for child in &root.children {
+        let position = match child.position() {
+            Some(position) => position.start.offset..position.end.offset,
+            None => continue,
+        };
+        match child {
+            Node::Heading(heading) if heading.depth <= 2 => {
+                in_release = false;
+                if heading.depth == 2 {
+                    if let Some(captures) = VERSION_HEADER.captures(&child.to_string()) {
+                        sections.push((captures[1].to_string(), position, None));
+                        in_release = true;
+                    }
+                }
+            }
+            Node::Definition(_) => {}
+            _ if in_release => {
+                if let Some((_, _, end)) = sections.last_mut() {
+                    *end = Some(position.end);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let index_of = |version: &str| {
+        sections
+            .iter()
+            .position(|(section_version, _, _)| section_version == version)
+            .ok_or_else(|| ChangelogError::ReleaseNotFound(version.to_string()))
+    };
+    let selected = match range {
+        VersionRange::Version(version) => {
+            let index = index_of(version)?;
+            index..index + 1
+        }
+        VersionRange::Range(from, to) => {
+            let start = to.as_deref().map(index_of).transpose()?.unwrap_or(0);
+            let end = from
+                .as_deref()
+                .map(index_of)
+                .transpose()?
+                .unwrap_or(sections.len());
+            start..end.max(start)
+        }
+    };
+
+    Ok(sections[selected]
+        .iter()
+        .map(|(version, heading, end)| ChangelogSection {
+            version: version.clone(),
+            heading: contents[heading.clone()].to_string(),
+            body: end
+                .map(|end| contents[heading.end..end].trim().to_string())
+                .unwrap_or_default(),
+        })
+        .collect())
+}
+
+pub fn generate_release_declarations<S: Into<String>>(
     changelog: &Changelog,
     repository: S,
 ) -> String {
@@ -242,8 +1374,77 @@ pub(crate) fn generate_release_declarations<S: Into<String>>(
 
 #[cfg(test)]
 mod test {
-    use crate::changelog::{generate_release_declarations, Changelog};
-    use chrono::{TimeZone, Utc};
+    use crate::changelog::{
+        choose_release_date, compile_root_changelog, extract_changelog_sections, find_changelog,
+        find_duplicated_unreleased_entries, find_release_date_problems, fix_release_date_formats,
+        generate_release_declarations, get_link_definitions, get_security_entries,
+        insert_root_changelog_release, insert_unreleased_change, link_cve_references,
+        link_pull_request_references, normalize_changelog_entries,
+        promote_changelog_unreleased_to_version, render_heading_anchor,
+        render_root_changelog_release, Changelog, ChangelogFileError, ReleaseDateFormat,
+        ReleaseDateProblem, ReleaseEntry, SecurityEntry, VersionRange,
+    };
+    use crate::versioning::SemverScheme;
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use indexmap::IndexMap;
+    use libcnb_data::buildpack::BuildpackVersion;
+    use libcnb_data::buildpack_id;
+    use std::fs;
+
+    #[test]
+    fn test_choose_release_date() {
+        let today = NaiveDate::from_ymd_opt(2024, 6, 2).unwrap();
+        let release_date = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+
+        assert_eq!(
+            choose_release_date(Some(release_date), Some("2024-05-31".to_string()), today).unwrap(),
+            release_date
+        );
+        assert_eq!(
+            choose_release_date(None, Some(" 2024-06-01\n".to_string()), today).unwrap(),
+            release_date
+        );
+        assert_eq!(
+            choose_release_date(None, Some(String::new()), today).unwrap(),
+            today
+        );
+        assert_eq!(choose_release_date(None, None, today).unwrap(), today);
+        assert!(choose_release_date(None, Some("06/01/2024".to_string()), today).is_err());
+    }
+
+    #[test]
+    fn test_find_changelog() {
+        let project_dir =
+            std::env::temp_dir().join(format!("find-changelog-{}", std::process::id()));
+        let buildpack_dir = project_dir.join("buildpacks/a");
+        fs::create_dir_all(&buildpack_dir).unwrap();
+        fs::write(project_dir.join("CHANGELOG.md"), "").unwrap();
+
+        let names = ["CHANGELOG.md".to_string(), "CHANGES.md".to_string()];
+        assert_eq!(
+            find_changelog(&buildpack_dir, &project_dir, &names).unwrap(),
+            project_dir.join("CHANGELOG.md")
+        );
+
+        fs::write(buildpack_dir.join("CHANGES.md"), "").unwrap();
+        assert_eq!(
+            find_changelog(&buildpack_dir, &project_dir, &names).unwrap(),
+            buildpack_dir.join("CHANGES.md")
+        );
+
+        match find_changelog(&buildpack_dir, &project_dir, &["HISTORY.md".to_string()]) {
+            Err(ChangelogFileError::NotFound(candidates)) => assert_eq!(
+                candidates,
+                vec![
+                    buildpack_dir.join("HISTORY.md"),
+                    project_dir.join("HISTORY.md")
+                ]
+            ),
+            _ => panic!("Expected changelog not to be found"),
+        }
+
+        fs::remove_dir_all(project_dir).unwrap();
+    }
 
     #[test]
     fn test_keep_a_changelog_unreleased_entry_with_changes_parsing() {
@@ -310,6 +1511,21 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_latest_release() {
+        let changelog = Changelog::try_from(
+            "## [Unreleased]\n\n## [1.0.1] 2023/05/10\n- Fix\n\n## [1.1.0] 2023/06/01\n- Feature\n\n## [1.0.0] 2023/04/01\n- Initial",
+        )
+        .unwrap();
+        assert_eq!(changelog.latest_release().unwrap().version, "1.1.0");
+        assert_eq!(
+            Changelog::try_from("## [Unreleased]")
+                .unwrap()
+                .latest_release(),
+            None
+        );
+    }
+
     #[test]
     fn test_release_entry_parsing_with_alternate_date_format() {
         let changelog = Changelog::try_from(
@@ -323,25 +1539,144 @@ mod test {
             Utc.with_ymd_and_hms(2023, 5, 10, 0, 0, 0).unwrap()
         );
         assert_eq!(release_entry.body, "- Upgrade libcnb to 0.12.0");
+        assert!(changelog
+            .to_string()
+            .contains("\n## [1.0.10] - 2023-05-10\n"));
+        assert!(changelog
+            .display_with(ReleaseDateFormat::Slashes)
+            .to_string()
+            .contains("\n## [1.0.10] - 2023/05/10\n"));
+    }
+
+    #[test]
+    fn test_release_entry_parsing_with_monotonic_version() {
+        let changelog =
+            Changelog::try_from("## [Unreleased]\n\n## [42] - 2023-05-10\n- Some change").unwrap();
+        let release_entry = changelog.releases.get("42").unwrap();
+        assert_eq!(release_entry.version, "42");
+        assert_eq!(release_entry.body, "- Some change");
+    }
+
+    #[test]
+    fn test_lenient_parsing_skips_release_entries_with_invalid_dates() {
+        let contents = "## [Unreleased]\n\nSome notes.\n\n- Some change\n\n## [1.0.1] - 2023-13-45\n\n- Bad date\n\n## [1.0.0] - 2023-05-10\n\n- Initial release";
+        assert!(Changelog::try_from(contents).is_err());
+
+        let changelog = Changelog::parse_lenient(contents).unwrap();
+        assert_eq!(
+            changelog.unreleased,
+            Some("Some notes.\n\n- Some change".to_string())
+        );
+        assert_eq!(changelog.releases.keys().collect::<Vec<_>>(), vec!["1.0.0"]);
+    }
+
+    #[test]
+    fn test_lenient_parsing_matches_strict_parsing_for_valid_dates() {
+        let contents = "## [Unreleased]\n\nSome notes.\n\n- Some change\n\n## [1.0.0] - 2023-05-10\n\nA paragraph.\n\n### Added\n\n- Initial release\n\n> A quote.";
+        assert_eq!(
+            Changelog::parse_lenient(contents).unwrap(),
+            Changelog::try_from(contents).unwrap()
+        );
+        assert_eq!(
+            Changelog::parse_lenient(contents)
+                .unwrap()
+                .releases
+                .get("1.0.0")
+                .map(|release| release.body.as_str()),
+            Some("A paragraph.\n\n### Added\n\n- Initial release\n\n> A quote.")
+        );
     }
 
-    #[test]
-    fn test_keep_a_changelog_parses_all_release_entries() {
-        let changelog = Changelog::try_from(KEEP_A_CHANGELOG_1_0_0).unwrap();
-        let releases = changelog.releases.keys().collect::<Vec<_>>();
-        assert_eq!(
-            releases,
-            vec![
-                "1.1.1", "1.1.0", "1.0.0", "0.3.0", "0.2.0", "0.1.0", "0.0.8", "0.0.7", "0.0.6",
-                "0.0.5", "0.0.4", "0.0.3", "0.0.2", "0.0.1",
-            ]
+    #[test]
+    fn test_keep_a_changelog_parses_all_release_entries() {
+        let changelog = Changelog::try_from(KEEP_A_CHANGELOG_1_0_0).unwrap();
+        let releases = changelog.releases.keys().collect::<Vec<_>>();
+        assert_eq!(
+            releases,
+            vec![
+                "1.1.1", "1.1.0", "1.0.0", "0.3.0", "0.2.0", "0.1.0", "0.0.8", "0.0.7", "0.0.6",
+                "0.0.5", "0.0.4", "0.0.3", "0.0.2", "0.0.1",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_keep_a_changelog_to_string() {
+        let changelog = Changelog::try_from(KEEP_A_CHANGELOG_1_0_0).unwrap();
+        assert_eq!(changelog.to_string(), KEEP_A_CHANGELOG_1_0_0);
+    }
+
+    #[test]
+    fn test_normalized_changelog() {
+        let changelog = Changelog::try_from(
+            r#"## [unreleased]
+
+* Unreleased change
+
+
++ Another unreleased change
+
+## [1.0.0] - 2023-01-01
+
+### added
+- First change
+
+## [1.0.10] - 2023-03-01
+### fixed
+*   Indented bullet
+```
+* not a bullet
+```
+
+## [1.0.2] - 2023-02-01
+
+- Some change
+"#,
+        )
+        .unwrap()
+        .normalized();
+
+        assert_eq!(
+            changelog.to_string(),
+            r#"# Changelog
+
+All notable changes to this project will be documented in this file.
+
+The format is based on [Keep a Changelog](https://keepachangelog.com/en/1.0.0/),
+and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0.html).
+
+## [Unreleased]
+
+- Unreleased change
+
+- Another unreleased change
+
+## [1.0.10] - 2023-03-01
+
+### Fixed
+
+-   Indented bullet
+```
+* not a bullet
+```
+
+## [1.0.2] - 2023-02-01
+
+- Some change
+
+## [1.0.0] - 2023-01-01
+
+### Added
+
+- First change
+"#
         );
     }
 
     #[test]
-    fn test_keep_a_changelog_to_string() {
+    fn test_normalized_keep_a_changelog_is_unchanged() {
         let changelog = Changelog::try_from(KEEP_A_CHANGELOG_1_0_0).unwrap();
-        assert_eq!(changelog.to_string(), KEEP_A_CHANGELOG_1_0_0);
+        assert_eq!(changelog.normalized().to_string(), KEEP_A_CHANGELOG_1_0_0);
     }
 
     #[test]
@@ -399,6 +1734,290 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_normalize_changelog_entries() {
+        let body = "### Fixed\n\n- Fixed the thing\n\n### Added\n\n- Updated `heroku/nodejs-engine` to `1.2.0`\n- Added node version 20.1.0\n  with a continued line\n- Updated `heroku/nodejs-engine` to `1.2.0`\n\n### added\n\n- Added node version 18.16.0";
+        assert_eq!(
+            normalize_changelog_entries(body),
+            "### Added\n\n- Added node version 20.1.0\n  with a continued line\n- Added node version 18.16.0\n- Updated `heroku/nodejs-engine` to `1.2.0`\n\n### Fixed\n\n- Fixed the thing"
+        );
+        assert_eq!(
+            normalize_changelog_entries(
+                "- Updated `a` to `1.0.0`\n- Fixed b\n- Updated `a` to `1.0.0`"
+            ),
+            "- Fixed b\n- Updated `a` to `1.0.0`"
+        );
+    }
+
+    #[test]
+    fn test_promote_changelog_unreleased_to_version_with_existing_entries() {
+        let release_entry_0_8_16 = ReleaseEntry {
+            version: "0.8.16".to_string(),
+            date: Utc.with_ymd_and_hms(2023, 2, 27, 0, 0, 0).unwrap(),
+            body: "- Added node version 19.7.0, 19.6.1, 14.21.3, 16.19.1, 18.14.1, 18.14.2.\n- Added node version 18.14.0, 19.6.0.".to_string(),
+            anchor: None,
+        };
+
+        let release_entry_0_8_15 = ReleaseEntry {
+            version: "0.8.15".to_string(),
+            date: Utc.with_ymd_and_hms(2023, 2, 27, 0, 0, 0).unwrap(),
+            body: "- `name` is no longer a required field in package.json. ([#447](https://github.com/heroku/buildpacks-nodejs/pull/447))\n- Added node version 19.5.0.".to_string(),
+            anchor: None,
+        };
+
+        let changelog = Changelog {
+            unreleased: Some(
+                "- Added node version 18.15.0.\n- Added yarn version 4.0.0-rc.2".to_string(),
+            ),
+            releases: IndexMap::from([
+                ("0.8.16".to_string(), release_entry_0_8_16.clone()),
+                ("0.8.15".to_string(), release_entry_0_8_15.clone()),
+            ]),
+        };
+
+        assert_eq!(
+            changelog.unreleased,
+            Some("- Added node version 18.15.0.\n- Added yarn version 4.0.0-rc.2".to_string())
+        );
+        assert_eq!(changelog.releases.get("0.8.17"), None);
+        assert_eq!(
+            changelog.releases.get("0.8.16"),
+            Some(&release_entry_0_8_16)
+        );
+        assert_eq!(
+            changelog.releases.get("0.8.15"),
+            Some(&release_entry_0_8_15)
+        );
+
+        let next_version = BuildpackVersion {
+            major: 0,
+            minor: 8,
+            patch: 17,
+        };
+        let date = Utc.with_ymd_and_hms(2023, 6, 16, 0, 0, 0).unwrap();
+        let updated_dependencies = vec![];
+        let changelog = promote_changelog_unreleased_to_version(
+            &changelog,
+            &next_version,
+            &date,
+            &updated_dependencies,
+            &SemverScheme,
+            false,
+            None,
+        );
+
+        assert_eq!(changelog.unreleased, None);
+        assert_eq!(
+            changelog.releases.get("0.8.17"),
+            Some(&ReleaseEntry {
+                version: "0.8.17".to_string(),
+                date,
+                body: "- Added node version 18.15.0.\n- Added yarn version 4.0.0-rc.2".to_string(),
+                anchor: None,
+            })
+        );
+        assert_eq!(
+            changelog.releases.get("0.8.16"),
+            Some(&release_entry_0_8_16)
+        );
+        assert_eq!(
+            changelog.releases.get("0.8.15"),
+            Some(&release_entry_0_8_15)
+        );
+    }
+
+    #[test]
+    fn test_promote_changelog_unreleased_to_existing_version() {
+        let changelog = Changelog::try_from(
+            "## [Unreleased]\n\n- Fixed the late fix\n\n## [1.0.1] - 2023-06-15 {#v1-0-1}\n\n- Fixed the first fix\n\n## [1.0.0] - 2023-06-01\n\n- Initial release\n",
+        )
+        .unwrap();
+        let date = Utc.with_ymd_and_hms(2023, 6, 16, 0, 0, 0).unwrap();
+        let changelog = promote_changelog_unreleased_to_version(
+            &changelog,
+            &BuildpackVersion {
+                major: 1,
+                minor: 0,
+                patch: 1,
+            },
+            &date,
+            &[],
+            &SemverScheme,
+            false,
+            None,
+        );
+
+        assert_eq!(
+            changelog.releases.keys().collect::<Vec<_>>(),
+            vec!["1.0.1", "1.0.0"]
+        );
+        assert_eq!(
+            changelog.releases.get("1.0.1"),
+            Some(&ReleaseEntry {
+                version: "1.0.1".to_string(),
+                date,
+                body: "- Fixed the first fix\n- Fixed the late fix".to_string(),
+                anchor: Some("{#v1-0-1}".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_promote_changelog_unreleased_to_version_with_no_entries() {
+        let changelog = Changelog {
+            unreleased: None,
+            releases: IndexMap::new(),
+        };
+
+        assert_eq!(changelog.unreleased, None);
+        assert_eq!(changelog.releases.get("0.8.17"), None);
+
+        let next_version = BuildpackVersion {
+            major: 0,
+            minor: 8,
+            patch: 17,
+        };
+        let date = Utc.with_ymd_and_hms(2023, 6, 16, 0, 0, 0).unwrap();
+        let updated_dependencies = vec![];
+        let changelog = promote_changelog_unreleased_to_version(
+            &changelog,
+            &next_version,
+            &date,
+            &updated_dependencies,
+            &SemverScheme,
+            false,
+            None,
+        );
+
+        assert_eq!(changelog.unreleased, None);
+        assert_eq!(
+            changelog.releases.get("0.8.17"),
+            Some(&ReleaseEntry {
+                version: "0.8.17".to_string(),
+                date,
+                body: "- No changes".to_string(),
+                anchor: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_promote_changelog_unreleased_to_version_with_existing_entries_and_updated_dependencies()
+    {
+        let release_entry_0_8_16 = ReleaseEntry {
+            version: "0.8.16".to_string(),
+            date: Utc.with_ymd_and_hms(2023, 2, 27, 0, 0, 0).unwrap(),
+            body: "- Added node version 19.7.0, 19.6.1, 14.21.3, 16.19.1, 18.14.1, 18.14.2.\n- Added node version 18.14.0, 19.6.0.".to_string(),
+            anchor: None,
+        };
+
+        let release_entry_0_8_15 = ReleaseEntry {
+            version: "0.8.15".to_string(),
+            date: Utc.with_ymd_and_hms(2023, 2, 27, 0, 0, 0).unwrap(),
+            body: "- `name` is no longer a required field in package.json. ([#447](https://github.com/heroku/buildpacks-nodejs/pull/447))\n- Added node version 19.5.0.".to_string(),
+            anchor: None,
+        };
+
+        let changelog = Changelog {
+            unreleased: Some(
+                "- Added node version 18.15.0.\n- Added yarn version 4.0.0-rc.2".to_string(),
+            ),
+            releases: IndexMap::from([
+                ("0.8.16".to_string(), release_entry_0_8_16.clone()),
+                ("0.8.15".to_string(), release_entry_0_8_15.clone()),
+            ]),
+        };
+
+        assert_eq!(
+            changelog.unreleased,
+            Some("- Added node version 18.15.0.\n- Added yarn version 4.0.0-rc.2".to_string())
+        );
+        assert_eq!(changelog.releases.get("0.8.17"), None);
+        assert_eq!(
+            changelog.releases.get("0.8.16"),
+            Some(&release_entry_0_8_16)
+        );
+        assert_eq!(
+            changelog.releases.get("0.8.15"),
+            Some(&release_entry_0_8_15)
+        );
+
+        let next_version = BuildpackVersion {
+            major: 0,
+            minor: 8,
+            patch: 17,
+        };
+        let date = Utc.with_ymd_and_hms(2023, 6, 16, 0, 0, 0).unwrap();
+        let updated_dependencies = vec![buildpack_id!("a"), buildpack_id!("b")];
+        let changelog = promote_changelog_unreleased_to_version(
+            &changelog,
+            &next_version,
+            &date,
+            &updated_dependencies,
+            &SemverScheme,
+            false,
+            None,
+        );
+
+        assert_eq!(changelog.unreleased, None);
+        assert_eq!(
+            changelog.releases.get("0.8.17"),
+            Some(&ReleaseEntry {
+                version: "0.8.17".to_string(),
+                date,
+                body: "- Added node version 18.15.0.\n- Added yarn version 4.0.0-rc.2\n- Updated `a` to `0.8.17`\n- Updated `b` to `0.8.17`".to_string(),
+                anchor: None,
+            })
+        );
+        assert_eq!(
+            changelog.releases.get("0.8.16"),
+            Some(&release_entry_0_8_16)
+        );
+        assert_eq!(
+            changelog.releases.get("0.8.15"),
+            Some(&release_entry_0_8_15)
+        );
+    }
+
+    #[test]
+    fn test_promote_changelog_unreleased_to_version_with_no_entries_and_updated_dependencies() {
+        let changelog = Changelog {
+            unreleased: None,
+            releases: IndexMap::new(),
+        };
+
+        assert_eq!(changelog.unreleased, None);
+        assert_eq!(changelog.releases.get("0.8.17"), None);
+
+        let next_version = BuildpackVersion {
+            major: 0,
+            minor: 8,
+            patch: 17,
+        };
+        let date = Utc.with_ymd_and_hms(2023, 6, 16, 0, 0, 0).unwrap();
+        let updated_dependencies = vec![buildpack_id!("a"), buildpack_id!("b")];
+        let changelog = promote_changelog_unreleased_to_version(
+            &changelog,
+            &next_version,
+            &date,
+            &updated_dependencies,
+            &SemverScheme,
+            false,
+            None,
+        );
+
+        assert_eq!(changelog.unreleased, None);
+        assert_eq!(
+            changelog.releases.get("0.8.17"),
+            Some(&ReleaseEntry {
+                version: "0.8.17".to_string(),
+                date,
+                body: "- Updated `a` to `0.8.17`\n- Updated `b` to `0.8.17`".to_string(),
+                anchor: None,
+            })
+        );
+    }
+
     const KEEP_A_CHANGELOG_1_0_0: &str = r#"# Changelog
 
 All notable changes to this project will be documented in this file.
@@ -625,4 +2244,377 @@ and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0
 - Good examples and basic guidelines, including proper date formatting.
 - Counter-examples: "What makes unicorns cry?".
 "#;
+
+    #[test]
+    fn test_extract_changelog_sections() {
+        let contents = "# Changelog
+
+## [Unreleased]
+
+- Pending change
+
+## [1.2.0] - 2023-06-01 {#v1-2-0}
+
+### Added
+
+* Support for *pnpm*  
+  with a hard line break
+
+## [1.1.0] - 2023-05-01
+
+## [1.0.0] - 2023-04-01
+
+- Initial release
+
+[unreleased]: https://github.com/heroku/buildpacks-nodejs/compare/v1.2.0...HEAD
+[1.2.0]: https://github.com/heroku/buildpacks-nodejs/compare/v1.1.0...v1.2.0
+";
+        let sections =
+            extract_changelog_sections(contents, &"1.2.0".parse::<VersionRange>().unwrap())
+                .unwrap();
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].heading, "## [1.2.0] - 2023-06-01 {#v1-2-0}");
+        assert_eq!(
+            sections[0].body,
+            "### Added\n\n* Support for *pnpm*  \n  with a hard line break"
+        );
+
+        let versions = |range: &str| {
+            extract_changelog_sections(contents, &range.parse::<VersionRange>().unwrap())
+                .unwrap()
+                .into_iter()
+                .map(|section| (section.version, section.body))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            versions("1.0.0..1.2.0")
+                .into_iter()
+                .map(|(version, _)| version)
+                .collect::<Vec<_>>(),
+            vec!["1.2.0", "1.1.0"]
+        );
+        assert_eq!(versions("1.1.0..").len(), 1);
+        assert_eq!(
+            versions("..1.1.0"),
+            vec![
+                ("1.1.0".to_string(), String::new()),
+                ("1.0.0".to_string(), "- Initial release".to_string())
+            ]
+        );
+        assert_eq!(versions("1.2.0..1.0.0"), vec![]);
+
+        assert!(
+            extract_changelog_sections(contents, &VersionRange::Version("2.0.0".to_string()))
+                .is_err()
+        );
+        assert!("1.0.0...1.2.0".parse::<VersionRange>().is_err());
+        assert!("latest".parse::<VersionRange>().is_err());
+    }
+
+    #[test]
+    fn test_get_link_definitions() {
+        let contents = r#"## [Unreleased]
+
+## [0.0.1] - 2023-03-05
+
+- Some change
+
+[unreleased]: https://github.com/heroku/buildpacks-nodejs/compare/v0.0.1...HEAD
+[0.0.1]: https://github.com/heroku/buildpacks-nodejs/releases/tag/v0.0.1
+"#;
+        assert_eq!(
+            get_link_definitions(contents).unwrap(),
+            vec![
+                "[unreleased]: https://github.com/heroku/buildpacks-nodejs/compare/v0.0.1...HEAD",
+                "[0.0.1]: https://github.com/heroku/buildpacks-nodejs/releases/tag/v0.0.1"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_insert_unreleased_change_into_existing_list() {
+        let contents = "# Changelog\n\n## [Unreleased]\n\n* Some change\n\n## [0.0.1] - 2023-03-05\n\n- Initial release\n\n[unreleased]: https://example.com\n";
+        assert_eq!(
+            insert_unreleased_change(contents, "Updated `node` to `20.1.0`").unwrap(),
+            "# Changelog\n\n## [Unreleased]\n\n* Some change\n* Updated `node` to `20.1.0`\n\n## [0.0.1] - 2023-03-05\n\n- Initial release\n\n[unreleased]: https://example.com\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_unreleased_change_into_empty_section() {
+        assert_eq!(
+            insert_unreleased_change(
+                "## [Unreleased]\n\n## [0.0.1] - 2023-03-05\n\n- Initial release\n",
+                "Some change"
+            )
+            .unwrap(),
+            "## [Unreleased]\n\n- Some change\n\n## [0.0.1] - 2023-03-05\n\n- Initial release\n"
+        );
+        assert_eq!(
+            insert_unreleased_change("## Unreleased\n", "Some change").unwrap(),
+            "## Unreleased\n\n- Some change\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_unreleased_change_after_paragraph() {
+        assert_eq!(
+            insert_unreleased_change("## [Unreleased]\n\nSome notes.\n", "Some change").unwrap(),
+            "## [Unreleased]\n\nSome notes.\n\n- Some change\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_unreleased_change_without_unreleased_section() {
+        assert!(
+            insert_unreleased_change("## [0.0.1] - 2023-03-05\n\n- Initial release\n", "x")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_find_duplicated_unreleased_entries() {
+        let changelog = Changelog::try_from(
+            r#"## [Unreleased]
+
+- Added support for Node.js 20
+- Fixed a bug
+
+## [1.0.1] - 2023-05-10
+
+* Fixed a bug
+
+## [1.0.0] - 2023-05-01
+
+- Initial release
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            find_duplicated_unreleased_entries(&changelog),
+            vec![("Fixed a bug".to_string(), "1.0.1".to_string())]
+        );
+
+        let changelog =
+            Changelog::try_from("## [Unreleased]\n\n## [1.0.0] - 2023-05-01\n\n- Initial release")
+                .unwrap();
+        assert!(find_duplicated_unreleased_entries(&changelog).is_empty());
+    }
+
+    #[test]
+    fn test_find_release_date_problems() {
+        let contents = "## [Unreleased]\n\n## [1.0.2] - 2023/05/03\n\n## [1.0.1] - 2023-05-04\n\n- Fix\n\n## [1.0.0] - 2023-05-01\n";
+        assert_eq!(
+            find_release_date_problems(contents, ReleaseDateFormat::Dashes).unwrap(),
+            vec![
+                ReleaseDateProblem::InconsistentFormat(
+                    "1.0.2".to_string(),
+                    "2023/05/03".to_string(),
+                    ReleaseDateFormat::Dashes
+                ),
+                ReleaseDateProblem::OutOfOrder(
+                    "1.0.1".to_string(),
+                    "2023-05-04".to_string(),
+                    "1.0.2".to_string(),
+                    "2023-05-03".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            find_release_date_problems(
+                "## [1.0.1] - 2023-05-01\n\n## [1.0.0] - 2023-05-01\n",
+                ReleaseDateFormat::Dashes
+            )
+            .unwrap(),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_fix_release_date_formats() {
+        let contents = "## [Unreleased]\n\n## [1.0.1] - 2023/05/03\n\n- Fix 2023/05/03\n\n## 1.0.0 (2023/05/01)\n";
+        assert_eq!(
+            fix_release_date_formats(contents, ReleaseDateFormat::Dashes).unwrap(),
+            "## [Unreleased]\n\n## [1.0.1] - 2023-05-03\n\n- Fix 2023/05/03\n\n## 1.0.0 (2023-05-01)\n"
+        );
+        assert_eq!(
+            fix_release_date_formats(
+                "## [1.0.1] - 2023-05-03\n\n## 1.0.0 (2023/05/01)\n",
+                ReleaseDateFormat::Slashes
+            )
+            .unwrap(),
+            "## [1.0.1] - 2023/05/03\n\n## 1.0.0 (2023/05/01)\n"
+        );
+    }
+
+    #[test]
+    fn test_link_pull_request_references() {
+        let repository_url = "https://github.com/heroku/buildpacks-nodejs";
+        let body = r#"- Fixed a bug (#12)
+- Already linked ([#13](https://github.com/heroku/buildpacks-nodejs/pull/13))
+- Upstream change https://github.com/heroku/libcnb.rs/pull/581
+- Same repository https://github.com/heroku/buildpacks-nodejs/issues/14
+- Not a reference `#15` or a&#16; or heading#17
+
+```
+#18
+```"#;
+        assert_eq!(
+            link_pull_request_references(body, repository_url),
+            r#"- Fixed a bug ([#12](https://github.com/heroku/buildpacks-nodejs/pull/12))
+- Already linked ([#13](https://github.com/heroku/buildpacks-nodejs/pull/13))
+- Upstream change [heroku/libcnb.rs#581](https://github.com/heroku/libcnb.rs/pull/581)
+- Same repository [#14](https://github.com/heroku/buildpacks-nodejs/issues/14)
+- Not a reference `#15` or a&#16; or heading#17
+
+```
+#18
+```"#
+        );
+    }
+
+    #[test]
+    fn test_get_security_entries() {
+        let body = "### Fixed\n\n- Fixed [CVE-2024-0001] parsing\n\n### security\n\n- Updated OpenSSL [CVE-2024-1234] [cve-2024-5678]\n- Patched yarn\n\n### Added\n\n- Added node 20";
+        assert_eq!(
+            get_security_entries(body),
+            vec![
+                SecurityEntry {
+                    entry: "Updated OpenSSL [CVE-2024-1234] [cve-2024-5678]".to_string(),
+                    cves: vec!["CVE-2024-1234".to_string(), "CVE-2024-5678".to_string()]
+                },
+                SecurityEntry {
+                    entry: "Patched yarn".to_string(),
+                    cves: vec![]
+                }
+            ]
+        );
+        assert!(get_security_entries("- Some change").is_empty());
+    }
+
+    #[test]
+    fn test_link_cve_references() {
+        assert_eq!(
+            link_cve_references("Updated OpenSSL [CVE-2024-1234] and [CVE-2024-5678](https://example.com)"),
+            "Updated OpenSSL [CVE-2024-1234](https://www.cve.org/CVERecord?id=CVE-2024-1234) and [CVE-2024-5678](https://example.com)"
+        );
+    }
+
+    #[test]
+    fn test_heading_anchors() {
+        assert_eq!(render_heading_anchor("{#v{slug}}", "1.2.3"), "{#v1-2-3}");
+        assert_eq!(
+            render_heading_anchor(r#"<a id="{version}"></a>"#, "2023.06.1"),
+            r#"<a id="2023.06.1"></a>"#
+        );
+
+        let changelog = Changelog::try_from(
+            r#"## [Unreleased]
+
+- Added node version 20.3.0
+
+## [1.1.0] - 2023-06-01 {#v1-1-0}
+
+- Added node version 20.2.0
+
+## [1.0.0] - 2023-05-01 <a id="v1-0-0"></a>
+
+- Initial release
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            changelog.releases["1.1.0"].anchor.as_deref(),
+            Some("{#v1-1-0}")
+        );
+        assert_eq!(
+            changelog.releases["1.0.0"].anchor.as_deref(),
+            Some(r#"<a id="v1-0-0"></a>"#)
+        );
+
+        let changelog = promote_changelog_unreleased_to_version(
+            &changelog,
+            &BuildpackVersion::new(1, 2, 0),
+            &Utc.with_ymd_and_hms(2023, 7, 1, 0, 0, 0).unwrap(),
+            &[],
+            &SemverScheme,
+            false,
+            Some("{#v{slug}}"),
+        );
+        let rendered = changelog.to_string();
+        assert!(
+            rendered.contains("## [1.2.0] - 2023-07-01 {#v1-2-0}\n\n- Added node version 20.3.0")
+        );
+        assert!(rendered.contains("## [1.1.0] - 2023-06-01 {#v1-1-0}\n"));
+        assert!(rendered.contains(r#"## [1.0.0] - 2023-05-01 <a id="v1-0-0"></a>"#));
+    }
+
+    #[test]
+    fn test_compile_root_changelog() {
+        let changelogs = vec![
+            (
+                buildpack_id!("heroku/nodejs"),
+                Changelog::try_from("## [Unreleased]\n\n## [1.1.0] - 2023-06-02\n\n### Added\n\n- Node.js 20\n\n## [1.0.0] - 2023-05-01\n\n- Initial release").unwrap(),
+            ),
+            (
+                buildpack_id!("heroku/java"),
+                Changelog::try_from("## [Unreleased]\n\n## [1.1.0] - 2023-06-01\n\n- No changes\n\n## [1.0.0] - 2023-05-01\n\n- Initial release").unwrap(),
+            ),
+        ];
+
+        let compiled = compile_root_changelog(&changelogs, ReleaseDateFormat::Dashes);
+        assert!(compiled.starts_with("# Changelog\n\n"));
+        assert!(compiled.ends_with(
+            r#"## [1.1.0] - 2023-06-02
+
+### heroku/nodejs
+
+#### Added
+
+- Node.js 20
+
+## [1.0.0] - 2023-05-01
+
+### heroku/java
+
+- Initial release
+
+### heroku/nodejs
+
+- Initial release
+"#
+        ));
+    }
+
+    #[test]
+    fn test_insert_root_changelog_release() {
+        let date = Utc.with_ymd_and_hms(2023, 6, 1, 0, 0, 0).unwrap();
+        let release = |change: &str| {
+            render_root_changelog_release(
+                "1.1.0",
+                &date,
+                &[(buildpack_id!("heroku/nodejs"), change.to_string())],
+                ReleaseDateFormat::Dashes,
+            )
+        };
+
+        let contents = insert_root_changelog_release("", "1.1.0", &release("- Change")).unwrap();
+        assert!(contents.starts_with("# Changelog\n\n"));
+        assert!(
+            contents.ends_with("\n\n## [1.1.0] - 2023-06-01\n\n### heroku/nodejs\n\n- Change\n")
+        );
+
+        let contents =
+            "# Changelog\n\n## [1.0.0] - 2023-05-01\n\n### heroku/nodejs\n\n- Initial release\n";
+        let inserted =
+            insert_root_changelog_release(contents, "1.1.0", &release("- Change")).unwrap();
+        assert_eq!(
+            inserted,
+            "# Changelog\n\n## [1.1.0] - 2023-06-01\n\n### heroku/nodejs\n\n- Change\n\n## [1.0.0] - 2023-05-01\n\n### heroku/nodejs\n\n- Initial release\n"
+        );
+        assert_eq!(
+            insert_root_changelog_release(&inserted, "1.1.0", &release("- Other change")).unwrap(),
+            inserted.replace("- Change", "- Other change")
+        );
+    }
 }
@@ -0,0 +1,113 @@
+use crate::http;
+use libcnb_data::buildpack::BuildpackId;
+use serde::Deserialize;
+use std::fmt::{Display, Formatter};
+
+const CNB_REGISTRY_API_URL: &str = "https://registry.buildpacks.io/api/v1";
+
+pub const REGISTRY_INDEX_REPOSITORY: &str = "buildpacks/registry-index";
+
+#[derive(Deserialize)]
+struct BuildpackResponse {
+    versions: Vec<BuildpackVersionEntry>,
+}
+
+#[derive(Deserialize)]
+struct BuildpackVersionEntry {
+    version: String,
+}
+
+pub fn get_published_versions(buildpack_id: &BuildpackId) -> Result<Vec<String>, CnbRegistryError> {
+    let url = format!("{CNB_REGISTRY_API_URL}/buildpacks/{buildpack_id}");
+    match http::get(&url).call() {
+        Ok(response) => response
+            .into_json::<BuildpackResponse>()
+            .map(|buildpack| {
+                buildpack
+                    .versions
+                    .into_iter()
+                    .map(|entry| entry.version)
+                    .collect()
+            })
+            .map_err(|e| CnbRegistryError::ReadingResponse(url, e)),
+        Err(ureq::Error::Status(404, _)) => Ok(vec![]),
+        Err(error) => Err(CnbRegistryError::Request(url, Box::new(error))),
+    }
+}
+
+#[derive(Deserialize)]
+struct BuildpackVersionResponse {
+    addr: String,
+}
+
+// the image address a published version was registered with, `None` if it isn't in the registry
+pub fn get_buildpack_address(
+    buildpack_id: &str,
+    version: &str,
+) -> Result<Option<String>, CnbRegistryError> {
+    let url = format!("{CNB_REGISTRY_API_URL}/buildpacks/{buildpack_id}/{version}");
+    match http::get(&url).call() {
+        Ok(response) => response
+            .into_json::<BuildpackVersionResponse>()
+            .map(|buildpack| Some(buildpack.addr))
+            .map_err(|e| CnbRegistryError::ReadingResponse(url, e)),
+        Err(ureq::Error::Status(404, _)) => Ok(None),
+        Err(error) => Err(CnbRegistryError::Request(url, Box::new(error))),
+    }
+}
+
+// the registry index is updated by a bot that processes issues with this title and body, `address`
+// must be a digest reference (e.g.; `docker.io/heroku/buildpack-nodejs@sha256:...`)
+pub fn registry_index_issue(
+    buildpack_id: &BuildpackId,
+    version: &str,
+    address: &str,
+) -> (String, String) {
+    (
+        format!("ADD {buildpack_id}@{version}"),
+        format!("id = \"{buildpack_id}\"\nversion = \"{version}\"\naddr = \"{address}\"\n"),
+    )
+}
+
+#[derive(Debug)]
+pub enum CnbRegistryError {
+    Request(String, Box<ureq::Error>),
+    ReadingResponse(String, std::io::Error),
+}
+
+impl Display for CnbRegistryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CnbRegistryError::Request(url, error) => {
+                write!(f, "CNB registry request failed - {url}\nError: {error}")
+            }
+            CnbRegistryError::ReadingResponse(url, error) => {
+                write!(
+                    f,
+                    "Could not read CNB registry response - {url}\nError: {error}"
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cnb_registry::registry_index_issue;
+    use libcnb_data::buildpack_id;
+
+    #[test]
+    fn test_registry_index_issue() {
+        assert_eq!(
+            registry_index_issue(
+                &buildpack_id!("heroku/nodejs"),
+                "1.0.0",
+                "docker.io/heroku/buildpack-nodejs@sha256:abc"
+            ),
+            (
+                "ADD heroku/nodejs@1.0.0".to_string(),
+                "id = \"heroku/nodejs\"\nversion = \"1.0.0\"\naddr = \"docker.io/heroku/buildpack-nodejs@sha256:abc\"\n".to_string()
+            )
+        );
+    }
+}
@@ -0,0 +1,71 @@
+use crate::commands::add_changelog_entry::errors::Error;
+use clap::Parser;
+use languages_github_actions::buildpack::{
+    get_buildpack_id, read_buildpack_file, BuildpackDiscoveryArgs,
+};
+use languages_github_actions::changelog::{insert_unreleased_change, ChangelogLocationArgs};
+use languages_github_actions::git::WorktreeGuardArgs;
+use languages_github_actions::log;
+use languages_github_actions::write;
+use libcnb_data::buildpack::BuildpackId;
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Adds an entry to the Unreleased section of a buildpack's changelog", long_about = None)]
+pub(crate) struct AddChangelogEntryArgs {
+    #[arg(long)]
+    pub(crate) buildpack_id: BuildpackId,
+    #[arg(long)]
+    pub(crate) entry: String,
+    #[command(flatten)]
+    pub(crate) worktree: WorktreeGuardArgs,
+    #[command(flatten)]
+    pub(crate) discovery: BuildpackDiscoveryArgs,
+    #[command(flatten)]
+    pub(crate) changelog: ChangelogLocationArgs,
+}
+
+pub(crate) fn execute(args: AddChangelogEntryArgs) -> Result<()> {
+    let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
+
+    args.worktree.check(&current_dir).map_err(Error::Git)?;
+
+    let buildpack_dirs = args
+        .discovery
+        .find_buildpack_dirs(&current_dir, &[current_dir.join("target")])
+        .map_err(|e| Error::FindingBuildpacks(current_dir.clone(), e))?;
+
+    let mut buildpack_file = None;
+    for dir in buildpack_dirs {
+        let candidate =
+            read_buildpack_file(dir.join("buildpack.toml")).map_err(Error::BuildpackFile)?;
+        if get_buildpack_id(&candidate).map_err(Error::BuildpackFile)? == args.buildpack_id {
+            buildpack_file = Some(candidate);
+            break;
+        }
+    }
+
+    let buildpack_file = buildpack_file.ok_or(Error::UnknownBuildpackId(args.buildpack_id))?;
+
+    let changelog_path = args
+        .changelog
+        .find_changelog(
+            buildpack_file.path.parent().unwrap_or(&current_dir),
+            &current_dir,
+        )
+        .map_err(Error::ChangelogFile)?;
+
+    let contents = std::fs::read_to_string(&changelog_path)
+        .map_err(|e| Error::ReadingChangelog(changelog_path.clone(), e))?;
+
+    let new_contents = insert_unreleased_change(&contents, args.entry.trim())
+        .map_err(|e| Error::AddingChangelogEntry(changelog_path.clone(), e))?;
+
+    write::write_file(&changelog_path, new_contents)
+        .map_err(|e| Error::WritingChangelog(changelog_path.clone(), e))?;
+
+    log!(path = changelog_path; "✅️ Added unreleased entry: {}", changelog_path.display());
+
+    Ok(())
+}
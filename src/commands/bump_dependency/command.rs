@@ -0,0 +1,109 @@
+use crate::commands::bump_dependency::errors::Error;
+use clap::Parser;
+use languages_github_actions::buildpack::{
+    get_buildpack_id, read_buildpack_file, update_buildpack_contents_with_value,
+    BuildpackDiscoveryArgs,
+};
+use languages_github_actions::changelog::{insert_unreleased_change, ChangelogLocationArgs};
+use languages_github_actions::git::WorktreeGuardArgs;
+use languages_github_actions::github::actions;
+use languages_github_actions::log;
+use languages_github_actions::write;
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Updates a pinned value in each detected buildpack and adds an unreleased changelog entry", long_about = None)]
+pub(crate) struct BumpDependencyArgs {
+    #[arg(long)]
+    pub(crate) key: String,
+    #[arg(long)]
+    pub(crate) value: String,
+    #[command(flatten)]
+    pub(crate) worktree: WorktreeGuardArgs,
+    #[command(flatten)]
+    pub(crate) discovery: BuildpackDiscoveryArgs,
+    #[command(flatten)]
+    pub(crate) changelog: ChangelogLocationArgs,
+}
+
+pub(crate) fn execute(args: BumpDependencyArgs) -> Result<()> {
+    let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
+
+    args.worktree.check(&current_dir).map_err(Error::Git)?;
+
+    let buildpack_dirs = args
+        .discovery
+        .find_buildpack_dirs(&current_dir, &[current_dir.join("target")])
+        .map_err(|e| Error::FindingBuildpacks(current_dir.clone(), e))?;
+
+    let mut matched_buildpacks = 0;
+    let mut summary = vec![];
+
+    for dir in buildpack_dirs {
+        let mut buildpack_file =
+            read_buildpack_file(dir.join("buildpack.toml")).map_err(Error::BuildpackFile)?;
+
+        let previous_value =
+            update_buildpack_contents_with_value(&mut buildpack_file, &args.key, &args.value)
+                .map_err(Error::BuildpackFile)?;
+
+        let previous_value = match previous_value {
+            Some(previous_value) => previous_value,
+            None => continue,
+        };
+
+        matched_buildpacks += 1;
+
+        if previous_value == args.value {
+            continue;
+        }
+
+        write::write_file(&buildpack_file.path, buildpack_file.document.to_string())
+            .map_err(|e| Error::WritingBuildpack(buildpack_file.path.clone(), e))?;
+
+        log!(
+            path = buildpack_file.path;
+            "✅️ Updated `{}` {previous_value} → {}: {}",
+            args.key,
+            args.value,
+            buildpack_file.path.display()
+        );
+
+        let changelog_path = args
+            .changelog
+            .find_changelog(&dir, &current_dir)
+            .map_err(Error::ChangelogFile)?;
+
+        let contents = std::fs::read_to_string(&changelog_path)
+            .map_err(|e| Error::ReadingChangelog(changelog_path.clone(), e))?;
+
+        let changelog_contents = insert_unreleased_change(
+            &contents,
+            &format!(
+                "Updated `{}` from `{previous_value}` to `{}`",
+                args.key, args.value
+            ),
+        )
+        .map_err(|e| Error::AddingChangelogEntry(changelog_path.clone(), e))?;
+
+        write::write_file(&changelog_path, changelog_contents)
+            .map_err(|e| Error::WritingChangelog(changelog_path.clone(), e))?;
+
+        log!(path = changelog_path; "✅️ Added unreleased entry: {}", changelog_path.display());
+
+        let buildpack_id = get_buildpack_id(&buildpack_file).map_err(Error::BuildpackFile)?;
+        summary.push(format!(
+            "- `{buildpack_id}`: `{previous_value}` → `{}`",
+            args.value
+        ));
+    }
+
+    if matched_buildpacks == 0 {
+        Err(Error::NoMatchingBuildpacks(args.key))?;
+    }
+
+    actions::set_output("summary", summary.join("\n")).map_err(Error::SetActionOutput)?;
+
+    Ok(())
+}
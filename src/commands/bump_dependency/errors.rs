@@ -0,0 +1,113 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::buildpack::{BuildpackDiscoveryError, BuildpackFileError};
+use languages_github_actions::changelog::{ChangelogError, ChangelogFileError};
+use languages_github_actions::git::GitError;
+use languages_github_actions::github::actions::SetOutputError;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    GetCurrentDir(io::Error),
+    Git(GitError),
+    FindingBuildpacks(PathBuf, BuildpackDiscoveryError),
+    BuildpackFile(BuildpackFileError),
+    NoMatchingBuildpacks(String),
+    WritingBuildpack(PathBuf, io::Error),
+    ChangelogFile(ChangelogFileError),
+    ReadingChangelog(PathBuf, io::Error),
+    AddingChangelogEntry(PathBuf, ChangelogError),
+    WritingChangelog(PathBuf, io::Error),
+    SetActionOutput(SetOutputError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::GetCurrentDir(error) => {
+                write!(f, "Failed to get current directory\nError: {error}")
+            }
+
+            Error::Git(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::FindingBuildpacks(path, error) => {
+                write!(
+                    f,
+                    "Could not find buildpacks\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::BuildpackFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::NoMatchingBuildpacks(key) => {
+                write!(f, "No buildpacks contain the key `{key}`")
+            }
+
+            Error::WritingBuildpack(path, error) => {
+                write!(
+                    f,
+                    "Could not write buildpack\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::ChangelogFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::ReadingChangelog(path, error) => {
+                write!(
+                    f,
+                    "Could not read changelog\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::AddingChangelogEntry(path, error) => {
+                write!(
+                    f,
+                    "Could not add changelog entry\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::WritingChangelog(path, error) => {
+                write!(
+                    f,
+                    "Could not write changelog\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::SetActionOutput(set_output_error) => match set_output_error {
+                SetOutputError::Opening(error) | SetOutputError::Writing(error) => {
+                    write!(f, "Could not write action output\nError: {error}")
+                }
+            },
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::ChangelogFile(error) => error.category(),
+            Error::Git(error) => error.category(),
+            Error::GetCurrentDir(_)
+            | Error::WritingBuildpack(_, _)
+            | Error::ReadingChangelog(_, _)
+            | Error::WritingChangelog(_, _) => ErrorCategory::Io,
+            Error::FindingBuildpacks(_, error) => error.category(),
+            Error::BuildpackFile(error) => error.category(),
+            Error::NoMatchingBuildpacks(_) => ErrorCategory::Config,
+            Error::AddingChangelogEntry(_, error) => error.category(),
+            Error::SetActionOutput(error) => error.category(),
+        }
+    }
+}
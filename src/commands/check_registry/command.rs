@@ -0,0 +1,177 @@
+use crate::commands::check_registry::errors::{Error, ReadinessProblem};
+use clap::Parser;
+use languages_github_actions::buildpack::{
+    get_buildpack_id, get_buildpack_version, read_buildpack_file, BuildpackDiscoveryArgs,
+};
+use languages_github_actions::cancellation::check_cancelled;
+use languages_github_actions::clock::{Clock, SystemClock};
+use languages_github_actions::cnb_registry::get_published_versions;
+use languages_github_actions::log;
+use languages_github_actions::versioning::{copy_version, BumpCoordinate, Scheme};
+use libcnb_data::buildpack::{BuildpackId, BuildpackVersion};
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Checks the CNB registry to verify each detected buildpack is ready to be released", long_about = None)]
+pub(crate) struct CheckRegistryArgs {
+    #[arg(long, value_enum)]
+    pub(crate) bump: BumpCoordinate,
+    #[arg(long, value_enum, default_value_t)]
+    pub(crate) scheme: Scheme,
+    #[arg(long)]
+    pub(crate) require_previous: bool,
+    #[command(flatten)]
+    pub(crate) discovery: BuildpackDiscoveryArgs,
+}
+
+pub(crate) fn execute(args: CheckRegistryArgs) -> Result<()> {
+    execute_with_clock(args, &SystemClock)
+}
+
+pub(crate) fn execute_with_clock(args: CheckRegistryArgs, clock: &dyn Clock) -> Result<()> {
+    let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
+
+    let buildpack_dirs = args
+        .discovery
+        .find_buildpack_dirs(&current_dir, &[current_dir.join("target")])
+        .map_err(|e| Error::FindingBuildpacks(current_dir.clone(), e))?;
+
+    let now = clock.now();
+    let version_scheme = args.scheme.version_scheme();
+
+    let mut problems = vec![];
+
+    for dir in buildpack_dirs {
+        check_cancelled().map_err(Error::Cancelled)?;
+
+        let buildpack_file =
+            read_buildpack_file(dir.join("buildpack.toml")).map_err(Error::BuildpackFile)?;
+        let buildpack_id = get_buildpack_id(&buildpack_file).map_err(Error::BuildpackFile)?;
+        let current_version =
+            get_buildpack_version(&buildpack_file).map_err(Error::BuildpackFile)?;
+        let next_version = version_scheme
+            .next_version(&current_version, &args.bump, &now)
+            .map_err(Error::InvalidNextVersion)?;
+
+        let published_versions =
+            get_published_versions(&buildpack_id).map_err(Error::QueryingRegistry)?;
+
+        let buildpack_problems = find_readiness_problems(
+            &buildpack_id,
+            &current_version,
+            &next_version,
+            &published_versions,
+            args.require_previous,
+        );
+
+        if buildpack_problems.is_empty() {
+            log!(
+                buildpack_id = buildpack_id;
+                "✅️ {buildpack_id} is ready to release {next_version}"
+            );
+        } else {
+            problems.extend(buildpack_problems);
+        }
+    }
+
+    if !problems.is_empty() {
+        Err(Error::NotReadyForRelease(problems))?;
+    }
+
+    Ok(())
+}
+
+fn find_readiness_problems(
+    buildpack_id: &BuildpackId,
+    current_version: &BuildpackVersion,
+    next_version: &BuildpackVersion,
+    published_versions: &[String],
+    require_previous: bool,
+) -> Vec<ReadinessProblem> {
+    let is_published =
+        |version: &BuildpackVersion| published_versions.contains(&version.to_string());
+
+    let mut problems = vec![];
+
+    if is_published(next_version) {
+        problems.push(ReadinessProblem::NextVersionAlreadyPublished(
+            buildpack_id.clone(),
+            copy_version(next_version),
+        ));
+    }
+
+    if require_previous && !is_published(current_version) {
+        problems.push(ReadinessProblem::PreviousVersionNotPublished(
+            buildpack_id.clone(),
+            copy_version(current_version),
+        ));
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::check_registry::command::find_readiness_problems;
+    use crate::commands::check_registry::errors::ReadinessProblem;
+    use languages_github_actions::versioning::copy_version;
+    use libcnb_data::buildpack::BuildpackVersion;
+    use libcnb_data::buildpack_id;
+
+    #[test]
+    fn test_find_readiness_problems() {
+        let current_version = BuildpackVersion {
+            major: 1,
+            minor: 0,
+            patch: 0,
+        };
+        let next_version = BuildpackVersion {
+            major: 1,
+            minor: 0,
+            patch: 1,
+        };
+
+        assert_eq!(
+            find_readiness_problems(
+                &buildpack_id!("heroku/nodejs"),
+                &current_version,
+                &next_version,
+                &["0.9.0".to_string(), "1.0.0".to_string()],
+                true
+            ),
+            vec![]
+        );
+
+        assert_eq!(
+            find_readiness_problems(
+                &buildpack_id!("heroku/nodejs"),
+                &current_version,
+                &next_version,
+                &["1.0.1".to_string()],
+                true
+            ),
+            vec![
+                ReadinessProblem::NextVersionAlreadyPublished(
+                    buildpack_id!("heroku/nodejs"),
+                    copy_version(&next_version)
+                ),
+                ReadinessProblem::PreviousVersionNotPublished(
+                    buildpack_id!("heroku/nodejs"),
+                    copy_version(&current_version)
+                )
+            ]
+        );
+
+        assert_eq!(
+            find_readiness_problems(
+                &buildpack_id!("heroku/nodejs"),
+                &current_version,
+                &next_version,
+                &[],
+                false
+            ),
+            vec![]
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::buildpack::{BuildpackDiscoveryError, BuildpackFileError};
+use languages_github_actions::cancellation::CancellationError;
+use languages_github_actions::cnb_registry::CnbRegistryError;
+use languages_github_actions::versioning::VersionSchemeError;
+use libcnb_data::buildpack::{BuildpackId, BuildpackVersion};
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    GetCurrentDir(std::io::Error),
+    FindingBuildpacks(PathBuf, BuildpackDiscoveryError),
+    BuildpackFile(BuildpackFileError),
+    InvalidNextVersion(VersionSchemeError),
+    QueryingRegistry(CnbRegistryError),
+    NotReadyForRelease(Vec<ReadinessProblem>),
+    Cancelled(CancellationError),
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) enum ReadinessProblem {
+    NextVersionAlreadyPublished(BuildpackId, BuildpackVersion),
+    PreviousVersionNotPublished(BuildpackId, BuildpackVersion),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::GetCurrentDir(error) => {
+                write!(f, "Failed to get current directory\nError: {error}")
+            }
+
+            Error::FindingBuildpacks(path, error) => {
+                write!(
+                    f,
+                    "Could not find buildpacks\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::BuildpackFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::InvalidNextVersion(error) => {
+                write!(f, "Could not determine the next version\nError: {error}")
+            }
+
+            Error::QueryingRegistry(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::NotReadyForRelease(problems) => {
+                write!(
+                    f,
+                    "Not ready for release:\n{}",
+                    problems
+                        .iter()
+                        .map(|problem| format!("• {problem}"))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            }
+
+            Error::Cancelled(error) => {
+                write!(f, "{error}")
+            }
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::GetCurrentDir(_) => ErrorCategory::Io,
+            Error::FindingBuildpacks(_, error) => error.category(),
+            Error::BuildpackFile(error) => error.category(),
+            Error::InvalidNextVersion(error) => error.category(),
+            Error::QueryingRegistry(error) => error.category(),
+            Error::NotReadyForRelease(_) => ErrorCategory::Validation,
+            Error::Cancelled(error) => error.category(),
+        }
+    }
+}
+
+impl Display for ReadinessProblem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadinessProblem::NextVersionAlreadyPublished(buildpack_id, version) => {
+                write!(
+                    f,
+                    "{buildpack_id} {version} is already published to the CNB registry"
+                )
+            }
+
+            ReadinessProblem::PreviousVersionNotPublished(buildpack_id, version) => {
+                write!(
+                    f,
+                    "{buildpack_id} {version} has not been published to the CNB registry"
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use crate::commands::check_version::errors::Error;
+use clap::Parser;
+use languages_github_actions::github::actions;
+use languages_github_actions::github::actions::{AnnotationLevel, AnnotationProperties};
+use languages_github_actions::github::api::get_latest_release;
+use languages_github_actions::log;
+use languages_github_actions::versioning::compare_versions;
+use libcnb_data::buildpack::BuildpackVersion;
+use std::cmp::Ordering;
+
+type Result<T> = std::result::Result<T, Error>;
+
+const BINARY_NAME: &str = "actions";
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Compares the version of this binary with the latest release and reports whether an update is available", long_about = None)]
+pub(crate) struct CheckVersionArgs {
+    #[arg(long, default_value_t = default_repository())]
+    pub(crate) repository: String,
+    #[arg(long)]
+    pub(crate) github_token: Option<String>,
+}
+
+pub(crate) fn execute(args: CheckVersionArgs) -> Result<()> {
+    let current_version = env!("CARGO_PKG_VERSION");
+
+    let release = get_latest_release(args.github_token.as_deref(), &args.repository)
+        .map_err(Error::GettingLatestRelease)?;
+    let latest_version = release
+        .tag_name
+        .strip_prefix('v')
+        .unwrap_or(&release.tag_name)
+        .to_string();
+
+    let update_available = is_update_available(current_version, &latest_version)?;
+    let installer_url = installer_url(&args.repository, &latest_version, std::env::consts::OS);
+
+    if update_available {
+        let message = format!(
+            "{BINARY_NAME} {current_version} is out of date, the latest release is {latest_version} ({})",
+            release.html_url
+        );
+        log!("⚠️ {message}");
+        actions::annotate(
+            AnnotationLevel::Warning,
+            &message,
+            &AnnotationProperties {
+                title: Some("Outdated languages-github-actions release".to_string()),
+                ..AnnotationProperties::default()
+            },
+        );
+    } else {
+        log!("✅️ {BINARY_NAME} {current_version} is up to date");
+    }
+
+    actions::set_output("current_version", current_version).map_err(Error::SetActionOutput)?;
+    actions::set_output("latest_version", latest_version).map_err(Error::SetActionOutput)?;
+    actions::set_output("update_available", update_available.to_string())
+        .map_err(Error::SetActionOutput)?;
+    actions::set_output("release_url", release.html_url).map_err(Error::SetActionOutput)?;
+    actions::set_output("installer_url", installer_url).map_err(Error::SetActionOutput)?;
+
+    Ok(())
+}
+
+// `owner/name` from the package's repository url (e.g.; `https://github.com/heroku/languages-github-actions.git`)
+fn default_repository() -> String {
+    env!("CARGO_PKG_REPOSITORY")
+        .trim_start_matches("https://github.com/")
+        .trim_end_matches(".git")
+        .to_string()
+}
+
+// a running binary that is newer than the latest release (e.g.; a local build) isn't outdated
+fn is_update_available(current_version: &str, latest_version: &str) -> Result<bool> {
+    let parse = |version: &str| {
+        BuildpackVersion::try_from(version.to_string())
+            .map_err(|_| Error::InvalidVersion(version.to_string()))
+    };
+    Ok(compare_versions(&parse(current_version)?, &parse(latest_version)?) == Ordering::Less)
+}
+
+// the same archive the bootstrap script downloads, which names platforms the way Node.js does
+fn installer_url(repository: &str, version: &str, os: &str) -> String {
+    let platform = match os {
+        "macos" => "darwin",
+        "windows" => "win32",
+        other => other,
+    };
+    format!(
+        "https://github.com/{repository}/releases/download/v{version}/{BINARY_NAME}-v{version}-{platform}-x64.tar.gz"
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::check_version::command::{installer_url, is_update_available};
+
+    #[test]
+    fn test_check_version() {
+        assert!(is_update_available("0.0.7", "0.0.10").unwrap());
+        assert!(!is_update_available("0.0.7", "0.0.7").unwrap());
+        assert!(!is_update_available("0.1.0", "0.0.10").unwrap());
+        assert!(is_update_available("0.0.7", "latest").is_err());
+
+        assert_eq!(
+            installer_url("heroku/languages-github-actions", "0.0.10", "macos"),
+            "https://github.com/heroku/languages-github-actions/releases/download/v0.0.10/actions-v0.0.10-darwin-x64.tar.gz"
+        );
+    }
+}
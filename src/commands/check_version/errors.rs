@@ -0,0 +1,44 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::github::actions::SetOutputError;
+use languages_github_actions::github::api::GitHubApiError;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    GettingLatestRelease(GitHubApiError),
+    InvalidVersion(String),
+    SetActionOutput(SetOutputError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::GettingLatestRelease(error) => {
+                write!(f, "Could not get the latest release\nError: {error}")
+            }
+
+            Error::InvalidVersion(version) => {
+                write!(
+                    f,
+                    "Could not compare versions, `{version}` is not a version"
+                )
+            }
+
+            Error::SetActionOutput(set_output_error) => match set_output_error {
+                SetOutputError::Opening(error) | SetOutputError::Writing(error) => {
+                    write!(f, "Could not write action output\nError: {error}")
+                }
+            },
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::GettingLatestRelease(error) => error.category(),
+            Error::InvalidVersion(_) => ErrorCategory::Validation,
+            Error::SetActionOutput(error) => error.category(),
+        }
+    }
+}
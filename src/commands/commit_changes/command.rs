@@ -0,0 +1,157 @@
+use crate::commands::commit_changes::errors::Error;
+use clap::Parser;
+use languages_github_actions::github::actions;
+use languages_github_actions::github::api::{
+    create_commit_on_branch, create_ref, force_update_ref, CommitOnBranch, FileAddition,
+};
+use languages_github_actions::github::auth::GitHubAuthArgs;
+use languages_github_actions::github::context::GitHubContext;
+use languages_github_actions::log;
+use std::path::{Component, Path, PathBuf};
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Commits the given files to a branch through the GitHub API so the commit is signed", long_about = None)]
+pub(crate) struct CommitChangesArgs {
+    #[arg(long)]
+    pub(crate) repository: Option<String>,
+    #[arg(long)]
+    pub(crate) branch: String,
+    #[arg(long)]
+    pub(crate) parent_sha: Option<String>,
+    #[arg(long)]
+    pub(crate) message: String,
+    #[arg(long, required = true, value_delimiter = ',', num_args = 1..)]
+    pub(crate) files: Vec<PathBuf>,
+    #[arg(long)]
+    pub(crate) create_branch: bool,
+    #[arg(long)]
+    pub(crate) force: bool,
+    #[command(flatten)]
+    pub(crate) auth: GitHubAuthArgs,
+}
+
+pub(crate) fn execute(args: CommitChangesArgs) -> Result<()> {
+    let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
+
+    let context = GitHubContext::from_env().map_err(Error::GitHubContext)?;
+
+    let repository = args
+        .repository
+        .or(context.repository)
+        .ok_or_else(|| missing_argument("--repository", "GITHUB_REPOSITORY"))?;
+
+    let parent_sha = args
+        .parent_sha
+        .or(context.sha)
+        .ok_or_else(|| missing_argument("--parent-sha", "GITHUB_SHA"))?;
+
+    let mut additions = vec![];
+    let mut deletions = vec![];
+
+    for file in &args.files {
+        let path = repository_path(file);
+        let full_path = current_dir.join(file);
+        if full_path.exists() {
+            let contents =
+                std::fs::read(&full_path).map_err(|e| Error::ReadingFile(full_path.clone(), e))?;
+            additions.push(FileAddition { path, contents });
+        } else {
+            deletions.push(path);
+        }
+    }
+
+    let token = args
+        .auth
+        .resolve_token(&repository)
+        .map_err(Error::Authenticating)?;
+
+    if args.force {
+        let updated = force_update_ref(&token, &repository, &args.branch, &parent_sha)
+            .map_err(Error::UpdatingBranch)?;
+        if !updated {
+            create_ref(&token, &repository, &args.branch, &parent_sha)
+                .map_err(Error::CreatingBranch)?;
+        }
+    } else if args.create_branch {
+        create_ref(&token, &repository, &args.branch, &parent_sha)
+            .map_err(Error::CreatingBranch)?;
+    }
+
+    let (headline, body) = split_commit_message(&args.message);
+
+    let commit = create_commit_on_branch(
+        &token,
+        &CommitOnBranch {
+            repository,
+            branch: args.branch.clone(),
+            expected_head_oid: parent_sha,
+            headline,
+            body,
+            additions,
+            deletions,
+        },
+    )
+    .map_err(Error::CreatingCommit)?;
+
+    log!("✅️ Committed {} to {}", commit.oid, args.branch);
+
+    actions::set_output("commit_sha", commit.oid).map_err(Error::SetActionOutput)?;
+    actions::set_output("commit_url", commit.url).map_err(Error::SetActionOutput)?;
+
+    Ok(())
+}
+
+fn missing_argument(argument: &str, variable: &str) -> Error {
+    Error::MissingArgument(argument.to_string(), variable.to_string())
+}
+
+fn repository_path(file: &Path) -> String {
+    file.components()
+        .filter_map(|component| match component {
+            Component::Normal(value) => Some(value.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn split_commit_message(message: &str) -> (String, Option<String>) {
+    let message = message.trim();
+    match message.split_once("\n\n") {
+        Some((headline, body)) => (headline.trim().to_string(), Some(body.trim().to_string())),
+        None => (message.to_string(), None),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::commit_changes::command::{repository_path, split_commit_message};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_split_commit_message() {
+        assert_eq!(
+            split_commit_message("Prepare release v1.0.0"),
+            ("Prepare release v1.0.0".to_string(), None)
+        );
+        assert_eq!(
+            split_commit_message(
+                "Prepare release v1.0.0\n\n- heroku/nodejs\n- heroku/nodejs-npm\n"
+            ),
+            (
+                "Prepare release v1.0.0".to_string(),
+                Some("- heroku/nodejs\n- heroku/nodejs-npm".to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn test_repository_path() {
+        assert_eq!(
+            repository_path(&PathBuf::from("./buildpacks/nodejs/CHANGELOG.md")),
+            "buildpacks/nodejs/CHANGELOG.md"
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::github::actions::SetOutputError;
+use languages_github_actions::github::api::GitHubApiError;
+use languages_github_actions::github::auth::AuthError;
+use languages_github_actions::github::context::GitHubContextError;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    GetCurrentDir(io::Error),
+    ReadingFile(PathBuf, io::Error),
+    GitHubContext(GitHubContextError),
+    MissingArgument(String, String),
+    Authenticating(AuthError),
+    CreatingBranch(GitHubApiError),
+    UpdatingBranch(GitHubApiError),
+    CreatingCommit(GitHubApiError),
+    SetActionOutput(SetOutputError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::GetCurrentDir(error) => {
+                write!(f, "Failed to get current directory\nError: {error}")
+            }
+
+            Error::ReadingFile(path, error) => {
+                write!(
+                    f,
+                    "Could not read file\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::GitHubContext(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::MissingArgument(argument, variable) => {
+                write!(
+                    f,
+                    "The {argument} argument is required when {variable} is not set"
+                )
+            }
+
+            Error::Authenticating(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::CreatingBranch(error) => {
+                write!(f, "Could not create branch\nError: {error}")
+            }
+
+            Error::UpdatingBranch(error) => {
+                write!(f, "Could not update branch\nError: {error}")
+            }
+
+            Error::CreatingCommit(error) => {
+                write!(f, "Could not create commit\nError: {error}")
+            }
+
+            Error::SetActionOutput(set_output_error) => match set_output_error {
+                SetOutputError::Opening(error) | SetOutputError::Writing(error) => {
+                    write!(f, "Could not write action output\nError: {error}")
+                }
+            },
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::GetCurrentDir(_) | Error::ReadingFile(_, _) => ErrorCategory::Io,
+            Error::GitHubContext(error) => error.category(),
+            Error::MissingArgument(_, _) => ErrorCategory::Config,
+            Error::Authenticating(error) => error.category(),
+            Error::CreatingBranch(error)
+            | Error::UpdatingBranch(error)
+            | Error::CreatingCommit(error) => error.category(),
+            Error::SetActionOutput(error) => error.category(),
+        }
+    }
+}
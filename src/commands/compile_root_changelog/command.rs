@@ -0,0 +1,114 @@
+use crate::commands::compile_root_changelog::errors::Error;
+use clap::Parser;
+use languages_github_actions::buildpack::{
+    get_buildpack_id, is_buildpack_release_disabled, read_buildpack_file, BuildpackDiscoveryArgs,
+};
+use languages_github_actions::changelog::{
+    compile_root_changelog, ChangelogLocationArgs, ReleaseDateFormat,
+};
+use languages_github_actions::git::WorktreeGuardArgs;
+use languages_github_actions::log;
+use languages_github_actions::write;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Compiles the releases from each detected buildpack's changelog into a changelog for the repository", long_about = None)]
+pub(crate) struct CompileRootChangelogArgs {
+    #[arg(long, default_value = "CHANGELOG.md")]
+    pub(crate) root_changelog: PathBuf,
+    #[arg(long)]
+    pub(crate) check: bool,
+    #[arg(long, value_enum, default_value_t)]
+    pub(crate) date_format: ReleaseDateFormat,
+    #[command(flatten)]
+    pub(crate) worktree: WorktreeGuardArgs,
+    #[command(flatten)]
+    pub(crate) discovery: BuildpackDiscoveryArgs,
+    #[command(flatten)]
+    pub(crate) changelog: ChangelogLocationArgs,
+}
+
+pub(crate) fn execute(args: CompileRootChangelogArgs) -> Result<()> {
+    let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
+
+    if !args.check {
+        args.worktree.check(&current_dir).map_err(Error::Git)?;
+    }
+
+    let root_changelog_path = current_dir.join(&args.root_changelog);
+
+    let buildpack_dirs = args
+        .discovery
+        .find_buildpack_dirs(&current_dir, &[current_dir.join("target")])
+        .map_err(|e| Error::FindingBuildpacks(current_dir.clone(), e))?;
+
+    let mut changelogs = vec![];
+
+    for dir in buildpack_dirs {
+        let buildpack_file =
+            read_buildpack_file(dir.join("buildpack.toml")).map_err(Error::BuildpackFile)?;
+
+        if is_buildpack_release_disabled(&buildpack_file) {
+            log!(
+                path = buildpack_file.path;
+                "⏭️ Skipping disabled buildpack: {}",
+                buildpack_file.path.display()
+            );
+            continue;
+        }
+
+        let buildpack_id = get_buildpack_id(&buildpack_file).map_err(Error::BuildpackFile)?;
+
+        let changelog_file = args
+            .changelog
+            .read_changelog_file_from_dir(&dir, &current_dir)
+            .map_err(Error::ChangelogFile)?;
+
+        // a buildpack without its own changelog falls back to the one in the repository root, which
+        // would be overwritten with a summary of itself
+        if changelog_file.path == root_changelog_path {
+            Err(Error::RootChangelogIsBuildpackChangelog(
+                root_changelog_path.clone(),
+                buildpack_id.clone(),
+            ))?;
+        }
+
+        changelogs.push((buildpack_id, changelog_file.changelog));
+    }
+
+    if changelogs.is_empty() {
+        Err(Error::NoBuildpacksFound(current_dir.clone()))?;
+    }
+
+    let compiled_contents = compile_root_changelog(&changelogs, args.date_format);
+
+    let contents = match std::fs::read_to_string(&root_changelog_path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == ErrorKind::NotFound => String::new(),
+        Err(error) => Err(Error::ReadingChangelog(root_changelog_path.clone(), error))?,
+    };
+
+    if compiled_contents == contents {
+        log!(
+            path = root_changelog_path;
+            "✅️ Root changelog is up to date: {}",
+            root_changelog_path.display()
+        );
+    } else if args.check {
+        Err(Error::OutdatedRootChangelog(root_changelog_path))?;
+    } else {
+        write::write_file(&root_changelog_path, compiled_contents)
+            .map_err(|e| Error::WritingChangelog(root_changelog_path.clone(), e))?;
+        log!(
+            path = root_changelog_path;
+            "✅️ Compiled root changelog from {} buildpacks: {}",
+            changelogs.len(),
+            root_changelog_path.display()
+        );
+    }
+
+    Ok(())
+}
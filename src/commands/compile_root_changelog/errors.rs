@@ -0,0 +1,106 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::buildpack::{BuildpackDiscoveryError, BuildpackFileError};
+use languages_github_actions::changelog::ChangelogFileError;
+use languages_github_actions::git::GitError;
+use libcnb_data::buildpack::BuildpackId;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    GetCurrentDir(io::Error),
+    Git(GitError),
+    FindingBuildpacks(PathBuf, BuildpackDiscoveryError),
+    NoBuildpacksFound(PathBuf),
+    BuildpackFile(BuildpackFileError),
+    ChangelogFile(ChangelogFileError),
+    RootChangelogIsBuildpackChangelog(PathBuf, BuildpackId),
+    ReadingChangelog(PathBuf, io::Error),
+    WritingChangelog(PathBuf, io::Error),
+    OutdatedRootChangelog(PathBuf),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::GetCurrentDir(error) => {
+                write!(f, "Failed to get current directory\nError: {error}")
+            }
+
+            Error::Git(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::FindingBuildpacks(path, error) => {
+                write!(
+                    f,
+                    "Could not find buildpacks\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::NoBuildpacksFound(path) => {
+                write!(f, "No buildpacks found under {}", path.display())
+            }
+
+            Error::BuildpackFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::ChangelogFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::RootChangelogIsBuildpackChangelog(path, buildpack_id) => {
+                write!(
+                    f,
+                    "The root changelog is also the changelog of {buildpack_id}, use a different path with `--root-changelog`\nPath: {}",
+                    path.display()
+                )
+            }
+
+            Error::ReadingChangelog(path, error) => {
+                write!(
+                    f,
+                    "Could not read changelog\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::WritingChangelog(path, error) => {
+                write!(
+                    f,
+                    "Could not write changelog\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::OutdatedRootChangelog(path) => {
+                write!(
+                    f,
+                    "The root changelog needs to be compiled\nPath: {}",
+                    path.display()
+                )
+            }
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::Git(error) => error.category(),
+            Error::BuildpackFile(error) => error.category(),
+            Error::ChangelogFile(error) => error.category(),
+            Error::GetCurrentDir(_)
+            | Error::ReadingChangelog(_, _)
+            | Error::WritingChangelog(_, _) => ErrorCategory::Io,
+            Error::FindingBuildpacks(_, error) => error.category(),
+            Error::NoBuildpacksFound(_) | Error::RootChangelogIsBuildpackChangelog(_, _) => {
+                ErrorCategory::Config
+            }
+            Error::OutdatedRootChangelog(_) => ErrorCategory::Validation,
+        }
+    }
+}
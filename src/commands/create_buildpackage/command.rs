@@ -0,0 +1,104 @@
+use crate::commands::create_buildpackage::errors::Error;
+use clap::{Parser, ValueEnum};
+use languages_github_actions::buildpackage::create_buildpackage;
+use languages_github_actions::github::actions;
+use languages_github_actions::log;
+use languages_github_actions::oci::{push_image, ImageReference, RegistryCredentials};
+use languages_github_actions::registry_auth::find_registry_credentials;
+use languages_github_actions::write;
+use std::path::PathBuf;
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Creates a CNB buildpackage from a packaged buildpack directory and optionally pushes it to a registry", long_about = None)]
+pub(crate) struct CreateBuildpackageArgs {
+    #[arg(long)]
+    pub(crate) path: PathBuf,
+    #[arg(long, required_unless_present = "push")]
+    pub(crate) output: Option<PathBuf>,
+    #[arg(long, value_enum, default_value_t)]
+    pub(crate) format: BuildpackageFormat,
+    #[arg(long)]
+    pub(crate) push: Option<String>,
+    #[arg(long, requires = "registry_password")]
+    pub(crate) registry_username: Option<String>,
+    #[arg(long, requires = "registry_username")]
+    pub(crate) registry_password: Option<String>,
+}
+
+#[derive(ValueEnum, Debug, Clone, Default)]
+pub(crate) enum BuildpackageFormat {
+    #[default]
+    Cnb,
+    Oci,
+}
+
+pub(crate) fn execute(args: CreateBuildpackageArgs) -> Result<()> {
+    let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
+    let package_dir = current_dir.join(&args.path);
+
+    let image_reference = args
+        .push
+        .as_ref()
+        .map(|image| {
+            let image = if image.starts_with("docker://") {
+                image.clone()
+            } else {
+                format!("docker://{image}")
+            };
+            ImageReference::try_from(image.as_str()).map_err(Error::InvalidImageReference)
+        })
+        .transpose()?;
+
+    let (metadata, image) = create_buildpackage(&package_dir).map_err(Error::Buildpackage)?;
+
+    log!(
+        "✅️ Created buildpackage for {}@{}: {}",
+        metadata.id,
+        metadata.version,
+        image.digest()
+    );
+
+    if let Some(output) = &args.output {
+        let output = current_dir.join(output);
+        match args.format {
+            BuildpackageFormat::Cnb => image
+                .to_oci_archive()
+                .and_then(|archive| write::write_file(&output, archive)),
+            BuildpackageFormat::Oci => image.write_oci_layout(&output),
+        }
+        .map_err(|e| Error::WritingBuildpackage(output.clone(), e))?;
+
+        log!(path = output; "✅️ Wrote buildpackage: {}", output.display());
+
+        actions::set_output("output_path", output.display().to_string())
+            .map_err(Error::SetActionOutput)?;
+    }
+
+    if let Some(image_reference) = &image_reference {
+        let credentials = match (&args.registry_username, &args.registry_password) {
+            (Some(username), Some(password)) => Some(RegistryCredentials {
+                username: username.clone(),
+                password: password.clone(),
+            }),
+            _ => find_registry_credentials(&image_reference.registry),
+        };
+
+        let digest = push_image(&image, image_reference, credentials.as_ref())
+            .map_err(|e| Error::PushingImage(image_reference.to_string(), e))?;
+
+        let pushed_image = format!(
+            "{}/{}@{digest}",
+            image_reference.registry, image_reference.repository
+        );
+
+        log!("✅️ Pushed buildpackage: {pushed_image}");
+
+        actions::set_output("image", pushed_image).map_err(Error::SetActionOutput)?;
+    }
+
+    actions::set_output("digest", image.digest()).map_err(Error::SetActionOutput)?;
+
+    Ok(())
+}
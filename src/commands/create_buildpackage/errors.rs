@@ -0,0 +1,67 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::buildpackage::BuildpackageError;
+use languages_github_actions::github::actions::SetOutputError;
+use languages_github_actions::oci::OciError;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    GetCurrentDir(io::Error),
+    InvalidImageReference(OciError),
+    Buildpackage(BuildpackageError),
+    WritingBuildpackage(PathBuf, io::Error),
+    PushingImage(String, OciError),
+    SetActionOutput(SetOutputError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::GetCurrentDir(error) => {
+                write!(f, "Failed to get current directory\nError: {error}")
+            }
+
+            Error::InvalidImageReference(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::Buildpackage(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::WritingBuildpackage(path, error) => {
+                write!(
+                    f,
+                    "Could not write buildpackage\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::PushingImage(image, error) => {
+                write!(
+                    f,
+                    "Could not push buildpackage\nValue: {image}\nError: {error}"
+                )
+            }
+
+            Error::SetActionOutput(set_output_error) => match set_output_error {
+                SetOutputError::Opening(error) | SetOutputError::Writing(error) => {
+                    write!(f, "Could not write action output\nError: {error}")
+                }
+            },
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::GetCurrentDir(_) | Error::WritingBuildpackage(_, _) => ErrorCategory::Io,
+            Error::InvalidImageReference(error) | Error::PushingImage(_, error) => error.category(),
+            Error::Buildpackage(error) => error.category(),
+            Error::SetActionOutput(error) => error.category(),
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use crate::commands::deprecate_buildpack::errors::Error;
+use chrono::NaiveDate;
+use clap::Parser;
+use glob::glob;
+use languages_github_actions::builder::{
+    builder_references_buildpack, read_builder_file, remove_buildpack_from_builder, BuilderFile,
+};
+use languages_github_actions::buildpack::{
+    get_buildpack_dependency_ids, get_buildpack_id, read_buildpack_file, set_buildpack_deprecation,
+    BuildpackDiscoveryArgs,
+};
+use languages_github_actions::changelog::{insert_unreleased_change, ChangelogLocationArgs};
+use languages_github_actions::git::WorktreeGuardArgs;
+use languages_github_actions::github::actions;
+use languages_github_actions::log;
+use languages_github_actions::write;
+use libcnb_data::buildpack::BuildpackId;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Deprecates a buildpack by recording its end-of-life date, adding a changelog entry, and optionally removing it from builders", long_about = None)]
+pub(crate) struct DeprecateBuildpackArgs {
+    #[arg(long = "id")]
+    pub(crate) buildpack_id: BuildpackId,
+    #[arg(long)]
+    pub(crate) message: String,
+    #[arg(long)]
+    pub(crate) eol_date: NaiveDate,
+    #[arg(long)]
+    pub(crate) remove_from_builders: bool,
+    #[arg(long, default_value = "builders/*/builder.toml")]
+    pub(crate) builder_pattern: String,
+    #[command(flatten)]
+    pub(crate) worktree: WorktreeGuardArgs,
+    #[command(flatten)]
+    pub(crate) discovery: BuildpackDiscoveryArgs,
+    #[command(flatten)]
+    pub(crate) changelog: ChangelogLocationArgs,
+}
+
+pub(crate) fn execute(args: DeprecateBuildpackArgs) -> Result<()> {
+    let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
+
+    args.worktree.check(&current_dir).map_err(Error::Git)?;
+
+    let buildpack_dirs = args
+        .discovery
+        .find_buildpack_dirs(&current_dir, &[current_dir.join("target")])
+        .map_err(|e| Error::FindingBuildpacks(current_dir.clone(), e))?;
+
+    // composites aren't edited since dropping a buildpack from their order is a decision of its own
+    let mut buildpack_file = None;
+    for dir in buildpack_dirs {
+        let candidate =
+            read_buildpack_file(dir.join("buildpack.toml")).map_err(Error::BuildpackFile)?;
+        if get_buildpack_id(&candidate).map_err(Error::BuildpackFile)? == args.buildpack_id {
+            buildpack_file = Some(candidate);
+        } else if get_buildpack_dependency_ids(&candidate)
+            .map_err(Error::BuildpackFile)?
+            .contains(&args.buildpack_id)
+        {
+            log!(
+                "⚠️ {} is still included in composite buildpack {}",
+                args.buildpack_id,
+                candidate
+                    .path
+                    .strip_prefix(&current_dir)
+                    .unwrap_or(&candidate.path)
+                    .display()
+            );
+        }
+    }
+    let mut buildpack_file =
+        buildpack_file.ok_or_else(|| Error::UnknownBuildpackId(args.buildpack_id.clone()))?;
+    let buildpack_dir = buildpack_file
+        .path
+        .parent()
+        .unwrap_or(&current_dir)
+        .to_path_buf();
+
+    let eol_date = args.eol_date.format("%Y-%m-%d").to_string();
+
+    let mut edits = vec![];
+
+    let buildpack_contents = buildpack_file.document.to_string();
+    set_buildpack_deprecation(&mut buildpack_file, &args.message, &eol_date)
+        .map_err(Error::BuildpackFile)?;
+    if buildpack_file.document.to_string() != buildpack_contents {
+        edits.push((
+            buildpack_file.path.clone(),
+            buildpack_file.document.to_string(),
+        ));
+    }
+
+    let changelog_path = args
+        .changelog
+        .find_changelog(&buildpack_dir, &current_dir)
+        .map_err(Error::ChangelogFile)?;
+    let changelog_contents = std::fs::read_to_string(&changelog_path)
+        .map_err(|e| Error::ReadingFile(changelog_path.clone(), e))?;
+    let changelog_entry = format!(
+        "Deprecated this buildpack, it will reach end-of-life on {eol_date}. {}",
+        args.message.trim()
+    );
+    // the entry is only added once so the command can be rerun (e.g.; to remove it from builders)
+    if !changelog_contents.contains(&changelog_entry) {
+        let changelog_contents = insert_unreleased_change(&changelog_contents, &changelog_entry)
+            .map_err(|e| Error::AddingChangelogEntry(changelog_path.clone(), e))?;
+        edits.push((changelog_path, changelog_contents));
+    }
+
+    let mut builder_files = find_builder_files(&current_dir, &args.builder_pattern)?
+        .into_iter()
+        .map(|path| read_builder_file(path).map_err(Error::BuilderFile))
+        .collect::<Result<Vec<_>>>()?;
+    let builders = update_builders(
+        &mut builder_files,
+        &args.buildpack_id,
+        args.remove_from_builders,
+    );
+    for builder_file in &builder_files {
+        if !builders.contains(&builder_file.path) {
+            continue;
+        }
+        let relative_path = builder_file
+            .path
+            .strip_prefix(&current_dir)
+            .unwrap_or(&builder_file.path);
+        if args.remove_from_builders {
+            edits.push((builder_file.path.clone(), builder_file.document.to_string()));
+        } else {
+            log!(
+                "⚠️ {} is still included in builder {}",
+                args.buildpack_id,
+                relative_path.display()
+            );
+        }
+    }
+
+    let modified_files = write::write_planned_edits(&edits, &current_dir, Error::WritingFile)?;
+    log!(
+        "✅️ Deprecated {} with an end-of-life date of {eol_date}",
+        args.buildpack_id
+    );
+
+    let builders = builders
+        .iter()
+        .map(|path| {
+            path.strip_prefix(&current_dir)
+                .unwrap_or(path)
+                .display()
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+
+    actions::set_output("buildpack_id", args.buildpack_id.to_string())
+        .map_err(Error::SetActionOutput)?;
+    actions::set_output("eol_date", &eol_date).map_err(Error::SetActionOutput)?;
+    actions::set_output(
+        "announcement",
+        format!(
+            "`{}` is deprecated and will reach end-of-life on {eol_date}.\n\n{}",
+            args.buildpack_id,
+            args.message.trim()
+        ),
+    )
+    .map_err(Error::SetActionOutput)?;
+    actions::set_output("builders", json!(builders).to_string()).map_err(Error::SetActionOutput)?;
+    actions::set_output("modified_files", modified_files.join("\n"))
+        .map_err(Error::SetActionOutput)?;
+
+    Ok(())
+}
+
+fn find_builder_files(dir: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+    let pattern = dir.join(pattern).to_string_lossy().to_string();
+    let mut paths = glob(&pattern)
+        .map_err(|e| Error::InvalidBuilderPattern(pattern.clone(), e))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(Error::FindingBuilders)?;
+    paths.sort();
+    Ok(paths)
+}
+
+// returns the builders that include the buildpack, which are only edited when it's being removed
+fn update_builders(
+    builder_files: &mut [BuilderFile],
+    buildpack_id: &BuildpackId,
+    remove: bool,
+) -> Vec<PathBuf> {
+    builder_files
+        .iter_mut()
+        .filter(|builder_file| builder_references_buildpack(builder_file, buildpack_id))
+        .map(|builder_file| {
+            if remove {
+                remove_buildpack_from_builder(builder_file, buildpack_id);
+            }
+            builder_file.path.clone()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::deprecate_buildpack::command::update_builders;
+    use languages_github_actions::builder::{builder_references_buildpack, BuilderFile};
+    use libcnb_data::buildpack_id;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+    use toml_edit::Document;
+
+    #[test]
+    fn test_update_builders() {
+        let builder_file = |path: &str, buildpack_id: &str| {
+            BuilderFile {
+            path: PathBuf::from(path),
+            document: Document::from_str(&format!(
+                "[[buildpacks]]\n  id = \"{buildpack_id}\"\n  uri = \"docker://docker.io/heroku/buildpack@sha256:abc\"\n\n[[order]]\n  [[order.group]]\n    id = \"{buildpack_id}\"\n    version = \"1.0.0\"\n"
+            ))
+            .unwrap(),
+        }
+        };
+        let mut builder_files = vec![
+            builder_file("builders/20/builder.toml", "heroku/nodejs-yarn"),
+            builder_file("builders/22/builder.toml", "heroku/procfile"),
+        ];
+
+        assert_eq!(
+            update_builders(
+                &mut builder_files,
+                &buildpack_id!("heroku/nodejs-yarn"),
+                false
+            ),
+            vec![PathBuf::from("builders/20/builder.toml")]
+        );
+        assert!(builder_references_buildpack(
+            &builder_files[0],
+            &buildpack_id!("heroku/nodejs-yarn")
+        ));
+
+        assert_eq!(
+            update_builders(
+                &mut builder_files,
+                &buildpack_id!("heroku/nodejs-yarn"),
+                true
+            ),
+            vec![PathBuf::from("builders/20/builder.toml")]
+        );
+        assert_eq!(builder_files[0].document.to_string(), "");
+        assert!(builder_references_buildpack(
+            &builder_files[1],
+            &buildpack_id!("heroku/procfile")
+        ));
+    }
+}
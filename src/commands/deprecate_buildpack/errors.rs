@@ -0,0 +1,124 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::builder::BuilderFileError;
+use languages_github_actions::buildpack::{BuildpackDiscoveryError, BuildpackFileError};
+use languages_github_actions::changelog::{ChangelogError, ChangelogFileError};
+use languages_github_actions::git::GitError;
+use languages_github_actions::github::actions::SetOutputError;
+use libcnb_data::buildpack::BuildpackId;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    GetCurrentDir(io::Error),
+    Git(GitError),
+    FindingBuildpacks(PathBuf, BuildpackDiscoveryError),
+    BuildpackFile(BuildpackFileError),
+    UnknownBuildpackId(BuildpackId),
+    InvalidBuilderPattern(String, glob::PatternError),
+    FindingBuilders(glob::GlobError),
+    BuilderFile(BuilderFileError),
+    ReadingFile(PathBuf, io::Error),
+    ChangelogFile(ChangelogFileError),
+    AddingChangelogEntry(PathBuf, ChangelogError),
+    WritingFile(PathBuf, io::Error),
+    SetActionOutput(SetOutputError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::GetCurrentDir(error) => {
+                write!(f, "Failed to get current directory\nError: {error}")
+            }
+
+            Error::Git(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::FindingBuildpacks(path, error) => {
+                write!(
+                    f,
+                    "Could not find buildpacks\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::BuildpackFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::UnknownBuildpackId(buildpack_id) => {
+                write!(f, "No buildpack with id {buildpack_id} was found")
+            }
+
+            Error::InvalidBuilderPattern(pattern, error) => {
+                write!(f, "Invalid builder pattern `{pattern}`\nError: {error}")
+            }
+
+            Error::FindingBuilders(error) => {
+                write!(f, "I/O error while finding builders\nError: {error}")
+            }
+
+            Error::BuilderFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::ReadingFile(path, error) => {
+                write!(
+                    f,
+                    "Could not read file\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::ChangelogFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::AddingChangelogEntry(path, error) => {
+                write!(
+                    f,
+                    "Could not add changelog entry\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::WritingFile(path, error) => {
+                write!(
+                    f,
+                    "Could not write file\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::SetActionOutput(set_output_error) => match set_output_error {
+                SetOutputError::Opening(error) | SetOutputError::Writing(error) => {
+                    write!(f, "Could not write action output\nError: {error}")
+                }
+            },
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::Git(error) => error.category(),
+            Error::GetCurrentDir(_)
+            | Error::FindingBuilders(_)
+            | Error::ReadingFile(_, _)
+            | Error::WritingFile(_, _) => ErrorCategory::Io,
+            Error::FindingBuildpacks(_, error) => error.category(),
+            Error::BuildpackFile(error) => error.category(),
+            Error::BuilderFile(error) => error.category(),
+            Error::UnknownBuildpackId(_) | Error::InvalidBuilderPattern(_, _) => {
+                ErrorCategory::Config
+            }
+            Error::ChangelogFile(error) => error.category(),
+            Error::AddingChangelogEntry(_, error) => error.category(),
+            Error::SetActionOutput(error) => error.category(),
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use crate::commands::diff_sbom::errors::Error;
+use clap::Parser;
+use languages_github_actions::github::actions;
+use languages_github_actions::log;
+use languages_github_actions::sbom::{diff_sboms, format_sbom_diff, read_sbom};
+use std::path::PathBuf;
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Compares two CycloneDX or SPDX SBOMs and reports the added, removed, and changed components", long_about = None)]
+pub(crate) struct DiffSbomArgs {
+    #[arg(long)]
+    pub(crate) base: PathBuf,
+    #[arg(long)]
+    pub(crate) head: PathBuf,
+}
+
+pub(crate) fn execute(args: DiffSbomArgs) -> Result<()> {
+    let base = read_sbom(&args.base).map_err(Error::Sbom)?;
+    let head = read_sbom(&args.head).map_err(Error::Sbom)?;
+
+    let diff = diff_sboms(&base, &head);
+
+    log!(
+        "✅️ Compared SBOMs: {} added, {} removed, {} changed",
+        diff.added.len(),
+        diff.removed.len(),
+        diff.changed.len()
+    );
+
+    let json = serde_json::to_string(&diff).map_err(Error::SerializingJson)?;
+
+    actions::set_output("diff", json).map_err(Error::SetActionOutput)?;
+    actions::set_output("markdown", format_sbom_diff(&diff)).map_err(Error::SetActionOutput)?;
+    actions::set_output("has_changes", (!diff.is_empty()).to_string())
+        .map_err(Error::SetActionOutput)?;
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::github::actions::SetOutputError;
+use languages_github_actions::sbom::SbomError;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    Sbom(SbomError),
+    SerializingJson(serde_json::Error),
+    SetActionOutput(SetOutputError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Sbom(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::SerializingJson(error) => {
+                write!(f, "Could not serialize SBOM diff into json\nError: {error}")
+            }
+
+            Error::SetActionOutput(set_output_error) => match set_output_error {
+                SetOutputError::Opening(error) | SetOutputError::Writing(error) => {
+                    write!(f, "Could not write action output\nError: {error}")
+                }
+            },
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::Sbom(error) => error.category(),
+            Error::SerializingJson(_) => ErrorCategory::Parse,
+            Error::SetActionOutput(error) => error.category(),
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::commands::extract_changelog::errors::Error;
+use clap::Parser;
+use languages_github_actions::buildpack::{
+    get_buildpack_id, read_buildpack_file, BuildpackDiscoveryArgs,
+};
+use languages_github_actions::changelog::{
+    extract_changelog_sections, ChangelogLocationArgs, ChangelogSection, VersionRange,
+};
+use languages_github_actions::github::actions;
+use languages_github_actions::github::actions::{LargeOutputArgs, LargeOutputError};
+use languages_github_actions::log;
+use libcnb_data::buildpack::BuildpackId;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Extracts the changelog sections of a buildpack for a version or a range of versions", long_about = None, disable_version_flag = true)]
+pub(crate) struct ExtractChangelogArgs {
+    #[arg(long)]
+    pub(crate) buildpack_id: Option<BuildpackId>,
+    #[arg(long, value_name = "VERSION|FROM..TO")]
+    pub(crate) version: String,
+    #[command(flatten)]
+    pub(crate) output: LargeOutputArgs,
+    #[command(flatten)]
+    pub(crate) discovery: BuildpackDiscoveryArgs,
+    #[command(flatten)]
+    pub(crate) changelog: ChangelogLocationArgs,
+}
+
+pub(crate) fn execute(args: ExtractChangelogArgs) -> Result<()> {
+    let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
+
+    let range = args
+        .version
+        .parse::<VersionRange>()
+        .map_err(Error::InvalidVersion)?;
+
+    let (buildpack_id, buildpack_dir) = find_buildpack_dir(&args, &current_dir)?;
+
+    let changelog_path = args
+        .changelog
+        .find_changelog(&buildpack_dir, &current_dir)
+        .map_err(Error::ChangelogFile)?;
+    let contents = std::fs::read_to_string(&changelog_path)
+        .map_err(|e| Error::ReadingChangelog(changelog_path.clone(), e))?;
+    let sections = extract_changelog_sections(&contents, &range)
+        .map_err(|e| Error::ExtractingChangelog(changelog_path.clone(), e))?;
+    if sections.is_empty() {
+        Err(Error::NoReleasesInRange(args.version.clone()))?;
+    }
+
+    for section in &sections {
+        log!(buildpack_id = buildpack_id; "✅️ Extracted {buildpack_id} {}", section.version);
+    }
+
+    args.output
+        .set_output("changelog", render_sections(&range, &sections))
+        .map_err(Error::SetActionOutput)?;
+    actions::set_output(
+        "versions",
+        json!(sections
+            .iter()
+            .map(|section| &section.version)
+            .collect::<Vec<_>>())
+        .to_string(),
+    )
+    .map_err(|e| Error::SetActionOutput(LargeOutputError::SetOutput(e)))?;
+
+    Ok(())
+}
+
+// the buildpack id can be left out in a repository with a single buildpack
+fn find_buildpack_dir(
+    args: &ExtractChangelogArgs,
+    current_dir: &Path,
+) -> Result<(BuildpackId, PathBuf)> {
+    let buildpack_dirs = args
+        .discovery
+        .find_buildpack_dirs(current_dir, &[current_dir.join("target")])
+        .map_err(|e| Error::FindingBuildpacks(current_dir.to_path_buf(), e))?;
+
+    let mut buildpacks = vec![];
+    for dir in buildpack_dirs {
+        let buildpack_file =
+            read_buildpack_file(dir.join("buildpack.toml")).map_err(Error::BuildpackFile)?;
+        let id = get_buildpack_id(&buildpack_file).map_err(Error::BuildpackFile)?;
+        buildpacks.push((id, dir));
+    }
+
+    match &args.buildpack_id {
+        Some(buildpack_id) => buildpacks
+            .into_iter()
+            .find(|(id, _)| id == buildpack_id)
+            .ok_or_else(|| Error::UnknownBuildpackId(buildpack_id.clone())),
+        None if buildpacks.len() == 1 => Ok(buildpacks.remove(0)),
+        None => Err(Error::AmbiguousBuildpack(
+            buildpacks.into_iter().map(|(id, _)| id).collect(),
+        )),
+    }
+}
+
+// a single version is just the section body, which is what a GitHub Release body needs, a range keeps
+// each section's heading so the releases can be told apart
+fn render_sections(range: &VersionRange, sections: &[ChangelogSection]) -> String {
+    match (range, sections) {
+        (VersionRange::Version(_), [section]) => section.body.clone(),
+        _ => sections
+            .iter()
+            .map(|section| {
+                if section.body.is_empty() {
+                    section.heading.clone()
+                } else {
+                    format!("{}\n\n{}", section.heading, section.body)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::extract_changelog::command::render_sections;
+    use languages_github_actions::changelog::{ChangelogSection, VersionRange};
+
+    #[test]
+    fn test_render_sections() {
+        let section = |version: &str, body: &str| ChangelogSection {
+            version: version.to_string(),
+            heading: format!("## [{version}] - 2023-05-10"),
+            body: body.to_string(),
+        };
+        let sections = vec![
+            section("1.1.0", "- Added `pnpm` support.\n\n  ```\n  pnpm\n  ```"),
+            section("1.0.0", ""),
+        ];
+
+        assert_eq!(
+            render_sections(&VersionRange::Version("1.1.0".to_string()), &sections[..1]),
+            "- Added `pnpm` support.\n\n  ```\n  pnpm\n  ```"
+        );
+        assert_eq!(
+            render_sections(&VersionRange::Range(None, Some("1.1.0".to_string())), &sections),
+            "## [1.1.0] - 2023-05-10\n\n- Added `pnpm` support.\n\n  ```\n  pnpm\n  ```\n\n## [1.0.0] - 2023-05-10"
+        );
+    }
+}
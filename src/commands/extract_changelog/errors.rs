@@ -0,0 +1,107 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::buildpack::{BuildpackDiscoveryError, BuildpackFileError};
+use languages_github_actions::changelog::{ChangelogError, ChangelogFileError};
+use languages_github_actions::github::actions::LargeOutputError;
+use libcnb_data::buildpack::BuildpackId;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    GetCurrentDir(std::io::Error),
+    InvalidVersion(ChangelogError),
+    FindingBuildpacks(PathBuf, BuildpackDiscoveryError),
+    BuildpackFile(BuildpackFileError),
+    UnknownBuildpackId(BuildpackId),
+    AmbiguousBuildpack(Vec<BuildpackId>),
+    ChangelogFile(ChangelogFileError),
+    ReadingChangelog(PathBuf, std::io::Error),
+    ExtractingChangelog(PathBuf, ChangelogError),
+    NoReleasesInRange(String),
+    SetActionOutput(LargeOutputError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::GetCurrentDir(error) => {
+                write!(f, "Failed to get current directory\nError: {error}")
+            }
+
+            Error::InvalidVersion(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::FindingBuildpacks(path, error) => {
+                write!(
+                    f,
+                    "Could not find buildpacks\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::BuildpackFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::UnknownBuildpackId(buildpack_id) => {
+                write!(f, "No buildpack found with id `{buildpack_id}`")
+            }
+
+            Error::AmbiguousBuildpack(buildpack_ids) => {
+                write!(
+                    f,
+                    "Expected a single buildpack but found {}, choose one with `--buildpack-id`{}",
+                    buildpack_ids.len(),
+                    buildpack_ids
+                        .iter()
+                        .map(|buildpack_id| format!("\n• {buildpack_id}"))
+                        .collect::<String>()
+                )
+            }
+
+            Error::ChangelogFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::ReadingChangelog(path, error) => {
+                write!(
+                    f,
+                    "Could not read changelog\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::ExtractingChangelog(path, error) => {
+                write!(
+                    f,
+                    "Could not extract changelog sections\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::NoReleasesInRange(range) => {
+                write!(f, "No releases in changelog for {range}")
+            }
+
+            Error::SetActionOutput(error) => {
+                write!(f, "{error}")
+            }
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::GetCurrentDir(_) | Error::ReadingChangelog(_, _) => ErrorCategory::Io,
+            Error::FindingBuildpacks(_, error) => error.category(),
+            Error::InvalidVersion(error) | Error::ExtractingChangelog(_, error) => error.category(),
+            Error::BuildpackFile(error) => error.category(),
+            Error::ChangelogFile(error) => error.category(),
+            Error::UnknownBuildpackId(_) | Error::AmbiguousBuildpack(_) => ErrorCategory::Config,
+            Error::NoReleasesInRange(_) => ErrorCategory::Validation,
+            Error::SetActionOutput(error) => error.category(),
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use crate::commands::flatten_buildpack::errors::Error;
+use crate::commands::flatten_buildpack::tree::{
+    flatten, parse_label_orders, parse_toml_orders, render_markdown, GroupEntry, NodeSource,
+    OrderNode,
+};
+use clap::Parser;
+use languages_github_actions::buildpack::{
+    get_buildpack_id, get_buildpack_version, read_buildpack_file, BuildpackDiscoveryArgs,
+    BuildpackFile,
+};
+use languages_github_actions::buildpackage::BUILDPACK_LAYERS_LABEL;
+use languages_github_actions::cancellation::check_cancelled;
+use languages_github_actions::cnb_registry::get_buildpack_address;
+use languages_github_actions::github::actions;
+use languages_github_actions::log;
+use languages_github_actions::oci::{fetch_image_config, ImageReference};
+use libcnb_data::buildpack::BuildpackId;
+use serde_json::Value;
+use std::collections::HashMap;
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Resolves the order of a composite buildpack into a flattened list and a tree of the buildpacks it includes", long_about = None)]
+pub(crate) struct FlattenBuildpackArgs {
+    #[arg(long)]
+    pub(crate) buildpack_id: BuildpackId,
+    #[arg(long)]
+    pub(crate) no_remote: bool,
+    #[command(flatten)]
+    pub(crate) discovery: BuildpackDiscoveryArgs,
+}
+
+pub(crate) fn execute(args: FlattenBuildpackArgs) -> Result<()> {
+    let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
+
+    let buildpack_dirs = args
+        .discovery
+        .find_buildpack_dirs(&current_dir, &[current_dir.join("target")])
+        .map_err(|e| Error::FindingBuildpacks(current_dir.clone(), e))?;
+
+    let mut local_buildpacks = HashMap::new();
+    for dir in buildpack_dirs {
+        let buildpack_file =
+            read_buildpack_file(dir.join("buildpack.toml")).map_err(Error::BuildpackFile)?;
+        let buildpack_id = get_buildpack_id(&buildpack_file).map_err(Error::BuildpackFile)?;
+        local_buildpacks.insert(buildpack_id.to_string(), buildpack_file);
+    }
+
+    if !local_buildpacks.contains_key(args.buildpack_id.as_str()) {
+        Err(Error::UnknownBuildpack(args.buildpack_id.clone()))?;
+    }
+
+    let mut resolver = OrderResolver {
+        local_buildpacks,
+        no_remote: args.no_remote,
+        image_buildpacks: HashMap::new(),
+    };
+    let root = resolver.resolve(
+        &GroupEntry {
+            id: args.buildpack_id.to_string(),
+            version: None,
+            optional: false,
+        },
+        &mut vec![],
+    )?;
+
+    let tree = render_markdown(&root);
+    eprint!("{tree}");
+
+    actions::set_output("buildpacks", flatten(&root).to_string())
+        .map_err(Error::SetActionOutput)?;
+    actions::set_output("tree", tree).map_err(Error::SetActionOutput)?;
+
+    Ok(())
+}
+
+struct ImageBuildpack {
+    address: String,
+    orders: Vec<Vec<GroupEntry>>,
+}
+
+// buildpacks in the project are read from their `buildpack.toml`, others are looked up in the CNB
+// registry and read from the layers label of their image, which also describes every buildpack
+// packaged with it so nested composites only need one lookup
+struct OrderResolver {
+    local_buildpacks: HashMap<String, BuildpackFile>,
+    no_remote: bool,
+    image_buildpacks: HashMap<(String, String), ImageBuildpack>,
+}
+
+impl OrderResolver {
+    fn resolve(&mut self, entry: &GroupEntry, path: &mut Vec<String>) -> Result<OrderNode> {
+        if path.contains(&entry.id) {
+            Err(Error::Cycle(
+                path.iter().chain([&entry.id]).cloned().collect(),
+            ))?;
+        }
+
+        let (version, source, groups) = match self.local_buildpacks.get(&entry.id) {
+            Some(buildpack_file) => {
+                let local_version =
+                    get_buildpack_version(buildpack_file).map_err(Error::BuildpackFile)?;
+                (
+                    entry
+                        .version
+                        .clone()
+                        .or_else(|| Some(local_version.to_string())),
+                    NodeSource::Local,
+                    parse_toml_orders(&buildpack_file.document),
+                )
+            }
+            None => match &entry.version {
+                Some(version) => match self.find_image_buildpack(&entry.id, version)? {
+                    Some(image_buildpack) => (
+                        Some(version.clone()),
+                        NodeSource::Image(image_buildpack.address.clone()),
+                        image_buildpack.orders.clone(),
+                    ),
+                    None => (Some(version.clone()), NodeSource::Unresolved, vec![]),
+                },
+                None => (None, NodeSource::Unresolved, vec![]),
+            },
+        };
+
+        path.push(entry.id.clone());
+        let mut orders = vec![];
+        for group in groups {
+            let mut order = vec![];
+            for child in &group {
+                order.push(self.resolve(child, path)?);
+            }
+            orders.push(order);
+        }
+        path.pop();
+
+        Ok(OrderNode {
+            id: entry.id.clone(),
+            version,
+            optional: entry.optional,
+            source,
+            orders,
+        })
+    }
+
+    fn find_image_buildpack(&mut self, id: &str, version: &str) -> Result<Option<&ImageBuildpack>> {
+        let key = (id.to_string(), version.to_string());
+        if !self.image_buildpacks.contains_key(&key) && !self.no_remote {
+            check_cancelled().map_err(Error::Cancelled)?;
+            if let Some(address) =
+                get_buildpack_address(id, version).map_err(Error::CheckingRegistry)?
+            {
+                log!("ℹ️ Reading {id}@{version} from {address}");
+                self.read_image_buildpacks(&address)?;
+            }
+        }
+        Ok(self.image_buildpacks.get(&key))
+    }
+
+    fn read_image_buildpacks(&mut self, address: &str) -> Result<()> {
+        let uri = format!("docker://{}", address.trim_start_matches("docker://"));
+        let image = ImageReference::try_from(uri.as_str())
+            .map_err(|e| Error::FetchingImage(address.to_string(), e))?;
+        let config =
+            fetch_image_config(&image).map_err(|e| Error::FetchingImage(address.to_string(), e))?;
+
+        let layers = config["config"]["Labels"][BUILDPACK_LAYERS_LABEL]
+            .as_str()
+            .and_then(|label| serde_json::from_str::<Value>(label).ok())
+            .ok_or_else(|| Error::InvalidLayersLabel(address.to_string()))?;
+
+        for (id, versions) in layers.as_object().into_iter().flatten() {
+            for (version, buildpack) in versions.as_object().into_iter().flatten() {
+                self.image_buildpacks
+                    .entry((id.clone(), version.clone()))
+                    .or_insert_with(|| ImageBuildpack {
+                        address: address.to_string(),
+                        orders: parse_label_orders(&buildpack["order"]),
+                    });
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::buildpack::{BuildpackDiscoveryError, BuildpackFileError};
+use languages_github_actions::cancellation::CancellationError;
+use languages_github_actions::cnb_registry::CnbRegistryError;
+use languages_github_actions::github::actions::SetOutputError;
+use languages_github_actions::oci::OciError;
+use libcnb_data::buildpack::BuildpackId;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    GetCurrentDir(io::Error),
+    FindingBuildpacks(PathBuf, BuildpackDiscoveryError),
+    BuildpackFile(BuildpackFileError),
+    UnknownBuildpack(BuildpackId),
+    Cycle(Vec<String>),
+    CheckingRegistry(CnbRegistryError),
+    FetchingImage(String, OciError),
+    InvalidLayersLabel(String),
+    Cancelled(CancellationError),
+    SetActionOutput(SetOutputError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::GetCurrentDir(error) => {
+                write!(f, "Failed to get current directory\nError: {error}")
+            }
+
+            Error::FindingBuildpacks(path, error) => {
+                write!(
+                    f,
+                    "Could not find buildpacks\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::BuildpackFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::UnknownBuildpack(buildpack_id) => {
+                write!(
+                    f,
+                    "No buildpack with id `{buildpack_id}` found in the project"
+                )
+            }
+
+            Error::Cycle(path) => {
+                write!(f, "Buildpack order contains a cycle: {}", path.join(" → "))
+            }
+
+            Error::CheckingRegistry(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::FetchingImage(address, error) => {
+                write!(
+                    f,
+                    "Could not read the buildpack image\nImage: {address}\nError: {error}"
+                )
+            }
+
+            Error::InvalidLayersLabel(address) => {
+                write!(
+                    f,
+                    "Buildpack image has a missing or invalid `io.buildpacks.buildpack.layers` label\nImage: {address}"
+                )
+            }
+
+            Error::Cancelled(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::SetActionOutput(set_output_error) => match set_output_error {
+                SetOutputError::Opening(error) | SetOutputError::Writing(error) => {
+                    write!(f, "Could not write action output\nError: {error}")
+                }
+            },
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::GetCurrentDir(_) => ErrorCategory::Io,
+            Error::FindingBuildpacks(_, error) => error.category(),
+            Error::BuildpackFile(error) => error.category(),
+            Error::UnknownBuildpack(_) => ErrorCategory::Config,
+            Error::Cycle(_) => ErrorCategory::Validation,
+            Error::CheckingRegistry(error) => error.category(),
+            Error::FetchingImage(_, error) => error.category(),
+            Error::InvalidLayersLabel(_) => ErrorCategory::Parse,
+            Error::Cancelled(error) => error.category(),
+            Error::SetActionOutput(error) => error.category(),
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use toml_edit::{Document, Item};
+
+// a buildpack referenced from an `[[order.group]]`, the version is missing when the group doesn't
+// pin one
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct GroupEntry {
+    pub(crate) id: String,
+    pub(crate) version: Option<String>,
+    pub(crate) optional: bool,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) enum NodeSource {
+    Local,
+    Image(String),
+    Unresolved,
+}
+
+impl Display for NodeSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NodeSource::Local => write!(f, "local"),
+            NodeSource::Image(_) => write!(f, "image"),
+            NodeSource::Unresolved => write!(f, "unresolved"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct OrderNode {
+    pub(crate) id: String,
+    pub(crate) version: Option<String>,
+    pub(crate) optional: bool,
+    pub(crate) source: NodeSource,
+    pub(crate) orders: Vec<Vec<OrderNode>>,
+}
+
+pub(crate) fn parse_toml_orders(document: &Document) -> Vec<Vec<GroupEntry>> {
+    document
+        .get("order")
+        .and_then(Item::as_array_of_tables)
+        .map(|orders| {
+            orders
+                .iter()
+                .map(|order| {
+                    order
+                        .get("group")
+                        .and_then(Item::as_array_of_tables)
+                        .map(|groups| {
+                            groups
+                                .iter()
+                                .filter_map(|group| {
+                                    Some(GroupEntry {
+                                        id: group.get("id")?.as_str()?.to_string(),
+                                        version: group
+                                            .get("version")
+                                            .and_then(Item::as_str)
+                                            .map(String::from),
+                                        optional: group
+                                            .get("optional")
+                                            .and_then(Item::as_bool)
+                                            .unwrap_or(false),
+                                    })
+                                })
+                                .collect()
+                        })
+                        .unwrap_or_default()
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// the `order` of a buildpack in the `io.buildpacks.buildpack.layers` label of a buildpackage
+pub(crate) fn parse_label_orders(order: &Value) -> Vec<Vec<GroupEntry>> {
+    order
+        .as_array()
+        .map(|orders| {
+            orders
+                .iter()
+                .map(|order| {
+                    order["group"]
+                        .as_array()
+                        .map(|groups| {
+                            groups
+                                .iter()
+                                .filter_map(|group| {
+                                    Some(GroupEntry {
+                                        id: group["id"].as_str()?.to_string(),
+                                        version: group["version"].as_str().map(String::from),
+                                        optional: group["optional"].as_bool().unwrap_or(false),
+                                    })
+                                })
+                                .collect()
+                        })
+                        .unwrap_or_default()
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// every buildpack below the root in the order it's first reached, a buildpack used by several
+// composites is only listed once per version
+pub(crate) fn flatten(root: &OrderNode) -> Value {
+    fn visit(
+        node: &OrderNode,
+        seen: &mut HashSet<(String, Option<String>)>,
+        list: &mut Vec<Value>,
+    ) {
+        for child in node.orders.iter().flatten() {
+            if seen.insert((child.id.clone(), child.version.clone())) {
+                list.push(json!({
+                    "id": child.id,
+                    "version": child.version,
+                    "optional": child.optional,
+                    "composite": !child.orders.is_empty(),
+                    "source": child.source.to_string(),
+                    "address": match &child.source {
+                        NodeSource::Image(address) => Some(address),
+                        _ => None,
+                    },
+                }));
+            }
+            visit(child, seen, list);
+        }
+    }
+
+    let mut list = vec![];
+    visit(root, &mut HashSet::new(), &mut list);
+    json!(list)
+}
+
+// a nested markdown list, composites with more than one order get a level for each order
+pub(crate) fn render_markdown(root: &OrderNode) -> String {
+    fn render(node: &OrderNode, depth: usize, lines: &mut Vec<String>) {
+        let indent = "  ".repeat(depth);
+        let name = match &node.version {
+            Some(version) => format!("{}@{version}", node.id),
+            None => node.id.clone(),
+        };
+        let notes = [
+            node.optional.then_some("optional"),
+            (node.source == NodeSource::Unresolved).then_some("unresolved"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+        if notes.is_empty() {
+            lines.push(format!("{indent}- {name}"));
+        } else {
+            lines.push(format!("{indent}- {name} ({})", notes.join(", ")));
+        }
+
+        match node.orders.as_slice() {
+            [group] => group
+                .iter()
+                .for_each(|child| render(child, depth + 1, lines)),
+            orders => {
+                for (index, group) in orders.iter().enumerate() {
+                    lines.push(format!("{indent}  - Order {}", index + 1));
+                    group
+                        .iter()
+                        .for_each(|child| render(child, depth + 2, lines));
+                }
+            }
+        }
+    }
+
+    let mut lines = vec![];
+    render(root, 0, &mut lines);
+    format!("{}\n", lines.join("\n"))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::flatten_buildpack::tree::{
+        flatten, parse_label_orders, parse_toml_orders, render_markdown, GroupEntry, NodeSource,
+        OrderNode,
+    };
+    use serde_json::json;
+    use std::str::FromStr;
+    use toml_edit::Document;
+
+    fn node(id: &str, version: &str, source: NodeSource, orders: Vec<Vec<OrderNode>>) -> OrderNode {
+        OrderNode {
+            id: id.to_string(),
+            version: Some(version.to_string()),
+            optional: false,
+            source,
+            orders,
+        }
+    }
+
+    #[test]
+    fn test_parse_orders() {
+        let document = Document::from_str(
+            r#"
+[[order]]
+[[order.group]]
+id = "heroku/nodejs-engine"
+version = "1.0.0"
+
+[[order.group]]
+id = "heroku/procfile"
+optional = true
+"#,
+        )
+        .unwrap();
+        let expected = vec![vec![
+            GroupEntry {
+                id: "heroku/nodejs-engine".to_string(),
+                version: Some("1.0.0".to_string()),
+                optional: false,
+            },
+            GroupEntry {
+                id: "heroku/procfile".to_string(),
+                version: None,
+                optional: true,
+            },
+        ]];
+        assert_eq!(parse_toml_orders(&document), expected);
+        assert_eq!(
+            parse_label_orders(&json!([{ "group": [
+                { "id": "heroku/nodejs-engine", "version": "1.0.0" },
+                { "id": "heroku/procfile", "optional": true },
+            ]}])),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_render_tree() {
+        let engine = node("heroku/nodejs-engine", "1.0.0", NodeSource::Local, vec![]);
+        let procfile = OrderNode {
+            optional: true,
+            ..node(
+                "heroku/procfile",
+                "2.0.0",
+                NodeSource::Image("docker.io/heroku/procfile@sha256:abc".to_string()),
+                vec![],
+            )
+        };
+        let yarn = node(
+            "heroku/nodejs-yarn",
+            "1.0.0",
+            NodeSource::Unresolved,
+            vec![],
+        );
+        let root = node(
+            "heroku/nodejs",
+            "1.0.0",
+            NodeSource::Local,
+            vec![
+                vec![engine.clone(), yarn, procfile.clone()],
+                vec![engine, procfile],
+            ],
+        );
+
+        assert_eq!(
+            render_markdown(&root),
+            r#"- heroku/nodejs@1.0.0
+  - Order 1
+    - heroku/nodejs-engine@1.0.0
+    - heroku/nodejs-yarn@1.0.0 (unresolved)
+    - heroku/procfile@2.0.0 (optional)
+  - Order 2
+    - heroku/nodejs-engine@1.0.0
+    - heroku/procfile@2.0.0 (optional)
+"#
+        );
+        assert_eq!(
+            flatten(&root),
+            json!([
+                { "id": "heroku/nodejs-engine", "version": "1.0.0", "optional": false, "composite": false, "source": "local", "address": null },
+                { "id": "heroku/nodejs-yarn", "version": "1.0.0", "optional": false, "composite": false, "source": "unresolved", "address": null },
+                { "id": "heroku/procfile", "version": "2.0.0", "optional": true, "composite": false, "source": "image", "address": "docker.io/heroku/procfile@sha256:abc" },
+            ])
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use crate::commands::fmt_changelogs::errors::Error;
+use clap::Parser;
+use languages_github_actions::buildpack::BuildpackDiscoveryArgs;
+use languages_github_actions::changelog::{
+    generate_release_declarations, get_link_definitions, Changelog, ChangelogLocationArgs,
+    ReleaseDateFormat,
+};
+use languages_github_actions::debug_artifacts::capture_markdown_failure;
+use languages_github_actions::git::WorktreeGuardArgs;
+use languages_github_actions::log;
+use languages_github_actions::write;
+use uriparse::URI;
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Re-renders the changelog of each detected buildpack into a canonical Keep a Changelog format", long_about = None)]
+pub(crate) struct FmtChangelogsArgs {
+    #[arg(long)]
+    pub(crate) check: bool,
+    #[arg(long)]
+    pub(crate) repository_url: Option<String>,
+    #[arg(long, value_enum, default_value_t)]
+    pub(crate) date_format: ReleaseDateFormat,
+    #[command(flatten)]
+    pub(crate) worktree: WorktreeGuardArgs,
+    #[command(flatten)]
+    pub(crate) discovery: BuildpackDiscoveryArgs,
+    #[command(flatten)]
+    pub(crate) changelog: ChangelogLocationArgs,
+}
+
+pub(crate) fn execute(args: FmtChangelogsArgs) -> Result<()> {
+    let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
+
+    args.worktree.check(&current_dir).map_err(Error::Git)?;
+
+    let repository_url = args
+        .repository_url
+        .map(|url| {
+            URI::try_from(url.as_str())
+                .map(|uri| uri.into_owned())
+                .map_err(|e| Error::InvalidRepositoryUrl(url.clone(), e))
+        })
+        .transpose()?;
+
+    let buildpack_dirs = args
+        .discovery
+        .find_buildpack_dirs(&current_dir, &[current_dir.join("target")])
+        .map_err(|e| Error::FindingBuildpacks(current_dir.clone(), e))?;
+
+    let mut unformatted_changelogs = vec![];
+
+    for dir in buildpack_dirs {
+        let path = args
+            .changelog
+            .find_changelog(&dir, &current_dir)
+            .map_err(Error::ChangelogFile)?;
+
+        let contents =
+            std::fs::read_to_string(&path).map_err(|e| Error::ReadingChangelog(path.clone(), e))?;
+
+        let changelog = Changelog::try_from(contents.as_str())
+            .map_err(|e| {
+                capture_markdown_failure(&path, &contents, &e);
+                Error::ParsingChangelog(path.clone(), e)
+            })?
+            .normalized();
+
+        let release_declarations = match &repository_url {
+            Some(repository) => generate_release_declarations(&changelog, repository.to_string()),
+            None => get_link_definitions(&contents)
+                .map_err(|e| Error::ParsingLinkDefinitions(path.clone(), e))?
+                .join("\n"),
+        };
+
+        let formatted_contents = if release_declarations.is_empty() {
+            changelog.display_with(args.date_format).to_string()
+        } else {
+            format!(
+                "{}\n{release_declarations}",
+                changelog.display_with(args.date_format)
+            )
+        };
+
+        if formatted_contents == contents {
+            continue;
+        }
+
+        if args.check {
+            log!(path = path; "❌ Changelog is not formatted: {}", path.display());
+            unformatted_changelogs.push(path);
+        } else {
+            write::write_file(&path, formatted_contents)
+                .map_err(|e| Error::WritingChangelog(path.clone(), e))?;
+            log!(path = path; "✅️ Formatted changelog: {}", path.display());
+        }
+    }
+
+    if !unformatted_changelogs.is_empty() {
+        Err(Error::UnformattedChangelogs(unformatted_changelogs))?;
+    }
+
+    Ok(())
+}
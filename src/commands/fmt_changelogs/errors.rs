@@ -0,0 +1,113 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::buildpack::BuildpackDiscoveryError;
+use languages_github_actions::changelog::{ChangelogError, ChangelogFileError};
+use languages_github_actions::git::GitError;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+use uriparse::URIError;
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    GetCurrentDir(io::Error),
+    Git(GitError),
+    InvalidRepositoryUrl(String, URIError),
+    FindingBuildpacks(PathBuf, BuildpackDiscoveryError),
+    ChangelogFile(ChangelogFileError),
+    ReadingChangelog(PathBuf, io::Error),
+    ParsingChangelog(PathBuf, ChangelogError),
+    ParsingLinkDefinitions(PathBuf, String),
+    WritingChangelog(PathBuf, io::Error),
+    UnformattedChangelogs(Vec<PathBuf>),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::GetCurrentDir(error) => {
+                write!(f, "Failed to get current directory\nError: {error}")
+            }
+
+            Error::Git(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::InvalidRepositoryUrl(value, error) => {
+                write!(f, "Invalid URL `{value}`\nError: {error}")
+            }
+
+            Error::FindingBuildpacks(path, error) => {
+                write!(
+                    f,
+                    "Could not find buildpacks\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::ChangelogFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::ReadingChangelog(path, error) => {
+                write!(
+                    f,
+                    "Could not read changelog\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::ParsingChangelog(path, error) => {
+                write!(
+                    f,
+                    "Could not parse changelog\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::ParsingLinkDefinitions(path, error) => {
+                write!(
+                    f,
+                    "Could not parse link definitions in changelog\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::WritingChangelog(path, error) => {
+                write!(
+                    f,
+                    "Could not write changelog\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::UnformattedChangelogs(paths) => {
+                write!(
+                    f,
+                    "The following changelogs need to be formatted:\n{}",
+                    paths
+                        .iter()
+                        .map(|path| format!("• {}", path.display()))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            }
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::ChangelogFile(error) => error.category(),
+            Error::Git(error) => error.category(),
+            Error::GetCurrentDir(_)
+            | Error::ReadingChangelog(_, _)
+            | Error::WritingChangelog(_, _) => ErrorCategory::Io,
+            Error::FindingBuildpacks(_, error) => error.category(),
+            Error::InvalidRepositoryUrl(_, _) => ErrorCategory::Config,
+            Error::ParsingChangelog(_, error) => error.category(),
+            Error::ParsingLinkDefinitions(_, _) => ErrorCategory::Parse,
+            Error::UnformattedChangelogs(_) => ErrorCategory::Validation,
+        }
+    }
+}
@@ -1,36 +1,705 @@
 use crate::commands::generate_buildpack_matrix::errors::Error;
-use crate::github::actions;
+use crate::commands::plan_release::command::PublishPlanEntry;
 use clap::Parser;
-use libcnb_package::{find_buildpack_dirs, read_buildpack_data};
-use std::collections::HashMap;
+use languages_github_actions::buildpack::{
+    get_buildpack_dependency_ids, get_buildpack_docker_repository, get_buildpack_id,
+    get_buildpack_version, is_buildpack_release_disabled, read_buildpack_file,
+    BuildpackDiscoveryArgs, BuildpackFile,
+};
+use languages_github_actions::git::get_changed_files;
+use languages_github_actions::github::actions;
+use languages_github_actions::github::actions::{LargeOutputArgs, LargeOutputError};
+use languages_github_actions::lifecycle::{min_lifecycle_version, ApiVersion};
+use languages_github_actions::log;
+use languages_github_actions::shared_paths::SharedPathsArgs;
+use libcnb_data::buildpack::BuildpackId;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+use toml_edit::Item;
 
 type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Parser, Debug)]
-#[command(author, version, about = "Generates a JSON list of {id, path} entries for each buildpack detected", long_about = None)]
-pub(crate) struct GenerateBuildpackMatrixArgs;
+#[command(author, version, about = "Generates a JSON list of packaging entries for each buildpack detected", long_about = None)]
+pub(crate) struct GenerateBuildpackMatrixArgs {
+    #[arg(long)]
+    pub(crate) pretty: bool,
+    #[arg(long)]
+    pub(crate) changed_since: Option<String>,
+    #[arg(long)]
+    pub(crate) publish_plan: Option<PathBuf>,
+    #[command(flatten)]
+    pub(crate) output: LargeOutputArgs,
+    #[command(flatten)]
+    pub(crate) discovery: BuildpackDiscoveryArgs,
+    #[command(flatten)]
+    pub(crate) shared_paths: SharedPathsArgs,
+}
+
+#[derive(Serialize, Debug, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum BuildpackType {
+    Bash,
+    Composite,
+    Libcnb,
+}
+
+// a single project is a repository with one buildpack at its root, anything else is a monorepo
+#[derive(Serialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum ProjectStructure {
+    Monorepo,
+    Single,
+}
+
+// the paths whose changes affect a buildpack, a composite is also affected when one of its
+// dependencies is
+#[derive(Debug)]
+struct ChangeScope {
+    id: BuildpackId,
+    paths: Vec<PathBuf>,
+    dependencies: Vec<BuildpackId>,
+}
+
+// fields are serialized in declaration order so the matrix JSON is stable between runs
+#[derive(Serialize, Debug, Eq, PartialEq)]
+struct BuildpackMatrixEntry {
+    id: String,
+    path: String,
+    version: String,
+    #[serde(rename = "type")]
+    buildpack_type: BuildpackType,
+    structure: ProjectStructure,
+    package_dir: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    package_command: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    docker_repository: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_lifecycle: Option<String>,
+}
 
-pub(crate) fn execute(_: GenerateBuildpackMatrixArgs) -> Result<()> {
+pub(crate) fn execute(args: GenerateBuildpackMatrixArgs) -> Result<()> {
     let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
+    let target_dir = current_dir.join("target");
 
-    let buildpacks = find_buildpack_dirs(&current_dir, &[current_dir.join("target")])
+    let buildpacks = args
+        .discovery
+        .find_buildpack_dirs(&current_dir, std::slice::from_ref(&target_dir))
         .map_err(|e| Error::FindingBuildpacks(current_dir.clone(), e))?
         .into_iter()
         .map(|dir| {
-            read_buildpack_data(&dir)
-                .map_err(Error::ReadingBuildpackData)
-                .map(|data| {
-                    HashMap::from([
-                        ("id", data.buildpack_descriptor.buildpack().id.to_string()),
-                        ("path", dir.to_string_lossy().to_string()),
-                    ])
+            read_buildpack_file(dir.join("buildpack.toml"))
+                .map(|buildpack_file| (dir, buildpack_file))
+                .map_err(Error::BuildpackFile)
+        })
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .filter(|(_, buildpack_file)| {
+            let disabled = is_buildpack_release_disabled(buildpack_file);
+            if disabled {
+                log!(
+                    path = buildpack_file.path;
+                    "⏭️ Skipping disabled buildpack: {}",
+                    buildpack_file.path.display()
+                );
+            }
+            !disabled
+        })
+        .collect::<Vec<_>>();
+
+    let buildpacks = match &args.changed_since {
+        Some(revision) => {
+            let changed_files = get_changed_files(&current_dir, revision)
+                .map_err(Error::Git)?
+                .into_iter()
+                .map(|path| current_dir.join(path))
+                .collect::<Vec<_>>();
+            let shared_paths = args
+                .shared_paths
+                .read(&current_dir)
+                .map_err(Error::SharedPaths)?;
+            let mut scopes = buildpacks
+                .iter()
+                .map(|(dir, buildpack_file)| create_change_scope(dir, buildpack_file))
+                .collect::<Result<Vec<_>>>()?;
+            for scope in &mut scopes {
+                scope.paths.extend(
+                    shared_paths
+                        .paths_for(&scope.id)
+                        .into_iter()
+                        .map(|path| current_dir.join(path)),
+                );
+            }
+            for buildpack_id in shared_paths.buildpack_ids() {
+                if !scopes.iter().any(|scope| &scope.id == buildpack_id) {
+                    log!(
+                        buildpack_id = buildpack_id;
+                        "⚠️ Shared paths reference an unknown buildpack: {buildpack_id}"
+                    );
+                }
+            }
+            let changed_buildpack_ids = find_changed_buildpacks(&scopes, &changed_files);
+            let total = buildpacks.len();
+
+            let buildpacks = buildpacks
+                .into_iter()
+                .zip(scopes)
+                .filter(|(_, scope)| {
+                    let changed = changed_buildpack_ids.contains(&scope.id);
+                    if !changed {
+                        log!("⏭️ Skipping unchanged buildpack: {}", scope.id);
+                    }
+                    changed
                 })
+                .map(|(buildpack, _)| buildpack)
+                .collect::<Vec<_>>();
+            log!(
+                "ℹ️ {} of {total} buildpacks changed since {revision}",
+                buildpacks.len()
+            );
+            buildpacks
+        }
+        None => buildpacks,
+    };
+
+    let buildpacks = match &args.publish_plan {
+        Some(path) => {
+            let contents = std::fs::read_to_string(path)
+                .map_err(|e| Error::ReadingPublishPlan(path.clone(), e))?;
+            let plan = serde_json::from_str::<Vec<PublishPlanEntry>>(&contents)
+                .map_err(|e| Error::ParsingPublishPlan(path.clone(), e))?;
+            let mut unpublished = vec![];
+            for (dir, buildpack_file) in buildpacks {
+                let id = get_buildpack_id(&buildpack_file).map_err(Error::BuildpackFile)?;
+                let version = get_buildpack_version(&buildpack_file)
+                    .map_err(Error::BuildpackFile)?
+                    .to_string();
+                if is_already_published(&plan, id.as_str(), &version) {
+                    log!("⏭️ Skipping already published buildpack: {id} {version}");
+                } else {
+                    unpublished.push((dir, buildpack_file));
+                }
+            }
+            unpublished
+        }
+        None => buildpacks,
+    };
+
+    let structure = match buildpacks.as_slice() {
+        [(dir, _)] if dir == &current_dir => ProjectStructure::Single,
+        _ => ProjectStructure::Monorepo,
+    };
+
+    let mut buildpacks = buildpacks
+        .into_iter()
+        .map(|(dir, buildpack_file)| {
+            create_matrix_entry(
+                &dir,
+                &current_dir,
+                &buildpack_file,
+                dir.join("Cargo.toml").exists(),
+                structure,
+            )
         })
         .collect::<Result<Vec<_>>>()?;
 
-    let json = serde_json::to_string(&buildpacks).map_err(Error::SerializingJson)?;
+    buildpacks.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let json = serialize_matrix(&buildpacks, args.pretty).map_err(Error::SerializingJson)?;
+
+    args.output
+        .set_output("buildpacks", json)
+        .map_err(Error::SetActionOutput)?;
 
-    actions::set_output("buildpacks", json).map_err(Error::SetActionOutput)?;
+    if let Some(min_lifecycle) = aggregate_min_lifecycle(&buildpacks) {
+        log!("📌 Buildpacks require lifecycle {min_lifecycle} or newer");
+        // a single version never needs the large output handling
+        actions::set_output("min_lifecycle", min_lifecycle)
+            .map_err(|e| Error::SetActionOutput(LargeOutputError::SetOutput(e)))?;
+    }
 
     Ok(())
 }
+
+// shared paths are declared relative to the buildpack directory as `metadata.release.affected_by`
+// (e.g.; `["../../common"]`)
+fn create_change_scope(dir: &Path, buildpack_file: &BuildpackFile) -> Result<ChangeScope> {
+    let affected_by = buildpack_file
+        .document
+        .get("metadata")
+        .and_then(Item::as_table_like)
+        .and_then(|metadata| metadata.get("release"))
+        .and_then(Item::as_table_like)
+        .and_then(|release| release.get("affected_by"));
+
+    let mut paths = vec![dir.to_path_buf()];
+    if let Some(affected_by) = affected_by {
+        let shared_paths = affected_by
+            .as_array()
+            .and_then(|values| {
+                values
+                    .iter()
+                    .map(|value| value.as_str())
+                    .collect::<Option<Vec<_>>>()
+            })
+            .ok_or_else(|| Error::InvalidAffectedBy(buildpack_file.path.clone()))?;
+        paths.extend(
+            shared_paths
+                .into_iter()
+                .map(|path| normalize_path(&dir.join(path))),
+        );
+    }
+
+    Ok(ChangeScope {
+        id: get_buildpack_id(buildpack_file).map_err(Error::BuildpackFile)?,
+        paths,
+        dependencies: get_buildpack_dependency_ids(buildpack_file).map_err(Error::BuildpackFile)?,
+    })
+}
+
+// resolves `..` without touching the filesystem since a shared path may have been deleted
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::CurDir => {}
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+fn find_changed_buildpacks(
+    scopes: &[ChangeScope],
+    changed_files: &[PathBuf],
+) -> HashSet<BuildpackId> {
+    let mut changed = scopes
+        .iter()
+        .filter(|scope| {
+            changed_files
+                .iter()
+                .any(|file| scope.paths.iter().any(|path| file.starts_with(path)))
+        })
+        .map(|scope| scope.id.clone())
+        .collect::<HashSet<_>>();
+
+    // repeated until nothing is added so composites of composites are included
+    loop {
+        let affected_composites = scopes
+            .iter()
+            .filter(|scope| !changed.contains(&scope.id))
+            .filter(|scope| scope.dependencies.iter().any(|id| changed.contains(id)))
+            .map(|scope| scope.id.clone())
+            .collect::<Vec<_>>();
+        if affected_composites.is_empty() {
+            return changed;
+        }
+        changed.extend(affected_composites);
+    }
+}
+
+// the lifecycle has to support the newest API declared by any buildpack, buildpacks with an API
+// the lifecycle table doesn't know about yet were already warned about and are left out
+fn aggregate_min_lifecycle(buildpacks: &[BuildpackMatrixEntry]) -> Option<String> {
+    buildpacks
+        .iter()
+        .filter(|buildpack| buildpack.min_lifecycle.is_some())
+        .filter_map(|buildpack| {
+            buildpack
+                .api
+                .as_ref()
+                .and_then(|api| api.parse::<ApiVersion>().ok())
+                .map(|api| (api, buildpack))
+        })
+        .max_by_key(|(api, _)| *api)
+        .and_then(|(_, buildpack)| buildpack.min_lifecycle.clone())
+}
+
+// only buildpacks the plan lists at the same version are skipped, anything missing from the plan or
+// planned for another version (e.g.; a plan from before the release was prepared) is still packaged
+fn is_already_published(plan: &[PublishPlanEntry], id: &str, version: &str) -> bool {
+    plan.iter()
+        .any(|entry| entry.id == id && entry.version == version && !entry.publish)
+}
+
+fn serialize_matrix(
+    buildpacks: &[BuildpackMatrixEntry],
+    pretty: bool,
+) -> serde_json::Result<String> {
+    if pretty {
+        serde_json::to_string_pretty(buildpacks)
+    } else {
+        serde_json::to_string(buildpacks)
+    }
+}
+
+// paths are relative to the repository root so they're the same wherever the repository is checked
+// out and can be used as a `working-directory` as is
+fn create_matrix_entry(
+    dir: &Path,
+    root_dir: &Path,
+    buildpack_file: &BuildpackFile,
+    is_cargo_project: bool,
+    structure: ProjectStructure,
+) -> Result<BuildpackMatrixEntry> {
+    let id = get_buildpack_id(buildpack_file).map_err(Error::BuildpackFile)?;
+    let version = get_buildpack_version(buildpack_file).map_err(Error::BuildpackFile)?;
+    let is_composite = !get_buildpack_dependency_ids(buildpack_file)
+        .map_err(Error::BuildpackFile)?
+        .is_empty();
+
+    let buildpack_type = if is_cargo_project {
+        BuildpackType::Libcnb
+    } else if is_composite {
+        BuildpackType::Composite
+    } else {
+        BuildpackType::Bash
+    };
+
+    let (package_dir, package_command) = match buildpack_type {
+        BuildpackType::Libcnb | BuildpackType::Composite => (
+            root_dir
+                .join("target")
+                .join("buildpack")
+                .join("release")
+                .join(id.replace('/', "_")),
+            Some("cargo libcnb package --release".to_string()),
+        ),
+        BuildpackType::Bash => (dir.to_path_buf(), None),
+    };
+
+    let docker_repository = get_buildpack_docker_repository(buildpack_file);
+    let image_name = docker_repository
+        .as_ref()
+        .map(|repository| format!("{repository}:{version}"));
+
+    let api = buildpack_file
+        .document
+        .get("api")
+        .and_then(Item::as_str)
+        .map(String::from);
+    let min_lifecycle = match &api {
+        Some(api) => {
+            let api_version = api
+                .parse::<ApiVersion>()
+                .map_err(|_| Error::InvalidApi(buildpack_file.path.clone(), api.clone()))?;
+            let min_lifecycle = min_lifecycle_version(api_version);
+            if min_lifecycle.is_none() {
+                log!("⚠️ Unknown lifecycle support for buildpack API {api} used by {id}");
+            }
+            min_lifecycle.map(String::from)
+        }
+        None => None,
+    };
+
+    Ok(BuildpackMatrixEntry {
+        id: id.to_string(),
+        path: relative_path(dir, root_dir),
+        version: version.to_string(),
+        buildpack_type,
+        structure,
+        package_dir: relative_path(&package_dir, root_dir),
+        package_command,
+        docker_repository,
+        image_name,
+        api,
+        min_lifecycle,
+    })
+}
+
+fn relative_path(path: &Path, root_dir: &Path) -> String {
+    match path.strip_prefix(root_dir) {
+        Ok(path) if path.as_os_str().is_empty() => ".".to_string(),
+        Ok(path) => path.to_string_lossy().to_string(),
+        Err(_) => path.to_string_lossy().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::generate_buildpack_matrix::command::{
+        aggregate_min_lifecycle, create_change_scope, create_matrix_entry, find_changed_buildpacks,
+        is_already_published, serialize_matrix, BuildpackMatrixEntry, BuildpackType,
+        ProjectStructure,
+    };
+    use crate::commands::generate_buildpack_matrix::errors::Error;
+    use crate::commands::plan_release::command::PublishPlanEntry;
+    use languages_github_actions::buildpack::BuildpackFile;
+    use libcnb_data::buildpack_id;
+    use std::collections::HashSet;
+    use std::path::{Path, PathBuf};
+    use std::str::FromStr;
+    use toml_edit::Document;
+
+    #[test]
+    fn test_create_matrix_entry_for_libcnb_buildpack() {
+        let buildpack_file = create_buildpack_file(
+            r#"api = "0.9"
+
+[buildpack]
+id = "heroku/nodejs-engine"
+version = "1.2.3"
+
+[metadata.release.docker]
+repository = "docker.io/heroku/buildpack-nodejs-engine"
+"#,
+        );
+        assert_eq!(
+            create_matrix_entry(
+                Path::new("/project/buildpacks/nodejs-engine"),
+                Path::new("/project"),
+                &buildpack_file,
+                true,
+                ProjectStructure::Monorepo
+            )
+            .unwrap(),
+            BuildpackMatrixEntry {
+                id: "heroku/nodejs-engine".to_string(),
+                path: "buildpacks/nodejs-engine".to_string(),
+                version: "1.2.3".to_string(),
+                buildpack_type: BuildpackType::Libcnb,
+                structure: ProjectStructure::Monorepo,
+                package_dir: "target/buildpack/release/heroku_nodejs-engine".to_string(),
+                package_command: Some("cargo libcnb package --release".to_string()),
+                docker_repository: Some("docker.io/heroku/buildpack-nodejs-engine".to_string()),
+                image_name: Some("docker.io/heroku/buildpack-nodejs-engine:1.2.3".to_string()),
+                api: Some("0.9".to_string()),
+                min_lifecycle: Some("0.15.0".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_create_matrix_entry_for_composite_buildpack() {
+        let buildpack_file = create_buildpack_file(
+            r#"[buildpack]
+id = "heroku/nodejs"
+version = "1.2.3"
+
+[[order]]
+[[order.group]]
+id = "heroku/nodejs-engine"
+version = "1.2.3"
+"#,
+        );
+        let entry = create_matrix_entry(
+            Path::new("/project/meta-buildpacks/nodejs"),
+            Path::new("/project"),
+            &buildpack_file,
+            false,
+            ProjectStructure::Monorepo,
+        )
+        .unwrap();
+        assert_eq!(entry.buildpack_type, BuildpackType::Composite);
+        assert_eq!(entry.path, "meta-buildpacks/nodejs");
+        assert_eq!(entry.package_dir, "target/buildpack/release/heroku_nodejs");
+        assert_eq!(entry.docker_repository, None);
+        assert_eq!(entry.image_name, None);
+    }
+
+    #[test]
+    fn test_create_matrix_entry_for_bash_buildpack() {
+        let buildpack_file = create_buildpack_file(
+            r#"[buildpack]
+id = "heroku/procfile"
+version = "2.0.0"
+"#,
+        );
+        let entry = create_matrix_entry(
+            Path::new("/project"),
+            Path::new("/project"),
+            &buildpack_file,
+            false,
+            ProjectStructure::Single,
+        )
+        .unwrap();
+        assert_eq!(entry.buildpack_type, BuildpackType::Bash);
+        assert_eq!(entry.package_dir, ".");
+        assert_eq!(entry.package_command, None);
+        assert_eq!(
+            serde_json::to_string(&entry).unwrap(),
+            r#"{"id":"heroku/procfile","path":".","version":"2.0.0","type":"bash","structure":"single","package_dir":"."}"#
+        );
+    }
+
+    #[test]
+    fn test_aggregate_min_lifecycle() {
+        let entries = [
+            ("heroku/nodejs-engine", "api = \"0.9\""),
+            ("heroku/nodejs-npm", "api = \"0.10\""),
+            ("heroku/nodejs-yarn", "api = \"0.99\""),
+            ("heroku/procfile", ""),
+        ]
+        .into_iter()
+        .map(|(id, api)| {
+            create_matrix_entry(
+                Path::new("/project"),
+                Path::new("/project"),
+                &create_buildpack_file(&format!(
+                    "{api}\n[buildpack]\nid = \"{id}\"\nversion = \"1.0.0\"\n"
+                )),
+                false,
+                ProjectStructure::Monorepo,
+            )
+            .unwrap()
+        })
+        .collect::<Vec<_>>();
+        assert_eq!(entries[1].min_lifecycle, Some("0.17.0".to_string()));
+        assert_eq!(entries[2].api, Some("0.99".to_string()));
+        assert_eq!(entries[2].min_lifecycle, None);
+        assert_eq!(
+            aggregate_min_lifecycle(&entries),
+            Some("0.17.0".to_string())
+        );
+        assert_eq!(aggregate_min_lifecycle(&entries[3..]), None);
+
+        assert!(matches!(
+            create_matrix_entry(
+                Path::new("/project"),
+                Path::new("/project"),
+                &create_buildpack_file(
+                    "api = \"latest\"\n[buildpack]\nid = \"heroku/procfile\"\nversion = \"1.0.0\"\n"
+                ),
+                false,
+                ProjectStructure::Single,
+            ),
+            Err(Error::InvalidApi(_, api)) if api == "latest"
+        ));
+    }
+
+    #[test]
+    fn test_serialize_matrix() {
+        let entries = vec![BuildpackMatrixEntry {
+            id: "heroku/procfile".to_string(),
+            path: "/project".to_string(),
+            version: "2.0.0".to_string(),
+            buildpack_type: BuildpackType::Bash,
+            structure: ProjectStructure::Single,
+            package_dir: "/project".to_string(),
+            package_command: None,
+            docker_repository: None,
+            image_name: None,
+            api: None,
+            min_lifecycle: None,
+        }];
+        assert_eq!(
+            serialize_matrix(&entries, false).unwrap(),
+            r#"[{"id":"heroku/procfile","path":"/project","version":"2.0.0","type":"bash","structure":"single","package_dir":"/project"}]"#
+        );
+        assert_eq!(
+            serialize_matrix(&entries, true).unwrap(),
+            r#"[
+  {
+    "id": "heroku/procfile",
+    "path": "/project",
+    "version": "2.0.0",
+    "type": "bash",
+    "structure": "single",
+    "package_dir": "/project"
+  }
+]"#
+        );
+    }
+
+    #[test]
+    fn test_is_already_published() {
+        let plan = serde_json::from_str::<Vec<PublishPlanEntry>>(
+            r#"[
+  {"id":"heroku/nodejs","version":"1.0.1","docker_repository":"docker.io/heroku/buildpack-nodejs","image_published":true,"registry_published":true,"publish":false},
+  {"id":"heroku/procfile","version":"2.0.0","registry_published":false,"publish":true}
+]"#,
+        )
+        .unwrap();
+
+        assert!(is_already_published(&plan, "heroku/nodejs", "1.0.1"));
+        assert!(!is_already_published(&plan, "heroku/nodejs", "1.1.0"));
+        assert!(!is_already_published(&plan, "heroku/procfile", "2.0.0"));
+        assert!(!is_already_published(&plan, "heroku/python", "1.0.0"));
+    }
+
+    #[test]
+    fn test_find_changed_buildpacks() {
+        let scopes = [
+            (
+                "/project/buildpacks/nodejs-engine",
+                "[buildpack]\nid = \"heroku/nodejs-engine\"\nversion = \"1.0.0\"\n\n[metadata.release]\naffected_by = [\"../../common\"]\n",
+            ),
+            (
+                "/project/buildpacks/nodejs-npm",
+                "[buildpack]\nid = \"heroku/nodejs-npm\"\nversion = \"1.0.0\"\n",
+            ),
+            (
+                "/project/meta-buildpacks/nodejs",
+                "[buildpack]\nid = \"heroku/nodejs\"\nversion = \"1.0.0\"\n\n[[order]]\n[[order.group]]\nid = \"heroku/nodejs-engine\"\nversion = \"1.0.0\"\n",
+            ),
+            (
+                "/project/meta-buildpacks/nodejs-function",
+                "[buildpack]\nid = \"heroku/nodejs-function\"\nversion = \"1.0.0\"\n\n[[order]]\n[[order.group]]\nid = \"heroku/nodejs\"\nversion = \"1.0.0\"\n",
+            ),
+        ]
+        .into_iter()
+        .map(|(dir, contents)| {
+            create_change_scope(Path::new(dir), &create_buildpack_file(contents)).unwrap()
+        })
+        .collect::<Vec<_>>();
+        assert_eq!(
+            scopes[0].paths,
+            vec![
+                PathBuf::from("/project/buildpacks/nodejs-engine"),
+                PathBuf::from("/project/common")
+            ]
+        );
+
+        assert_eq!(
+            find_changed_buildpacks(
+                &scopes,
+                &[PathBuf::from("/project/buildpacks/nodejs-npm/src/main.rs")]
+            ),
+            HashSet::from([buildpack_id!("heroku/nodejs-npm")])
+        );
+        assert_eq!(
+            find_changed_buildpacks(&scopes, &[PathBuf::from("/project/common/lib.sh")]),
+            HashSet::from([
+                buildpack_id!("heroku/nodejs-engine"),
+                buildpack_id!("heroku/nodejs"),
+                buildpack_id!("heroku/nodejs-function")
+            ])
+        );
+        assert_eq!(
+            find_changed_buildpacks(
+                &scopes,
+                &[
+                    PathBuf::from("/project/README.md"),
+                    PathBuf::from("/project/buildpacks/nodejs-npm-extra/buildpack.toml")
+                ]
+            ),
+            HashSet::new()
+        );
+
+        assert!(matches!(
+            create_change_scope(
+                Path::new("/project"),
+                &create_buildpack_file(
+                    "[buildpack]\nid = \"test\"\nversion = \"1.0.0\"\n\n[metadata.release]\naffected_by = \"common\"\n"
+                )
+            ),
+            Err(Error::InvalidAffectedBy(_))
+        ));
+    }
+
+    fn create_buildpack_file(contents: &str) -> BuildpackFile {
+        BuildpackFile {
+            path: PathBuf::from("/path/to/test/buildpack.toml"),
+            document: Document::from_str(contents).unwrap(),
+        }
+    }
+}
@@ -1,15 +1,24 @@
-use crate::github::actions::SetOutputError;
-use libcnb_package::ReadBuildpackDataError;
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::buildpack::{BuildpackDiscoveryError, BuildpackFileError};
+use languages_github_actions::git::GitError;
+use languages_github_actions::github::actions::LargeOutputError;
+use languages_github_actions::shared_paths::SharedPathsError;
 use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
 
 #[derive(Debug)]
 pub(crate) enum Error {
     GetCurrentDir(std::io::Error),
-    FindingBuildpacks(PathBuf, std::io::Error),
-    ReadingBuildpackData(ReadBuildpackDataError),
+    FindingBuildpacks(PathBuf, BuildpackDiscoveryError),
+    BuildpackFile(BuildpackFileError),
+    InvalidApi(PathBuf, String),
+    Git(GitError),
+    InvalidAffectedBy(PathBuf),
+    SharedPaths(SharedPathsError),
+    ReadingPublishPlan(PathBuf, std::io::Error),
+    ParsingPublishPlan(PathBuf, serde_json::Error),
     SerializingJson(serde_json::Error),
-    SetActionOutput(SetOutputError),
+    SetActionOutput(LargeOutputError),
 }
 
 impl Display for Error {
@@ -22,16 +31,14 @@ impl Display for Error {
             Error::FindingBuildpacks(path, error) => {
                 write!(
                     f,
-                    "I/O error while finding buildpacks\nPath: {}\nError: {error}",
+                    "Could not find buildpacks\nPath: {}\nError: {error}",
                     path.display()
                 )
             }
 
-            Error::SetActionOutput(set_output_error) => match set_output_error {
-                SetOutputError::Opening(error) | SetOutputError::Writing(error) => {
-                    write!(f, "Could not write action output\nError: {error}")
-                }
-            },
+            Error::SetActionOutput(error) => {
+                write!(f, "{error}")
+            }
 
             Error::SerializingJson(error) => {
                 write!(
@@ -40,22 +47,65 @@ impl Display for Error {
                 )
             }
 
-            Error::ReadingBuildpackData(error) => match error {
-                ReadBuildpackDataError::ReadingBuildpack { path, source } => {
-                    write!(
-                        f,
-                        "Failed to read buildpack\nPath: {}\nError: {source}",
-                        path.display()
-                    )
-                }
-                ReadBuildpackDataError::ParsingBuildpack { path, source } => {
-                    write!(
-                        f,
-                        "Failed to parse buildpack\nPath: {}\nError: {source}",
-                        path.display()
-                    )
-                }
-            },
+            Error::BuildpackFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::InvalidApi(path, api) => {
+                write!(
+                    f,
+                    "Invalid buildpack API `{api}`, expected the format `<major>.<minor>`\nPath: {}",
+                    path.display()
+                )
+            }
+
+            Error::Git(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::SharedPaths(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::ReadingPublishPlan(path, error) => {
+                write!(
+                    f,
+                    "Could not read publish plan\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::ParsingPublishPlan(path, error) => {
+                write!(
+                    f,
+                    "Could not parse publish plan\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::InvalidAffectedBy(path) => {
+                write!(
+                    f,
+                    "Field `metadata.release.affected_by` in buildpack.toml must be a list of paths\nPath: {}",
+                    path.display()
+                )
+            }
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::GetCurrentDir(_) | Error::ReadingPublishPlan(_, _) => ErrorCategory::Io,
+            Error::FindingBuildpacks(_, error) => error.category(),
+            Error::BuildpackFile(error) => error.category(),
+            Error::InvalidApi(_, _) => ErrorCategory::Validation,
+            Error::InvalidAffectedBy(_) => ErrorCategory::Config,
+            Error::Git(error) => error.category(),
+            Error::SharedPaths(error) => error.category(),
+            Error::SerializingJson(_) | Error::ParsingPublishPlan(_, _) => ErrorCategory::Parse,
+            Error::SetActionOutput(error) => error.category(),
         }
     }
 }
@@ -1,11 +1,26 @@
-use crate::changelog::Changelog;
 use crate::commands::generate_changelog::errors::Error;
-use crate::github::actions;
+use crate::commands::generate_changelog::locale::{find_locales, RenderLocale};
+use crate::commands::generate_changelog::render::{
+    render_changelog, ChangelogFormat, ChangelogSection,
+};
 use clap::Parser;
+use languages_github_actions::buildpack::{
+    get_buildpack_id, is_buildpack_release_disabled, read_buildpack_file, BuildpackDiscoveryArgs,
+};
+use languages_github_actions::changelog::{
+    link_pull_request_references, Changelog, ChangelogLocationArgs, ReleaseEntry,
+};
+use languages_github_actions::debug_artifacts::capture_markdown_failure;
+use languages_github_actions::github::actions::LargeOutputArgs;
+use languages_github_actions::github::context::GitHubContext;
+use languages_github_actions::log;
+use languages_github_actions::sbom::{
+    diff_sboms, find_buildpack_sbom, format_dependencies_section, read_sbom, Sbom,
+};
 use libcnb_data::buildpack::BuildpackId;
-use libcnb_package::{find_buildpack_dirs, read_buildpack_data};
+use serde_json::json;
 use std::collections::{BTreeMap, HashMap};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 type Result<T> = std::result::Result<T, Error>;
 
@@ -16,114 +31,332 @@ pub(crate) struct GenerateChangelogArgs {
     unreleased: bool,
     #[arg(long, group = "section")]
     version: Option<String>,
+    #[arg(long, group = "section")]
+    list_versions: bool,
+    #[arg(long)]
+    lenient: bool,
+    #[arg(long, value_enum, default_value_t, conflicts_with = "list_versions")]
+    format: ChangelogFormat,
+    #[arg(long, requires = "current_sbom_dir", conflicts_with = "list_versions")]
+    previous_sbom_dir: Option<PathBuf>,
+    #[arg(long, requires = "previous_sbom_dir", conflicts_with = "list_versions")]
+    current_sbom_dir: Option<PathBuf>,
+    #[arg(long, conflicts_with = "list_versions")]
+    locales_dir: Option<PathBuf>,
+    #[command(flatten)]
+    output: LargeOutputArgs,
+    #[command(flatten)]
+    discovery: BuildpackDiscoveryArgs,
+    #[command(flatten)]
+    changelog: ChangelogLocationArgs,
 }
 
 enum ChangelogEntryType {
     Unreleased,
+    Latest,
     Version(String),
 }
 
 pub(crate) fn execute(args: GenerateChangelogArgs) -> Result<()> {
     let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
 
-    let buildpack_dirs = find_buildpack_dirs(&current_dir, &[current_dir.join("target")])
+    // locales are read up front so a broken template fails before any output is written
+    let locales = match &args.locales_dir {
+        Some(_) if matches!(args.format, ChangelogFormat::Json) => Err(Error::LocalizedJson)?,
+        Some(locales_dir) => find_locales(locales_dir)?
+            .into_iter()
+            .map(|(name, path)| RenderLocale::read(&path).map(|locale| (name, locale)))
+            .collect::<Result<Vec<_>>>()?,
+        None => vec![],
+    };
+
+    let buildpack_dirs = args
+        .discovery
+        .find_buildpack_dirs(&current_dir, &[current_dir.join("target")])
         .map_err(|e| Error::FindingBuildpacks(current_dir.clone(), e))?;
 
     let changelog_entry_type = match args.version {
+        Some(version) if version == "latest" => ChangelogEntryType::Latest,
         Some(version) => ChangelogEntryType::Version(version),
         None => ChangelogEntryType::Unreleased,
     };
 
-    let changes_by_buildpack = buildpack_dirs
+    let buildpack_files = buildpack_dirs
+        .iter()
+        .map(|dir| read_buildpack_file(dir.join("buildpack.toml")).map_err(Error::BuildpackFile))
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .filter(|buildpack_file| {
+            let disabled = is_buildpack_release_disabled(buildpack_file);
+            if disabled {
+                log!(
+                    path = buildpack_file.path;
+                    "⏭️ Skipping disabled buildpack: {}",
+                    buildpack_file.path.display()
+                );
+            }
+            !disabled
+        })
+        .collect::<Vec<_>>();
+
+    let changelogs_by_buildpack = buildpack_files
         .iter()
-        .map(|dir| {
-            read_buildpack_data(dir)
-                .map_err(Error::GetBuildpackId)
-                .map(|data| data.buildpack_descriptor.buildpack().id.clone())
+        .map(|buildpack_file| {
+            get_buildpack_id(buildpack_file)
+                .map_err(Error::BuildpackFile)
                 .and_then(|buildpack_id| {
-                    read_changelog_entry(dir.join("CHANGELOG.md"), &changelog_entry_type)
-                        .map(|contents| (buildpack_id, contents))
+                    args.changelog
+                        .find_changelog(
+                            buildpack_file.path.parent().unwrap_or(&current_dir),
+                            &current_dir,
+                        )
+                        .map_err(Error::ChangelogFile)
+                        .and_then(|path| read_changelog(path, args.lenient))
+                        .map(|changelog| (buildpack_id, changelog))
                 })
         })
         .collect::<Result<HashMap<_, _>>>()?;
 
-    let changelog = generate_changelog(&changes_by_buildpack);
+    if args.list_versions {
+        let versions = list_versions(&changelogs_by_buildpack);
+        args.output
+            .set_output("versions", versions)
+            .map_err(Error::SetActionOutput)?;
+        return Ok(());
+    }
+
+    let mut sections_by_buildpack = changelogs_by_buildpack
+        .into_iter()
+        .map(|(buildpack_id, changelog)| {
+            let section = get_changelog_entry(&changelog, &changelog_entry_type);
+            (buildpack_id, section)
+        })
+        .collect::<HashMap<_, _>>();
+
+    // bare references only resolve on GitHub pages of the origin repository
+    if let Some(repository_url) = GitHubContext::from_env()
+        .map_err(Error::GitHubContext)?
+        .repository_url()
+    {
+        for section in sections_by_buildpack.values_mut().flatten() {
+            section.body = section
+                .body
+                .as_deref()
+                .map(|body| link_pull_request_references(body, &repository_url));
+        }
+    }
+
+    if let (Some(previous_sbom_dir), Some(current_sbom_dir)) =
+        (&args.previous_sbom_dir, &args.current_sbom_dir)
+    {
+        for (buildpack_id, section) in &mut sections_by_buildpack {
+            if let Some(dependencies) =
+                read_dependency_changes(buildpack_id, previous_sbom_dir, current_sbom_dir)?
+            {
+                *section = append_dependency_changes(section.take(), &dependencies);
+            }
+        }
+    }
 
-    actions::set_output("changelog", changelog).map_err(Error::SetActionOutput)?;
+    let changelog = render_changelog(
+        &sections_by_buildpack,
+        &args.format,
+        &RenderLocale::default(),
+    );
+
+    args.output
+        .set_output("changelog", changelog)
+        .map_err(Error::SetActionOutput)?;
+
+    for (name, locale) in &locales {
+        let changelog = render_changelog(&sections_by_buildpack, &args.format, locale);
+        localized_output(&args.output, name)
+            .set_output(format!("changelog_{}", name.replace('-', "_")), changelog)
+            .map_err(Error::SetActionOutput)?;
+        log!("✅️ Rendered changelog for locale {name}");
+    }
 
     Ok(())
 }
 
-fn read_changelog_entry(
-    path: PathBuf,
-    changelog_entry_type: &ChangelogEntryType,
-) -> Result<Option<Option<String>>> {
+// each locale is written next to the `output_file` with the locale before the extension (e.g.;
+// `changelog.ja.md`) so the outputs don't overwrite each other
+fn localized_output(output: &LargeOutputArgs, locale: &str) -> LargeOutputArgs {
+    LargeOutputArgs {
+        output_file: output.output_file.as_ref().map(|output_file| {
+            let stem = output_file
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let file_name = match output_file.extension() {
+                Some(extension) => format!("{stem}.{locale}.{}", extension.to_string_lossy()),
+                None => format!("{stem}.{locale}"),
+            };
+            output_file.with_file_name(file_name)
+        }),
+        output_encoding: output.output_encoding.clone(),
+    }
+}
+
+fn read_changelog(path: PathBuf, lenient: bool) -> Result<Changelog> {
     let contents =
         std::fs::read_to_string(&path).map_err(|e| Error::ReadingChangelog(path.clone(), e))?;
-    let changelog = Changelog::try_from(contents.as_str())
-        .map_err(|e| Error::ParsingChangelog(path.clone(), e))?;
-    Ok(match changelog_entry_type {
-        ChangelogEntryType::Unreleased => Some(changelog.unreleased),
-        ChangelogEntryType::Version(version) => changelog
-            .releases
-            .get(version)
-            .map(|entry| Some(entry.body.clone())),
+    if lenient {
+        Changelog::parse_lenient(contents.as_str())
+    } else {
+        Changelog::try_from(contents.as_str())
+    }
+    .map_err(|e| {
+        capture_markdown_failure(&path, &contents, &e);
+        Error::ParsingChangelog(path, e)
     })
 }
 
-fn generate_changelog(
-    changes_by_buildpack: &HashMap<BuildpackId, Option<Option<String>>>,
-) -> String {
-    let changelog = changes_by_buildpack
+fn get_changelog_entry(
+    changelog: &Changelog,
+    changelog_entry_type: &ChangelogEntryType,
+) -> Option<ChangelogSection> {
+    let release_section = |entry: &ReleaseEntry| ChangelogSection {
+        version: Some(entry.version.clone()),
+        date: Some(entry.date),
+        body: Some(entry.body.clone()),
+    };
+    match changelog_entry_type {
+        ChangelogEntryType::Unreleased => Some(ChangelogSection {
+            version: None,
+            date: None,
+            body: changelog.unreleased.clone(),
+        }),
+        ChangelogEntryType::Latest => changelog.latest_release().map(release_section),
+        ChangelogEntryType::Version(version) => {
+            changelog.releases.get(version).map(release_section)
+        }
+    }
+}
+
+fn read_dependency_changes(
+    buildpack_id: &BuildpackId,
+    previous_sbom_dir: &Path,
+    current_sbom_dir: &Path,
+) -> Result<Option<String>> {
+    let current = match find_buildpack_sbom(current_sbom_dir, buildpack_id) {
+        Some(current_path) => read_sbom(&current_path).map_err(Error::Sbom)?,
+        None => {
+            log!(
+                buildpack_id = buildpack_id, path = current_sbom_dir;
+                "⚠️ No SBOM found for {buildpack_id}: {}",
+                current_sbom_dir.display()
+            );
+            return Ok(None);
+        }
+    };
+
+    let previous = match find_buildpack_sbom(previous_sbom_dir, buildpack_id) {
+        Some(previous_path) => read_sbom(&previous_path).map_err(Error::Sbom)?,
+        None => Sbom::default(),
+    };
+
+    let diff = diff_sboms(&previous, &current);
+    Ok((!diff.is_empty()).then(|| format_dependencies_section(&diff)))
+}
+
+fn append_dependency_changes(
+    section: Option<ChangelogSection>,
+    dependencies: &str,
+) -> Option<ChangelogSection> {
+    section.map(|section| ChangelogSection {
+        body: Some(match section.body {
+            Some(value) => format!("{}\n\n{dependencies}", value.trim_end()),
+            None => dependencies.to_string(),
+        }),
+        ..section
+    })
+}
+
+fn list_versions(changelogs_by_buildpack: &HashMap<BuildpackId, Changelog>) -> String {
+    let versions = changelogs_by_buildpack
         .iter()
-        .map(|(buildpack_id, changes)| (buildpack_id.to_string(), changes))
-        .collect::<BTreeMap<_, _>>()
-        .into_iter()
-        .filter_map(|(buildpack_id, changes)| {
-            changes.as_ref().map(|contents| match contents {
-                Some(value) => format!("# {buildpack_id}\n\n{value}"),
-                None => format!("# {buildpack_id}\n\n- No changes"),
-            })
+        .map(|(buildpack_id, changelog)| {
+            let versions = changelog
+                .releases
+                .values()
+                .map(|entry| {
+                    json!({
+                        "version": entry.version,
+                        "date": entry.date.format("%Y-%m-%d").to_string(),
+                    })
+                })
+                .collect::<Vec<_>>();
+            (buildpack_id.to_string(), versions)
         })
-        .collect::<Vec<_>>()
-        .join("\n\n");
-    format!("{}\n\n", changelog.trim())
+        .collect::<BTreeMap<_, _>>();
+    json!(versions).to_string()
 }
 
 #[cfg(test)]
 mod test {
-    use crate::commands::generate_changelog::command::generate_changelog;
+    use crate::commands::generate_changelog::command::{append_dependency_changes, list_versions};
+    use crate::commands::generate_changelog::render::ChangelogSection;
+    use languages_github_actions::changelog::Changelog;
     use libcnb_data::buildpack_id;
     use std::collections::HashMap;
 
     #[test]
-    fn test_generating_changelog() {
+    fn test_append_dependency_changes() {
+        let dependencies = "### Dependencies\n\n- Updated `node` from 18.16.0 to 18.17.0";
+        assert_eq!(
+            append_dependency_changes(
+                Some(ChangelogSection {
+                    version: Some("1.0.0".to_string()),
+                    date: None,
+                    body: Some("- change a.1\n".to_string())
+                }),
+                dependencies
+            ),
+            Some(ChangelogSection {
+                version: Some("1.0.0".to_string()),
+                date: None,
+                body: Some(format!("- change a.1\n\n{dependencies}"))
+            })
+        );
+        assert_eq!(
+            append_dependency_changes(
+                Some(ChangelogSection {
+                    version: None,
+                    date: None,
+                    body: None
+                }),
+                dependencies
+            ),
+            Some(ChangelogSection {
+                version: None,
+                date: None,
+                body: Some(dependencies.to_string())
+            })
+        );
+        assert_eq!(append_dependency_changes(None, dependencies), None);
+    }
+
+    #[test]
+    fn test_list_versions() {
         let values = HashMap::from([
-            (buildpack_id!("c"), Some(Some("- change c.1".to_string()))),
+            (
+                buildpack_id!("b"),
+                Changelog::try_from("## [Unreleased]\n\n## [1.0.0] 2023/04/01\n- Initial")
+                    .unwrap(),
+            ),
             (
                 buildpack_id!("a"),
-                Some(Some("- change a.1\n- change a.2".to_string())),
+                Changelog::try_from(
+                    "## [Unreleased]\n\n## [0.2.0] 2023/05/10\n- Fix\n\n## [0.1.0] 2023/04/01\n- Initial",
+                )
+                .unwrap(),
             ),
-            (buildpack_id!("b"), None),
-            (buildpack_id!("d"), Some(None)),
         ]);
 
         assert_eq!(
-            generate_changelog(&values),
-            r#"# a
-
-- change a.1
-- change a.2
-
-# c
-
-- change c.1
-
-# d
-
-- No changes
-
-"#
-        )
+            list_versions(&values),
+            r#"{"a":[{"date":"2023-05-10","version":"0.2.0"},{"date":"2023-04-01","version":"0.1.0"}],"b":[{"date":"2023-04-01","version":"1.0.0"}]}"#
+        );
     }
 }
@@ -1,17 +1,26 @@
-use crate::changelog::ChangelogError;
-use crate::github::actions::SetOutputError;
-use libcnb_package::ReadBuildpackDataError;
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::buildpack::{BuildpackDiscoveryError, BuildpackFileError};
+use languages_github_actions::changelog::{ChangelogError, ChangelogFileError};
+use languages_github_actions::github::actions::LargeOutputError;
+use languages_github_actions::github::context::GitHubContextError;
+use languages_github_actions::sbom::SbomError;
 use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
 
 #[derive(Debug)]
 pub(crate) enum Error {
     GetCurrentDir(std::io::Error),
-    FindingBuildpacks(PathBuf, std::io::Error),
-    GetBuildpackId(ReadBuildpackDataError),
+    FindingBuildpacks(PathBuf, BuildpackDiscoveryError),
+    BuildpackFile(BuildpackFileError),
+    ChangelogFile(ChangelogFileError),
     ReadingChangelog(PathBuf, std::io::Error),
     ParsingChangelog(PathBuf, ChangelogError),
-    SetActionOutput(SetOutputError),
+    Sbom(SbomError),
+    ReadingLocale(PathBuf, std::io::Error),
+    InvalidLocale(PathBuf, String),
+    LocalizedJson,
+    GitHubContext(GitHubContextError),
+    SetActionOutput(LargeOutputError),
 }
 
 impl Display for Error {
@@ -24,34 +33,22 @@ impl Display for Error {
             Error::FindingBuildpacks(path, error) => {
                 write!(
                     f,
-                    "I/O error while finding buildpacks\nPath: {}\nError: {error}",
+                    "Could not find buildpacks\nPath: {}\nError: {error}",
                     path.display()
                 )
             }
 
-            Error::GetBuildpackId(read_buildpack_data_error) => match read_buildpack_data_error {
-                ReadBuildpackDataError::ReadingBuildpack { path, source } => {
-                    write!(
-                        f,
-                        "Error reading buildpack\nPath: {}\nError: {source}",
-                        path.display()
-                    )
-                }
+            Error::BuildpackFile(error) => {
+                write!(f, "{error}")
+            }
 
-                ReadBuildpackDataError::ParsingBuildpack { path, source } => {
-                    write!(
-                        f,
-                        "Error parsing buildpack\nPath: {}\nError: {source}",
-                        path.display()
-                    )
-                }
-            },
+            Error::SetActionOutput(error) => {
+                write!(f, "{error}")
+            }
 
-            Error::SetActionOutput(set_output_error) => match set_output_error {
-                SetOutputError::Opening(error) | SetOutputError::Writing(error) => {
-                    write!(f, "Could not write action output\nError: {error}")
-                }
-            },
+            Error::ChangelogFile(error) => {
+                write!(f, "{error}")
+            }
 
             Error::ReadingChangelog(path, error) => {
                 write!(
@@ -61,6 +58,37 @@ impl Display for Error {
                 )
             }
 
+            Error::Sbom(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::ReadingLocale(path, error) => {
+                write!(
+                    f,
+                    "Could not read changelog locale\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::InvalidLocale(path, message) => {
+                write!(
+                    f,
+                    "Invalid changelog locale\nPath: {}\nError: {message}",
+                    path.display()
+                )
+            }
+
+            Error::LocalizedJson => {
+                write!(
+                    f,
+                    "Localized changelogs can only be rendered in the markdown, plain, or html format"
+                )
+            }
+
+            Error::GitHubContext(error) => {
+                write!(f, "{error}")
+            }
+
             Error::ParsingChangelog(path, error) => {
                 write!(
                     f,
@@ -71,3 +99,21 @@ impl Display for Error {
         }
     }
 }
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::ChangelogFile(error) => error.category(),
+            Error::GetCurrentDir(_)
+            | Error::ReadingChangelog(_, _)
+            | Error::ReadingLocale(_, _) => ErrorCategory::Io,
+            Error::FindingBuildpacks(_, error) => error.category(),
+            Error::InvalidLocale(_, _) | Error::LocalizedJson => ErrorCategory::Config,
+            Error::BuildpackFile(error) => error.category(),
+            Error::ParsingChangelog(_, error) => error.category(),
+            Error::Sbom(error) => error.category(),
+            Error::GitHubContext(error) => error.category(),
+            Error::SetActionOutput(error) => error.category(),
+        }
+    }
+}
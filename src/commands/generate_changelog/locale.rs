@@ -0,0 +1,259 @@
+use crate::commands::generate_changelog::errors::Error;
+use chrono::format::{Item as FormatItem, StrftimeItems};
+use chrono::{DateTime, Datelike, Utc};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use toml_edit::{Document, Item};
+
+const HEADING_PLACEHOLDERS: [&str; 3] = ["{buildpack_id}", "{version}", "{date}"];
+
+// the text the markdown and plain renderers write around the changelog entries, the default is the
+// English text the renderers have always used so existing outputs are unchanged
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct RenderLocale {
+    pub(crate) markdown_heading: String,
+    pub(crate) plain_heading: String,
+    pub(crate) security_heading: String,
+    pub(crate) no_changes: String,
+    pub(crate) unreleased: String,
+    pub(crate) date_format: DateFormat,
+}
+
+impl Default for RenderLocale {
+    fn default() -> Self {
+        RenderLocale {
+            markdown_heading: "{buildpack_id}".to_string(),
+            plain_heading: "{buildpack_id} ({version})".to_string(),
+            security_heading: "Security".to_string(),
+            no_changes: "No changes".to_string(),
+            unreleased: "Unreleased".to_string(),
+            date_format: DateFormat::default(),
+        }
+    }
+}
+
+impl RenderLocale {
+    pub(crate) fn read(path: &Path) -> Result<RenderLocale, Error> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| Error::ReadingLocale(path.to_path_buf(), e))?;
+        RenderLocale::parse(&contents)
+            .map_err(|message| Error::InvalidLocale(path.to_path_buf(), message))
+    }
+
+    // keys that aren't set keep their English default
+    fn parse(contents: &str) -> Result<RenderLocale, String> {
+        let document = Document::from_str(contents).map_err(|e| e.to_string())?;
+        let mut locale = RenderLocale::default();
+
+        for (key, value) in document.iter() {
+            let field = match key {
+                "markdown_heading" => &mut locale.markdown_heading,
+                "plain_heading" => &mut locale.plain_heading,
+                "security_heading" => &mut locale.security_heading,
+                "no_changes" => &mut locale.no_changes,
+                "unreleased" => &mut locale.unreleased,
+                "date" => {
+                    locale.date_format = DateFormat::parse(value)?;
+                    continue;
+                }
+                _ => Err(format!("Unknown key `{key}`"))?,
+            };
+            *field = value
+                .as_str()
+                .ok_or_else(|| format!("Key `{key}` must be a string"))?
+                .to_string();
+        }
+
+        for heading in [&locale.markdown_heading, &locale.plain_heading] {
+            lazy_static! {
+                static ref PLACEHOLDER: Regex =
+                    Regex::new(r"\{[^}]*}").expect("Should be a valid regex");
+            }
+            if let Some(placeholder) = PLACEHOLDER
+                .find_iter(heading)
+                .find(|placeholder| !HEADING_PLACEHOLDERS.contains(&placeholder.as_str()))
+            {
+                Err(format!(
+                    "Unknown heading placeholder `{}`, expected one of {}",
+                    placeholder.as_str(),
+                    HEADING_PLACEHOLDERS.join(", ")
+                ))?;
+            }
+        }
+
+        Ok(locale)
+    }
+
+    // `{version}` falls back to the unreleased text and `{date}` is empty for unreleased changes
+    pub(crate) fn render_heading(
+        &self,
+        template: &str,
+        buildpack_id: &str,
+        version: Option<&str>,
+        date: Option<&DateTime<Utc>>,
+    ) -> String {
+        template
+            .replace("{buildpack_id}", buildpack_id)
+            .replace("{version}", version.unwrap_or(&self.unreleased))
+            .replace(
+                "{date}",
+                &date
+                    .map(|date| self.date_format.format(date))
+                    .unwrap_or_default(),
+            )
+    }
+}
+
+// a strftime pattern, chrono only knows English month names so `%B` is replaced with the locale's
+// month names when they are given
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct DateFormat {
+    pattern: String,
+    month_names: Option<Vec<String>>,
+}
+
+impl Default for DateFormat {
+    fn default() -> Self {
+        DateFormat {
+            pattern: "%Y-%m-%d".to_string(),
+            month_names: None,
+        }
+    }
+}
+
+impl DateFormat {
+    fn parse(item: &Item) -> Result<DateFormat, String> {
+        let table = item
+            .as_table_like()
+            .ok_or_else(|| "Key `date` must be a table".to_string())?;
+        let mut date_format = DateFormat::default();
+
+        for (key, value) in table.iter() {
+            match key {
+                "format" => {
+                    date_format.pattern = value
+                        .as_str()
+                        .ok_or_else(|| "Key `date.format` must be a string".to_string())?
+                        .to_string();
+                }
+                "months" => {
+                    let month_names = value
+                        .as_array()
+                        .map(|months| {
+                            months
+                                .iter()
+                                .filter_map(|month| month.as_str().map(String::from))
+                                .collect::<Vec<_>>()
+                        })
+                        .filter(|months| months.len() == 12)
+                        .ok_or_else(|| {
+                            "Key `date.months` must be a list of 12 month names".to_string()
+                        })?;
+                    date_format.month_names = Some(month_names);
+                }
+                _ => Err(format!("Unknown key `date.{key}`"))?,
+            }
+        }
+
+        // chrono panics when formatting with an invalid pattern so it is checked up front
+        if StrftimeItems::new(&date_format.pattern).any(|item| item == FormatItem::Error) {
+            Err(format!("Invalid date format `{}`", date_format.pattern))?;
+        }
+
+        Ok(date_format)
+    }
+
+    pub(crate) fn format(&self, date: &DateTime<Utc>) -> String {
+        let pattern = match &self.month_names {
+            Some(month_names) => self.pattern.replace(
+                "%B",
+                &month_names[date.month0() as usize].replace('%', "%%"),
+            ),
+            None => self.pattern.clone(),
+        };
+        date.format(&pattern).to_string()
+    }
+}
+
+// each `<locale>.toml` file in the directory is a locale, the locale name is used in the output name
+// so it is limited to letters, numbers, `-`, and `_`
+pub(crate) fn find_locales(dir: &Path) -> Result<Vec<(String, PathBuf)>, Error> {
+    lazy_static! {
+        static ref LOCALE_NAME: Regex =
+            Regex::new(r"^[A-Za-z0-9_-]+$").expect("Should be a valid regex");
+    }
+
+    let mut locales = std::fs::read_dir(dir)
+        .map_err(|e| Error::ReadingLocale(dir.to_path_buf(), e))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<_>>>()
+        .map_err(|e| Error::ReadingLocale(dir.to_path_buf(), e))?
+        .into_iter()
+        .filter(|path| path.is_file() && path.extension().map_or(false, |ext| ext == "toml"))
+        .map(|path| {
+            let name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            if LOCALE_NAME.is_match(&name) {
+                Ok((name, path))
+            } else {
+                Err(Error::InvalidLocale(
+                    path.clone(),
+                    "Locale file names may only contain letters, numbers, `-`, and `_`".to_string(),
+                ))
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    locales.sort();
+
+    Ok(locales)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::generate_changelog::locale::RenderLocale;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_parse_locale() {
+        let locale = RenderLocale::parse(
+            r#"
+markdown_heading = "{buildpack_id} {version} ({date})"
+unreleased = "未リリース"
+
+[date]
+format = "%Y年%-m月%-d日 (%B)"
+months = ["1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月"]
+"#,
+        )
+        .unwrap();
+        let date = Utc.with_ymd_and_hms(2023, 4, 1, 0, 0, 0).unwrap();
+        assert_eq!(
+            locale.render_heading(
+                &locale.markdown_heading,
+                "heroku/nodejs",
+                Some("1.0.0"),
+                Some(&date)
+            ),
+            "heroku/nodejs 1.0.0 (2023年4月1日 (4月))"
+        );
+        assert_eq!(
+            locale.render_heading(&locale.plain_heading, "heroku/nodejs", None, None),
+            "heroku/nodejs (未リリース)"
+        );
+        assert_eq!(locale.no_changes, "No changes");
+        assert_eq!(
+            RenderLocale::default().date_format.format(&date),
+            "2023-04-01"
+        );
+
+        assert!(RenderLocale::parse("no_changes = 1").is_err());
+        assert!(RenderLocale::parse("title = \"Changes\"").is_err());
+        assert!(RenderLocale::parse("markdown_heading = \"{name}\"").is_err());
+        assert!(RenderLocale::parse("[date]\nformat = \"%Q\"").is_err());
+        assert!(RenderLocale::parse("[date]\nmonths = [\"Jan\"]").is_err());
+    }
+}
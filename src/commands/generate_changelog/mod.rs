@@ -1,4 +1,6 @@
 pub(crate) mod command;
 pub(crate) mod errors;
+pub(crate) mod locale;
+pub(crate) mod render;
 
 pub(crate) use command::execute;
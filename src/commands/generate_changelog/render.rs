@@ -0,0 +1,501 @@
+use crate::commands::generate_changelog::locale::RenderLocale;
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use languages_github_actions::changelog::{
+    get_list_entries, get_security_entries, link_cve_references, SecurityEntry,
+};
+use lazy_static::lazy_static;
+use libcnb_data::buildpack::BuildpackId;
+use regex::Regex;
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(ValueEnum, Debug, Clone, Default)]
+pub(crate) enum ChangelogFormat {
+    #[default]
+    Markdown,
+    Json,
+    Plain,
+    Html,
+}
+
+// the changelog section selected for a buildpack, `version` and `date` are `None` for the
+// unreleased section and `body` is `None` when the section has no content
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct ChangelogSection {
+    pub(crate) version: Option<String>,
+    pub(crate) date: Option<DateTime<Utc>>,
+    pub(crate) body: Option<String>,
+}
+
+// buildpacks without a matching section are left out of every format, the locale only changes the
+// text around the entries so the json format is the same for every locale
+pub(crate) fn render_changelog(
+    sections_by_buildpack: &HashMap<BuildpackId, Option<ChangelogSection>>,
+    format: &ChangelogFormat,
+    locale: &RenderLocale,
+) -> String {
+    let sections = sections_by_buildpack
+        .iter()
+        .filter_map(|(buildpack_id, section)| {
+            section
+                .as_ref()
+                .map(|section| (buildpack_id.to_string(), section))
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    match format {
+        ChangelogFormat::Markdown => render_markdown(&sections, locale),
+        ChangelogFormat::Json => render_json(&sections),
+        ChangelogFormat::Plain => render_plain(&sections, locale),
+        ChangelogFormat::Html => render_html(&sections, locale),
+    }
+}
+
+// security fixes from every buildpack are repeated in a section above the buildpacks so they
+// can't be missed in long release notes
+fn collect_security_entries(
+    sections: &BTreeMap<String, &ChangelogSection>,
+) -> Vec<(String, SecurityEntry)> {
+    sections
+        .iter()
+        .flat_map(|(buildpack_id, section)| {
+            section
+                .body
+                .as_deref()
+                .map(get_security_entries)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|entry| (buildpack_id.clone(), entry))
+        })
+        .collect()
+}
+
+fn render_markdown_security(
+    sections: &BTreeMap<String, &ChangelogSection>,
+    locale: &RenderLocale,
+) -> Option<String> {
+    let security_entries = collect_security_entries(sections);
+    (!security_entries.is_empty()).then(|| {
+        format!(
+            "# {}\n\n{}",
+            locale.security_heading,
+            security_entries
+                .iter()
+                .map(|(buildpack_id, security_entry)| {
+                    format!(
+                        "- {buildpack_id}: {}",
+                        link_cve_references(&security_entry.entry)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        )
+    })
+}
+
+fn render_markdown_section(
+    buildpack_id: &str,
+    section: &ChangelogSection,
+    locale: &RenderLocale,
+) -> String {
+    let heading = locale.render_heading(
+        &locale.markdown_heading,
+        buildpack_id,
+        section.version.as_deref(),
+        section.date.as_ref(),
+    );
+    match &section.body {
+        Some(value) => format!("# {heading}\n\n{value}"),
+        None => format!("# {heading}\n\n- {}", locale.no_changes),
+    }
+}
+
+fn render_markdown(
+    sections: &BTreeMap<String, &ChangelogSection>,
+    locale: &RenderLocale,
+) -> String {
+    let changelog =
+        render_markdown_security(sections, locale)
+            .into_iter()
+            .chain(sections.iter().map(|(buildpack_id, section)| {
+                render_markdown_section(buildpack_id, section, locale)
+            }))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+    format!("{}\n\n", changelog.trim())
+}
+
+// a fragment for embedding in dashboards or release emails, each section is wrapped in an element
+// with a class (and the buildpack id) that the embedding page can style, raw HTML in the entries is
+// escaped by the markdown renderer
+fn render_html(sections: &BTreeMap<String, &ChangelogSection>, locale: &RenderLocale) -> String {
+    let to_html = |markdown: &str| {
+        markdown::to_html_with_options(markdown, &markdown::Options::gfm())
+            .expect("Should compile markdown without MDX constructs")
+    };
+    let security = render_markdown_security(sections, locale).map(|security| {
+        format!(
+            "<section class=\"changelog-security\">\n{}\n</section>",
+            to_html(&security).trim_end()
+        )
+    });
+    // buildpack ids only contain characters that are safe in an attribute value
+    let changelog = security
+        .into_iter()
+        .chain(sections.iter().map(|(buildpack_id, section)| {
+            format!(
+                "<section class=\"changelog-buildpack\" data-buildpack-id=\"{buildpack_id}\">\n{}\n</section>",
+                to_html(&render_markdown_section(buildpack_id, section, locale)).trim_end()
+            )
+        }))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("<div class=\"changelog\">\n{changelog}\n</div>\n")
+}
+
+fn render_json(sections: &BTreeMap<String, &ChangelogSection>) -> String {
+    let changelog = sections
+        .iter()
+        .map(|(buildpack_id, section)| {
+            let changes = section
+                .body
+                .as_deref()
+                .map(get_list_entries)
+                .unwrap_or_default();
+            let security = section
+                .body
+                .as_deref()
+                .map(get_security_entries)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|security_entry| {
+                    json!({
+                        "entry": security_entry.entry,
+                        "cves": security_entry.cves,
+                    })
+                })
+                .collect::<Vec<_>>();
+            (
+                buildpack_id,
+                json!({
+                    "version": section.version,
+                    "changes": changes,
+                    "security": security,
+                }),
+            )
+        })
+        .collect::<BTreeMap<_, _>>();
+    json!(changelog).to_string()
+}
+
+fn render_plain(sections: &BTreeMap<String, &ChangelogSection>, locale: &RenderLocale) -> String {
+    let security_entries = collect_security_entries(sections);
+    let security = (!security_entries.is_empty()).then(|| {
+        format!(
+            "{}\n{}\n\n{}",
+            locale.security_heading,
+            "=".repeat(locale.security_heading.chars().count()),
+            security_entries
+                .iter()
+                .map(|(buildpack_id, security_entry)| {
+                    format!("- {buildpack_id}: {}", to_plain_text(&security_entry.entry))
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        )
+    });
+    let changelog = security
+        .into_iter()
+        .chain(sections.iter().map(|(buildpack_id, section)| {
+            let title = locale.render_heading(
+                &locale.plain_heading,
+                buildpack_id,
+                section.version.as_deref(),
+                section.date.as_ref(),
+            );
+            let body = section
+                .body
+                .as_deref()
+                .map(to_plain_text)
+                .unwrap_or_else(|| format!("- {}", locale.no_changes));
+            format!("{title}\n{}\n\n{body}", "=".repeat(title.chars().count()))
+        }))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    format!("{}\n", changelog.trim())
+}
+
+// strips the markdown syntax that doesn't read well outside a renderer, list markers are kept
+fn to_plain_text(body: &str) -> String {
+    lazy_static! {
+        static ref LINK: Regex =
+            Regex::new(r"\[([^\]]+)]\(([^)]+)\)").expect("Should be a valid regex");
+        static ref EMPHASIS: Regex =
+            Regex::new(r"(\*\*|__|`)([^*_`]+)(\*\*|__|`)").expect("Should be a valid regex");
+    }
+
+    body.lines()
+        .map(|line| match line.trim_start_matches('#') {
+            heading if heading.len() < line.len() => format!("{}:", heading.trim()),
+            _ => line.trim_end().to_string(),
+        })
+        .map(|line| LINK.replace_all(&line, "$1 ($2)").to_string())
+        .map(|line| EMPHASIS.replace_all(&line, "$2").to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::generate_changelog::locale::RenderLocale;
+    use crate::commands::generate_changelog::render::{
+        render_changelog, ChangelogFormat, ChangelogSection,
+    };
+    use chrono::{TimeZone, Utc};
+    use libcnb_data::buildpack_id;
+    use std::collections::HashMap;
+
+    fn sections() -> HashMap<libcnb_data::buildpack::BuildpackId, Option<ChangelogSection>> {
+        HashMap::from([
+            (
+                buildpack_id!("c"),
+                Some(ChangelogSection {
+                    version: Some("1.0.1".to_string()),
+                    date: None,
+                    body: Some("- change c.1".to_string()),
+                }),
+            ),
+            (
+                buildpack_id!("a"),
+                Some(ChangelogSection {
+                    version: Some("0.2.0".to_string()),
+                    date: None,
+                    body: Some(
+                        "### Fixed\n\n- change a.1 ([#12](https://github.com/heroku/a/pull/12))\n- Updated `node`"
+                            .to_string(),
+                    ),
+                }),
+            ),
+            (buildpack_id!("b"), None),
+            (
+                buildpack_id!("d"),
+                Some(ChangelogSection {
+                    version: None,
+                    date: None,
+                    body: None,
+                }),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        assert_eq!(
+            render_changelog(
+                &sections(),
+                &ChangelogFormat::Markdown,
+                &RenderLocale::default()
+            ),
+            r#"# a
+
+### Fixed
+
+- change a.1 ([#12](https://github.com/heroku/a/pull/12))
+- Updated `node`
+
+# c
+
+- change c.1
+
+# d
+
+- No changes
+
+"#
+        );
+    }
+
+    #[test]
+    fn test_render_json() {
+        assert_eq!(
+            render_changelog(
+                &sections(),
+                &ChangelogFormat::Json,
+                &RenderLocale::default()
+            ),
+            r#"{"a":{"changes":["change a.1 ([#12](https://github.com/heroku/a/pull/12))","Updated `node`"],"security":[],"version":"0.2.0"},"c":{"changes":["change c.1"],"security":[],"version":"1.0.1"},"d":{"changes":[],"security":[],"version":null}}"#
+        );
+    }
+
+    #[test]
+    fn test_render_plain() {
+        assert_eq!(
+            render_changelog(
+                &sections(),
+                &ChangelogFormat::Plain,
+                &RenderLocale::default()
+            ),
+            r#"a (0.2.0)
+=========
+
+Fixed:
+
+- change a.1 (#12 (https://github.com/heroku/a/pull/12))
+- Updated node
+
+c (1.0.1)
+=========
+
+- change c.1
+
+d (Unreleased)
+==============
+
+- No changes
+"#
+        );
+    }
+
+    #[test]
+    fn test_render_html() {
+        let sections = HashMap::from([(
+            buildpack_id!("heroku/nodejs"),
+            Some(ChangelogSection {
+                version: Some("1.0.0".to_string()),
+                date: None,
+                body: Some(
+                    "### Security\n\n- Updated OpenSSL [CVE-2024-0727]\n\n### Fixed\n\n- Escaped <script> in `npm` output".to_string(),
+                ),
+            }),
+        )]);
+        assert_eq!(
+            render_changelog(&sections, &ChangelogFormat::Html, &RenderLocale::default()),
+            r#"<div class="changelog">
+<section class="changelog-security">
+<h1>Security</h1>
+<ul>
+<li>heroku/nodejs: Updated OpenSSL <a href="https://www.cve.org/CVERecord?id=CVE-2024-0727">CVE-2024-0727</a></li>
+</ul>
+</section>
+<section class="changelog-buildpack" data-buildpack-id="heroku/nodejs">
+<h1>heroku/nodejs</h1>
+<h3>Security</h3>
+<ul>
+<li>Updated OpenSSL [CVE-2024-0727]</li>
+</ul>
+<h3>Fixed</h3>
+<ul>
+<li>Escaped &lt;script&gt; in <code>npm</code> output</li>
+</ul>
+</section>
+</div>
+"#
+        );
+    }
+
+    #[test]
+    fn test_render_localized() {
+        let locale = RenderLocale {
+            markdown_heading: "{buildpack_id} {version}".to_string(),
+            plain_heading: "{buildpack_id} {version} ({date})".to_string(),
+            no_changes: "変更なし".to_string(),
+            unreleased: "未リリース".to_string(),
+            ..RenderLocale::default()
+        };
+        let sections = HashMap::from([
+            (
+                buildpack_id!("a"),
+                Some(ChangelogSection {
+                    version: Some("0.2.0".to_string()),
+                    date: Some(Utc.with_ymd_and_hms(2023, 5, 10, 0, 0, 0).unwrap()),
+                    body: Some("- change a.1".to_string()),
+                }),
+            ),
+            (
+                buildpack_id!("b"),
+                Some(ChangelogSection {
+                    version: None,
+                    date: None,
+                    body: None,
+                }),
+            ),
+        ]);
+
+        assert_eq!(
+            render_changelog(&sections, &ChangelogFormat::Markdown, &locale),
+            "# a 0.2.0\n\n- change a.1\n\n# b 未リリース\n\n- 変更なし\n\n"
+        );
+        assert_eq!(
+            render_changelog(&sections, &ChangelogFormat::Plain, &locale),
+            "a 0.2.0 (2023-05-10)\n====================\n\n- change a.1\n\nb 未リリース ()\n==========\n\n- 変更なし\n"
+        );
+    }
+
+    #[test]
+    fn test_render_security_section() {
+        let sections = HashMap::from([
+            (
+                buildpack_id!("b"),
+                Some(ChangelogSection {
+                    version: Some("1.0.1".to_string()),
+                    date: None,
+                    body: Some("### Security\n\n- Patched yarn".to_string()),
+                }),
+            ),
+            (
+                buildpack_id!("a"),
+                Some(ChangelogSection {
+                    version: Some("1.0.1".to_string()),
+                    date: None,
+                    body: Some(
+                        "### Fixed\n\n- change a.1\n\n### Security\n\n- Updated OpenSSL [CVE-2024-1234]"
+                            .to_string(),
+                    ),
+                }),
+            ),
+        ]);
+
+        assert_eq!(
+            render_changelog(
+                &sections,
+                &ChangelogFormat::Markdown,
+                &RenderLocale::default()
+            ),
+            r#"# Security
+
+- a: Updated OpenSSL [CVE-2024-1234](https://www.cve.org/CVERecord?id=CVE-2024-1234)
+- b: Patched yarn
+
+# a
+
+### Fixed
+
+- change a.1
+
+### Security
+
+- Updated OpenSSL [CVE-2024-1234]
+
+# b
+
+### Security
+
+- Patched yarn
+
+"#
+        );
+        assert_eq!(
+            render_changelog(&sections, &ChangelogFormat::Json, &RenderLocale::default()),
+            r#"{"a":{"changes":["change a.1","Updated OpenSSL [CVE-2024-1234]"],"security":[{"cves":["CVE-2024-1234"],"entry":"Updated OpenSSL [CVE-2024-1234]"}],"version":"1.0.1"},"b":{"changes":["Patched yarn"],"security":[{"cves":[],"entry":"Patched yarn"}],"version":"1.0.1"}}"#
+        );
+        assert!(
+            render_changelog(&sections, &ChangelogFormat::Plain, &RenderLocale::default())
+                .starts_with(
+                "Security\n========\n\n- a: Updated OpenSSL [CVE-2024-1234]\n- b: Patched yarn\n\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,388 @@
+use crate::commands::generate_release_checklist::errors::Error;
+use crate::commands::update_builder_remote::command::builder_path;
+use clap::Parser;
+use languages_github_actions::builder::{get_builder_buildpack_versions, parse_builder_file};
+use languages_github_actions::buildpack::{
+    get_buildpack_docker_repository, get_buildpack_id, get_buildpack_version,
+    is_buildpack_release_disabled, read_buildpack_file, BuildpackDiscoveryArgs, BuildpackFile,
+};
+use languages_github_actions::cancellation::check_cancelled;
+use languages_github_actions::changelog::ChangelogLocationArgs;
+use languages_github_actions::git::get_remote_tags;
+use languages_github_actions::github::actions;
+use languages_github_actions::github::api::{get_default_branch, get_file_contents};
+use languages_github_actions::github::compare::{render_tag_name, DEFAULT_TAG_TEMPLATE};
+use languages_github_actions::log;
+use languages_github_actions::oci::{image_exists, ImageReference};
+use languages_github_actions::versioning::{compare_versions, copy_version};
+use libcnb_data::buildpack::{BuildpackId, BuildpackVersion};
+use std::path::{Path, PathBuf};
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Generates a markdown checklist with the status of each step of a release", long_about = None, disable_version_flag = true)]
+pub(crate) struct GenerateReleaseChecklistArgs {
+    #[arg(long)]
+    pub(crate) version: Option<String>,
+    #[arg(long, default_value = DEFAULT_TAG_TEMPLATE)]
+    pub(crate) tag_template: String,
+    #[arg(long, default_value = "origin")]
+    pub(crate) remote: String,
+    #[arg(long, requires_all = ["builders", "github_token"])]
+    pub(crate) builder_repository: Option<String>,
+    #[arg(long, requires = "builder_repository")]
+    pub(crate) builder_ref: Option<String>,
+    #[arg(long, requires = "builder_repository", value_delimiter = ',', num_args = 1..)]
+    pub(crate) builders: Vec<String>,
+    #[arg(long, requires = "builder_repository")]
+    pub(crate) github_token: Option<String>,
+    #[command(flatten)]
+    pub(crate) discovery: BuildpackDiscoveryArgs,
+    #[command(flatten)]
+    pub(crate) changelog: ChangelogLocationArgs,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) enum ItemStatus {
+    Done,
+    Pending(Vec<String>),
+    Skipped(String),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct ChecklistItem {
+    pub(crate) title: String,
+    pub(crate) status: ItemStatus,
+}
+
+impl ChecklistItem {
+    fn from_problems(title: String, problems: Vec<String>) -> ChecklistItem {
+        ChecklistItem {
+            title,
+            status: if problems.is_empty() {
+                ItemStatus::Done
+            } else {
+                ItemStatus::Pending(problems)
+            },
+        }
+    }
+}
+
+struct ReleasedBuildpack {
+    id: BuildpackId,
+    version: BuildpackVersion,
+    dir: PathBuf,
+    docker_repository: Option<String>,
+}
+
+pub(crate) fn execute(args: GenerateReleaseChecklistArgs) -> Result<()> {
+    let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
+
+    let buildpacks = args
+        .discovery
+        .find_buildpack_dirs(&current_dir, &[current_dir.join("target")])
+        .map_err(|e| Error::FindingBuildpacks(current_dir.clone(), e))?
+        .into_iter()
+        .map(|dir| read_buildpack_file(dir.join("buildpack.toml")).map_err(Error::BuildpackFile))
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .filter(|buildpack_file| {
+            let disabled = is_buildpack_release_disabled(buildpack_file);
+            if disabled {
+                log!(
+                    path = buildpack_file.path;
+                    "⏭️ Skipping disabled buildpack: {}",
+                    buildpack_file.path.display()
+                );
+            }
+            !disabled
+        })
+        .map(|buildpack_file| read_released_buildpack(&buildpack_file, &current_dir))
+        .collect::<Result<Vec<_>>>()?;
+
+    let version = match args.version {
+        Some(version) => BuildpackVersion::try_from(version.clone())
+            .map_err(|e| Error::InvalidVersion(version, e))?,
+        None => buildpacks
+            .iter()
+            .map(|buildpack| &buildpack.version)
+            .max_by(|a, b| compare_versions(a, b))
+            .map(copy_version)
+            .ok_or_else(|| Error::NoBuildpacksFound(current_dir.clone()))?,
+    };
+
+    let items = vec![
+        check_version_bumped(&buildpacks, &version),
+        check_changelogs_rolled(&buildpacks, &version, &args.changelog, &current_dir)?,
+        match &args.builder_repository {
+            Some(builder_repository) => check_builders_updated(
+                &buildpacks,
+                &version,
+                builder_repository,
+                args.builder_ref.as_deref(),
+                &args.builders,
+                args.github_token
+                    .as_deref()
+                    .expect("Should be required with a builder repository"),
+            )?,
+            None => ChecklistItem {
+                title: "Builders updated".to_string(),
+                status: ItemStatus::Skipped("no builder repository given".to_string()),
+            },
+        },
+        check_images_published(&buildpacks, &version)?,
+        check_tags_pushed(
+            &buildpacks,
+            &version,
+            &args.tag_template,
+            &args.remote,
+            &current_dir,
+        )?,
+    ];
+
+    // steps that couldn't be checked are left unticked but don't hold up the release
+    let complete = !items
+        .iter()
+        .any(|item| matches!(item.status, ItemStatus::Pending(_)));
+    if complete {
+        log!("✅️ No release steps for {version} are pending");
+    } else {
+        log!("⚠️ Some release steps for {version} are not done yet");
+    }
+
+    actions::set_output("checklist", render_checklist(&version, &items))
+        .map_err(Error::SetActionOutput)?;
+
+    actions::set_output("complete", complete.to_string()).map_err(Error::SetActionOutput)?;
+
+    Ok(())
+}
+
+fn read_released_buildpack(
+    buildpack_file: &BuildpackFile,
+    current_dir: &Path,
+) -> Result<ReleasedBuildpack> {
+    Ok(ReleasedBuildpack {
+        id: get_buildpack_id(buildpack_file).map_err(Error::BuildpackFile)?,
+        version: get_buildpack_version(buildpack_file).map_err(Error::BuildpackFile)?,
+        dir: buildpack_file
+            .path
+            .parent()
+            .unwrap_or(current_dir)
+            .to_path_buf(),
+        docker_repository: get_buildpack_docker_repository(buildpack_file),
+    })
+}
+
+fn check_version_bumped(
+    buildpacks: &[ReleasedBuildpack],
+    version: &BuildpackVersion,
+) -> ChecklistItem {
+    ChecklistItem::from_problems(
+        format!("Version bumped to {version}"),
+        buildpacks
+            .iter()
+            .filter(|buildpack| &buildpack.version != version)
+            .map(|buildpack| format!("`{}` is at {}", buildpack.id, buildpack.version))
+            .collect(),
+    )
+}
+
+fn check_changelogs_rolled(
+    buildpacks: &[ReleasedBuildpack],
+    version: &BuildpackVersion,
+    changelog: &ChangelogLocationArgs,
+    current_dir: &Path,
+) -> Result<ChecklistItem> {
+    let mut problems = vec![];
+    for buildpack in buildpacks {
+        let changelog_file = changelog
+            .read_changelog_file_from_dir(&buildpack.dir, current_dir)
+            .map_err(Error::ChangelogFile)?;
+        if !changelog_file
+            .changelog
+            .releases
+            .contains_key(&version.to_string())
+        {
+            problems.push(format!("`{}` has no {version} section", buildpack.id));
+        }
+        if changelog_file.changelog.unreleased.is_some() {
+            problems.push(format!("`{}` still has unreleased changes", buildpack.id));
+        }
+    }
+    Ok(ChecklistItem::from_problems(
+        "Changelogs rolled".to_string(),
+        problems,
+    ))
+}
+
+fn check_builders_updated(
+    buildpacks: &[ReleasedBuildpack],
+    version: &BuildpackVersion,
+    builder_repository: &str,
+    builder_ref: Option<&str>,
+    builders: &[String],
+    token: &str,
+) -> Result<ChecklistItem> {
+    let builder_ref = match builder_ref {
+        Some(builder_ref) => builder_ref.to_string(),
+        None => get_default_branch(token, builder_repository).map_err(Error::ReadingBuilders)?,
+    };
+
+    let mut referenced = false;
+    let mut problems = vec![];
+    for builder in builders {
+        check_cancelled().map_err(Error::Cancelled)?;
+        let path = builder_path(builder);
+        let contents = get_file_contents(token, builder_repository, &path, &builder_ref)
+            .map_err(Error::ReadingBuilders)?
+            .ok_or_else(|| Error::BuilderNotFound(builder_repository.to_string(), path.clone()))?;
+        let builder_file =
+            parse_builder_file(PathBuf::from(&path), &contents).map_err(Error::BuilderFile)?;
+        for buildpack in buildpacks {
+            for pinned_version in get_builder_buildpack_versions(&builder_file, &buildpack.id) {
+                referenced = true;
+                if pinned_version != version.to_string() {
+                    problems.push(format!(
+                        "`{builder}` pins `{}` to {pinned_version}",
+                        buildpack.id
+                    ));
+                }
+            }
+        }
+    }
+
+    if !referenced {
+        problems.push(format!(
+            "No builder in `{builder_repository}` references the released buildpacks"
+        ));
+    }
+
+    Ok(ChecklistItem::from_problems(
+        "Builders updated".to_string(),
+        problems,
+    ))
+}
+
+fn check_images_published(
+    buildpacks: &[ReleasedBuildpack],
+    version: &BuildpackVersion,
+) -> Result<ChecklistItem> {
+    let images = buildpacks
+        .iter()
+        .filter_map(|buildpack| buildpack.docker_repository.as_ref())
+        .map(|repository| format!("docker://{repository}:{version}"))
+        .collect::<Vec<_>>();
+
+    if images.is_empty() {
+        return Ok(ChecklistItem {
+            title: "Images published".to_string(),
+            status: ItemStatus::Skipped("no buildpack has a docker repository".to_string()),
+        });
+    }
+
+    let mut problems = vec![];
+    for uri in images {
+        check_cancelled().map_err(Error::Cancelled)?;
+        let image = ImageReference::try_from(uri.as_str())
+            .map_err(|e| Error::QueryingImage(uri.clone(), e))?;
+        if !image_exists(&image).map_err(|e| Error::QueryingImage(uri.clone(), e))? {
+            problems.push(format!("`{image}` was not found"));
+        }
+    }
+
+    Ok(ChecklistItem::from_problems(
+        "Images published".to_string(),
+        problems,
+    ))
+}
+
+fn check_tags_pushed(
+    buildpacks: &[ReleasedBuildpack],
+    version: &BuildpackVersion,
+    tag_template: &str,
+    remote: &str,
+    current_dir: &Path,
+) -> Result<ChecklistItem> {
+    check_cancelled().map_err(Error::Cancelled)?;
+    let remote_tags = get_remote_tags(current_dir, remote).map_err(Error::Git)?;
+
+    let mut tags = buildpacks
+        .iter()
+        .map(|buildpack| render_tag_name(tag_template, &buildpack.id, version))
+        .collect::<Vec<_>>();
+    tags.sort();
+    tags.dedup();
+
+    Ok(ChecklistItem::from_problems(
+        "Tags pushed".to_string(),
+        tags.into_iter()
+            .filter(|tag| !remote_tags.contains(tag))
+            .map(|tag| format!("`{tag}` is not on `{remote}`"))
+            .collect(),
+    ))
+}
+
+// nested bullets explain what's left for a step so the checklist can be pasted into a pull request
+// description as is
+pub(crate) fn render_checklist(version: &BuildpackVersion, items: &[ChecklistItem]) -> String {
+    let lines = items
+        .iter()
+        .flat_map(|item| match &item.status {
+            ItemStatus::Done => vec![format!("- [x] {}", item.title)],
+            ItemStatus::Pending(problems) => std::iter::once(format!("- [ ] {}", item.title))
+                .chain(problems.iter().map(|problem| format!("  - {problem}")))
+                .collect(),
+            ItemStatus::Skipped(reason) => {
+                vec![format!("- [ ] {} _(not checked: {reason})_", item.title)]
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("### Release checklist for {version}\n\n{lines}\n")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::generate_release_checklist::command::{
+        render_checklist, ChecklistItem, ItemStatus,
+    };
+    use libcnb_data::buildpack::BuildpackVersion;
+
+    #[test]
+    fn test_render_checklist() {
+        let items = vec![
+            ChecklistItem {
+                title: "Version bumped to 1.2.3".to_string(),
+                status: ItemStatus::Done,
+            },
+            ChecklistItem {
+                title: "Builders updated".to_string(),
+                status: ItemStatus::Skipped("no builder repository given".to_string()),
+            },
+            ChecklistItem {
+                title: "Tags pushed".to_string(),
+                status: ItemStatus::Pending(vec!["`v1.2.3` is not on `origin`".to_string()]),
+            },
+        ];
+
+        assert_eq!(
+            render_checklist(
+                &BuildpackVersion {
+                    major: 1,
+                    minor: 2,
+                    patch: 3
+                },
+                &items
+            ),
+            r#"### Release checklist for 1.2.3
+
+- [x] Version bumped to 1.2.3
+- [ ] Builders updated _(not checked: no builder repository given)_
+- [ ] Tags pushed
+  - `v1.2.3` is not on `origin`
+"#
+        );
+    }
+}
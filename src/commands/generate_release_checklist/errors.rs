@@ -0,0 +1,120 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::builder::BuilderFileError;
+use languages_github_actions::buildpack::{BuildpackDiscoveryError, BuildpackFileError};
+use languages_github_actions::cancellation::CancellationError;
+use languages_github_actions::changelog::ChangelogFileError;
+use languages_github_actions::git::GitError;
+use languages_github_actions::github::actions::SetOutputError;
+use languages_github_actions::github::api::GitHubApiError;
+use languages_github_actions::oci::OciError;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    GetCurrentDir(std::io::Error),
+    FindingBuildpacks(PathBuf, BuildpackDiscoveryError),
+    BuildpackFile(BuildpackFileError),
+    NoBuildpacksFound(PathBuf),
+    InvalidVersion(String, libcnb_data::buildpack::BuildpackVersionError),
+    ChangelogFile(ChangelogFileError),
+    ReadingBuilders(GitHubApiError),
+    BuilderNotFound(String, String),
+    BuilderFile(BuilderFileError),
+    QueryingImage(String, OciError),
+    Git(GitError),
+    Cancelled(CancellationError),
+    SetActionOutput(SetOutputError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::GetCurrentDir(error) => {
+                write!(f, "Could not get the current directory\nError: {error}")
+            }
+
+            Error::FindingBuildpacks(path, error) => {
+                write!(
+                    f,
+                    "Could not find buildpacks\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::BuildpackFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::NoBuildpacksFound(path) => {
+                write!(f, "No buildpacks found under {}", path.display())
+            }
+
+            Error::InvalidVersion(value, error) => {
+                write!(
+                    f,
+                    "The version argument is invalid\nValue: {value}\nError: {error}"
+                )
+            }
+
+            Error::ChangelogFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::ReadingBuilders(error) => {
+                write!(f, "Could not read the builder repository\nError: {error}")
+            }
+
+            Error::BuilderNotFound(repository, path) => {
+                write!(
+                    f,
+                    "No builder.toml file found in the builder repository\nRepository: {repository}\nPath: {path}"
+                )
+            }
+
+            Error::BuilderFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::QueryingImage(uri, error) => {
+                write!(
+                    f,
+                    "Could not check if the buildpack image exists\nValue: {uri}\nError: {error}"
+                )
+            }
+
+            Error::Git(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::Cancelled(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::SetActionOutput(set_output_error) => match set_output_error {
+                SetOutputError::Opening(error) | SetOutputError::Writing(error) => {
+                    write!(f, "Could not write action output\nError: {error}")
+                }
+            },
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::GetCurrentDir(_) => ErrorCategory::Io,
+            Error::FindingBuildpacks(_, error) => error.category(),
+            Error::BuildpackFile(error) => error.category(),
+            Error::NoBuildpacksFound(_) | Error::InvalidVersion(_, _) => ErrorCategory::Config,
+            Error::ChangelogFile(error) => error.category(),
+            Error::ReadingBuilders(error) => error.category(),
+            Error::BuilderNotFound(_, _) => ErrorCategory::Validation,
+            Error::BuilderFile(error) => error.category(),
+            Error::QueryingImage(_, error) => error.category(),
+            Error::Git(error) => error.category(),
+            Error::Cancelled(error) => error.category(),
+            Error::SetActionOutput(error) => error.category(),
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use crate::commands::generate_token::errors::Error;
+use clap::Parser;
+use languages_github_actions::clock::{Clock, SystemClock};
+use languages_github_actions::github::actions;
+use languages_github_actions::github::auth::generate_installation_token;
+use languages_github_actions::log;
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Generates an installation token for a GitHub App", long_about = None)]
+pub(crate) struct GenerateTokenArgs {
+    #[arg(long)]
+    pub(crate) app_id: String,
+    #[arg(long)]
+    pub(crate) private_key: String,
+    #[arg(long)]
+    pub(crate) repository: String,
+}
+
+pub(crate) fn execute(args: GenerateTokenArgs) -> Result<()> {
+    execute_with_clock(args, &SystemClock)
+}
+
+pub(crate) fn execute_with_clock(args: GenerateTokenArgs, clock: &dyn Clock) -> Result<()> {
+    let token =
+        generate_installation_token(&args.app_id, &args.private_key, &args.repository, clock)
+            .map_err(Error::GeneratingToken)?;
+
+    actions::add_mask(&token);
+    actions::set_output("token", token).map_err(Error::SetActionOutput)?;
+
+    log!("✅️ Generated installation token for {}", args.repository);
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::github::actions::SetOutputError;
+use languages_github_actions::github::auth::AuthError;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    GeneratingToken(AuthError),
+    SetActionOutput(SetOutputError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::GeneratingToken(error) => {
+                write!(f, "Could not generate installation token\nError: {error}")
+            }
+
+            Error::SetActionOutput(set_output_error) => match set_output_error {
+                SetOutputError::Opening(error) | SetOutputError::Writing(error) => {
+                    write!(f, "Could not write action output\nError: {error}")
+                }
+            },
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::GeneratingToken(error) => error.category(),
+            Error::SetActionOutput(error) => error.category(),
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use crate::commands::hash_artifacts::errors::Error;
+use clap::Parser;
+use glob::glob;
+use languages_github_actions::github::actions;
+use languages_github_actions::log;
+use languages_github_actions::write;
+use serde::Serialize;
+use sha2::{Digest, Sha256, Sha512};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Computes checksums and sizes for release artifacts", long_about = None)]
+pub(crate) struct HashArtifactsArgs {
+    #[arg(long = "artifact", required = true)]
+    pub(crate) artifacts: Vec<String>,
+    #[arg(long)]
+    pub(crate) sums_file: Option<PathBuf>,
+}
+
+#[derive(Serialize, Debug, Eq, PartialEq)]
+struct ArtifactHashes {
+    path: String,
+    size: u64,
+    sha256: String,
+    sha512: String,
+}
+
+pub(crate) fn execute(args: HashArtifactsArgs) -> Result<()> {
+    let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
+
+    let artifact_files = find_artifact_files(&current_dir, &args.artifacts)?;
+
+    let mut artifacts = vec![];
+    for artifact_file in &artifact_files {
+        let hashes = hash_artifact(artifact_file)
+            .map_err(|e| Error::HashingArtifact(artifact_file.clone(), e))?;
+        let artifact = ArtifactHashes {
+            path: artifact_file
+                .strip_prefix(&current_dir)
+                .unwrap_or(artifact_file)
+                .display()
+                .to_string(),
+            ..hashes
+        };
+        log!(
+            "✅️ Hashed {} ({} bytes): {}",
+            artifact.path,
+            artifact.size,
+            artifact.sha256
+        );
+        artifacts.push(artifact);
+    }
+
+    if let Some(sums_file) = &args.sums_file {
+        let contents = render_sha256_sums(&artifact_files, &artifacts)?;
+        write::write_file(sums_file, contents)
+            .map_err(|e| Error::WritingSumsFile(sums_file.clone(), e))?;
+        log!("✅️ Wrote checksums to {}", sums_file.display());
+        actions::set_output("sums_file", sums_file.display().to_string())
+            .map_err(Error::SetActionOutput)?;
+    }
+
+    let json = serde_json::to_string(&artifacts).map_err(Error::SerializingJson)?;
+    actions::set_output("artifacts", json).map_err(Error::SetActionOutput)?;
+
+    Ok(())
+}
+
+// entries without glob characters are taken as paths so a missing artifact is reported when it's
+// hashed, a pattern has to match at least one file
+fn find_artifact_files(dir: &Path, artifacts: &[String]) -> Result<Vec<PathBuf>> {
+    let mut artifact_files = vec![];
+    for artifact in artifacts {
+        let matched_files = if artifact.contains(['*', '?', '[']) {
+            let pattern = dir.join(artifact).to_string_lossy().to_string();
+            let mut matched_files = glob(&pattern)
+                .map_err(|e| Error::InvalidArtifactPattern(artifact.clone(), e))?
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(Error::FindingArtifacts)?
+                .into_iter()
+                .filter(|path| path.is_file())
+                .collect::<Vec<_>>();
+            if matched_files.is_empty() {
+                Err(Error::UnmatchedArtifactPattern(artifact.clone()))?;
+            }
+            matched_files.sort();
+            matched_files
+        } else {
+            vec![dir.join(artifact)]
+        };
+
+        for matched_file in matched_files {
+            if !artifact_files.contains(&matched_file) {
+                artifact_files.push(matched_file);
+            }
+        }
+    }
+    Ok(artifact_files)
+}
+
+// both digests are computed in a single read so large `.cnb` files are only streamed once
+fn hash_artifact(path: &Path) -> std::io::Result<ArtifactHashes> {
+    let mut file = File::open(path)?;
+    let mut sha256 = Sha256::new();
+    let mut sha512 = Sha512::new();
+    let mut size = 0;
+    let mut buffer = [0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        sha256.update(&buffer[..read]);
+        sha512.update(&buffer[..read]);
+        size += read as u64;
+    }
+    Ok(ArtifactHashes {
+        path: path.display().to_string(),
+        size,
+        sha256: format!("{:x}", sha256.finalize()),
+        sha512: format!("{:x}", sha512.finalize()),
+    })
+}
+
+// uses the `sha256sum` format with bare file names since release assets are downloaded without
+// their directories, which means two artifacts can't share a file name
+fn render_sha256_sums(artifact_files: &[PathBuf], artifacts: &[ArtifactHashes]) -> Result<String> {
+    let mut paths_by_name: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    for artifact_file in artifact_files {
+        let name = artifact_file
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| artifact_file.display().to_string());
+        paths_by_name
+            .entry(name)
+            .or_default()
+            .push(artifact_file.clone());
+    }
+    if let Some((name, paths)) = paths_by_name.iter().find(|(_, paths)| paths.len() > 1) {
+        Err(Error::DuplicateArtifactName(name.clone(), paths.clone()))?;
+    }
+
+    Ok(artifact_files
+        .iter()
+        .zip(artifacts)
+        .map(|(artifact_file, artifact)| {
+            format!(
+                "{}  {}\n",
+                artifact.sha256,
+                artifact_file
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default()
+            )
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::hash_artifacts::command::{
+        find_artifact_files, hash_artifact, render_sha256_sums,
+    };
+    use crate::commands::hash_artifacts::errors::Error;
+
+    #[test]
+    fn test_hash_artifacts() {
+        let dir = std::env::temp_dir().join(format!("hash-artifacts-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("packaged/x86_64")).unwrap();
+        std::fs::create_dir_all(dir.join("packaged/aarch64")).unwrap();
+        std::fs::write(dir.join("packaged/x86_64/nodejs.cnb"), "hello\n").unwrap();
+        std::fs::write(dir.join("packaged/aarch64/nodejs.cnb"), "").unwrap();
+        std::fs::write(dir.join("packaged/x86_64/notes.txt"), "notes").unwrap();
+
+        let artifact_files = find_artifact_files(
+            &dir,
+            &[
+                "packaged/*/nodejs.cnb".to_string(),
+                "packaged/x86_64/nodejs.cnb".to_string(),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            artifact_files,
+            vec![
+                dir.join("packaged/aarch64/nodejs.cnb"),
+                dir.join("packaged/x86_64/nodejs.cnb")
+            ]
+        );
+
+        let hashes = hash_artifact(&dir.join("packaged/x86_64/nodejs.cnb")).unwrap();
+        assert_eq!(hashes.size, 6);
+        assert_eq!(
+            hashes.sha256,
+            "5891b5b522d5df086d0ff0b110fbd9d21bb4fc7163af34d08286a2e846f6be03"
+        );
+        assert_eq!(hashes.sha512, "e7c22b994c59d9cf2b48e549b1e24666636045930d3da7c1acb299d1c3b7f931f94aae41edda2c2b207a36e10f8bcb8d45223e54878f5b316e7ce3b6bc019629");
+
+        let artifacts = artifact_files
+            .iter()
+            .map(|path| hash_artifact(path).unwrap())
+            .collect::<Vec<_>>();
+        assert!(matches!(
+            render_sha256_sums(&artifact_files, &artifacts),
+            Err(Error::DuplicateArtifactName(name, _)) if name == "nodejs.cnb"
+        ));
+        assert_eq!(
+            render_sha256_sums(&artifact_files[1..], &artifacts[1..]).unwrap(),
+            "5891b5b522d5df086d0ff0b110fbd9d21bb4fc7163af34d08286a2e846f6be03  nodejs.cnb\n"
+        );
+
+        assert!(matches!(
+            find_artifact_files(&dir, &["packaged/*.tgz".to_string()]),
+            Err(Error::UnmatchedArtifactPattern(_))
+        ));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,94 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::github::actions::SetOutputError;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    GetCurrentDir(std::io::Error),
+    InvalidArtifactPattern(String, glob::PatternError),
+    FindingArtifacts(glob::GlobError),
+    UnmatchedArtifactPattern(String),
+    HashingArtifact(PathBuf, std::io::Error),
+    DuplicateArtifactName(String, Vec<PathBuf>),
+    WritingSumsFile(PathBuf, std::io::Error),
+    SerializingJson(serde_json::Error),
+    SetActionOutput(SetOutputError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::GetCurrentDir(error) => {
+                write!(f, "Could not get the current directory\nError: {error}")
+            }
+
+            Error::InvalidArtifactPattern(pattern, error) => {
+                write!(f, "Invalid artifact pattern `{pattern}`\nError: {error}")
+            }
+
+            Error::FindingArtifacts(error) => {
+                write!(f, "Could not find artifacts\nError: {error}")
+            }
+
+            Error::UnmatchedArtifactPattern(pattern) => {
+                write!(f, "No artifacts matched the pattern `{pattern}`")
+            }
+
+            Error::HashingArtifact(path, error) => {
+                write!(
+                    f,
+                    "Could not hash artifact\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::DuplicateArtifactName(name, paths) => {
+                write!(
+                    f,
+                    "Artifacts in a sums file must have unique file names, `{name}` is used by:\n{}",
+                    paths
+                        .iter()
+                        .map(|path| format!("• {}", path.display()))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            }
+
+            Error::WritingSumsFile(path, error) => {
+                write!(
+                    f,
+                    "Could not write sums file\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::SerializingJson(error) => {
+                write!(f, "Could not serialize artifacts into json\nError: {error}")
+            }
+
+            Error::SetActionOutput(set_output_error) => match set_output_error {
+                SetOutputError::Opening(error) | SetOutputError::Writing(error) => {
+                    write!(f, "Could not write action output\nError: {error}")
+                }
+            },
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::GetCurrentDir(_)
+            | Error::FindingArtifacts(_)
+            | Error::HashingArtifact(_, _)
+            | Error::WritingSumsFile(_, _) => ErrorCategory::Io,
+            Error::InvalidArtifactPattern(_, _) | Error::UnmatchedArtifactPattern(_) => {
+                ErrorCategory::Config
+            }
+            Error::DuplicateArtifactName(_, _) => ErrorCategory::Validation,
+            Error::SerializingJson(_) => ErrorCategory::Parse,
+            Error::SetActionOutput(error) => error.category(),
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use crate::commands::lint_buildpacks::config::{LintConfig, Severity};
+use crate::commands::lint_buildpacks::errors::{Error, LintProblem};
+use clap::Parser;
+use languages_github_actions::buildpack::{
+    read_buildpack_file, BuildpackDiscoveryArgs, BuildpackFile,
+};
+use languages_github_actions::lifecycle::ApiVersion;
+use languages_github_actions::log;
+use std::path::PathBuf;
+use toml_edit::Item;
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Checks the buildpack.toml of each detected buildpack for missing metadata and unsupported API versions", long_about = None)]
+pub(crate) struct LintBuildpacksArgs {
+    #[arg(long)]
+    pub(crate) config: Option<PathBuf>,
+    #[command(flatten)]
+    pub(crate) discovery: BuildpackDiscoveryArgs,
+}
+
+pub(crate) fn execute(args: LintBuildpacksArgs) -> Result<()> {
+    let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
+
+    let config = match &args.config {
+        Some(path) => LintConfig::read(&current_dir.join(path))?,
+        None => LintConfig::default(),
+    };
+
+    let buildpack_dirs = args
+        .discovery
+        .find_buildpack_dirs(&current_dir, &[current_dir.join("target")])
+        .map_err(|e| Error::FindingBuildpacks(current_dir.clone(), e))?;
+
+    let mut problems = vec![];
+
+    for dir in buildpack_dirs {
+        let buildpack_file =
+            read_buildpack_file(dir.join("buildpack.toml")).map_err(Error::BuildpackFile)?;
+
+        // the directory name can't be checked for a buildpack at the root of the repository since
+        // it depends on where the repository was checked out
+        let dir_name = Some(&dir)
+            .filter(|dir| *dir != &current_dir)
+            .and_then(|dir| dir.file_name())
+            .map(|name| name.to_string_lossy().to_string());
+
+        let mut buildpack_problems = vec![];
+        for problem in lint_buildpack(
+            &buildpack_file,
+            dir_name.as_deref(),
+            config.min_api,
+            config.max_api,
+        ) {
+            match config.severity(problem.rule()) {
+                Severity::Error => buildpack_problems.push(problem),
+                Severity::Warning => log!("⚠️ {problem}"),
+                Severity::Off => {}
+            }
+        }
+
+        if buildpack_problems.is_empty() {
+            log!(
+                path = buildpack_file.path;
+                "✅️ No problems found: {}",
+                buildpack_file.path.display()
+            );
+        } else {
+            problems.extend(buildpack_problems);
+        }
+    }
+
+    if !problems.is_empty() {
+        Err(Error::LintFailed(problems))?;
+    }
+
+    Ok(())
+}
+
+fn lint_buildpack(
+    buildpack_file: &BuildpackFile,
+    dir_name: Option<&str>,
+    min_api: ApiVersion,
+    max_api: ApiVersion,
+) -> Vec<LintProblem> {
+    let path = &buildpack_file.path;
+    let buildpack = buildpack_file
+        .document
+        .get("buildpack")
+        .and_then(Item::as_table_like);
+    let buildpack_str = |name: &str| {
+        buildpack
+            .and_then(|buildpack| buildpack.get(name))
+            .and_then(Item::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    };
+
+    let mut problems = vec![];
+
+    let has_licenses = buildpack
+        .and_then(|buildpack| buildpack.get("licenses"))
+        .and_then(Item::as_array_of_tables)
+        .map(|licenses| {
+            !licenses.is_empty()
+                && licenses
+                    .iter()
+                    .all(|license| license.contains_key("type") || license.contains_key("uri"))
+        })
+        .unwrap_or(false);
+    if !has_licenses {
+        problems.push(LintProblem::MissingLicenses(path.clone()));
+    }
+
+    if buildpack_str("homepage").is_none() {
+        problems.push(LintProblem::MissingHomepage(path.clone()));
+    }
+
+    if buildpack_str("description").is_none() {
+        problems.push(LintProblem::MissingDescription(path.clone()));
+    }
+
+    // ids are `namespace/name` and the directory is expected to be named after the `name` part
+    // (e.g.; `buildpacks/nodejs-engine` for `heroku/nodejs-engine`) or the whole id with `/`
+    // replaced by `_` as in packaged buildpack directories
+    if let (Some(id), Some(dir_name)) = (buildpack_str("id"), dir_name) {
+        let name = id.rsplit('/').next().unwrap_or(id);
+        if dir_name != name && dir_name != id.replace('/', "_") {
+            problems.push(LintProblem::IdDoesNotMatchDirectory(
+                path.clone(),
+                id.to_string(),
+                dir_name.to_string(),
+            ));
+        }
+    }
+
+    match buildpack_file.document.get("api").and_then(Item::as_str) {
+        Some(api) => match api.parse::<ApiVersion>() {
+            Ok(version) if version >= min_api && version <= max_api => {}
+            _ => problems.push(LintProblem::UnsupportedApi(
+                path.clone(),
+                api.to_string(),
+                min_api,
+                max_api,
+            )),
+        },
+        None => problems.push(LintProblem::MissingApi(path.clone())),
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::lint_buildpacks::command::lint_buildpack;
+    use crate::commands::lint_buildpacks::config::LintConfig;
+    use crate::commands::lint_buildpacks::errors::LintProblem;
+    use languages_github_actions::buildpack::BuildpackFile;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+    use toml_edit::Document;
+
+    fn lint(contents: &str, dir_name: Option<&str>) -> Vec<LintProblem> {
+        let config = LintConfig::default();
+        lint_buildpack(
+            &BuildpackFile {
+                path: PathBuf::from("/a/buildpack.toml"),
+                document: Document::from_str(contents).unwrap(),
+            },
+            dir_name,
+            config.min_api,
+            config.max_api,
+        )
+    }
+
+    #[test]
+    fn test_lint_buildpack() {
+        let contents = r#"
+api = "0.9"
+
+[buildpack]
+id = "heroku/nodejs-engine"
+version = "1.0.0"
+homepage = "https://github.com/heroku/buildpacks-nodejs"
+description = "Installs Node.js"
+
+[[buildpack.licenses]]
+type = "BSD-3-Clause"
+"#;
+        assert_eq!(lint(contents, Some("nodejs-engine")), vec![]);
+        assert_eq!(lint(contents, Some("heroku_nodejs-engine")), vec![]);
+        assert_eq!(lint(contents, None), vec![]);
+        assert_eq!(
+            lint(contents, Some("engine")),
+            vec![LintProblem::IdDoesNotMatchDirectory(
+                PathBuf::from("/a/buildpack.toml"),
+                "heroku/nodejs-engine".to_string(),
+                "engine".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_lint_buildpack_with_missing_metadata() {
+        let problems = lint(
+            "api = \"0.10\"\n\n[buildpack]\nid = \"heroku/nodejs\"\nhomepage = \" \"\n",
+            Some("nodejs"),
+        );
+        assert_eq!(
+            problems,
+            vec![
+                LintProblem::MissingLicenses(PathBuf::from("/a/buildpack.toml")),
+                LintProblem::MissingHomepage(PathBuf::from("/a/buildpack.toml")),
+                LintProblem::MissingDescription(PathBuf::from("/a/buildpack.toml")),
+            ]
+        );
+
+        let problems = lint(
+            "api = \"0.6\"\n\n[buildpack]\nid = \"heroku/nodejs\"\n",
+            None,
+        );
+        assert_eq!(
+            problems.last().unwrap().to_string(),
+            "Buildpack API 0.6 is outside the supported range 0.7 to 0.10 (/a/buildpack.toml)"
+        );
+    }
+}
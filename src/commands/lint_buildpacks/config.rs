@@ -0,0 +1,171 @@
+use crate::commands::lint_buildpacks::errors::Error;
+use languages_github_actions::lifecycle::ApiVersion;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+use toml_edit::{Document, Item};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub(crate) enum LintRule {
+    Licenses,
+    Homepage,
+    Description,
+    IdMatchesDirectory,
+    ApiVersion,
+}
+
+impl LintRule {
+    const ALL: [LintRule; 5] = [
+        LintRule::Licenses,
+        LintRule::Homepage,
+        LintRule::Description,
+        LintRule::IdMatchesDirectory,
+        LintRule::ApiVersion,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            LintRule::Licenses => "licenses",
+            LintRule::Homepage => "homepage",
+            LintRule::Description => "description",
+            LintRule::IdMatchesDirectory => "id-matches-directory",
+            LintRule::ApiVersion => "api-version",
+        }
+    }
+
+    fn default_severity(&self) -> Severity {
+        match self {
+            LintRule::Licenses | LintRule::IdMatchesDirectory | LintRule::ApiVersion => {
+                Severity::Error
+            }
+            LintRule::Homepage | LintRule::Description => Severity::Warning,
+        }
+    }
+}
+
+impl Display for LintRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum Severity {
+    Error,
+    Warning,
+    Off,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) struct LintConfig {
+    severities: HashMap<LintRule, Severity>,
+    pub(crate) min_api: ApiVersion,
+    pub(crate) max_api: ApiVersion,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        LintConfig {
+            severities: HashMap::new(),
+            min_api: ApiVersion(0, 7),
+            max_api: ApiVersion(0, 10),
+        }
+    }
+}
+
+impl LintConfig {
+    pub(crate) fn read(path: &Path) -> Result<LintConfig, Error> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| Error::ReadingConfig(path.to_path_buf(), e))?;
+        LintConfig::parse(&contents)
+            .map_err(|message| Error::InvalidConfig(path.to_path_buf(), message))
+    }
+
+    // rules are set in a `[rules]` table by name to `error`, `warning`, or `off` and the supported
+    // buildpack API range in an `[api]` table with `min` and `max`
+    fn parse(contents: &str) -> Result<LintConfig, String> {
+        let document = Document::from_str(contents).map_err(|e| e.to_string())?;
+        let mut config = LintConfig::default();
+
+        if let Some(rules) = document.get("rules").and_then(Item::as_table_like) {
+            for (name, value) in rules.iter() {
+                let rule = LintRule::ALL
+                    .into_iter()
+                    .find(|rule| rule.name() == name)
+                    .ok_or_else(|| format!("Unknown rule `{name}`"))?;
+                let severity = match value.as_str() {
+                    Some("error") => Severity::Error,
+                    Some("warning") => Severity::Warning,
+                    Some("off") => Severity::Off,
+                    _ => Err(format!(
+                        "Rule `{name}` must be one of `error`, `warning`, or `off`"
+                    ))?,
+                };
+                config.severities.insert(rule, severity);
+            }
+        }
+
+        if let Some(api) = document.get("api").and_then(Item::as_table_like) {
+            for (name, value) in api.iter() {
+                let version = value
+                    .as_str()
+                    .and_then(|value| value.parse::<ApiVersion>().ok())
+                    .ok_or_else(|| format!("API `{name}` must be a version like `0.9`"))?;
+                match name {
+                    "min" => config.min_api = version,
+                    "max" => config.max_api = version,
+                    _ => Err(format!("Unknown API setting `{name}`"))?,
+                }
+            }
+        }
+
+        if config.min_api > config.max_api {
+            Err(format!(
+                "API `min` ({}) is newer than `max` ({})",
+                config.min_api, config.max_api
+            ))?;
+        }
+
+        Ok(config)
+    }
+
+    pub(crate) fn severity(&self, rule: LintRule) -> Severity {
+        self.severities
+            .get(&rule)
+            .copied()
+            .unwrap_or_else(|| rule.default_severity())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::lint_buildpacks::config::{LintConfig, LintRule, Severity};
+    use languages_github_actions::lifecycle::ApiVersion;
+
+    #[test]
+    fn test_parse_config() {
+        let config = LintConfig::parse(
+            r#"
+[rules]
+homepage = "error"
+licenses = "off"
+
+[api]
+max = "0.10"
+min = "0.9"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.severity(LintRule::Homepage), Severity::Error);
+        assert_eq!(config.severity(LintRule::Licenses), Severity::Off);
+        assert_eq!(config.severity(LintRule::Description), Severity::Warning);
+        assert_eq!(config.min_api, ApiVersion(0, 9));
+        assert_eq!(config.max_api, ApiVersion(0, 10));
+
+        assert!(LintConfig::parse("[rules]\nmissing = \"error\"").is_err());
+        assert!(LintConfig::parse("[rules]\nhomepage = \"fatal\"").is_err());
+        assert!(LintConfig::parse("[api]\nmin = \"0.10\"\nmax = \"0.9\"").is_err());
+    }
+}
@@ -0,0 +1,145 @@
+use crate::commands::lint_buildpacks::config::LintRule;
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::buildpack::{BuildpackDiscoveryError, BuildpackFileError};
+use languages_github_actions::lifecycle::ApiVersion;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    GetCurrentDir(io::Error),
+    ReadingConfig(PathBuf, io::Error),
+    InvalidConfig(PathBuf, String),
+    FindingBuildpacks(PathBuf, BuildpackDiscoveryError),
+    BuildpackFile(BuildpackFileError),
+    LintFailed(Vec<LintProblem>),
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) enum LintProblem {
+    MissingLicenses(PathBuf),
+    MissingHomepage(PathBuf),
+    MissingDescription(PathBuf),
+    IdDoesNotMatchDirectory(PathBuf, String, String),
+    MissingApi(PathBuf),
+    UnsupportedApi(PathBuf, String, ApiVersion, ApiVersion),
+}
+
+impl LintProblem {
+    pub(crate) fn rule(&self) -> LintRule {
+        match self {
+            LintProblem::MissingLicenses(_) => LintRule::Licenses,
+            LintProblem::MissingHomepage(_) => LintRule::Homepage,
+            LintProblem::MissingDescription(_) => LintRule::Description,
+            LintProblem::IdDoesNotMatchDirectory(_, _, _) => LintRule::IdMatchesDirectory,
+            LintProblem::MissingApi(_) | LintProblem::UnsupportedApi(_, _, _, _) => {
+                LintRule::ApiVersion
+            }
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::GetCurrentDir(error) => {
+                write!(f, "Failed to get current directory\nError: {error}")
+            }
+
+            Error::ReadingConfig(path, error) => {
+                write!(
+                    f,
+                    "Could not read lint config\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::InvalidConfig(path, error) => {
+                write!(
+                    f,
+                    "Invalid lint config\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::FindingBuildpacks(path, error) => {
+                write!(
+                    f,
+                    "Could not find buildpacks\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::BuildpackFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::LintFailed(problems) => {
+                write!(
+                    f,
+                    "Problems found in buildpacks:\n{}",
+                    problems
+                        .iter()
+                        .map(|problem| format!("• {problem}"))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            }
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::GetCurrentDir(_) | Error::ReadingConfig(_, _) => ErrorCategory::Io,
+            Error::FindingBuildpacks(_, error) => error.category(),
+            Error::InvalidConfig(_, _) => ErrorCategory::Config,
+            Error::BuildpackFile(error) => error.category(),
+            Error::LintFailed(_) => ErrorCategory::Validation,
+        }
+    }
+}
+
+impl Display for LintProblem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LintProblem::MissingLicenses(path) => {
+                write!(
+                    f,
+                    "No licenses declared in `[[buildpack.licenses]]` ({})",
+                    path.display()
+                )
+            }
+
+            LintProblem::MissingHomepage(path) => {
+                write!(f, "No `buildpack.homepage` ({})", path.display())
+            }
+
+            LintProblem::MissingDescription(path) => {
+                write!(f, "No `buildpack.description` ({})", path.display())
+            }
+
+            LintProblem::IdDoesNotMatchDirectory(path, id, dir_name) => {
+                write!(
+                    f,
+                    "Buildpack id `{id}` does not match its directory `{dir_name}` ({})",
+                    path.display()
+                )
+            }
+
+            LintProblem::MissingApi(path) => {
+                write!(f, "No buildpack `api` version ({})", path.display())
+            }
+
+            LintProblem::UnsupportedApi(path, api, min_api, max_api) => {
+                write!(
+                    f,
+                    "Buildpack API {api} is outside the supported range {min_api} to {max_api} ({})",
+                    path.display()
+                )
+            }
+        }
+    }
+}
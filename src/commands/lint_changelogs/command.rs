@@ -0,0 +1,170 @@
+use crate::commands::lint_changelogs::errors::{Error, LintProblem};
+use crate::commands::lint_changelogs::style::{check_style, fix_style, StyleConfig, StyleRule};
+use clap::Parser;
+use languages_github_actions::buildpack::BuildpackDiscoveryArgs;
+use languages_github_actions::changelog::{
+    find_duplicated_unreleased_entries, ChangelogFile, ChangelogLocationArgs,
+};
+use languages_github_actions::log;
+use languages_github_actions::write;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Checks the changelog of each detected buildpack for common mistakes", long_about = None)]
+pub(crate) struct LintChangelogsArgs {
+    #[arg(long)]
+    pub(crate) require_entries: bool,
+    #[arg(long)]
+    pub(crate) config: Option<PathBuf>,
+    #[arg(long)]
+    pub(crate) fix: bool,
+    #[command(flatten)]
+    pub(crate) discovery: BuildpackDiscoveryArgs,
+    #[command(flatten)]
+    pub(crate) changelog: ChangelogLocationArgs,
+}
+
+pub(crate) fn execute(args: LintChangelogsArgs) -> Result<()> {
+    let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
+
+    let style_rules = match &args.config {
+        Some(path) => StyleConfig::read(&current_dir.join(path))?.rules(),
+        None => vec![],
+    };
+
+    let buildpack_dirs = args
+        .discovery
+        .find_buildpack_dirs(&current_dir, &[current_dir.join("target")])
+        .map_err(|e| Error::FindingBuildpacks(current_dir.clone(), e))?;
+
+    let mut problems = vec![];
+    // buildpacks without their own changelog share the one at the repository root
+    let mut styled_changelogs = HashSet::new();
+
+    for dir in buildpack_dirs {
+        let changelog_file = args
+            .changelog
+            .read_changelog_file_from_dir(&dir, &current_dir)
+            .map_err(Error::ChangelogFile)?;
+
+        let mut changelog_problems = lint_changelog(&changelog_file, args.require_entries);
+
+        if !style_rules.is_empty() && styled_changelogs.insert(changelog_file.path.clone()) {
+            changelog_problems.extend(lint_changelog_style(
+                &changelog_file.path,
+                &style_rules,
+                args.fix,
+            )?);
+        }
+
+        if changelog_problems.is_empty() {
+            log!(
+                path = changelog_file.path;
+                "✅️ No problems found: {}",
+                changelog_file.path.display()
+            );
+        } else {
+            problems.extend(changelog_problems);
+        }
+    }
+
+    if !problems.is_empty() {
+        Err(Error::LintFailed(problems))?;
+    }
+
+    Ok(())
+}
+
+fn lint_changelog(changelog_file: &ChangelogFile, require_entries: bool) -> Vec<LintProblem> {
+    let mut problems = find_duplicated_unreleased_entries(&changelog_file.changelog)
+        .into_iter()
+        .map(|(entry, version)| {
+            LintProblem::DuplicatedEntry(changelog_file.path.clone(), entry, version)
+        })
+        .collect::<Vec<_>>();
+
+    if require_entries && changelog_file.changelog.unreleased.is_none() {
+        problems.push(LintProblem::NoUnreleasedEntries(
+            changelog_file.path.clone(),
+        ));
+    }
+
+    problems
+}
+
+// with `fix`, the fixable problems are rewritten first so only the remaining ones are reported
+fn lint_changelog_style(
+    path: &PathBuf,
+    rules: &[Box<dyn StyleRule>],
+    fix: bool,
+) -> Result<Vec<LintProblem>> {
+    let mut contents =
+        std::fs::read_to_string(path).map_err(|e| Error::ReadingChangelog(path.clone(), e))?;
+
+    if fix {
+        let fixed = fix_style(&contents, rules);
+        if fixed != contents {
+            write::write_file(path, &fixed)
+                .map_err(|e| Error::WritingChangelog(path.clone(), e))?;
+            log!(path = path; "✅️ Fixed changelog style: {}", path.display());
+            contents = fixed;
+        }
+    }
+
+    Ok(check_style(&contents, rules)
+        .into_iter()
+        .map(|violation| {
+            LintProblem::Style(
+                path.clone(),
+                violation.line,
+                violation.rule,
+                violation.message,
+            )
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::lint_changelogs::command::lint_changelog;
+    use crate::commands::lint_changelogs::errors::LintProblem;
+    use languages_github_actions::changelog::{Changelog, ChangelogFile};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_lint_changelog() {
+        let changelog_file = ChangelogFile {
+            path: PathBuf::from("/a/CHANGELOG.md"),
+            changelog: Changelog::try_from(
+                "## [Unreleased]\n\n- Some change\n\n## [1.0.0] - 2023-05-01\n\n- Some change",
+            )
+            .unwrap(),
+        };
+        assert_eq!(
+            lint_changelog(&changelog_file, true),
+            vec![LintProblem::DuplicatedEntry(
+                PathBuf::from("/a/CHANGELOG.md"),
+                "Some change".to_string(),
+                "1.0.0".to_string()
+            )]
+        );
+
+        let changelog_file = ChangelogFile {
+            path: PathBuf::from("/a/CHANGELOG.md"),
+            changelog: Changelog::try_from(
+                "## [Unreleased]\n\n## [1.0.0] - 2023-05-01\n\n- Some change",
+            )
+            .unwrap(),
+        };
+        assert_eq!(lint_changelog(&changelog_file, false), vec![]);
+        assert_eq!(
+            lint_changelog(&changelog_file, true),
+            vec![LintProblem::NoUnreleasedEntries(PathBuf::from(
+                "/a/CHANGELOG.md"
+            ))]
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::buildpack::BuildpackDiscoveryError;
+use languages_github_actions::changelog::ChangelogFileError;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    GetCurrentDir(io::Error),
+    ReadingConfig(PathBuf, io::Error),
+    InvalidConfig(PathBuf, String),
+    FindingBuildpacks(PathBuf, BuildpackDiscoveryError),
+    ChangelogFile(ChangelogFileError),
+    ReadingChangelog(PathBuf, io::Error),
+    WritingChangelog(PathBuf, io::Error),
+    LintFailed(Vec<LintProblem>),
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) enum LintProblem {
+    DuplicatedEntry(PathBuf, String, String),
+    NoUnreleasedEntries(PathBuf),
+    Style(PathBuf, usize, &'static str, String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::GetCurrentDir(error) => {
+                write!(f, "Failed to get current directory\nError: {error}")
+            }
+
+            Error::ReadingConfig(path, error) => {
+                write!(
+                    f,
+                    "Could not read lint config\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::InvalidConfig(path, error) => {
+                write!(
+                    f,
+                    "Invalid lint config\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::FindingBuildpacks(path, error) => {
+                write!(
+                    f,
+                    "Could not find buildpacks\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::ChangelogFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::ReadingChangelog(path, error) => {
+                write!(
+                    f,
+                    "Could not read changelog\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::WritingChangelog(path, error) => {
+                write!(
+                    f,
+                    "Could not write changelog\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::LintFailed(problems) => {
+                write!(
+                    f,
+                    "Problems found in changelogs:\n{}",
+                    problems
+                        .iter()
+                        .map(|problem| format!("• {problem}"))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            }
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::GetCurrentDir(_)
+            | Error::ReadingConfig(_, _)
+            | Error::ReadingChangelog(_, _)
+            | Error::WritingChangelog(_, _) => ErrorCategory::Io,
+            Error::FindingBuildpacks(_, error) => error.category(),
+            Error::InvalidConfig(_, _) => ErrorCategory::Config,
+            Error::ChangelogFile(error) => error.category(),
+            Error::LintFailed(_) => ErrorCategory::Validation,
+        }
+    }
+}
+
+impl Display for LintProblem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LintProblem::DuplicatedEntry(path, entry, version) => {
+                write!(
+                    f,
+                    "Unreleased entry `{entry}` was already released in {version} ({})",
+                    path.display()
+                )
+            }
+
+            LintProblem::NoUnreleasedEntries(path) => {
+                write!(f, "No unreleased entries ({})", path.display())
+            }
+
+            LintProblem::Style(path, line, rule, message) => {
+                write!(f, "{message} [{rule}] ({}:{line})", path.display())
+            }
+        }
+    }
+}
@@ -0,0 +1,390 @@
+use crate::commands::lint_changelogs::errors::Error;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::path::Path;
+use std::str::FromStr;
+use toml_edit::{Document, Item};
+
+// a style rule checks the lines of a single changelog bullet, including any indented continuation
+// lines, and can optionally rewrite them
+pub(crate) trait StyleRule {
+    fn name(&self) -> &'static str;
+
+    fn check(&self, bullet: &Bullet) -> Option<String>;
+
+    // rules that can't be fixed automatically leave the bullet as it is
+    fn fix(&self, _bullet: &mut Bullet) {}
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct Bullet {
+    pub(crate) line: usize,
+    pub(crate) marker: String,
+    pub(crate) lines: Vec<String>,
+}
+
+impl Bullet {
+    fn text(&self) -> &str {
+        &self.lines[0][self.marker.len()..]
+    }
+
+    fn last_line(&self) -> &str {
+        self.lines.last().map(String::as_str).unwrap_or_default()
+    }
+
+    fn last_line_mut(&mut self) -> &mut String {
+        self.lines.last_mut().expect("A bullet should have a line")
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) struct StyleViolation {
+    pub(crate) line: usize,
+    pub(crate) rule: &'static str,
+    pub(crate) message: String,
+}
+
+struct TrailingWhitespace;
+
+impl StyleRule for TrailingWhitespace {
+    fn name(&self) -> &'static str {
+        "trailing-whitespace"
+    }
+
+    fn check(&self, bullet: &Bullet) -> Option<String> {
+        bullet
+            .lines
+            .iter()
+            .any(|line| line.trim_end() != line)
+            .then(|| "Trailing whitespace".to_string())
+    }
+
+    fn fix(&self, bullet: &mut Bullet) {
+        for line in &mut bullet.lines {
+            line.truncate(line.trim_end().len());
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum FinalPeriod {
+    Always,
+    Never,
+}
+
+impl StyleRule for FinalPeriod {
+    fn name(&self) -> &'static str {
+        "final-period"
+    }
+
+    fn check(&self, bullet: &Bullet) -> Option<String> {
+        let last_line = bullet.last_line().trim_end();
+        match self {
+            FinalPeriod::Always if !last_line.ends_with(['.', '!', '?', ':']) => {
+                Some("Entry should end with a period".to_string())
+            }
+            FinalPeriod::Never if last_line.ends_with('.') && !last_line.ends_with("...") => {
+                Some("Entry should not end with a period".to_string())
+            }
+            _ => None,
+        }
+    }
+
+    fn fix(&self, bullet: &mut Bullet) {
+        if self.check(bullet).is_none() {
+            return;
+        }
+        let last_line = bullet.last_line_mut();
+        let trailing = last_line.len() - last_line.trim_end().len();
+        let end = last_line.len() - trailing;
+        match self {
+            FinalPeriod::Always => last_line.insert(end, '.'),
+            FinalPeriod::Never => {
+                last_line.remove(end - 1);
+            }
+        }
+    }
+}
+
+// entries starting with code (e.g.; `npm`) or a link are left alone since their case is significant
+struct SentenceCase;
+
+impl StyleRule for SentenceCase {
+    fn name(&self) -> &'static str {
+        "sentence-case"
+    }
+
+    fn check(&self, bullet: &Bullet) -> Option<String> {
+        bullet
+            .text()
+            .chars()
+            .next()
+            .filter(|c| c.is_lowercase())
+            .map(|_| "Entry should start with an uppercase letter".to_string())
+    }
+
+    fn fix(&self, bullet: &mut Bullet) {
+        if self.check(bullet).is_none() {
+            return;
+        }
+        let marker_len = bullet.marker.len();
+        let first_line = &mut bullet.lines[0];
+        let first = first_line[marker_len..]
+            .chars()
+            .next()
+            .expect("A lowercase entry should have a first character");
+        first_line.replace_range(
+            marker_len..marker_len + first.len_utf8(),
+            &first.to_uppercase().to_string(),
+        );
+    }
+}
+
+struct MaxLineLength(usize);
+
+impl StyleRule for MaxLineLength {
+    fn name(&self) -> &'static str {
+        "max-line-length"
+    }
+
+    fn check(&self, bullet: &Bullet) -> Option<String> {
+        bullet
+            .lines
+            .iter()
+            .map(|line| line.trim_end().chars().count())
+            .max()
+            .filter(|length| *length > self.0)
+            .map(|length| format!("Line is {length} characters, the maximum is {}", self.0))
+    }
+}
+
+// every rule is off unless it is enabled in the `[style]` table of the lint config
+#[derive(Debug, Default, Eq, PartialEq)]
+pub(crate) struct StyleConfig {
+    trailing_whitespace: bool,
+    final_period: Option<FinalPeriod>,
+    sentence_case: bool,
+    max_line_length: Option<usize>,
+}
+
+impl StyleConfig {
+    pub(crate) fn read(path: &Path) -> Result<StyleConfig, Error> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| Error::ReadingConfig(path.to_path_buf(), e))?;
+        StyleConfig::parse(&contents)
+            .map_err(|message| Error::InvalidConfig(path.to_path_buf(), message))
+    }
+
+    fn parse(contents: &str) -> Result<StyleConfig, String> {
+        let document = Document::from_str(contents).map_err(|e| e.to_string())?;
+        let mut config = StyleConfig::default();
+
+        if let Some(style) = document.get("style").and_then(Item::as_table_like) {
+            for (name, value) in style.iter() {
+                match name {
+                    "trailing-whitespace" => {
+                        config.trailing_whitespace = value
+                            .as_bool()
+                            .ok_or_else(|| format!("Rule `{name}` must be `true` or `false`"))?;
+                    }
+                    "sentence-case" => {
+                        config.sentence_case = value
+                            .as_bool()
+                            .ok_or_else(|| format!("Rule `{name}` must be `true` or `false`"))?;
+                    }
+                    "final-period" => {
+                        config.final_period = match value.as_str() {
+                            Some("always") => Some(FinalPeriod::Always),
+                            Some("never") => Some(FinalPeriod::Never),
+                            Some("off") => None,
+                            _ => Err(format!(
+                                "Rule `{name}` must be one of `always`, `never`, or `off`"
+                            ))?,
+                        };
+                    }
+                    "max-line-length" => {
+                        config.max_line_length = Some(
+                            value
+                                .as_integer()
+                                .and_then(|value| usize::try_from(value).ok())
+                                .filter(|value| *value > 0)
+                                .ok_or_else(|| {
+                                    format!("Rule `{name}` must be a positive number")
+                                })?,
+                        );
+                    }
+                    _ => Err(format!("Unknown rule `{name}`"))?,
+                }
+            }
+        }
+
+        Ok(config)
+    }
+
+    pub(crate) fn rules(&self) -> Vec<Box<dyn StyleRule>> {
+        let mut rules: Vec<Box<dyn StyleRule>> = vec![];
+        if self.trailing_whitespace {
+            rules.push(Box::new(TrailingWhitespace));
+        }
+        if self.sentence_case {
+            rules.push(Box::new(SentenceCase));
+        }
+        if let Some(final_period) = self.final_period {
+            rules.push(Box::new(final_period));
+        }
+        if let Some(max_line_length) = self.max_line_length {
+            rules.push(Box::new(MaxLineLength(max_line_length)));
+        }
+        rules
+    }
+}
+
+// bullets are list items outside of code blocks, a bullet continues over the indented lines that
+// follow it until a blank line or another list item
+fn find_bullets(lines: &[&str]) -> Vec<Bullet> {
+    lazy_static! {
+        static ref LIST_ITEM: Regex = Regex::new(r"^\s*[-*+]\s+").expect("Should be a valid regex");
+    }
+
+    let mut bullets: Vec<Bullet> = vec![];
+    let mut in_bullet = false;
+    let mut in_code_block = false;
+
+    for (index, line) in lines.iter().enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            in_bullet = false;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+
+        match LIST_ITEM.find(line) {
+            Some(marker) => {
+                bullets.push(Bullet {
+                    line: index + 1,
+                    marker: marker.as_str().to_string(),
+                    lines: vec![line.to_string()],
+                });
+                in_bullet = true;
+            }
+            None if in_bullet && line.starts_with([' ', '\t']) && !line.trim().is_empty() => {
+                if let Some(bullet) = bullets.last_mut() {
+                    bullet.lines.push(line.to_string());
+                }
+            }
+            None => in_bullet = false,
+        }
+    }
+
+    bullets
+}
+
+pub(crate) fn check_style(contents: &str, rules: &[Box<dyn StyleRule>]) -> Vec<StyleViolation> {
+    let lines = contents.split('\n').collect::<Vec<_>>();
+    let mut violations = vec![];
+    for bullet in find_bullets(&lines) {
+        for rule in rules {
+            if let Some(message) = rule.check(&bullet) {
+                violations.push(StyleViolation {
+                    line: bullet.line,
+                    rule: rule.name(),
+                    message,
+                });
+            }
+        }
+    }
+    violations
+}
+
+pub(crate) fn fix_style(contents: &str, rules: &[Box<dyn StyleRule>]) -> String {
+    let mut lines = contents.split('\n').map(String::from).collect::<Vec<_>>();
+    let bullets = find_bullets(&lines.iter().map(String::as_str).collect::<Vec<_>>());
+    for mut bullet in bullets {
+        for rule in rules {
+            rule.fix(&mut bullet);
+        }
+        for (offset, line) in bullet.lines.into_iter().enumerate() {
+            lines[bullet.line - 1 + offset] = line;
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::lint_changelogs::style::{check_style, fix_style, StyleConfig};
+
+    const CHANGELOG: &str = "# Changelog
+
+## [Unreleased]
+
+- added support for pnpm 8 \n- Updated `npm` to 9.6.0.
+  See the release notes
+- Fixed a very long entry that goes on and on
+
+```
+- not an entry
+```
+";
+
+    #[test]
+    fn test_check_style() {
+        let config = StyleConfig::parse(
+            "[style]\ntrailing-whitespace = true\nsentence-case = true\nfinal-period = \"always\"\nmax-line-length = 40\n",
+        )
+        .unwrap();
+        let violations = check_style(CHANGELOG, &config.rules())
+            .into_iter()
+            .map(|violation| format!("{}:{}", violation.line, violation.rule))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            violations,
+            vec![
+                "5:trailing-whitespace",
+                "5:sentence-case",
+                "5:final-period",
+                "6:final-period",
+                "8:final-period",
+                "8:max-line-length",
+            ]
+        );
+
+        assert!(StyleConfig::parse("[style]\nfinal-period = \"sometimes\"").is_err());
+        assert!(StyleConfig::parse("[style]\nmax-line-length = 0").is_err());
+        assert!(StyleConfig::parse("[style]\nspelling = true").is_err());
+    }
+
+    #[test]
+    fn test_fix_style() {
+        let config = StyleConfig::parse(
+            "[style]\ntrailing-whitespace = true\nsentence-case = true\nfinal-period = \"always\"\n",
+        )
+        .unwrap();
+        let fixed = fix_style(CHANGELOG, &config.rules());
+        assert_eq!(
+            fixed,
+            "# Changelog
+
+## [Unreleased]
+
+- Added support for pnpm 8.
+- Updated `npm` to 9.6.0.
+  See the release notes.
+- Fixed a very long entry that goes on and on.
+
+```
+- not an entry
+```
+"
+        );
+        assert_eq!(check_style(&fixed, &config.rules()), vec![]);
+
+        let config = StyleConfig::parse("[style]\nfinal-period = \"never\"").unwrap();
+        assert_eq!(
+            fix_style("- Some change.\n", &config.rules()),
+            "- Some change\n"
+        );
+    }
+}
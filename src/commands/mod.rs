@@ -1,4 +1,38 @@
+pub(crate) mod add_changelog_entry;
+pub(crate) mod bump_dependency;
+pub(crate) mod check_registry;
+pub(crate) mod check_version;
+pub(crate) mod commit_changes;
+pub(crate) mod compile_root_changelog;
+pub(crate) mod create_buildpackage;
+pub(crate) mod deprecate_buildpack;
+pub(crate) mod diff_sbom;
+pub(crate) mod extract_changelog;
+pub(crate) mod flatten_buildpack;
+pub(crate) mod fmt_changelogs;
 pub(crate) mod generate_buildpack_matrix;
 pub(crate) mod generate_changelog;
+pub(crate) mod generate_release_checklist;
+pub(crate) mod generate_token;
+pub(crate) mod hash_artifacts;
+pub(crate) mod lint_buildpacks;
+pub(crate) mod lint_changelogs;
+pub(crate) mod package_buildpack;
+pub(crate) mod plan_release;
+pub(crate) mod post_pr_comment;
+pub(crate) mod post_release_announcement;
 pub(crate) mod prepare_release;
+pub(crate) mod register_buildpack;
+pub(crate) mod release_impact;
+pub(crate) mod rename_buildpack;
+pub(crate) mod run_release;
+pub(crate) mod set_buildpack_targets;
+pub(crate) mod show_release_state;
+pub(crate) mod sync_docs;
+pub(crate) mod sync_meta_buildpacks;
+pub(crate) mod update_action_pins;
 pub(crate) mod update_builder;
+pub(crate) mod update_builder_remote;
+pub(crate) mod validate_builders;
+pub(crate) mod verify_changelog_dates;
+pub(crate) mod verify_published;
@@ -0,0 +1,139 @@
+use crate::commands::package_buildpack::errors::Error;
+use cargo_metadata::MetadataCommand;
+use clap::Parser;
+use languages_github_actions::buildpack::{
+    get_buildpack_docker_repository, get_buildpack_id, get_buildpack_version, read_buildpack_file,
+};
+use languages_github_actions::github::actions;
+use languages_github_actions::log;
+use libcnb_data::buildpack::BuildpackId;
+use libcnb_package::build::build_buildpack_binaries;
+use libcnb_package::cross_compile::{cross_compile_assistance, CrossCompileAssistance};
+use libcnb_package::{assemble_buildpack_directory, CargoProfile};
+use std::path::{Path, PathBuf};
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Compiles a libcnb.rs buildpack and assembles it into a packaged buildpack directory", long_about = None)]
+pub(crate) struct PackageBuildpackArgs {
+    #[arg(long, default_value = ".")]
+    pub(crate) path: PathBuf,
+    #[arg(long)]
+    pub(crate) release: bool,
+    #[arg(long, default_value = "x86_64-unknown-linux-musl")]
+    pub(crate) target: String,
+}
+
+pub(crate) fn execute(args: PackageBuildpackArgs) -> Result<()> {
+    let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
+    let buildpack_dir = current_dir.join(&args.path);
+
+    let buildpack_file =
+        read_buildpack_file(buildpack_dir.join("buildpack.toml")).map_err(Error::BuildpackFile)?;
+    let buildpack_id = get_buildpack_id(&buildpack_file).map_err(Error::BuildpackFile)?;
+    let buildpack_version = get_buildpack_version(&buildpack_file).map_err(Error::BuildpackFile)?;
+
+    let cargo_manifest = buildpack_dir.join("Cargo.toml");
+    if !cargo_manifest.exists() {
+        Err(Error::NotALibcnbBuildpack(buildpack_dir.clone()))?;
+    }
+
+    let cargo_metadata = MetadataCommand::new()
+        .manifest_path(&cargo_manifest)
+        .exec()
+        .map_err(|e| Error::ReadingCargoMetadata(cargo_manifest.clone(), e))?;
+
+    let cargo_env = match cross_compile_assistance(&args.target) {
+        CrossCompileAssistance::Configuration { cargo_env } => cargo_env,
+        CrossCompileAssistance::NoAssistance => vec![],
+        CrossCompileAssistance::HelpText(help_text) => Err(Error::CrossCompileConfiguration(
+            args.target.clone(),
+            help_text,
+        ))?,
+    };
+
+    let cargo_profile = if args.release {
+        CargoProfile::Release
+    } else {
+        CargoProfile::Dev
+    };
+
+    log!(buildpack_id = buildpack_id; "🏗️ Building {buildpack_id} for {}", args.target);
+
+    let buildpack_binaries = build_buildpack_binaries(
+        &buildpack_dir,
+        &cargo_metadata,
+        cargo_profile,
+        &cargo_env,
+        &args.target,
+    )
+    .map_err(|e| Error::BuildingBinaries(buildpack_dir.clone(), e))?;
+
+    let package_dir = get_package_dir(
+        cargo_metadata.target_directory.as_std_path(),
+        &buildpack_id,
+        args.release,
+    );
+
+    if package_dir.exists() {
+        std::fs::remove_dir_all(&package_dir)
+            .map_err(|e| Error::CleaningPackageDir(package_dir.clone(), e))?;
+    }
+
+    assemble_buildpack_directory(&package_dir, &buildpack_file.path, &buildpack_binaries)
+        .map_err(|e| Error::AssemblingBuildpack(package_dir.clone(), e))?;
+
+    log!(
+        buildpack_id = buildpack_id, path = package_dir;
+        "✅️ Packaged {buildpack_id}: {}",
+        package_dir.display()
+    );
+
+    actions::set_output("package_dir", package_dir.display().to_string())
+        .map_err(Error::SetActionOutput)?;
+
+    if let Some(docker_repository) = get_buildpack_docker_repository(&buildpack_file) {
+        actions::set_output(
+            "image_name",
+            format!("{docker_repository}:{buildpack_version}"),
+        )
+        .map_err(Error::SetActionOutput)?;
+    }
+
+    Ok(())
+}
+
+fn get_package_dir(target_dir: &Path, buildpack_id: &BuildpackId, release: bool) -> PathBuf {
+    target_dir
+        .join("buildpack")
+        .join(if release { "release" } else { "debug" })
+        .join(buildpack_id.replace('/', "_"))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::package_buildpack::command::get_package_dir;
+    use libcnb_data::buildpack_id;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_get_package_dir() {
+        assert_eq!(
+            get_package_dir(
+                Path::new("/repo/target"),
+                &buildpack_id!("heroku/nodejs"),
+                true
+            ),
+            PathBuf::from("/repo/target/buildpack/release/heroku_nodejs")
+        );
+        assert_eq!(
+            get_package_dir(
+                Path::new("/repo/target"),
+                &buildpack_id!("heroku/nodejs"),
+                false
+            ),
+            PathBuf::from("/repo/target/buildpack/debug/heroku_nodejs")
+        );
+    }
+}
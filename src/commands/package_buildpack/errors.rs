@@ -0,0 +1,104 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::buildpack::BuildpackFileError;
+use languages_github_actions::github::actions::SetOutputError;
+use libcnb_package::build::BuildBinariesError;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    GetCurrentDir(io::Error),
+    BuildpackFile(BuildpackFileError),
+    NotALibcnbBuildpack(PathBuf),
+    ReadingCargoMetadata(PathBuf, cargo_metadata::Error),
+    CrossCompileConfiguration(String, String),
+    BuildingBinaries(PathBuf, BuildBinariesError),
+    CleaningPackageDir(PathBuf, io::Error),
+    AssemblingBuildpack(PathBuf, io::Error),
+    SetActionOutput(SetOutputError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::GetCurrentDir(error) => {
+                write!(f, "Failed to get current directory\nError: {error}")
+            }
+
+            Error::BuildpackFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::NotALibcnbBuildpack(path) => {
+                write!(
+                    f,
+                    "Only libcnb.rs buildpacks can be packaged, no Cargo.toml found\nPath: {}",
+                    path.display()
+                )
+            }
+
+            Error::ReadingCargoMetadata(path, error) => {
+                write!(
+                    f,
+                    "Could not read Cargo metadata\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::CrossCompileConfiguration(target, help_text) => {
+                write!(
+                    f,
+                    "Could not configure cross-compilation for {target}\n{help_text}"
+                )
+            }
+
+            Error::BuildingBinaries(path, error) => {
+                write!(
+                    f,
+                    "Could not build buildpack binaries\nPath: {}\nError: {error:?}",
+                    path.display()
+                )
+            }
+
+            Error::CleaningPackageDir(path, error) => {
+                write!(
+                    f,
+                    "Could not remove previous package directory\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::AssemblingBuildpack(path, error) => {
+                write!(
+                    f,
+                    "Could not assemble packaged buildpack\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::SetActionOutput(set_output_error) => match set_output_error {
+                SetOutputError::Opening(error) | SetOutputError::Writing(error) => {
+                    write!(f, "Could not write action output\nError: {error}")
+                }
+            },
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::GetCurrentDir(_)
+            | Error::CleaningPackageDir(_, _)
+            | Error::AssemblingBuildpack(_, _) => ErrorCategory::Io,
+            Error::BuildpackFile(error) => error.category(),
+            Error::NotALibcnbBuildpack(_) | Error::CrossCompileConfiguration(_, _) => {
+                ErrorCategory::Config
+            }
+            Error::ReadingCargoMetadata(_, _) => ErrorCategory::Parse,
+            Error::BuildingBinaries(_, _) => ErrorCategory::Validation,
+            Error::SetActionOutput(error) => error.category(),
+        }
+    }
+}
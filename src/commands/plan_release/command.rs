@@ -0,0 +1,195 @@
+use crate::commands::plan_release::errors::Error;
+use clap::Parser;
+use languages_github_actions::buildpack::{
+    get_buildpack_docker_repository, get_buildpack_id, get_buildpack_version,
+    is_buildpack_release_disabled, read_buildpack_file, BuildpackDiscoveryArgs,
+};
+use languages_github_actions::cancellation::check_cancelled;
+use languages_github_actions::cnb_registry::get_published_versions;
+use languages_github_actions::github::actions;
+use languages_github_actions::github::actions::{LargeOutputArgs, LargeOutputError};
+use languages_github_actions::log;
+use languages_github_actions::oci::{image_exists, ImageReference};
+use serde::{Deserialize, Serialize};
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Compares each buildpack's version with the CNB registry and Docker Hub to plan which buildpacks need publishing", long_about = None)]
+pub(crate) struct PlanReleaseArgs {
+    #[arg(long)]
+    pub(crate) pretty: bool,
+    #[command(flatten)]
+    pub(crate) output: LargeOutputArgs,
+    #[command(flatten)]
+    pub(crate) discovery: BuildpackDiscoveryArgs,
+}
+
+// fields are serialized in declaration order so the plan JSON is stable between runs,
+// `image_published` is left out for buildpacks without a docker repository
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+pub(crate) struct PublishPlanEntry {
+    pub(crate) id: String,
+    pub(crate) version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) docker_repository: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) image_published: Option<bool>,
+    pub(crate) registry_published: bool,
+    pub(crate) publish: bool,
+}
+
+pub(crate) fn execute(args: PlanReleaseArgs) -> Result<()> {
+    let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
+
+    let buildpack_dirs = args
+        .discovery
+        .find_buildpack_dirs(&current_dir, &[current_dir.join("target")])
+        .map_err(|e| Error::FindingBuildpacks(current_dir.clone(), e))?;
+
+    let mut plan = vec![];
+    for dir in buildpack_dirs {
+        check_cancelled().map_err(Error::Cancelled)?;
+
+        let buildpack_file =
+            read_buildpack_file(dir.join("buildpack.toml")).map_err(Error::BuildpackFile)?;
+        if is_buildpack_release_disabled(&buildpack_file) {
+            log!(
+                path = buildpack_file.path;
+                "⏭️ Skipping disabled buildpack: {}",
+                buildpack_file.path.display()
+            );
+            continue;
+        }
+
+        let buildpack_id = get_buildpack_id(&buildpack_file).map_err(Error::BuildpackFile)?;
+        let version = get_buildpack_version(&buildpack_file)
+            .map_err(Error::BuildpackFile)?
+            .to_string();
+        let docker_repository = get_buildpack_docker_repository(&buildpack_file);
+
+        let published_versions =
+            get_published_versions(&buildpack_id).map_err(Error::QueryingRegistry)?;
+        let image_published = match &docker_repository {
+            Some(repository) => {
+                let uri = format!("docker://{repository}:{version}");
+                let image = ImageReference::try_from(uri.as_str())
+                    .map_err(|e| Error::QueryingImage(uri.clone(), e))?;
+                Some(image_exists(&image).map_err(|e| Error::QueryingImage(uri, e))?)
+            }
+            None => None,
+        };
+
+        let entry = create_plan_entry(
+            buildpack_id.to_string(),
+            version,
+            docker_repository,
+            image_published,
+            &published_versions,
+        );
+        if entry.publish {
+            log!("📦 {} {} needs publishing", entry.id, entry.version);
+        } else {
+            log!("⏭️ {} {} is already published", entry.id, entry.version);
+        }
+        plan.push(entry);
+    }
+
+    plan.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let json = if args.pretty {
+        serde_json::to_string_pretty(&plan)
+    } else {
+        serde_json::to_string(&plan)
+    }
+    .map_err(Error::SerializingJson)?;
+
+    args.output
+        .set_output("plan", json)
+        .map_err(Error::SetActionOutput)?;
+
+    let publish = plan.iter().any(|entry| entry.publish);
+    actions::set_output("publish", publish.to_string())
+        .map_err(|e| Error::SetActionOutput(LargeOutputError::SetOutput(e)))?;
+
+    Ok(())
+}
+
+// a version only counts as published once it's in the CNB registry and, when the buildpack has a
+// docker repository, its image has been pushed, a partially published version is published again
+fn create_plan_entry(
+    id: String,
+    version: String,
+    docker_repository: Option<String>,
+    image_published: Option<bool>,
+    published_versions: &[String],
+) -> PublishPlanEntry {
+    let registry_published = published_versions.contains(&version);
+    PublishPlanEntry {
+        publish: !registry_published || image_published == Some(false),
+        id,
+        version,
+        docker_repository,
+        image_published,
+        registry_published,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::plan_release::command::{create_plan_entry, PublishPlanEntry};
+
+    #[test]
+    fn test_create_plan_entry() {
+        let published_versions = vec!["1.0.0".to_string(), "1.0.1".to_string()];
+
+        assert_eq!(
+            create_plan_entry(
+                "heroku/nodejs".to_string(),
+                "1.0.1".to_string(),
+                Some("docker.io/heroku/buildpack-nodejs".to_string()),
+                Some(true),
+                &published_versions
+            ),
+            PublishPlanEntry {
+                id: "heroku/nodejs".to_string(),
+                version: "1.0.1".to_string(),
+                docker_repository: Some("docker.io/heroku/buildpack-nodejs".to_string()),
+                image_published: Some(true),
+                registry_published: true,
+                publish: false,
+            }
+        );
+        assert!(
+            create_plan_entry(
+                "heroku/nodejs".to_string(),
+                "1.0.1".to_string(),
+                Some("docker.io/heroku/buildpack-nodejs".to_string()),
+                Some(false),
+                &published_versions
+            )
+            .publish
+        );
+        assert!(
+            create_plan_entry(
+                "heroku/nodejs".to_string(),
+                "1.1.0".to_string(),
+                None,
+                None,
+                &published_versions
+            )
+            .publish
+        );
+        assert_eq!(
+            serde_json::to_string(&create_plan_entry(
+                "heroku/procfile".to_string(),
+                "1.0.0".to_string(),
+                None,
+                None,
+                &published_versions
+            ))
+            .unwrap(),
+            r#"{"id":"heroku/procfile","version":"1.0.0","registry_published":true,"publish":false}"#
+        );
+    }
+}
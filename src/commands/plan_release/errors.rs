@@ -0,0 +1,83 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::buildpack::{BuildpackDiscoveryError, BuildpackFileError};
+use languages_github_actions::cancellation::CancellationError;
+use languages_github_actions::cnb_registry::CnbRegistryError;
+use languages_github_actions::github::actions::LargeOutputError;
+use languages_github_actions::oci::OciError;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    GetCurrentDir(std::io::Error),
+    FindingBuildpacks(PathBuf, BuildpackDiscoveryError),
+    BuildpackFile(BuildpackFileError),
+    QueryingRegistry(CnbRegistryError),
+    QueryingImage(String, OciError),
+    SerializingJson(serde_json::Error),
+    SetActionOutput(LargeOutputError),
+    Cancelled(CancellationError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::GetCurrentDir(error) => {
+                write!(f, "Failed to get current directory\nError: {error}")
+            }
+
+            Error::FindingBuildpacks(path, error) => {
+                write!(
+                    f,
+                    "Could not find buildpacks\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::BuildpackFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::QueryingRegistry(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::QueryingImage(uri, error) => {
+                write!(
+                    f,
+                    "Could not check if the buildpack image exists\nValue: {uri}\nError: {error}"
+                )
+            }
+
+            Error::SerializingJson(error) => {
+                write!(
+                    f,
+                    "Could not serialize the publish plan into json\nError: {error}"
+                )
+            }
+
+            Error::SetActionOutput(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::Cancelled(error) => {
+                write!(f, "{error}")
+            }
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::GetCurrentDir(_) => ErrorCategory::Io,
+            Error::FindingBuildpacks(_, error) => error.category(),
+            Error::BuildpackFile(error) => error.category(),
+            Error::QueryingRegistry(error) => error.category(),
+            Error::QueryingImage(_, error) => error.category(),
+            Error::SerializingJson(_) => ErrorCategory::Parse,
+            Error::SetActionOutput(error) => error.category(),
+            Error::Cancelled(error) => error.category(),
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use crate::commands::post_pr_comment::errors::Error;
+use clap::Parser;
+use languages_github_actions::github::actions;
+use languages_github_actions::github::api::{
+    create_issue_comment, list_issue_comments, update_issue_comment, IssueComment,
+};
+use languages_github_actions::github::auth::GitHubAuthArgs;
+use languages_github_actions::github::context::GitHubContext;
+use languages_github_actions::log;
+use std::io::Read;
+use std::path::PathBuf;
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Creates or updates a comment on a pull request, identified by a hidden marker", long_about = None)]
+pub(crate) struct PostPrCommentArgs {
+    #[arg(long)]
+    pub(crate) body_file: Option<PathBuf>,
+    #[arg(long, default_value = "languages-github-actions")]
+    pub(crate) marker: String,
+    #[arg(long)]
+    pub(crate) repository: Option<String>,
+    #[arg(long)]
+    pub(crate) pull_request: Option<u64>,
+    #[command(flatten)]
+    pub(crate) auth: GitHubAuthArgs,
+}
+
+pub(crate) fn execute(args: PostPrCommentArgs) -> Result<()> {
+    let context = GitHubContext::from_env().map_err(Error::GitHubContext)?;
+
+    let repository = args
+        .repository
+        .or_else(|| context.repository.clone())
+        .ok_or_else(|| missing_argument("--repository", "GITHUB_REPOSITORY"))?;
+
+    let pull_request = args
+        .pull_request
+        .or_else(|| context.pull_request_number())
+        .ok_or_else(|| missing_argument("--pull-request", "the pull request event"))?;
+
+    let body = read_body(args.body_file)?;
+    let body = format!("{}\n{}", marker_comment(&args.marker), body.trim_end());
+
+    let token = args
+        .auth
+        .resolve_token(&repository)
+        .map_err(Error::Authenticating)?;
+
+    let comments =
+        list_issue_comments(&token, &repository, pull_request).map_err(Error::ListingComments)?;
+
+    let comment = match find_marked_comment(&comments, &args.marker) {
+        Some(existing) => {
+            let comment = update_issue_comment(&token, &repository, existing.id, &body)
+                .map_err(Error::UpdatingComment)?;
+            log!(
+                "✅️ Updated comment on #{pull_request}: {}",
+                comment.html_url
+            );
+            comment
+        }
+        None => {
+            let comment = create_issue_comment(&token, &repository, pull_request, &body)
+                .map_err(Error::CreatingComment)?;
+            log!(
+                "✅️ Created comment on #{pull_request}: {}",
+                comment.html_url
+            );
+            comment
+        }
+    };
+
+    actions::set_output("comment_id", comment.id.to_string()).map_err(Error::SetActionOutput)?;
+    actions::set_output("comment_url", comment.html_url).map_err(Error::SetActionOutput)?;
+
+    Ok(())
+}
+
+fn missing_argument(argument: &str, source: &str) -> Error {
+    Error::MissingArgument(argument.to_string(), source.to_string())
+}
+
+// the body is read from stdin when no file is given so reports can be piped in directly
+fn read_body(body_file: Option<PathBuf>) -> Result<String> {
+    let body = match body_file {
+        Some(path) if path.as_os_str() != "-" => {
+            std::fs::read_to_string(&path).map_err(|e| Error::ReadingBody(path, e))?
+        }
+        _ => {
+            let mut body = String::new();
+            std::io::stdin()
+                .read_to_string(&mut body)
+                .map_err(|e| Error::ReadingBody(PathBuf::from("-"), e))?;
+            body
+        }
+    };
+    if body.trim().is_empty() {
+        Err(Error::EmptyBody)?;
+    }
+    Ok(body)
+}
+
+fn marker_comment(marker: &str) -> String {
+    format!("<!-- {marker} -->")
+}
+
+fn find_marked_comment<'a>(comments: &'a [IssueComment], marker: &str) -> Option<&'a IssueComment> {
+    let marker = marker_comment(marker);
+    comments.iter().find(|comment| {
+        comment
+            .body
+            .as_deref()
+            .filter(|body| body.lines().any(|line| line.trim() == marker))
+            .is_some()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::post_pr_comment::command::find_marked_comment;
+    use languages_github_actions::github::api::IssueComment;
+
+    #[test]
+    fn test_find_marked_comment() {
+        let comment = |id: u64, body: Option<&str>| IssueComment {
+            id,
+            body: body.map(String::from),
+            html_url: format!(
+                "https://github.com/heroku/buildpacks-nodejs/pull/1#issuecomment-{id}"
+            ),
+        };
+        let comments = vec![
+            comment(1, None),
+            comment(2, Some("Mentions <!-- release-impact --> inline")),
+            comment(3, Some("<!-- changelog-preview -->\n# Changelog")),
+            comment(4, Some("<!-- release-impact -->\n## Release impact")),
+        ];
+
+        assert_eq!(
+            find_marked_comment(&comments, "release-impact").map(|comment| comment.id),
+            Some(4)
+        );
+        assert_eq!(
+            find_marked_comment(&comments, "changelog-preview").map(|comment| comment.id),
+            Some(3)
+        );
+        assert_eq!(find_marked_comment(&comments, "missing"), None);
+    }
+}
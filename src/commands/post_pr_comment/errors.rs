@@ -0,0 +1,87 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::github::actions::SetOutputError;
+use languages_github_actions::github::api::GitHubApiError;
+use languages_github_actions::github::auth::AuthError;
+use languages_github_actions::github::context::GitHubContextError;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    GitHubContext(GitHubContextError),
+    MissingArgument(String, String),
+    ReadingBody(PathBuf, io::Error),
+    EmptyBody,
+    Authenticating(AuthError),
+    ListingComments(GitHubApiError),
+    CreatingComment(GitHubApiError),
+    UpdatingComment(GitHubApiError),
+    SetActionOutput(SetOutputError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::GitHubContext(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::MissingArgument(argument, source) => {
+                write!(
+                    f,
+                    "The {argument} argument is required when it can't be read from {source}"
+                )
+            }
+
+            Error::ReadingBody(path, error) => {
+                write!(
+                    f,
+                    "Could not read comment body\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::EmptyBody => {
+                write!(f, "The comment body is empty")
+            }
+
+            Error::Authenticating(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::ListingComments(error) => {
+                write!(f, "Could not list pull request comments\nError: {error}")
+            }
+
+            Error::CreatingComment(error) => {
+                write!(f, "Could not create pull request comment\nError: {error}")
+            }
+
+            Error::UpdatingComment(error) => {
+                write!(f, "Could not update pull request comment\nError: {error}")
+            }
+
+            Error::SetActionOutput(set_output_error) => match set_output_error {
+                SetOutputError::Opening(error) | SetOutputError::Writing(error) => {
+                    write!(f, "Could not write action output\nError: {error}")
+                }
+            },
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::GitHubContext(error) => error.category(),
+            Error::MissingArgument(_, _) | Error::EmptyBody => ErrorCategory::Config,
+            Error::ReadingBody(_, _) => ErrorCategory::Io,
+            Error::Authenticating(error) => error.category(),
+            Error::ListingComments(error)
+            | Error::CreatingComment(error)
+            | Error::UpdatingComment(error) => error.category(),
+            Error::SetActionOutput(error) => error.category(),
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use crate::commands::post_release_announcement::errors::Error;
+use chrono::NaiveDate;
+use clap::Parser;
+use languages_github_actions::changelog::resolve_release_date;
+use languages_github_actions::clock::SystemClock;
+use languages_github_actions::github::actions;
+use languages_github_actions::github::api::{
+    create_or_find_discussion, create_or_find_issue, get_discussion_categories,
+};
+use languages_github_actions::github::auth::GitHubAuthArgs;
+use languages_github_actions::github::compare::{
+    compare_url, is_per_buildpack_tag_template, is_valid_tag_template, DEFAULT_TAG_TEMPLATE,
+};
+use languages_github_actions::github::context::GitHubContext;
+use languages_github_actions::log;
+use std::io::Read;
+use std::path::PathBuf;
+
+type Result<T> = std::result::Result<T, Error>;
+
+const DEFAULT_TITLE_TEMPLATE: &str = "Release v{{ version }}";
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Publishes the release notes of a release as a GitHub Discussion or issue", long_about = None, disable_version_flag = true)]
+pub(crate) struct PostReleaseAnnouncementArgs {
+    #[arg(long)]
+    pub(crate) version: String,
+    #[arg(long)]
+    pub(crate) previous_version: Option<String>,
+    #[arg(long)]
+    pub(crate) date: Option<NaiveDate>,
+    #[arg(long)]
+    pub(crate) notes_file: Option<PathBuf>,
+    #[arg(long, default_value = DEFAULT_TITLE_TEMPLATE)]
+    pub(crate) title_template: String,
+    #[arg(long, default_value = DEFAULT_TAG_TEMPLATE)]
+    pub(crate) tag_template: String,
+    #[arg(long)]
+    pub(crate) repository: Option<String>,
+    #[arg(long, default_value = "Announcements", conflicts_with = "issue")]
+    pub(crate) category: String,
+    #[arg(long)]
+    pub(crate) issue: bool,
+    #[command(flatten)]
+    pub(crate) auth: GitHubAuthArgs,
+}
+
+pub(crate) fn execute(args: PostReleaseAnnouncementArgs) -> Result<()> {
+    let context = GitHubContext::from_env().map_err(Error::GitHubContext)?;
+
+    let repository = args
+        .repository
+        .or_else(|| context.repository.clone())
+        .ok_or_else(|| {
+            Error::MissingArgument("--repository".to_string(), "GITHUB_REPOSITORY".to_string())
+        })?;
+
+    // the announcement is for the whole repository so there's no buildpack id to put in a tag
+    if !is_valid_tag_template(&args.tag_template)
+        || is_per_buildpack_tag_template(&args.tag_template)
+    {
+        Err(Error::InvalidTagTemplate(args.tag_template.clone()))?;
+    }
+
+    let notes = read_notes(args.notes_file)?;
+    let date = resolve_release_date(args.date, &SystemClock).map_err(Error::InvalidDate)?;
+    let tag = render_tag(&args.tag_template, &args.version);
+    let compare_link = args.previous_version.as_ref().map(|previous_version| {
+        let previous_tag = render_tag(&args.tag_template, previous_version);
+        CompareLink {
+            url: compare_url(
+                &format!("{}/{repository}", context.server_url),
+                &previous_tag,
+                &tag,
+            ),
+            previous_tag,
+        }
+    });
+
+    let title = render_title(
+        &args.title_template,
+        &args.version,
+        args.previous_version.as_deref(),
+        &date,
+    )?;
+    let body = render_announcement(&tag, &date, &notes, compare_link.as_ref());
+
+    let token = args
+        .auth
+        .resolve_token(&repository)
+        .map_err(Error::Authenticating)?;
+
+    let (number, url) = if args.issue {
+        let issue = create_or_find_issue(&token, &repository, &title, &body)
+            .map_err(Error::CreatingIssue)?;
+        (issue.number, issue.html_url)
+    } else {
+        let categories =
+            get_discussion_categories(&token, &repository).map_err(Error::GettingCategories)?;
+        let category = categories.find(&args.category).ok_or_else(|| {
+            Error::UnknownCategory(
+                args.category.clone(),
+                categories
+                    .categories
+                    .iter()
+                    .map(|category| category.name.clone())
+                    .collect(),
+            )
+        })?;
+        let discussion = create_or_find_discussion(
+            &token,
+            &repository,
+            &categories.repository_id,
+            category,
+            &title,
+            &body,
+        )
+        .map_err(Error::CreatingDiscussion)?;
+        (discussion.number, discussion.url)
+    };
+
+    log!("✅️ Posted release announcement: {url}");
+
+    actions::set_output("number", number.to_string()).map_err(Error::SetActionOutput)?;
+    actions::set_output("url", url).map_err(Error::SetActionOutput)?;
+
+    Ok(())
+}
+
+struct CompareLink {
+    previous_tag: String,
+    url: String,
+}
+
+// the notes are read from stdin when no file is given so the output of generate-changelog can be
+// piped in directly
+fn read_notes(notes_file: Option<PathBuf>) -> Result<String> {
+    let notes = match notes_file {
+        Some(path) if path.as_os_str() != "-" => {
+            std::fs::read_to_string(&path).map_err(|e| Error::ReadingNotes(path, e))?
+        }
+        _ => {
+            let mut notes = String::new();
+            std::io::stdin()
+                .read_to_string(&mut notes)
+                .map_err(|e| Error::ReadingNotes(PathBuf::from("-"), e))?;
+            notes
+        }
+    };
+    if notes.trim().is_empty() {
+        Err(Error::EmptyNotes)?;
+    }
+    Ok(notes)
+}
+
+fn render_tag(tag_template: &str, version: &str) -> String {
+    tag_template.replace("{version}", version)
+}
+
+fn render_title(
+    title_template: &str,
+    version: &str,
+    previous_version: Option<&str>,
+    date: &NaiveDate,
+) -> Result<String> {
+    let mut context = tera::Context::new();
+    context.insert("version", version);
+    context.insert("previous_version", &previous_version);
+    context.insert("date", &date.to_string());
+    tera::Tera::one_off(title_template, &context, false)
+        .map(|rendered| rendered.trim().to_string())
+        .map_err(Error::RenderingTemplate)
+}
+
+fn render_announcement(
+    tag: &str,
+    date: &NaiveDate,
+    notes: &str,
+    compare_link: Option<&CompareLink>,
+) -> String {
+    let mut announcement = format!("## {tag} ({date})\n\n{}\n", notes.trim());
+    if let Some(compare_link) = compare_link {
+        announcement.push_str(&format!(
+            "\n**Full changelog:** [{}...{tag}]({})\n",
+            compare_link.previous_tag, compare_link.url
+        ));
+    }
+    announcement
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::post_release_announcement::command::{
+        render_announcement, render_title, CompareLink,
+    };
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_render_announcement() {
+        let date = NaiveDate::from_ymd_opt(2023, 5, 10).unwrap();
+        let notes = "## heroku/nodejs\n\n- Added Node.js 20.\n\n";
+
+        assert_eq!(
+            render_announcement(
+                "v1.1.0",
+                &date,
+                notes,
+                Some(&CompareLink {
+                    previous_tag: "v1.0.0".to_string(),
+                    url: "https://github.com/heroku/buildpacks-nodejs/compare/v1.0.0...v1.1.0"
+                        .to_string()
+                })
+            ),
+            "## v1.1.0 (2023-05-10)\n\n## heroku/nodejs\n\n- Added Node.js 20.\n\n**Full changelog:** [v1.0.0...v1.1.0](https://github.com/heroku/buildpacks-nodejs/compare/v1.0.0...v1.1.0)\n"
+        );
+        assert_eq!(
+            render_announcement("v1.1.0", &date, notes, None),
+            "## v1.1.0 (2023-05-10)\n\n## heroku/nodejs\n\n- Added Node.js 20.\n"
+        );
+
+        assert_eq!(
+            render_title(
+                "Node.js buildpacks v{{ version }}{% if previous_version %} (from v{{ previous_version }}){% endif %}",
+                "1.1.0",
+                Some("1.0.0"),
+                &date
+            )
+            .unwrap(),
+            "Node.js buildpacks v1.1.0 (from v1.0.0)"
+        );
+        assert!(render_title("Release {{ missing }}", "1.1.0", None, &date).is_err());
+    }
+}
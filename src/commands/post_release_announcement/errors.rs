@@ -0,0 +1,127 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::changelog::ChangelogError;
+use languages_github_actions::github::actions::SetOutputError;
+use languages_github_actions::github::api::GitHubApiError;
+use languages_github_actions::github::auth::AuthError;
+use languages_github_actions::github::context::GitHubContextError;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    GitHubContext(GitHubContextError),
+    MissingArgument(String, String),
+    InvalidTagTemplate(String),
+    ReadingNotes(PathBuf, io::Error),
+    EmptyNotes,
+    InvalidDate(ChangelogError),
+    RenderingTemplate(tera::Error),
+    Authenticating(AuthError),
+    GettingCategories(GitHubApiError),
+    UnknownCategory(String, Vec<String>),
+    CreatingDiscussion(GitHubApiError),
+    CreatingIssue(GitHubApiError),
+    SetActionOutput(SetOutputError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::GitHubContext(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::MissingArgument(argument, source) => {
+                write!(
+                    f,
+                    "The {argument} argument is required when it can't be read from {source}"
+                )
+            }
+
+            Error::InvalidTagTemplate(template) => {
+                write!(
+                    f,
+                    "Invalid tag template `{template}`, it must contain a {{version}} placeholder and no {{buildpack_id}} placeholder"
+                )
+            }
+
+            Error::ReadingNotes(path, error) => {
+                write!(
+                    f,
+                    "Could not read release notes\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::EmptyNotes => {
+                write!(f, "The release notes are empty")
+            }
+
+            Error::RenderingTemplate(error) => {
+                // tera keeps the details of parse and render failures in the error's sources
+                write!(f, "Could not render `title_template`\nError: {error}")?;
+                let mut source = std::error::Error::source(error);
+                while let Some(error) = source {
+                    write!(f, "\n{error}")?;
+                    source = error.source();
+                }
+                Ok(())
+            }
+
+            Error::Authenticating(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::GettingCategories(error) => {
+                write!(f, "Could not get discussion categories\nError: {error}")
+            }
+
+            Error::UnknownCategory(category, categories) => {
+                write!(
+                    f,
+                    "Unknown discussion category `{category}`, the repository has: {}",
+                    categories.join(", ")
+                )
+            }
+
+            Error::InvalidDate(error) => {
+                write!(f, "Could not determine the release date\nError: {error}")
+            }
+
+            Error::CreatingDiscussion(error) => {
+                write!(f, "Could not create discussion\nError: {error}")
+            }
+
+            Error::CreatingIssue(error) => {
+                write!(f, "Could not create issue\nError: {error}")
+            }
+
+            Error::SetActionOutput(set_output_error) => match set_output_error {
+                SetOutputError::Opening(error) | SetOutputError::Writing(error) => {
+                    write!(f, "Could not write action output\nError: {error}")
+                }
+            },
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::GitHubContext(error) => error.category(),
+            Error::MissingArgument(_, _)
+            | Error::InvalidTagTemplate(_)
+            | Error::EmptyNotes
+            | Error::RenderingTemplate(_)
+            | Error::UnknownCategory(_, _) => ErrorCategory::Config,
+            Error::InvalidDate(error) => error.category(),
+            Error::ReadingNotes(_, _) => ErrorCategory::Io,
+            Error::Authenticating(error) => error.category(),
+            Error::GettingCategories(error)
+            | Error::CreatingDiscussion(error)
+            | Error::CreatingIssue(error) => error.category(),
+            Error::SetActionOutput(error) => error.category(),
+        }
+    }
+}
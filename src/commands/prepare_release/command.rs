@@ -1,20 +1,54 @@
-use crate::changelog::{generate_release_declarations, Changelog, ReleaseEntry};
 use crate::commands::prepare_release::errors::Error;
-use crate::github::actions;
 use chrono::{DateTime, Utc};
-use clap::{Parser, ValueEnum};
-use indexmap::IndexMap;
+use clap::Parser;
+use languages_github_actions::buildpack::{
+    get_buildpack_dependency_ids, get_buildpack_docker_repository, get_buildpack_id,
+    get_buildpack_version, is_buildpack_release_disabled, read_buildpack_file,
+    sort_buildpacks_by_dependencies, sync_buildpack_order_versions,
+    update_buildpack_contents_with_new_version, update_package_dependency_uris,
+    BuildpackDiscoveryArgs, BuildpackFile,
+};
+use languages_github_actions::cancellation::{check_cancelled, CancellationError};
+use languages_github_actions::changelog::{
+    generate_release_declarations, insert_root_changelog_release,
+    promote_changelog_unreleased_to_version, render_root_changelog_release, Changelog,
+    ChangelogLocationArgs, ReleaseDateArgs, ReleaseDateFormat,
+};
+use languages_github_actions::clock::{Clock, SystemClock};
+use languages_github_actions::git::{get_commit_sha, WorktreeGuardArgs};
+use languages_github_actions::github::actions;
+use languages_github_actions::github::actions::{AnnotationProperties, OutputFormat};
+use languages_github_actions::github::compare::{
+    compare_url, is_per_buildpack_tag_template, is_valid_tag_template, render_tag_name,
+    DEFAULT_TAG_TEMPLATE,
+};
+use languages_github_actions::log;
+use languages_github_actions::release_manifest::{ReleaseManifest, ReleasedBuildpack};
+use languages_github_actions::release_state::{
+    find_release_state_drift, get_release_records, read_release_state_file, set_release_record,
+    ReleaseRecord, ReleaseStateDrift, RELEASE_STATE_FILE,
+};
+use languages_github_actions::version_locations::{
+    get_version_locations, update_version_locations,
+};
+use languages_github_actions::versioning::{copy_version, BumpCoordinate, Scheme};
+use languages_github_actions::write::{with_rollback, WrittenFiles};
 use libcnb_data::buildpack::{BuildpackId, BuildpackVersion};
-use libcnb_package::find_buildpack_dirs;
-use std::collections::{HashMap, HashSet};
-use std::fs::write;
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
-use std::str::FromStr;
-use toml_edit::{value, ArrayOfTables, Document, Table};
+use toml_edit::Document;
 use uriparse::URI;
 
 type Result<T> = std::result::Result<T, Error>;
 
+const DEFAULT_COMMIT_MESSAGE_TEMPLATE: &str = "Prepare release v{{ version }}";
+
+const DEFAULT_PR_TITLE_TEMPLATE: &str = "Prepare release v{{ version }}";
+
+const PACKAGE_FILE: &str = "package.toml";
+
 #[derive(Parser, Debug)]
 #[command(author, version, about = "Bumps the version of each detected buildpack and adds an entry for any unreleased changes from the changelog", long_about = None)]
 pub(crate) struct PrepareReleaseArgs {
@@ -22,30 +56,97 @@ pub(crate) struct PrepareReleaseArgs {
     pub(crate) bump: BumpCoordinate,
     #[arg(long)]
     pub(crate) repository_url: Option<String>,
+    #[arg(long, value_enum, default_value_t)]
+    pub(crate) scheme: Scheme,
+    #[arg(long, conflicts_with = "exclude")]
+    pub(crate) only: Vec<BuildpackId>,
+    #[arg(long)]
+    pub(crate) exclude: Vec<BuildpackId>,
+    #[arg(long, value_enum, default_value_t)]
+    pub(crate) output_format: OutputFormat,
+    #[arg(long)]
+    pub(crate) print_manifest: bool,
+    #[arg(long, default_value = DEFAULT_TAG_TEMPLATE)]
+    pub(crate) tag_template: String,
+    #[arg(long)]
+    pub(crate) normalize_entries: bool,
+    #[arg(long)]
+    pub(crate) heading_anchor_template: Option<String>,
+    #[arg(long, value_enum, default_value_t)]
+    pub(crate) date_format: ReleaseDateFormat,
+    #[arg(long)]
+    pub(crate) root_changelog: Option<PathBuf>,
+    #[arg(long)]
+    pub(crate) version_file: Option<PathBuf>,
+    #[arg(long)]
+    pub(crate) force: bool,
+    #[arg(long, default_value = DEFAULT_COMMIT_MESSAGE_TEMPLATE)]
+    pub(crate) commit_message_template: String,
+    #[arg(long, default_value = DEFAULT_PR_TITLE_TEMPLATE)]
+    pub(crate) pr_title_template: String,
+    #[command(flatten)]
+    pub(crate) worktree: WorktreeGuardArgs,
+    #[command(flatten)]
+    pub(crate) discovery: BuildpackDiscoveryArgs,
+    #[command(flatten)]
+    pub(crate) changelog: ChangelogLocationArgs,
+    #[command(flatten)]
+    pub(crate) release_date: ReleaseDateArgs,
 }
 
-#[derive(ValueEnum, Debug, Clone)]
-pub(crate) enum BumpCoordinate {
-    Major,
-    Minor,
-    Patch,
+// the release is planned entirely in memory so callers like `run-release` can validate it before anything is written
+pub(crate) struct ReleasePlan {
+    pub(crate) current_dir: PathBuf,
+    pub(crate) from_version: BuildpackVersion,
+    pub(crate) to_version: BuildpackVersion,
+    pub(crate) release_version: String,
+    pub(crate) buildpacks: Vec<BuildpackRelease>,
+    pub(crate) version_locations: BTreeMap<PathBuf, String>,
+    pub(crate) compare_links: Option<BTreeMap<String, String>>,
+    pub(crate) compare_url: Option<String>,
+    pub(crate) root_changelog: Option<(PathBuf, String)>,
+    pub(crate) release_state: Option<(PathBuf, String)>,
+    pub(crate) version_file: Option<(PathBuf, String)>,
+    pub(crate) commit_message: String,
+    pub(crate) pr_title: String,
 }
 
-struct BuildpackFile {
-    path: PathBuf,
-    document: Document,
+pub(crate) struct BuildpackRelease {
+    pub(crate) buildpack_id: BuildpackId,
+    pub(crate) buildpack_file: BuildpackFile,
+    pub(crate) buildpack_contents: String,
+    pub(crate) package_file: Option<(PathBuf, String)>,
+    pub(crate) previous_changelog: Changelog,
+    pub(crate) changelog_path: PathBuf,
+    pub(crate) changelog: Changelog,
+    pub(crate) changelog_contents: String,
 }
 
-struct ChangelogFile {
-    path: PathBuf,
-    changelog: Changelog,
+pub(crate) fn execute(args: PrepareReleaseArgs) -> Result<()> {
+    execute_with_clock(args, &SystemClock)
 }
 
-pub(crate) fn execute(args: PrepareReleaseArgs) -> Result<()> {
+pub(crate) fn execute_with_clock(args: PrepareReleaseArgs, clock: &dyn Clock) -> Result<()> {
+    let plan = plan_release(&args, clock)?;
+    let modified_files = write_release(&plan)?;
+    set_release_outputs(&plan, &modified_files, &args.output_format)?;
+
+    // printed after the outputs so it's the last JSON line for `update-builder --manifest -` to read
+    if args.print_manifest {
+        println!("{}", release_manifest(&plan).to_json());
+    }
+
+    Ok(())
+}
+
+pub(crate) fn plan_release(args: &PrepareReleaseArgs, clock: &dyn Clock) -> Result<ReleasePlan> {
     let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
 
+    args.worktree.check(&current_dir).map_err(Error::Git)?;
+
     let repository_url = args
         .repository_url
+        .as_ref()
         .map(|url| {
             URI::try_from(url.as_str())
                 .map(|uri| uri.into_owned())
@@ -53,162 +154,782 @@ pub(crate) fn execute(args: PrepareReleaseArgs) -> Result<()> {
         })
         .transpose()?;
 
-    let buildpack_dirs = find_buildpack_dirs(&current_dir, &[current_dir.join("target")])
+    if !is_valid_tag_template(&args.tag_template) {
+        Err(Error::InvalidTagTemplate(args.tag_template.clone()))?;
+    }
+
+    let buildpack_dirs = args
+        .discovery
+        .find_buildpack_dirs(&current_dir, &[current_dir.join("target")])
         .map_err(|e| Error::FindingBuildpacks(current_dir.clone(), e))?;
 
     if buildpack_dirs.is_empty() {
-        Err(Error::NoBuildpacksFound(current_dir))?;
+        Err(Error::NoBuildpacksFound(current_dir.clone()))?;
     }
 
     let buildpack_files = buildpack_dirs
         .iter()
-        .map(|dir| read_buildpack_file(dir.join("buildpack.toml")))
-        .collect::<Result<Vec<_>>>()?;
+        .map(|dir| read_buildpack_file(dir.join("buildpack.toml")).map_err(Error::BuildpackFile))
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .filter(|buildpack_file| {
+            let disabled = is_buildpack_release_disabled(buildpack_file);
+            if disabled {
+                log!(
+                    path = buildpack_file.path;
+                    "⏭️ Skipping disabled buildpack: {}",
+                    buildpack_file.path.display()
+                );
+            }
+            !disabled
+        })
+        .collect::<Vec<_>>();
+
+    let buildpack_files = select_buildpack_files(buildpack_files, &args.only, &args.exclude)?;
 
-    let changelog_files = buildpack_dirs
+    if buildpack_files.is_empty() {
+        Err(Error::NoBuildpacksSelected)?;
+    }
+
+    let changelog_files = buildpack_files
         .iter()
-        .map(|dir| read_changelog_file(dir.join("CHANGELOG.md")))
+        .map(|buildpack_file| {
+            args.changelog
+                .read_changelog_file_from_dir(
+                    buildpack_file.path.parent().unwrap_or(&current_dir),
+                    &current_dir,
+                )
+                .map_err(Error::ChangelogFile)
+        })
         .collect::<Result<Vec<_>>>()?;
 
     let updated_buildpack_ids = buildpack_files
         .iter()
-        .map(get_buildpack_id)
+        .map(|buildpack_file| get_buildpack_id(buildpack_file).map_err(Error::BuildpackFile))
+        .collect::<Result<Vec<_>>>()?;
+
+    let version_file = args
+        .version_file
+        .as_ref()
+        .map(|path| {
+            let path = current_dir.join(path);
+            std::fs::read_to_string(&path)
+                .map_err(|e| Error::ReadingVersionFile(path.clone(), e))
+                .map(|contents| (path, contents))
+        })
+        .transpose()?;
+
+    let current_version = match &version_file {
+        Some((path, contents)) => {
+            parse_version_file(contents).ok_or_else(|| Error::InvalidVersionFile(path.clone()))?
+        }
+        None => get_fixed_version(&buildpack_files)?,
+    };
+
+    let now = args
+        .release_date
+        .release_date(clock)
+        .map_err(Error::InvalidReleaseDate)?;
+
+    let version_scheme = args.scheme.version_scheme();
+
+    let next_version = version_scheme
+        .next_version(&current_version, &args.bump, &now)
+        .map_err(Error::InvalidNextVersion)?;
+
+    let release_version = version_scheme.format_version(&next_version);
+
+    let (commit_message, pr_title) = render_release_messages(
+        &args.commit_message_template,
+        &args.pr_title_template,
+        &release_version,
+        &current_version,
+        &updated_buildpack_ids,
+    )?;
+
+    // the release state is only maintained once the file exists (e.g.; after it was created with
+    // `show-release-state --repair`)
+    let mut release_state_file = read_release_state_file(current_dir.join(RELEASE_STATE_FILE))
+        .map_err(Error::ReleaseState)?;
+    let release_records = release_state_file
+        .as_ref()
+        .map(get_release_records)
+        .transpose()
+        .map_err(Error::ReleaseState)?
+        .unwrap_or_default();
+
+    let buildpack_versions = updated_buildpack_ids
+        .iter()
+        .map(|buildpack_id| (buildpack_id.to_string(), current_version.to_string()))
+        .collect();
+    for drift in find_release_state_drift(&release_records, &buildpack_versions) {
+        if let ReleaseStateDrift::VersionMismatch { .. } = drift {
+            log!("⚠️ Release state is out of date, {drift}");
+        }
+    }
+
+    // composites can include other composites so they're updated after everything they include,
+    // package.toml dependencies can reference a released buildpack by its docker repository
+    let dependency_ids = buildpack_files
+        .iter()
+        .zip(&updated_buildpack_ids)
+        .map(|(buildpack_file, buildpack_id)| {
+            get_buildpack_dependency_ids(buildpack_file)
+                .map(|dependency_ids| (buildpack_id.clone(), dependency_ids))
+                .map_err(Error::BuildpackFile)
+        })
         .collect::<Result<Vec<_>>>()?;
+    let release_order =
+        sort_buildpacks_by_dependencies(&dependency_ids).map_err(Error::DependencyCycle)?;
+    let released_buildpacks = buildpack_files
+        .iter()
+        .zip(&updated_buildpack_ids)
+        .map(|(buildpack_file, buildpack_id)| {
+            (
+                buildpack_id.clone(),
+                get_buildpack_docker_repository(buildpack_file),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut unordered_releases = buildpack_files
+        .into_iter()
+        .zip(changelog_files)
+        .zip(&updated_buildpack_ids)
+        .map(Some)
+        .collect::<Vec<_>>();
+    let ordered_releases = release_order
+        .into_iter()
+        .filter_map(|index| unordered_releases[index].take())
+        .collect::<Vec<_>>();
 
-    let current_version = get_fixed_version(&buildpack_files)?;
+    let mut buildpacks = vec![];
+    let mut version_locations = vec![];
 
-    let next_version = get_next_version(&current_version, args.bump);
+    for ((mut buildpack_file, changelog_file), buildpack_id) in ordered_releases {
+        check_cancelled().map_err(Error::Cancelled)?;
 
-    for (mut buildpack_file, changelog_file) in buildpack_files.into_iter().zip(changelog_files) {
-        let updated_dependencies = get_buildpack_dependency_ids(&buildpack_file)?
+        let updated_dependencies = get_buildpack_dependency_ids(&buildpack_file)
+            .map_err(Error::BuildpackFile)?
             .into_iter()
             .filter(|buildpack_id| updated_buildpack_ids.contains(buildpack_id))
             .collect::<Vec<_>>();
 
-        let new_buildpack_contents = update_buildpack_contents_with_new_version(
+        version_locations
+            .extend(get_version_locations(&buildpack_file).map_err(Error::VersionLocation)?);
+
+        let buildpack_contents = update_buildpack_contents_with_new_version(
             &mut buildpack_file,
             &next_version,
             &updated_dependencies,
-        )?;
-
-        write(&buildpack_file.path, new_buildpack_contents)
-            .map_err(|e| Error::WritingBuildpack(buildpack_file.path.clone(), e))?;
-
-        eprintln!(
-            "✅️ Updated version {current_version} → {next_version}: {}",
-            buildpack_file.path.display(),
-        );
+        )
+        .map_err(Error::BuildpackFile)?;
+
+        let package_file = plan_package_file(&buildpack_file, &released_buildpacks, &next_version)?;
+
+        // without this check a second release of the same version would replace its section
+        if changelog_file
+            .changelog
+            .releases
+            .contains_key(&release_version)
+        {
+            if args.force {
+                log!(
+                    path = changelog_file.path;
+                    "⚠️ Merging unreleased changes into the existing {release_version} section: {}",
+                    changelog_file.path.display()
+                );
+            } else {
+                Err(Error::VersionAlreadyReleased(
+                    changelog_file.path.clone(),
+                    release_version.clone(),
+                ))?;
+            }
+        }
 
-        let new_changelog = promote_changelog_unreleased_to_version(
+        let changelog = promote_changelog_unreleased_to_version(
             &changelog_file.changelog,
             &next_version,
-            &Utc::now(),
+            &now,
             &updated_dependencies,
+            version_scheme.as_ref(),
+            args.normalize_entries,
+            args.heading_anchor_template.as_deref(),
         );
 
         let changelog_contents = match &repository_url {
             Some(repository) => {
                 let release_declarations =
-                    generate_release_declarations(&new_changelog, repository.to_string());
-                format!("{new_changelog}\n{release_declarations}")
+                    generate_release_declarations(&changelog, repository.to_string());
+                format!(
+                    "{}\n{release_declarations}",
+                    changelog.display_with(args.date_format)
+                )
             }
-            None => new_changelog.to_string(),
+            None => changelog.display_with(args.date_format).to_string(),
         };
 
-        write(&changelog_file.path, changelog_contents)
-            .map_err(|e| Error::WritingChangelog(changelog_file.path.clone(), e))?;
+        buildpacks.push(BuildpackRelease {
+            buildpack_id: buildpack_id.clone(),
+            buildpack_file,
+            buildpack_contents,
+            package_file,
+            previous_changelog: changelog_file.changelog,
+            changelog_path: changelog_file.path,
+            changelog,
+            changelog_contents,
+        });
+    }
 
-        eprintln!(
-            "✅️ Added release entry {next_version}: {}",
-            changelog_file.path.display()
-        );
+    verify_dependency_pins(&buildpacks, &buildpack_dirs, &next_version)?;
+
+    let version_locations = update_version_locations(&version_locations, &next_version.to_string())
+        .map_err(Error::VersionLocation)?;
+
+    let compare_links = repository_url.as_ref().map(|repository_url| {
+        generate_compare_links(
+            &repository_url.to_string(),
+            &args.tag_template,
+            &updated_buildpack_ids,
+            &current_version,
+            &next_version,
+            &release_records
+                .iter()
+                .map(|(buildpack_id, record)| (buildpack_id.clone(), record.version.clone()))
+                .collect(),
+        )
+    });
+
+    let compare_url = compare_links
+        .as_ref()
+        .filter(|_| !is_per_buildpack_tag_template(&args.tag_template))
+        .and_then(|compare_links| compare_links.values().next().cloned());
+
+    let root_changelog = args
+        .root_changelog
+        .as_ref()
+        .map(|path| {
+            plan_root_changelog(
+                &current_dir.join(path),
+                &release_version,
+                &now,
+                &buildpacks,
+                args.date_format,
+            )
+        })
+        .transpose()?;
+
+    // the release commit doesn't exist yet so the recorded sha is the commit the release was
+    // prepared from
+    let release_state = match &mut release_state_file {
+        Some(release_state_file) => {
+            let sha = get_commit_sha(&current_dir, "HEAD").map_err(Error::Git)?;
+            for buildpack_id in &updated_buildpack_ids {
+                set_release_record(
+                    release_state_file,
+                    buildpack_id,
+                    &ReleaseRecord {
+                        version: release_version.clone(),
+                        date: now.date_naive(),
+                        sha: Some(sha.clone()),
+                    },
+                );
+            }
+            Some((
+                release_state_file.path.clone(),
+                release_state_file.document.to_string(),
+            ))
+        }
+        None => None,
+    };
+
+    let version_file =
+        version_file.map(|(path, contents)| (path, render_version_file(&contents, &next_version)));
+
+    Ok(ReleasePlan {
+        current_dir,
+        release_version,
+        from_version: current_version,
+        to_version: next_version,
+        buildpacks,
+        version_locations,
+        compare_links,
+        compare_url,
+        root_changelog,
+        release_state,
+        version_file,
+        commit_message,
+        pr_title,
+    })
+}
+
+// a package.toml next to the buildpack lists the images or registry entries it's packaged with,
+// the ones pointing at a released buildpack move to the new version along with `[[order.group]]`
+fn plan_package_file(
+    buildpack_file: &BuildpackFile,
+    released_buildpacks: &[(BuildpackId, Option<String>)],
+    next_version: &BuildpackVersion,
+) -> Result<Option<(PathBuf, String)>> {
+    let path = buildpack_file.path.with_file_name(PACKAGE_FILE);
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(Error::ReadingPackageFile(path, error)),
+    };
+    let mut document = contents
+        .parse::<Document>()
+        .map_err(|e| Error::ParsingPackageFile(path.clone(), e))?;
+    update_package_dependency_uris(&mut document, released_buildpacks, next_version);
+    Ok(Some((path, document.to_string())))
+}
+
+// every composite in the release has to pin the buildpacks released with it at the new version, a
+// composite left out of the release (e.g.; with `--only`) keeps its pins so it only gets a warning
+fn verify_dependency_pins(
+    buildpacks: &[BuildpackRelease],
+    buildpack_dirs: &[PathBuf],
+    next_version: &BuildpackVersion,
+) -> Result<()> {
+    let released_versions = buildpacks
+        .iter()
+        .map(|buildpack| (buildpack.buildpack_id.clone(), copy_version(next_version)))
+        .collect::<HashMap<_, _>>();
+
+    let mut inconsistent_pins = vec![];
+    for buildpack in buildpacks {
+        let mut buildpack_file = BuildpackFile {
+            path: buildpack.buildpack_file.path.clone(),
+            document: buildpack.buildpack_file.document.clone(),
+        };
+        for drift in sync_buildpack_order_versions(&mut buildpack_file, &released_versions)
+            .map_err(Error::BuildpackFile)?
+        {
+            inconsistent_pins.push((buildpack.buildpack_id.clone(), drift));
+        }
+    }
+    if !inconsistent_pins.is_empty() {
+        Err(Error::InconsistentDependencyPins(inconsistent_pins))?;
     }
 
-    actions::set_output("from_version", current_version.to_string())
-        .map_err(Error::SetActionOutput)?;
-    actions::set_output("to_version", next_version.to_string()).map_err(Error::SetActionOutput)?;
+    for dir in buildpack_dirs {
+        let mut buildpack_file =
+            read_buildpack_file(dir.join("buildpack.toml")).map_err(Error::BuildpackFile)?;
+        let buildpack_id = get_buildpack_id(&buildpack_file).map_err(Error::BuildpackFile)?;
+        if released_versions.contains_key(&buildpack_id) {
+            continue;
+        }
+        for drift in sync_buildpack_order_versions(&mut buildpack_file, &released_versions)
+            .map_err(Error::BuildpackFile)?
+            .into_iter()
+            .filter(|drift| !drift.pinned.is_empty())
+        {
+            log!(
+                buildpack_id = buildpack_id;
+                "⚠️ {buildpack_id} isn't part of the release and still pins {} at {}, the release moves it to {}",
+                drift.buildpack_id, drift.pinned, drift.version
+            );
+        }
+    }
 
     Ok(())
 }
 
-fn read_buildpack_file(path: PathBuf) -> Result<BuildpackFile> {
-    let contents =
-        std::fs::read_to_string(&path).map_err(|e| Error::ReadingBuildpack(path.clone(), e))?;
-    let document =
-        Document::from_str(&contents).map_err(|e| Error::ParsingBuildpack(path.clone(), e))?;
-    Ok(BuildpackFile { path, document })
-}
+// rendered with Tera so workflows can use the release details without building the text in YAML
+// (e.g.; `{% for buildpack_id in buildpack_ids %}...{% endfor %}`)
+fn render_release_messages(
+    commit_message_template: &str,
+    pr_title_template: &str,
+    release_version: &str,
+    from_version: &BuildpackVersion,
+    buildpack_ids: &[BuildpackId],
+) -> Result<(String, String)> {
+    let mut context = tera::Context::new();
+    context.insert("version", release_version);
+    context.insert("previous_version", &from_version.to_string());
+    context.insert(
+        "buildpack_ids",
+        &buildpack_ids
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+    );
+
+    let render = |name: &str, template: &str| {
+        tera::Tera::one_off(template, &context, false)
+            .map(|rendered| rendered.trim().to_string())
+            .map_err(|e| Error::RenderingTemplate(name.to_string(), e))
+    };
 
-fn read_changelog_file(path: PathBuf) -> Result<ChangelogFile> {
-    let contents =
-        std::fs::read_to_string(&path).map_err(|e| Error::ReadingChangelog(path.clone(), e))?;
-    let changelog = Changelog::try_from(contents.as_str())
-        .map_err(|e| Error::ParsingChangelog(path.clone(), e))?;
-    Ok(ChangelogFile { path, changelog })
+    Ok((
+        render("commit_message_template", commit_message_template)?,
+        render("pr_title_template", pr_title_template)?,
+    ))
 }
 
-fn get_buildpack_id(buildpack_file: &BuildpackFile) -> Result<BuildpackId> {
-    let buildpack_id = buildpack_file
-        .document
-        .get("buildpack")
-        .and_then(|value| value.as_table_like())
-        .and_then(|buildpack| buildpack.get("id"))
-        .and_then(|id| id.as_str().map(|v| v.to_string()))
-        .ok_or(Error::MissingRequiredField(
-            buildpack_file.path.clone(),
-            "buildpack.id".to_string(),
+// the root changelog only gets a section for this release, earlier releases are left as they are
+fn plan_root_changelog(
+    path: &Path,
+    release_version: &str,
+    date: &DateTime<Utc>,
+    buildpacks: &[BuildpackRelease],
+    date_format: ReleaseDateFormat,
+) -> Result<(PathBuf, String)> {
+    if let Some(buildpack) = buildpacks
+        .iter()
+        .find(|buildpack| buildpack.changelog_path == path)
+    {
+        Err(Error::RootChangelogIsBuildpackChangelog(
+            path.to_path_buf(),
+            buildpack.buildpack_id.clone(),
         ))?;
-    buildpack_id
-        .parse()
-        .map_err(|_| Error::InvalidBuildpackId(buildpack_file.path.clone(), buildpack_id.clone()))
+    }
+
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == ErrorKind::NotFound => String::new(),
+        Err(error) => Err(Error::ReadingRootChangelog(path.to_path_buf(), error))?,
+    };
+
+    let entries = buildpacks
+        .iter()
+        .map(|buildpack| {
+            (
+                buildpack.buildpack_id.clone(),
+                buildpack
+                    .changelog
+                    .releases
+                    .get(release_version)
+                    .map(|entry| entry.body.clone())
+                    .unwrap_or_default(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let release = render_root_changelog_release(release_version, date, &entries, date_format);
+
+    let contents = insert_root_changelog_release(&contents, release_version, &release)
+        .map_err(|e| Error::UpdatingRootChangelog(path.to_path_buf(), e))?;
+
+    Ok((path.to_path_buf(), contents))
 }
 
-fn get_buildpack_version(buildpack_file: &BuildpackFile) -> Result<BuildpackVersion> {
-    let version = buildpack_file
-        .document
-        .get("buildpack")
-        .and_then(|value| value.as_table_like())
-        .and_then(|buildpack| buildpack.get("version"))
-        .and_then(|version| version.as_str().map(|v| v.to_string()))
-        .ok_or(Error::MissingRequiredField(
-            buildpack_file.path.clone(),
-            "buildpack.version".to_string(),
-        ))?;
-    BuildpackVersion::try_from(version.clone())
-        .map_err(|_| Error::InvalidBuildpackVersion(buildpack_file.path.clone(), version))
+// only files whose contents actually change are written and returned, so re-running a release that
+// was already prepared reports nothing as modified
+pub(crate) fn write_release(plan: &ReleasePlan) -> Result<Vec<PathBuf>> {
+    write_release_with_cancellation(plan, &check_cancelled)
+}
+
+fn write_release_with_cancellation(
+    plan: &ReleasePlan,
+    cancelled: &dyn Fn() -> std::result::Result<(), CancellationError>,
+) -> Result<Vec<PathBuf>> {
+    with_rollback(|written_files| write_release_files(plan, cancelled, written_files))
 }
 
-fn get_buildpack_dependency_ids(buildpack_file: &BuildpackFile) -> Result<Vec<BuildpackId>> {
-    buildpack_file
-        .document
-        .get("order")
-        .and_then(|value| value.as_array_of_tables())
-        .unwrap_or(&ArrayOfTables::default())
+fn write_release_files(
+    plan: &ReleasePlan,
+    cancelled: &dyn Fn() -> std::result::Result<(), CancellationError>,
+    written_files: &mut WrittenFiles,
+) -> Result<Vec<PathBuf>> {
+    let mut modified_files = vec![];
+
+    for buildpack in &plan.buildpacks {
+        cancelled().map_err(Error::Cancelled)?;
+
+        let buildpack_path = &buildpack.buildpack_file.path;
+        if written_files
+            .write_if_changed(buildpack_path, &buildpack.buildpack_contents)
+            .map_err(|e| Error::WritingBuildpack(buildpack_path.clone(), e))?
+        {
+            modified_files.push(buildpack_path.clone());
+
+            log!(
+                buildpack_id = buildpack.buildpack_id, path = buildpack_path;
+                "✅️ Updated version {} → {}: {}",
+                plan.from_version,
+                plan.to_version,
+                buildpack_path.display(),
+            );
+
+            // listed in the workflow summary so each bump can be seen without the logs
+            actions::notice(
+                format!("{} → {}", plan.from_version, plan.to_version),
+                &AnnotationProperties {
+                    title: Some(buildpack.buildpack_id.to_string()),
+                    file: Some(
+                        buildpack_path
+                            .strip_prefix(&plan.current_dir)
+                            .unwrap_or(buildpack_path)
+                            .display()
+                            .to_string(),
+                    ),
+                },
+            );
+        }
+
+        if let Some((path, contents)) = &buildpack.package_file {
+            if written_files
+                .write_if_changed(path, contents)
+                .map_err(|e| Error::WritingPackageFile(path.clone(), e))?
+            {
+                modified_files.push(path.clone());
+
+                log!(
+                    buildpack_id = buildpack.buildpack_id, path = path;
+                    "✅️ Updated dependency versions {} → {}: {}",
+                    plan.from_version,
+                    plan.to_version,
+                    path.display(),
+                );
+            }
+        }
+
+        if written_files
+            .write_if_changed(&buildpack.changelog_path, &buildpack.changelog_contents)
+            .map_err(|e| Error::WritingChangelog(buildpack.changelog_path.clone(), e))?
+        {
+            modified_files.push(buildpack.changelog_path.clone());
+
+            log!(
+                buildpack_id = buildpack.buildpack_id, path = buildpack.changelog_path;
+                "✅️ Added release entry {}: {}",
+                plan.to_version,
+                buildpack.changelog_path.display()
+            );
+        }
+    }
+
+    cancelled().map_err(Error::Cancelled)?;
+
+    if let Some((path, contents)) = &plan.root_changelog {
+        if written_files
+            .write_if_changed(path, contents)
+            .map_err(|e| Error::WritingChangelog(path.clone(), e))?
+        {
+            modified_files.push(path.clone());
+
+            log!(
+                path = path;
+                "✅️ Added release entry {}: {}",
+                plan.to_version,
+                path.display()
+            );
+        }
+    }
+
+    if let Some((path, contents)) = &plan.release_state {
+        if written_files
+            .write_if_changed(path, contents)
+            .map_err(|e| Error::WritingReleaseState(path.clone(), e))?
+        {
+            modified_files.push(path.clone());
+
+            log!(
+                path = path;
+                "✅️ Recorded release {}: {}",
+                plan.to_version,
+                path.display()
+            );
+        }
+    }
+
+    if let Some((path, contents)) = &plan.version_file {
+        if written_files
+            .write_if_changed(path, contents)
+            .map_err(|e| Error::WritingVersionFile(path.clone(), e))?
+        {
+            modified_files.push(path.clone());
+
+            log!(
+                path = path;
+                "✅️ Updated version file {} → {}: {}",
+                plan.from_version,
+                plan.to_version,
+                path.display()
+            );
+        }
+    }
+
+    for (path, contents) in &plan.version_locations {
+        if written_files
+            .write_if_changed(path, contents)
+            .map_err(|e| Error::WritingVersionLocation(path.clone(), e))?
+        {
+            modified_files.push(path.clone());
+
+            log!(
+                path = path;
+                "✅️ Updated version location {} → {}: {}",
+                plan.from_version,
+                plan.to_version,
+                path.display()
+            );
+        }
+    }
+
+    if modified_files.is_empty() {
+        log!("ℹ️ No files changed for release {}", plan.to_version);
+    }
+
+    Ok(modified_files)
+}
+
+// a buildpack has changed when its `buildpack.toml` or changelog was modified
+fn buildpacks_changed(plan: &ReleasePlan, modified_files: &[PathBuf]) -> BTreeMap<String, bool> {
+    plan.buildpacks
         .iter()
-        .flat_map(|order| {
-            order
-                .get("group")
-                .and_then(|value| value.as_array_of_tables())
-                .unwrap_or(&ArrayOfTables::default())
-                .iter()
-                .map(|group| get_group_buildpack_id(group, &buildpack_file.path))
-                .collect::<Vec<_>>()
+        .map(|buildpack| {
+            let changed = modified_files.contains(&buildpack.buildpack_file.path)
+                || modified_files.contains(&buildpack.changelog_path);
+            (buildpack.buildpack_id.to_string(), changed)
         })
-        .collect::<Result<Vec<_>>>()
+        .collect()
 }
 
-fn get_group_buildpack_id(group: &Table, path: &Path) -> Result<BuildpackId> {
-    group
-        .get("id")
-        .and_then(|id| id.as_str())
-        .ok_or(Error::MissingRequiredField(
-            path.to_path_buf(),
-            "order[].group[].id".to_string(),
-        ))
-        .and_then(|id| {
-            id.parse::<BuildpackId>()
-                .map_err(|_| Error::InvalidBuildpackId(path.to_path_buf(), id.to_string()))
+pub(crate) fn set_release_outputs(
+    plan: &ReleasePlan,
+    modified_files: &[PathBuf],
+    output_format: &OutputFormat,
+) -> Result<()> {
+    let selected_buildpack_ids = plan
+        .buildpacks
+        .iter()
+        .map(|buildpack| buildpack.buildpack_id.to_string())
+        .collect::<Vec<_>>();
+    let selected_buildpack_ids =
+        serde_json::to_string(&selected_buildpack_ids).map_err(Error::SerializingJson)?;
+
+    actions::set_output("buildpack_ids", selected_buildpack_ids).map_err(Error::SetActionOutput)?;
+    actions::set_output("from_version", plan.from_version.to_string())
+        .map_err(Error::SetActionOutput)?;
+    actions::set_output("to_version", plan.to_version.to_string())
+        .map_err(Error::SetActionOutput)?;
+
+    if let Some(compare_url) = &plan.compare_url {
+        actions::set_output("compare_url", compare_url).map_err(Error::SetActionOutput)?;
+    }
+
+    if let Some(compare_links) = &plan.compare_links {
+        actions::set_output("compare_links", json!(compare_links).to_string())
+            .map_err(Error::SetActionOutput)?;
+    }
+
+    actions::set_output("commit_message", &plan.commit_message).map_err(Error::SetActionOutput)?;
+    actions::set_output("pr_title", &plan.pr_title).map_err(Error::SetActionOutput)?;
+
+    actions::set_output("changed", (!modified_files.is_empty()).to_string())
+        .map_err(Error::SetActionOutput)?;
+    actions::set_output(
+        "buildpacks_changed",
+        json!(buildpacks_changed(plan, modified_files)).to_string(),
+    )
+    .map_err(Error::SetActionOutput)?;
+
+    let modified_files = modified_files
+        .iter()
+        .map(|path| {
+            path.strip_prefix(&plan.current_dir)
+                .unwrap_or(path)
+                .display()
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+    actions::set_output(
+        "modified_files",
+        actions::format_list_output(&modified_files, output_format),
+    )
+    .map_err(Error::SetActionOutput)?;
+
+    Ok(())
+}
+
+pub(crate) fn release_manifest(plan: &ReleasePlan) -> ReleaseManifest {
+    ReleaseManifest {
+        from_version: plan.from_version.to_string(),
+        to_version: plan.to_version.to_string(),
+        buildpacks: plan
+            .buildpacks
+            .iter()
+            .map(|buildpack| ReleasedBuildpack {
+                id: buildpack.buildpack_id.clone(),
+                version: plan.to_version.to_string(),
+            })
+            .collect(),
+    }
+}
+
+fn select_buildpack_files(
+    buildpack_files: Vec<BuildpackFile>,
+    only: &[BuildpackId],
+    exclude: &[BuildpackId],
+) -> Result<Vec<BuildpackFile>> {
+    let buildpack_ids = buildpack_files
+        .iter()
+        .map(|buildpack_file| get_buildpack_id(buildpack_file).map_err(Error::BuildpackFile))
+        .collect::<Result<Vec<_>>>()?;
+
+    let unknown_buildpack_ids = only
+        .iter()
+        .chain(exclude)
+        .filter(|buildpack_id| !buildpack_ids.contains(buildpack_id))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    if !unknown_buildpack_ids.is_empty() {
+        return Err(Error::UnknownBuildpackIds(unknown_buildpack_ids));
+    }
+
+    Ok(buildpack_files
+        .into_iter()
+        .zip(buildpack_ids)
+        .filter(|(_, buildpack_id)| only.is_empty() || only.contains(buildpack_id))
+        .filter(|(_, buildpack_id)| !exclude.contains(buildpack_id))
+        .map(|(buildpack_file, _)| buildpack_file)
+        .collect())
+}
+
+// a buildpack with a version in the release state is compared from that release, which is only
+// different from `from_version` when the state has drifted
+fn generate_compare_links(
+    repository_url: &str,
+    tag_template: &str,
+    buildpack_ids: &[BuildpackId],
+    from_version: &BuildpackVersion,
+    to_version: &BuildpackVersion,
+    recorded_versions: &BTreeMap<String, String>,
+) -> BTreeMap<String, String> {
+    buildpack_ids
+        .iter()
+        .map(|buildpack_id| {
+            let base_tag = match recorded_versions.get(buildpack_id.as_str()) {
+                Some(recorded_version) => {
+                    render_tag_name(tag_template, buildpack_id, recorded_version)
+                }
+                None => render_tag_name(tag_template, buildpack_id, from_version),
+            };
+            let url = compare_url(
+                repository_url,
+                &base_tag,
+                &render_tag_name(tag_template, buildpack_id, to_version),
+            );
+            (buildpack_id.to_string(), url)
         })
+        .collect()
+}
+
+// a version file (e.g.; `VERSION`) holds just the version and replaces the check that every
+// `buildpack.toml` has the same version, the buildpacks are still all bumped to the next version
+fn parse_version_file(contents: &str) -> Option<BuildpackVersion> {
+    BuildpackVersion::try_from(contents.trim().to_string()).ok()
+}
+
+// the trailing newline is kept if the file had one
+fn render_version_file(contents: &str, version: &BuildpackVersion) -> String {
+    if contents.ends_with('\n') {
+        format!("{version}\n")
+    } else {
+        version.to_string()
+    }
 }
 
 fn get_fixed_version(buildpack_files: &[BuildpackFile]) -> Result<BuildpackVersion> {
@@ -217,6 +938,7 @@ fn get_fixed_version(buildpack_files: &[BuildpackFile]) -> Result<BuildpackVersi
         .map(|buildpack_file| {
             get_buildpack_version(buildpack_file)
                 .map(|version| (buildpack_file.path.clone(), version))
+                .map_err(Error::BuildpackFile)
         })
         .collect::<Result<HashMap<_, _>>>()?;
 
@@ -236,132 +958,24 @@ fn get_fixed_version(buildpack_files: &[BuildpackFile]) -> Result<BuildpackVersi
         .ok_or(Error::NoFixedVersion)
 }
 
-fn get_next_version(current_version: &BuildpackVersion, bump: BumpCoordinate) -> BuildpackVersion {
-    let BuildpackVersion {
-        major,
-        minor,
-        patch,
-    } = current_version;
-
-    match bump {
-        BumpCoordinate::Major => BuildpackVersion {
-            major: major + 1,
-            minor: 0,
-            patch: 0,
-        },
-        BumpCoordinate::Minor => BuildpackVersion {
-            major: *major,
-            minor: minor + 1,
-            patch: 0,
-        },
-        BumpCoordinate::Patch => BuildpackVersion {
-            major: *major,
-            minor: *minor,
-            patch: patch + 1,
-        },
-    }
-}
-
-fn update_buildpack_contents_with_new_version(
-    buildpack_file: &mut BuildpackFile,
-    next_version: &BuildpackVersion,
-    updated_dependencies: &[BuildpackId],
-) -> Result<String> {
-    let buildpack = buildpack_file
-        .document
-        .get_mut("buildpack")
-        .and_then(|value| value.as_table_like_mut())
-        .ok_or(Error::MissingRequiredField(
-            buildpack_file.path.clone(),
-            "buildpack".to_string(),
-        ))?;
-
-    buildpack.insert("version", value(next_version.to_string()));
-
-    let mut empty_orders = ArrayOfTables::default();
-    let mut empty_groups = ArrayOfTables::default();
-
-    let orders = buildpack_file
-        .document
-        .get_mut("order")
-        .and_then(|value| value.as_array_of_tables_mut())
-        .unwrap_or(&mut empty_orders);
-    for order in orders.iter_mut() {
-        let groups = order
-            .get_mut("group")
-            .and_then(|value| value.as_array_of_tables_mut())
-            .unwrap_or(&mut empty_groups);
-        for group in groups.iter_mut() {
-            let buildpack_id = get_group_buildpack_id(group, &buildpack_file.path)?;
-            if updated_dependencies.contains(&buildpack_id) {
-                group.insert("version", value(next_version.to_string()));
-            }
-        }
-    }
-
-    Ok(buildpack_file.document.to_string())
-}
-
-fn promote_changelog_unreleased_to_version(
-    changelog: &Changelog,
-    version: &BuildpackVersion,
-    date: &DateTime<Utc>,
-    updated_dependencies: &[BuildpackId],
-) -> Changelog {
-    let updated_dependencies_text = if updated_dependencies.is_empty() {
-        None
-    } else {
-        Some(
-            updated_dependencies
-                .iter()
-                .map(|id| format!("- Updated `{id}` to `{version}`"))
-                .collect::<Vec<_>>()
-                .join("\n"),
-        )
-    };
-
-    let changes_with_dependencies = (&changelog.unreleased, &updated_dependencies_text);
-
-    let body = if let (Some(changes), Some(dependencies)) = changes_with_dependencies {
-        format!("{}\n{}", changes.trim_end(), dependencies)
-    } else if let (Some(changes), None) = changes_with_dependencies {
-        changes.clone()
-    } else if let (None, Some(dependencies)) = changes_with_dependencies {
-        dependencies.clone()
-    } else {
-        "- No changes".to_string()
-    };
-
-    let new_release_entry = ReleaseEntry {
-        version: version.to_string(),
-        date: *date,
-        body,
-    };
-
-    let mut releases = IndexMap::from([(version.to_string(), new_release_entry)]);
-    for (id, entry) in &changelog.releases {
-        releases.insert(id.clone(), entry.clone());
-    }
-    Changelog {
-        unreleased: None,
-        releases,
-    }
-}
-
 #[cfg(test)]
 mod test {
-    use crate::changelog::{Changelog, ReleaseEntry};
     use crate::commands::prepare_release::command::{
-        get_fixed_version, promote_changelog_unreleased_to_version,
-        update_buildpack_contents_with_new_version, BuildpackFile,
+        generate_compare_links, get_fixed_version, parse_version_file, release_manifest,
+        render_release_messages, render_version_file, select_buildpack_files,
+        write_release_with_cancellation, BuildpackRelease, ReleasePlan,
+        DEFAULT_COMMIT_MESSAGE_TEMPLATE, DEFAULT_PR_TITLE_TEMPLATE,
     };
     use crate::commands::prepare_release::errors::Error;
-    use chrono::{TimeZone, Utc};
-    use indexmap::IndexMap;
+    use languages_github_actions::buildpack::BuildpackFile;
+    use languages_github_actions::cancellation::CancellationError;
+    use languages_github_actions::changelog::Changelog;
     use libcnb_data::buildpack::BuildpackVersion;
     use libcnb_data::buildpack_id;
-    use std::collections::HashMap;
-    use std::path::PathBuf;
+    use std::cell::Cell;
+    use std::collections::{BTreeMap, HashMap};
+    use std::fs;
+    use std::path::{Path, PathBuf};
     use std::str::FromStr;
     use toml_edit::Document;
 
@@ -382,7 +996,7 @@ version = "0.0.0"
 "#,
         );
         assert_eq!(
-            get_fixed_version(&vec![buildpack_a, buildpack_b]).unwrap(),
+            get_fixed_version(&[buildpack_a, buildpack_b]).unwrap(),
             BuildpackVersion {
                 major: 0,
                 minor: 0,
@@ -407,7 +1021,7 @@ id = "b"
 version = "0.0.1"
 "#,
         );
-        match get_fixed_version(&vec![buildpack_a, buildpack_b]).unwrap_err() {
+        match get_fixed_version(&[buildpack_a, buildpack_b]).unwrap_err() {
             Error::NotAllVersionsMatch(version_map) => {
                 assert_eq!(
                     HashMap::from([
@@ -436,298 +1050,265 @@ version = "0.0.1"
     }
 
     #[test]
-    fn test_update_buildpack_contents_with_new_version() {
-        let toml = r#"[buildpack]
-id = "test"
-version = "0.0.0"
-            "#;
-
-        let mut buildpack_file = create_buildpack_file(toml);
-        let next_version = BuildpackVersion {
-            major: 1,
-            minor: 0,
-            patch: 0,
-        };
+    fn test_version_file() {
+        let version = parse_version_file("1.2.3\n").unwrap();
         assert_eq!(
-            update_buildpack_contents_with_new_version(&mut buildpack_file, &next_version, &[])
-                .unwrap(),
-            r#"[buildpack]
-id = "test"
-version = "1.0.0"
-            "#
-        );
-    }
-
-    #[test]
-    fn test_update_buildpack_contents_with_new_version_and_order_groups_are_present() {
-        let toml = r#"[buildpack]
-id = "test"
-version = "0.0.9"
-
-[[order]]
-[[order.group]]
-id = "dep-a"
-version = "0.0.9"
-
-[[order.group]]
-id = "dep-b"
-version = "0.0.9"
-
-[[order.group]]
-id = "heroku/procfile"
-version = "2.0.0"
-optional = true
-            "#;
-
-        let mut buildpack_file = create_buildpack_file(toml);
-        let next_version = BuildpackVersion {
-            major: 0,
-            minor: 0,
-            patch: 10,
-        };
-        assert_eq!(
-            update_buildpack_contents_with_new_version(
-                &mut buildpack_file,
-                &next_version,
-                &[buildpack_id!("dep-a"), buildpack_id!("dep-b")]
-            )
-            .unwrap(),
-            r#"[buildpack]
-id = "test"
-version = "0.0.10"
-
-[[order]]
-[[order.group]]
-id = "dep-a"
-version = "0.0.10"
-
-[[order.group]]
-id = "dep-b"
-version = "0.0.10"
-
-[[order.group]]
-id = "heroku/procfile"
-version = "2.0.0"
-optional = true
-            "#
+            version,
+            BuildpackVersion {
+                major: 1,
+                minor: 2,
+                patch: 3
+            }
         );
+        assert_eq!(render_version_file("1.2.2\n", &version), "1.2.3\n");
+        assert_eq!(render_version_file("1.2.2", &version), "1.2.3");
+        assert_eq!(parse_version_file("v1.2.3"), None);
+        assert_eq!(parse_version_file("version = 1.2.3"), None);
     }
 
     #[test]
-    fn test_promote_changelog_unreleased_to_version_with_existing_entries() {
-        let release_entry_0_8_16 = ReleaseEntry {
-            version: "0.8.16".to_string(),
-            date: Utc.with_ymd_and_hms(2023, 2, 27, 0, 0, 0).unwrap(),
-            body: "- Added node version 19.7.0, 19.6.1, 14.21.3, 16.19.1, 18.14.1, 18.14.2.\n- Added node version 18.14.0, 19.6.0.".to_string()
-        };
-
-        let release_entry_0_8_15 = ReleaseEntry {
-            version: "0.8.15".to_string(),
-            date: Utc.with_ymd_and_hms(2023, 2, 27, 0, 0, 0).unwrap(),
-            body: "- `name` is no longer a required field in package.json. ([#447](https://github.com/heroku/buildpacks-nodejs/pull/447))\n- Added node version 19.5.0.".to_string()
+    fn test_select_buildpack_files() {
+        let create_buildpack_files = || {
+            vec![
+                create_buildpack_file_with_name(
+                    "/a/buildpack.toml",
+                    "[buildpack]\nid = \"a\"\nversion = \"0.0.0\"\n",
+                ),
+                create_buildpack_file_with_name(
+                    "/b/buildpack.toml",
+                    "[buildpack]\nid = \"b\"\nversion = \"0.0.1\"\n",
+                ),
+                create_buildpack_file_with_name(
+                    "/c/buildpack.toml",
+                    "[buildpack]\nid = \"c\"\nversion = \"0.0.0\"\n",
+                ),
+            ]
         };
 
-        let changelog = Changelog {
-            unreleased: Some(
-                "- Added node version 18.15.0.\n- Added yarn version 4.0.0-rc.2".to_string(),
-            ),
-            releases: IndexMap::from([
-                ("0.8.16".to_string(), release_entry_0_8_16.clone()),
-                ("0.8.15".to_string(), release_entry_0_8_15.clone()),
-            ]),
+        let selected_paths = |buildpack_files: Vec<BuildpackFile>| {
+            buildpack_files
+                .into_iter()
+                .map(|buildpack_file| buildpack_file.path)
+                .collect::<Vec<_>>()
         };
 
         assert_eq!(
-            changelog.unreleased,
-            Some("- Added node version 18.15.0.\n- Added yarn version 4.0.0-rc.2".to_string())
-        );
-        assert_eq!(changelog.releases.get("0.8.17"), None);
-        assert_eq!(
-            changelog.releases.get("0.8.16"),
-            Some(&release_entry_0_8_16)
-        );
-        assert_eq!(
-            changelog.releases.get("0.8.15"),
-            Some(&release_entry_0_8_15)
-        );
-
-        let next_version = BuildpackVersion {
-            major: 0,
-            minor: 8,
-            patch: 17,
-        };
-        let date = Utc.with_ymd_and_hms(2023, 6, 16, 0, 0, 0).unwrap();
-        let updated_dependencies = vec![];
-        let changelog = promote_changelog_unreleased_to_version(
-            &changelog,
-            &next_version,
-            &date,
-            &updated_dependencies,
+            selected_paths(select_buildpack_files(create_buildpack_files(), &[], &[]).unwrap()),
+            vec![
+                PathBuf::from("/a/buildpack.toml"),
+                PathBuf::from("/b/buildpack.toml"),
+                PathBuf::from("/c/buildpack.toml")
+            ]
         );
 
-        assert_eq!(changelog.unreleased, None);
-        assert_eq!(
-            changelog.releases.get("0.8.17"),
-            Some(&ReleaseEntry {
-                version: "0.8.17".to_string(),
-                date,
-                body: "- Added node version 18.15.0.\n- Added yarn version 4.0.0-rc.2".to_string()
-            })
-        );
+        let selected = select_buildpack_files(
+            create_buildpack_files(),
+            &[buildpack_id!("a"), buildpack_id!("c")],
+            &[],
+        )
+        .unwrap();
+        assert!(get_fixed_version(&selected).is_ok());
         assert_eq!(
-            changelog.releases.get("0.8.16"),
-            Some(&release_entry_0_8_16)
+            selected_paths(selected),
+            vec![
+                PathBuf::from("/a/buildpack.toml"),
+                PathBuf::from("/c/buildpack.toml")
+            ]
         );
+
         assert_eq!(
-            changelog.releases.get("0.8.15"),
-            Some(&release_entry_0_8_15)
+            selected_paths(
+                select_buildpack_files(create_buildpack_files(), &[], &[buildpack_id!("b")])
+                    .unwrap()
+            ),
+            vec![
+                PathBuf::from("/a/buildpack.toml"),
+                PathBuf::from("/c/buildpack.toml")
+            ]
         );
+
+        match select_buildpack_files(create_buildpack_files(), &[buildpack_id!("d")], &[]) {
+            Err(Error::UnknownBuildpackIds(buildpack_ids)) => {
+                assert_eq!(buildpack_ids, vec![buildpack_id!("d")]);
+            }
+            _ => panic!("Expected error UnknownBuildpackIds"),
+        }
     }
 
     #[test]
-    fn test_promote_changelog_unreleased_to_version_with_no_entries() {
-        let changelog = Changelog {
-            unreleased: None,
-            releases: IndexMap::new(),
+    fn test_render_release_messages() {
+        let buildpack_ids = [buildpack_id!("heroku/nodejs"), buildpack_id!("heroku/npm")];
+        let from_version = BuildpackVersion {
+            major: 1,
+            minor: 0,
+            patch: 0,
         };
 
-        assert_eq!(changelog.unreleased, None);
-        assert_eq!(changelog.releases.get("0.8.17"), None);
-
-        let next_version = BuildpackVersion {
-            major: 0,
-            minor: 8,
-            patch: 17,
-        };
-        let date = Utc.with_ymd_and_hms(2023, 6, 16, 0, 0, 0).unwrap();
-        let updated_dependencies = vec![];
-        let changelog = promote_changelog_unreleased_to_version(
-            &changelog,
-            &next_version,
-            &date,
-            &updated_dependencies,
+        assert_eq!(
+            render_release_messages(
+                DEFAULT_COMMIT_MESSAGE_TEMPLATE,
+                DEFAULT_PR_TITLE_TEMPLATE,
+                "1.1.0",
+                &from_version,
+                &buildpack_ids
+            )
+            .unwrap(),
+            (
+                "Prepare release v1.1.0".to_string(),
+                "Prepare release v1.1.0".to_string()
+            )
         );
 
-        assert_eq!(changelog.unreleased, None);
         assert_eq!(
-            changelog.releases.get("0.8.17"),
-            Some(&ReleaseEntry {
-                version: "0.8.17".to_string(),
-                date,
-                body: "- No changes".to_string()
-            })
+            render_release_messages(
+                "Release {{ version }}\n\n{% for id in buildpack_ids %}- {{ id }}\n{% endfor %}",
+                "{{ buildpack_ids | join(sep=\", \") }} {{ previous_version }} → {{ version }}",
+                "1.1.0",
+                &from_version,
+                &buildpack_ids
+            )
+            .unwrap(),
+            (
+                "Release 1.1.0\n\n- heroku/nodejs\n- heroku/npm".to_string(),
+                "heroku/nodejs, heroku/npm 1.0.0 → 1.1.0".to_string()
+            )
         );
+
+        assert!(matches!(
+            render_release_messages(
+                "{{ tag }}",
+                DEFAULT_PR_TITLE_TEMPLATE,
+                "1.1.0",
+                &from_version,
+                &buildpack_ids
+            ),
+            Err(Error::RenderingTemplate(name, _)) if name == "commit_message_template"
+        ));
     }
 
     #[test]
-    fn test_promote_changelog_unreleased_to_version_with_existing_entries_and_updated_dependencies()
-    {
-        let release_entry_0_8_16 = ReleaseEntry {
-            version: "0.8.16".to_string(),
-            date: Utc.with_ymd_and_hms(2023, 2, 27, 0, 0, 0).unwrap(),
-            body: "- Added node version 19.7.0, 19.6.1, 14.21.3, 16.19.1, 18.14.1, 18.14.2.\n- Added node version 18.14.0, 19.6.0.".to_string()
-        };
-
-        let release_entry_0_8_15 = ReleaseEntry {
-            version: "0.8.15".to_string(),
-            date: Utc.with_ymd_and_hms(2023, 2, 27, 0, 0, 0).unwrap(),
-            body: "- `name` is no longer a required field in package.json. ([#447](https://github.com/heroku/buildpacks-nodejs/pull/447))\n- Added node version 19.5.0.".to_string()
+    fn test_generate_compare_links() {
+        let buildpack_ids = [buildpack_id!("a"), buildpack_id!("b")];
+        let from_version = BuildpackVersion {
+            major: 1,
+            minor: 0,
+            patch: 0,
         };
-
-        let changelog = Changelog {
-            unreleased: Some(
-                "- Added node version 18.15.0.\n- Added yarn version 4.0.0-rc.2".to_string(),
-            ),
-            releases: IndexMap::from([
-                ("0.8.16".to_string(), release_entry_0_8_16.clone()),
-                ("0.8.15".to_string(), release_entry_0_8_15.clone()),
-            ]),
+        let to_version = BuildpackVersion {
+            major: 1,
+            minor: 1,
+            patch: 0,
         };
 
-        assert_eq!(
-            changelog.unreleased,
-            Some("- Added node version 18.15.0.\n- Added yarn version 4.0.0-rc.2".to_string())
+        let compare_links = generate_compare_links(
+            "https://github.com/heroku/repo",
+            "v{version}",
+            &buildpack_ids,
+            &from_version,
+            &to_version,
+            &BTreeMap::new(),
         );
-        assert_eq!(changelog.releases.get("0.8.17"), None);
         assert_eq!(
-            changelog.releases.get("0.8.16"),
-            Some(&release_entry_0_8_16)
+            compare_links.get("a").unwrap(),
+            "https://github.com/heroku/repo/compare/v1.0.0...v1.1.0"
         );
-        assert_eq!(
-            changelog.releases.get("0.8.15"),
-            Some(&release_entry_0_8_15)
-        );
-
-        let next_version = BuildpackVersion {
-            major: 0,
-            minor: 8,
-            patch: 17,
-        };
-        let date = Utc.with_ymd_and_hms(2023, 6, 16, 0, 0, 0).unwrap();
-        let updated_dependencies = vec![buildpack_id!("a"), buildpack_id!("b")];
-        let changelog = promote_changelog_unreleased_to_version(
-            &changelog,
-            &next_version,
-            &date,
-            &updated_dependencies,
-        );
-
-        assert_eq!(changelog.unreleased, None);
-        assert_eq!(
-            changelog.releases.get("0.8.17"),
-            Some(&ReleaseEntry {
-                version: "0.8.17".to_string(),
-                date,
-                body: "- Added node version 18.15.0.\n- Added yarn version 4.0.0-rc.2\n- Updated `a` to `0.8.17`\n- Updated `b` to `0.8.17`".to_string()
-            })
+        assert_eq!(compare_links.get("a"), compare_links.get("b"));
+
+        let compare_links = generate_compare_links(
+            "https://github.com/heroku/repo",
+            "{buildpack_id}-{version}",
+            &buildpack_ids,
+            &from_version,
+            &to_version,
+            &BTreeMap::from([("a".to_string(), "0.9.0".to_string())]),
         );
         assert_eq!(
-            changelog.releases.get("0.8.16"),
-            Some(&release_entry_0_8_16)
+            compare_links.get("a").unwrap(),
+            "https://github.com/heroku/repo/compare/a-0.9.0...a-1.1.0"
         );
         assert_eq!(
-            changelog.releases.get("0.8.15"),
-            Some(&release_entry_0_8_15)
+            compare_links.get("b").unwrap(),
+            "https://github.com/heroku/repo/compare/b-1.0.0...b-1.1.0"
         );
     }
 
     #[test]
-    fn test_promote_changelog_unreleased_to_version_with_no_entries_and_updated_dependencies() {
-        let changelog = Changelog {
-            unreleased: None,
-            releases: IndexMap::new(),
-        };
-
-        assert_eq!(changelog.unreleased, None);
-        assert_eq!(changelog.releases.get("0.8.17"), None);
+    fn test_write_release_restores_files_when_cancelled() {
+        let dir = std::env::temp_dir().join(format!("write-release-{}", std::process::id()));
+        for id in ["a", "b"] {
+            fs::create_dir_all(dir.join(id)).unwrap();
+            fs::write(dir.join(id).join("buildpack.toml"), "version = \"1.0.0\"\n").unwrap();
+        }
+        let plan = create_release_plan(&dir);
+
+        // the second check happens before the second buildpack is written
+        let checks = Cell::new(0);
+        let result = write_release_with_cancellation(&plan, &|| {
+            checks.set(checks.get() + 1);
+            if checks.get() > 1 {
+                Err(CancellationError::Signal("SIGTERM".to_string()))
+            } else {
+                Ok(())
+            }
+        });
+        assert!(matches!(result, Err(Error::Cancelled(_))));
+        for id in ["a", "b"] {
+            assert_eq!(
+                fs::read_to_string(dir.join(id).join("buildpack.toml")).unwrap(),
+                "version = \"1.0.0\"\n"
+            );
+            assert!(!dir.join(id).join("CHANGELOG.md").exists());
+        }
 
-        let next_version = BuildpackVersion {
-            major: 0,
-            minor: 8,
-            patch: 17,
-        };
-        let date = Utc.with_ymd_and_hms(2023, 6, 16, 0, 0, 0).unwrap();
-        let updated_dependencies = vec![buildpack_id!("a"), buildpack_id!("b")];
-        let changelog = promote_changelog_unreleased_to_version(
-            &changelog,
-            &next_version,
-            &date,
-            &updated_dependencies,
+        let modified_files = write_release_with_cancellation(&plan, &|| Ok(())).unwrap();
+        assert_eq!(modified_files.len(), 4);
+        assert_eq!(
+            fs::read_to_string(dir.join("b").join("buildpack.toml")).unwrap(),
+            "version = \"1.1.0\"\n"
         );
 
-        assert_eq!(changelog.unreleased, None);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_release_manifest() {
+        let plan = create_release_plan(Path::new("/project"));
         assert_eq!(
-            changelog.releases.get("0.8.17"),
-            Some(&ReleaseEntry {
-                version: "0.8.17".to_string(),
-                date,
-                body: "- Updated `a` to `0.8.17`\n- Updated `b` to `0.8.17`".to_string()
-            })
+            release_manifest(&plan).to_json(),
+            r#"{"from_version":"1.0.0","to_version":"1.1.0","buildpacks":[{"id":"a","version":"1.1.0"},{"id":"b","version":"1.1.0"}]}"#
         );
     }
 
-    fn create_buildpack_file(contents: &str) -> BuildpackFile {
-        create_buildpack_file_with_name("/path/to/test/buildpack.toml", contents)
+    fn create_release_plan(dir: &Path) -> ReleasePlan {
+        let create_buildpack_release = |id: &str| BuildpackRelease {
+            buildpack_id: id.parse().unwrap(),
+            buildpack_file: BuildpackFile {
+                path: dir.join(id).join("buildpack.toml"),
+                document: Document::from_str("version = \"1.1.0\"\n").unwrap(),
+            },
+            buildpack_contents: "version = \"1.1.0\"\n".to_string(),
+            package_file: None,
+            previous_changelog: Changelog::try_from("## [Unreleased]\n").unwrap(),
+            changelog_path: dir.join(id).join("CHANGELOG.md"),
+            changelog: Changelog::try_from("## [Unreleased]\n\n## [1.1.0] - 2023-06-01\n").unwrap(),
+            changelog_contents: "## [Unreleased]\n\n## [1.1.0] - 2023-06-01\n".to_string(),
+        };
+
+        ReleasePlan {
+            current_dir: dir.to_path_buf(),
+            from_version: BuildpackVersion::new(1, 0, 0),
+            to_version: BuildpackVersion::new(1, 1, 0),
+            release_version: "1.1.0".to_string(),
+            buildpacks: vec![create_buildpack_release("a"), create_buildpack_release("b")],
+            version_locations: BTreeMap::new(),
+            compare_links: None,
+            compare_url: None,
+            root_changelog: None,
+            release_state: None,
+            version_file: None,
+            commit_message: String::new(),
+            pr_title: String::new(),
+        }
     }
 
     fn create_buildpack_file_with_name(name: &str, contents: &str) -> BuildpackFile {
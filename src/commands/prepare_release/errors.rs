@@ -1,6 +1,15 @@
-use crate::changelog::ChangelogError;
-use crate::github::actions::SetOutputError;
-use libcnb_data::buildpack::BuildpackVersion;
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::buildpack::{
+    BuildpackDiscoveryError, BuildpackFileError, OrderVersionDrift,
+};
+use languages_github_actions::cancellation::CancellationError;
+use languages_github_actions::changelog::{ChangelogError, ChangelogFileError};
+use languages_github_actions::git::GitError;
+use languages_github_actions::github::actions::SetOutputError;
+use languages_github_actions::release_state::ReleaseStateError;
+use languages_github_actions::version_locations::VersionLocationError;
+use languages_github_actions::versioning::VersionSchemeError;
+use libcnb_data::buildpack::{BuildpackId, BuildpackVersion};
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::io;
@@ -10,21 +19,41 @@ use uriparse::URIError;
 #[derive(Debug)]
 pub(crate) enum Error {
     GetCurrentDir(io::Error),
+    Git(GitError),
     InvalidRepositoryUrl(String, URIError),
+    InvalidTagTemplate(String),
+    RenderingTemplate(String, tera::Error),
     NoBuildpacksFound(PathBuf),
+    NoBuildpacksSelected,
+    UnknownBuildpackIds(Vec<BuildpackId>),
     NotAllVersionsMatch(HashMap<PathBuf, BuildpackVersion>),
     NoFixedVersion,
-    FindingBuildpacks(PathBuf, io::Error),
-    ReadingChangelog(PathBuf, io::Error),
-    ParsingChangelog(PathBuf, ChangelogError),
-    ReadingBuildpack(PathBuf, io::Error),
-    ParsingBuildpack(PathBuf, toml_edit::TomlError),
-    MissingRequiredField(PathBuf, String),
-    InvalidBuildpackId(PathBuf, String),
-    InvalidBuildpackVersion(PathBuf, String),
+    InvalidNextVersion(VersionSchemeError),
+    InvalidReleaseDate(ChangelogError),
+    FindingBuildpacks(PathBuf, BuildpackDiscoveryError),
+    ChangelogFile(ChangelogFileError),
+    BuildpackFile(BuildpackFileError),
     WritingBuildpack(PathBuf, io::Error),
+    DependencyCycle(Vec<BuildpackId>),
+    InconsistentDependencyPins(Vec<(BuildpackId, OrderVersionDrift)>),
+    ReadingPackageFile(PathBuf, io::Error),
+    ParsingPackageFile(PathBuf, toml_edit::TomlError),
+    WritingPackageFile(PathBuf, io::Error),
     WritingChangelog(PathBuf, io::Error),
+    VersionAlreadyReleased(PathBuf, String),
+    RootChangelogIsBuildpackChangelog(PathBuf, BuildpackId),
+    ReadingRootChangelog(PathBuf, io::Error),
+    UpdatingRootChangelog(PathBuf, ChangelogError),
+    VersionLocation(VersionLocationError),
+    WritingVersionLocation(PathBuf, io::Error),
+    ReleaseState(ReleaseStateError),
+    WritingReleaseState(PathBuf, io::Error),
+    ReadingVersionFile(PathBuf, io::Error),
+    InvalidVersionFile(PathBuf),
+    WritingVersionFile(PathBuf, io::Error),
+    SerializingJson(serde_json::Error),
     SetActionOutput(SetOutputError),
+    Cancelled(CancellationError),
 }
 
 impl Display for Error {
@@ -34,14 +63,55 @@ impl Display for Error {
                 write!(f, "Failed to get current directory\nError: {error}")
             }
 
+            Error::Git(error) => {
+                write!(f, "{error}")
+            }
+
             Error::InvalidRepositoryUrl(value, error) => {
                 write!(f, "Invalid URL `{value}`\nError: {error}")
             }
 
+            Error::InvalidTagTemplate(value) => {
+                write!(
+                    f,
+                    "Invalid tag template `{value}`, it must contain a {{version}} placeholder"
+                )
+            }
+
+            Error::RenderingTemplate(name, error) => {
+                // tera keeps the details of parse and render failures in the error's sources
+                write!(f, "Could not render `{name}`\nError: {error}")?;
+                let mut source = std::error::Error::source(error);
+                while let Some(error) = source {
+                    write!(f, "\n{error}")?;
+                    source = error.source();
+                }
+                Ok(())
+            }
+
             Error::NoBuildpacksFound(path) => {
                 write!(f, "No buildpacks found under {}", path.display())
             }
 
+            Error::NoBuildpacksSelected => {
+                write!(
+                    f,
+                    "No buildpacks were selected by the --only/--exclude filters"
+                )
+            }
+
+            Error::UnknownBuildpackIds(buildpack_ids) => {
+                write!(
+                    f,
+                    "Unknown buildpack ids given to the --only/--exclude filters:\n{}",
+                    buildpack_ids
+                        .iter()
+                        .map(|buildpack_id| format!("• {buildpack_id}"))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            }
+
             Error::NotAllVersionsMatch(version_map) => {
                 write!(
                     f,
@@ -58,52 +128,87 @@ impl Display for Error {
                 write!(f, "No fixed version could be determined")
             }
 
+            Error::InvalidNextVersion(error) => {
+                write!(f, "Could not determine the next version\nError: {error}")
+            }
+
+            Error::InvalidReleaseDate(error) => {
+                write!(f, "Could not determine the release date\nError: {error}")
+            }
+
             Error::FindingBuildpacks(path, error) => {
                 write!(
                     f,
-                    "I/O error while finding buildpacks\nPath: {}\nError: {error}",
+                    "Could not find buildpacks\nPath: {}\nError: {error}",
                     path.display()
                 )
             }
-            Error::ReadingBuildpack(path, error) => {
+            Error::BuildpackFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::WritingBuildpack(path, error) => {
                 write!(
                     f,
-                    "Could not read buildpack\nPath: {}\nError: {error}",
+                    "Could not write buildpack\nPath: {}\nError: {error}",
                     path.display()
                 )
             }
-            Error::ParsingBuildpack(path, error) => {
+
+            Error::DependencyCycle(buildpack_ids) => {
                 write!(
                     f,
-                    "Could not parse buildpack\nPath: {}\nError: {error}",
-                    path.display()
+                    "Buildpacks include each other in their [[order]] so they can't be released:\n{}",
+                    buildpack_ids
+                        .iter()
+                        .map(|buildpack_id| format!("• {buildpack_id}"))
+                        .collect::<Vec<_>>()
+                        .join("\n")
                 )
             }
 
-            Error::WritingBuildpack(path, error) => {
+            Error::InconsistentDependencyPins(pins) => {
                 write!(
                     f,
-                    "Could not write buildpack\nPath: {}\nError: {error}",
+                    "Composite buildpacks pin versions that don't match the release:\n{}",
+                    pins.iter()
+                        .map(|(buildpack_id, drift)| format!(
+                            "• {buildpack_id} pins {} at {}, expected {}",
+                            drift.buildpack_id, drift.pinned, drift.version
+                        ))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            }
+
+            Error::ReadingPackageFile(path, error) => {
+                write!(
+                    f,
+                    "Could not read package file\nPath: {}\nError: {error}",
                     path.display()
                 )
             }
 
-            Error::ReadingChangelog(path, error) => {
+            Error::ParsingPackageFile(path, error) => {
                 write!(
                     f,
-                    "Could not read changelog\nPath: {}\nError: {error}",
+                    "Could not parse package file\nPath: {}\nError: {error}",
                     path.display()
                 )
             }
 
-            Error::ParsingChangelog(path, error) => {
+            Error::WritingPackageFile(path, error) => {
                 write!(
                     f,
-                    "Could not parse changelog\nPath: {}\nError: {error}",
+                    "Could not write package file\nPath: {}\nError: {error}",
                     path.display()
                 )
             }
 
+            Error::ChangelogFile(error) => {
+                write!(f, "{error}")
+            }
+
             Error::WritingChangelog(path, error) => {
                 write!(
                     f,
@@ -112,35 +217,145 @@ impl Display for Error {
                 )
             }
 
-            Error::SetActionOutput(set_output_error) => match set_output_error {
-                SetOutputError::Opening(error) | SetOutputError::Writing(error) => {
-                    write!(f, "Could not write action output\nError: {error}")
-                }
-            },
+            Error::VersionAlreadyReleased(path, version) => {
+                write!(
+                    f,
+                    "The changelog already has a section for version {version}, use `--force` to merge the unreleased changes into it\nPath: {}",
+                    path.display()
+                )
+            }
+
+            Error::RootChangelogIsBuildpackChangelog(path, buildpack_id) => {
+                write!(
+                    f,
+                    "The root changelog is also the changelog of {buildpack_id}, use a different path with `--root-changelog`\nPath: {}",
+                    path.display()
+                )
+            }
+
+            Error::ReadingRootChangelog(path, error) => {
+                write!(
+                    f,
+                    "Could not read root changelog\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::UpdatingRootChangelog(path, error) => {
+                write!(
+                    f,
+                    "Could not update root changelog\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::VersionLocation(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::WritingVersionLocation(path, error) => {
+                write!(
+                    f,
+                    "Could not write version location\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::ReleaseState(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::WritingReleaseState(path, error) => {
+                write!(
+                    f,
+                    "Could not write release state\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
 
-            Error::MissingRequiredField(path, field) => {
+            Error::ReadingVersionFile(path, error) => {
                 write!(
                     f,
-                    "Missing required field `{field}` in buildpack.toml\nPath: {}",
+                    "Could not read version file\nPath: {}\nError: {error}",
                     path.display()
                 )
             }
 
-            Error::InvalidBuildpackId(path, id) => {
+            Error::InvalidVersionFile(path) => {
                 write!(
                     f,
-                    "Invalid buildpack id `{id}` in buildpack.toml\nPath: {}",
+                    "Version file should only contain a version (e.g.; `1.2.3`)\nPath: {}",
                     path.display()
                 )
             }
 
-            Error::InvalidBuildpackVersion(path, version) => {
+            Error::WritingVersionFile(path, error) => {
                 write!(
                     f,
-                    "Invalid buildpack version `{version}` in buildpack.toml\nPath: {}",
+                    "Could not write version file\nPath: {}\nError: {error}",
                     path.display()
                 )
             }
+
+            Error::SerializingJson(error) => {
+                write!(
+                    f,
+                    "Could not serialize buildpack ids into json\nError: {error}"
+                )
+            }
+
+            Error::SetActionOutput(set_output_error) => match set_output_error {
+                SetOutputError::Opening(error) | SetOutputError::Writing(error) => {
+                    write!(f, "Could not write action output\nError: {error}")
+                }
+            },
+
+            Error::Cancelled(error) => {
+                write!(f, "{error}")
+            }
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::Git(error) => error.category(),
+            Error::GetCurrentDir(_)
+            | Error::WritingBuildpack(_, _)
+            | Error::ReadingPackageFile(_, _)
+            | Error::WritingPackageFile(_, _)
+            | Error::WritingChangelog(_, _)
+            | Error::ReadingRootChangelog(_, _)
+            | Error::WritingVersionLocation(_, _)
+            | Error::WritingReleaseState(_, _)
+            | Error::ReadingVersionFile(_, _)
+            | Error::WritingVersionFile(_, _) => ErrorCategory::Io,
+            Error::FindingBuildpacks(_, error) => error.category(),
+            Error::InvalidRepositoryUrl(_, _)
+            | Error::InvalidTagTemplate(_)
+            | Error::RenderingTemplate(_, _)
+            | Error::NoBuildpacksFound(_)
+            | Error::NoBuildpacksSelected
+            | Error::UnknownBuildpackIds(_)
+            | Error::RootChangelogIsBuildpackChangelog(_, _) => ErrorCategory::Config,
+            Error::UpdatingRootChangelog(_, error) => error.category(),
+            Error::NotAllVersionsMatch(_)
+            | Error::NoFixedVersion
+            | Error::InvalidVersionFile(_)
+            | Error::VersionAlreadyReleased(_, _)
+            | Error::DependencyCycle(_)
+            | Error::InconsistentDependencyPins(_) => ErrorCategory::Validation,
+            Error::ParsingPackageFile(_, _) => ErrorCategory::Parse,
+            Error::InvalidNextVersion(error) => error.category(),
+            Error::InvalidReleaseDate(error) => error.category(),
+            Error::ChangelogFile(error) => error.category(),
+            Error::BuildpackFile(error) => error.category(),
+            Error::VersionLocation(error) => error.category(),
+            Error::ReleaseState(error) => error.category(),
+            Error::SerializingJson(_) => ErrorCategory::Parse,
+            Error::SetActionOutput(error) => error.category(),
+            Error::Cancelled(error) => error.category(),
         }
     }
 }
@@ -0,0 +1,217 @@
+use crate::commands::register_buildpack::errors::Error;
+use clap::Parser;
+use languages_github_actions::cancellation::{check_cancelled, parse_timeout};
+use languages_github_actions::cnb_registry::{
+    get_published_versions, registry_index_issue, REGISTRY_INDEX_REPOSITORY,
+};
+use languages_github_actions::github::actions;
+use languages_github_actions::github::api::{create_or_find_issue, get_issue, Issue};
+use languages_github_actions::github::auth::GitHubAuthArgs;
+use languages_github_actions::log;
+use languages_github_actions::oci::{resolve_digest, DigestCache, ImageReference};
+use libcnb_data::buildpack::{BuildpackId, BuildpackVersion};
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Files a request to add a released buildpack version to the CNB registry index and waits for it to be accepted", long_about = None, disable_version_flag = true)]
+pub(crate) struct RegisterBuildpackArgs {
+    #[arg(long)]
+    pub(crate) buildpack_id: BuildpackId,
+    #[arg(long)]
+    pub(crate) version: String,
+    #[arg(long)]
+    pub(crate) address: String,
+    #[arg(long, default_value = REGISTRY_INDEX_REPOSITORY)]
+    pub(crate) registry_index: String,
+    #[arg(long)]
+    pub(crate) no_wait: bool,
+    #[arg(long, value_parser = parse_timeout, default_value = "15m", conflicts_with = "no_wait")]
+    pub(crate) wait_timeout: Duration,
+    #[arg(long, value_parser = parse_timeout, default_value = "30s", conflicts_with = "no_wait")]
+    pub(crate) poll_interval: Duration,
+    #[command(flatten)]
+    pub(crate) auth: GitHubAuthArgs,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum RegistrationStatus {
+    AlreadyRegistered,
+    Pending,
+    Accepted,
+    Rejected,
+}
+
+impl Display for RegistrationStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegistrationStatus::AlreadyRegistered => write!(f, "already-registered"),
+            RegistrationStatus::Pending => write!(f, "pending"),
+            RegistrationStatus::Accepted => write!(f, "accepted"),
+            RegistrationStatus::Rejected => write!(f, "rejected"),
+        }
+    }
+}
+
+pub(crate) fn execute(args: RegisterBuildpackArgs) -> Result<()> {
+    let buildpack_id = args.buildpack_id;
+
+    let version = BuildpackVersion::try_from(args.version.clone())
+        .map_err(|e| Error::InvalidVersion(args.version, e))?
+        .to_string();
+
+    if is_published(&buildpack_id, &version)? {
+        log!(
+            buildpack_id = buildpack_id;
+            "ℹ️ {buildpack_id}@{version} is already in the CNB registry"
+        );
+        actions::set_output("status", RegistrationStatus::AlreadyRegistered.to_string())
+            .map_err(Error::SetActionOutput)?;
+        return Ok(());
+    }
+
+    let address = resolve_address(&args.address)?;
+
+    let token = args
+        .auth
+        .resolve_token(&args.registry_index)
+        .map_err(Error::Authenticating)?;
+
+    let (title, body) = registry_index_issue(&buildpack_id, &version, &address);
+    let mut issue = create_or_find_issue(&token, &args.registry_index, &title, &body)
+        .map_err(Error::CreatingIssue)?;
+    log!(buildpack_id = buildpack_id; "✅️ Requested {buildpack_id}@{version}: {}", issue.html_url);
+
+    actions::set_output("issue_number", issue.number.to_string())
+        .map_err(Error::SetActionOutput)?;
+    actions::set_output("issue_url", &issue.html_url).map_err(Error::SetActionOutput)?;
+
+    let mut status = RegistrationStatus::Pending;
+    if !args.no_wait {
+        let deadline = Instant::now() + args.wait_timeout;
+        loop {
+            if let Some(result) =
+                registration_status(&issue, is_published(&buildpack_id, &version)?)
+            {
+                status = result;
+                break;
+            }
+            if Instant::now() + args.poll_interval > deadline {
+                break;
+            }
+            log!(
+                "ℹ️ Waiting {}s for the registry index to process {}",
+                args.poll_interval.as_secs(),
+                issue.html_url
+            );
+            wait(args.poll_interval)?;
+            issue = get_issue(&token, &args.registry_index, issue.number)
+                .map_err(Error::CheckingIssue)?;
+        }
+    }
+
+    actions::set_output("status", status.to_string()).map_err(Error::SetActionOutput)?;
+
+    match status {
+        RegistrationStatus::Rejected => Err(Error::Rejected(issue.html_url))?,
+        RegistrationStatus::Pending => log!(
+            buildpack_id = buildpack_id;
+            "⚠️ {buildpack_id}@{version} was not processed yet, check {}",
+            issue.html_url
+        ),
+        _ => {
+            log!(
+                buildpack_id = buildpack_id;
+                "✅️ {buildpack_id}@{version} was accepted into the CNB registry"
+            )
+        }
+    }
+
+    Ok(())
+}
+
+fn is_published(buildpack_id: &BuildpackId, version: &str) -> Result<bool> {
+    check_cancelled().map_err(Error::Cancelled)?;
+    get_published_versions(buildpack_id)
+        .map(|versions| versions.iter().any(|published| published == version))
+        .map_err(Error::CheckingRegistry)
+}
+
+// the registry index only accepts immutable addresses so a tag is resolved to its digest first
+fn resolve_address(address: &str) -> Result<String> {
+    let uri = format!(
+        "docker://{}",
+        address.trim().trim_start_matches("docker://")
+    );
+    let image = ImageReference::try_from(uri.as_str())
+        .map_err(|e| Error::InvalidAddress(address.to_string(), e))?;
+    if image.is_digest() {
+        return Ok(image.to_string());
+    }
+    check_cancelled().map_err(Error::Cancelled)?;
+    let digest = resolve_digest(&image, &DigestCache::default())
+        .map_err(|e| Error::InvalidAddress(address.to_string(), e))?;
+    Ok(image.with_digest(&digest).to_string())
+}
+
+// the registry index bot labels requests it can't process as a failure and closes the ones it
+// has added to the index, which can be before the registry API lists the new version
+fn registration_status(issue: &Issue, published: bool) -> Option<RegistrationStatus> {
+    if issue.labels.iter().any(|label| label.name == "failure") {
+        Some(RegistrationStatus::Rejected)
+    } else if published || issue.state == "closed" {
+        Some(RegistrationStatus::Accepted)
+    } else {
+        None
+    }
+}
+
+// sleeps in short steps so a cancelled job doesn't have to wait out the whole interval
+fn wait(duration: Duration) -> Result<()> {
+    let until = Instant::now() + duration;
+    while Instant::now() < until {
+        check_cancelled().map_err(Error::Cancelled)?;
+        std::thread::sleep(Duration::from_secs(1).min(until - Instant::now()));
+    }
+    check_cancelled().map_err(Error::Cancelled)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::register_buildpack::command::{registration_status, RegistrationStatus};
+    use languages_github_actions::github::api::{Issue, IssueLabel};
+
+    fn issue(state: &str, labels: &[&str]) -> Issue {
+        Issue {
+            number: 1,
+            html_url: "https://github.com/buildpacks/registry-index/issues/1".to_string(),
+            title: "ADD heroku/nodejs@1.0.0".to_string(),
+            state: state.to_string(),
+            labels: labels
+                .iter()
+                .map(|name| IssueLabel {
+                    name: name.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_registration_status() {
+        assert_eq!(registration_status(&issue("open", &[]), false), None);
+        assert_eq!(
+            registration_status(&issue("open", &[]), true),
+            Some(RegistrationStatus::Accepted)
+        );
+        assert_eq!(
+            registration_status(&issue("closed", &[]), false),
+            Some(RegistrationStatus::Accepted)
+        );
+        assert_eq!(
+            registration_status(&issue("closed", &["failure"]), false),
+            Some(RegistrationStatus::Rejected)
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::cancellation::CancellationError;
+use languages_github_actions::cnb_registry::CnbRegistryError;
+use languages_github_actions::github::actions::SetOutputError;
+use languages_github_actions::github::api::GitHubApiError;
+use languages_github_actions::github::auth::AuthError;
+use languages_github_actions::oci::OciError;
+use libcnb_data::buildpack::BuildpackVersionError;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    InvalidVersion(String, BuildpackVersionError),
+    InvalidAddress(String, OciError),
+    CheckingRegistry(CnbRegistryError),
+    Authenticating(AuthError),
+    CreatingIssue(GitHubApiError),
+    CheckingIssue(GitHubApiError),
+    Rejected(String),
+    Cancelled(CancellationError),
+    SetActionOutput(SetOutputError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::InvalidVersion(value, error) => {
+                write!(
+                    f,
+                    "The version argument is invalid\nValue: {value}\nError: {error}"
+                )
+            }
+
+            Error::InvalidAddress(value, error) => {
+                write!(
+                    f,
+                    "Could not resolve the image address\nValue: {value}\nError: {error}"
+                )
+            }
+
+            Error::CheckingRegistry(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::Authenticating(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::CreatingIssue(error) => {
+                write!(f, "Could not file the registry index request\n{error}")
+            }
+
+            Error::CheckingIssue(error) => {
+                write!(f, "Could not check the registry index request\n{error}")
+            }
+
+            Error::Rejected(url) => {
+                write!(
+                    f,
+                    "The registry index rejected the request, see the issue for details\nIssue: {url}"
+                )
+            }
+
+            Error::Cancelled(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::SetActionOutput(set_output_error) => match set_output_error {
+                SetOutputError::Opening(error) | SetOutputError::Writing(error) => {
+                    write!(f, "Could not write action output\nError: {error}")
+                }
+            },
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::InvalidVersion(_, _) => ErrorCategory::Config,
+            Error::InvalidAddress(_, error) => error.category(),
+            Error::CheckingRegistry(error) => error.category(),
+            Error::Authenticating(error) => error.category(),
+            Error::CreatingIssue(error) | Error::CheckingIssue(error) => error.category(),
+            Error::Rejected(_) => ErrorCategory::Validation,
+            Error::Cancelled(error) => error.category(),
+            Error::SetActionOutput(error) => error.category(),
+        }
+    }
+}
@@ -0,0 +1,423 @@
+use crate::commands::prepare_release::command::{plan_release, PrepareReleaseArgs, ReleasePlan};
+use crate::commands::release_impact::errors::Error;
+use clap::Parser;
+use glob::glob;
+use languages_github_actions::builder::{
+    get_builder_buildpack_versions, read_builder_file, BuilderFile,
+};
+use languages_github_actions::buildpack::get_buildpack_dependency_ids;
+use languages_github_actions::changelog::get_list_entries;
+use languages_github_actions::clock::SystemClock;
+use languages_github_actions::git::get_changed_files;
+use languages_github_actions::github::actions;
+use languages_github_actions::github::compare::render_tag_name;
+use languages_github_actions::log;
+use languages_github_actions::shared_paths::{SharedPaths, SharedPathsArgs};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Reports what a release would change without writing any files", long_about = None)]
+pub(crate) struct ReleaseImpactArgs {
+    #[command(flatten)]
+    pub(crate) release: PrepareReleaseArgs,
+    #[arg(long)]
+    pub(crate) builder_path: Option<PathBuf>,
+    #[arg(long, default_value = "builders/*/builder.toml")]
+    pub(crate) builder_pattern: String,
+    #[command(flatten)]
+    pub(crate) shared_paths: SharedPathsArgs,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct ReleaseImpact {
+    from_version: String,
+    to_version: String,
+    buildpacks: Vec<(String, Vec<String>)>,
+    composite_buildpacks: Vec<(String, Vec<String>)>,
+    shared_paths: Vec<(String, Vec<String>)>,
+    builders: Vec<BuilderImpact>,
+}
+
+// the versions each builder currently pins for the buildpacks being released
+#[derive(Debug, Eq, PartialEq)]
+struct BuilderImpact {
+    path: String,
+    pinned_versions: Vec<(String, Vec<String>)>,
+}
+
+pub(crate) fn execute(args: ReleaseImpactArgs) -> Result<()> {
+    let plan = plan_release(&args.release, &SystemClock)
+        .map_err(|e| Error::PrepareRelease(Box::new(e)))?;
+
+    let builder_dir = args
+        .builder_path
+        .map(|path| plan.current_dir.join(path))
+        .unwrap_or_else(|| plan.current_dir.clone());
+
+    let builder_files = find_builder_files(&builder_dir, &args.builder_pattern)?
+        .into_iter()
+        .map(|path| read_builder_file(path).map_err(Error::BuilderFile))
+        .collect::<Result<Vec<_>>>()?;
+
+    let shared_paths = args
+        .shared_paths
+        .read(&plan.current_dir)
+        .map_err(Error::SharedPaths)?;
+    let changed_shared_paths =
+        find_changed_shared_paths(&plan, &shared_paths, &args.release.tag_template);
+
+    let impact = analyze_release(&plan, &builder_files, &builder_dir, changed_shared_paths)?;
+
+    for (path, buildpack_ids) in &impact.shared_paths {
+        for (buildpack_id, changes) in &impact.buildpacks {
+            if changes.is_empty() && buildpack_ids.contains(buildpack_id) {
+                log!(
+                    buildpack_id = buildpack_id;
+                    "⚠️ {buildpack_id} has no unreleased changes but the shared path {path} changed"
+                );
+            }
+        }
+    }
+
+    actions::set_output("summary", render_markdown(&impact)).map_err(Error::SetActionOutput)?;
+    actions::set_output("impact", render_json(&impact)).map_err(Error::SetActionOutput)?;
+
+    Ok(())
+}
+
+// the shared paths changed since each buildpack's last release tag, a tag that can't be compared
+// (e.g.; the first release or a shallow clone) is skipped with a warning
+fn find_changed_shared_paths(
+    plan: &ReleasePlan,
+    shared_paths: &SharedPaths,
+    tag_template: &str,
+) -> Vec<(String, Vec<String>)> {
+    let mut changed_files_by_tag = BTreeMap::new();
+    let mut changed_shared_paths: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for buildpack in &plan.buildpacks {
+        if shared_paths.paths_for(&buildpack.buildpack_id).is_empty() {
+            continue;
+        }
+        let tag = render_tag_name(tag_template, &buildpack.buildpack_id, &plan.from_version);
+        let changed_files = changed_files_by_tag.entry(tag.clone()).or_insert_with(|| {
+            match get_changed_files(&plan.current_dir, &tag) {
+                Ok(changed_files) => Some(changed_files.into_iter().map(PathBuf::from).collect()),
+                Err(error) => {
+                    log!("⚠️ Shared paths not checked for changes since {tag}\n{error}");
+                    None
+                }
+            }
+        });
+        let changed_files: &Vec<PathBuf> = match changed_files {
+            Some(changed_files) => changed_files,
+            None => continue,
+        };
+        for (path, buildpack_ids) in shared_paths.changed(changed_files) {
+            if buildpack_ids.contains(&buildpack.buildpack_id) {
+                changed_shared_paths
+                    .entry(path.to_string())
+                    .or_default()
+                    .push(buildpack.buildpack_id.to_string());
+            }
+        }
+    }
+
+    changed_shared_paths.into_iter().collect()
+}
+
+fn analyze_release(
+    plan: &ReleasePlan,
+    builder_files: &[BuilderFile],
+    builder_dir: &Path,
+    shared_paths: Vec<(String, Vec<String>)>,
+) -> Result<ReleaseImpact> {
+    let buildpack_ids = plan
+        .buildpacks
+        .iter()
+        .map(|buildpack| buildpack.buildpack_id.clone())
+        .collect::<Vec<_>>();
+
+    let buildpacks = plan
+        .buildpacks
+        .iter()
+        .map(|buildpack| {
+            let changes = buildpack
+                .previous_changelog
+                .unreleased
+                .as_deref()
+                .map(get_list_entries)
+                .unwrap_or_default();
+            (buildpack.buildpack_id.to_string(), changes)
+        })
+        .collect();
+
+    let mut composite_buildpacks = vec![];
+    for buildpack in &plan.buildpacks {
+        let dependencies = get_buildpack_dependency_ids(&buildpack.buildpack_file)
+            .map_err(Error::BuildpackFile)?
+            .into_iter()
+            .filter(|buildpack_id| buildpack_ids.contains(buildpack_id))
+            .map(|buildpack_id| buildpack_id.to_string())
+            .collect::<Vec<_>>();
+        if !dependencies.is_empty() {
+            composite_buildpacks.push((buildpack.buildpack_id.to_string(), dependencies));
+        }
+    }
+
+    let builders = builder_files
+        .iter()
+        .filter_map(|builder_file| {
+            let pinned_versions = buildpack_ids
+                .iter()
+                .map(|buildpack_id| {
+                    (
+                        buildpack_id.to_string(),
+                        get_builder_buildpack_versions(builder_file, buildpack_id),
+                    )
+                })
+                .filter(|(_, versions)| !versions.is_empty())
+                .collect::<Vec<_>>();
+            let path = builder_file
+                .path
+                .strip_prefix(builder_dir)
+                .unwrap_or(&builder_file.path)
+                .display()
+                .to_string();
+            (!pinned_versions.is_empty()).then_some(BuilderImpact {
+                path,
+                pinned_versions,
+            })
+        })
+        .collect();
+
+    Ok(ReleaseImpact {
+        from_version: plan.from_version.to_string(),
+        to_version: plan.to_version.to_string(),
+        buildpacks,
+        composite_buildpacks,
+        shared_paths,
+        builders,
+    })
+}
+
+fn render_markdown(impact: &ReleaseImpact) -> String {
+    let to_version = &impact.to_version;
+    let mut lines = vec![
+        format!("## Release impact: {} → {to_version}", impact.from_version),
+        String::new(),
+        "### Buildpacks".to_string(),
+        String::new(),
+    ];
+
+    for (buildpack_id, changes) in &impact.buildpacks {
+        match changes.len() {
+            0 => lines.push(format!("- `{buildpack_id}` (no changes)")),
+            1 => lines.push(format!("- `{buildpack_id}` (1 change)")),
+            count => lines.push(format!("- `{buildpack_id}` ({count} changes)")),
+        }
+    }
+
+    if !impact.composite_buildpacks.is_empty() {
+        lines.extend([
+            String::new(),
+            "### Composite Buildpacks".to_string(),
+            String::new(),
+        ]);
+        for (buildpack_id, dependencies) in &impact.composite_buildpacks {
+            lines.push(format!(
+                "- `{buildpack_id}` pins {} to {to_version}",
+                format_ids(dependencies)
+            ));
+        }
+    }
+
+    if !impact.shared_paths.is_empty() {
+        lines.extend([String::new(), "### Shared Paths".to_string(), String::new()]);
+        for (path, buildpack_ids) in &impact.shared_paths {
+            lines.push(format!(
+                "- `{path}` changed since the last release, affecting {}",
+                format_ids(buildpack_ids)
+            ));
+        }
+    }
+
+    lines.extend([String::new(), "### Builders".to_string(), String::new()]);
+    if impact.builders.is_empty() {
+        lines.push("- No builders reference these buildpacks".to_string());
+    }
+    for builder in &impact.builders {
+        lines.push(format!(
+            "- `{}`: {}",
+            builder.path,
+            builder
+                .pinned_versions
+                .iter()
+                .map(|(buildpack_id, versions)| format!(
+                    "`{buildpack_id}` {} → {to_version}",
+                    versions.join(", ")
+                ))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    format!("{}\n", lines.join("\n"))
+}
+
+fn format_ids(ids: &[String]) -> String {
+    ids.iter()
+        .map(|id| format!("`{id}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn render_json(impact: &ReleaseImpact) -> String {
+    json!({
+        "from_version": impact.from_version,
+        "to_version": impact.to_version,
+        "buildpacks": impact.buildpacks.iter().map(|(buildpack_id, changes)| json!({
+            "id": buildpack_id,
+            "changes": changes,
+        })).collect::<Vec<_>>(),
+        "composite_buildpacks": impact.composite_buildpacks.iter().map(|(buildpack_id, dependencies)| json!({
+            "id": buildpack_id,
+            "dependencies": dependencies,
+        })).collect::<Vec<_>>(),
+        "shared_paths": impact.shared_paths.iter().map(|(path, buildpack_ids)| json!({
+            "path": path,
+            "buildpacks": buildpack_ids,
+        })).collect::<Vec<_>>(),
+        "builders": impact.builders.iter().map(|builder| json!({
+            "path": builder.path,
+            "buildpacks": builder.pinned_versions.iter().map(|(buildpack_id, versions)| json!({
+                "id": buildpack_id,
+                "versions": versions,
+            })).collect::<Vec<_>>(),
+        })).collect::<Vec<_>>(),
+    })
+    .to_string()
+}
+
+fn find_builder_files(dir: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+    let pattern = dir.join(pattern).to_string_lossy().to_string();
+    let mut paths = glob(&pattern)
+        .map_err(|e| Error::InvalidBuilderPattern(pattern.clone(), e))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(Error::FindingBuilders)?;
+    paths.sort();
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::prepare_release::command::{BuildpackRelease, ReleasePlan};
+    use crate::commands::release_impact::command::{analyze_release, render_markdown};
+    use languages_github_actions::builder::BuilderFile;
+    use languages_github_actions::buildpack::BuildpackFile;
+    use languages_github_actions::changelog::Changelog;
+    use libcnb_data::buildpack::BuildpackVersion;
+    use std::collections::BTreeMap;
+    use std::path::{Path, PathBuf};
+    use std::str::FromStr;
+    use toml_edit::Document;
+
+    #[test]
+    fn test_analyze_release() {
+        let create_buildpack_release =
+            |id: &str, contents: &str, unreleased: &str| BuildpackRelease {
+                buildpack_id: id.parse().unwrap(),
+                buildpack_file: BuildpackFile {
+                    path: PathBuf::from(format!("/{id}/buildpack.toml")),
+                    document: Document::from_str(&format!(
+                        "[buildpack]\nid = \"{id}\"\nversion = \"1.1.0\"\n{contents}"
+                    ))
+                    .unwrap(),
+                },
+                buildpack_contents: String::new(),
+                package_file: None,
+                previous_changelog: Changelog::try_from(
+                    format!("## [Unreleased]\n\n{unreleased}").as_str(),
+                )
+                .unwrap(),
+                changelog_path: PathBuf::from(format!("/{id}/CHANGELOG.md")),
+                changelog: Changelog::try_from("## [Unreleased]\n").unwrap(),
+                changelog_contents: String::new(),
+            };
+
+        let plan = ReleasePlan {
+            current_dir: PathBuf::from("/"),
+            from_version: BuildpackVersion::try_from("1.0.0".to_string()).unwrap(),
+            to_version: BuildpackVersion::try_from("1.1.0".to_string()).unwrap(),
+            release_version: "1.1.0".to_string(),
+            buildpacks: vec![
+                create_buildpack_release("heroku/engine", "", "- Some change\n- Other change"),
+                create_buildpack_release(
+                    "heroku/nodejs",
+                    "[[order]]\n[[order.group]]\nid = \"heroku/engine\"\nversion = \"1.1.0\"\n",
+                    "",
+                ),
+            ],
+            version_locations: BTreeMap::new(),
+            compare_links: None,
+            compare_url: None,
+            root_changelog: None,
+            release_state: None,
+            version_file: None,
+            commit_message: "Prepare release v1.0.0".to_string(),
+            pr_title: "Prepare release v1.0.0".to_string(),
+        };
+
+        let builder_files = [
+            BuilderFile {
+                path: PathBuf::from("/builders/builder-22/builder.toml"),
+                document: Document::from_str(
+                    "[[order]]\n[[order.group]]\nid = \"heroku/nodejs\"\nversion = \"1.0.0\"\n",
+                )
+                .unwrap(),
+            },
+            BuilderFile {
+                path: PathBuf::from("/builders/builder-other/builder.toml"),
+                document: Document::from_str(
+                    "[[order]]\n[[order.group]]\nid = \"heroku/java\"\nversion = \"2.0.0\"\n",
+                )
+                .unwrap(),
+            },
+        ];
+
+        let impact = analyze_release(
+            &plan,
+            &builder_files,
+            Path::new("/"),
+            vec![("common".to_string(), vec!["heroku/engine".to_string()])],
+        )
+        .unwrap();
+
+        assert_eq!(
+            render_markdown(&impact),
+            r#"## Release impact: 1.0.0 → 1.1.0
+
+### Buildpacks
+
+- `heroku/engine` (2 changes)
+- `heroku/nodejs` (no changes)
+
+### Composite Buildpacks
+
+- `heroku/nodejs` pins `heroku/engine` to 1.1.0
+
+### Shared Paths
+
+- `common` changed since the last release, affecting `heroku/engine`
+
+### Builders
+
+- `builders/builder-22/builder.toml`: `heroku/nodejs` 1.0.0 → 1.1.0
+"#
+        );
+    }
+}
@@ -0,0 +1,73 @@
+use crate::commands::prepare_release::errors::Error as PrepareReleaseError;
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::builder::BuilderFileError;
+use languages_github_actions::buildpack::BuildpackFileError;
+use languages_github_actions::github::actions::SetOutputError;
+use languages_github_actions::shared_paths::SharedPathsError;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    PrepareRelease(Box<PrepareReleaseError>),
+    BuildpackFile(BuildpackFileError),
+    BuilderFile(BuilderFileError),
+    InvalidBuilderPattern(String, glob::PatternError),
+    FindingBuilders(glob::GlobError),
+    SharedPaths(SharedPathsError),
+    SetActionOutput(SetOutputError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::PrepareRelease(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::BuildpackFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::BuilderFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::InvalidBuilderPattern(pattern, error) => {
+                write!(f, "Invalid builder pattern `{pattern}`\nError: {error}")
+            }
+
+            Error::FindingBuilders(error) => {
+                write!(
+                    f,
+                    "I/O error while finding builders\nPath: {}\nError: {}",
+                    error.path().display(),
+                    error.error()
+                )
+            }
+
+            Error::SharedPaths(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::SetActionOutput(set_output_error) => match set_output_error {
+                SetOutputError::Opening(error) | SetOutputError::Writing(error) => {
+                    write!(f, "Could not write action output\nError: {error}")
+                }
+            },
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::PrepareRelease(error) => error.category(),
+            Error::BuildpackFile(error) => error.category(),
+            Error::BuilderFile(error) => error.category(),
+            Error::InvalidBuilderPattern(_, _) => ErrorCategory::Config,
+            Error::FindingBuilders(_) => ErrorCategory::Io,
+            Error::SharedPaths(error) => error.category(),
+            Error::SetActionOutput(error) => error.category(),
+        }
+    }
+}
@@ -0,0 +1,268 @@
+use crate::commands::rename_buildpack::errors::Error;
+use clap::Parser;
+use glob::glob;
+use languages_github_actions::buildpack::{
+    get_buildpack_id, read_buildpack_file, BuildpackDiscoveryArgs,
+};
+use languages_github_actions::changelog::{insert_unreleased_change, ChangelogLocationArgs};
+use languages_github_actions::git::WorktreeGuardArgs;
+use languages_github_actions::github::actions;
+use languages_github_actions::log;
+use languages_github_actions::patch::{apply_patches, quote_like, scan_toml_tables, Patch};
+use languages_github_actions::write;
+use libcnb_data::buildpack::BuildpackId;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use toml_edit::{Document, Item, TableLike};
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Renames a buildpack and updates every reference to it from composite buildpacks and builders", long_about = None)]
+pub(crate) struct RenameBuildpackArgs {
+    #[arg(long)]
+    pub(crate) from: BuildpackId,
+    #[arg(long)]
+    pub(crate) to: BuildpackId,
+    #[arg(long, default_value = "builders/*/builder.toml")]
+    pub(crate) builder_pattern: String,
+    #[command(flatten)]
+    pub(crate) worktree: WorktreeGuardArgs,
+    #[command(flatten)]
+    pub(crate) discovery: BuildpackDiscoveryArgs,
+    #[command(flatten)]
+    pub(crate) changelog: ChangelogLocationArgs,
+}
+
+pub(crate) fn execute(args: RenameBuildpackArgs) -> Result<()> {
+    let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
+
+    args.worktree.check(&current_dir).map_err(Error::Git)?;
+
+    let buildpack_dirs = args
+        .discovery
+        .find_buildpack_dirs(&current_dir, &[current_dir.join("target")])
+        .map_err(|e| Error::FindingBuildpacks(current_dir.clone(), e))?;
+
+    let mut renamed_dir = None;
+    let mut paths = vec![];
+    for dir in buildpack_dirs {
+        let buildpack_file =
+            read_buildpack_file(dir.join("buildpack.toml")).map_err(Error::BuildpackFile)?;
+        let buildpack_id = get_buildpack_id(&buildpack_file).map_err(Error::BuildpackFile)?;
+        if buildpack_id == args.to {
+            Err(Error::BuildpackIdTaken(
+                args.to.clone(),
+                buildpack_file.path.clone(),
+            ))?;
+        }
+        if buildpack_id == args.from {
+            renamed_dir = Some(dir);
+        }
+        paths.push(buildpack_file.path);
+    }
+    let renamed_dir = renamed_dir.ok_or_else(|| Error::UnknownBuildpackId(args.from.clone()))?;
+
+    paths.extend(find_builder_files(&current_dir, &args.builder_pattern)?);
+
+    let files = paths
+        .into_iter()
+        .map(|path| {
+            std::fs::read_to_string(&path)
+                .map(|contents| (path.clone(), contents))
+                .map_err(|e| Error::ReadingFile(path, e))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut edits = rename_buildpack_references(&files, &args.from, &args.to)?;
+
+    let changelog_path = args
+        .changelog
+        .find_changelog(&renamed_dir, &current_dir)
+        .map_err(Error::ChangelogFile)?;
+    let changelog_contents = std::fs::read_to_string(&changelog_path)
+        .map_err(|e| Error::ReadingFile(changelog_path.clone(), e))?;
+    let changelog_contents = insert_unreleased_change(
+        &changelog_contents,
+        &format!(
+            "Renamed the buildpack from `{}` to `{}`.",
+            args.from, args.to
+        ),
+    )
+    .map_err(|e| Error::AddingChangelogEntry(changelog_path.clone(), e))?;
+    edits.push((changelog_path, changelog_contents));
+
+    let modified_files = write::write_planned_edits(&edits, &current_dir, Error::WritingFile)?;
+    log!("✅️ Renamed {} to {}", args.from, args.to);
+
+    actions::set_output("modified_files", modified_files.join("\n"))
+        .map_err(Error::SetActionOutput)?;
+
+    Ok(())
+}
+
+fn find_builder_files(dir: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+    let pattern = dir.join(pattern).to_string_lossy().to_string();
+    let mut paths = glob(&pattern)
+        .map_err(|e| Error::InvalidBuilderPattern(pattern.clone(), e))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(Error::FindingBuilders)?;
+    paths.sort();
+    Ok(paths)
+}
+
+// the buildpack's own `[buildpack]` id and the `[[order.group]]` and `[[buildpacks]]` entries that
+// reference it are edited as patches so formatting and comments are kept, files without a reference
+// are left out of the result
+fn rename_buildpack_references(
+    files: &[(PathBuf, String)],
+    from: &BuildpackId,
+    to: &BuildpackId,
+) -> Result<Vec<(PathBuf, String)>> {
+    let mut edits = vec![];
+    for (path, contents) in files {
+        let patches = scan_toml_tables(contents)
+            .into_iter()
+            .filter(|table| {
+                matches!(
+                    (table.name.as_str(), table.is_array),
+                    ("buildpack", false) | ("buildpacks", true) | ("order.group", true)
+                )
+            })
+            .filter(|table| table.get_str(contents, "id").as_deref() == Some(from.as_str()))
+            .filter_map(|table| table.values.get("id").cloned())
+            .map(|span| Patch::replace(span.clone(), quote_like(&contents[span], to.as_str())))
+            .collect::<Vec<_>>();
+
+        if patches.is_empty() && count_references(path, contents, from)? == 0 {
+            continue;
+        }
+
+        let renamed =
+            apply_patches(contents, patches).map_err(|e| Error::Patching(path.clone(), e))?;
+
+        // references the scan can't edit (e.g.; inline tables) would be left pointing at the old id
+        if count_references(path, &renamed, from)? > 0 {
+            Err(Error::UnrenamedReferences(path.clone(), from.clone()))?;
+        }
+
+        edits.push((path.clone(), renamed));
+    }
+    Ok(edits)
+}
+
+fn count_references(path: &Path, contents: &str, buildpack_id: &BuildpackId) -> Result<usize> {
+    let document =
+        Document::from_str(contents).map_err(|e| Error::ParsingFile(path.to_path_buf(), e))?;
+    let matches_id = |table: &dyn TableLike| {
+        table.get("id").and_then(Item::as_str) == Some(buildpack_id.as_str())
+    };
+
+    let own_id = document
+        .get("buildpack")
+        .and_then(Item::as_table_like)
+        .filter(|table| matches_id(*table))
+        .map_or(0, |_| 1);
+    let buildpacks = document.get("buildpacks").map_or(0, |buildpacks| {
+        table_likes(buildpacks)
+            .into_iter()
+            .filter(|table| matches_id(*table))
+            .count()
+    });
+    let groups = document.get("order").map_or(0, |orders| {
+        table_likes(orders)
+            .into_iter()
+            .filter_map(|order| order.get("group"))
+            .flat_map(table_likes)
+            .filter(|table| matches_id(*table))
+            .count()
+    });
+
+    Ok(own_id + buildpacks + groups)
+}
+
+// entries can be written as an array of tables or as an array of inline tables
+fn table_likes(item: &Item) -> Vec<&dyn TableLike> {
+    match item {
+        Item::ArrayOfTables(tables) => tables.iter().map(|table| table as &dyn TableLike).collect(),
+        Item::Value(value) => value
+            .as_array()
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(|value| value.as_inline_table())
+                    .map(|table| table as &dyn TableLike)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::rename_buildpack::command::rename_buildpack_references;
+    use crate::commands::rename_buildpack::errors::Error;
+    use libcnb_data::buildpack_id;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_rename_buildpack_references() {
+        let files = vec![
+            (
+                PathBuf::from("buildpacks/engine/buildpack.toml"),
+                "api = \"0.9\"\n\n[buildpack]\nid = \"heroku/nodejs-engine\" # renamed soon\nversion = \"1.0.0\"\n".to_string(),
+            ),
+            (
+                PathBuf::from("meta-buildpacks/nodejs/buildpack.toml"),
+                "[buildpack]\nid = \"heroku/nodejs\"\nversion = \"1.0.0\"\n\n[[order]]\n[[order.group]]\nid = 'heroku/nodejs-engine'\nversion = \"1.0.0\"\n\n[[order.group]]\nid = \"heroku/nodejs-npm\"\nversion = \"1.0.0\"\n".to_string(),
+            ),
+            (
+                PathBuf::from("buildpacks/procfile/buildpack.toml"),
+                "[buildpack]\nid = \"heroku/procfile\"\nversion = \"1.0.0\"\n".to_string(),
+            ),
+            (
+                PathBuf::from("builders/22/builder.toml"),
+                "[[buildpacks]]\n  id = \"heroku/nodejs-engine\"\n  uri = \"docker://docker.io/heroku/buildpack-nodejs-engine@sha256:abc\"\n\n[[order]]\n  [[order.group]]\n    id = \"heroku/nodejs-engine\"\n    version = \"1.0.0\"\n".to_string(),
+            ),
+        ];
+
+        let edits = rename_buildpack_references(
+            &files,
+            &buildpack_id!("heroku/nodejs-engine"),
+            &buildpack_id!("heroku/nodejs-runtime"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            edits,
+            vec![
+                (
+                    PathBuf::from("buildpacks/engine/buildpack.toml"),
+                    "api = \"0.9\"\n\n[buildpack]\nid = \"heroku/nodejs-runtime\" # renamed soon\nversion = \"1.0.0\"\n".to_string(),
+                ),
+                (
+                    PathBuf::from("meta-buildpacks/nodejs/buildpack.toml"),
+                    "[buildpack]\nid = \"heroku/nodejs\"\nversion = \"1.0.0\"\n\n[[order]]\n[[order.group]]\nid = 'heroku/nodejs-runtime'\nversion = \"1.0.0\"\n\n[[order.group]]\nid = \"heroku/nodejs-npm\"\nversion = \"1.0.0\"\n".to_string(),
+                ),
+                (
+                    PathBuf::from("builders/22/builder.toml"),
+                    "[[buildpacks]]\n  id = \"heroku/nodejs-runtime\"\n  uri = \"docker://docker.io/heroku/buildpack-nodejs-engine@sha256:abc\"\n\n[[order]]\n  [[order.group]]\n    id = \"heroku/nodejs-runtime\"\n    version = \"1.0.0\"\n".to_string(),
+                ),
+            ]
+        );
+
+        assert!(matches!(
+            rename_buildpack_references(
+                &[(
+                    PathBuf::from("builder.toml"),
+                    "[[order]]\ngroup = [{ id = \"heroku/nodejs-engine\", version = \"1.0.0\" }]\n"
+                        .to_string()
+                )],
+                &buildpack_id!("heroku/nodejs-engine"),
+                &buildpack_id!("heroku/nodejs-runtime"),
+            ),
+            Err(Error::UnrenamedReferences(_, _))
+        ));
+    }
+}
@@ -0,0 +1,264 @@
+use crate::commands::generate_changelog::locale::RenderLocale;
+use crate::commands::generate_changelog::render::{
+    render_changelog, ChangelogFormat, ChangelogSection,
+};
+use crate::commands::prepare_release::command::{
+    plan_release, release_manifest, set_release_outputs, write_release, PrepareReleaseArgs,
+    ReleasePlan,
+};
+use crate::commands::run_release::errors::{Error, ReleaseProblem};
+use clap::Parser;
+use languages_github_actions::buildpack::get_buildpack_version;
+use languages_github_actions::changelog::{
+    find_duplicated_unreleased_entries, get_security_entries,
+};
+use languages_github_actions::clock::SystemClock;
+use languages_github_actions::github::actions::LargeOutputArgs;
+use languages_github_actions::log;
+use languages_github_actions::versioning::copy_version;
+use std::collections::HashMap;
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Prepares, validates, and writes a release then generates its changelog in a single step", long_about = None)]
+pub(crate) struct RunReleaseArgs {
+    #[command(flatten)]
+    pub(crate) release: PrepareReleaseArgs,
+    #[arg(long)]
+    pub(crate) require_entries: bool,
+    #[arg(long)]
+    pub(crate) security: bool,
+    #[arg(long)]
+    pub(crate) dry_run: bool,
+    #[command(flatten)]
+    pub(crate) changelog_output: LargeOutputArgs,
+}
+
+pub(crate) fn execute(args: RunReleaseArgs) -> Result<()> {
+    let plan = plan_release(&args.release, &SystemClock)
+        .map_err(|e| Error::PrepareRelease(Box::new(e)))?;
+
+    let problems = validate_release(&plan, args.require_entries, args.security);
+    if !problems.is_empty() {
+        Err(Error::ValidationFailed(problems))?;
+    }
+    log!("✅️ Validated release {}", plan.to_version);
+
+    let modified_files = if args.dry_run {
+        log!("⏭️ Skipping writes for dry run");
+        vec![]
+    } else {
+        write_release(&plan).map_err(|e| Error::PrepareRelease(Box::new(e)))?
+    };
+
+    set_release_outputs(&plan, &modified_files, &args.release.output_format)
+        .map_err(|e| Error::PrepareRelease(Box::new(e)))?;
+
+    args.changelog_output
+        .set_output("changelog", generate_release_changelog(&plan))
+        .map_err(Error::SetActionOutput)?;
+
+    if args.release.print_manifest {
+        println!("{}", release_manifest(&plan).to_json());
+    }
+
+    Ok(())
+}
+
+// a security release only needs one buildpack with a `### Security` entry since the other
+// buildpacks are usually released alongside it unchanged
+fn validate_release(
+    plan: &ReleasePlan,
+    require_entries: bool,
+    security: bool,
+) -> Vec<ReleaseProblem> {
+    let mut problems = vec![];
+
+    for buildpack in &plan.buildpacks {
+        let buildpack_id = &buildpack.buildpack_id;
+
+        match get_buildpack_version(&buildpack.buildpack_file) {
+            Ok(version) if version == plan.to_version => {}
+            Ok(version) => problems.push(ReleaseProblem::VersionMismatch(
+                buildpack_id.clone(),
+                version.to_string(),
+                copy_version(&plan.to_version),
+            )),
+            Err(error) => problems.push(ReleaseProblem::VersionMismatch(
+                buildpack_id.clone(),
+                error.to_string(),
+                copy_version(&plan.to_version),
+            )),
+        }
+
+        problems.extend(
+            find_duplicated_unreleased_entries(&buildpack.previous_changelog)
+                .into_iter()
+                .map(|(entry, version)| {
+                    ReleaseProblem::DuplicatedEntry(buildpack_id.clone(), entry, version)
+                }),
+        );
+
+        match buildpack.changelog.releases.get(&plan.release_version) {
+            None => problems.push(ReleaseProblem::MissingReleaseEntry(
+                buildpack_id.clone(),
+                plan.release_version.clone(),
+            )),
+            Some(entry) if require_entries && entry.body.trim() == "- No changes" => problems.push(
+                ReleaseProblem::NoChanges(buildpack_id.clone(), plan.release_version.clone()),
+            ),
+            Some(_) => {}
+        }
+    }
+
+    let has_security_entries = plan.buildpacks.iter().any(|buildpack| {
+        buildpack
+            .changelog
+            .releases
+            .get(&plan.release_version)
+            .map(|entry| !get_security_entries(&entry.body).is_empty())
+            .unwrap_or_default()
+    });
+
+    if security && !has_security_entries {
+        problems.push(ReleaseProblem::MissingSecurityEntry(
+            plan.release_version.clone(),
+        ));
+    }
+
+    problems
+}
+
+fn generate_release_changelog(plan: &ReleasePlan) -> String {
+    let changes_by_buildpack = plan
+        .buildpacks
+        .iter()
+        .map(|buildpack| {
+            let changes = buildpack
+                .changelog
+                .releases
+                .get(&plan.release_version)
+                .map(|entry| ChangelogSection {
+                    version: Some(entry.version.clone()),
+                    date: Some(entry.date),
+                    body: Some(entry.body.clone()),
+                });
+            (buildpack.buildpack_id.clone(), changes)
+        })
+        .collect::<HashMap<_, _>>();
+
+    render_changelog(
+        &changes_by_buildpack,
+        &ChangelogFormat::Markdown,
+        &RenderLocale::default(),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::prepare_release::command::{BuildpackRelease, ReleasePlan};
+    use crate::commands::run_release::command::{generate_release_changelog, validate_release};
+    use crate::commands::run_release::errors::ReleaseProblem;
+    use languages_github_actions::buildpack::BuildpackFile;
+    use languages_github_actions::changelog::Changelog;
+    use libcnb_data::buildpack::BuildpackVersion;
+    use libcnb_data::buildpack_id;
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+    use toml_edit::Document;
+
+    #[test]
+    fn test_validate_release() {
+        let plan = create_release_plan();
+        assert!(validate_release(&plan, false, false).is_empty());
+        assert_eq!(
+            validate_release(&plan, true, false),
+            vec![ReleaseProblem::NoChanges(
+                buildpack_id!("b"),
+                "1.1.0".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_validate_release_with_duplicated_entries() {
+        let mut plan = create_release_plan();
+        plan.buildpacks[0].previous_changelog = Changelog::try_from(
+            "## [Unreleased]\n\n- Some change\n\n## [1.0.0] - 2023-05-01\n\n- Some change",
+        )
+        .unwrap();
+        assert_eq!(
+            validate_release(&plan, false, false),
+            vec![ReleaseProblem::DuplicatedEntry(
+                buildpack_id!("a"),
+                "Some change".to_string(),
+                "1.0.0".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_validate_security_release() {
+        let mut plan = create_release_plan();
+        assert_eq!(
+            validate_release(&plan, false, true),
+            vec![ReleaseProblem::MissingSecurityEntry("1.1.0".to_string())]
+        );
+        plan.buildpacks[1].changelog = Changelog::try_from(
+            "## [Unreleased]\n\n## [1.1.0] - 2023-06-01\n\n### Security\n\n- Updated OpenSSL [CVE-2024-1234]\n",
+        )
+        .unwrap();
+        assert!(validate_release(&plan, false, true).is_empty());
+    }
+
+    #[test]
+    fn test_generate_release_changelog() {
+        assert_eq!(
+            generate_release_changelog(&create_release_plan()),
+            "# a\n\n- Some change\n\n# b\n\n- No changes\n\n"
+        );
+    }
+
+    fn create_release_plan() -> ReleasePlan {
+        let create_buildpack_release = |id: &str, changes: &str| BuildpackRelease {
+            buildpack_id: id.parse().unwrap(),
+            buildpack_file: BuildpackFile {
+                path: PathBuf::from(format!("/{id}/buildpack.toml")),
+                document: Document::from_str(&format!(
+                    "[buildpack]\nid = \"{id}\"\nversion = \"1.1.0\"\n"
+                ))
+                .unwrap(),
+            },
+            buildpack_contents: String::new(),
+            package_file: None,
+            previous_changelog: Changelog::try_from("## [Unreleased]\n").unwrap(),
+            changelog_path: PathBuf::from(format!("/{id}/CHANGELOG.md")),
+            changelog: Changelog::try_from(
+                format!("## [Unreleased]\n\n## [1.1.0] - 2023-06-01\n\n{changes}\n").as_str(),
+            )
+            .unwrap(),
+            changelog_contents: String::new(),
+        };
+
+        ReleasePlan {
+            current_dir: PathBuf::from("/"),
+            from_version: BuildpackVersion::try_from("1.0.0".to_string()).unwrap(),
+            to_version: BuildpackVersion::try_from("1.1.0".to_string()).unwrap(),
+            release_version: "1.1.0".to_string(),
+            buildpacks: vec![
+                create_buildpack_release("a", "- Some change"),
+                create_buildpack_release("b", "- No changes"),
+            ],
+            version_locations: BTreeMap::new(),
+            compare_links: None,
+            compare_url: None,
+            root_changelog: None,
+            release_state: None,
+            version_file: None,
+            commit_message: "Prepare release v1.0.0".to_string(),
+            pr_title: "Prepare release v1.0.0".to_string(),
+        }
+    }
+}
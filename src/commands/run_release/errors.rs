@@ -0,0 +1,92 @@
+use crate::commands::prepare_release::errors::Error as PrepareReleaseError;
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::github::actions::LargeOutputError;
+use libcnb_data::buildpack::{BuildpackId, BuildpackVersion};
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    PrepareRelease(Box<PrepareReleaseError>),
+    ValidationFailed(Vec<ReleaseProblem>),
+    SetActionOutput(LargeOutputError),
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) enum ReleaseProblem {
+    VersionMismatch(BuildpackId, String, BuildpackVersion),
+    DuplicatedEntry(BuildpackId, String, String),
+    MissingReleaseEntry(BuildpackId, String),
+    NoChanges(BuildpackId, String),
+    MissingSecurityEntry(String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::PrepareRelease(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::ValidationFailed(problems) => {
+                write!(
+                    f,
+                    "Release validation failed, no files were written:\n{}",
+                    problems
+                        .iter()
+                        .map(|problem| format!("• {problem}"))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            }
+
+            Error::SetActionOutput(error) => {
+                write!(f, "{error}")
+            }
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::PrepareRelease(error) => error.category(),
+            Error::ValidationFailed(_) => ErrorCategory::Validation,
+            Error::SetActionOutput(error) => error.category(),
+        }
+    }
+}
+
+impl Display for ReleaseProblem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReleaseProblem::VersionMismatch(buildpack_id, actual, expected) => {
+                write!(
+                    f,
+                    "{buildpack_id} would be released as `{actual}` instead of {expected}"
+                )
+            }
+
+            ReleaseProblem::DuplicatedEntry(buildpack_id, entry, version) => {
+                write!(
+                    f,
+                    "{buildpack_id} unreleased entry `{entry}` was already released in {version}"
+                )
+            }
+
+            ReleaseProblem::MissingReleaseEntry(buildpack_id, version) => {
+                write!(f, "{buildpack_id} changelog has no entry for {version}")
+            }
+
+            ReleaseProblem::NoChanges(buildpack_id, version) => {
+                write!(f, "{buildpack_id} has no changes for {version}")
+            }
+
+            ReleaseProblem::MissingSecurityEntry(version) => {
+                write!(
+                    f,
+                    "{version} is a security release but no changelog has a Security entry for it"
+                )
+            }
+        }
+    }
+}
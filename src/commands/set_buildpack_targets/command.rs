@@ -0,0 +1,291 @@
+use crate::commands::set_buildpack_targets::errors::Error;
+use crate::commands::set_buildpack_targets::target::{Distro, Target};
+use clap::Parser;
+use languages_github_actions::buildpack::{
+    get_buildpack_dependency_ids, get_buildpack_id, read_buildpack_file, BuildpackDiscoveryArgs,
+};
+use languages_github_actions::git::WorktreeGuardArgs;
+use languages_github_actions::github::actions;
+use languages_github_actions::lifecycle::ApiVersion;
+use languages_github_actions::log;
+use languages_github_actions::patch::{apply_patches, scan_toml_tables, Patch};
+use languages_github_actions::write;
+use libcnb_data::buildpack::BuildpackId;
+use std::path::Path;
+use std::str::FromStr;
+use toml_edit::{Document, Item, Value};
+
+type Result<T> = std::result::Result<T, Error>;
+
+// `[[targets]]` replaced `[[stacks]]` in buildpack API 0.10
+const MIN_TARGETS_API: ApiVersion = ApiVersion(0, 10);
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Adds or updates the [[targets]] of each selected buildpack", long_about = None)]
+pub(crate) struct SetBuildpackTargetsArgs {
+    #[arg(long = "target", required = true)]
+    pub(crate) targets: Vec<Target>,
+    #[arg(long = "distro")]
+    pub(crate) distros: Vec<Distro>,
+    #[arg(long, conflicts_with = "exclude")]
+    pub(crate) only: Vec<BuildpackId>,
+    #[arg(long)]
+    pub(crate) exclude: Vec<BuildpackId>,
+    #[command(flatten)]
+    pub(crate) worktree: WorktreeGuardArgs,
+    #[command(flatten)]
+    pub(crate) discovery: BuildpackDiscoveryArgs,
+}
+
+// an existing `[[targets]]` entry with the `[[targets.distros]]` entries that follow it, `end` is
+// where new distros for the target are inserted
+#[derive(Debug)]
+struct ExistingTarget {
+    target: Target,
+    distros: Vec<Distro>,
+    end: usize,
+}
+
+pub(crate) fn execute(args: SetBuildpackTargetsArgs) -> Result<()> {
+    let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
+
+    args.worktree.check(&current_dir).map_err(Error::Git)?;
+
+    if !args.distros.is_empty() {
+        if let Some(target) = args.targets.iter().find(|target| target.os != "linux") {
+            Err(Error::DistrosRequireLinux(target.clone()))?;
+        }
+    }
+
+    let buildpack_dirs = args
+        .discovery
+        .find_buildpack_dirs(&current_dir, &[current_dir.join("target")])
+        .map_err(|e| Error::FindingBuildpacks(current_dir.clone(), e))?;
+
+    let buildpack_files = buildpack_dirs
+        .iter()
+        .map(|dir| read_buildpack_file(dir.join("buildpack.toml")).map_err(Error::BuildpackFile))
+        .collect::<Result<Vec<_>>>()?;
+    let buildpack_ids = buildpack_files
+        .iter()
+        .map(|buildpack_file| get_buildpack_id(buildpack_file).map_err(Error::BuildpackFile))
+        .collect::<Result<Vec<_>>>()?;
+
+    let unknown_buildpack_ids = args
+        .only
+        .iter()
+        .chain(&args.exclude)
+        .filter(|buildpack_id| !buildpack_ids.contains(buildpack_id))
+        .cloned()
+        .collect::<Vec<_>>();
+    if !unknown_buildpack_ids.is_empty() {
+        Err(Error::UnknownBuildpackIds(unknown_buildpack_ids))?;
+    }
+
+    let mut edits = vec![];
+    for (buildpack_file, buildpack_id) in buildpack_files.iter().zip(&buildpack_ids) {
+        if !(args.only.is_empty() || args.only.contains(buildpack_id))
+            || args.exclude.contains(buildpack_id)
+        {
+            continue;
+        }
+
+        // composites take their targets from the buildpacks in their order
+        if !get_buildpack_dependency_ids(buildpack_file)
+            .map_err(Error::BuildpackFile)?
+            .is_empty()
+        {
+            log!(buildpack_id = buildpack_id; "⏭️ Skipping composite buildpack {buildpack_id}");
+            continue;
+        }
+
+        if let Some(api) = buildpack_file.document.get("api").and_then(Item::as_str) {
+            if matches!(api.parse::<ApiVersion>(), Ok(version) if version < MIN_TARGETS_API) {
+                log!(
+                    buildpack_id = buildpack_id;
+                    "⚠️ {buildpack_id} declares buildpack API {api} but targets require {MIN_TARGETS_API} or newer"
+                );
+            }
+        }
+
+        let path = &buildpack_file.path;
+        let contents =
+            std::fs::read_to_string(path).map_err(|e| Error::ReadingFile(path.clone(), e))?;
+        let updated = set_targets(path, &contents, &args.targets, &args.distros)?;
+        if updated == contents {
+            log!(buildpack_id = buildpack_id; "ℹ️ {buildpack_id} already declares every target");
+        } else {
+            edits.push((path.clone(), updated));
+        }
+    }
+
+    let modified_files = write::write_planned_edits(&edits, &current_dir, Error::WritingFile)?;
+
+    actions::set_output("modified_files", modified_files.join("\n"))
+        .map_err(Error::SetActionOutput)?;
+
+    Ok(())
+}
+
+// targets are edited as patches so formatting and comments are kept, a target that is already
+// declared only gets the distros it's missing and new targets are added after the existing ones or,
+// when there are none, after the `[buildpack]` table
+fn set_targets(
+    path: &Path,
+    contents: &str,
+    targets: &[Target],
+    distros: &[Distro],
+) -> Result<String> {
+    let document =
+        Document::from_str(contents).map_err(|e| Error::ParsingFile(path.to_path_buf(), e))?;
+    if let Some(item) = document.get("targets") {
+        let is_editable = item.as_array_of_tables().map_or(false, |tables| {
+            tables.iter().all(|table| {
+                table
+                    .get("distros")
+                    .map_or(true, |distros| distros.is_array_of_tables())
+            })
+        });
+        if !is_editable {
+            Err(Error::UnsupportedTargets(path.to_path_buf()))?;
+        }
+    }
+
+    let tables = scan_toml_tables(contents);
+    let mut existing_targets: Vec<ExistingTarget> = vec![];
+    for table in &tables {
+        match (table.name.as_str(), table.is_array) {
+            ("targets", true) => existing_targets.push(ExistingTarget {
+                target: Target {
+                    os: table.get_str(contents, "os").unwrap_or_default(),
+                    arch: table.get_str(contents, "arch").unwrap_or_default(),
+                    variant: table.get_str(contents, "variant"),
+                },
+                distros: vec![],
+                end: table.content_end(contents),
+            }),
+            ("targets.distros", true) => {
+                if let Some(existing_target) = existing_targets.last_mut() {
+                    existing_target.distros.push(Distro {
+                        name: table.get_str(contents, "name").unwrap_or_default(),
+                        version: table.get_str(contents, "version").unwrap_or_default(),
+                    });
+                    existing_target.end = table.content_end(contents);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let new_targets_offset = existing_targets
+        .last()
+        .map(|existing_target| existing_target.end)
+        .or_else(|| {
+            tables
+                .iter()
+                .filter(|table| table.name == "buildpack" || table.name.starts_with("buildpack."))
+                .map(|table| table.content_end(contents))
+                .max()
+        })
+        .unwrap_or_else(|| contents.trim_end().len());
+
+    let mut patches = vec![];
+    for target in targets {
+        match existing_targets
+            .iter()
+            .find(|existing_target| &existing_target.target == target)
+        {
+            Some(existing_target) => {
+                for distro in distros {
+                    if !existing_target.distros.contains(distro) {
+                        patches.push(Patch::insert(
+                            existing_target.end,
+                            format!("\n\n{}", render_distro(distro)),
+                        ));
+                    }
+                }
+            }
+            None => {
+                let mut text = format!("\n\n{}", render_target(target));
+                for distro in distros {
+                    text.push_str(&format!("\n\n{}", render_distro(distro)));
+                }
+                patches.push(Patch::insert(new_targets_offset, text));
+            }
+        }
+    }
+
+    let updated =
+        apply_patches(contents, patches).map_err(|e| Error::Patching(path.to_path_buf(), e))?;
+    Document::from_str(&updated).map_err(|e| Error::ParsingFile(path.to_path_buf(), e))?;
+    Ok(updated)
+}
+
+fn render_target(target: &Target) -> String {
+    let mut text = format!(
+        "[[targets]]\nos = {}\narch = {}",
+        Value::from(target.os.as_str()),
+        Value::from(target.arch.as_str())
+    );
+    if let Some(variant) = &target.variant {
+        text.push_str(&format!("\nvariant = {}", Value::from(variant.as_str())));
+    }
+    text
+}
+
+fn render_distro(distro: &Distro) -> String {
+    format!(
+        "[[targets.distros]]\nname = {}\nversion = {}",
+        Value::from(distro.name.as_str()),
+        Value::from(distro.version.as_str())
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::set_buildpack_targets::command::set_targets;
+    use std::path::Path;
+
+    #[test]
+    fn test_set_targets() {
+        let path = Path::new("buildpacks/nodejs-engine/buildpack.toml");
+        let targets = vec![
+            "linux/amd64".parse().unwrap(),
+            "linux/arm64".parse().unwrap(),
+        ];
+        let distros = vec![
+            "ubuntu@22.04".parse().unwrap(),
+            "ubuntu@24.04".parse().unwrap(),
+        ];
+
+        let contents = "api = \"0.10\"\n\n[buildpack]\nid = \"heroku/nodejs-engine\" # the runtime\nversion = \"1.0.0\"\n\n[[targets]]\nos = 'linux'\narch = 'amd64'\n\n[[targets.distros]]\nname = \"ubuntu\"\nversion = \"22.04\"\n\n# used by the release workflow\n[metadata.release]\ndocker = { repository = \"docker.io/heroku/buildpack-nodejs-engine\" }\n";
+        let updated = set_targets(path, contents, &targets, &distros).unwrap();
+        assert_eq!(
+            updated,
+            "api = \"0.10\"\n\n[buildpack]\nid = \"heroku/nodejs-engine\" # the runtime\nversion = \"1.0.0\"\n\n[[targets]]\nos = 'linux'\narch = 'amd64'\n\n[[targets.distros]]\nname = \"ubuntu\"\nversion = \"22.04\"\n\n[[targets.distros]]\nname = \"ubuntu\"\nversion = \"24.04\"\n\n[[targets]]\nos = \"linux\"\narch = \"arm64\"\n\n[[targets.distros]]\nname = \"ubuntu\"\nversion = \"22.04\"\n\n[[targets.distros]]\nname = \"ubuntu\"\nversion = \"24.04\"\n\n# used by the release workflow\n[metadata.release]\ndocker = { repository = \"docker.io/heroku/buildpack-nodejs-engine\" }\n"
+        );
+        assert_eq!(
+            set_targets(path, &updated, &targets, &distros).unwrap(),
+            updated
+        );
+
+        assert_eq!(
+            set_targets(
+                path,
+                "[buildpack]\nid = \"heroku/procfile\"\n\n[metadata]\nkey = \"value\"\n",
+                &targets[1..],
+                &[]
+            )
+            .unwrap(),
+            "[buildpack]\nid = \"heroku/procfile\"\n\n[[targets]]\nos = \"linux\"\narch = \"arm64\"\n\n[metadata]\nkey = \"value\"\n"
+        );
+
+        assert!(set_targets(
+            path,
+            "targets = [{ os = \"linux\", arch = \"amd64\" }]\n",
+            &targets,
+            &[]
+        )
+        .is_err());
+    }
+}
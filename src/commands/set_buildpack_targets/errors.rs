@@ -0,0 +1,135 @@
+use crate::commands::set_buildpack_targets::target::Target;
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::buildpack::{BuildpackDiscoveryError, BuildpackFileError};
+use languages_github_actions::git::GitError;
+use languages_github_actions::github::actions::SetOutputError;
+use languages_github_actions::patch::PatchError;
+use libcnb_data::buildpack::BuildpackId;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+use toml_edit::TomlError;
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    GetCurrentDir(io::Error),
+    Git(GitError),
+    DistrosRequireLinux(Target),
+    FindingBuildpacks(PathBuf, BuildpackDiscoveryError),
+    BuildpackFile(BuildpackFileError),
+    UnknownBuildpackIds(Vec<BuildpackId>),
+    ReadingFile(PathBuf, io::Error),
+    ParsingFile(PathBuf, TomlError),
+    UnsupportedTargets(PathBuf),
+    Patching(PathBuf, PatchError),
+    WritingFile(PathBuf, io::Error),
+    SetActionOutput(SetOutputError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::GetCurrentDir(error) => {
+                write!(f, "Failed to get current directory\nError: {error}")
+            }
+
+            Error::Git(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::DistrosRequireLinux(target) => {
+                write!(
+                    f,
+                    "Distros can only be set for linux targets but {target} was given"
+                )
+            }
+
+            Error::FindingBuildpacks(path, error) => {
+                write!(
+                    f,
+                    "Could not find buildpacks\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::BuildpackFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::UnknownBuildpackIds(buildpack_ids) => {
+                write!(
+                    f,
+                    "Unknown buildpack ids given to the --only/--exclude filters:\n{}",
+                    buildpack_ids
+                        .iter()
+                        .map(|buildpack_id| format!("• {buildpack_id}"))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            }
+
+            Error::ReadingFile(path, error) => {
+                write!(
+                    f,
+                    "Could not read file\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::ParsingFile(path, error) => {
+                write!(
+                    f,
+                    "Could not parse file\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::UnsupportedTargets(path) => {
+                write!(
+                    f,
+                    "Targets can only be updated when they are written as [[targets]] and [[targets.distros]] tables (e.g.; not inline tables), update them by hand and try again\nPath: {}",
+                    path.display()
+                )
+            }
+
+            Error::Patching(path, error) => {
+                write!(
+                    f,
+                    "Could not update targets\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::WritingFile(path, error) => {
+                write!(
+                    f,
+                    "Could not write file\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::SetActionOutput(set_output_error) => match set_output_error {
+                SetOutputError::Opening(error) | SetOutputError::Writing(error) => {
+                    write!(f, "Could not write action output\nError: {error}")
+                }
+            },
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::Git(error) => error.category(),
+            Error::GetCurrentDir(_) | Error::ReadingFile(_, _) | Error::WritingFile(_, _) => {
+                ErrorCategory::Io
+            }
+            Error::FindingBuildpacks(_, error) => error.category(),
+            Error::BuildpackFile(error) => error.category(),
+            Error::DistrosRequireLinux(_) | Error::UnknownBuildpackIds(_) => ErrorCategory::Config,
+            Error::UnsupportedTargets(_) => ErrorCategory::Validation,
+            Error::ParsingFile(_, _) | Error::Patching(_, _) => ErrorCategory::Parse,
+            Error::SetActionOutput(error) => error.category(),
+        }
+    }
+}
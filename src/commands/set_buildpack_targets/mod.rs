@@ -0,0 +1,5 @@
+pub(crate) mod command;
+pub(crate) mod errors;
+pub(crate) mod target;
+
+pub(crate) use command::execute;
@@ -0,0 +1,163 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+const KNOWN_OS: [&str; 2] = ["linux", "windows"];
+
+const KNOWN_ARCHITECTURES: [&str; 6] = ["amd64", "arm64", "arm", "386", "ppc64le", "s390x"];
+
+const KNOWN_DISTROS: [&str; 7] = [
+    "alpine",
+    "amazonlinux",
+    "centos",
+    "debian",
+    "fedora",
+    "rhel",
+    "ubuntu",
+];
+
+// the variants an architecture can have follow the OCI image spec
+fn known_variants(arch: &str) -> &'static [&'static str] {
+    match arch {
+        "arm64" => &["v8"],
+        "arm" => &["v6", "v7", "v8"],
+        _ => &[],
+    }
+}
+
+// a `[[targets]]` entry written as `os/arch[/variant]` (e.g.; `linux/arm64/v8`)
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct Target {
+    pub(crate) os: String,
+    pub(crate) arch: String,
+    pub(crate) variant: Option<String>,
+}
+
+impl FromStr for Target {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let parts = value.split('/').collect::<Vec<_>>();
+        let (os, arch, variant) = match parts.as_slice() {
+            [os, arch] => (*os, *arch, None),
+            [os, arch, variant] => (*os, *arch, Some(*variant)),
+            _ => Err(format!(
+                "Target `{value}` must be written as os/arch or os/arch/variant"
+            ))?,
+        };
+
+        if !KNOWN_OS.contains(&os) {
+            Err(format!(
+                "Unknown os `{os}`, expected one of {}",
+                KNOWN_OS.join(", ")
+            ))?;
+        }
+        if !KNOWN_ARCHITECTURES.contains(&arch) {
+            Err(format!(
+                "Unknown arch `{arch}`, expected one of {}",
+                KNOWN_ARCHITECTURES.join(", ")
+            ))?;
+        }
+        if let Some(variant) = variant {
+            if !known_variants(arch).contains(&variant) {
+                Err(format!("Unknown variant `{variant}` for arch `{arch}`"))?;
+            }
+        }
+
+        Ok(Target {
+            os: os.to_string(),
+            arch: arch.to_string(),
+            variant: variant.map(String::from),
+        })
+    }
+}
+
+impl Display for Target {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.os, self.arch)?;
+        if let Some(variant) = &self.variant {
+            write!(f, "/{variant}")?;
+        }
+        Ok(())
+    }
+}
+
+// a `[[targets.distros]]` entry written as `name@version` (e.g.; `ubuntu@22.04`)
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct Distro {
+    pub(crate) name: String,
+    pub(crate) version: String,
+}
+
+impl FromStr for Distro {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        lazy_static! {
+            static ref DISTRO_VERSION: Regex =
+                Regex::new(r"^[0-9A-Za-z][0-9A-Za-z._-]*$").expect("Should be a valid regex");
+        }
+
+        let (name, version) = value
+            .split_once('@')
+            .ok_or_else(|| format!("Distro `{value}` must be written as name@version"))?;
+
+        if !KNOWN_DISTROS.contains(&name) {
+            Err(format!(
+                "Unknown distro `{name}`, expected one of {}",
+                KNOWN_DISTROS.join(", ")
+            ))?;
+        }
+        if !DISTRO_VERSION.is_match(version) {
+            Err(format!("Invalid version `{version}` for distro `{name}`"))?;
+        }
+
+        Ok(Distro {
+            name: name.to_string(),
+            version: version.to_string(),
+        })
+    }
+}
+
+impl Display for Distro {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}@{}", self.name, self.version)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::set_buildpack_targets::target::{Distro, Target};
+
+    #[test]
+    fn test_parse_targets() {
+        assert_eq!(
+            "linux/arm64/v8".parse::<Target>(),
+            Ok(Target {
+                os: "linux".to_string(),
+                arch: "arm64".to_string(),
+                variant: Some("v8".to_string())
+            })
+        );
+        assert_eq!(
+            "linux/amd64".parse::<Target>().unwrap().to_string(),
+            "linux/amd64"
+        );
+        assert_eq!(
+            "ubuntu@22.04".parse::<Distro>(),
+            Ok(Distro {
+                name: "ubuntu".to_string(),
+                version: "22.04".to_string()
+            })
+        );
+
+        assert!("linux".parse::<Target>().is_err());
+        assert!("darwin/arm64".parse::<Target>().is_err());
+        assert!("linux/x86_64".parse::<Target>().is_err());
+        assert!("linux/amd64/v8".parse::<Target>().is_err());
+        assert!("ubuntu".parse::<Distro>().is_err());
+        assert!("gentoo@2.14".parse::<Distro>().is_err());
+        assert!("ubuntu@".parse::<Distro>().is_err());
+    }
+}
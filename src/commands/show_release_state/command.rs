@@ -0,0 +1,331 @@
+use crate::commands::show_release_state::errors::Error;
+use chrono::NaiveDate;
+use clap::Parser;
+use languages_github_actions::buildpack::{
+    get_buildpack_id, get_buildpack_version, is_buildpack_release_disabled, read_buildpack_file,
+    BuildpackDiscoveryArgs,
+};
+use languages_github_actions::changelog::ChangelogLocationArgs;
+use languages_github_actions::git::{get_commit_sha, WorktreeGuardArgs};
+use languages_github_actions::github::actions;
+use languages_github_actions::github::compare::{render_tag_name, DEFAULT_TAG_TEMPLATE};
+use languages_github_actions::log;
+use languages_github_actions::release_state::{
+    find_release_state_drift, get_release_records, read_release_state_file, remove_release_record,
+    set_release_record, ReleaseRecord, ReleaseStateFile, RELEASE_STATE_FILE,
+};
+use languages_github_actions::write;
+use libcnb_data::buildpack::BuildpackId;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Shows the last release of each buildpack recorded in the release state file and any drift from the project", long_about = None)]
+pub(crate) struct ShowReleaseStateArgs {
+    #[arg(long)]
+    pub(crate) repair: bool,
+    #[arg(long, default_value = DEFAULT_TAG_TEMPLATE)]
+    pub(crate) tag_template: String,
+    #[command(flatten)]
+    pub(crate) worktree: WorktreeGuardArgs,
+    #[command(flatten)]
+    pub(crate) discovery: BuildpackDiscoveryArgs,
+    #[command(flatten)]
+    pub(crate) changelog: ChangelogLocationArgs,
+}
+
+// a buildpack in the project with the version from its `buildpack.toml` and, when that version has
+// been released, the date of its changelog section
+struct ProjectBuildpack {
+    buildpack_id: BuildpackId,
+    dir: PathBuf,
+    version: String,
+    release_date: Option<NaiveDate>,
+}
+
+pub(crate) fn execute(args: ShowReleaseStateArgs) -> Result<()> {
+    let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
+
+    if args.repair {
+        args.worktree.check(&current_dir).map_err(Error::Git)?;
+    }
+
+    let buildpacks = find_project_buildpacks(&args, &current_dir)?;
+
+    let path = current_dir.join(RELEASE_STATE_FILE);
+    let mut release_state_file = read_release_state_file(&path).map_err(Error::ReleaseState)?;
+
+    if args.repair {
+        let mut repaired_file = release_state_file
+            .clone()
+            .unwrap_or_else(|| ReleaseStateFile::new(&path));
+        repair_release_state(&mut repaired_file, &buildpacks, &args.tag_template, |tag| {
+            get_commit_sha(&current_dir, tag).ok()
+        })?;
+        write::write_file(&path, repaired_file.document.to_string())
+            .map_err(|e| Error::WritingReleaseState(path.clone(), e))?;
+        log!(path = path; "✅️ Repaired release state: {}", path.display());
+        release_state_file = Some(repaired_file);
+    }
+
+    let release_records = match &release_state_file {
+        Some(release_state_file) => {
+            get_release_records(release_state_file).map_err(Error::ReleaseState)?
+        }
+        None => {
+            log!(
+                path = path;
+                "ℹ️ No release state found, create it with `--repair`: {}",
+                path.display()
+            );
+            BTreeMap::new()
+        }
+    };
+
+    for (buildpack_id, record) in &release_records {
+        log!(
+            buildpack_id = buildpack_id;
+            "📌 {buildpack_id} {} released {}{}",
+            record.version,
+            record.date,
+            record
+                .sha
+                .as_ref()
+                .map(|sha| format!(" from {}", &sha[..sha.len().min(12)]))
+                .unwrap_or_default()
+        );
+    }
+
+    let buildpack_versions = buildpacks
+        .iter()
+        .map(|buildpack| {
+            (
+                buildpack.buildpack_id.to_string(),
+                buildpack.version.clone(),
+            )
+        })
+        .collect();
+    let drift = find_release_state_drift(&release_records, &buildpack_versions)
+        .into_iter()
+        .map(|drift| drift.to_string())
+        .collect::<Vec<_>>();
+    if release_state_file.is_some() {
+        for drift in &drift {
+            log!("⚠️ {drift}");
+        }
+    }
+
+    actions::set_output("release_state", render_json(&release_records))
+        .map_err(Error::SetActionOutput)?;
+    actions::set_output("drift", json!(drift).to_string()).map_err(Error::SetActionOutput)?;
+
+    Ok(())
+}
+
+fn find_project_buildpacks(
+    args: &ShowReleaseStateArgs,
+    current_dir: &Path,
+) -> Result<Vec<ProjectBuildpack>> {
+    let buildpack_dirs = args
+        .discovery
+        .find_buildpack_dirs(current_dir, &[current_dir.join("target")])
+        .map_err(|e| Error::FindingBuildpacks(current_dir.to_path_buf(), e))?;
+
+    let mut buildpacks = vec![];
+    for dir in buildpack_dirs {
+        let buildpack_file =
+            read_buildpack_file(dir.join("buildpack.toml")).map_err(Error::BuildpackFile)?;
+        if is_buildpack_release_disabled(&buildpack_file) {
+            log!(
+                path = buildpack_file.path;
+                "⏭️ Skipping disabled buildpack: {}",
+                buildpack_file.path.display()
+            );
+            continue;
+        }
+        let buildpack_id = get_buildpack_id(&buildpack_file).map_err(Error::BuildpackFile)?;
+        let version = get_buildpack_version(&buildpack_file)
+            .map_err(Error::BuildpackFile)?
+            .to_string();
+        let changelog_file = args
+            .changelog
+            .read_changelog_file_from_dir(&dir, current_dir)
+            .map_err(Error::ChangelogFile)?;
+        let release_date = changelog_file
+            .changelog
+            .releases
+            .get(&version)
+            .map(|entry| entry.date.date_naive());
+        buildpacks.push(ProjectBuildpack {
+            buildpack_id,
+            dir,
+            version,
+            release_date,
+        });
+    }
+    Ok(buildpacks)
+}
+
+// rebuilds the records from the project, the version comes from `buildpack.toml`, the date from the
+// version's changelog section, and the sha from the version's tag when it can be found locally,
+// buildpacks that haven't released their current version yet keep whatever was recorded
+fn repair_release_state(
+    release_state_file: &mut ReleaseStateFile,
+    buildpacks: &[ProjectBuildpack],
+    tag_template: &str,
+    resolve_tag: impl Fn(&str) -> Option<String>,
+) -> Result<()> {
+    let records = get_release_records(release_state_file).map_err(Error::ReleaseState)?;
+
+    for buildpack_id in records.keys() {
+        if !buildpacks
+            .iter()
+            .any(|buildpack| buildpack.buildpack_id.as_str() == buildpack_id)
+        {
+            remove_release_record(release_state_file, buildpack_id);
+            log!(
+                buildpack_id = buildpack_id;
+                "✅️ Removed {buildpack_id}, it was not found in the project"
+            );
+        }
+    }
+
+    for buildpack in buildpacks {
+        let release_date = match buildpack.release_date {
+            Some(release_date) => release_date,
+            None => {
+                log!(
+                    path = buildpack.dir;
+                    "⚠️ No changelog section for {} {}, its record was left as it is: {}",
+                    buildpack.buildpack_id,
+                    buildpack.version,
+                    buildpack.dir.display()
+                );
+                continue;
+            }
+        };
+        let tag = render_tag_name(tag_template, &buildpack.buildpack_id, &buildpack.version);
+        let sha = resolve_tag(&tag);
+        if sha.is_none() {
+            log!(
+                "⚠️ Tag {tag} was not found, {} is recorded without a sha",
+                buildpack.buildpack_id
+            );
+        }
+        set_release_record(
+            release_state_file,
+            &buildpack.buildpack_id,
+            &ReleaseRecord {
+                version: buildpack.version.clone(),
+                date: release_date,
+                sha,
+            },
+        );
+    }
+
+    Ok(())
+}
+
+fn render_json(release_records: &BTreeMap<String, ReleaseRecord>) -> String {
+    json!(release_records
+        .iter()
+        .map(|(buildpack_id, record)| {
+            (
+                buildpack_id,
+                json!({
+                    "version": record.version,
+                    "date": record.date.to_string(),
+                    "sha": record.sha,
+                }),
+            )
+        })
+        .collect::<BTreeMap<_, _>>())
+    .to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::show_release_state::command::{
+        render_json, repair_release_state, ProjectBuildpack,
+    };
+    use chrono::NaiveDate;
+    use languages_github_actions::release_state::{get_release_records, ReleaseStateFile};
+    use libcnb_data::buildpack_id;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+    use toml_edit::Document;
+
+    #[test]
+    fn test_repair_release_state() {
+        let mut release_state_file = ReleaseStateFile {
+            path: PathBuf::from(".release-state.toml"),
+            document: Document::from_str(
+                r#"# maintained by prepare-release
+[buildpacks."heroku/nodejs"]
+version = "1.0.0"
+date = "2023-05-01"
+
+[buildpacks."heroku/nodejs-engine"]
+version = "2.0.0"
+date = "2023-06-01"
+
+[buildpacks."heroku/yarn"]
+version = "1.0.0"
+date = "2023-05-01"
+"#,
+            )
+            .unwrap(),
+        };
+        let buildpacks = vec![
+            ProjectBuildpack {
+                buildpack_id: buildpack_id!("heroku/nodejs"),
+                dir: PathBuf::from("/project/meta-buildpacks/nodejs"),
+                version: "1.1.0".to_string(),
+                release_date: NaiveDate::from_ymd_opt(2023, 6, 1),
+            },
+            ProjectBuildpack {
+                buildpack_id: buildpack_id!("heroku/nodejs-engine"),
+                dir: PathBuf::from("/project/buildpacks/nodejs-engine"),
+                version: "2.1.0".to_string(),
+                release_date: None,
+            },
+            ProjectBuildpack {
+                buildpack_id: buildpack_id!("heroku/procfile"),
+                dir: PathBuf::from("/project/buildpacks/procfile"),
+                version: "1.1.0".to_string(),
+                release_date: NaiveDate::from_ymd_opt(2023, 6, 1),
+            },
+        ];
+
+        repair_release_state(&mut release_state_file, &buildpacks, "v{version}", |tag| {
+            (tag == "v1.1.0").then(|| "1b2c3d".to_string())
+        })
+        .unwrap();
+
+        assert_eq!(
+            release_state_file.document.to_string(),
+            r#"# maintained by prepare-release
+[buildpacks."heroku/nodejs"]
+version = "1.1.0"
+date = "2023-06-01"
+sha = "1b2c3d"
+
+[buildpacks."heroku/nodejs-engine"]
+version = "2.0.0"
+date = "2023-06-01"
+
+[buildpacks."heroku/procfile"]
+version = "1.1.0"
+date = "2023-06-01"
+sha = "1b2c3d"
+"#
+        );
+        assert_eq!(
+            render_json(&get_release_records(&release_state_file).unwrap()),
+            r#"{"heroku/nodejs":{"date":"2023-06-01","sha":"1b2c3d","version":"1.1.0"},"heroku/nodejs-engine":{"date":"2023-06-01","sha":null,"version":"2.0.0"},"heroku/procfile":{"date":"2023-06-01","sha":"1b2c3d","version":"1.1.0"}}"#
+        );
+    }
+}
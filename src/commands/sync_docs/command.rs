@@ -0,0 +1,213 @@
+use crate::commands::sync_docs::errors::Error;
+use clap::Parser;
+use languages_github_actions::buildpack::BuildpackDiscoveryArgs;
+use languages_github_actions::log;
+use languages_github_actions::patch::{apply_patches, Patch};
+use languages_github_actions::versioning::copy_version;
+use languages_github_actions::write;
+use lazy_static::lazy_static;
+use libcnb_data::buildpack::{BuildpackId, BuildpackVersion};
+use libcnb_package::read_buildpack_data;
+use markdown::mdast::Node;
+use markdown::{to_mdast, ParseOptions};
+use regex::Regex;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Updates buildpack version references in the fenced code blocks of markdown files", long_about = None)]
+pub(crate) struct SyncDocsArgs {
+    #[arg(long, value_delimiter = ',', num_args = 1..)]
+    pub(crate) files: Vec<PathBuf>,
+    #[command(flatten)]
+    pub(crate) discovery: BuildpackDiscoveryArgs,
+}
+
+pub(crate) fn execute(args: SyncDocsArgs) -> Result<()> {
+    let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
+
+    let buildpack_versions = args
+        .discovery
+        .find_buildpack_dirs(&current_dir, &[current_dir.join("target")])
+        .map_err(|e| Error::FindingBuildpacks(current_dir.clone(), e))?
+        .into_iter()
+        .map(|dir| {
+            read_buildpack_data(&dir)
+                .map_err(Error::ReadingBuildpackData)
+                .map(|data| {
+                    let buildpack = data.buildpack_descriptor.buildpack();
+                    (buildpack.id.clone(), copy_version(&buildpack.version))
+                })
+        })
+        .collect::<Result<HashMap<_, _>>>()?;
+
+    let markdown_files = if args.files.is_empty() {
+        find_markdown_files(&current_dir)
+            .map_err(|e| Error::FindingMarkdownFiles(current_dir.clone(), e))?
+    } else {
+        args.files
+            .into_iter()
+            .map(|file| current_dir.join(file))
+            .collect()
+    };
+
+    for markdown_file in markdown_files {
+        let contents = std::fs::read_to_string(&markdown_file)
+            .map_err(|e| Error::ReadingMarkdown(markdown_file.clone(), e))?;
+
+        let new_contents = update_buildpack_references(&contents, &buildpack_versions)
+            .map_err(|e| Error::ParsingMarkdown(markdown_file.clone(), e))?;
+
+        if new_contents != contents {
+            write::write_file(&markdown_file, new_contents)
+                .map_err(|e| Error::WritingMarkdown(markdown_file.clone(), e))?;
+
+            log!(
+                path = markdown_file;
+                "✅️ Updated buildpack references: {}",
+                markdown_file.display()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn find_markdown_files(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut markdown_files = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_ignored = path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .filter(|name| name.starts_with('.') || name == "target" || name == "node_modules")
+            .is_some();
+        if is_ignored {
+            continue;
+        }
+        if path.is_dir() {
+            markdown_files.extend(find_markdown_files(&path)?);
+        } else if path.extension().filter(|ext| *ext == "md").is_some() {
+            markdown_files.push(path);
+        }
+    }
+    markdown_files.sort();
+    Ok(markdown_files)
+}
+
+fn update_buildpack_references(
+    contents: &str,
+    buildpack_versions: &HashMap<BuildpackId, BuildpackVersion>,
+) -> std::result::Result<String, String> {
+    lazy_static! {
+        static ref BUILDPACK_REFERENCE: Regex =
+            Regex::new(r"([a-zA-Z0-9.\-]+/[a-zA-Z0-9.\-/]+)@(\d+\.\d+\.\d+)")
+                .expect("Should be a valid regex");
+    }
+
+    let mut code_blocks = vec![];
+    collect_code_blocks(
+        &to_mdast(contents, &ParseOptions::default())?,
+        &mut code_blocks,
+    );
+
+    let mut patches = vec![];
+    for code_block in code_blocks {
+        for captures in BUILDPACK_REFERENCE.captures_iter(&contents[code_block.clone()]) {
+            let version_match = captures.get(2).expect("Version group should be present");
+            let released_version = buildpack_versions
+                .iter()
+                .find(|(id, _)| id.as_str() == &captures[1])
+                .map(|(_, version)| version.to_string());
+            if let Some(released_version) = released_version {
+                if released_version != version_match.as_str() {
+                    let start = code_block.start + version_match.start();
+                    let end = code_block.start + version_match.end();
+                    patches.push(Patch::replace(start..end, released_version));
+                }
+            }
+        }
+    }
+
+    apply_patches(contents, patches).map_err(|e| e.to_string())
+}
+
+fn collect_code_blocks(node: &Node, code_blocks: &mut Vec<Range<usize>>) {
+    if let Node::Code(_) = node {
+        if let Some(position) = node.position() {
+            code_blocks.push(position.start.offset..position.end.offset);
+        }
+    } else if let Some(children) = node.children() {
+        for child in children {
+            collect_code_blocks(child, code_blocks);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::sync_docs::command::update_buildpack_references;
+    use libcnb_data::buildpack::BuildpackVersion;
+    use libcnb_data::buildpack_id;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_update_buildpack_references() {
+        let buildpack_versions = HashMap::from([
+            (
+                buildpack_id!("heroku/nodejs"),
+                BuildpackVersion {
+                    major: 1,
+                    minor: 2,
+                    patch: 4,
+                },
+            ),
+            (
+                buildpack_id!("heroku/nodejs-npm"),
+                BuildpackVersion {
+                    major: 1,
+                    minor: 2,
+                    patch: 4,
+                },
+            ),
+        ]);
+
+        let contents = r#"# Usage
+
+Outside of a code block heroku/nodejs@1.2.3 is left alone.
+
+```shell
+pack build my-app --buildpack heroku/nodejs@1.2.3
+pack build my-app --buildpack heroku/nodejs-npm@1.0.0 --buildpack heroku/procfile@2.0.0
+```
+
+- In a list:
+
+  ```
+  heroku/nodejs@0.0.1
+  ```
+"#;
+
+        assert_eq!(
+            update_buildpack_references(contents, &buildpack_versions).unwrap(),
+            r#"# Usage
+
+Outside of a code block heroku/nodejs@1.2.3 is left alone.
+
+```shell
+pack build my-app --buildpack heroku/nodejs@1.2.4
+pack build my-app --buildpack heroku/nodejs-npm@1.2.4 --buildpack heroku/procfile@2.0.0
+```
+
+- In a list:
+
+  ```
+  heroku/nodejs@1.2.4
+  ```
+"#
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::buildpack::BuildpackDiscoveryError;
+use libcnb_package::ReadBuildpackDataError;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    GetCurrentDir(std::io::Error),
+    FindingBuildpacks(PathBuf, BuildpackDiscoveryError),
+    ReadingBuildpackData(ReadBuildpackDataError),
+    FindingMarkdownFiles(PathBuf, std::io::Error),
+    ReadingMarkdown(PathBuf, std::io::Error),
+    ParsingMarkdown(PathBuf, String),
+    WritingMarkdown(PathBuf, std::io::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::GetCurrentDir(error) => {
+                write!(f, "Failed to get current directory\nError: {error}")
+            }
+
+            Error::FindingBuildpacks(path, error) => {
+                write!(
+                    f,
+                    "Could not find buildpacks\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::ReadingBuildpackData(error) => match error {
+                ReadBuildpackDataError::ReadingBuildpack { path, source } => {
+                    write!(
+                        f,
+                        "Failed to read buildpack\nPath: {}\nError: {source}",
+                        path.display()
+                    )
+                }
+                ReadBuildpackDataError::ParsingBuildpack { path, source } => {
+                    write!(
+                        f,
+                        "Failed to parse buildpack\nPath: {}\nError: {source}",
+                        path.display()
+                    )
+                }
+            },
+
+            Error::FindingMarkdownFiles(path, error) => {
+                write!(
+                    f,
+                    "I/O error while finding markdown files\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::ReadingMarkdown(path, error) => {
+                write!(
+                    f,
+                    "Could not read markdown file\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::ParsingMarkdown(path, error) => {
+                write!(
+                    f,
+                    "Could not parse markdown file\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::WritingMarkdown(path, error) => {
+                write!(
+                    f,
+                    "Could not write markdown file\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::GetCurrentDir(_)
+            | Error::FindingMarkdownFiles(_, _)
+            | Error::ReadingMarkdown(_, _)
+            | Error::WritingMarkdown(_, _) => ErrorCategory::Io,
+            Error::FindingBuildpacks(_, error) => error.category(),
+            Error::ReadingBuildpackData(error) => error.category(),
+            Error::ParsingMarkdown(_, _) => ErrorCategory::Parse,
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use crate::commands::sync_meta_buildpacks::errors::Error;
+use clap::Parser;
+use languages_github_actions::buildpack::{
+    get_buildpack_id, get_buildpack_version, read_buildpack_file, sync_buildpack_order_versions,
+    BuildpackDiscoveryArgs,
+};
+use languages_github_actions::git::WorktreeGuardArgs;
+use languages_github_actions::github::actions;
+use languages_github_actions::github::actions::OutputFormat;
+use languages_github_actions::log;
+use languages_github_actions::write;
+use std::collections::HashMap;
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Updates the order group versions of each composite buildpack to match the buildpacks in the project", long_about = None)]
+pub(crate) struct SyncMetaBuildpacksArgs {
+    #[arg(long)]
+    pub(crate) check: bool,
+    #[arg(long, value_enum, default_value_t)]
+    pub(crate) output_format: OutputFormat,
+    #[command(flatten)]
+    pub(crate) worktree: WorktreeGuardArgs,
+    #[command(flatten)]
+    pub(crate) discovery: BuildpackDiscoveryArgs,
+}
+
+pub(crate) fn execute(args: SyncMetaBuildpacksArgs) -> Result<()> {
+    let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
+
+    args.worktree.check(&current_dir).map_err(Error::Git)?;
+
+    let buildpack_files = args
+        .discovery
+        .find_buildpack_dirs(&current_dir, &[current_dir.join("target")])
+        .map_err(|e| Error::FindingBuildpacks(current_dir.clone(), e))?
+        .into_iter()
+        .map(|dir| read_buildpack_file(dir.join("buildpack.toml")).map_err(Error::BuildpackFile))
+        .collect::<Result<Vec<_>>>()?;
+
+    let versions = buildpack_files
+        .iter()
+        .map(|buildpack_file| {
+            Ok((
+                get_buildpack_id(buildpack_file).map_err(Error::BuildpackFile)?,
+                get_buildpack_version(buildpack_file).map_err(Error::BuildpackFile)?,
+            ))
+        })
+        .collect::<Result<HashMap<_, _>>>()?;
+
+    let mut drifted_buildpacks = vec![];
+    let mut modified_files = vec![];
+
+    for mut buildpack_file in buildpack_files {
+        let drifted = sync_buildpack_order_versions(&mut buildpack_file, &versions)
+            .map_err(Error::BuildpackFile)?;
+
+        if drifted.is_empty() {
+            continue;
+        }
+
+        let path = buildpack_file.path;
+        for drift in &drifted {
+            log!(
+                path = path;
+                "⚠️ {} is pinned to {} instead of {}: {}",
+                drift.buildpack_id,
+                drift.pinned,
+                drift.version,
+                path.display()
+            );
+        }
+
+        if args.check {
+            drifted_buildpacks.push((path, drifted));
+        } else {
+            write::write_file(&path, buildpack_file.document.to_string())
+                .map_err(|e| Error::WritingBuildpack(path.clone(), e))?;
+            log!(path = path; "✅️ Synced order group versions: {}", path.display());
+            modified_files.push(
+                path.strip_prefix(&current_dir)
+                    .unwrap_or(&path)
+                    .display()
+                    .to_string(),
+            );
+        }
+    }
+
+    if !drifted_buildpacks.is_empty() {
+        Err(Error::DriftedBuildpacks(drifted_buildpacks))?;
+    }
+
+    actions::set_output(
+        "modified_files",
+        actions::format_list_output(&modified_files, &args.output_format),
+    )
+    .map_err(Error::SetActionOutput)?;
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::buildpack::{
+    BuildpackDiscoveryError, BuildpackFileError, OrderVersionDrift,
+};
+use languages_github_actions::git::GitError;
+use languages_github_actions::github::actions::SetOutputError;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    GetCurrentDir(io::Error),
+    Git(GitError),
+    FindingBuildpacks(PathBuf, BuildpackDiscoveryError),
+    BuildpackFile(BuildpackFileError),
+    WritingBuildpack(PathBuf, io::Error),
+    DriftedBuildpacks(Vec<(PathBuf, Vec<OrderVersionDrift>)>),
+    SetActionOutput(SetOutputError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::GetCurrentDir(error) => {
+                write!(f, "Failed to get current directory\nError: {error}")
+            }
+
+            Error::Git(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::FindingBuildpacks(path, error) => {
+                write!(
+                    f,
+                    "Could not find buildpacks\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::BuildpackFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::WritingBuildpack(path, error) => {
+                write!(
+                    f,
+                    "Could not write buildpack\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::DriftedBuildpacks(drifted_buildpacks) => {
+                write!(
+                    f,
+                    "The following composite buildpacks pin versions that differ from the project:\n{}",
+                    drifted_buildpacks
+                        .iter()
+                        .flat_map(|(path, drifted)| {
+                            drifted.iter().map(move |drift| {
+                                format!(
+                                    "• {} is pinned to {} instead of {} in {}",
+                                    drift.buildpack_id,
+                                    drift.pinned,
+                                    drift.version,
+                                    path.display()
+                                )
+                            })
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            }
+
+            Error::SetActionOutput(set_output_error) => match set_output_error {
+                SetOutputError::Opening(error) | SetOutputError::Writing(error) => {
+                    write!(f, "Could not write action output\nError: {error}")
+                }
+            },
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::Git(error) => error.category(),
+            Error::GetCurrentDir(_) | Error::WritingBuildpack(_, _) => ErrorCategory::Io,
+            Error::FindingBuildpacks(_, error) => error.category(),
+            Error::BuildpackFile(error) => error.category(),
+            Error::DriftedBuildpacks(_) => ErrorCategory::Validation,
+            Error::SetActionOutput(error) => error.category(),
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use crate::commands::update_action_pins::errors::Error;
+use clap::Parser;
+use glob::glob;
+use languages_github_actions::git::WorktreeGuardArgs;
+use languages_github_actions::github::actions;
+use languages_github_actions::github::actions::OutputFormat;
+use languages_github_actions::log;
+use languages_github_actions::patch::{apply_patches, Patch, PatchError};
+use languages_github_actions::write;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Updates the pinned version of an action referenced from GitHub workflows", long_about = None)]
+pub(crate) struct UpdateActionPinsArgs {
+    #[arg(long, required = true)]
+    pub(crate) action_version: String,
+    #[arg(long)]
+    pub(crate) sha: Option<String>,
+    #[arg(long, default_value = "heroku/languages-github-actions")]
+    pub(crate) action_repository: String,
+    #[arg(long = "workflow-pattern", default_values = [".github/workflows/*.yml", ".github/workflows/*.yaml"])]
+    pub(crate) workflow_patterns: Vec<String>,
+    #[arg(long, value_enum, default_value_t)]
+    pub(crate) output_format: OutputFormat,
+    #[command(flatten)]
+    pub(crate) worktree: WorktreeGuardArgs,
+}
+
+pub(crate) fn execute(args: UpdateActionPinsArgs) -> Result<()> {
+    let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
+
+    args.worktree.check(&current_dir).map_err(Error::Git)?;
+
+    if let Some(sha) = &args.sha {
+        if !is_commit_sha(sha) {
+            Err(Error::InvalidSha(sha.clone()))?;
+        }
+    }
+
+    let action_reference = action_reference_regex(&args.action_repository);
+
+    let mut workflow_files = vec![];
+    for pattern in &args.workflow_patterns {
+        workflow_files.extend(find_workflow_files(&current_dir, pattern)?);
+    }
+    workflow_files.sort();
+    workflow_files.dedup();
+
+    let mut modified_files = vec![];
+    for workflow_file in workflow_files {
+        let contents = std::fs::read_to_string(&workflow_file)
+            .map_err(|e| Error::ReadingWorkflow(workflow_file.clone(), e))?;
+
+        let new_contents = update_action_references(
+            &contents,
+            &action_reference,
+            &args.action_version,
+            args.sha.as_deref(),
+        )
+        .map_err(|e| Error::UpdatingWorkflow(workflow_file.clone(), e))?;
+
+        if new_contents != contents {
+            write::write_file(&workflow_file, new_contents)
+                .map_err(|e| Error::WritingWorkflow(workflow_file.clone(), e))?;
+
+            log!(
+                path = workflow_file;
+                "✅️ Updated {} to {}: {}",
+                args.action_repository,
+                args.action_version,
+                workflow_file.display()
+            );
+
+            modified_files.push(
+                workflow_file
+                    .strip_prefix(&current_dir)
+                    .unwrap_or(&workflow_file)
+                    .display()
+                    .to_string(),
+            );
+        }
+    }
+
+    if modified_files.is_empty() {
+        log!(
+            "ℹ️ No workflow references to {} needed updating",
+            args.action_repository
+        );
+    }
+
+    actions::set_output(
+        "modified_files",
+        actions::format_list_output(&modified_files, &args.output_format),
+    )
+    .map_err(Error::SetActionOutput)?;
+
+    Ok(())
+}
+
+fn find_workflow_files(dir: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+    let pattern = dir.join(pattern).to_string_lossy().to_string();
+    glob(&pattern)
+        .map_err(|e| Error::InvalidWorkflowPattern(pattern.clone(), e))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(Error::FindingWorkflows)
+}
+
+fn is_commit_sha(value: &str) -> bool {
+    value.len() == 40 && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+// matches `uses: owner/repo(/path)?@ref # comment` lines, repository names are case-insensitive
+// on GitHub so `Heroku/Languages-GitHub-Actions` is matched as well
+fn action_reference_regex(action_repository: &str) -> Regex {
+    Regex::new(&format!(
+        r#"(?m)^[ \t]*(?:-[ \t]+)?uses:[ \t]*["']?(?i:{})(?:/[^@\s"'#]*)?@(?P<ref>[^\s"'#]+)["']?(?P<comment>[ \t]+#[^\r\n]*)?"#,
+        regex::escape(action_repository)
+    ))
+    .expect("Should be a valid regex")
+}
+
+// when pinning to a commit sha the version is kept in a trailing comment so it stays readable,
+// a stale version comment is dropped when pinning back to a tag and other comments are left alone
+fn update_action_references(
+    contents: &str,
+    action_reference: &Regex,
+    version: &str,
+    sha: Option<&str>,
+) -> std::result::Result<String, PatchError> {
+    lazy_static! {
+        static ref VERSION_COMMENT: Regex =
+            Regex::new(r"^#\s*v?\d+(?:\.\d+)*\S*$").expect("Should be a valid regex");
+    }
+
+    let mut patches = vec![];
+    for captures in action_reference.captures_iter(contents) {
+        let ref_match = captures.name("ref").expect("Ref group should be present");
+        let new_ref = sha.unwrap_or(version);
+        if ref_match.as_str() != new_ref {
+            patches.push(Patch::replace(ref_match.range(), new_ref));
+        }
+
+        let version_comment = captures
+            .name("comment")
+            .filter(|comment| VERSION_COMMENT.is_match(comment.as_str().trim()));
+        let new_comment = format!(" # {version}");
+        match (sha, captures.name("comment"), version_comment) {
+            (Some(_), _, Some(comment)) if comment.as_str() != new_comment => {
+                patches.push(Patch::replace(comment.range(), new_comment));
+            }
+            (Some(_), None, _) => {
+                let reference = captures.get(0).expect("Match should be present");
+                patches.push(Patch::insert(reference.end(), new_comment));
+            }
+            (None, _, Some(comment)) => {
+                patches.push(Patch::replace(comment.range(), ""));
+            }
+            _ => {}
+        }
+    }
+
+    apply_patches(contents, patches)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::update_action_pins::command::{
+        action_reference_regex, update_action_references,
+    };
+
+    const WORKFLOW: &str = r#"name: Release
+on: workflow_dispatch
+jobs:
+  prepare:
+    uses: heroku/languages-github-actions/.github/workflows/_buildpacks-release.yml@v1.0.0
+  release:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v4
+      - uses: "heroku/languages-github-actions/.github/actions/run-release@v1.0.0"
+      - name: Update builder
+        uses: Heroku/languages-github-actions/.github/actions/update-builder@0123456789abcdef0123456789abcdef01234567 # v0.9.0
+      - uses: heroku/languages-github-actions-extra@v1.0.0
+"#;
+
+    #[test]
+    fn test_update_action_references_to_version() {
+        let action_reference = action_reference_regex("heroku/languages-github-actions");
+        assert_eq!(
+            update_action_references(WORKFLOW, &action_reference, "v1.1.0", None).unwrap(),
+            r#"name: Release
+on: workflow_dispatch
+jobs:
+  prepare:
+    uses: heroku/languages-github-actions/.github/workflows/_buildpacks-release.yml@v1.1.0
+  release:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v4
+      - uses: "heroku/languages-github-actions/.github/actions/run-release@v1.1.0"
+      - name: Update builder
+        uses: Heroku/languages-github-actions/.github/actions/update-builder@v1.1.0
+      - uses: heroku/languages-github-actions-extra@v1.0.0
+"#
+        );
+    }
+
+    #[test]
+    fn test_update_action_references_to_sha() {
+        let action_reference = action_reference_regex("heroku/languages-github-actions");
+        let sha = "fedcba9876543210fedcba9876543210fedcba98";
+        let updated =
+            update_action_references(WORKFLOW, &action_reference, "v1.1.0", Some(sha)).unwrap();
+        assert!(updated.contains(&format!("_buildpacks-release.yml@{sha} # v1.1.0\n")));
+        assert!(updated.contains(&format!("run-release@{sha}\" # v1.1.0\n")));
+        assert!(updated.contains(&format!("update-builder@{sha} # v1.1.0\n")));
+        assert!(updated.contains("- uses: actions/checkout@v4\n"));
+        assert!(updated.contains("- uses: heroku/languages-github-actions-extra@v1.0.0\n"));
+        assert_eq!(
+            update_action_references(&updated, &action_reference, "v1.1.0", Some(sha)).unwrap(),
+            updated
+        );
+    }
+}
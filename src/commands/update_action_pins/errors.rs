@@ -0,0 +1,98 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::git::GitError;
+use languages_github_actions::github::actions::SetOutputError;
+use languages_github_actions::patch::PatchError;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    GetCurrentDir(std::io::Error),
+    Git(GitError),
+    InvalidSha(String),
+    InvalidWorkflowPattern(String, glob::PatternError),
+    FindingWorkflows(glob::GlobError),
+    ReadingWorkflow(PathBuf, std::io::Error),
+    UpdatingWorkflow(PathBuf, PatchError),
+    WritingWorkflow(PathBuf, std::io::Error),
+    SetActionOutput(SetOutputError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::GetCurrentDir(error) => {
+                write!(f, "Could not get the current directory\nError: {error}")
+            }
+
+            Error::Git(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::InvalidSha(value) => {
+                write!(
+                    f,
+                    "The sha argument must be a full 40 character commit sha\nValue: {value}"
+                )
+            }
+
+            Error::InvalidWorkflowPattern(pattern, error) => {
+                write!(f, "Invalid workflow pattern `{pattern}`\nError: {error}")
+            }
+
+            Error::FindingWorkflows(error) => {
+                write!(
+                    f,
+                    "I/O error while finding workflows\nPath: {}\nError: {}",
+                    error.path().display(),
+                    error.error()
+                )
+            }
+
+            Error::ReadingWorkflow(path, error) => {
+                write!(
+                    f,
+                    "Could not read workflow\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::UpdatingWorkflow(path, error) => {
+                write!(
+                    f,
+                    "Could not update action references in workflow\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::WritingWorkflow(path, error) => {
+                write!(
+                    f,
+                    "Could not write workflow\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::SetActionOutput(set_output_error) => match set_output_error {
+                SetOutputError::Opening(error) | SetOutputError::Writing(error) => {
+                    write!(f, "Could not write action output\nError: {error}")
+                }
+            },
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::Git(error) => error.category(),
+            Error::GetCurrentDir(_)
+            | Error::FindingWorkflows(_)
+            | Error::ReadingWorkflow(_, _)
+            | Error::WritingWorkflow(_, _) => ErrorCategory::Io,
+            Error::InvalidSha(_) | Error::InvalidWorkflowPattern(_, _) => ErrorCategory::Config,
+            Error::UpdatingWorkflow(_, _) => ErrorCategory::Parse,
+            Error::SetActionOutput(error) => error.category(),
+        }
+    }
+}
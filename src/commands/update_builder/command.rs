@@ -1,9 +1,28 @@
 use crate::update_builder::errors::Error;
 use clap::Parser;
+use glob::glob;
+use languages_github_actions::builder::{
+    add_buildpack_to_builder, get_builder_buildpack_uris, get_builder_buildpack_versions,
+    pin_builder_buildpack_digests, read_builder_channels, read_builder_file, render_buildpack_uri,
+    set_builder_buildpack_optional, set_builder_last_updated,
+    update_builder_contents_with_buildpack, BuilderChannel, BuilderChannels, BuilderFile,
+};
+use languages_github_actions::cancellation::check_cancelled;
+use languages_github_actions::changelog::{insert_unreleased_change, ChangelogLocationArgs};
+use languages_github_actions::clock::{Clock, SystemClock};
+use languages_github_actions::git::WorktreeGuardArgs;
+use languages_github_actions::github::actions;
+use languages_github_actions::github::actions::{AnnotationProperties, OutputFormat};
+use languages_github_actions::log;
+use languages_github_actions::oci::{image_exists, DigestCache, ImageReference};
+use languages_github_actions::release_manifest::read_release_manifest;
+use languages_github_actions::versioning::compare_versions;
+use languages_github_actions::write::{with_rollback, WrittenFiles};
 use libcnb_data::buildpack::{BuildpackId, BuildpackVersion};
-use std::path::PathBuf;
-use std::str::FromStr;
-use toml_edit::{value, Document};
+use serde_json::json;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use uriparse::URIReference;
 
 type Result<T> = std::result::Result<T, Error>;
@@ -11,203 +30,849 @@ type Result<T> = std::result::Result<T, Error>;
 #[derive(Parser, Debug)]
 #[command(author, version, about = "Updates all references to a buildpack in heroku/builder for the given list of builders", long_about = None)]
 pub(crate) struct UpdateBuilderArgs {
-    #[arg(long)]
-    pub(crate) buildpack_id: BuildpackId,
-    #[arg(long)]
-    pub(crate) buildpack_version: String,
-    #[arg(long)]
-    pub(crate) buildpack_uri: String,
-    #[arg(long, required = true, value_delimiter = ',', num_args = 1..)]
+    #[arg(long, required_unless_present = "manifest")]
+    pub(crate) buildpack_id: Option<BuildpackId>,
+    #[arg(long, required_unless_present = "manifest")]
+    pub(crate) buildpack_version: Option<String>,
+    #[arg(long, required_unless_present = "buildpack_uri_template")]
+    pub(crate) buildpack_uri: Option<String>,
+    #[arg(long, conflicts_with = "buildpack_uri")]
+    pub(crate) buildpack_uri_template: Option<String>,
+    #[arg(long, requires = "buildpack_uri_template")]
+    pub(crate) buildpack_digest: Option<String>,
+    #[arg(long, conflicts_with_all = ["buildpack_id", "buildpack_version", "buildpack_digest", "create_entry"], requires = "buildpack_uri_template")]
+    pub(crate) manifest: Option<PathBuf>,
+    #[arg(long, required_unless_present_any = ["all_builders", "channel"], value_delimiter = ',', num_args = 1..)]
     pub(crate) builders: Vec<String>,
+    #[arg(long, conflicts_with = "builders")]
+    pub(crate) all_builders: bool,
+    #[arg(long, default_value = "builders/*/builder.toml")]
+    pub(crate) builder_pattern: String,
+    #[arg(long, value_enum)]
+    pub(crate) channel: Option<BuilderChannel>,
+    #[arg(long, requires = "channel", default_value = "channels.toml")]
+    pub(crate) channels_file: PathBuf,
     #[arg(long, required = true)]
     pub(crate) path: String,
+    #[arg(long)]
+    pub(crate) verify_image: bool,
+    #[arg(long)]
+    pub(crate) pin_digests: bool,
+    #[arg(long, requires = "pin_digests", default_value_t = 8)]
+    pub(crate) registry_concurrency: usize,
+    #[arg(long, requires = "pin_digests")]
+    pub(crate) digest_cache: Option<PathBuf>,
+    #[arg(long)]
+    pub(crate) fail_on_no_match: bool,
+    #[arg(long)]
+    pub(crate) allow_downgrade: bool,
+    #[arg(long)]
+    pub(crate) optional: Option<bool>,
+    #[arg(long, conflicts_with = "all_builders")]
+    pub(crate) create_entry: bool,
+    #[arg(long, requires = "create_entry")]
+    pub(crate) order_index: Option<usize>,
+    #[arg(long)]
+    pub(crate) update_timestamp: bool,
+    #[arg(long)]
+    pub(crate) update_changelog: bool,
+    #[arg(long, value_enum, default_value_t)]
+    pub(crate) output_format: OutputFormat,
+    #[command(flatten)]
+    pub(crate) worktree: WorktreeGuardArgs,
+    #[command(flatten)]
+    pub(crate) changelog: ChangelogLocationArgs,
+}
+
+struct BuildpackUpdate {
+    id: BuildpackId,
+    version: BuildpackVersion,
+    uri: URIReference<'static>,
 }
 
-struct BuilderFile {
-    path: PathBuf,
-    document: Document,
+// the builders selected for the update split by whether they reference any of the buildpacks, along
+// with what they contained beforehand so unchanged builders aren't rewritten
+#[derive(Default)]
+struct MatchedBuilders<'a> {
+    updated: Vec<BuilderFile>,
+    skipped: Vec<PathBuf>,
+    original_contents: HashMap<PathBuf, String>,
+    // each buildpack an updated builder references with the versions it was pinned to before
+    matches: HashMap<PathBuf, Vec<(&'a BuildpackUpdate, Vec<String>)>>,
+}
+
+impl<'a> MatchedBuilders<'a> {
+    fn is_changed(&self, builder_file: &BuilderFile) -> bool {
+        self.original_contents.get(&builder_file.path) != Some(&builder_file.document.to_string())
+    }
+
+    fn matches(&self, path: &Path) -> &[(&'a BuildpackUpdate, Vec<String>)] {
+        self.matches
+            .get(path)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Default)]
+struct UpdateOutputs {
+    modified_files: Vec<String>,
+    builders_updated: Vec<String>,
+    builders_skipped: Vec<String>,
+    builders_changed: BTreeMap<String, bool>,
+    builder_channels: BTreeMap<String, String>,
 }
 
 pub(crate) fn execute(args: UpdateBuilderArgs) -> Result<()> {
-    let current_dir = std::env::current_dir()
-        .map_err(Error::GetCurrentDir)
-        .map(|dir| dir.join(PathBuf::from(args.path)))?;
+    execute_with_clock(args, &SystemClock)
+}
+
+pub(crate) fn execute_with_clock(args: UpdateBuilderArgs, clock: &dyn Clock) -> Result<()> {
+    let working_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
+    let current_dir = working_dir.join(PathBuf::from(&args.path));
+
+    args.worktree.check(&current_dir).map_err(Error::Git)?;
+
+    let updates = match &args.manifest {
+        Some(path) => {
+            let manifest = read_release_manifest(path).map_err(Error::ReleaseManifest)?;
+            manifest
+                .buildpacks
+                .into_iter()
+                .map(|buildpack| parse_buildpack_update(&args, buildpack.id, &buildpack.version))
+                .collect::<Result<Vec<_>>>()?
+        }
+        None => vec![parse_buildpack_update(
+            &args,
+            args.buildpack_id
+                .clone()
+                .expect("Should be required when no manifest is given"),
+            args.buildpack_version
+                .as_deref()
+                .expect("Should be required when no manifest is given"),
+        )?],
+    };
+
+    let channel = args
+        .channel
+        .map(|channel| {
+            read_builder_channels(current_dir.join(&args.channels_file))
+                .map(|channels| (channel, channels))
+                .map_err(Error::BuilderChannels)
+        })
+        .transpose()?;
+
+    let builder_files = select_builder_files(&args, &current_dir, channel.as_ref())?;
+
+    if args.verify_image {
+        for update in &updates {
+            check_cancelled().map_err(Error::Cancelled)?;
+            verify_image(&update.uri)?;
+        }
+    }
+
+    let mut matched = match_builders(&args, &updates, builder_files)?;
 
-    let buildpack_id = args.buildpack_id;
+    check_downgrades(&args, &updates, &matched)?;
 
-    let buildpack_uri = URIReference::try_from(args.buildpack_uri.as_str())
-        .map_err(|e| Error::InvalidBuildpackUri(args.buildpack_uri.clone(), e))?;
+    // when discovering builders, skipping those that don't use the buildpack is expected
+    let unmatched = if args.all_builders {
+        matched.updated.is_empty()
+    } else {
+        !matched.skipped.is_empty()
+    };
 
-    let buildpack_version = BuildpackVersion::try_from(args.buildpack_version.to_string())
-        .map_err(|e| Error::InvalidBuildpackVersion(args.buildpack_version, e))?;
+    if args.fail_on_no_match && unmatched {
+        Err(Error::UnmatchedBuilders(
+            updates.iter().map(|update| update.id.clone()).collect(),
+            matched.skipped.clone(),
+        ))?;
+    }
 
-    let builder_files = args
-        .builders
-        .iter()
-        .map(|builder| read_builder_file(current_dir.join(builder).join("builder.toml")))
+    if args.pin_digests {
+        let digest_cache = match &args.digest_cache {
+            Some(path) => DigestCache::load(working_dir.join(path)).map_err(Error::DigestCache)?,
+            None => DigestCache::default(),
+        };
+        let uris = matched
+            .updated
+            .iter()
+            .flat_map(get_builder_buildpack_uris)
+            .collect();
+        pin_builder_buildpack_digests(
+            &mut matched.updated,
+            uris,
+            args.registry_concurrency,
+            &digest_cache,
+        )
+        .map_err(Error::PinningDigests)?;
+        digest_cache.save().map_err(Error::DigestCache)?;
+    }
+
+    let changelog_edits = plan_builder_metadata(&args, &current_dir, clock, &mut matched)?;
+
+    // builders are either all written or left untouched, those already written are put back when
+    // one can't be written or the command is cancelled in between
+    let outputs = with_rollback(|written_files| {
+        write_builders(
+            &matched,
+            &changelog_edits,
+            channel.as_ref().map(|(channel, _)| *channel),
+            &working_dir,
+            written_files,
+        )
+    })?;
+
+    set_update_outputs(&outputs, &args.output_format, channel.is_some())
+}
+
+fn parse_buildpack_update(
+    args: &UpdateBuilderArgs,
+    id: BuildpackId,
+    version: &str,
+) -> Result<BuildpackUpdate> {
+    let version = BuildpackVersion::try_from(version.to_string())
+        .map_err(|e| Error::InvalidBuildpackVersion(version.to_string(), e))?;
+
+    let uri = match &args.buildpack_uri_template {
+        Some(template) => {
+            render_buildpack_uri(template, &id, &version, args.buildpack_digest.as_deref())
+                .map_err(|e| Error::BuildpackUriTemplate(template.clone(), e))?
+        }
+        None => args
+            .buildpack_uri
+            .clone()
+            .expect("Should be required when no URI template is given"),
+    };
+
+    let uri = URIReference::try_from(uri.as_str())
+        .map_err(|e| Error::InvalidBuildpackUri(uri.clone(), e))?
+        .into_owned();
+
+    Ok(BuildpackUpdate { id, version, uri })
+}
+
+fn select_builder_files(
+    args: &UpdateBuilderArgs,
+    current_dir: &Path,
+    channel: Option<&(BuilderChannel, BuilderChannels)>,
+) -> Result<Vec<BuilderFile>> {
+    let builders = expand_builder_patterns(current_dir, &args.builders)?;
+
+    let builder_paths = match channel {
+        _ if args.all_builders => find_builder_files(current_dir, &args.builder_pattern)?,
+        Some((channel, channels)) if builders.is_empty() => channels
+            .builders_in(*channel)
+            .into_iter()
+            .map(|builder| current_dir.join(builder).join("builder.toml"))
+            .collect(),
+        _ => builders
+            .iter()
+            .map(|builder| current_dir.join(builder).join("builder.toml"))
+            .collect(),
+    };
+
+    let builder_paths = match channel {
+        Some((channel, channels)) => {
+            filter_builders_by_channel(builder_paths, *channel, channels, current_dir)
+        }
+        None => builder_paths,
+    };
+
+    let builder_files = builder_paths
+        .into_iter()
+        .map(|path| read_builder_file(path).map_err(Error::BuilderFile))
         .collect::<Result<Vec<_>>>()?;
 
     if builder_files.is_empty() {
-        Err(Error::NoBuilderFiles(args.builders))?;
+        Err(Error::NoBuilderFiles(match channel {
+            Some((channel, _)) => vec![format!(
+                "{channel} channel of {}",
+                args.channels_file.display()
+            )],
+            None if args.all_builders => vec![args.builder_pattern.clone()],
+            None => builders,
+        }))?;
     }
 
+    Ok(builder_files)
+}
+
+fn match_builders<'a>(
+    args: &UpdateBuilderArgs,
+    updates: &'a [BuildpackUpdate],
+    builder_files: Vec<BuilderFile>,
+) -> Result<MatchedBuilders<'a>> {
+    let mut matched = MatchedBuilders::default();
+
     for mut builder_file in builder_files {
-        let new_contents = update_builder_contents_with_buildpack(
-            &mut builder_file,
-            &buildpack_id,
-            &buildpack_version,
-            &buildpack_uri,
-        )?;
-
-        std::fs::write(&builder_file.path, new_contents)
-            .map_err(|e| Error::WritingBuilder(builder_file.path.clone(), e))?;
-
-        eprintln!(
-            "✅️ Updated {buildpack_id} for builder: {}",
-            builder_file.path.display()
-        );
+        matched
+            .original_contents
+            .insert(builder_file.path.clone(), builder_file.document.to_string());
+
+        let mut matches = vec![];
+        for update in updates {
+            let buildpack_id = &update.id;
+            let previous_versions = get_builder_buildpack_versions(&builder_file, buildpack_id);
+
+            if args.create_entry
+                && add_buildpack_to_builder(
+                    &mut builder_file,
+                    buildpack_id,
+                    &update.version,
+                    &update.uri,
+                    args.order_index,
+                )
+                .map_err(Error::BuilderFile)?
+            {
+                log!(
+                    buildpack_id = buildpack_id, path = builder_file.path;
+                    "✅️ Added {buildpack_id} to builder: {}",
+                    builder_file.path.display()
+                );
+            }
+
+            match update_builder_contents_with_buildpack(
+                &mut builder_file,
+                buildpack_id,
+                &update.version,
+                &update.uri,
+            )
+            .map_err(Error::BuilderFile)?
+            {
+                Some(_) => {
+                    if let Some(optional) = args.optional {
+                        update_optional(&mut builder_file, buildpack_id, optional);
+                    }
+                    matches.push((update, previous_versions));
+                }
+                None => {
+                    log!(
+                        buildpack_id = buildpack_id, path = builder_file.path;
+                        "⚠️ Builder does not reference {buildpack_id}: {}",
+                        builder_file.path.display()
+                    );
+                }
+            }
+        }
+
+        if matches.is_empty() {
+            matched.skipped.push(builder_file.path);
+        } else {
+            matched.matches.insert(builder_file.path.clone(), matches);
+            matched.updated.push(builder_file);
+        }
+    }
+
+    Ok(matched)
+}
+
+// every buildpack is compared so all refused downgrades are logged, the first one is reported
+fn check_downgrades(
+    args: &UpdateBuilderArgs,
+    updates: &[BuildpackUpdate],
+    matched: &MatchedBuilders,
+) -> Result<()> {
+    let mut refused = vec![];
+    for update in updates {
+        let mut downgrades = vec![];
+        for builder_file in &matched.updated {
+            for (_, previous_versions) in matched
+                .matches(&builder_file.path)
+                .iter()
+                .filter(|(matched_update, _)| matched_update.id == update.id)
+            {
+                for existing_version in previous_versions {
+                    if is_downgrade(
+                        &update.id,
+                        existing_version,
+                        &update.version,
+                        &builder_file.path,
+                        args.allow_downgrade,
+                    ) {
+                        downgrades.push((builder_file.path.clone(), existing_version.clone()));
+                    }
+                }
+            }
+        }
+
+        if !downgrades.is_empty() {
+            refused.push(Error::Downgrade(
+                update.id.clone(),
+                update.version.to_string(),
+                downgrades,
+            ));
+        }
+    }
+
+    match refused.into_iter().next() {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+// the timestamp and changelog entries are only added to builders whose buildpacks changed, and the
+// changelog edits are planned up front so a changelog that can't be updated leaves everything as it was
+fn plan_builder_metadata(
+    args: &UpdateBuilderArgs,
+    current_dir: &Path,
+    clock: &dyn Clock,
+    matched: &mut MatchedBuilders,
+) -> Result<BTreeMap<PathBuf, String>> {
+    let timestamp = clock.now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+    let mut changelog_edits: BTreeMap<PathBuf, String> = BTreeMap::new();
+    let mut updated = std::mem::take(&mut matched.updated);
+
+    for builder_file in &mut updated {
+        if !matched.is_changed(builder_file) {
+            continue;
+        }
+
+        if args.update_timestamp {
+            set_builder_last_updated(builder_file, &timestamp).map_err(Error::BuilderFile)?;
+        }
+
+        if args.update_changelog {
+            let builder_dir = builder_file.path.parent().unwrap_or(current_dir);
+            let changelog_path = args
+                .changelog
+                .find_changelog(builder_dir, current_dir)
+                .map_err(Error::ChangelogFile)?;
+            let mut contents = match changelog_edits.get(&changelog_path) {
+                Some(contents) => contents.clone(),
+                None => std::fs::read_to_string(&changelog_path)
+                    .map_err(|e| Error::ReadingChangelog(changelog_path.clone(), e))?,
+            };
+            // a changelog outside the builder directory can be shared so entries name the builder
+            let builder = (changelog_path.parent() != Some(builder_dir))
+                .then(|| relative_path(builder_dir, current_dir));
+            for (update, previous_versions) in matched.matches(&builder_file.path) {
+                let entry = changelog_entry(
+                    &update.id,
+                    previous_versions,
+                    &update.version,
+                    builder.as_deref(),
+                );
+                contents = insert_unreleased_change(&contents, &entry)
+                    .map_err(|e| Error::AddingChangelogEntry(changelog_path.clone(), e))?;
+            }
+            changelog_edits.insert(changelog_path, contents);
+        }
+    }
+
+    matched.updated = updated;
+    Ok(changelog_edits)
+}
+
+fn write_builders(
+    matched: &MatchedBuilders,
+    changelog_edits: &BTreeMap<PathBuf, String>,
+    channel: Option<BuilderChannel>,
+    working_dir: &Path,
+    written_files: &mut WrittenFiles,
+) -> Result<UpdateOutputs> {
+    let mut outputs = UpdateOutputs::default();
+
+    for builder_file in &matched.updated {
+        let path = &builder_file.path;
+        outputs
+            .builders_updated
+            .push(relative_path(path, working_dir));
+
+        // the builder may already pin this exact version, rewriting it would only produce an empty commit
+        let changed = matched.is_changed(builder_file);
+        outputs
+            .builders_changed
+            .insert(relative_path(path, working_dir), changed);
+        if !changed {
+            for (update, _) in matched.matches(path) {
+                let buildpack_id = &update.id;
+                log!(
+                    buildpack_id = buildpack_id, path = path;
+                    "ℹ️ Builder already up to date with {buildpack_id}: {}",
+                    path.display()
+                );
+            }
+            continue;
+        }
+
+        check_cancelled().map_err(Error::Cancelled)?;
+        written_files
+            .write_if_changed(path, &builder_file.document.to_string())
+            .map_err(|e| Error::WritingBuilder(path.clone(), e))?;
+        outputs
+            .modified_files
+            .push(relative_path(path, working_dir));
+
+        if let Some(channel) = channel {
+            outputs
+                .builder_channels
+                .insert(relative_path(path, working_dir), channel.to_string());
+        }
+
+        for (update, previous_versions) in matched.matches(path) {
+            let buildpack_id = &update.id;
+            let buildpack_version = &update.version;
+            match channel {
+                Some(channel) => {
+                    log!(
+                        buildpack_id = buildpack_id, path = path;
+                        "✅️ Updated {buildpack_id} for builder in the {channel} channel: {}",
+                        path.display()
+                    );
+                }
+                None => {
+                    log!(
+                        buildpack_id = buildpack_id, path = path;
+                        "✅️ Updated {buildpack_id} for builder: {}",
+                        path.display()
+                    )
+                }
+            }
+
+            // listed in the workflow summary so each builder's change can be seen without the logs
+            let message = if previous_versions.is_empty() {
+                format!("Added {buildpack_id} {buildpack_version}")
+            } else {
+                format!(
+                    "{buildpack_id} {} → {buildpack_version}",
+                    previous_versions.join(", ")
+                )
+            };
+            actions::notice(
+                message,
+                &AnnotationProperties {
+                    title: Some(relative_path(path, working_dir)),
+                    file: Some(relative_path(path, working_dir)),
+                },
+            );
+        }
+    }
+
+    for (path, contents) in changelog_edits {
+        check_cancelled().map_err(Error::Cancelled)?;
+        written_files
+            .write_if_changed(path, contents)
+            .map_err(|e| Error::WritingChangelog(path.clone(), e))?;
+        log!(path = path; "✅️ Added changelog entry: {}", path.display());
+        outputs
+            .modified_files
+            .push(relative_path(path, working_dir));
+    }
+
+    for path in &matched.skipped {
+        let builder = relative_path(path, working_dir);
+        outputs.builders_changed.insert(builder.clone(), false);
+        outputs.builders_skipped.push(builder);
+    }
+
+    Ok(outputs)
+}
+
+fn set_update_outputs(
+    outputs: &UpdateOutputs,
+    output_format: &OutputFormat,
+    has_channel: bool,
+) -> Result<()> {
+    actions::set_output(
+        "modified_files",
+        actions::format_list_output(&outputs.modified_files, output_format),
+    )
+    .map_err(Error::SetActionOutput)?;
+
+    actions::set_output(
+        "builders_updated",
+        actions::format_list_output(&outputs.builders_updated, output_format),
+    )
+    .map_err(Error::SetActionOutput)?;
+
+    actions::set_output(
+        "builders_skipped",
+        actions::format_list_output(&outputs.builders_skipped, output_format),
+    )
+    .map_err(Error::SetActionOutput)?;
+
+    actions::set_output("changed", (!outputs.modified_files.is_empty()).to_string())
+        .map_err(Error::SetActionOutput)?;
+
+    actions::set_output(
+        "builders_changed",
+        json!(outputs.builders_changed).to_string(),
+    )
+    .map_err(Error::SetActionOutput)?;
+
+    if has_channel {
+        actions::set_output(
+            "builder_channels",
+            json!(outputs.builder_channels).to_string(),
+        )
+        .map_err(Error::SetActionOutput)?;
     }
 
     Ok(())
 }
 
-fn read_builder_file(path: PathBuf) -> Result<BuilderFile> {
-    let contents =
-        std::fs::read_to_string(&path).map_err(|e| Error::ReadingBuilder(path.clone(), e))?;
-    let document =
-        Document::from_str(&contents).map_err(|e| Error::ParsingBuilder(path.clone(), e))?;
-    Ok(BuilderFile { path, document })
+fn changelog_entry(
+    buildpack_id: &BuildpackId,
+    previous_versions: &[String],
+    buildpack_version: &BuildpackVersion,
+    builder: Option<&str>,
+) -> String {
+    let change = if previous_versions.is_empty() {
+        format!("Added `{buildpack_id}` `{buildpack_version}`")
+    } else {
+        format!(
+            "Updated `{buildpack_id}` from `{}` to `{buildpack_version}`",
+            previous_versions.join("`, `")
+        )
+    };
+    match builder {
+        Some(builder) => format!("{change} in `{builder}`."),
+        None => format!("{change}."),
+    }
 }
 
-fn update_builder_contents_with_buildpack(
-    builder_file: &mut BuilderFile,
+// logs how the pinned version compares to the new one, only refused downgrades return `true`
+pub(crate) fn is_downgrade(
     buildpack_id: &BuildpackId,
+    existing_version: &str,
     buildpack_version: &BuildpackVersion,
-    buildpack_uri: &URIReference,
-) -> Result<String> {
-    builder_file
-        .document
-        .get_mut("buildpacks")
-        .and_then(|value| value.as_array_of_tables_mut())
-        .unwrap_or(&mut toml_edit::ArrayOfTables::default())
-        .iter_mut()
-        .for_each(|buildpack| {
-            let matches_id = buildpack
-                .get("id")
-                .and_then(|item| item.as_str())
-                .filter(|value| value == &buildpack_id.as_str())
-                .is_some();
-            if matches_id {
-                buildpack["uri"] = value(buildpack_uri.to_string());
+    builder_path: &Path,
+    allow_downgrade: bool,
+) -> bool {
+    let builder_path = builder_path.display();
+    let existing = match BuildpackVersion::try_from(existing_version.to_string()) {
+        Ok(existing) => existing,
+        Err(_) => {
+            log!(
+                buildpack_id = buildpack_id;
+                "⚠️ Could not compare pinned version {existing_version} of {buildpack_id} to {buildpack_version}: {builder_path}"
+            );
+            return false;
+        }
+    };
+
+    match compare_versions(buildpack_version, &existing) {
+        Ordering::Less if allow_downgrade => {
+            log!(
+                buildpack_id = buildpack_id;
+                "⚠️ Downgrading {buildpack_id} from {existing} to {buildpack_version}: {builder_path}"
+            );
+            false
+        }
+        Ordering::Less => {
+            log!(
+                buildpack_id = buildpack_id;
+                "❌ Refusing to downgrade {buildpack_id} from {existing} to {buildpack_version}: {builder_path}"
+            );
+            true
+        }
+        Ordering::Equal => {
+            log!(
+                buildpack_id = buildpack_id;
+                "ℹ️ {buildpack_id} is already pinned to {existing}: {builder_path}"
+            );
+            false
+        }
+        Ordering::Greater => {
+            log!(
+                buildpack_id = buildpack_id;
+                "ℹ️ Upgrading {buildpack_id} from {existing} to {buildpack_version}: {builder_path}"
+            );
+            false
+        }
+    }
+}
+
+pub(crate) fn update_optional(
+    builder_file: &mut BuilderFile,
+    buildpack_id: &BuildpackId,
+    optional: bool,
+) {
+    if set_builder_buildpack_optional(builder_file, buildpack_id, optional) {
+        log!(
+            buildpack_id = buildpack_id, path = builder_file.path;
+            "✅️ Marked {buildpack_id} as {} in builder: {}",
+            if optional { "optional" } else { "required" },
+            builder_file.path.display()
+        );
+    }
+}
+
+// builders in other channels are left alone so they can receive the update in a later rollout
+fn filter_builders_by_channel(
+    builder_paths: Vec<PathBuf>,
+    channel: BuilderChannel,
+    channels: &BuilderChannels,
+    current_dir: &Path,
+) -> Vec<PathBuf> {
+    builder_paths
+        .into_iter()
+        .filter(|path| {
+            let builder_dir = path
+                .parent()
+                .map(|dir| dir.strip_prefix(current_dir).unwrap_or(dir))
+                .unwrap_or(path);
+            let builder_channel = channels.channel_of(builder_dir);
+            if builder_channel != channel {
+                log!(
+                    path = path;
+                    "⏭️ Builder is in the {builder_channel} channel: {}",
+                    path.display()
+                );
             }
-        });
-
-    let order_list = builder_file
-        .document
-        .get_mut("order")
-        .and_then(|value| value.as_array_of_tables_mut())
-        .ok_or(Error::BuilderMissingRequiredKey(
-            builder_file.path.clone(),
-            "order".to_string(),
-        ))?;
+            builder_channel == channel
+        })
+        .collect()
+}
+
+fn relative_path(path: &Path, working_dir: &Path) -> String {
+    path.strip_prefix(working_dir)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+fn find_builder_files(dir: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+    let pattern = dir.join(pattern).to_string_lossy().to_string();
+    let mut paths = glob(&pattern)
+        .map_err(|e| Error::InvalidBuilderPattern(pattern.clone(), e))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(Error::FindingBuilders)?;
+    paths.sort();
+    Ok(paths)
+}
 
-    for order in order_list.iter_mut() {
-        let group_list = order
-            .get_mut("group")
-            .and_then(|value| value.as_array_of_tables_mut())
-            .ok_or(Error::BuilderMissingRequiredKey(
-                builder_file.path.clone(),
-                "group".to_string(),
+// entries with glob characters (e.g.; `builder-*`) are expanded to the matching directories under
+// `dir` that contain a `builder.toml`, other entries are kept as given
+fn expand_builder_patterns(dir: &Path, builders: &[String]) -> Result<Vec<String>> {
+    let mut expanded = vec![];
+    for builder in builders {
+        if !builder.contains(['*', '?', '[']) {
+            if !expanded.contains(builder) {
+                expanded.push(builder.clone());
+            }
+            continue;
+        }
+
+        let pattern = dir.join(builder).to_string_lossy().to_string();
+        let matched_dirs = glob(&pattern)
+            .map_err(|e| Error::InvalidBuilderPattern(builder.clone(), e))?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(Error::FindingBuilders)?
+            .into_iter()
+            .filter(|path| path.is_dir())
+            .collect::<Vec<_>>();
+
+        let mut matched_builders = matched_dirs
+            .iter()
+            .filter(|path| path.join("builder.toml").is_file())
+            .map(|path| relative_path(path, dir))
+            .collect::<Vec<_>>();
+        matched_builders.sort();
+
+        if matched_builders.is_empty() {
+            Err(Error::UnmatchedBuilderPattern(
+                builder.clone(),
+                matched_dirs
+                    .iter()
+                    .map(|path| relative_path(path, dir))
+                    .collect(),
             ))?;
+        }
 
-        for group in group_list.iter_mut() {
-            let matches_id = group
-                .get("id")
-                .and_then(|item| item.as_str())
-                .filter(|value| value == &buildpack_id.as_str())
-                .is_some();
-            if matches_id {
-                group["version"] = value(buildpack_version.to_string());
+        log!(
+            "ℹ️ Pattern `{builder}` matched builders: {}",
+            matched_builders.join(", ")
+        );
+        for matched_builder in matched_builders {
+            if !expanded.contains(&matched_builder) {
+                expanded.push(matched_builder);
             }
         }
     }
+    Ok(expanded)
+}
+
+fn verify_image(buildpack_uri: &URIReference) -> Result<()> {
+    let uri = buildpack_uri.to_string();
 
-    Ok(builder_file.document.to_string())
+    let image = ImageReference::try_from(uri.as_str())
+        .map_err(|e| Error::VerifyingImage(uri.clone(), e))?;
+
+    if !image_exists(&image).map_err(|e| Error::VerifyingImage(uri.clone(), e))? {
+        Err(Error::ImageNotFound(uri))?;
+    }
+
+    log!("✅️ Verified image exists: {image}");
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod test {
-    use crate::commands::update_builder::command::{
-        update_builder_contents_with_buildpack, BuilderFile,
-    };
+    use crate::commands::update_builder::command::{changelog_entry, expand_builder_patterns};
+    use crate::commands::update_builder::errors::Error;
     use libcnb_data::buildpack::BuildpackVersion;
     use libcnb_data::buildpack_id;
-    use std::path::PathBuf;
-    use std::str::FromStr;
-    use toml_edit::Document;
-    use uriparse::URIReference;
+    use std::fs;
 
     #[test]
-    fn test_update_builder_contents_with_buildpack() {
-        let toml = r#"
-[[buildpacks]]
-  id = "heroku/java"
-  uri = "docker://docker.io/heroku/buildpack-java@sha256:21990393c93927b16f76c303ae007ea7e95502d52b0317ca773d4cd51e7a5682"
-
-[[buildpacks]]
-  id = "heroku/nodejs"
-  uri = "docker://docker.io/heroku/buildpack-nodejs@sha256:22ec91eebee2271b99368844f193c4bb3c6084201062f89b3e45179b938c3241"
-
-[[order]]
-  [[order.group]]
-    id = "heroku/nodejs"
-    version = "0.6.5"  
-
-[[order]]
-  [[order.group]]
-    id = "heroku/java"
-    version = "0.6.9"
-
-  [[order.group]]
-    id = "heroku/procfile"
-    version = "2.0.0"
-    optional = true
-"#;
-        let mut builder_file = BuilderFile {
-            path: PathBuf::from("/path/to/builder.toml"),
-            document: Document::from_str(toml).unwrap(),
-        };
+    fn test_expand_builder_patterns() {
+        let project_dir =
+            std::env::temp_dir().join(format!("expand-builder-patterns-{}", std::process::id()));
+        for dir in [
+            "builder-22",
+            "builder-20",
+            "builder-docs",
+            "salesforce-functions",
+        ] {
+            fs::create_dir_all(project_dir.join(dir)).unwrap();
+        }
+        for dir in ["builder-22", "builder-20", "salesforce-functions"] {
+            fs::write(project_dir.join(dir).join("builder.toml"), "").unwrap();
+        }
+
         assert_eq!(
-            update_builder_contents_with_buildpack(
-                &mut builder_file,
-                &buildpack_id!("heroku/java"),
-                &BuildpackVersion::try_from("0.6.10".to_string()).unwrap(),
-                &URIReference::try_from("docker://docker.io/heroku/buildpack-java@sha256:c6dd500be06a2a1e764c30359c5dd4f4955a98b572ef3095b2f6115cd8a87c99").unwrap()
-            ).unwrap(),
-            r#"
-[[buildpacks]]
-  id = "heroku/java"
-  uri = "docker://docker.io/heroku/buildpack-java@sha256:c6dd500be06a2a1e764c30359c5dd4f4955a98b572ef3095b2f6115cd8a87c99"
-
-[[buildpacks]]
-  id = "heroku/nodejs"
-  uri = "docker://docker.io/heroku/buildpack-nodejs@sha256:22ec91eebee2271b99368844f193c4bb3c6084201062f89b3e45179b938c3241"
-
-[[order]]
-  [[order.group]]
-    id = "heroku/nodejs"
-    version = "0.6.5"  
-
-[[order]]
-  [[order.group]]
-    id = "heroku/java"
-    version = "0.6.10"
-
-  [[order.group]]
-    id = "heroku/procfile"
-    version = "2.0.0"
-    optional = true
-"#
-        )
+            expand_builder_patterns(
+                &project_dir,
+                &["builder-*".to_string(), "salesforce-functions".to_string()]
+            )
+            .unwrap(),
+            vec!["builder-20", "builder-22", "salesforce-functions"]
+        );
+
+        match expand_builder_patterns(&project_dir, &["*-docs".to_string()]) {
+            Err(Error::UnmatchedBuilderPattern(pattern, matched_dirs)) => {
+                assert_eq!(pattern, "*-docs");
+                assert_eq!(matched_dirs, vec!["builder-docs"]);
+            }
+            result => panic!("Expected an unmatched pattern error, got {result:?}"),
+        }
+
+        fs::remove_dir_all(project_dir).unwrap();
+    }
+
+    #[test]
+    fn test_changelog_entry() {
+        let version = BuildpackVersion::try_from("1.1.0".to_string()).unwrap();
+        assert_eq!(
+            changelog_entry(
+                &buildpack_id!("heroku/nodejs"),
+                &["1.0.0".to_string()],
+                &version,
+                None
+            ),
+            "Updated `heroku/nodejs` from `1.0.0` to `1.1.0`."
+        );
+        assert_eq!(
+            changelog_entry(
+                &buildpack_id!("heroku/nodejs"),
+                &[],
+                &version,
+                Some("builder-22")
+            ),
+            "Added `heroku/nodejs` `1.1.0` in `builder-22`."
+        );
     }
 }
@@ -1,16 +1,44 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::builder::{
+    BuilderChannelsError, BuilderFileError, BuildpackUriTemplateError, PinDigestsError,
+};
+use languages_github_actions::cancellation::CancellationError;
+use languages_github_actions::changelog::{ChangelogError, ChangelogFileError};
+use languages_github_actions::git::GitError;
+use languages_github_actions::github::actions::SetOutputError;
+use languages_github_actions::oci::OciError;
+use languages_github_actions::release_manifest::ReleaseManifestError;
+use libcnb_data::buildpack::BuildpackId;
 use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
 
 #[derive(Debug)]
 pub(crate) enum Error {
     GetCurrentDir(std::io::Error),
+    Git(GitError),
     InvalidBuildpackUri(String, uriparse::URIReferenceError),
+    BuildpackUriTemplate(String, BuildpackUriTemplateError),
     InvalidBuildpackVersion(String, libcnb_data::buildpack::BuildpackVersionError),
-    ReadingBuilder(PathBuf, std::io::Error),
-    ParsingBuilder(PathBuf, toml_edit::TomlError),
-    BuilderMissingRequiredKey(PathBuf, String),
+    BuilderFile(BuilderFileError),
+    BuilderChannels(BuilderChannelsError),
     WritingBuilder(PathBuf, std::io::Error),
+    ChangelogFile(ChangelogFileError),
+    ReadingChangelog(PathBuf, std::io::Error),
+    AddingChangelogEntry(PathBuf, ChangelogError),
+    WritingChangelog(PathBuf, std::io::Error),
     NoBuilderFiles(Vec<String>),
+    InvalidBuilderPattern(String, glob::PatternError),
+    UnmatchedBuilderPattern(String, Vec<String>),
+    FindingBuilders(glob::GlobError),
+    VerifyingImage(String, OciError),
+    ImageNotFound(String),
+    PinningDigests(PinDigestsError),
+    DigestCache(OciError),
+    UnmatchedBuilders(Vec<BuildpackId>, Vec<PathBuf>),
+    Downgrade(BuildpackId, String, Vec<(PathBuf, String)>),
+    ReleaseManifest(ReleaseManifestError),
+    Cancelled(CancellationError),
+    SetActionOutput(SetOutputError),
 }
 
 impl Display for Error {
@@ -20,6 +48,10 @@ impl Display for Error {
                 write!(f, "Could not get the current directory\nError: {error}")
             }
 
+            Error::Git(error) => {
+                write!(f, "{error}")
+            }
+
             Error::InvalidBuildpackUri(value, error) => {
                 write!(
                     f,
@@ -27,6 +59,13 @@ impl Display for Error {
                 )
             }
 
+            Error::BuildpackUriTemplate(template, error) => {
+                write!(
+                    f,
+                    "Could not render the buildpack URI template\nTemplate: {template}\nError: {error}"
+                )
+            }
+
             Error::InvalidBuildpackVersion(value, error) => {
                 write!(
                     f,
@@ -34,34 +73,46 @@ impl Display for Error {
                 )
             }
 
-            Error::ReadingBuilder(path, error) => {
+            Error::BuilderFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::BuilderChannels(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::WritingBuilder(path, error) => {
                 write!(
                     f,
-                    "Could not read builder\nPath: {}\nError: {error}",
+                    "Error writing builder\nPath: {}\nError: {error}",
                     path.display()
                 )
             }
 
-            Error::ParsingBuilder(path, error) => {
+            Error::ChangelogFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::ReadingChangelog(path, error) => {
                 write!(
                     f,
-                    "Could not parse builder\nPath: {}\nError: {error}",
+                    "Could not read changelog\nPath: {}\nError: {error}",
                     path.display()
                 )
             }
 
-            Error::BuilderMissingRequiredKey(path, key) => {
+            Error::AddingChangelogEntry(path, error) => {
                 write!(
                     f,
-                    "Missing required key `{key}` in builder\nPath: {}",
+                    "Could not add changelog entry\nPath: {}\nError: {error}",
                     path.display()
                 )
             }
 
-            Error::WritingBuilder(path, error) => {
+            Error::WritingChangelog(path, error) => {
                 write!(
                     f,
-                    "Error writing builder\nPath: {}\nError: {error}",
+                    "Could not write changelog\nPath: {}\nError: {error}",
                     path.display()
                 )
             }
@@ -77,6 +128,132 @@ impl Display for Error {
                         .join("\n")
                 )
             }
+
+            Error::InvalidBuilderPattern(pattern, error) => {
+                write!(f, "Invalid builder pattern `{pattern}`\nError: {error}")
+            }
+
+            Error::UnmatchedBuilderPattern(pattern, matched_dirs) => {
+                if matched_dirs.is_empty() {
+                    write!(f, "No directories matched the builder pattern `{pattern}`")
+                } else {
+                    write!(
+                        f,
+                        "None of the directories matched by the builder pattern `{pattern}` contain a builder.toml\n{}",
+                        matched_dirs
+                            .iter()
+                            .map(|dir| format!("• {dir}"))
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    )
+                }
+            }
+
+            Error::FindingBuilders(error) => {
+                write!(
+                    f,
+                    "I/O error while finding builders\nPath: {}\nError: {}",
+                    error.path().display(),
+                    error.error()
+                )
+            }
+
+            Error::VerifyingImage(uri, error) => {
+                write!(
+                    f,
+                    "Could not verify the buildpack image exists\nValue: {uri}\nError: {error}"
+                )
+            }
+
+            Error::ImageNotFound(uri) => {
+                write!(
+                    f,
+                    "The buildpack image does not exist in the registry\nValue: {uri}"
+                )
+            }
+
+            Error::PinningDigests(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::DigestCache(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::UnmatchedBuilders(buildpack_ids, paths) => {
+                write!(
+                    f,
+                    "No references to {} were found in the following builders\n{}",
+                    buildpack_ids
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    paths
+                        .iter()
+                        .map(|path| format!("• {}", path.display()))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            }
+
+            Error::Downgrade(buildpack_id, version, downgrades) => {
+                write!(
+                    f,
+                    "Refusing to downgrade {buildpack_id} to {version} without --allow-downgrade\n{}",
+                    downgrades
+                        .iter()
+                        .map(|(path, existing)| format!("• {existing} in {}", path.display()))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            }
+
+            Error::ReleaseManifest(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::Cancelled(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::SetActionOutput(set_output_error) => match set_output_error {
+                SetOutputError::Opening(error) | SetOutputError::Writing(error) => {
+                    write!(f, "Could not write action output\nError: {error}")
+                }
+            },
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::Git(error) => error.category(),
+            Error::GetCurrentDir(_)
+            | Error::WritingBuilder(_, _)
+            | Error::ReadingChangelog(_, _)
+            | Error::WritingChangelog(_, _)
+            | Error::FindingBuilders(_) => ErrorCategory::Io,
+            Error::ChangelogFile(error) => error.category(),
+            Error::AddingChangelogEntry(_, error) => error.category(),
+            Error::InvalidBuildpackUri(_, _)
+            | Error::InvalidBuildpackVersion(_, _)
+            | Error::NoBuilderFiles(_)
+            | Error::InvalidBuilderPattern(_, _)
+            | Error::UnmatchedBuilderPattern(_, _) => ErrorCategory::Config,
+            Error::BuildpackUriTemplate(_, error) => error.category(),
+            Error::BuilderFile(error) => error.category(),
+            Error::BuilderChannels(error) => error.category(),
+            Error::VerifyingImage(_, error) => error.category(),
+            Error::PinningDigests(error) => error.category(),
+            Error::DigestCache(error) => error.category(),
+            Error::ImageNotFound(_)
+            | Error::UnmatchedBuilders(_, _)
+            | Error::Downgrade(_, _, _) => ErrorCategory::Validation,
+            Error::ReleaseManifest(error) => error.category(),
+            Error::Cancelled(error) => error.category(),
+            Error::SetActionOutput(error) => error.category(),
         }
     }
 }
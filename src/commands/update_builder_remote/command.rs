@@ -0,0 +1,311 @@
+use crate::commands::update_builder::command::{is_downgrade, update_optional};
+use crate::commands::update_builder_remote::errors::Error;
+use clap::Parser;
+use languages_github_actions::builder::{
+    get_builder_buildpack_versions, parse_builder_file, render_buildpack_uri,
+    update_builder_contents_with_buildpack, BuilderFile,
+};
+use languages_github_actions::cancellation::check_cancelled;
+use languages_github_actions::github::actions;
+use languages_github_actions::github::actions::OutputFormat;
+use languages_github_actions::github::api::{
+    create_commit_on_branch, create_or_find_pull_request, create_ref, force_update_ref,
+    get_branch_sha, get_default_branch, get_file_contents, CommitOnBranch, FileAddition,
+};
+use languages_github_actions::github::auth::GitHubAuthArgs;
+use languages_github_actions::log;
+use libcnb_data::buildpack::{BuildpackId, BuildpackVersion};
+use std::path::PathBuf;
+use uriparse::URIReference;
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Updates all references to a buildpack in the builders of another repository and opens a pull request with the changes", long_about = None)]
+pub(crate) struct UpdateBuilderRemoteArgs {
+    #[arg(long)]
+    pub(crate) repository: String,
+    #[arg(long)]
+    pub(crate) base_branch: Option<String>,
+    #[arg(long)]
+    pub(crate) branch: Option<String>,
+    #[arg(long)]
+    pub(crate) buildpack_id: BuildpackId,
+    #[arg(long)]
+    pub(crate) buildpack_version: String,
+    #[arg(long, required_unless_present = "buildpack_uri_template")]
+    pub(crate) buildpack_uri: Option<String>,
+    #[arg(long, conflicts_with = "buildpack_uri")]
+    pub(crate) buildpack_uri_template: Option<String>,
+    #[arg(long, requires = "buildpack_uri_template")]
+    pub(crate) buildpack_digest: Option<String>,
+    #[arg(long, required = true, value_delimiter = ',', num_args = 1..)]
+    pub(crate) builders: Vec<String>,
+    #[arg(long)]
+    pub(crate) fail_on_no_match: bool,
+    #[arg(long)]
+    pub(crate) allow_downgrade: bool,
+    #[arg(long)]
+    pub(crate) optional: Option<bool>,
+    #[arg(long)]
+    pub(crate) title: Option<String>,
+    #[arg(long, value_enum, default_value_t)]
+    pub(crate) output_format: OutputFormat,
+    #[command(flatten)]
+    pub(crate) auth: GitHubAuthArgs,
+}
+
+pub(crate) fn execute(args: UpdateBuilderRemoteArgs) -> Result<()> {
+    let repository = args.repository;
+    let buildpack_id = args.buildpack_id;
+
+    let buildpack_version = BuildpackVersion::try_from(args.buildpack_version.to_string())
+        .map_err(|e| Error::InvalidBuildpackVersion(args.buildpack_version, e))?;
+
+    let buildpack_uri = match args.buildpack_uri_template {
+        Some(template) => render_buildpack_uri(
+            &template,
+            &buildpack_id,
+            &buildpack_version,
+            args.buildpack_digest.as_deref(),
+        )
+        .map_err(|e| Error::BuildpackUriTemplate(template, e))?,
+        None => args
+            .buildpack_uri
+            .expect("Should be required when no URI template is given"),
+    };
+
+    let buildpack_uri = URIReference::try_from(buildpack_uri.as_str())
+        .map_err(|e| Error::InvalidBuildpackUri(buildpack_uri.clone(), e))?;
+
+    let token = args
+        .auth
+        .resolve_token(&repository)
+        .map_err(Error::Authenticating)?;
+
+    let base_branch = match args.base_branch {
+        Some(base_branch) => base_branch,
+        None => get_default_branch(&token, &repository).map_err(Error::ReadingRepository)?,
+    };
+
+    let base_sha =
+        get_branch_sha(&token, &repository, &base_branch).map_err(Error::ReadingRepository)?;
+
+    let mut builder_files = vec![];
+    for builder in &args.builders {
+        check_cancelled().map_err(Error::Cancelled)?;
+        let path = builder_path(builder);
+        let contents = get_file_contents(&token, &repository, &path, &base_sha)
+            .map_err(Error::ReadingRepository)?
+            .ok_or_else(|| Error::BuilderNotFound(repository.clone(), path.clone()))?;
+        builder_files
+            .push(parse_builder_file(PathBuf::from(&path), &contents).map_err(Error::BuilderFile)?);
+    }
+
+    let mut updated_builder_files = vec![];
+    let mut skipped_builder_files = vec![];
+    let mut downgrades = vec![];
+
+    for mut builder_file in builder_files {
+        for existing_version in get_builder_buildpack_versions(&builder_file, &buildpack_id) {
+            if is_downgrade(
+                &buildpack_id,
+                &existing_version,
+                &buildpack_version,
+                &builder_file.path,
+                args.allow_downgrade,
+            ) {
+                downgrades.push((builder_file.path.clone(), existing_version));
+            }
+        }
+
+        match update_builder_contents_with_buildpack(
+            &mut builder_file,
+            &buildpack_id,
+            &buildpack_version,
+            &buildpack_uri,
+        )
+        .map_err(Error::BuilderFile)?
+        {
+            Some(_) => {
+                if let Some(optional) = args.optional {
+                    update_optional(&mut builder_file, &buildpack_id, optional);
+                }
+                updated_builder_files.push(builder_file);
+            }
+            None => {
+                log!(
+                    buildpack_id = buildpack_id;
+                    "⚠️ Builder does not reference {buildpack_id}: {repository}/{}",
+                    builder_file.path.display()
+                );
+                skipped_builder_files.push(builder_file.path);
+            }
+        }
+    }
+
+    if !downgrades.is_empty() {
+        Err(Error::Downgrade(
+            buildpack_id.clone(),
+            buildpack_version.to_string(),
+            downgrades,
+        ))?;
+    }
+
+    if args.fail_on_no_match && !skipped_builder_files.is_empty() {
+        Err(Error::UnmatchedBuilders(
+            buildpack_id.clone(),
+            skipped_builder_files.clone(),
+        ))?;
+    }
+
+    let builders_updated = updated_builder_files
+        .iter()
+        .map(|builder_file| builder_file.path.display().to_string())
+        .collect::<Vec<_>>();
+
+    let builders_skipped = skipped_builder_files
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>();
+
+    actions::set_output(
+        "builders_updated",
+        actions::format_list_output(&builders_updated, &args.output_format),
+    )
+    .map_err(Error::SetActionOutput)?;
+
+    actions::set_output(
+        "builders_skipped",
+        actions::format_list_output(&builders_skipped, &args.output_format),
+    )
+    .map_err(Error::SetActionOutput)?;
+
+    if updated_builder_files.is_empty() {
+        log!(
+            buildpack_id = buildpack_id;
+            "ℹ️ No builders in {repository} needed an update for {buildpack_id}"
+        );
+        return Ok(());
+    }
+
+    let branch = args
+        .branch
+        .unwrap_or_else(|| default_branch_name(&buildpack_id, &buildpack_version));
+
+    // nothing in the builder repository has changed yet, past this point the branch, commit, and
+    // pull request are all created so a rerun finds them in a consistent state
+    check_cancelled().map_err(Error::Cancelled)?;
+
+    // the branch is reset to the base on every run so retries replace the previous attempt
+    let updated =
+        force_update_ref(&token, &repository, &branch, &base_sha).map_err(Error::UpdatingBranch)?;
+    if !updated {
+        create_ref(&token, &repository, &branch, &base_sha).map_err(Error::UpdatingBranch)?;
+    }
+
+    let title = args
+        .title
+        .unwrap_or_else(|| format!("Update {buildpack_id} to {buildpack_version}"));
+
+    let body = pull_request_body(&buildpack_id, &buildpack_version, &updated_builder_files);
+
+    let commit = create_commit_on_branch(
+        &token,
+        &CommitOnBranch {
+            repository: repository.clone(),
+            branch: branch.clone(),
+            expected_head_oid: base_sha,
+            headline: title.clone(),
+            body: Some(body.clone()),
+            additions: updated_builder_files
+                .iter()
+                .map(|builder_file| FileAddition {
+                    path: builder_file.path.display().to_string(),
+                    contents: builder_file.document.to_string().into_bytes(),
+                })
+                .collect(),
+            deletions: vec![],
+        },
+    )
+    .map_err(Error::CreatingCommit)?;
+
+    log!("✅️ Committed {} to {repository}@{branch}", commit.oid);
+
+    let pull_request =
+        create_or_find_pull_request(&token, &repository, &branch, &base_branch, &title, &body)
+            .map_err(Error::OpeningPullRequest)?;
+
+    log!(
+        "✅️ Opened pull request #{}: {}",
+        pull_request.number,
+        pull_request.html_url
+    );
+
+    actions::set_output("branch", branch).map_err(Error::SetActionOutput)?;
+    actions::set_output("commit_sha", commit.oid).map_err(Error::SetActionOutput)?;
+    actions::set_output("pull_request_number", pull_request.number.to_string())
+        .map_err(Error::SetActionOutput)?;
+    actions::set_output("pull_request_url", pull_request.html_url)
+        .map_err(Error::SetActionOutput)?;
+
+    Ok(())
+}
+
+pub(crate) fn builder_path(builder: &str) -> String {
+    format!("{}/builder.toml", builder.trim_matches('/'))
+}
+
+fn default_branch_name(buildpack_id: &BuildpackId, buildpack_version: &BuildpackVersion) -> String {
+    format!(
+        "update-builder/{}-{buildpack_version}",
+        buildpack_id.replace('/', "-")
+    )
+}
+
+fn pull_request_body(
+    buildpack_id: &BuildpackId,
+    buildpack_version: &BuildpackVersion,
+    builder_files: &[BuilderFile],
+) -> String {
+    format!(
+        "Updates `{buildpack_id}` to `{buildpack_version}` in the following builders:\n\n{}",
+        builder_files
+            .iter()
+            .map(|builder_file| format!("- `{}`", builder_file.path.display()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::update_builder_remote::command::{
+        builder_path, default_branch_name, pull_request_body,
+    };
+    use languages_github_actions::builder::parse_builder_file;
+    use libcnb_data::buildpack::BuildpackVersion;
+    use libcnb_data::buildpack_id;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_remote_builder_update_details() {
+        let buildpack_id = buildpack_id!("heroku/nodejs");
+        let buildpack_version = BuildpackVersion::new(1, 2, 3);
+
+        assert_eq!(builder_path("builder-22/"), "builder-22/builder.toml");
+        assert_eq!(
+            default_branch_name(&buildpack_id, &buildpack_version),
+            "update-builder/heroku-nodejs-1.2.3"
+        );
+
+        let builder_files = vec![
+            parse_builder_file(PathBuf::from("builder-22/builder.toml"), "").unwrap(),
+            parse_builder_file(PathBuf::from("salesforce-functions/builder.toml"), "").unwrap(),
+        ];
+        assert_eq!(
+            pull_request_body(&buildpack_id, &buildpack_version, &builder_files),
+            "Updates `heroku/nodejs` to `1.2.3` in the following builders:\n\n- `builder-22/builder.toml`\n- `salesforce-functions/builder.toml`"
+        );
+    }
+}
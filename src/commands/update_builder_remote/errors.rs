@@ -0,0 +1,141 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::builder::{BuilderFileError, BuildpackUriTemplateError};
+use languages_github_actions::cancellation::CancellationError;
+use languages_github_actions::github::actions::SetOutputError;
+use languages_github_actions::github::api::GitHubApiError;
+use languages_github_actions::github::auth::AuthError;
+use libcnb_data::buildpack::BuildpackId;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    InvalidBuildpackUri(String, uriparse::URIReferenceError),
+    BuildpackUriTemplate(String, BuildpackUriTemplateError),
+    InvalidBuildpackVersion(String, libcnb_data::buildpack::BuildpackVersionError),
+    Authenticating(AuthError),
+    ReadingRepository(GitHubApiError),
+    BuilderNotFound(String, String),
+    BuilderFile(BuilderFileError),
+    UnmatchedBuilders(BuildpackId, Vec<PathBuf>),
+    Downgrade(BuildpackId, String, Vec<(PathBuf, String)>),
+    UpdatingBranch(GitHubApiError),
+    CreatingCommit(GitHubApiError),
+    OpeningPullRequest(GitHubApiError),
+    Cancelled(CancellationError),
+    SetActionOutput(SetOutputError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::InvalidBuildpackUri(value, error) => {
+                write!(
+                    f,
+                    "The buildpack URI argument is invalid\nValue: {value}\nError: {error}"
+                )
+            }
+
+            Error::BuildpackUriTemplate(template, error) => {
+                write!(
+                    f,
+                    "Could not render the buildpack URI template\nTemplate: {template}\nError: {error}"
+                )
+            }
+
+            Error::InvalidBuildpackVersion(value, error) => {
+                write!(
+                    f,
+                    "The buildpack version argument is invalid\nValue: {value}\nError: {error}"
+                )
+            }
+
+            Error::Authenticating(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::ReadingRepository(error) => {
+                write!(f, "Could not read the builder repository\nError: {error}")
+            }
+
+            Error::BuilderNotFound(repository, path) => {
+                write!(
+                    f,
+                    "No builder.toml file found in the builder repository\nRepository: {repository}\nPath: {path}"
+                )
+            }
+
+            Error::BuilderFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::UnmatchedBuilders(buildpack_id, paths) => {
+                write!(
+                    f,
+                    "No references to {buildpack_id} were found in the following builders\n{}",
+                    paths
+                        .iter()
+                        .map(|path| format!("• {}", path.display()))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            }
+
+            Error::Downgrade(buildpack_id, version, downgrades) => {
+                write!(
+                    f,
+                    "Refusing to downgrade {buildpack_id} to {version} without --allow-downgrade\n{}",
+                    downgrades
+                        .iter()
+                        .map(|(path, existing)| format!("• {existing} in {}", path.display()))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            }
+
+            Error::UpdatingBranch(error) => {
+                write!(f, "Could not update the builder branch\nError: {error}")
+            }
+
+            Error::CreatingCommit(error) => {
+                write!(f, "Could not commit the builder changes\nError: {error}")
+            }
+
+            Error::OpeningPullRequest(error) => {
+                write!(f, "Could not open the builder pull request\nError: {error}")
+            }
+
+            Error::Cancelled(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::SetActionOutput(set_output_error) => match set_output_error {
+                SetOutputError::Opening(error) | SetOutputError::Writing(error) => {
+                    write!(f, "Could not write action output\nError: {error}")
+                }
+            },
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::InvalidBuildpackUri(_, _) | Error::InvalidBuildpackVersion(_, _) => {
+                ErrorCategory::Config
+            }
+            Error::BuildpackUriTemplate(_, error) => error.category(),
+            Error::Authenticating(error) => error.category(),
+            Error::ReadingRepository(error)
+            | Error::UpdatingBranch(error)
+            | Error::CreatingCommit(error)
+            | Error::OpeningPullRequest(error) => error.category(),
+            Error::BuilderFile(error) => error.category(),
+            Error::BuilderNotFound(_, _)
+            | Error::UnmatchedBuilders(_, _)
+            | Error::Downgrade(_, _, _) => ErrorCategory::Validation,
+            Error::Cancelled(error) => error.category(),
+            Error::SetActionOutput(error) => error.category(),
+        }
+    }
+}
@@ -0,0 +1,396 @@
+use crate::commands::validate_builders::config::{LintConfig, LintRule, Severity};
+use crate::commands::validate_builders::errors::{Error, LintProblem};
+use clap::Parser;
+use glob::glob;
+use languages_github_actions::builder::{
+    pin_builder_buildpack_digests, read_builder_file, remove_duplicate_builder_buildpacks,
+    BuilderFile,
+};
+use languages_github_actions::log;
+use languages_github_actions::oci::{DigestCache, ImageReference};
+use languages_github_actions::write;
+use std::path::{Path, PathBuf};
+use toml_edit::Item;
+
+type Result<T> = std::result::Result<T, Error>;
+
+const BUILDPACKS_URI_KEY: &str = "buildpacks.uri";
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Checks each builder.toml for insecure uris, unpinned images, and duplicate buildpacks", long_about = None)]
+pub(crate) struct ValidateBuildersArgs {
+    #[arg(long, default_value = "builders/*/builder.toml")]
+    pub(crate) builder_pattern: String,
+    #[arg(long)]
+    pub(crate) config: Option<PathBuf>,
+    #[arg(long)]
+    pub(crate) fix: bool,
+    #[arg(long, requires = "fix", default_value_t = 8)]
+    pub(crate) registry_concurrency: usize,
+}
+
+pub(crate) fn execute(args: ValidateBuildersArgs) -> Result<()> {
+    let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
+
+    let config = match &args.config {
+        Some(path) => LintConfig::read(&current_dir.join(path))?,
+        None => LintConfig::default(),
+    };
+
+    let builder_paths = find_builder_files(&current_dir, &args.builder_pattern)?;
+    if builder_paths.is_empty() {
+        Err(Error::NoBuilderFiles(args.builder_pattern.clone()))?;
+    }
+
+    let mut builder_files = builder_paths
+        .into_iter()
+        .map(read_builder_file)
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(Error::BuilderFile)?;
+
+    if args.fix {
+        let original_contents = builder_files
+            .iter()
+            .map(|builder_file| builder_file.document.to_string())
+            .collect::<Vec<_>>();
+
+        fix_builders(&mut builder_files, &config, args.registry_concurrency)?;
+
+        for (builder_file, original_contents) in builder_files.iter().zip(original_contents) {
+            let contents = builder_file.document.to_string();
+            if contents != original_contents {
+                write::write_file(&builder_file.path, contents)
+                    .map_err(|e| Error::WritingBuilder(builder_file.path.clone(), e))?;
+            }
+        }
+    }
+
+    let mut problems = vec![];
+
+    for builder_file in &builder_files {
+        let mut builder_problems = vec![];
+        for problem in lint_builder(builder_file) {
+            match config.severity(problem.rule()) {
+                Severity::Error => builder_problems.push(problem),
+                Severity::Warning => log!("⚠️ {problem}"),
+                Severity::Off => {}
+            }
+        }
+
+        if builder_problems.is_empty() {
+            log!(
+                path = builder_file.path;
+                "✅️ No problems found: {}",
+                builder_file.path.display()
+            );
+        } else {
+            problems.extend(builder_problems);
+        }
+    }
+
+    if !problems.is_empty() {
+        Err(Error::LintFailed(problems))?;
+    }
+
+    Ok(())
+}
+
+fn find_builder_files(dir: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+    let pattern = dir.join(pattern).to_string_lossy().to_string();
+    let mut paths = glob(&pattern)
+        .map_err(|e| Error::InvalidBuilderPattern(pattern.clone(), e))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(Error::FindingBuilders)?;
+    paths.sort();
+    Ok(paths)
+}
+
+// only fixes that can't change what ends up in the builder are applied, identical duplicate entries
+// are removed and tag-based buildpack images are pinned to the digest the tag currently points at,
+// insecure uris and stack images are left for a person to update
+fn fix_builders(
+    builder_files: &mut [BuilderFile],
+    config: &LintConfig,
+    concurrency: usize,
+) -> Result<()> {
+    if config.severity(LintRule::DuplicateBuildpack) != Severity::Off {
+        for builder_file in builder_files.iter_mut() {
+            if remove_duplicate_builder_buildpacks(builder_file) {
+                log!(
+                    path = builder_file.path;
+                    "✅️ Removed duplicate buildpacks from builder: {}",
+                    builder_file.path.display()
+                );
+            }
+        }
+    }
+
+    let uris = builder_files
+        .iter()
+        .flat_map(lint_builder)
+        .filter(|problem| config.severity(problem.rule()) != Severity::Off)
+        .filter_map(|problem| match problem {
+            LintProblem::LatestTag(_, key, uri) if key == BUILDPACKS_URI_KEY => Some(uri),
+            LintProblem::MissingDigest(_, uri) => Some(uri),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    pin_builder_buildpack_digests(builder_files, uris, concurrency, &DigestCache::default())
+        .map_err(Error::PinningDigests)
+}
+
+fn lint_builder(builder_file: &BuilderFile) -> Vec<LintProblem> {
+    let path = &builder_file.path;
+    let document = &builder_file.document;
+
+    let mut problems = vec![];
+    let mut ids: Vec<(&str, usize)> = vec![];
+
+    let buildpacks = document
+        .get("buildpacks")
+        .and_then(Item::as_array_of_tables)
+        .into_iter()
+        .flat_map(|buildpacks| buildpacks.iter());
+    for buildpack in buildpacks {
+        if let Some(id) = buildpack.get("id").and_then(Item::as_str) {
+            match ids.iter_mut().find(|(seen_id, _)| *seen_id == id) {
+                Some((_, count)) => *count += 1,
+                None => ids.push((id, 1)),
+            }
+        }
+
+        if let Some(uri) = buildpack.get("uri").and_then(Item::as_str) {
+            if is_insecure_uri(uri) {
+                problems.push(LintProblem::InsecureUri(
+                    path.clone(),
+                    BUILDPACKS_URI_KEY.to_string(),
+                    uri.to_string(),
+                ));
+            }
+            if uri.starts_with("docker://") {
+                match ImageReference::try_from(uri) {
+                    Ok(image) if image.is_digest() => {}
+                    Ok(image) if image.reference == "latest" => {
+                        problems.push(LintProblem::LatestTag(
+                            path.clone(),
+                            BUILDPACKS_URI_KEY.to_string(),
+                            uri.to_string(),
+                        ))
+                    }
+                    Ok(_) => {
+                        problems.push(LintProblem::MissingDigest(path.clone(), uri.to_string()))
+                    }
+                    Err(_) => {}
+                }
+            }
+        }
+    }
+
+    for (id, count) in ids {
+        if count > 1 {
+            problems.push(LintProblem::DuplicateBuildpack(
+                path.clone(),
+                id.to_string(),
+                count,
+            ));
+        }
+    }
+
+    if let Some(uri) = document
+        .get("lifecycle")
+        .and_then(|lifecycle| lifecycle.get("uri"))
+        .and_then(Item::as_str)
+    {
+        if is_insecure_uri(uri) {
+            problems.push(LintProblem::InsecureUri(
+                path.clone(),
+                "lifecycle.uri".to_string(),
+                uri.to_string(),
+            ));
+        }
+    }
+
+    for (key, image) in get_stack_images(builder_file) {
+        let is_latest = ImageReference::try_from(format!("docker://{image}").as_str())
+            .map_or(false, |image| {
+                !image.is_digest() && image.reference == "latest"
+            });
+        if is_latest {
+            problems.push(LintProblem::LatestTag(
+                path.clone(),
+                key.to_string(),
+                image.to_string(),
+            ));
+        }
+    }
+
+    problems
+}
+
+fn is_insecure_uri(uri: &str) -> bool {
+    uri.get(..7)
+        .map_or(false, |scheme| scheme.eq_ignore_ascii_case("http://"))
+}
+
+// the build and run images from both the older `[stack]` table and the newer `[build]` and
+// `[[run.images]]` tables
+fn get_stack_images(builder_file: &BuilderFile) -> Vec<(&'static str, &str)> {
+    let document = &builder_file.document;
+    let mut images = vec![];
+
+    if let Some(stack) = document.get("stack") {
+        for (key, name) in [
+            ("stack.build-image", "build-image"),
+            ("stack.run-image", "run-image"),
+            ("stack.run-image-mirrors", "run-image-mirrors"),
+        ] {
+            for image in strings(stack.get(name)) {
+                images.push((key, image));
+            }
+        }
+    }
+
+    if let Some(build) = document.get("build") {
+        for image in strings(build.get("image")) {
+            images.push(("build.image", image));
+        }
+    }
+
+    let run_images = document
+        .get("run")
+        .and_then(|run| run.get("images"))
+        .and_then(Item::as_array_of_tables)
+        .into_iter()
+        .flat_map(|run_images| run_images.iter());
+    for run_image in run_images {
+        for image in strings(run_image.get("image")) {
+            images.push(("run.images.image", image));
+        }
+        for image in strings(run_image.get("mirrors")) {
+            images.push(("run.images.mirrors", image));
+        }
+    }
+
+    images
+}
+
+// a value that's either a single image or a list of them, like `mirrors`
+fn strings(item: Option<&Item>) -> Vec<&str> {
+    match item {
+        Some(item) if item.is_str() => item.as_str().into_iter().collect(),
+        Some(item) => item
+            .as_array()
+            .map(|array| array.iter().filter_map(|value| value.as_str()).collect())
+            .unwrap_or_default(),
+        None => vec![],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::validate_builders::command::lint_builder;
+    use crate::commands::validate_builders::errors::LintProblem;
+    use languages_github_actions::builder::BuilderFile;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+    use toml_edit::Document;
+
+    fn lint(contents: &str) -> Vec<LintProblem> {
+        lint_builder(&BuilderFile {
+            path: PathBuf::from("/a/builder.toml"),
+            document: Document::from_str(contents).unwrap(),
+        })
+    }
+
+    #[test]
+    fn test_lint_builder() {
+        assert_eq!(
+            lint(
+                r#"
+[[buildpacks]]
+  id = "heroku/nodejs"
+  uri = "docker://docker.io/heroku/buildpack-nodejs@sha256:abc"
+
+[[buildpacks]]
+  id = "heroku/procfile"
+  uri = "https://example.com/procfile.tgz"
+
+[lifecycle]
+  version = "0.17.0"
+
+[stack]
+  id = "heroku-22"
+  build-image = "heroku/heroku:22-cnb-build"
+  run-image = "heroku/heroku:22-cnb"
+  run-image-mirrors = ["registry.example.com/heroku/heroku@sha256:def"]
+"#
+            ),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_lint_builder_with_problems() {
+        let path = PathBuf::from("/a/builder.toml");
+        assert_eq!(
+            lint(
+                r#"
+[[buildpacks]]
+  id = "heroku/nodejs"
+  uri = "docker://docker.io/heroku/buildpack-nodejs:latest"
+
+[[buildpacks]]
+  id = "heroku/procfile"
+  uri = "HTTP://example.com/procfile.tgz"
+
+[[buildpacks]]
+  id = "heroku/nodejs"
+  uri = "docker://docker.io/heroku/buildpack-nodejs:1.0.0"
+
+[lifecycle]
+  uri = "http://example.com/lifecycle.tgz"
+
+[build]
+  image = "heroku/heroku:22-cnb-build"
+
+[[run.images]]
+  image = "heroku/heroku"
+  mirrors = ["registry.example.com/heroku/heroku:latest"]
+"#
+            ),
+            vec![
+                LintProblem::LatestTag(
+                    path.clone(),
+                    "buildpacks.uri".to_string(),
+                    "docker://docker.io/heroku/buildpack-nodejs:latest".to_string()
+                ),
+                LintProblem::InsecureUri(
+                    path.clone(),
+                    "buildpacks.uri".to_string(),
+                    "HTTP://example.com/procfile.tgz".to_string()
+                ),
+                LintProblem::MissingDigest(
+                    path.clone(),
+                    "docker://docker.io/heroku/buildpack-nodejs:1.0.0".to_string()
+                ),
+                LintProblem::DuplicateBuildpack(path.clone(), "heroku/nodejs".to_string(), 2),
+                LintProblem::InsecureUri(
+                    path.clone(),
+                    "lifecycle.uri".to_string(),
+                    "http://example.com/lifecycle.tgz".to_string()
+                ),
+                LintProblem::LatestTag(
+                    path.clone(),
+                    "run.images.image".to_string(),
+                    "heroku/heroku".to_string()
+                ),
+                LintProblem::LatestTag(
+                    path,
+                    "run.images.mirrors".to_string(),
+                    "registry.example.com/heroku/heroku:latest".to_string()
+                ),
+            ]
+        );
+    }
+}
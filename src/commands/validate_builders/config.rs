@@ -0,0 +1,127 @@
+use crate::commands::validate_builders::errors::Error;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+use toml_edit::{Document, Item};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub(crate) enum LintRule {
+    InsecureUri,
+    LatestTag,
+    MissingDigest,
+    DuplicateBuildpack,
+}
+
+impl LintRule {
+    const ALL: [LintRule; 4] = [
+        LintRule::InsecureUri,
+        LintRule::LatestTag,
+        LintRule::MissingDigest,
+        LintRule::DuplicateBuildpack,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            LintRule::InsecureUri => "insecure-uri",
+            LintRule::LatestTag => "latest-tag",
+            LintRule::MissingDigest => "missing-digest",
+            LintRule::DuplicateBuildpack => "duplicate-buildpack",
+        }
+    }
+
+    fn default_severity(&self) -> Severity {
+        match self {
+            LintRule::InsecureUri | LintRule::LatestTag | LintRule::DuplicateBuildpack => {
+                Severity::Error
+            }
+            LintRule::MissingDigest => Severity::Warning,
+        }
+    }
+}
+
+impl Display for LintRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum Severity {
+    Error,
+    Warning,
+    Off,
+}
+
+#[derive(Debug, Default, Eq, PartialEq)]
+pub(crate) struct LintConfig {
+    severities: HashMap<LintRule, Severity>,
+}
+
+impl LintConfig {
+    pub(crate) fn read(path: &Path) -> Result<LintConfig, Error> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| Error::ReadingConfig(path.to_path_buf(), e))?;
+        LintConfig::parse(&contents)
+            .map_err(|message| Error::InvalidConfig(path.to_path_buf(), message))
+    }
+
+    // rules are set in a `[rules]` table by name to `error`, `warning`, or `off`
+    fn parse(contents: &str) -> Result<LintConfig, String> {
+        let document = Document::from_str(contents).map_err(|e| e.to_string())?;
+        let mut config = LintConfig::default();
+
+        if let Some(rules) = document.get("rules").and_then(Item::as_table_like) {
+            for (name, value) in rules.iter() {
+                let rule = LintRule::ALL
+                    .into_iter()
+                    .find(|rule| rule.name() == name)
+                    .ok_or_else(|| format!("Unknown rule `{name}`"))?;
+                let severity = match value.as_str() {
+                    Some("error") => Severity::Error,
+                    Some("warning") => Severity::Warning,
+                    Some("off") => Severity::Off,
+                    _ => Err(format!(
+                        "Rule `{name}` must be one of `error`, `warning`, or `off`"
+                    ))?,
+                };
+                config.severities.insert(rule, severity);
+            }
+        }
+
+        Ok(config)
+    }
+
+    pub(crate) fn severity(&self, rule: LintRule) -> Severity {
+        self.severities
+            .get(&rule)
+            .copied()
+            .unwrap_or_else(|| rule.default_severity())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::validate_builders::config::{LintConfig, LintRule, Severity};
+
+    #[test]
+    fn test_parse_config() {
+        let config = LintConfig::parse(
+            r#"
+[rules]
+missing-digest = "error"
+latest-tag = "warning"
+duplicate-buildpack = "off"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.severity(LintRule::MissingDigest), Severity::Error);
+        assert_eq!(config.severity(LintRule::LatestTag), Severity::Warning);
+        assert_eq!(config.severity(LintRule::DuplicateBuildpack), Severity::Off);
+        assert_eq!(config.severity(LintRule::InsecureUri), Severity::Error);
+
+        assert!(LintConfig::parse("[rules]\nmissing = \"error\"").is_err());
+        assert!(LintConfig::parse("[rules]\nlatest-tag = \"fatal\"").is_err());
+    }
+}
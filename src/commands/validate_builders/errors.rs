@@ -0,0 +1,165 @@
+use crate::commands::validate_builders::config::LintRule;
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::builder::{BuilderFileError, PinDigestsError};
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    GetCurrentDir(io::Error),
+    ReadingConfig(PathBuf, io::Error),
+    InvalidConfig(PathBuf, String),
+    InvalidBuilderPattern(String, glob::PatternError),
+    FindingBuilders(glob::GlobError),
+    NoBuilderFiles(String),
+    BuilderFile(BuilderFileError),
+    WritingBuilder(PathBuf, io::Error),
+    PinningDigests(PinDigestsError),
+    LintFailed(Vec<LintProblem>),
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) enum LintProblem {
+    InsecureUri(PathBuf, String, String),
+    LatestTag(PathBuf, String, String),
+    MissingDigest(PathBuf, String),
+    DuplicateBuildpack(PathBuf, String, usize),
+}
+
+impl LintProblem {
+    pub(crate) fn rule(&self) -> LintRule {
+        match self {
+            LintProblem::InsecureUri(_, _, _) => LintRule::InsecureUri,
+            LintProblem::LatestTag(_, _, _) => LintRule::LatestTag,
+            LintProblem::MissingDigest(_, _) => LintRule::MissingDigest,
+            LintProblem::DuplicateBuildpack(_, _, _) => LintRule::DuplicateBuildpack,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::GetCurrentDir(error) => {
+                write!(f, "Failed to get current directory\nError: {error}")
+            }
+
+            Error::ReadingConfig(path, error) => {
+                write!(
+                    f,
+                    "Could not read lint config\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::InvalidConfig(path, error) => {
+                write!(
+                    f,
+                    "Invalid lint config\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::InvalidBuilderPattern(pattern, error) => {
+                write!(f, "Invalid builder pattern `{pattern}`\nError: {error}")
+            }
+
+            Error::FindingBuilders(error) => {
+                write!(
+                    f,
+                    "I/O error while finding builders\nPath: {}\nError: {}",
+                    error.path().display(),
+                    error.error()
+                )
+            }
+
+            Error::NoBuilderFiles(pattern) => {
+                write!(f, "No builder.toml files matched the pattern `{pattern}`")
+            }
+
+            Error::BuilderFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::WritingBuilder(path, error) => {
+                write!(
+                    f,
+                    "Error writing builder\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::PinningDigests(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::LintFailed(problems) => {
+                write!(
+                    f,
+                    "Problems found in builders:\n{}",
+                    problems
+                        .iter()
+                        .map(|problem| format!("• {problem}"))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            }
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::GetCurrentDir(_)
+            | Error::ReadingConfig(_, _)
+            | Error::FindingBuilders(_)
+            | Error::WritingBuilder(_, _) => ErrorCategory::Io,
+            Error::InvalidConfig(_, _)
+            | Error::InvalidBuilderPattern(_, _)
+            | Error::NoBuilderFiles(_) => ErrorCategory::Config,
+            Error::BuilderFile(error) => error.category(),
+            Error::PinningDigests(error) => error.category(),
+            Error::LintFailed(_) => ErrorCategory::Validation,
+        }
+    }
+}
+
+impl Display for LintProblem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LintProblem::InsecureUri(path, key, uri) => {
+                write!(
+                    f,
+                    "`{key}` uses an insecure `http://` uri `{uri}` ({})",
+                    path.display()
+                )
+            }
+
+            LintProblem::LatestTag(path, key, image) => {
+                write!(
+                    f,
+                    "`{key}` uses the `latest` tag or no tag in `{image}` ({})",
+                    path.display()
+                )
+            }
+
+            LintProblem::MissingDigest(path, uri) => {
+                write!(
+                    f,
+                    "`buildpacks.uri` is not pinned to a digest in `{uri}` ({})",
+                    path.display()
+                )
+            }
+
+            LintProblem::DuplicateBuildpack(path, id, count) => {
+                write!(
+                    f,
+                    "Buildpack `{id}` is declared {count} times in `[[buildpacks]]` ({})",
+                    path.display()
+                )
+            }
+        }
+    }
+}
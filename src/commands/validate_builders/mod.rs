@@ -0,0 +1,5 @@
+pub(crate) mod command;
+pub(crate) mod config;
+pub(crate) mod errors;
+
+pub(crate) use command::execute;
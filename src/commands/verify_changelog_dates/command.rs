@@ -0,0 +1,93 @@
+use crate::commands::verify_changelog_dates::errors::Error;
+use clap::Parser;
+use languages_github_actions::buildpack::BuildpackDiscoveryArgs;
+use languages_github_actions::changelog::{
+    find_release_date_problems, fix_release_date_formats, ChangelogLocationArgs, ReleaseDateFormat,
+    ReleaseDateProblem,
+};
+use languages_github_actions::debug_artifacts::capture_markdown_failure;
+use languages_github_actions::git::WorktreeGuardArgs;
+use languages_github_actions::log;
+use languages_github_actions::write;
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Checks that the release dates in each detected buildpack's changelog are well-formatted and in order", long_about = None)]
+pub(crate) struct VerifyChangelogDatesArgs {
+    #[arg(long)]
+    pub(crate) fix: bool,
+    #[arg(long, value_enum, default_value_t)]
+    pub(crate) date_format: ReleaseDateFormat,
+    #[command(flatten)]
+    pub(crate) worktree: WorktreeGuardArgs,
+    #[command(flatten)]
+    pub(crate) discovery: BuildpackDiscoveryArgs,
+    #[command(flatten)]
+    pub(crate) changelog: ChangelogLocationArgs,
+}
+
+pub(crate) fn execute(args: VerifyChangelogDatesArgs) -> Result<()> {
+    let current_dir = std::env::current_dir().map_err(Error::GetCurrentDir)?;
+
+    if args.fix {
+        args.worktree.check(&current_dir).map_err(Error::Git)?;
+    }
+
+    let buildpack_dirs = args
+        .discovery
+        .find_buildpack_dirs(&current_dir, &[current_dir.join("target")])
+        .map_err(|e| Error::FindingBuildpacks(current_dir.clone(), e))?;
+
+    let mut problems = vec![];
+
+    for dir in buildpack_dirs {
+        let path = args
+            .changelog
+            .find_changelog(&dir, &current_dir)
+            .map_err(Error::ChangelogFile)?;
+
+        let mut contents =
+            std::fs::read_to_string(&path).map_err(|e| Error::ReadingChangelog(path.clone(), e))?;
+
+        if args.fix {
+            let fixed_contents =
+                fix_release_date_formats(&contents, args.date_format).map_err(|e| {
+                    capture_markdown_failure(&path, &contents, &e);
+                    Error::ParsingChangelog(path.clone(), e)
+                })?;
+            if fixed_contents != contents {
+                write::write_file(&path, &fixed_contents)
+                    .map_err(|e| Error::WritingChangelog(path.clone(), e))?;
+                log!(path = path; "✅️ Fixed release date formats: {}", path.display());
+                contents = fixed_contents;
+            }
+        }
+
+        let changelog_problems =
+            find_release_date_problems(&contents, args.date_format).map_err(|e| {
+                capture_markdown_failure(&path, &contents, &e);
+                Error::ParsingChangelog(path.clone(), e)
+            })?;
+
+        if changelog_problems.is_empty() {
+            log!(path = path; "✅️ Release dates are valid: {}", path.display());
+        } else {
+            problems.extend(
+                changelog_problems
+                    .into_iter()
+                    .map(|problem| (path.clone(), problem)),
+            );
+        }
+    }
+
+    if !problems.is_empty() {
+        let fix_format = problems
+            .iter()
+            .any(|(_, problem)| matches!(problem, ReleaseDateProblem::InconsistentFormat(_, _, _)))
+            .then_some(args.date_format);
+        Err(Error::InvalidReleaseDates(problems, fix_format))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::buildpack::BuildpackDiscoveryError;
+use languages_github_actions::changelog::{
+    ChangelogError, ChangelogFileError, ReleaseDateFormat, ReleaseDateProblem,
+};
+use languages_github_actions::git::GitError;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    GetCurrentDir(io::Error),
+    Git(GitError),
+    FindingBuildpacks(PathBuf, BuildpackDiscoveryError),
+    ChangelogFile(ChangelogFileError),
+    ReadingChangelog(PathBuf, io::Error),
+    ParsingChangelog(PathBuf, ChangelogError),
+    WritingChangelog(PathBuf, io::Error),
+    InvalidReleaseDates(
+        Vec<(PathBuf, ReleaseDateProblem)>,
+        Option<ReleaseDateFormat>,
+    ),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::GetCurrentDir(error) => {
+                write!(f, "Failed to get current directory\nError: {error}")
+            }
+
+            Error::Git(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::FindingBuildpacks(path, error) => {
+                write!(
+                    f,
+                    "Could not find buildpacks\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::ChangelogFile(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::ReadingChangelog(path, error) => {
+                write!(
+                    f,
+                    "Could not read changelog\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::ParsingChangelog(path, error) => {
+                write!(
+                    f,
+                    "Could not parse changelog\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::WritingChangelog(path, error) => {
+                write!(
+                    f,
+                    "Could not write changelog\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            Error::InvalidReleaseDates(problems, fix_format) => {
+                write!(
+                    f,
+                    "Problems found with changelog release dates:\n{}",
+                    problems
+                        .iter()
+                        .map(|(path, problem)| format!("• {problem} ({})", path.display()))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )?;
+                if let Some(date_format) = fix_format {
+                    write!(
+                        f,
+                        "\n\nRun with `--fix` to rewrite the dates in {date_format} format"
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::ChangelogFile(error) => error.category(),
+            Error::Git(error) => error.category(),
+            Error::GetCurrentDir(_)
+            | Error::ReadingChangelog(_, _)
+            | Error::WritingChangelog(_, _) => ErrorCategory::Io,
+            Error::FindingBuildpacks(_, error) => error.category(),
+            Error::ParsingChangelog(_, error) => error.category(),
+            Error::InvalidReleaseDates(_, _) => ErrorCategory::Validation,
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use crate::commands::verify_published::errors::{Error, PublishProblem};
+use clap::Parser;
+use languages_github_actions::buildpackage::{BUILDPACKAGE_METADATA_LABEL, BUILDPACK_LAYERS_LABEL};
+use languages_github_actions::cancellation::check_cancelled;
+use languages_github_actions::github::actions;
+use languages_github_actions::log;
+use languages_github_actions::oci::{
+    fetch_image_config, resolve_digest, DigestCache, ImageReference, OciError,
+};
+use libcnb_data::buildpack::{BuildpackId, BuildpackVersion};
+use serde_json::Value;
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Verifies a released buildpack image is published with the same digest and metadata to every registry", long_about = None, disable_version_flag = true)]
+pub(crate) struct VerifyPublishedArgs {
+    #[arg(long)]
+    pub(crate) buildpack_id: BuildpackId,
+    #[arg(long)]
+    pub(crate) version: String,
+    #[arg(long, required = true, value_delimiter = ',', num_args = 1..)]
+    pub(crate) registries: Vec<String>,
+}
+
+pub(crate) fn execute(args: VerifyPublishedArgs) -> Result<()> {
+    let buildpack_id = args.buildpack_id;
+
+    let version = BuildpackVersion::try_from(args.version.clone())
+        .map_err(|e| Error::InvalidVersion(args.version, e))?;
+
+    // every registry is queried directly so a stale cache can't hide a missing tag
+    let digest_cache = DigestCache::default();
+
+    let mut problems = vec![];
+    let mut digests = vec![];
+
+    for registry in &args.registries {
+        check_cancelled().map_err(Error::Cancelled)?;
+
+        let uri = format!(
+            "docker://{}:{version}",
+            registry.trim().trim_start_matches("docker://")
+        );
+        let image = ImageReference::try_from(uri.as_str())
+            .map_err(|e| Error::InvalidRegistry(registry.clone(), e))?;
+
+        let digest = match resolve_digest(&image, &digest_cache) {
+            Ok(digest) => digest,
+            Err(OciError::ManifestNotFound(_)) => {
+                problems.push(PublishProblem::MissingTag(image.to_string()));
+                continue;
+            }
+            Err(error) => Err(Error::QueryingRegistry(image.to_string(), error))?,
+        };
+
+        let config = fetch_image_config(&image.with_digest(&digest))
+            .map_err(|e| Error::QueryingRegistry(image.to_string(), e))?;
+
+        let label_problems =
+            find_label_problems(&image.to_string(), &config, &buildpack_id, &version);
+        if label_problems.is_empty() {
+            log!("✅️ Verified {image} ({digest})");
+        }
+        problems.extend(label_problems);
+
+        digests.push((image.to_string(), digest));
+    }
+
+    if let Some(problem) = find_digest_problem(&digests) {
+        problems.push(problem);
+    }
+
+    if !problems.is_empty() {
+        Err(Error::VerificationFailed(problems))?;
+    }
+
+    if let Some((_, digest)) = digests.first() {
+        actions::set_output("digest", digest).map_err(Error::SetActionOutput)?;
+    }
+
+    Ok(())
+}
+
+// the same image pushed to several registries keeps its manifest digest, anything else means a
+// registry has a different (or re-pushed) build
+fn find_digest_problem(digests: &[(String, String)]) -> Option<PublishProblem> {
+    let first = digests.first()?;
+    digests
+        .iter()
+        .any(|(_, digest)| digest != &first.1)
+        .then(|| PublishProblem::DigestMismatch(digests.to_vec()))
+}
+
+fn find_label_problems(
+    image: &str,
+    config: &Value,
+    buildpack_id: &BuildpackId,
+    version: &BuildpackVersion,
+) -> Vec<PublishProblem> {
+    let labels = &config["config"]["Labels"];
+    let read_label = |name: &str| -> std::result::Result<Value, PublishProblem> {
+        let value = labels[name]
+            .as_str()
+            .ok_or_else(|| PublishProblem::MissingLabel(image.to_string(), name.to_string()))?;
+        serde_json::from_str(value).map_err(|e| {
+            PublishProblem::InvalidLabel(image.to_string(), name.to_string(), e.to_string())
+        })
+    };
+
+    let mut problems = vec![];
+
+    match read_label(BUILDPACKAGE_METADATA_LABEL) {
+        Ok(metadata) => {
+            for (field, expected) in [
+                ("id", buildpack_id.to_string()),
+                ("version", version.to_string()),
+            ] {
+                let actual = metadata[field].as_str().unwrap_or_default();
+                if actual != expected {
+                    problems.push(PublishProblem::WrongMetadata(
+                        image.to_string(),
+                        field.to_string(),
+                        expected,
+                        actual.to_string(),
+                    ));
+                }
+            }
+        }
+        Err(problem) => problems.push(problem),
+    }
+
+    match read_label(BUILDPACK_LAYERS_LABEL) {
+        Ok(layers) => {
+            if layers[buildpack_id.as_str()][version.to_string()].is_null() {
+                problems.push(PublishProblem::MissingBuildpackLayer(
+                    image.to_string(),
+                    format!("{buildpack_id}@{version}"),
+                ));
+            }
+        }
+        Err(problem) => problems.push(problem),
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::verify_published::command::{find_digest_problem, find_label_problems};
+    use crate::commands::verify_published::errors::PublishProblem;
+    use libcnb_data::buildpack::BuildpackVersion;
+    use libcnb_data::buildpack_id;
+    use serde_json::json;
+
+    #[test]
+    fn test_find_label_problems() {
+        let version = BuildpackVersion {
+            major: 1,
+            minor: 2,
+            patch: 3,
+        };
+        let config = json!({
+            "config": {
+                "Labels": {
+                    "io.buildpacks.buildpackage.metadata": r#"{"id":"heroku/nodejs","version":"1.2.2"}"#,
+                    "io.buildpacks.buildpack.layers": r#"{"heroku/nodejs":{"1.2.3":{"api":"0.9"}}}"#,
+                }
+            }
+        });
+
+        assert_eq!(
+            find_label_problems("image", &config, &buildpack_id!("heroku/nodejs"), &version),
+            vec![PublishProblem::WrongMetadata(
+                "image".to_string(),
+                "version".to_string(),
+                "1.2.3".to_string(),
+                "1.2.2".to_string()
+            )]
+        );
+        assert_eq!(
+            find_label_problems("image", &config, &buildpack_id!("heroku/java"), &version).len(),
+            3
+        );
+        assert_eq!(
+            find_label_problems(
+                "image",
+                &json!({}),
+                &buildpack_id!("heroku/nodejs"),
+                &version
+            ),
+            vec![
+                PublishProblem::MissingLabel(
+                    "image".to_string(),
+                    "io.buildpacks.buildpackage.metadata".to_string()
+                ),
+                PublishProblem::MissingLabel(
+                    "image".to_string(),
+                    "io.buildpacks.buildpack.layers".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_digest_problem() {
+        let same = vec![
+            ("a".to_string(), "sha256:1".to_string()),
+            ("b".to_string(), "sha256:1".to_string()),
+        ];
+        assert_eq!(find_digest_problem(&same), None);
+        assert_eq!(find_digest_problem(&[]), None);
+
+        let different = vec![
+            ("a".to_string(), "sha256:1".to_string()),
+            ("b".to_string(), "sha256:2".to_string()),
+        ];
+        assert_eq!(
+            find_digest_problem(&different),
+            Some(PublishProblem::DigestMismatch(different.clone()))
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use crate::error::{CategorizedError, ErrorCategory};
+use languages_github_actions::cancellation::CancellationError;
+use languages_github_actions::github::actions::SetOutputError;
+use languages_github_actions::oci::OciError;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    InvalidVersion(String, libcnb_data::buildpack::BuildpackVersionError),
+    InvalidRegistry(String, OciError),
+    QueryingRegistry(String, OciError),
+    VerificationFailed(Vec<PublishProblem>),
+    Cancelled(CancellationError),
+    SetActionOutput(SetOutputError),
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) enum PublishProblem {
+    MissingTag(String),
+    DigestMismatch(Vec<(String, String)>),
+    MissingLabel(String, String),
+    InvalidLabel(String, String, String),
+    WrongMetadata(String, String, String, String),
+    MissingBuildpackLayer(String, String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::InvalidVersion(value, error) => {
+                write!(
+                    f,
+                    "The version argument is invalid\nValue: {value}\nError: {error}"
+                )
+            }
+
+            Error::InvalidRegistry(value, error) => {
+                write!(
+                    f,
+                    "The registries argument is invalid\nValue: {value}\nError: {error}"
+                )
+            }
+
+            Error::QueryingRegistry(image, error) => {
+                write!(
+                    f,
+                    "Could not verify the published image\nValue: {image}\nError: {error}"
+                )
+            }
+
+            Error::VerificationFailed(problems) => {
+                write!(
+                    f,
+                    "The published images failed verification:\n{}",
+                    problems
+                        .iter()
+                        .map(|problem| format!("• {problem}"))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            }
+
+            Error::Cancelled(error) => {
+                write!(f, "{error}")
+            }
+
+            Error::SetActionOutput(set_output_error) => match set_output_error {
+                SetOutputError::Opening(error) | SetOutputError::Writing(error) => {
+                    write!(f, "Could not write action output\nError: {error}")
+                }
+            },
+        }
+    }
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::InvalidVersion(_, _) | Error::InvalidRegistry(_, _) => ErrorCategory::Config,
+            Error::QueryingRegistry(_, error) => error.category(),
+            Error::VerificationFailed(_) => ErrorCategory::Validation,
+            Error::Cancelled(error) => error.category(),
+            Error::SetActionOutput(error) => error.category(),
+        }
+    }
+}
+
+impl Display for PublishProblem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PublishProblem::MissingTag(image) => {
+                write!(f, "{image} does not exist")
+            }
+
+            PublishProblem::DigestMismatch(digests) => {
+                write!(
+                    f,
+                    "The digests differ across registries ({})",
+                    digests
+                        .iter()
+                        .map(|(image, digest)| format!("{image} is {digest}"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            }
+
+            PublishProblem::MissingLabel(image, label) => {
+                write!(f, "{image} has no {label} label")
+            }
+
+            PublishProblem::InvalidLabel(image, label, error) => {
+                write!(f, "{image} has an invalid {label} label - {error}")
+            }
+
+            PublishProblem::WrongMetadata(image, field, expected, actual) => {
+                write!(
+                    f,
+                    "{image} has buildpackage {field} `{actual}`, expected `{expected}`"
+                )
+            }
+
+            PublishProblem::MissingBuildpackLayer(image, buildpack) => {
+                write!(f, "{image} has no layer for {buildpack}")
+            }
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub(crate) mod command;
+pub(crate) mod errors;
+
+pub(crate) use command::execute;
@@ -0,0 +1,193 @@
+use crate::log;
+use lazy_static::lazy_static;
+use markdown::mdast::Node;
+use markdown::{to_mdast, ParseOptions};
+use serde_json::{json, Value};
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use toml_edit::TomlError;
+
+pub const DEBUG_ARTIFACTS_DIR_ENV: &str = "ACTIONS_DEBUG_ARTIFACTS_DIR";
+
+static CAPTURE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+lazy_static! {
+    static ref ARTIFACTS_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+}
+
+// when set (or when `ACTIONS_DEBUG_ARTIFACTS_DIR` is), files that fail to parse are captured into
+// this directory so a workflow can upload them, a malformed changelog is otherwise hard to debug
+// from a runner log alone
+pub fn set_artifacts_dir(dir: PathBuf) {
+    *ARTIFACTS_DIR
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(dir);
+}
+
+fn artifacts_dir() -> Option<PathBuf> {
+    ARTIFACTS_DIR
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .clone()
+        .or_else(|| {
+            std::env::var_os(DEBUG_ARTIFACTS_DIR_ENV)
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from)
+        })
+}
+
+pub fn capture_markdown_failure(path: &Path, contents: &str, error: &dyn Display) {
+    capture(
+        path,
+        contents,
+        error,
+        Some(markdown_ast(contents)),
+        markdown_spans(contents),
+    );
+}
+
+pub fn capture_toml_failure(path: &Path, contents: &str, error: &TomlError) {
+    capture(
+        path,
+        contents,
+        error,
+        None,
+        toml_error_spans(contents, error),
+    );
+}
+
+// capturing is best-effort, a problem writing the artifacts is reported but never replaces the
+// original parsing error
+fn capture(path: &Path, contents: &str, error: &dyn Display, ast: Option<Value>, spans: Value) {
+    let dir = match artifacts_dir() {
+        Some(dir) => dir,
+        None => return,
+    };
+
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "contents".to_string());
+    let capture_dir = dir.join(format!(
+        "{:03}-{file_name}",
+        CAPTURE_COUNT.fetch_add(1, Ordering::SeqCst) + 1
+    ));
+
+    let mut files = vec![
+        (file_name, contents.to_string()),
+        (
+            "error.json".to_string(),
+            json!({
+                "path": path.display().to_string(),
+                "error": error.to_string(),
+            })
+            .to_string(),
+        ),
+        ("spans.json".to_string(), spans.to_string()),
+    ];
+    if let Some(ast) = ast {
+        files.push(("ast.json".to_string(), ast.to_string()));
+    }
+
+    let written = std::fs::create_dir_all(&capture_dir).and_then(|_| {
+        files
+            .iter()
+            .try_for_each(|(name, contents)| std::fs::write(capture_dir.join(name), contents))
+    });
+
+    match written {
+        Ok(_) => log!(
+            "ℹ️ Wrote debug artifacts for {} to {}",
+            path.display(),
+            capture_dir.display()
+        ),
+        Err(error) => log!(
+            "⚠️ Could not write debug artifacts for {} to {}: {error}",
+            path.display(),
+            capture_dir.display()
+        ),
+    }
+}
+
+fn markdown_ast(contents: &str) -> Value {
+    match to_mdast(contents, &ParseOptions::default()) {
+        Ok(ast) => serde_json::to_value(ast).unwrap_or_else(|e| json!({ "error": e.to_string() })),
+        Err(error) => json!({ "error": error }),
+    }
+}
+
+// the headings are what the changelog parser splits sections on, so their text and byte ranges
+// are usually enough to see why a version or date wasn't recognized
+fn markdown_spans(contents: &str) -> Value {
+    let headings = match to_mdast(contents, &ParseOptions::default()) {
+        Ok(Node::Root(root)) => root
+            .children
+            .iter()
+            .filter_map(|child| match (child, child.position()) {
+                (Node::Heading(heading), Some(position)) => Some(json!({
+                    "depth": heading.depth,
+                    "text": child.to_string(),
+                    "line": position.start.line,
+                    "start": position.start.offset,
+                    "end": position.end.offset,
+                    "source": contents.get(position.start.offset..position.end.offset),
+                })),
+                _ => None,
+            })
+            .collect::<Vec<_>>(),
+        _ => vec![],
+    };
+    json!({ "headings": headings })
+}
+
+fn toml_error_spans(contents: &str, error: &TomlError) -> Value {
+    let span = error.span().map(|span| {
+        let before = &contents[..span.start.min(contents.len())];
+        json!({
+            "start": span.start,
+            "end": span.end,
+            "line": before.matches('\n').count() + 1,
+            "column": before.len() - before.rfind('\n').map(|i| i + 1).unwrap_or_default() + 1,
+            "source": contents.get(span),
+        })
+    });
+    json!({
+        "message": error.message(),
+        "error": span,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::debug_artifacts::{markdown_spans, toml_error_spans};
+    use serde_json::json;
+    use std::str::FromStr;
+    use toml_edit::Document;
+
+    #[test]
+    fn test_markdown_spans() {
+        let contents = "# Changelog\n\n## [Unreleased]\n\n## [1.0.0] - 2023-13-01\n\n- Initial\n";
+        assert_eq!(
+            markdown_spans(contents)["headings"][2],
+            json!({
+                "depth": 2,
+                "text": "[1.0.0] - 2023-13-01",
+                "line": 5,
+                "start": 30,
+                "end": 53,
+                "source": "## [1.0.0] - 2023-13-01",
+            })
+        );
+    }
+
+    #[test]
+    fn test_toml_error_spans() {
+        let contents = "[buildpack]\nid = \"heroku/nodejs\"\nversion = \n";
+        let error = Document::from_str(contents).unwrap_err();
+        let spans = toml_error_spans(contents, &error);
+        assert_eq!(spans["error"]["line"], 3);
+        assert_eq!(spans["error"]["column"], 11);
+    }
+}
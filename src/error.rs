@@ -0,0 +1,390 @@
+use clap::ValueEnum;
+use languages_github_actions::builder::{
+    BuilderChannelsError, BuilderFileError, BuildpackUriTemplateError, PinDigestsError,
+};
+use languages_github_actions::buildpack::{BuildpackDiscoveryError, BuildpackFileError};
+use languages_github_actions::buildpackage::BuildpackageError;
+use languages_github_actions::cancellation::{check_cancelled, CancellationError};
+use languages_github_actions::changelog::{ChangelogError, ChangelogFileError};
+use languages_github_actions::cnb_registry::CnbRegistryError;
+use languages_github_actions::experimental::ExperimentalFeatureError;
+use languages_github_actions::git::GitError;
+use languages_github_actions::github::actions::{LargeOutputError, SetOutputError};
+use languages_github_actions::github::api::GitHubApiError;
+use languages_github_actions::github::auth::AuthError;
+use languages_github_actions::github::context::GitHubContextError;
+use languages_github_actions::log;
+use languages_github_actions::oci::OciError;
+use languages_github_actions::release_manifest::ReleaseManifestError;
+use languages_github_actions::release_state::ReleaseStateError;
+use languages_github_actions::sbom::SbomError;
+use languages_github_actions::shared_paths::SharedPathsError;
+use languages_github_actions::version_locations::VersionLocationError;
+use languages_github_actions::versioning::VersionSchemeError;
+use libcnb_package::ReadBuildpackDataError;
+use serde::Serialize;
+use serde_json::json;
+use std::fmt::Display;
+
+#[derive(Serialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ErrorCategory {
+    Config,
+    Parse,
+    Io,
+    Network,
+    Validation,
+    Cancelled,
+}
+
+impl ErrorCategory {
+    pub(crate) fn exit_code(&self) -> i32 {
+        match self {
+            ErrorCategory::Config => 2,
+            ErrorCategory::Parse => 3,
+            ErrorCategory::Io => 4,
+            ErrorCategory::Network => 5,
+            ErrorCategory::Validation => 6,
+            ErrorCategory::Cancelled => 7,
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Default)]
+pub(crate) enum ErrorFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+pub(crate) trait CategorizedError {
+    fn category(&self) -> ErrorCategory;
+}
+
+pub(crate) fn exit_with_error<E: CategorizedError + Display>(
+    error: E,
+    error_format: &ErrorFormat,
+) -> ! {
+    // a request cut off by `--timeout` or a signal fails with its own error (e.g.; a network
+    // timeout), it's still reported as cancelled since that's why it failed
+    let category = match check_cancelled() {
+        Ok(()) => error.category(),
+        Err(_) => ErrorCategory::Cancelled,
+    };
+    match error_format {
+        // the JSON error object is already structured so it's written as-is
+        ErrorFormat::Text => log!("{}", format_error(&error, category, error_format)),
+        ErrorFormat::Json => eprintln!("{}", format_error(&error, category, error_format)),
+    }
+    std::process::exit(category.exit_code())
+}
+
+fn format_error<E: Display>(
+    error: &E,
+    category: ErrorCategory,
+    error_format: &ErrorFormat,
+) -> String {
+    match error_format {
+        ErrorFormat::Text => format!("❌ {error}"),
+        ErrorFormat::Json => json!({
+            "category": category,
+            "exit_code": category.exit_code(),
+            "message": error.to_string(),
+        })
+        .to_string(),
+    }
+}
+
+impl CategorizedError for BuildpackFileError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            BuildpackFileError::Reading(_, _) => ErrorCategory::Io,
+            BuildpackFileError::Parsing(_, _) => ErrorCategory::Parse,
+            BuildpackFileError::MissingRequiredField(_, _)
+            | BuildpackFileError::InvalidBuildpackId(_, _)
+            | BuildpackFileError::InvalidBuildpackVersion(_, _)
+            | BuildpackFileError::InvalidFieldValue(_, _)
+            | BuildpackFileError::InvalidTable(_, _) => ErrorCategory::Validation,
+        }
+    }
+}
+
+impl CategorizedError for BuildpackDiscoveryError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            BuildpackDiscoveryError::Walking(_) => ErrorCategory::Io,
+            BuildpackDiscoveryError::DuplicateBuildpackIds(_) => ErrorCategory::Validation,
+        }
+    }
+}
+
+impl CategorizedError for BuilderFileError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            BuilderFileError::Reading(_, _) => ErrorCategory::Io,
+            BuilderFileError::Parsing(_, _) | BuilderFileError::Patching(_, _) => {
+                ErrorCategory::Parse
+            }
+            BuilderFileError::MissingRequiredKey(_, _)
+            | BuilderFileError::InvalidOrderIndex(_, _, _)
+            | BuilderFileError::InvalidTable(_, _) => ErrorCategory::Validation,
+        }
+    }
+}
+
+impl CategorizedError for PinDigestsError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            PinDigestsError::ResolvingDigest(_, error) => error.category(),
+            PinDigestsError::Cancelled(error) => error.category(),
+        }
+    }
+}
+
+impl CategorizedError for BuilderChannelsError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            BuilderChannelsError::Reading(_, _) => ErrorCategory::Io,
+            BuilderChannelsError::Parsing(_, _) => ErrorCategory::Parse,
+            BuilderChannelsError::UnknownChannel(_, _)
+            | BuilderChannelsError::InvalidBuilders(_, _)
+            | BuilderChannelsError::ConflictingChannels(_, _) => ErrorCategory::Config,
+        }
+    }
+}
+
+impl CategorizedError for BuildpackUriTemplateError {
+    fn category(&self) -> ErrorCategory {
+        ErrorCategory::Config
+    }
+}
+
+impl CategorizedError for BuildpackageError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            BuildpackageError::BuildpackFile(error) => error.category(),
+            BuildpackageError::CompositeBuildpack(_) => ErrorCategory::Config,
+            BuildpackageError::CreatingLayer(_, _) => ErrorCategory::Io,
+        }
+    }
+}
+
+impl CategorizedError for ChangelogError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            ChangelogError::NoUnreleasedSection | ChangelogError::ReleaseNotFound(_) => {
+                ErrorCategory::Validation
+            }
+            ChangelogError::InvalidVersionRange(_)
+            | ChangelogError::InvalidReleaseDateOverride(_) => ErrorCategory::Config,
+            _ => ErrorCategory::Parse,
+        }
+    }
+}
+
+impl CategorizedError for ChangelogFileError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            ChangelogFileError::NotFound(_) => ErrorCategory::Config,
+            ChangelogFileError::Reading(_, _) => ErrorCategory::Io,
+            ChangelogFileError::Parsing(_, error) => error.category(),
+        }
+    }
+}
+
+impl CategorizedError for ExperimentalFeatureError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            ExperimentalFeatureError::Disabled(_) => ErrorCategory::Config,
+        }
+    }
+}
+
+impl CategorizedError for VersionLocationError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            VersionLocationError::InvalidRule(_, _, _)
+            | VersionLocationError::InvalidPattern(_, _, _) => ErrorCategory::Config,
+            VersionLocationError::Reading(_, _) => ErrorCategory::Io,
+            VersionLocationError::ParsingToml(_, _) => ErrorCategory::Parse,
+            VersionLocationError::NoMatch(_, _) | VersionLocationError::InvalidKey(_, _) => {
+                ErrorCategory::Validation
+            }
+        }
+    }
+}
+
+impl CategorizedError for ReleaseManifestError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            ReleaseManifestError::Reading(_, _) => ErrorCategory::Io,
+            ReleaseManifestError::Parsing(_, _) => ErrorCategory::Parse,
+        }
+    }
+}
+
+impl CategorizedError for ReleaseStateError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            ReleaseStateError::Reading(_, _) => ErrorCategory::Io,
+            ReleaseStateError::Parsing(_, _) => ErrorCategory::Parse,
+            ReleaseStateError::InvalidRecord(_, _) => ErrorCategory::Config,
+        }
+    }
+}
+
+impl CategorizedError for SharedPathsError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            SharedPathsError::Reading(_, _) => ErrorCategory::Io,
+            SharedPathsError::Parsing(_, _) => ErrorCategory::Parse,
+            SharedPathsError::Invalid(_, _) => ErrorCategory::Config,
+        }
+    }
+}
+
+impl CategorizedError for VersionSchemeError {
+    fn category(&self) -> ErrorCategory {
+        ErrorCategory::Validation
+    }
+}
+
+impl CategorizedError for OciError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            OciError::UnsupportedScheme(_) | OciError::InvalidImageReference(_) => {
+                ErrorCategory::Config
+            }
+            OciError::ReadingDigestCache(_, _) | OciError::WritingDigestCache(_, _) => {
+                ErrorCategory::Io
+            }
+            OciError::ParsingDigestCache(_, _) => ErrorCategory::Parse,
+            OciError::ManifestNotFound(_) => ErrorCategory::Validation,
+            OciError::InvalidManifest(_) | OciError::ReadingResponse(_, _) => ErrorCategory::Parse,
+            OciError::Cancelled(error) => error.category(),
+            _ => ErrorCategory::Network,
+        }
+    }
+}
+
+impl CategorizedError for CancellationError {
+    fn category(&self) -> ErrorCategory {
+        ErrorCategory::Cancelled
+    }
+}
+
+impl CategorizedError for CnbRegistryError {
+    fn category(&self) -> ErrorCategory {
+        ErrorCategory::Network
+    }
+}
+
+impl CategorizedError for AuthError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            AuthError::MissingCredentials | AuthError::InvalidPrivateKey => ErrorCategory::Config,
+            AuthError::Jwt(_) | AuthError::Signing(_) => ErrorCategory::Validation,
+            AuthError::Request(_, _) | AuthError::ReadingResponse(_, _) => ErrorCategory::Network,
+        }
+    }
+}
+
+impl CategorizedError for GitHubApiError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            GitHubApiError::InvalidContent(_, _) => ErrorCategory::Parse,
+            _ => ErrorCategory::Network,
+        }
+    }
+}
+
+impl CategorizedError for GitHubContextError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            GitHubContextError::ReadingEvent(_, _) => ErrorCategory::Io,
+            GitHubContextError::ParsingEvent(_, _) => ErrorCategory::Parse,
+            GitHubContextError::InvalidBooleanInput(_, _) => ErrorCategory::Config,
+        }
+    }
+}
+
+impl CategorizedError for GitError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            GitError::Running(_, _) => ErrorCategory::Io,
+            GitError::Failed(_, _) => ErrorCategory::Config,
+            GitError::UncommittedChanges(_) | GitError::UnexpectedBranch(_, _) => {
+                ErrorCategory::Validation
+            }
+        }
+    }
+}
+
+impl CategorizedError for SetOutputError {
+    fn category(&self) -> ErrorCategory {
+        ErrorCategory::Io
+    }
+}
+
+impl CategorizedError for SbomError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            SbomError::Reading(_, _) => ErrorCategory::Io,
+            SbomError::Parsing(_, _) => ErrorCategory::Parse,
+        }
+    }
+}
+
+impl CategorizedError for LargeOutputError {
+    fn category(&self) -> ErrorCategory {
+        ErrorCategory::Io
+    }
+}
+
+impl CategorizedError for ReadBuildpackDataError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            ReadBuildpackDataError::ReadingBuildpack { .. } => ErrorCategory::Io,
+            ReadBuildpackDataError::ParsingBuildpack { .. } => ErrorCategory::Parse,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::{format_error, CategorizedError, ErrorCategory, ErrorFormat};
+    use languages_github_actions::changelog::ChangelogError;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_exit_codes_are_distinct() {
+        let categories = [
+            ErrorCategory::Config,
+            ErrorCategory::Parse,
+            ErrorCategory::Io,
+            ErrorCategory::Network,
+            ErrorCategory::Validation,
+            ErrorCategory::Cancelled,
+        ];
+        let exit_codes = categories
+            .iter()
+            .map(|category| category.exit_code())
+            .collect::<HashSet<_>>();
+        assert_eq!(exit_codes.len(), categories.len());
+        assert!(!exit_codes.contains(&0));
+        assert!(!exit_codes.contains(&1));
+    }
+
+    #[test]
+    fn test_format_error() {
+        let error = ChangelogError::NoUnreleasedSection;
+        assert_eq!(
+            format_error(&error, error.category(), &ErrorFormat::Text),
+            "❌ No Unreleased section in changelog"
+        );
+        assert_eq!(
+            format_error(&error, error.category(), &ErrorFormat::Json),
+            r#"{"category":"validation","exit_code":6,"message":"No Unreleased section in changelog"}"#
+        );
+    }
+}
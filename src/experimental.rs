@@ -0,0 +1,101 @@
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+
+pub const EXPERIMENTAL_ENV: &str = "ACTIONS_EXPERIMENTAL";
+
+const ALL_FEATURES: [&str; 3] = ["1", "true", "all"];
+
+// the experimental commands and behaviors enabled for this run, `ACTIONS_EXPERIMENTAL` is either
+// a comma-separated list of feature names or one of `1`, `true`, or `all` to enable everything
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ExperimentalFeatures {
+    all: bool,
+    names: BTreeSet<String>,
+}
+
+impl ExperimentalFeatures {
+    pub fn from_env() -> ExperimentalFeatures {
+        std::env::var(EXPERIMENTAL_ENV)
+            .map(|value| ExperimentalFeatures::from_value(&value))
+            .unwrap_or_default()
+    }
+
+    pub fn from_value(value: &str) -> ExperimentalFeatures {
+        let names = value
+            .split(',')
+            .map(|name| name.trim().to_lowercase())
+            .filter(|name| !name.is_empty())
+            .collect::<BTreeSet<_>>();
+        ExperimentalFeatures {
+            all: names
+                .iter()
+                .any(|name| ALL_FEATURES.contains(&name.as_str())),
+            names,
+        }
+    }
+
+    pub fn is_enabled(&self, feature: &str) -> bool {
+        self.all || self.names.contains(&feature.to_lowercase())
+    }
+
+    pub fn any_enabled(&self) -> bool {
+        self.all || !self.names.is_empty()
+    }
+
+    pub fn require(&self, feature: &str) -> Result<(), ExperimentalFeatureError> {
+        if self.is_enabled(feature) {
+            Ok(())
+        } else {
+            Err(ExperimentalFeatureError::Disabled(feature.to_string()))
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ExperimentalFeatureError {
+    Disabled(String),
+}
+
+impl Display for ExperimentalFeatureError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExperimentalFeatureError::Disabled(feature) => {
+                write!(
+                    f,
+                    "`{feature}` is experimental and must be enabled with `{EXPERIMENTAL_ENV}={feature}` or `{EXPERIMENTAL_ENV}=all`"
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::experimental::ExperimentalFeatures;
+
+    #[test]
+    fn test_features_from_list() {
+        let features = ExperimentalFeatures::from_value(" sync-meta-buildpacks, Other-Feature ,,");
+        assert!(features.any_enabled());
+        assert!(features.is_enabled("sync-meta-buildpacks"));
+        assert!(features.is_enabled("other-feature"));
+        assert!(!features.is_enabled("missing"));
+        assert!(features.require("missing").is_err());
+    }
+
+    #[test]
+    fn test_all_features() {
+        for value in ["1", "true", "ALL"] {
+            let features = ExperimentalFeatures::from_value(value);
+            assert!(features.is_enabled("anything"));
+        }
+    }
+
+    #[test]
+    fn test_no_features() {
+        let features = ExperimentalFeatures::from_value("");
+        assert!(!features.any_enabled());
+        assert!(!features.is_enabled("sync-meta-buildpacks"));
+        assert_eq!(features, ExperimentalFeatures::default());
+    }
+}
@@ -0,0 +1,187 @@
+use clap::Args;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+type Result<T> = std::result::Result<T, GitError>;
+
+#[derive(Args, Debug)]
+pub struct WorktreeGuardArgs {
+    #[arg(long)]
+    pub require_clean_worktree: bool,
+    #[arg(long)]
+    pub expected_branch: Option<String>,
+}
+
+impl WorktreeGuardArgs {
+    pub fn check(&self, dir: &Path) -> Result<()> {
+        if self.require_clean_worktree {
+            let changes = get_uncommitted_changes(dir)?;
+            if !changes.is_empty() {
+                Err(GitError::UncommittedChanges(changes))?;
+            }
+        }
+
+        if let Some(expected_branch) = &self.expected_branch {
+            let current_branch = get_current_branch(dir)?;
+            if &current_branch != expected_branch {
+                Err(GitError::UnexpectedBranch(
+                    expected_branch.clone(),
+                    current_branch,
+                ))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn get_uncommitted_changes(dir: &Path) -> Result<Vec<String>> {
+    run_git(dir, &["status", "--porcelain"]).map(|output| parse_porcelain_status(&output))
+}
+
+pub fn get_current_branch(dir: &Path) -> Result<String> {
+    run_git(dir, &["rev-parse", "--abbrev-ref", "HEAD"]).map(|output| output.trim().to_string())
+}
+
+// the full sha of the commit a revision (e.g.; `HEAD` or a tag name) points to
+pub fn get_commit_sha(dir: &Path, revision: &str) -> Result<String> {
+    run_git(
+        dir,
+        &["rev-parse", "--verify", &format!("{revision}^{{commit}}")],
+    )
+    .map(|output| output.trim().to_string())
+}
+
+// paths changed between the merge base of `revision` and `HEAD`, relative to `dir`, so a pull
+// request branch only sees its own changes
+pub fn get_changed_files(dir: &Path, revision: &str) -> Result<Vec<String>> {
+    run_git(
+        dir,
+        &[
+            "diff",
+            "--name-only",
+            "--relative",
+            &format!("{revision}...HEAD"),
+            "--",
+        ],
+    )
+    .map(|output| {
+        output
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(String::from)
+            .collect()
+    })
+}
+
+// tag names on the remote, annotated tags are listed once even though `ls-remote` reports both
+// the tag and the commit it peels to
+pub fn get_remote_tags(dir: &Path, remote: &str) -> Result<Vec<String>> {
+    run_git(dir, &["ls-remote", "--tags", remote]).map(|output| parse_remote_tags(&output))
+}
+
+fn run_git(dir: &Path, args: &[&str]) -> Result<String> {
+    let command = format!("git {}", args.join(" "));
+
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| GitError::Running(command.clone(), e))?;
+
+    if !output.status.success() {
+        Err(GitError::Failed(
+            command,
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))?;
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn parse_porcelain_status(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.get(3..).unwrap_or(line).to_string())
+        .collect()
+}
+
+fn parse_remote_tags(output: &str) -> Vec<String> {
+    let mut tags = output
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .filter_map(|(_, name)| name.strip_prefix("refs/tags/"))
+        .map(|name| name.trim_end_matches("^{}").to_string())
+        .collect::<Vec<_>>();
+    tags.dedup();
+    tags
+}
+
+#[derive(Debug)]
+pub enum GitError {
+    Running(String, io::Error),
+    Failed(String, String),
+    UncommittedChanges(Vec<String>),
+    UnexpectedBranch(String, String),
+}
+
+impl Display for GitError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GitError::Running(command, error) => {
+                write!(f, "Could not run `{command}`\nError: {error}")
+            }
+
+            GitError::Failed(command, stderr) => {
+                write!(f, "Command `{command}` failed\nError: {stderr}")
+            }
+
+            GitError::UncommittedChanges(paths) => {
+                write!(
+                    f,
+                    "Refusing to run with uncommitted changes in the worktree:\n{}",
+                    paths
+                        .iter()
+                        .map(|path| format!("• {path}"))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            }
+
+            GitError::UnexpectedBranch(expected, actual) => {
+                write!(
+                    f,
+                    "Refusing to run on branch `{actual}`, expected branch `{expected}`"
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::git::{parse_porcelain_status, parse_remote_tags};
+
+    #[test]
+    fn test_parse_porcelain_status() {
+        assert_eq!(
+            parse_porcelain_status(" M Cargo.toml\n?? src/git.rs\nR  old.rs -> new.rs\n"),
+            vec!["Cargo.toml", "src/git.rs", "old.rs -> new.rs"]
+        );
+        assert!(parse_porcelain_status("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_remote_tags() {
+        assert_eq!(
+            parse_remote_tags(
+                "1b2c\trefs/tags/v1.0.0\n3d4e\trefs/tags/v1.0.1\n5f6a\trefs/tags/v1.0.1^{}\n"
+            ),
+            vec!["v1.0.0", "v1.0.1"]
+        );
+        assert!(parse_remote_tags("").is_empty());
+    }
+}
@@ -1,40 +1,480 @@
+use crate::log;
+use crate::progress::{track, ProgressStep};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use clap::{Args, ValueEnum};
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use rand::distributions::{Alphanumeric, DistString};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
 use std::fs::OpenOptions;
 use std::io;
 use std::io::{stdout, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::sync::Mutex;
 
-pub(crate) fn set_output<N: Into<String>, V: Into<String>>(
+// outside of GitHub Actions there is no `GITHUB_OUTPUT` so outputs can be collected in a file other CI
+// systems can read instead, a `.json` file gets a single object and anything else is written as dotenv
+pub const OUTPUTS_FILE_ENV: &str = "ACTIONS_OUTPUTS_FILE";
+
+pub const OUTPUTS_JSON_OUTPUT: &str = "outputs_json";
+
+// step outputs are capped at 1MB so `outputs_json` can't copy every value, large ones (e.g.; a changelog
+// or matrix written without `--output-file`) are left out and the whole object is kept to half the cap
+const OUTPUTS_JSON_VALUE_LIMIT: usize = 64 * 1024;
+const OUTPUTS_JSON_LIMIT: usize = 512 * 1024;
+
+static STDOUT_NOTICE: AtomicBool = AtomicBool::new(false);
+
+// keyed by name so setting an output again replaces it, values over the limit are only kept as `None`
+static WRITTEN_OUTPUTS: Mutex<BTreeMap<String, Option<String>>> = Mutex::new(BTreeMap::new());
+
+pub fn set_output<N: Into<String>, V: Into<String>>(
     name: N,
     value: V,
 ) -> Result<(), SetOutputError> {
     let name = name.into();
     let value = value.into();
+    track(ProgressStep::Output, name.clone(), || {
+        write_output(&name, &value)
+    })?;
+    WRITTEN_OUTPUTS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(
+            name,
+            (value.len() <= OUTPUTS_JSON_VALUE_LIMIT).then_some(value),
+        );
+    Ok(())
+}
 
-    let line = if value.contains('\n') {
-        let delimiter = Alphanumeric.sample_string(&mut rand::thread_rng(), 20);
-        format!("{name}<<{delimiter}\n{value}\n{delimiter}")
-    } else {
-        format!("{name}={value}")
+// every output written by a command collected into one JSON object so a workflow can pass the whole
+// result along (e.g.; to another job) without listing each output, nothing is written when the
+// command didn't set any outputs
+pub fn set_outputs_json() -> Result<(), SetOutputError> {
+    let outputs = WRITTEN_OUTPUTS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone();
+    if outputs.is_empty() {
+        return Ok(());
+    }
+    let (value, omitted) = format_outputs_json(&outputs);
+    for name in omitted {
+        log!("ℹ️ Left {name} out of {OUTPUTS_JSON_OUTPUT} since it's too large, read the {name} output instead");
+    }
+    track(ProgressStep::Output, OUTPUTS_JSON_OUTPUT, || {
+        write_output(OUTPUTS_JSON_OUTPUT, &value)
+    })
+}
+
+// returns the object along with the names of the outputs left out of it to stay under the limit
+fn format_outputs_json(outputs: &BTreeMap<String, Option<String>>) -> (String, Vec<String>) {
+    let mut object = serde_json::Map::new();
+    let mut omitted = vec![];
+    let mut size = 0;
+    for (name, value) in outputs {
+        match value {
+            Some(value) if size + name.len() + value.len() <= OUTPUTS_JSON_LIMIT => {
+                size += name.len() + value.len();
+                object.insert(name.clone(), serde_json::Value::from(value.as_str()));
+            }
+            _ => omitted.push(name.clone()),
+        }
+    }
+    (serde_json::Value::Object(object).to_string(), omitted)
+}
+
+fn write_output(name: &str, value: &str) -> Result<(), SetOutputError> {
+    if let Ok(github_output) = std::env::var("GITHUB_OUTPUT") {
+        let line = if value.contains('\n') {
+            let delimiter = Alphanumeric.sample_string(&mut rand::thread_rng(), 20);
+            format!("{name}<<{delimiter}\n{value}\n{delimiter}")
+        } else {
+            format!("{name}={value}")
+        };
+        return append_line(PathBuf::from(github_output), &line);
+    }
+
+    match std::env::var_os(OUTPUTS_FILE_ENV).filter(|value| !value.is_empty()) {
+        Some(outputs_file) => {
+            let outputs_file = PathBuf::from(outputs_file);
+            if outputs_file.extension().map_or(false, |ext| ext == "json") {
+                write_json_output(&outputs_file, name, value)
+            } else {
+                append_line(outputs_file, &format_dotenv_output(name, value))
+            }
+        }
+        None => {
+            if !STDOUT_NOTICE.swap(true, AtomicOrdering::Relaxed) {
+                log!("ℹ️ GITHUB_OUTPUT is not set, printing outputs instead (use --outputs-file or {OUTPUTS_FILE_ENV} to write them to a file)");
+            }
+            stdout()
+                .write_all(format!("{}\n", format_dotenv_output(name, value)).as_bytes())
+                .map_err(SetOutputError::Writing)
+        }
+    }
+}
+
+fn append_line(path: PathBuf, line: &str) -> Result<(), SetOutputError> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(SetOutputError::Opening)?
+        .write_all(format!("{line}\n").as_bytes())
+        .map_err(SetOutputError::Writing)
+}
+
+fn write_json_output(path: &Path, name: &str, value: &str) -> Result<(), SetOutputError> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => Some(contents),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+        Err(error) => return Err(SetOutputError::Opening(error)),
     };
-    let line = format!("{line}\n");
+    let outputs = merge_json_output(contents.as_deref(), name, value)
+        .map_err(|e| SetOutputError::Opening(io::Error::new(io::ErrorKind::InvalidData, e)))?;
+    std::fs::write(path, outputs).map_err(SetOutputError::Writing)
+}
 
-    let mut file: Box<dyn Write> = match std::env::var("GITHUB_OUTPUT") {
-        Ok(github_output) => {
-            let append_file = OpenOptions::new()
-                .append(true)
-                .open(github_output)
-                .map_err(SetOutputError::Opening)?;
-            Box::new(append_file)
+fn merge_json_output(
+    contents: Option<&str>,
+    name: &str,
+    value: &str,
+) -> Result<String, serde_json::Error> {
+    let mut outputs = match contents
+        .map(str::trim)
+        .filter(|contents| !contents.is_empty())
+    {
+        Some(contents) => {
+            serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(contents)?
         }
-        Err(_) => Box::new(stdout()),
+        None => serde_json::Map::new(),
     };
+    outputs.insert(name.to_string(), serde_json::Value::from(value));
+    serde_json::to_string_pretty(&outputs)
+}
 
-    file.write_all(line.as_bytes())
-        .map_err(SetOutputError::Writing)
+// values are always double-quoted so multi-line outputs stay on one line
+fn format_dotenv_output(name: &str, value: &str) -> String {
+    let value = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("{name}=\"{value}\"")
+}
+
+pub fn add_mask<V: Into<String>>(value: V) {
+    println!("::add-mask::{}", value.into());
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum AnnotationLevel {
+    Notice,
+    Warning,
+    Error,
+}
+
+impl Display for AnnotationLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnnotationLevel::Notice => write!(f, "notice"),
+            AnnotationLevel::Warning => write!(f, "warning"),
+            AnnotationLevel::Error => write!(f, "error"),
+        }
+    }
+}
+
+// `file` should be relative to the repository root so GitHub can link the annotation to it
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct AnnotationProperties {
+    pub title: Option<String>,
+    pub file: Option<String>,
+}
+
+// annotations are workflow commands printed to stdout, they're only printed when running in GitHub
+// Actions since elsewhere stdout may be where the outputs are printed
+pub fn annotate<M: Into<String>>(
+    level: AnnotationLevel,
+    message: M,
+    properties: &AnnotationProperties,
+) {
+    if std::env::var("GITHUB_ACTIONS").map_or(false, |value| value == "true") {
+        println!("{}", format_annotation(level, &message.into(), properties));
+    }
+}
+
+pub fn notice<M: Into<String>>(message: M, properties: &AnnotationProperties) {
+    annotate(AnnotationLevel::Notice, message, properties);
+}
+
+fn format_annotation(
+    level: AnnotationLevel,
+    message: &str,
+    properties: &AnnotationProperties,
+) -> String {
+    let escape_data = |value: &str| {
+        value
+            .replace('%', "%25")
+            .replace('\r', "%0D")
+            .replace('\n', "%0A")
+    };
+    let escape_property = |value: &str| escape_data(value).replace(':', "%3A").replace(',', "%2C");
+
+    let properties = [("title", &properties.title), ("file", &properties.file)]
+        .into_iter()
+        .filter_map(|(name, value)| {
+            value
+                .as_ref()
+                .map(|value| format!("{name}={}", escape_property(value)))
+        })
+        .collect::<Vec<_>>();
+
+    if properties.is_empty() {
+        format!("::{level}::{}", escape_data(message))
+    } else {
+        format!(
+            "::{level} {}::{}",
+            properties.join(","),
+            escape_data(message)
+        )
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Default)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+pub fn format_list_output(values: &[String], output_format: &OutputFormat) -> String {
+    match output_format {
+        OutputFormat::Text => values.join("\n"),
+        OutputFormat::Json => serde_json::Value::from(values).to_string(),
+    }
 }
 
 #[derive(Debug)]
-pub(crate) enum SetOutputError {
+pub enum SetOutputError {
     Opening(io::Error),
     Writing(io::Error),
 }
+
+// step outputs are capped at 1MB so large values can be written to a file and/or compressed
+#[derive(Args, Debug)]
+pub struct LargeOutputArgs {
+    #[arg(long)]
+    pub output_file: Option<PathBuf>,
+    #[arg(long, value_enum, default_value_t)]
+    pub output_encoding: OutputEncoding,
+}
+
+#[derive(ValueEnum, Debug, Clone, Default)]
+pub enum OutputEncoding {
+    #[default]
+    Plain,
+    GzipBase64,
+}
+
+impl LargeOutputArgs {
+    pub fn set_output<N: Into<String>, V: Into<String>>(
+        &self,
+        name: N,
+        value: V,
+    ) -> Result<(), LargeOutputError> {
+        let name = name.into();
+        let value = encode_output(&value.into(), &self.output_encoding)
+            .map_err(LargeOutputError::Encoding)?;
+
+        match &self.output_file {
+            Some(output_file) => {
+                std::fs::write(output_file, value)
+                    .map_err(|e| LargeOutputError::WritingFile(output_file.clone(), e))?;
+                set_output(
+                    format!("{name}_file"),
+                    output_file.to_string_lossy().to_string(),
+                )
+            }
+            None => set_output(name, value),
+        }
+        .map_err(LargeOutputError::SetOutput)
+    }
+}
+
+pub fn encode_output(value: &str, encoding: &OutputEncoding) -> io::Result<String> {
+    match encoding {
+        OutputEncoding::Plain => Ok(value.to_string()),
+        OutputEncoding::GzipBase64 => {
+            let mut encoder = GzEncoder::new(vec![], Compression::default());
+            encoder.write_all(value.as_bytes())?;
+            Ok(STANDARD.encode(encoder.finish()?))
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum LargeOutputError {
+    Encoding(io::Error),
+    WritingFile(PathBuf, io::Error),
+    SetOutput(SetOutputError),
+}
+
+impl Display for LargeOutputError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LargeOutputError::Encoding(error) => {
+                write!(f, "Could not encode action output\nError: {error}")
+            }
+
+            LargeOutputError::WritingFile(path, error) => {
+                write!(
+                    f,
+                    "Could not write action output file\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            LargeOutputError::SetOutput(SetOutputError::Opening(error))
+            | LargeOutputError::SetOutput(SetOutputError::Writing(error)) => {
+                write!(f, "Could not write action output\nError: {error}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::github::actions::{
+        encode_output, format_annotation, format_dotenv_output, format_list_output,
+        format_outputs_json, merge_json_output, AnnotationLevel, AnnotationProperties,
+        OutputEncoding, OutputFormat, OUTPUTS_JSON_LIMIT, OUTPUTS_JSON_VALUE_LIMIT,
+    };
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use flate2::read::GzDecoder;
+    use std::collections::BTreeMap;
+    use std::io::Read;
+
+    #[test]
+    fn test_format_annotation() {
+        assert_eq!(
+            format_annotation(
+                AnnotationLevel::Notice,
+                "heroku/nodejs 1.0.0 → 1.1.0",
+                &AnnotationProperties::default()
+            ),
+            "::notice::heroku/nodejs 1.0.0 → 1.1.0"
+        );
+        assert_eq!(
+            format_annotation(
+                AnnotationLevel::Warning,
+                "100% done\nnext line",
+                &AnnotationProperties {
+                    title: Some("heroku/nodejs: 1,2".to_string()),
+                    file: Some("buildpacks/nodejs/buildpack.toml".to_string()),
+                }
+            ),
+            "::warning title=heroku/nodejs%3A 1%2C2,file=buildpacks/nodejs/buildpack.toml::100%25 done%0Anext line"
+        );
+    }
+
+    #[test]
+    fn test_format_list_output() {
+        let values = vec![
+            "buildpacks/a/buildpack.toml".to_string(),
+            "buildpacks/a/CHANGELOG.md".to_string(),
+        ];
+        assert_eq!(
+            format_list_output(&values, &OutputFormat::Text),
+            "buildpacks/a/buildpack.toml\nbuildpacks/a/CHANGELOG.md"
+        );
+        assert_eq!(
+            format_list_output(&values, &OutputFormat::Json),
+            r#"["buildpacks/a/buildpack.toml","buildpacks/a/CHANGELOG.md"]"#
+        );
+    }
+
+    #[test]
+    fn test_encode_output() {
+        let value = r#"[{"id":"heroku/nodejs"}]"#;
+        assert_eq!(encode_output(value, &OutputEncoding::Plain).unwrap(), value);
+
+        let encoded = encode_output(value, &OutputEncoding::GzipBase64).unwrap();
+        let compressed = STANDARD.decode(encoded).unwrap();
+        let mut decoded = String::new();
+        GzDecoder::new(compressed.as_slice())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn test_format_dotenv_output() {
+        assert_eq!(
+            format_dotenv_output("to_version", "1.2.0"),
+            r#"to_version="1.2.0""#
+        );
+        assert_eq!(
+            format_dotenv_output("changelog", "# heroku/nodejs\n\n- Said \"hi\" C:\\"),
+            r##"changelog="# heroku/nodejs\n\n- Said \"hi\" C:\\""##
+        );
+    }
+
+    #[test]
+    fn test_merge_json_output() {
+        let outputs = merge_json_output(None, "from_version", "1.1.0").unwrap();
+        let outputs = merge_json_output(Some(&outputs), "to_version", "1.2.0").unwrap();
+        let outputs = merge_json_output(Some(&outputs), "from_version", "1.1.1").unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&outputs).unwrap(),
+            serde_json::json!({ "from_version": "1.1.1", "to_version": "1.2.0" })
+        );
+        assert!(merge_json_output(Some("[]"), "to_version", "1.2.0").is_err());
+    }
+
+    #[test]
+    fn test_format_outputs_json() {
+        let outputs = BTreeMap::from([
+            ("from_version".to_string(), Some("1.1.1".to_string())),
+            (
+                "changelog".to_string(),
+                Some("- Added \"npm\"\n".to_string()),
+            ),
+        ]);
+        let (value, omitted) = format_outputs_json(&outputs);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&value).unwrap(),
+            serde_json::json!({ "from_version": "1.1.1", "changelog": "- Added \"npm\"\n" })
+        );
+        assert!(!value.contains('\n'));
+        assert!(omitted.is_empty());
+    }
+
+    #[test]
+    fn test_format_outputs_json_leaves_out_large_values() {
+        let chunk = "x".repeat(OUTPUTS_JSON_VALUE_LIMIT);
+        let mut outputs = BTreeMap::from([
+            ("changed".to_string(), Some("true".to_string())),
+            ("changelog".to_string(), None),
+            (
+                "changelog_file".to_string(),
+                Some("changelog.md".to_string()),
+            ),
+        ]);
+        for index in 0..10 {
+            outputs.insert(format!("chunk_{index}"), Some(chunk.clone()));
+        }
+
+        let (value, omitted) = format_outputs_json(&outputs);
+        assert!(value.len() <= OUTPUTS_JSON_LIMIT + 1024);
+        assert_eq!(omitted, vec!["changelog", "chunk_7", "chunk_8", "chunk_9"]);
+
+        let value = serde_json::from_str::<serde_json::Value>(&value).unwrap();
+        assert_eq!(value["changed"], "true");
+        assert_eq!(value["changelog_file"], "changelog.md");
+        assert_eq!(value["chunk_6"], chunk.as_str());
+    }
+}
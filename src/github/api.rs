@@ -0,0 +1,731 @@
+use crate::github::GITHUB_API_URL;
+use crate::http;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::fmt::{Display, Formatter};
+
+const CREATE_COMMIT_ON_BRANCH_MUTATION: &str = r#"
+mutation ($input: CreateCommitOnBranchInput!) {
+  createCommitOnBranch(input: $input) {
+    commit {
+      oid
+      url
+    }
+  }
+}
+"#;
+
+const DISCUSSION_CATEGORIES_QUERY: &str = r#"
+query ($owner: String!, $name: String!) {
+  repository(owner: $owner, name: $name) {
+    id
+    discussionCategories(first: 100) {
+      nodes {
+        id
+        name
+        slug
+      }
+    }
+  }
+}
+"#;
+
+const DISCUSSIONS_QUERY: &str = r#"
+query ($owner: String!, $name: String!, $categoryId: ID!) {
+  repository(owner: $owner, name: $name) {
+    discussions(first: 100, categoryId: $categoryId, orderBy: { field: CREATED_AT, direction: DESC }) {
+      nodes {
+        number
+        title
+        url
+      }
+    }
+  }
+}
+"#;
+
+const CREATE_DISCUSSION_MUTATION: &str = r#"
+mutation ($input: CreateDiscussionInput!) {
+  createDiscussion(input: $input) {
+    discussion {
+      number
+      title
+      url
+    }
+  }
+}
+"#;
+
+pub struct FileAddition {
+    pub path: String,
+    pub contents: Vec<u8>,
+}
+
+pub struct CommitOnBranch {
+    pub repository: String,
+    pub branch: String,
+    pub expected_head_oid: String,
+    pub headline: String,
+    pub body: Option<String>,
+    pub additions: Vec<FileAddition>,
+    pub deletions: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+pub struct CreatedCommit {
+    pub oid: String,
+    pub url: String,
+}
+
+#[derive(Deserialize)]
+struct GraphQlResponse<T> {
+    data: Option<T>,
+    errors: Option<Vec<GraphQlError>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateCommitOnBranchData {
+    create_commit_on_branch: Option<CreateCommitOnBranchPayload>,
+}
+
+#[derive(Deserialize)]
+struct CreateCommitOnBranchPayload {
+    commit: CreatedCommit,
+}
+
+#[derive(Deserialize)]
+struct GraphQlError {
+    message: String,
+}
+
+pub fn create_ref(
+    token: &str,
+    repository: &str,
+    branch: &str,
+    sha: &str,
+) -> Result<(), GitHubApiError> {
+    let url = format!("{GITHUB_API_URL}/repos/{repository}/git/refs");
+    http::post(&url)
+        .set("Accept", "application/vnd.github+json")
+        .set("Authorization", &format!("Bearer {token}"))
+        .send_json(json!({ "ref": format!("refs/heads/{branch}"), "sha": sha }))
+        .map_err(|e| GitHubApiError::Request(url, Box::new(e)))?;
+    Ok(())
+}
+
+pub fn force_update_ref(
+    token: &str,
+    repository: &str,
+    branch: &str,
+    sha: &str,
+) -> Result<bool, GitHubApiError> {
+    let url = format!("{GITHUB_API_URL}/repos/{repository}/git/refs/heads/{branch}");
+    match http::patch(&url)
+        .set("Accept", "application/vnd.github+json")
+        .set("Authorization", &format!("Bearer {token}"))
+        .send_json(json!({ "sha": sha, "force": true }))
+    {
+        Ok(_) => Ok(true),
+        Err(ureq::Error::Status(404 | 422, _)) => Ok(false),
+        Err(error) => Err(GitHubApiError::Request(url, Box::new(error))),
+    }
+}
+
+// GraphQL reports failures in the response body, a request with errors is treated as failed even
+// when it returned partial data
+fn post_graphql<T: DeserializeOwned>(
+    token: &str,
+    query: &str,
+    variables: Value,
+) -> Result<T, GitHubApiError> {
+    let url = graphql_url();
+    let response = http::post(&url)
+        .set("Authorization", &format!("Bearer {token}"))
+        .send_json(json!({ "query": query, "variables": variables }))
+        .map_err(|e| GitHubApiError::Request(url.clone(), Box::new(e)))?
+        .into_json::<GraphQlResponse<T>>()
+        .map_err(|e| GitHubApiError::ReadingResponse(url.clone(), e))?;
+
+    if let Some(errors) = response.errors {
+        return Err(GitHubApiError::GraphQl(
+            url,
+            errors.into_iter().map(|error| error.message).collect(),
+        ));
+    }
+
+    response.data.ok_or(GitHubApiError::MissingData(url))
+}
+
+fn graphql_url() -> String {
+    format!("{GITHUB_API_URL}/graphql")
+}
+
+pub fn create_commit_on_branch(
+    token: &str,
+    commit: &CommitOnBranch,
+) -> Result<CreatedCommit, GitHubApiError> {
+    post_graphql::<CreateCommitOnBranchData>(
+        token,
+        CREATE_COMMIT_ON_BRANCH_MUTATION,
+        json!({ "input": create_commit_on_branch_input(commit) }),
+    )?
+    .create_commit_on_branch
+    .map(|payload| payload.commit)
+    .ok_or_else(|| GitHubApiError::MissingData(graphql_url()))
+}
+
+fn create_commit_on_branch_input(commit: &CommitOnBranch) -> Value {
+    let mut message = json!({ "headline": commit.headline });
+    if let Some(body) = &commit.body {
+        message["body"] = json!(body);
+    }
+
+    json!({
+        "branch": {
+            "repositoryNameWithOwner": commit.repository,
+            "branchName": commit.branch,
+        },
+        "expectedHeadOid": commit.expected_head_oid,
+        "message": message,
+        "fileChanges": {
+            "additions": commit
+                .additions
+                .iter()
+                .map(|addition| json!({
+                    "path": addition.path,
+                    "contents": STANDARD.encode(&addition.contents),
+                }))
+                .collect::<Vec<_>>(),
+            "deletions": commit
+                .deletions
+                .iter()
+                .map(|path| json!({ "path": path }))
+                .collect::<Vec<_>>(),
+        },
+    })
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+pub struct IssueComment {
+    pub id: u64,
+    pub body: Option<String>,
+    pub html_url: String,
+}
+
+const COMMENTS_PER_PAGE: usize = 100;
+
+// pull request comments are issue comments as far as the REST API is concerned
+pub fn list_issue_comments(
+    token: &str,
+    repository: &str,
+    number: u64,
+) -> Result<Vec<IssueComment>, GitHubApiError> {
+    let mut comments = vec![];
+    for page in 1.. {
+        let url = format!(
+            "{GITHUB_API_URL}/repos/{repository}/issues/{number}/comments?per_page={COMMENTS_PER_PAGE}&page={page}"
+        );
+        let page_comments = http::get(&url)
+            .set("Accept", "application/vnd.github+json")
+            .set("Authorization", &format!("Bearer {token}"))
+            .call()
+            .map_err(|e| GitHubApiError::Request(url.clone(), Box::new(e)))?
+            .into_json::<Vec<IssueComment>>()
+            .map_err(|e| GitHubApiError::ReadingResponse(url, e))?;
+        let last_page = page_comments.len() < COMMENTS_PER_PAGE;
+        comments.extend(page_comments);
+        if last_page {
+            break;
+        }
+    }
+    Ok(comments)
+}
+
+pub fn create_issue_comment(
+    token: &str,
+    repository: &str,
+    number: u64,
+    body: &str,
+) -> Result<IssueComment, GitHubApiError> {
+    let url = format!("{GITHUB_API_URL}/repos/{repository}/issues/{number}/comments");
+    http::post(&url)
+        .set("Accept", "application/vnd.github+json")
+        .set("Authorization", &format!("Bearer {token}"))
+        .send_json(json!({ "body": body }))
+        .map_err(|e| GitHubApiError::Request(url.clone(), Box::new(e)))?
+        .into_json()
+        .map_err(|e| GitHubApiError::ReadingResponse(url, e))
+}
+
+pub fn update_issue_comment(
+    token: &str,
+    repository: &str,
+    comment_id: u64,
+    body: &str,
+) -> Result<IssueComment, GitHubApiError> {
+    let url = format!("{GITHUB_API_URL}/repos/{repository}/issues/comments/{comment_id}");
+    http::patch(&url)
+        .set("Accept", "application/vnd.github+json")
+        .set("Authorization", &format!("Bearer {token}"))
+        .send_json(json!({ "body": body }))
+        .map_err(|e| GitHubApiError::Request(url.clone(), Box::new(e)))?
+        .into_json()
+        .map_err(|e| GitHubApiError::ReadingResponse(url, e))
+}
+
+#[derive(Deserialize)]
+struct Repository {
+    default_branch: String,
+}
+
+#[derive(Deserialize)]
+struct GitRef {
+    object: GitObject,
+}
+
+#[derive(Deserialize)]
+struct GitObject {
+    sha: String,
+}
+
+#[derive(Deserialize)]
+struct FileContents {
+    content: String,
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+pub struct PullRequest {
+    pub number: u64,
+    pub html_url: String,
+}
+
+pub fn get_default_branch(token: &str, repository: &str) -> Result<String, GitHubApiError> {
+    let url = format!("{GITHUB_API_URL}/repos/{repository}");
+    http::get(&url)
+        .set("Accept", "application/vnd.github+json")
+        .set("Authorization", &format!("Bearer {token}"))
+        .call()
+        .map_err(|e| GitHubApiError::Request(url.clone(), Box::new(e)))?
+        .into_json::<Repository>()
+        .map(|repository| repository.default_branch)
+        .map_err(|e| GitHubApiError::ReadingResponse(url, e))
+}
+
+pub fn get_branch_sha(
+    token: &str,
+    repository: &str,
+    branch: &str,
+) -> Result<String, GitHubApiError> {
+    let url = format!("{GITHUB_API_URL}/repos/{repository}/git/ref/heads/{branch}");
+    http::get(&url)
+        .set("Accept", "application/vnd.github+json")
+        .set("Authorization", &format!("Bearer {token}"))
+        .call()
+        .map_err(|e| GitHubApiError::Request(url.clone(), Box::new(e)))?
+        .into_json::<GitRef>()
+        .map(|git_ref| git_ref.object.sha)
+        .map_err(|e| GitHubApiError::ReadingResponse(url, e))
+}
+
+// returns `None` when the file doesn't exist at the given ref
+pub fn get_file_contents(
+    token: &str,
+    repository: &str,
+    path: &str,
+    git_ref: &str,
+) -> Result<Option<String>, GitHubApiError> {
+    let url = format!("{GITHUB_API_URL}/repos/{repository}/contents/{path}?ref={git_ref}");
+    let file_contents = match http::get(&url)
+        .set("Accept", "application/vnd.github+json")
+        .set("Authorization", &format!("Bearer {token}"))
+        .call()
+    {
+        Ok(response) => response
+            .into_json::<FileContents>()
+            .map_err(|e| GitHubApiError::ReadingResponse(url.clone(), e))?,
+        Err(ureq::Error::Status(404, _)) => return Ok(None),
+        Err(error) => return Err(GitHubApiError::Request(url, Box::new(error))),
+    };
+
+    // the API wraps base64 content at 60 characters
+    let encoded = file_contents
+        .content
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>();
+    let decoded = STANDARD
+        .decode(encoded)
+        .map_err(|e| GitHubApiError::InvalidContent(url.clone(), e.to_string()))?;
+    String::from_utf8(decoded)
+        .map(Some)
+        .map_err(|e| GitHubApiError::InvalidContent(url, e.to_string()))
+}
+
+// an open pull request for the same branch is reused so reruns don't fail
+pub fn create_or_find_pull_request(
+    token: &str,
+    repository: &str,
+    head: &str,
+    base: &str,
+    title: &str,
+    body: &str,
+) -> Result<PullRequest, GitHubApiError> {
+    let url = format!("{GITHUB_API_URL}/repos/{repository}/pulls");
+    match http::post(&url)
+        .set("Accept", "application/vnd.github+json")
+        .set("Authorization", &format!("Bearer {token}"))
+        .send_json(json!({ "title": title, "head": head, "base": base, "body": body }))
+    {
+        Ok(response) => response
+            .into_json()
+            .map_err(|e| GitHubApiError::ReadingResponse(url, e)),
+        Err(ureq::Error::Status(422, _)) => {
+            let owner = repository.split('/').next().unwrap_or_default();
+            let list_url = format!("{url}?state=open&head={owner}:{head}&base={base}");
+            http::get(&list_url)
+                .set("Accept", "application/vnd.github+json")
+                .set("Authorization", &format!("Bearer {token}"))
+                .call()
+                .map_err(|e| GitHubApiError::Request(list_url.clone(), Box::new(e)))?
+                .into_json::<Vec<PullRequest>>()
+                .map_err(|e| GitHubApiError::ReadingResponse(list_url.clone(), e))?
+                .into_iter()
+                .next()
+                .ok_or(GitHubApiError::MissingData(list_url))
+        }
+        Err(error) => Err(GitHubApiError::Request(url, Box::new(error))),
+    }
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+pub struct Issue {
+    pub number: u64,
+    pub html_url: String,
+    pub title: String,
+    pub state: String,
+    #[serde(default)]
+    pub labels: Vec<IssueLabel>,
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+pub struct IssueLabel {
+    pub name: String,
+}
+
+// an open issue with the same title is reused so reruns don't file the same request twice
+pub fn create_or_find_issue(
+    token: &str,
+    repository: &str,
+    title: &str,
+    body: &str,
+) -> Result<Issue, GitHubApiError> {
+    let url = format!("{GITHUB_API_URL}/repos/{repository}/issues");
+
+    let list_url = format!("{url}?state=open&per_page=100");
+    let existing = http::get(&list_url)
+        .set("Accept", "application/vnd.github+json")
+        .set("Authorization", &format!("Bearer {token}"))
+        .call()
+        .map_err(|e| GitHubApiError::Request(list_url.clone(), Box::new(e)))?
+        .into_json::<Vec<Issue>>()
+        .map_err(|e| GitHubApiError::ReadingResponse(list_url, e))?
+        .into_iter()
+        .find(|issue| issue.title == title);
+    if let Some(issue) = existing {
+        return Ok(issue);
+    }
+
+    http::post(&url)
+        .set("Accept", "application/vnd.github+json")
+        .set("Authorization", &format!("Bearer {token}"))
+        .send_json(json!({ "title": title, "body": body }))
+        .map_err(|e| GitHubApiError::Request(url.clone(), Box::new(e)))?
+        .into_json()
+        .map_err(|e| GitHubApiError::ReadingResponse(url, e))
+}
+
+pub fn get_issue(token: &str, repository: &str, number: u64) -> Result<Issue, GitHubApiError> {
+    let url = format!("{GITHUB_API_URL}/repos/{repository}/issues/{number}");
+    http::get(&url)
+        .set("Accept", "application/vnd.github+json")
+        .set("Authorization", &format!("Bearer {token}"))
+        .call()
+        .map_err(|e| GitHubApiError::Request(url.clone(), Box::new(e)))?
+        .into_json()
+        .map_err(|e| GitHubApiError::ReadingResponse(url, e))
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+pub struct Release {
+    pub tag_name: String,
+    pub html_url: String,
+}
+
+// releases of a public repository can be read without a token, one only raises the rate limit
+pub fn get_latest_release(
+    token: Option<&str>,
+    repository: &str,
+) -> Result<Release, GitHubApiError> {
+    let url = format!("{GITHUB_API_URL}/repos/{repository}/releases/latest");
+    let mut request = http::get(&url).set("Accept", "application/vnd.github+json");
+    if let Some(token) = token {
+        request = request.set("Authorization", &format!("Bearer {token}"));
+    }
+    request
+        .call()
+        .map_err(|e| GitHubApiError::Request(url.clone(), Box::new(e)))?
+        .into_json()
+        .map_err(|e| GitHubApiError::ReadingResponse(url, e))
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+pub struct DiscussionCategory {
+    pub id: String,
+    pub name: String,
+    pub slug: String,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct DiscussionCategories {
+    pub repository_id: String,
+    pub categories: Vec<DiscussionCategory>,
+}
+
+impl DiscussionCategories {
+    // categories are matched by name ignoring case or by the slug used in their URL
+    pub fn find(&self, name: &str) -> Option<&DiscussionCategory> {
+        self.categories
+            .iter()
+            .find(|category| category.name.eq_ignore_ascii_case(name) || category.slug == name)
+    }
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+pub struct Discussion {
+    pub number: u64,
+    pub title: String,
+    pub url: String,
+}
+
+#[derive(Deserialize)]
+struct Nodes<T> {
+    nodes: Vec<T>,
+}
+
+#[derive(Deserialize)]
+struct RepositoryData<T> {
+    repository: Option<T>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DiscussionCategoriesRepository {
+    id: String,
+    discussion_categories: Nodes<DiscussionCategory>,
+}
+
+#[derive(Deserialize)]
+struct DiscussionsRepository {
+    discussions: Nodes<Discussion>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateDiscussionData {
+    create_discussion: Option<CreateDiscussionPayload>,
+}
+
+#[derive(Deserialize)]
+struct CreateDiscussionPayload {
+    discussion: Discussion,
+}
+
+// the GraphQL API addresses repositories by owner and name rather than `owner/name`
+fn repository_variables(repository: &str) -> Value {
+    let (owner, name) = repository.split_once('/').unwrap_or((repository, ""));
+    json!({ "owner": owner, "name": name })
+}
+
+pub fn get_discussion_categories(
+    token: &str,
+    repository: &str,
+) -> Result<DiscussionCategories, GitHubApiError> {
+    post_graphql::<RepositoryData<DiscussionCategoriesRepository>>(
+        token,
+        DISCUSSION_CATEGORIES_QUERY,
+        repository_variables(repository),
+    )?
+    .repository
+    .map(|repository| DiscussionCategories {
+        repository_id: repository.id,
+        categories: repository.discussion_categories.nodes,
+    })
+    .ok_or_else(|| GitHubApiError::MissingData(graphql_url()))
+}
+
+// a recent discussion with the same title in the category is reused so reruns don't post twice
+pub fn create_or_find_discussion(
+    token: &str,
+    repository: &str,
+    repository_id: &str,
+    category: &DiscussionCategory,
+    title: &str,
+    body: &str,
+) -> Result<Discussion, GitHubApiError> {
+    let mut variables = repository_variables(repository);
+    variables["categoryId"] = json!(category.id);
+    let existing =
+        post_graphql::<RepositoryData<DiscussionsRepository>>(token, DISCUSSIONS_QUERY, variables)?
+            .repository
+            .ok_or_else(|| GitHubApiError::MissingData(graphql_url()))?
+            .discussions
+            .nodes
+            .into_iter()
+            .find(|discussion| discussion.title == title);
+    if let Some(discussion) = existing {
+        return Ok(discussion);
+    }
+
+    post_graphql::<CreateDiscussionData>(
+        token,
+        CREATE_DISCUSSION_MUTATION,
+        json!({
+            "input": {
+                "repositoryId": repository_id,
+                "categoryId": category.id,
+                "title": title,
+                "body": body,
+            }
+        }),
+    )?
+    .create_discussion
+    .map(|payload| payload.discussion)
+    .ok_or_else(|| GitHubApiError::MissingData(graphql_url()))
+}
+
+#[derive(Debug)]
+pub enum GitHubApiError {
+    Request(String, Box<ureq::Error>),
+    ReadingResponse(String, std::io::Error),
+    GraphQl(String, Vec<String>),
+    MissingData(String),
+    InvalidContent(String, String),
+}
+
+impl Display for GitHubApiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GitHubApiError::Request(url, error) => {
+                write!(f, "GitHub API request failed - {url}\nError: {error}")
+            }
+            GitHubApiError::ReadingResponse(url, error) => {
+                write!(
+                    f,
+                    "Could not read GitHub API response - {url}\nError: {error}"
+                )
+            }
+            GitHubApiError::GraphQl(url, errors) => {
+                write!(
+                    f,
+                    "GitHub API returned errors - {url}\n{}",
+                    errors
+                        .iter()
+                        .map(|error| format!("• {error}"))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            }
+            GitHubApiError::MissingData(url) => {
+                write!(f, "GitHub API response contained no data - {url}")
+            }
+            GitHubApiError::InvalidContent(url, error) => {
+                write!(
+                    f,
+                    "GitHub API returned file content that could not be decoded - {url}\nError: {error}"
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::github::api::{
+        create_commit_on_branch_input, CommitOnBranch, DiscussionCategories, DiscussionCategory,
+        FileAddition,
+    };
+    use serde_json::json;
+
+    #[test]
+    fn test_create_commit_on_branch_input() {
+        let commit = CommitOnBranch {
+            repository: "heroku/buildpacks-nodejs".to_string(),
+            branch: "prepare-release".to_string(),
+            expected_head_oid: "abc123".to_string(),
+            headline: "Prepare release v1.0.0".to_string(),
+            body: Some("Some details".to_string()),
+            additions: vec![FileAddition {
+                path: "buildpacks/nodejs/buildpack.toml".to_string(),
+                contents: b"hello".to_vec(),
+            }],
+            deletions: vec!["buildpacks/nodejs/old.txt".to_string()],
+        };
+
+        assert_eq!(
+            create_commit_on_branch_input(&commit),
+            json!({
+                "branch": {
+                    "repositoryNameWithOwner": "heroku/buildpacks-nodejs",
+                    "branchName": "prepare-release",
+                },
+                "expectedHeadOid": "abc123",
+                "message": {
+                    "headline": "Prepare release v1.0.0",
+                    "body": "Some details",
+                },
+                "fileChanges": {
+                    "additions": [
+                        { "path": "buildpacks/nodejs/buildpack.toml", "contents": "aGVsbG8=" }
+                    ],
+                    "deletions": [
+                        { "path": "buildpacks/nodejs/old.txt" }
+                    ],
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_find_discussion_category() {
+        let category = |id: &str, name: &str, slug: &str| DiscussionCategory {
+            id: id.to_string(),
+            name: name.to_string(),
+            slug: slug.to_string(),
+        };
+        let categories = DiscussionCategories {
+            repository_id: "R_1".to_string(),
+            categories: vec![
+                category("DIC_1", "General", "general"),
+                category("DIC_2", "Release Notes", "release-notes"),
+            ],
+        };
+
+        assert_eq!(
+            categories
+                .find("release notes")
+                .map(|category| &category.id),
+            Some(&"DIC_2".to_string())
+        );
+        assert_eq!(
+            categories
+                .find("release-notes")
+                .map(|category| &category.id),
+            Some(&"DIC_2".to_string())
+        );
+        assert_eq!(categories.find("Announcements"), None);
+    }
+}
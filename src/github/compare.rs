@@ -0,0 +1,78 @@
+use libcnb_data::buildpack::BuildpackId;
+use std::fmt::Display;
+
+pub const DEFAULT_TAG_TEMPLATE: &str = "v{version}";
+
+pub fn is_valid_tag_template(template: &str) -> bool {
+    template.contains("{version}")
+}
+
+pub fn is_per_buildpack_tag_template(template: &str) -> bool {
+    template.contains("{buildpack_id}")
+}
+
+pub fn render_tag_name(
+    template: &str,
+    buildpack_id: &BuildpackId,
+    version: &impl Display,
+) -> String {
+    template
+        .replace("{buildpack_id}", buildpack_id.as_str())
+        .replace("{version}", &version.to_string())
+}
+
+pub fn compare_url(repository: &str, base_tag: &str, head_tag: &str) -> String {
+    format!(
+        "{}/compare/{base_tag}...{head_tag}",
+        repository.trim_end_matches('/')
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::github::compare::{
+        compare_url, is_per_buildpack_tag_template, is_valid_tag_template, render_tag_name,
+        DEFAULT_TAG_TEMPLATE,
+    };
+    use libcnb_data::buildpack_id;
+
+    #[test]
+    fn test_render_tag_name() {
+        assert_eq!(
+            render_tag_name(
+                DEFAULT_TAG_TEMPLATE,
+                &buildpack_id!("heroku/nodejs"),
+                &"1.0.0"
+            ),
+            "v1.0.0"
+        );
+        assert_eq!(
+            render_tag_name(
+                "{buildpack_id}/v{version}",
+                &buildpack_id!("heroku/nodejs"),
+                &"1.0.0"
+            ),
+            "heroku/nodejs/v1.0.0"
+        );
+    }
+
+    #[test]
+    fn test_tag_templates() {
+        assert!(is_valid_tag_template(DEFAULT_TAG_TEMPLATE));
+        assert!(!is_valid_tag_template("release"));
+        assert!(!is_per_buildpack_tag_template(DEFAULT_TAG_TEMPLATE));
+        assert!(is_per_buildpack_tag_template("{buildpack_id}@{version}"));
+    }
+
+    #[test]
+    fn test_compare_url() {
+        assert_eq!(
+            compare_url(
+                "https://github.com/heroku/buildpacks-nodejs/",
+                "v1.0.0",
+                "v1.1.0"
+            ),
+            "https://github.com/heroku/buildpacks-nodejs/compare/v1.0.0...v1.1.0"
+        );
+    }
+}
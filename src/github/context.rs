@@ -0,0 +1,230 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+const DEFAULT_SERVER_URL: &str = "https://github.com";
+
+// the workflow run details GitHub exposes through environment variables, see
+// https://docs.github.com/en/actions/learn-github-actions/variables#default-environment-variables
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GitHubContext {
+    pub server_url: String,
+    pub repository: Option<String>,
+    pub git_ref: Option<String>,
+    pub head_ref: Option<String>,
+    pub sha: Option<String>,
+    pub event_name: Option<String>,
+    pub event: Option<Value>,
+    pub inputs: HashMap<String, String>,
+}
+
+impl GitHubContext {
+    pub fn from_env() -> Result<GitHubContext, GitHubContextError> {
+        GitHubContext::from_vars(std::env::vars())
+    }
+
+    pub fn from_vars<I: IntoIterator<Item = (String, String)>>(
+        vars: I,
+    ) -> Result<GitHubContext, GitHubContextError> {
+        let vars = vars
+            .into_iter()
+            .filter(|(_, value)| !value.is_empty())
+            .collect::<HashMap<_, _>>();
+
+        let event = vars
+            .get("GITHUB_EVENT_PATH")
+            .map(|path| read_event(PathBuf::from(path)))
+            .transpose()?;
+
+        let inputs = vars
+            .iter()
+            .filter_map(|(name, value)| {
+                name.strip_prefix("INPUT_")
+                    .map(|input| (input.to_lowercase(), value.clone()))
+            })
+            .collect();
+
+        Ok(GitHubContext {
+            server_url: vars
+                .get("GITHUB_SERVER_URL")
+                .map(|url| url.trim_end_matches('/').to_string())
+                .unwrap_or_else(|| DEFAULT_SERVER_URL.to_string()),
+            repository: vars.get("GITHUB_REPOSITORY").cloned(),
+            git_ref: vars.get("GITHUB_REF").cloned(),
+            head_ref: vars.get("GITHUB_HEAD_REF").cloned(),
+            sha: vars.get("GITHUB_SHA").cloned(),
+            event_name: vars.get("GITHUB_EVENT_NAME").cloned(),
+            event,
+            inputs,
+        })
+    }
+
+    pub fn repository_url(&self) -> Option<String> {
+        self.repository
+            .as_ref()
+            .map(|repository| format!("{}/{repository}", self.server_url))
+    }
+
+    // pull request workflows run on a merge ref so the source branch comes from `GITHUB_HEAD_REF`
+    pub fn branch(&self) -> Option<String> {
+        self.head_ref.clone().or_else(|| {
+            self.git_ref
+                .as_deref()
+                .and_then(|git_ref| git_ref.strip_prefix("refs/heads/"))
+                .map(String::from)
+        })
+    }
+
+    pub fn pull_request_number(&self) -> Option<u64> {
+        self.git_ref
+            .as_deref()
+            .and_then(|git_ref| git_ref.strip_prefix("refs/pull/"))
+            .and_then(|remainder| remainder.split('/').next())
+            .and_then(|number| number.parse().ok())
+            .or_else(|| {
+                self.event.as_ref().and_then(|event| {
+                    event["pull_request"]["number"]
+                        .as_u64()
+                        .or_else(|| event["number"].as_u64())
+                })
+            })
+    }
+
+    // input names are matched the way the actions toolkit does, spaces become underscores
+    pub fn input(&self, name: &str) -> Option<&str> {
+        self.inputs
+            .get(&name.replace(' ', "_").to_lowercase())
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+    }
+
+    pub fn boolean_input(&self, name: &str) -> Result<Option<bool>, GitHubContextError> {
+        match self.input(name) {
+            None => Ok(None),
+            Some("true" | "True" | "TRUE") => Ok(Some(true)),
+            Some("false" | "False" | "FALSE") => Ok(Some(false)),
+            Some(value) => Err(GitHubContextError::InvalidBooleanInput(
+                name.to_string(),
+                value.to_string(),
+            )),
+        }
+    }
+}
+
+fn read_event(path: PathBuf) -> Result<Value, GitHubContextError> {
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| GitHubContextError::ReadingEvent(path.clone(), e))?;
+    serde_json::from_str(&contents).map_err(|e| GitHubContextError::ParsingEvent(path, e))
+}
+
+#[derive(Debug)]
+pub enum GitHubContextError {
+    ReadingEvent(PathBuf, std::io::Error),
+    ParsingEvent(PathBuf, serde_json::Error),
+    InvalidBooleanInput(String, String),
+}
+
+impl Display for GitHubContextError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GitHubContextError::ReadingEvent(path, error) => {
+                write!(
+                    f,
+                    "Could not read GitHub event payload\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+            GitHubContextError::ParsingEvent(path, error) => {
+                write!(
+                    f,
+                    "Could not parse GitHub event payload\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+            GitHubContextError::InvalidBooleanInput(name, value) => {
+                write!(
+                    f,
+                    "Input `{name}` must be one of `true`, `True`, `TRUE`, `false`, `False`, or `FALSE` - {value}"
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::github::context::GitHubContext;
+
+    fn vars(values: &[(&str, &str)]) -> Vec<(String, String)> {
+        values
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_push_context() {
+        let context = GitHubContext::from_vars(vars(&[
+            ("GITHUB_REPOSITORY", "heroku/buildpacks-nodejs"),
+            ("GITHUB_REF", "refs/heads/main"),
+            ("GITHUB_HEAD_REF", ""),
+            ("GITHUB_SHA", "ffac537e6cbbf934b08745a378932722df287a53"),
+            ("GITHUB_EVENT_NAME", "push"),
+            ("INPUT_BUILDPACK_ID", " heroku/nodejs "),
+            ("INPUT_DRY_RUN", "True"),
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            context.repository_url(),
+            Some("https://github.com/heroku/buildpacks-nodejs".to_string())
+        );
+        assert_eq!(context.branch(), Some("main".to_string()));
+        assert_eq!(
+            context.sha.as_deref(),
+            Some("ffac537e6cbbf934b08745a378932722df287a53")
+        );
+        assert_eq!(context.pull_request_number(), None);
+        assert_eq!(context.input("buildpack_id"), Some("heroku/nodejs"));
+        assert_eq!(context.input("missing"), None);
+        assert_eq!(context.boolean_input("dry run").unwrap(), Some(true));
+        assert_eq!(context.boolean_input("missing").unwrap(), None);
+    }
+
+    #[test]
+    fn test_pull_request_context() {
+        let context = GitHubContext::from_vars(vars(&[
+            ("GITHUB_SERVER_URL", "https://github.example.com/"),
+            ("GITHUB_REPOSITORY", "heroku/buildpacks-nodejs"),
+            ("GITHUB_REF", "refs/pull/42/merge"),
+            ("GITHUB_HEAD_REF", "update-node"),
+            ("INPUT_VERIFY", "yes"),
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            context.repository_url(),
+            Some("https://github.example.com/heroku/buildpacks-nodejs".to_string())
+        );
+        assert_eq!(context.branch(), Some("update-node".to_string()));
+        assert_eq!(context.pull_request_number(), Some(42));
+        assert!(context.boolean_input("verify").is_err());
+    }
+
+    #[test]
+    fn test_event_payload() {
+        let path = std::env::temp_dir().join(format!("github-event-{}.json", std::process::id()));
+        std::fs::write(&path, r#"{"action":"opened","pull_request":{"number":7}}"#).unwrap();
+
+        let context = GitHubContext::from_vars(vars(&[
+            ("GITHUB_EVENT_PATH", path.to_str().unwrap()),
+            ("GITHUB_REF", "refs/heads/main"),
+        ]))
+        .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(context.event.as_ref().unwrap()["action"], "opened");
+        assert_eq!(context.pull_request_number(), Some(7));
+    }
+}
@@ -1 +1,7 @@
-pub(crate) mod actions;
+pub mod actions;
+pub mod api;
+pub mod auth;
+pub mod compare;
+pub mod context;
+
+pub(crate) const GITHUB_API_URL: &str = "https://api.github.com";
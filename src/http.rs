@@ -0,0 +1,51 @@
+use crate::cancellation::remaining_time;
+use lazy_static::lazy_static;
+use std::time::Duration;
+use ureq::{Agent, AgentBuilder, Request};
+
+// without a `--timeout`, a connection that can't be made or stops sending data is still given up on
+// instead of blocking the command forever
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+const READ_WRITE_TIMEOUT: Duration = Duration::from_secs(300);
+
+// a request started right at the deadline still needs a non-zero timeout to fail instead of waiting
+const MIN_REQUEST_TIMEOUT: Duration = Duration::from_millis(1);
+
+lazy_static! {
+    // shared by every request so connections to the same host are reused (e.g.; registry lookups)
+    static ref AGENT: Agent = AgentBuilder::new()
+        .timeout_connect(CONNECT_TIMEOUT)
+        .timeout_read(READ_WRITE_TIMEOUT)
+        .timeout_write(READ_WRITE_TIMEOUT)
+        .build();
+}
+
+pub fn get(url: &str) -> Request {
+    request("GET", url)
+}
+
+pub fn head(url: &str) -> Request {
+    request("HEAD", url)
+}
+
+pub fn post(url: &str) -> Request {
+    request("POST", url)
+}
+
+pub fn put(url: &str) -> Request {
+    request("PUT", url)
+}
+
+pub fn patch(url: &str) -> Request {
+    request("PATCH", url)
+}
+
+// with `--timeout` set, each request is limited to the time left so a registry or GitHub request
+// that hangs fails once the deadline passes
+fn request(method: &str, url: &str) -> Request {
+    let request = AGENT.request(method, url);
+    match remaining_time() {
+        Some(remaining) => request.timeout(remaining.max(MIN_REQUEST_TIMEOUT)),
+        None => request,
+    }
+}
@@ -0,0 +1,168 @@
+use chrono::Utc;
+use clap::Parser;
+use languages_github_actions::github::actions::OUTPUTS_FILE_ENV;
+use lazy_static::lazy_static;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+lazy_static! {
+    // commands read the current directory and environment so only one can run at a time
+    static ref COMMAND_LOCK: Mutex<()> = Mutex::new(());
+}
+
+// environment variables that change how commands behave on CI, they are cleared so a test run in a
+// workflow sees the same results as one run locally
+const CLEARED_ENV: [&str; 4] = [
+    "GITHUB_OUTPUT",
+    "GITHUB_ACTIONS",
+    "BUILDPACK_DISCOVERY_CACHE_DIR",
+    "RELEASE_DATE",
+];
+
+// a project materialized in a temporary directory, removed again when the fixture is dropped
+pub(crate) struct Fixture {
+    root: PathBuf,
+}
+
+impl Fixture {
+    pub(crate) fn new(name: &str) -> Fixture {
+        let root = std::env::temp_dir().join(format!("it-{name}-{}", std::process::id()));
+        if root.exists() {
+            std::fs::remove_dir_all(&root).expect("Should remove the previous fixture");
+        }
+        std::fs::create_dir_all(&root).expect("Should create the fixture directory");
+        Fixture { root }
+    }
+
+    pub(crate) fn file(self, path: &str, contents: &str) -> Fixture {
+        let path = self.root.join(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("Should create the file's directory");
+        }
+        std::fs::write(path, contents).expect("Should write the fixture file");
+        self
+    }
+
+    // a component buildpack with an empty unreleased section in its changelog
+    pub(crate) fn buildpack(self, dir: &str, id: &str, version: &str) -> Fixture {
+        self.file(
+            &format!("{dir}/buildpack.toml"),
+            &format!("api = \"0.10\"\n\n[buildpack]\nid = \"{id}\"\nversion = \"{version}\"\n"),
+        )
+        .file(
+            &format!("{dir}/CHANGELOG.md"),
+            "# Changelog\n\n## [Unreleased]\n",
+        )
+    }
+
+    // a composite buildpack with a single order made of the given buildpacks
+    pub(crate) fn composite(
+        self,
+        dir: &str,
+        id: &str,
+        version: &str,
+        group: &[(&str, &str)],
+    ) -> Fixture {
+        let mut contents = format!(
+            "api = \"0.10\"\n\n[buildpack]\nid = \"{id}\"\nversion = \"{version}\"\n\n[[order]]\n"
+        );
+        for (id, version) in group {
+            contents.push_str(&format!(
+                "\n[[order.group]]\nid = \"{id}\"\nversion = \"{version}\"\n"
+            ));
+        }
+        self.file(&format!("{dir}/buildpack.toml"), &contents).file(
+            &format!("{dir}/CHANGELOG.md"),
+            "# Changelog\n\n## [Unreleased]\n",
+        )
+    }
+
+    // a builder that includes each buildpack from its docker image and lists them in one order group
+    pub(crate) fn builder(self, dir: &str, buildpacks: &[(&str, &str)]) -> Fixture {
+        let mut contents = String::new();
+        for (id, version) in buildpacks {
+            contents.push_str(&format!(
+                "[[buildpacks]]\n  id = \"{id}\"\n  uri = \"docker://docker.io/{}:{version}\"\n\n",
+                id.replace('/', "/buildpack-")
+            ));
+        }
+        contents.push_str("[[order]]\n");
+        for (id, version) in buildpacks {
+            contents.push_str(&format!(
+                "  [[order.group]]\n    id = \"{id}\"\n    version = \"{version}\"\n"
+            ));
+        }
+        self.file(&format!("{dir}/builder.toml"), &contents)
+    }
+
+    pub(crate) fn read(&self, path: &str) -> String {
+        std::fs::read_to_string(self.root.join(path)).expect("Should read the fixture file")
+    }
+
+    // runs a command from the project root with its arguments parsed the same way as on the command
+    // line, the outputs are collected from an outputs file instead of being printed
+    pub(crate) fn run<A, E>(
+        &self,
+        execute: impl FnOnce(A) -> Result<(), E>,
+        args: &[&str],
+    ) -> Result<BTreeMap<String, String>, String>
+    where
+        A: Parser,
+        E: Display,
+    {
+        let _lock = COMMAND_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let args = A::try_parse_from(std::iter::once("actions").chain(args.iter().copied()))
+            .map_err(|e| e.to_string())?;
+
+        let outputs_file = self.root.with_extension("outputs.json");
+        if outputs_file.exists() {
+            std::fs::remove_file(&outputs_file).expect("Should remove the previous outputs");
+        }
+
+        let previous_dir = std::env::current_dir().expect("Should get the current directory");
+        std::env::set_current_dir(&self.root).expect("Should change to the fixture directory");
+        for name in CLEARED_ENV {
+            std::env::remove_var(name);
+        }
+        std::env::set_var(OUTPUTS_FILE_ENV, &outputs_file);
+
+        let result = execute(args);
+
+        std::env::remove_var(OUTPUTS_FILE_ENV);
+        std::env::set_current_dir(previous_dir).expect("Should restore the current directory");
+
+        result.map_err(|e| e.to_string())?;
+
+        Ok(match std::fs::read_to_string(&outputs_file) {
+            Ok(contents) => serde_json::from_str(&contents).expect("Outputs should be valid JSON"),
+            Err(_) => BTreeMap::new(),
+        })
+    }
+
+    // the files and outputs in a single document for comparing with a snapshot, the fixture directory
+    // and today's date are replaced since they change between runs
+    pub(crate) fn render(&self, files: &[&str], outputs: &BTreeMap<String, String>) -> String {
+        let mut rendered = String::new();
+        for file in files {
+            rendered.push_str(&format!("=== {file} ===\n{}\n", self.read(file)));
+        }
+        for (name, value) in outputs {
+            rendered.push_str(&format!("=== output: {name} ===\n{value}\n\n"));
+        }
+        rendered
+            .replace(&self.root.display().to_string(), "[root]")
+            .replace(&Utc::now().format("%Y-%m-%d").to_string(), "[today]")
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.root);
+        let _ = std::fs::remove_file(self.root.with_extension("outputs.json"));
+    }
+}
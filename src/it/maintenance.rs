@@ -0,0 +1,96 @@
+use crate::commands::{deprecate_buildpack, rename_buildpack, set_buildpack_targets};
+use crate::it::fixture::Fixture;
+use crate::it::snapshot::assert_snapshot;
+
+#[test]
+fn test_maintenance_flow() {
+    let fixture = Fixture::new("maintenance-flow")
+        .buildpack("buildpacks/nodejs-engine", "heroku/nodejs-engine", "1.0.0")
+        .buildpack("buildpacks/nodejs-npm", "heroku/nodejs-npm", "1.0.0")
+        .composite(
+            "meta-buildpacks/nodejs",
+            "heroku/nodejs",
+            "1.0.0",
+            &[
+                ("heroku/nodejs-engine", "1.0.0"),
+                ("heroku/nodejs-npm", "1.0.0"),
+            ],
+        )
+        .builder(
+            "builders/22",
+            &[
+                ("heroku/nodejs-engine", "1.0.0"),
+                ("heroku/nodejs-npm", "1.0.0"),
+            ],
+        );
+
+    fixture
+        .run(
+            rename_buildpack::execute,
+            &[
+                "--from",
+                "heroku/nodejs-engine",
+                "--to",
+                "heroku/nodejs-runtime",
+            ],
+        )
+        .unwrap();
+
+    fixture
+        .run(
+            set_buildpack_targets::execute,
+            &[
+                "--target",
+                "linux/amd64",
+                "--target",
+                "linux/arm64",
+                "--distro",
+                "ubuntu@22.04",
+            ],
+        )
+        .unwrap();
+
+    let outputs = fixture
+        .run(
+            deprecate_buildpack::execute,
+            &[
+                "--id",
+                "heroku/nodejs-npm",
+                "--message",
+                "Use heroku/nodejs-corepack instead.",
+                "--eol-date",
+                "2024-06-01",
+                "--remove-from-builders",
+            ],
+        )
+        .unwrap();
+
+    assert_snapshot(
+        "maintenance_flow",
+        &fixture.render(
+            &[
+                "buildpacks/nodejs-engine/buildpack.toml",
+                "buildpacks/nodejs-engine/CHANGELOG.md",
+                "buildpacks/nodejs-npm/buildpack.toml",
+                "buildpacks/nodejs-npm/CHANGELOG.md",
+                "meta-buildpacks/nodejs/buildpack.toml",
+                "builders/22/builder.toml",
+            ],
+            &outputs,
+        ),
+    );
+
+    // the renamed id is now taken
+    assert!(fixture
+        .run(
+            rename_buildpack::execute,
+            &[
+                "--from",
+                "heroku/nodejs-npm",
+                "--to",
+                "heroku/nodejs-runtime"
+            ],
+        )
+        .unwrap_err()
+        .starts_with("A buildpack with id heroku/nodejs-runtime already exists"));
+}
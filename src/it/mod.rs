@@ -0,0 +1,6 @@
+// end-to-end tests that run commands against a project materialized on disk, covering the flows
+// across commands that the unit tests next to each command can't
+mod fixture;
+mod maintenance;
+mod release;
+mod snapshot;
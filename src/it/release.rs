@@ -0,0 +1,245 @@
+use crate::commands::{
+    add_changelog_entry, generate_buildpack_matrix, generate_changelog, prepare_release,
+    update_builder,
+};
+use crate::it::fixture::Fixture;
+use crate::it::snapshot::assert_snapshot;
+use chrono::{TimeZone, Utc};
+use languages_github_actions::clock::FixedClock;
+
+const FILES: [&str; 6] = [
+    "buildpacks/nodejs-engine/buildpack.toml",
+    "buildpacks/nodejs-engine/CHANGELOG.md",
+    "buildpacks/nodejs-npm/buildpack.toml",
+    "buildpacks/nodejs-npm/CHANGELOG.md",
+    "meta-buildpacks/nodejs/buildpack.toml",
+    "meta-buildpacks/nodejs/CHANGELOG.md",
+];
+
+fn nodejs_project(name: &str) -> Fixture {
+    Fixture::new(name)
+        .buildpack("buildpacks/nodejs-engine", "heroku/nodejs-engine", "1.0.0")
+        .buildpack("buildpacks/nodejs-npm", "heroku/nodejs-npm", "1.0.0")
+        .composite(
+            "meta-buildpacks/nodejs",
+            "heroku/nodejs",
+            "1.0.0",
+            &[
+                ("heroku/nodejs-engine", "1.0.0"),
+                ("heroku/nodejs-npm", "1.0.0"),
+            ],
+        )
+}
+
+#[test]
+fn test_release_flow() {
+    let fixture = nodejs_project("release-flow");
+
+    fixture
+        .run(
+            add_changelog_entry::execute,
+            &[
+                "--buildpack-id",
+                "heroku/nodejs-engine",
+                "--entry",
+                "Added support for Node.js 20.",
+            ],
+        )
+        .unwrap();
+
+    let outputs = fixture
+        .run(
+            prepare_release::execute,
+            &[
+                "--bump",
+                "minor",
+                "--repository-url",
+                "https://github.com/heroku/buildpacks-nodejs",
+                "--release-date",
+                "2023-06-16",
+            ],
+        )
+        .unwrap();
+    assert_snapshot("release_flow_prepare", &fixture.render(&FILES, &outputs));
+
+    let outputs = fixture
+        .run(generate_changelog::execute, &["--version", "1.1.0"])
+        .unwrap();
+    assert_snapshot("release_flow_changelog", &fixture.render(&[], &outputs));
+}
+
+// each release continues from the last one and `--only` leaves the other buildpacks untouched
+#[test]
+fn test_repeated_releases() {
+    let fixture = nodejs_project("repeated-releases");
+    let clock = FixedClock(Utc.with_ymd_and_hms(2023, 6, 16, 12, 0, 0).unwrap());
+
+    for _ in 0..2 {
+        fixture
+            .run(
+                |args| prepare_release::command::execute_with_clock(args, &clock),
+                &["--bump", "patch", "--only", "heroku/nodejs-npm"],
+            )
+            .unwrap();
+    }
+    assert_snapshot(
+        "repeated_releases",
+        &fixture.render(&FILES[..4], &Default::default()),
+    );
+}
+
+// builders that don't include the buildpack are reported as skipped and left untouched, only the
+// changed builder gets a timestamp
+#[test]
+fn test_update_builder_flow() {
+    let fixture = Fixture::new("update-builder-flow")
+        .builder("builders/22", &[("heroku/nodejs", "1.0.0")])
+        .builder("builders/20", &[("heroku/nodejs", "1.1.0")])
+        .builder("builders/go", &[("heroku/go", "1.0.0")])
+        .file("CHANGELOG.md", "# Changelog\n\n## [Unreleased]\n");
+
+    let clock = FixedClock(Utc.with_ymd_and_hms(2023, 6, 16, 12, 0, 0).unwrap());
+
+    let outputs = fixture
+        .run(
+            |args| update_builder::command::execute_with_clock(args, &clock),
+            &[
+                "--buildpack-id",
+                "heroku/nodejs",
+                "--buildpack-version",
+                "1.1.0",
+                "--buildpack-uri-template",
+                "docker://docker.io/heroku/buildpack-nodejs:{version}",
+                "--all-builders",
+                "--update-changelog",
+                "--update-timestamp",
+                "--path",
+                ".",
+            ],
+        )
+        .unwrap();
+    assert_snapshot(
+        "update_builder_flow",
+        &fixture.render(
+            &[
+                "builders/20/builder.toml",
+                "builders/22/builder.toml",
+                "builders/go/builder.toml",
+                "CHANGELOG.md",
+            ],
+            &outputs,
+        ),
+    );
+
+    let downgrade = fixture.run(
+        update_builder::execute,
+        &[
+            "--buildpack-id",
+            "heroku/nodejs",
+            "--buildpack-version",
+            "1.0.1",
+            "--buildpack-uri-template",
+            "docker://docker.io/heroku/buildpack-nodejs:{version}",
+            "--builders",
+            "builders/22",
+            "--path",
+            ".",
+        ],
+    );
+    assert!(downgrade.unwrap_err().contains("Refusing to downgrade"));
+}
+
+// a manifest updates each of its buildpacks that a builder includes, the lines printed around it by
+// prepare-release are ignored
+#[test]
+fn test_update_builder_from_manifest() {
+    let fixture = Fixture::new("update-builder-from-manifest")
+        .builder(
+            "builders/22",
+            &[
+                ("heroku/nodejs-engine", "1.0.0"),
+                ("heroku/nodejs-npm", "1.0.0"),
+            ],
+        )
+        .builder("builders/go", &[("heroku/go", "1.0.0")])
+        .file(
+            "release.json",
+            concat!(
+                "::notice title=heroku/nodejs-engine::1.0.0 → 1.1.0\n",
+                r#"{"from_version":"1.0.0","to_version":"1.1.0","buildpacks":[{"id":"heroku/nodejs-engine","version":"1.1.0"},{"id":"heroku/nodejs-npm","version":"1.1.0"}]}"#,
+                "\n"
+            ),
+        );
+
+    let outputs = fixture
+        .run(
+            update_builder::execute,
+            &[
+                "--manifest",
+                "release.json",
+                "--buildpack-uri-template",
+                "docker://docker.io/{buildpack_id}:{version}",
+                "--all-builders",
+                "--path",
+                ".",
+            ],
+        )
+        .unwrap();
+    assert_snapshot(
+        "update_builder_from_manifest",
+        &fixture.render(
+            &["builders/22/builder.toml", "builders/go/builder.toml"],
+            &outputs,
+        ),
+    );
+}
+
+// a release heading with a date that doesn't exist fails the changelog unless `--lenient` is given,
+// which leaves that release out
+#[test]
+fn test_generate_changelog_lenient() {
+    let fixture = Fixture::new("generate-changelog-lenient")
+        .buildpack("buildpacks/nodejs-engine", "heroku/nodejs-engine", "1.0.1")
+        .file(
+            "buildpacks/nodejs-engine/CHANGELOG.md",
+            "# Changelog\n\n## [Unreleased]\n\n## [1.0.1] - 2023-02-30\n\n- Fixed a typo.\n\n## [1.0.0] - 2023-02-01\n\n- Initial release.\n",
+        );
+
+    let strict = fixture.run(generate_changelog::execute, &["--version", "latest"]);
+    let error = strict.unwrap_err();
+    assert!(error.contains("Invalid date"), "{error}");
+
+    let outputs = fixture
+        .run(
+            generate_changelog::execute,
+            &["--version", "latest", "--lenient"],
+        )
+        .unwrap();
+    assert_eq!(
+        outputs["changelog"],
+        "# heroku/nodejs-engine\n\n- Initial release.\n\n"
+    );
+}
+
+// buildpacks found in each project directory keep their paths relative to the repository root
+#[test]
+fn test_generate_buildpack_matrix_from_project_dirs() {
+    let fixture = nodejs_project("buildpack-matrix-project-dirs");
+
+    let outputs = fixture
+        .run(
+            generate_buildpack_matrix::execute,
+            &[
+                "--project-dir",
+                "buildpacks",
+                "--project-dir",
+                "meta-buildpacks",
+                "--pretty",
+            ],
+        )
+        .unwrap();
+    assert_snapshot(
+        "buildpack_matrix_project_dirs",
+        &fixture.render(&[], &outputs),
+    );
+}
@@ -0,0 +1,29 @@
+use std::path::PathBuf;
+
+pub(crate) const UPDATE_SNAPSHOTS_ENV: &str = "UPDATE_SNAPSHOTS";
+
+// snapshots are kept in `src/it/snapshots/<name>.snap` and reviewed like any other change, run the
+// tests with `UPDATE_SNAPSHOTS=1` to write them after an intended change in behavior
+pub(crate) fn assert_snapshot(name: &str, actual: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src/it/snapshots")
+        .join(format!("{name}.snap"));
+
+    if std::env::var_os(UPDATE_SNAPSHOTS_ENV).is_some() {
+        std::fs::create_dir_all(path.parent().expect("Snapshot should have a directory"))
+            .expect("Should create the snapshots directory");
+        std::fs::write(&path, actual).expect("Should write the snapshot");
+        return;
+    }
+
+    match std::fs::read_to_string(&path) {
+        Ok(expected) => assert_eq!(
+            actual, expected,
+            "Snapshot {name} doesn't match, run with {UPDATE_SNAPSHOTS_ENV}=1 to update it"
+        ),
+        Err(_) => panic!(
+            "Snapshot {} is missing, run with {UPDATE_SNAPSHOTS_ENV}=1 to create it\n\n{actual}",
+            path.display()
+        ),
+    }
+}
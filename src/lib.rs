@@ -0,0 +1,25 @@
+pub mod builder;
+pub mod buildpack;
+pub mod buildpackage;
+pub mod cancellation;
+pub mod changelog;
+pub mod clock;
+pub mod cnb_registry;
+pub mod debug_artifacts;
+pub mod experimental;
+pub mod git;
+pub mod github;
+pub mod http;
+pub mod lifecycle;
+pub mod logging;
+pub mod oci;
+pub mod patch;
+pub mod progress;
+pub mod registry_auth;
+pub mod release_manifest;
+pub mod release_state;
+pub mod sbom;
+pub mod shared_paths;
+pub mod version_locations;
+pub mod versioning;
+pub mod write;
@@ -0,0 +1,65 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// buildpack API versions are `major.minor` so they are compared as a pair of numbers, `0.10` is
+// newer than `0.9`
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub struct ApiVersion(pub u64, pub u64);
+
+impl FromStr for ApiVersion {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (major, minor) = value.split_once('.').ok_or(())?;
+        Ok(ApiVersion(
+            major.parse().map_err(|_| ())?,
+            minor.parse().map_err(|_| ())?,
+        ))
+    }
+}
+
+impl Display for ApiVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.0, self.1)
+    }
+}
+
+// the first lifecycle release that supports each buildpack API, taken from the lifecycle release
+// notes, this needs a new entry whenever a lifecycle release adds a buildpack API
+const LIFECYCLE_BUILDPACK_APIS: [(ApiVersion, &str); 10] = [
+    (ApiVersion(0, 2), "0.7.0"),
+    (ApiVersion(0, 3), "0.8.0"),
+    (ApiVersion(0, 4), "0.9.0"),
+    (ApiVersion(0, 5), "0.10.0"),
+    (ApiVersion(0, 6), "0.11.0"),
+    (ApiVersion(0, 7), "0.12.0"),
+    (ApiVersion(0, 8), "0.14.0"),
+    (ApiVersion(0, 9), "0.15.0"),
+    (ApiVersion(0, 10), "0.17.0"),
+    (ApiVersion(0, 11), "0.20.0"),
+];
+
+// `None` for an API the table doesn't know about yet
+pub fn min_lifecycle_version(api: ApiVersion) -> Option<&'static str> {
+    LIFECYCLE_BUILDPACK_APIS
+        .iter()
+        .find(|(supported_api, _)| *supported_api == api)
+        .map(|(_, lifecycle_version)| *lifecycle_version)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::lifecycle::{min_lifecycle_version, ApiVersion};
+
+    #[test]
+    fn test_min_lifecycle_version() {
+        assert_eq!("0.10".parse::<ApiVersion>(), Ok(ApiVersion(0, 10)));
+        assert!("0.10".parse::<ApiVersion>().unwrap() > "0.9".parse::<ApiVersion>().unwrap());
+        assert_eq!("1".parse::<ApiVersion>(), Err(()));
+
+        assert_eq!(min_lifecycle_version(ApiVersion(0, 9)), Some("0.15.0"));
+        assert_eq!(min_lifecycle_version(ApiVersion(0, 10)), Some("0.17.0"));
+        assert_eq!(min_lifecycle_version(ApiVersion(0, 1)), None);
+        assert_eq!(min_lifecycle_version(ApiVersion(1, 0)), None);
+    }
+}
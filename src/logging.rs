@@ -0,0 +1,168 @@
+use lazy_static::lazy_static;
+use serde_json::{json, Map, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+pub const JSON_LOGS_ENV: &str = "ACTIONS_JSON_LOGS";
+
+static JSON_LOGS: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    static ref COMMAND: Mutex<String> = Mutex::new(String::new());
+}
+
+// messages start with an emoji that marks their level, anything without one is informational
+const LEVEL_PREFIXES: [(&str, &str); 8] = [
+    ("❌", "error"),
+    ("⚠️", "warning"),
+    ("✅️", "info"),
+    ("⏭️", "info"),
+    ("ℹ️", "info"),
+    ("📦", "info"),
+    ("📌", "info"),
+    ("🏗️", "info"),
+];
+
+// logs a status message to stderr, in text mode it's printed as-is and with json logs it becomes a
+// record with the buildpack and path it's about (e.g.; `log!(path = path; "✅️ Updated {}", ...)`)
+#[macro_export]
+macro_rules! log {
+    (buildpack_id = $buildpack_id:expr, path = $path:expr; $($arg:tt)+) => {
+        $crate::logging::write_log(
+            Some($buildpack_id.to_string()),
+            Some(::std::path::Path::new(&$path).display().to_string()),
+            &format!($($arg)+),
+        )
+    };
+    (buildpack_id = $buildpack_id:expr; $($arg:tt)+) => {
+        $crate::logging::write_log(Some($buildpack_id.to_string()), None, &format!($($arg)+))
+    };
+    (path = $path:expr; $($arg:tt)+) => {
+        $crate::logging::write_log(
+            None,
+            Some(::std::path::Path::new(&$path).display().to_string()),
+            &format!($($arg)+),
+        )
+    };
+    ($($arg:tt)+) => {
+        $crate::logging::write_log(None, None, &format!($($arg)+))
+    };
+}
+
+// with json logs, every message is written as one JSON object per line so log aggregation on
+// self-hosted runners can index it by level, command, buildpack, and path
+pub fn set_json_logs(command: impl Into<String>) {
+    *COMMAND
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner) = command.into();
+    JSON_LOGS.store(true, Ordering::Relaxed);
+}
+
+pub fn json_logs_from_env() -> bool {
+    std::env::var(JSON_LOGS_ENV).map_or(false, |value| value == "true" || value == "1")
+}
+
+pub fn write_log(buildpack_id: Option<String>, path: Option<String>, message: &str) {
+    if !JSON_LOGS.load(Ordering::Relaxed) {
+        eprintln!("{message}");
+        return;
+    }
+
+    let command = COMMAND
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .clone();
+    eprintln!("{}", log_record(&command, buildpack_id, path, message));
+}
+
+// without an explicit path, the `Path:` line most messages and errors include is used
+fn log_record(
+    command: &str,
+    buildpack_id: Option<String>,
+    path: Option<String>,
+    message: &str,
+) -> Value {
+    let (level, message) = LEVEL_PREFIXES
+        .iter()
+        .find_map(|(prefix, level)| {
+            message
+                .strip_prefix(prefix)
+                .map(|message| (*level, message.trim_start()))
+        })
+        .unwrap_or(("info", message));
+
+    let path = path.or_else(|| {
+        message
+            .lines()
+            .find_map(|line| line.strip_prefix("Path: "))
+            .map(String::from)
+    });
+
+    let mut record = Map::new();
+    record.insert("level".to_string(), json!(level));
+    record.insert("command".to_string(), json!(command));
+    if let Some(buildpack_id) = buildpack_id {
+        record.insert("buildpack_id".to_string(), json!(buildpack_id));
+    }
+    if let Some(path) = path {
+        record.insert("path".to_string(), json!(path));
+    }
+    record.insert("message".to_string(), json!(message));
+    Value::Object(record)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::logging::log_record;
+    use serde_json::json;
+
+    #[test]
+    fn test_log_record() {
+        assert_eq!(
+            log_record(
+                "prepare-release",
+                Some("heroku/nodejs".to_string()),
+                Some("buildpacks/nodejs/buildpack.toml".to_string()),
+                "✅️ Updated version 1.0.0 → 1.1.0: buildpacks/nodejs/buildpack.toml"
+            ),
+            json!({
+                "level": "info",
+                "command": "prepare-release",
+                "buildpack_id": "heroku/nodejs",
+                "path": "buildpacks/nodejs/buildpack.toml",
+                "message": "Updated version 1.0.0 → 1.1.0: buildpacks/nodejs/buildpack.toml"
+            })
+        );
+        assert_eq!(
+            log_record(
+                "update-builder",
+                None,
+                None,
+                "❌ Could not read builder\nPath: builders/22/builder.toml\nError: denied"
+            ),
+            json!({
+                "level": "error",
+                "command": "update-builder",
+                "path": "builders/22/builder.toml",
+                "message": "Could not read builder\nPath: builders/22/builder.toml\nError: denied"
+            })
+        );
+        assert_eq!(
+            log_record(
+                "lint-changelogs",
+                None,
+                None,
+                "⚠️ Release state is out of date"
+            ),
+            json!({
+                "level": "warning",
+                "command": "lint-changelogs",
+                "message": "Release state is out of date"
+            })
+        );
+        assert_eq!(
+            log_record("check-registry", None, None, "Checked 3 buildpacks")["level"],
+            "info"
+        );
+    }
+}
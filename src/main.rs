@@ -1,55 +1,451 @@
+use crate::commands::add_changelog_entry::command::AddChangelogEntryArgs;
+use crate::commands::bump_dependency::command::BumpDependencyArgs;
+use crate::commands::check_registry::command::CheckRegistryArgs;
+use crate::commands::check_version::command::CheckVersionArgs;
+use crate::commands::commit_changes::command::CommitChangesArgs;
+use crate::commands::compile_root_changelog::command::CompileRootChangelogArgs;
+use crate::commands::create_buildpackage::command::CreateBuildpackageArgs;
+use crate::commands::deprecate_buildpack::command::DeprecateBuildpackArgs;
+use crate::commands::diff_sbom::command::DiffSbomArgs;
+use crate::commands::extract_changelog::command::ExtractChangelogArgs;
+use crate::commands::flatten_buildpack::command::FlattenBuildpackArgs;
+use crate::commands::fmt_changelogs::command::FmtChangelogsArgs;
 use crate::commands::generate_buildpack_matrix::command::GenerateBuildpackMatrixArgs;
 use crate::commands::generate_changelog::command::GenerateChangelogArgs;
+use crate::commands::generate_release_checklist::command::GenerateReleaseChecklistArgs;
+use crate::commands::generate_token::command::GenerateTokenArgs;
+use crate::commands::hash_artifacts::command::HashArtifactsArgs;
+use crate::commands::lint_buildpacks::command::LintBuildpacksArgs;
+use crate::commands::lint_changelogs::command::LintChangelogsArgs;
+use crate::commands::package_buildpack::command::PackageBuildpackArgs;
+use crate::commands::plan_release::command::PlanReleaseArgs;
+use crate::commands::post_pr_comment::command::PostPrCommentArgs;
+use crate::commands::post_release_announcement::command::PostReleaseAnnouncementArgs;
 use crate::commands::prepare_release::command::PrepareReleaseArgs;
+use crate::commands::register_buildpack::command::RegisterBuildpackArgs;
+use crate::commands::release_impact::command::ReleaseImpactArgs;
+use crate::commands::rename_buildpack::command::RenameBuildpackArgs;
+use crate::commands::run_release::command::RunReleaseArgs;
+use crate::commands::set_buildpack_targets::command::SetBuildpackTargetsArgs;
+use crate::commands::show_release_state::command::ShowReleaseStateArgs;
+use crate::commands::sync_docs::command::SyncDocsArgs;
+use crate::commands::sync_meta_buildpacks::command::SyncMetaBuildpacksArgs;
+use crate::commands::update_action_pins::command::UpdateActionPinsArgs;
 use crate::commands::update_builder::command::UpdateBuilderArgs;
+use crate::commands::update_builder_remote::command::UpdateBuilderRemoteArgs;
+use crate::commands::validate_builders::command::ValidateBuildersArgs;
+use crate::commands::verify_changelog_dates::command::VerifyChangelogDatesArgs;
+use crate::commands::verify_published::command::VerifyPublishedArgs;
 use crate::commands::{
-    generate_buildpack_matrix, generate_changelog, prepare_release, update_builder,
+    add_changelog_entry, bump_dependency, check_registry, check_version, commit_changes,
+    compile_root_changelog, create_buildpackage, deprecate_buildpack, diff_sbom, extract_changelog,
+    flatten_buildpack, fmt_changelogs, generate_buildpack_matrix, generate_changelog,
+    generate_release_checklist, generate_token, hash_artifacts, lint_buildpacks, lint_changelogs,
+    package_buildpack, plan_release, post_pr_comment, post_release_announcement, prepare_release,
+    register_buildpack, release_impact, rename_buildpack, run_release, set_buildpack_targets,
+    show_release_state, sync_docs, sync_meta_buildpacks, update_action_pins, update_builder,
+    update_builder_remote, validate_builders, verify_changelog_dates, verify_published,
 };
-use clap::Parser;
+use crate::error::{exit_with_error, ErrorFormat};
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand};
+use languages_github_actions::cancellation::{install_signal_handlers, parse_timeout, set_timeout};
+use languages_github_actions::debug_artifacts::set_artifacts_dir;
+use languages_github_actions::experimental::ExperimentalFeatures;
+use languages_github_actions::github::actions;
+use languages_github_actions::github::actions::{LargeOutputError, OUTPUTS_FILE_ENV};
+use languages_github_actions::logging::{json_logs_from_env, set_json_logs};
+use languages_github_actions::progress::{set_progress_format, ProgressFormat};
+use std::path::PathBuf;
+use std::time::Duration;
 
-mod changelog;
 mod commands;
-mod github;
-
-const UNSPECIFIED_ERROR: i32 = 1;
+mod error;
+#[cfg(test)]
+mod it;
 
 #[derive(Parser)]
 #[command(bin_name = "actions")]
-pub(crate) enum Cli {
+pub(crate) struct Cli {
+    #[arg(long, value_enum, global = true, default_value_t)]
+    error_format: ErrorFormat,
+    #[arg(long, global = true)]
+    outputs_file: Option<PathBuf>,
+    #[arg(long, global = true, value_parser = parse_timeout)]
+    timeout: Option<Duration>,
+    #[arg(long, global = true, value_name = "DIR")]
+    debug_artifacts: Option<PathBuf>,
+    #[arg(long, value_enum, global = true, default_value_t)]
+    progress_format: ProgressFormat,
+    #[arg(long, global = true)]
+    json_logs: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum Command {
+    AddChangelogEntry(AddChangelogEntryArgs),
+    BumpDependency(BumpDependencyArgs),
+    CheckRegistry(CheckRegistryArgs),
+    CommitChanges(CommitChangesArgs),
+    CompileRootChangelog(CompileRootChangelogArgs),
+    CreateBuildpackage(CreateBuildpackageArgs),
+    DeprecateBuildpack(DeprecateBuildpackArgs),
+    DiffSbom(DiffSbomArgs),
+    ExtractChangelog(ExtractChangelogArgs),
+    FlattenBuildpack(FlattenBuildpackArgs),
+    FmtChangelogs(FmtChangelogsArgs),
     GenerateBuildpackMatrix(GenerateBuildpackMatrixArgs),
     GenerateChangelog(GenerateChangelogArgs),
+    GenerateReleaseChecklist(GenerateReleaseChecklistArgs),
+    GenerateToken(GenerateTokenArgs),
+    HashArtifacts(HashArtifactsArgs),
+    LintBuildpacks(LintBuildpacksArgs),
+    LintChangelogs(LintChangelogsArgs),
+    PackageBuildpack(PackageBuildpackArgs),
+    PlanRelease(PlanReleaseArgs),
+    PostPrComment(PostPrCommentArgs),
+    PostReleaseAnnouncement(PostReleaseAnnouncementArgs),
     PrepareRelease(PrepareReleaseArgs),
+    RegisterBuildpack(RegisterBuildpackArgs),
+    ReleaseImpact(ReleaseImpactArgs),
+    RenameBuildpack(RenameBuildpackArgs),
+    RunRelease(RunReleaseArgs),
+    SetBuildpackTargets(SetBuildpackTargetsArgs),
+    ShowReleaseState(ShowReleaseStateArgs),
+    SyncDocs(SyncDocsArgs),
+    UpdateActionPins(UpdateActionPinsArgs),
     UpdateBuilder(UpdateBuilderArgs),
+    UpdateBuilderRemote(UpdateBuilderRemoteArgs),
+    ValidateBuilders(ValidateBuildersArgs),
+    VerifyChangelogDates(VerifyChangelogDatesArgs),
+    VerifyPublished(VerifyPublishedArgs),
+    #[command(name = "self")]
+    SelfCommands(SelfArgs),
+    Experimental(ExperimentalArgs),
+}
+
+/// Commands that manage the actions binary itself
+#[derive(Args)]
+pub(crate) struct SelfArgs {
+    #[command(subcommand)]
+    command: SelfCommand,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum SelfCommand {
+    CheckVersion(CheckVersionArgs),
+}
+
+/// Commands that are still in development, each must be enabled with `ACTIONS_EXPERIMENTAL`
+#[derive(Args)]
+pub(crate) struct ExperimentalArgs {
+    #[command(subcommand)]
+    command: ExperimentalCommand,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum ExperimentalCommand {
+    SyncMetaBuildpacks(SyncMetaBuildpacksArgs),
+}
+
+// experimental commands stay out of the help output until they are enabled
+fn cli_command(features: &ExperimentalFeatures) -> clap::Command {
+    Cli::command().mut_subcommand("experimental", |experimental| {
+        let names = experimental
+            .get_subcommands()
+            .map(|command| command.get_name().to_string())
+            .collect::<Vec<_>>();
+        names.iter().fold(
+            experimental.hide(!features.any_enabled()),
+            |experimental, name| {
+                experimental
+                    .mut_subcommand(name, |command| command.hide(!features.is_enabled(name)))
+            },
+        )
+    })
 }
 
 fn main() {
-    match Cli::parse() {
-        Cli::GenerateBuildpackMatrix(args) => {
+    let features = ExperimentalFeatures::from_env();
+    let matches = cli_command(&features).get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|error| error.exit());
+    let error_format = cli.error_format;
+
+    // set before any command runs so every output written through `actions::set_output` sees it
+    if let Some(outputs_file) = &cli.outputs_file {
+        std::env::set_var(OUTPUTS_FILE_ENV, outputs_file);
+    }
+
+    if let Some(debug_artifacts) = cli.debug_artifacts {
+        set_artifacts_dir(debug_artifacts);
+    }
+
+    set_progress_format(cli.progress_format);
+
+    // records name the command that wrote them, nested commands are joined (e.g.; `self check-version`)
+    if cli.json_logs || json_logs_from_env() {
+        let mut command = vec![];
+        let mut subcommand = matches.subcommand();
+        while let Some((name, matches)) = subcommand {
+            command.push(name);
+            subcommand = matches.subcommand();
+        }
+        set_json_logs(command.join(" "));
+    }
+
+    install_signal_handlers();
+    if let Some(timeout) = cli.timeout {
+        set_timeout(timeout);
+    }
+
+    match cli.command {
+        Command::AddChangelogEntry(args) => {
+            if let Err(error) = add_changelog_entry::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::BumpDependency(args) => {
+            if let Err(error) = bump_dependency::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::CheckRegistry(args) => {
+            if let Err(error) = check_registry::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::CommitChanges(args) => {
+            if let Err(error) = commit_changes::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::CompileRootChangelog(args) => {
+            if let Err(error) = compile_root_changelog::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::CreateBuildpackage(args) => {
+            if let Err(error) = create_buildpackage::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::DeprecateBuildpack(args) => {
+            if let Err(error) = deprecate_buildpack::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::DiffSbom(args) => {
+            if let Err(error) = diff_sbom::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::ExtractChangelog(args) => {
+            if let Err(error) = extract_changelog::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::FlattenBuildpack(args) => {
+            if let Err(error) = flatten_buildpack::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::FmtChangelogs(args) => {
+            if let Err(error) = fmt_changelogs::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::GenerateBuildpackMatrix(args) => {
             if let Err(error) = generate_buildpack_matrix::execute(args) {
-                eprintln!("❌ {error}");
-                std::process::exit(UNSPECIFIED_ERROR);
+                exit_with_error(error, &error_format);
             }
         }
 
-        Cli::GenerateChangelog(args) => {
+        Command::GenerateChangelog(args) => {
             if let Err(error) = generate_changelog::execute(args) {
-                eprintln!("❌ {error}");
-                std::process::exit(UNSPECIFIED_ERROR);
+                exit_with_error(error, &error_format);
             }
         }
 
-        Cli::PrepareRelease(args) => {
+        Command::GenerateReleaseChecklist(args) => {
+            if let Err(error) = generate_release_checklist::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::GenerateToken(args) => {
+            if let Err(error) = generate_token::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::HashArtifacts(args) => {
+            if let Err(error) = hash_artifacts::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::LintBuildpacks(args) => {
+            if let Err(error) = lint_buildpacks::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::LintChangelogs(args) => {
+            if let Err(error) = lint_changelogs::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::PackageBuildpack(args) => {
+            if let Err(error) = package_buildpack::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::PlanRelease(args) => {
+            if let Err(error) = plan_release::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::PostPrComment(args) => {
+            if let Err(error) = post_pr_comment::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::PostReleaseAnnouncement(args) => {
+            if let Err(error) = post_release_announcement::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::PrepareRelease(args) => {
             if let Err(error) = prepare_release::execute(args) {
-                eprintln!("❌ {error}");
-                std::process::exit(UNSPECIFIED_ERROR);
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::RegisterBuildpack(args) => {
+            if let Err(error) = register_buildpack::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::ReleaseImpact(args) => {
+            if let Err(error) = release_impact::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::RenameBuildpack(args) => {
+            if let Err(error) = rename_buildpack::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::RunRelease(args) => {
+            if let Err(error) = run_release::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::SetBuildpackTargets(args) => {
+            if let Err(error) = set_buildpack_targets::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::ShowReleaseState(args) => {
+            if let Err(error) = show_release_state::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::SyncDocs(args) => {
+            if let Err(error) = sync_docs::execute(args) {
+                exit_with_error(error, &error_format);
             }
         }
 
-        Cli::UpdateBuilder(args) => {
+        Command::UpdateActionPins(args) => {
+            if let Err(error) = update_action_pins::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::UpdateBuilder(args) => {
             if let Err(error) = update_builder::execute(args) {
-                eprintln!("❌ {error}");
-                std::process::exit(UNSPECIFIED_ERROR);
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::UpdateBuilderRemote(args) => {
+            if let Err(error) = update_builder_remote::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::ValidateBuilders(args) => {
+            if let Err(error) = validate_builders::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::VerifyChangelogDates(args) => {
+            if let Err(error) = verify_changelog_dates::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::VerifyPublished(args) => {
+            if let Err(error) = verify_published::execute(args) {
+                exit_with_error(error, &error_format);
+            }
+        }
+
+        Command::SelfCommands(args) => match args.command {
+            SelfCommand::CheckVersion(args) => {
+                if let Err(error) = check_version::execute(args) {
+                    exit_with_error(error, &error_format);
+                }
+            }
+        },
+
+        Command::Experimental(args) => {
+            if let Some(name) = matches
+                .subcommand_matches("experimental")
+                .and_then(|matches| matches.subcommand_name())
+            {
+                if let Err(error) = features.require(name) {
+                    exit_with_error(error, &error_format);
+                }
+            }
+
+            match args.command {
+                ExperimentalCommand::SyncMetaBuildpacks(args) => {
+                    if let Err(error) = sync_meta_buildpacks::execute(args) {
+                        exit_with_error(error, &error_format);
+                    }
+                }
             }
         }
     }
+
+    // failed commands exit above so this only collects the outputs of a successful run
+    if let Err(error) = actions::set_outputs_json() {
+        exit_with_error(LargeOutputError::SetOutput(error), &error_format);
+    }
 }
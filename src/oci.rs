@@ -0,0 +1,978 @@
+use crate::cancellation::{check_cancelled, CancellationError};
+use crate::http;
+use crate::registry_auth::find_registry_credentials;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+const DOCKER_HUB_REGISTRY: &str = "docker.io";
+const DOCKER_HUB_API_HOST: &str = "registry-1.docker.io";
+pub const OCI_MANIFEST_MEDIA_TYPE: &str = "application/vnd.oci.image.manifest.v1+json";
+pub const OCI_CONFIG_MEDIA_TYPE: &str = "application/vnd.oci.image.config.v1+json";
+pub const OCI_LAYER_MEDIA_TYPE: &str = "application/vnd.oci.image.layer.v1.tar";
+const MANIFEST_MEDIA_TYPES: &str = "application/vnd.oci.image.index.v1+json, application/vnd.oci.image.manifest.v1+json, application/vnd.docker.distribution.manifest.list.v2+json, application/vnd.docker.distribution.manifest.v2+json";
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ImageReference {
+    pub registry: String,
+    pub repository: String,
+    pub reference: String,
+}
+
+impl ImageReference {
+    fn api_host(&self) -> &str {
+        if self.registry == DOCKER_HUB_REGISTRY {
+            DOCKER_HUB_API_HOST
+        } else {
+            &self.registry
+        }
+    }
+
+    fn blob_url(&self, digest: &str) -> String {
+        format!(
+            "https://{}/v2/{}/blobs/{digest}",
+            self.api_host(),
+            self.repository
+        )
+    }
+
+    fn manifest_url(&self) -> String {
+        format!(
+            "https://{}/v2/{}/manifests/{}",
+            self.api_host(),
+            self.repository,
+            self.reference
+        )
+    }
+
+    pub fn is_digest(&self) -> bool {
+        self.reference.contains(':')
+    }
+
+    pub fn with_digest(&self, digest: &str) -> ImageReference {
+        ImageReference {
+            reference: digest.to_string(),
+            ..self.clone()
+        }
+    }
+}
+
+impl TryFrom<&str> for ImageReference {
+    type Error = OciError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let image = value
+            .strip_prefix("docker://")
+            .ok_or_else(|| OciError::UnsupportedScheme(value.to_string()))?;
+
+        let (registry, remainder) = match image.split_once('/') {
+            Some((host, remainder)) if host.contains(['.', ':']) || host == "localhost" => {
+                (host.to_string(), remainder)
+            }
+            _ => (DOCKER_HUB_REGISTRY.to_string(), image),
+        };
+
+        let (repository, reference) = match remainder.split_once('@') {
+            Some((repository, digest)) => (repository, digest),
+            None => match remainder.rsplit_once(':') {
+                Some((repository, tag)) if !tag.contains('/') => (repository, tag),
+                _ => (remainder, "latest"),
+            },
+        };
+
+        if repository.is_empty() || reference.is_empty() {
+            return Err(OciError::InvalidImageReference(value.to_string()));
+        }
+
+        let repository = if registry == DOCKER_HUB_REGISTRY && !repository.contains('/') {
+            format!("library/{repository}")
+        } else {
+            repository.to_string()
+        };
+
+        Ok(ImageReference {
+            registry,
+            repository,
+            reference: reference.to_string(),
+        })
+    }
+}
+
+impl Display for ImageReference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let separator = if self.is_digest() { '@' } else { ':' };
+        write!(
+            f,
+            "{}/{}{separator}{}",
+            self.registry, self.repository, self.reference
+        )
+    }
+}
+
+pub fn image_exists(image: &ImageReference) -> Result<bool, OciError> {
+    let url = image.manifest_url();
+    match head_manifest(&url, None)? {
+        ManifestStatus::Found => Ok(true),
+        ManifestStatus::NotFound => Ok(false),
+        ManifestStatus::Unauthorized(challenge) => {
+            let authorization = request_pull_authorization(image, &url, challenge.as_deref())?;
+            match head_manifest(&url, Some(&authorization))? {
+                ManifestStatus::Found => Ok(true),
+                ManifestStatus::NotFound => Ok(false),
+                ManifestStatus::Unauthorized(_) => Err(OciError::Unauthorized(url)),
+            }
+        }
+    }
+}
+
+enum ManifestStatus {
+    Found,
+    NotFound,
+    Unauthorized(Option<String>),
+}
+
+fn head_manifest(url: &str, authorization: Option<&str>) -> Result<ManifestStatus, OciError> {
+    let request = http::head(url).set("Accept", MANIFEST_MEDIA_TYPES);
+    match with_authorization(request, authorization).call() {
+        Ok(_) => Ok(ManifestStatus::Found),
+        Err(ureq::Error::Status(404, _)) => Ok(ManifestStatus::NotFound),
+        Err(ureq::Error::Status(401, response)) => Ok(ManifestStatus::Unauthorized(
+            response.header("WWW-Authenticate").map(String::from),
+        )),
+        Err(error) => Err(OciError::Request(url.to_string(), Box::new(error))),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+struct CachedDigest {
+    digest: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    etag: Option<String>,
+}
+
+// digests keyed by `registry/repository:tag`, persisted as JSON so repeated runs only need a
+// conditional request per tag instead of a full manifest lookup
+#[derive(Default)]
+pub struct DigestCache {
+    path: Option<PathBuf>,
+    entries: Mutex<BTreeMap<String, CachedDigest>>,
+}
+
+impl DigestCache {
+    pub fn load(path: PathBuf) -> Result<DigestCache, OciError> {
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map_err(|e| OciError::ParsingDigestCache(path.clone(), e))?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(error) => Err(OciError::ReadingDigestCache(path.clone(), error))?,
+        };
+        Ok(DigestCache {
+            path: Some(path),
+            entries: Mutex::new(entries),
+        })
+    }
+
+    pub fn save(&self) -> Result<(), OciError> {
+        if let Some(path) = &self.path {
+            let contents = serde_json::to_string_pretty(&*self.lock())
+                .expect("Digest cache should serialize to JSON");
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| OciError::WritingDigestCache(path.clone(), e))?;
+            }
+            std::fs::write(path, contents)
+                .map_err(|e| OciError::WritingDigestCache(path.clone(), e))?;
+        }
+        Ok(())
+    }
+
+    fn get(&self, image: &ImageReference) -> Option<CachedDigest> {
+        self.lock().get(&image.to_string()).cloned()
+    }
+
+    fn insert(&self, image: &ImageReference, entry: CachedDigest) {
+        self.lock().insert(image.to_string(), entry);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, CachedDigest>> {
+        self.entries
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+pub fn resolve_digest(image: &ImageReference, cache: &DigestCache) -> Result<String, OciError> {
+    if image.is_digest() {
+        return Ok(image.reference.clone());
+    }
+
+    let url = image.manifest_url();
+    let cached = cache.get(image);
+    let etag = cached.as_ref().and_then(|entry| entry.etag.as_deref());
+
+    let status = match head_manifest_digest(&url, None, etag)? {
+        DigestStatus::Unauthorized(challenge) => {
+            let authorization = request_pull_authorization(image, &url, challenge.as_deref())?;
+            match head_manifest_digest(&url, Some(&authorization), etag)? {
+                DigestStatus::Unauthorized(_) => Err(OciError::Unauthorized(url.clone()))?,
+                status => status,
+            }
+        }
+        status => status,
+    };
+
+    match (status, cached) {
+        (DigestStatus::NotModified, Some(cached)) => Ok(cached.digest),
+        (DigestStatus::Found { digest, etag }, _) => {
+            let digest = digest.ok_or_else(|| OciError::MissingDigest(url.clone()))?;
+            cache.insert(
+                image,
+                CachedDigest {
+                    digest: digest.clone(),
+                    etag,
+                },
+            );
+            Ok(digest)
+        }
+        (DigestStatus::NotFound, _) => Err(OciError::ManifestNotFound(url)),
+        _ => Err(OciError::MissingDigest(url)),
+    }
+}
+
+// lookups are spread over at most `concurrency` threads, results are returned in input order
+pub fn resolve_digests(
+    images: &[ImageReference],
+    concurrency: usize,
+    cache: &DigestCache,
+) -> Vec<Result<String, OciError>> {
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(
+        images
+            .iter()
+            .map(|_| None)
+            .collect::<Vec<Option<Result<String, OciError>>>>(),
+    );
+
+    std::thread::scope(|scope| {
+        for _ in 0..concurrency.clamp(1, images.len().max(1)) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                match images.get(index) {
+                    Some(image) => {
+                        let result = check_cancelled()
+                            .map_err(OciError::Cancelled)
+                            .and_then(|_| resolve_digest(image, cache));
+                        results
+                            .lock()
+                            .unwrap_or_else(std::sync::PoisonError::into_inner)[index] =
+                            Some(result);
+                    }
+                    None => break,
+                }
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .into_iter()
+        .map(|result| result.expect("Every image should have been resolved"))
+        .collect()
+}
+
+enum DigestStatus {
+    Found {
+        digest: Option<String>,
+        etag: Option<String>,
+    },
+    NotModified,
+    NotFound,
+    Unauthorized(Option<String>),
+}
+
+fn head_manifest_digest(
+    url: &str,
+    authorization: Option<&str>,
+    etag: Option<&str>,
+) -> Result<DigestStatus, OciError> {
+    let mut request = with_authorization(
+        http::head(url).set("Accept", MANIFEST_MEDIA_TYPES),
+        authorization,
+    );
+    if let Some(etag) = etag {
+        request = request.set("If-None-Match", etag);
+    }
+    match request.call() {
+        Ok(response) if response.status() == 304 => Ok(DigestStatus::NotModified),
+        Ok(response) => Ok(DigestStatus::Found {
+            digest: response.header("Docker-Content-Digest").map(String::from),
+            etag: response.header("ETag").map(String::from),
+        }),
+        Err(ureq::Error::Status(404, _)) => Ok(DigestStatus::NotFound),
+        Err(ureq::Error::Status(401, response)) => Ok(DigestStatus::Unauthorized(
+            response.header("WWW-Authenticate").map(String::from),
+        )),
+        Err(error) => Err(OciError::Request(url.to_string(), Box::new(error))),
+    }
+}
+
+// multi-platform images point at an index, the linux/amd64 manifest is used to read the config
+// since that's the platform buildpacks are published for
+pub fn fetch_image_config(image: &ImageReference) -> Result<Value, OciError> {
+    let mut authorization = None;
+
+    let url = image.manifest_url();
+    let mut manifest = get_json(image, &url, MANIFEST_MEDIA_TYPES, &mut authorization)?;
+    if manifest.get("manifests").is_some() {
+        let digest =
+            select_platform_manifest(&manifest).ok_or_else(|| OciError::InvalidManifest(url))?;
+        manifest = get_json(
+            image,
+            &image.with_digest(&digest).manifest_url(),
+            MANIFEST_MEDIA_TYPES,
+            &mut authorization,
+        )?;
+    }
+
+    let config_digest = manifest["config"]["digest"]
+        .as_str()
+        .ok_or_else(|| OciError::InvalidManifest(image.manifest_url()))?;
+    get_json(
+        image,
+        &image.blob_url(config_digest),
+        "*/*",
+        &mut authorization,
+    )
+}
+
+fn select_platform_manifest(index: &Value) -> Option<String> {
+    let manifests = index["manifests"].as_array()?;
+    manifests
+        .iter()
+        .find(|manifest| {
+            manifest["platform"]["os"] == "linux" && manifest["platform"]["architecture"] == "amd64"
+        })
+        .or_else(|| manifests.first())
+        .and_then(|manifest| manifest["digest"].as_str())
+        .map(String::from)
+}
+
+// the pull authorization from the first challenge is reused for the rest of the requests to the
+// repository
+fn get_json(
+    image: &ImageReference,
+    url: &str,
+    accept: &str,
+    authorization: &mut Option<String>,
+) -> Result<Value, OciError> {
+    let request = |authorization: Option<&str>| {
+        with_authorization(http::get(url).set("Accept", accept), authorization)
+    };
+
+    let response = match request(authorization.as_deref()).call() {
+        Err(ureq::Error::Status(401, response)) if authorization.is_none() => {
+            let new_authorization =
+                request_pull_authorization(image, url, response.header("WWW-Authenticate"))?;
+            let response = request(Some(&new_authorization)).call();
+            *authorization = Some(new_authorization);
+            response
+        }
+        response => response,
+    };
+
+    match response {
+        Ok(response) => response
+            .into_json::<Value>()
+            .map_err(|e| OciError::ReadingResponse(url.to_string(), e)),
+        Err(ureq::Error::Status(404, _)) => Err(OciError::ManifestNotFound(url.to_string())),
+        Err(ureq::Error::Status(401, _)) => Err(OciError::Unauthorized(url.to_string())),
+        Err(error) => Err(OciError::Request(url.to_string(), Box::new(error))),
+    }
+}
+
+// pulls are tried anonymously first, once the registry asks for authentication the credentials
+// configured for it (see `registry_auth`) are used if there are any
+fn request_pull_authorization(
+    image: &ImageReference,
+    url: &str,
+    challenge: Option<&str>,
+) -> Result<String, OciError> {
+    let challenge = challenge.ok_or_else(|| OciError::MissingAuthChallenge(url.to_string()))?;
+    let credentials = find_registry_credentials(&image.registry);
+    request_authorization(url, challenge, None, credentials.as_ref())
+}
+
+// the value of the `Authorization` header answering a challenge, registries that use basic auth take
+// the credentials as-is while token auth exchanges them (or nothing, for anonymous pulls) for a token
+fn request_authorization(
+    url: &str,
+    challenge: &str,
+    scope: Option<&str>,
+    credentials: Option<&RegistryCredentials>,
+) -> Result<String, OciError> {
+    if challenge.trim().to_lowercase().starts_with("basic") {
+        return credentials
+            .map(RegistryCredentials::basic_authorization)
+            .ok_or_else(|| OciError::Unauthorized(url.to_string()));
+    }
+
+    request_bearer_token(challenge, scope, credentials).map(|token| format!("Bearer {token}"))
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    token: Option<String>,
+    access_token: Option<String>,
+}
+
+fn request_bearer_token(
+    challenge: &str,
+    scope: Option<&str>,
+    credentials: Option<&RegistryCredentials>,
+) -> Result<String, OciError> {
+    let params = parse_bearer_challenge(challenge)
+        .ok_or_else(|| OciError::UnsupportedAuthChallenge(challenge.to_string()))?;
+
+    let realm = params
+        .get("realm")
+        .ok_or_else(|| OciError::UnsupportedAuthChallenge(challenge.to_string()))?;
+
+    let mut request = http::get(realm);
+    if let Some(value) = params.get("service") {
+        request = request.query("service", value);
+    }
+    if let Some(value) = scope.or(params.get("scope").map(String::as_str)) {
+        request = request.query("scope", value);
+    }
+    if let Some(credentials) = credentials {
+        request = request.set("Authorization", &credentials.basic_authorization());
+    }
+
+    let response = request
+        .call()
+        .map_err(|e| OciError::Request(realm.clone(), Box::new(e)))?
+        .into_json::<TokenResponse>()
+        .map_err(|e| OciError::ReadingTokenResponse(realm.clone(), e))?;
+
+    response
+        .token
+        .or(response.access_token)
+        .ok_or_else(|| OciError::MissingToken(realm.clone()))
+}
+
+fn parse_bearer_challenge(challenge: &str) -> Option<HashMap<String, String>> {
+    lazy_static! {
+        static ref CHALLENGE_PARAM: Regex =
+            Regex::new(r#"(\w+)="([^"]*)""#).expect("Should be a valid regex");
+    }
+
+    let (scheme, params) = challenge.trim().split_once(' ')?;
+    if !scheme.eq_ignore_ascii_case("bearer") {
+        return None;
+    }
+
+    Some(
+        CHALLENGE_PARAM
+            .captures_iter(params)
+            .map(|captures| (captures[1].to_string(), captures[2].to_string()))
+            .collect(),
+    )
+}
+
+pub struct Blob {
+    pub media_type: String,
+    pub digest: String,
+    pub data: Vec<u8>,
+}
+
+impl Blob {
+    pub fn new(media_type: &str, data: Vec<u8>) -> Blob {
+        Blob {
+            media_type: media_type.to_string(),
+            digest: format!("sha256:{:x}", Sha256::digest(&data)),
+            data,
+        }
+    }
+
+    fn descriptor(&self) -> Value {
+        json!({
+            "mediaType": self.media_type,
+            "digest": self.digest,
+            "size": self.data.len(),
+        })
+    }
+
+    fn layout_path(&self) -> String {
+        format!("blobs/{}", self.digest.replacen(':', "/", 1))
+    }
+}
+
+pub struct Image {
+    pub manifest: Blob,
+    pub config: Blob,
+    pub layers: Vec<Blob>,
+}
+
+impl Image {
+    pub fn new(config: &Value, layers: Vec<Blob>) -> Image {
+        let config = Blob::new(OCI_CONFIG_MEDIA_TYPE, config.to_string().into_bytes());
+        let manifest = json!({
+            "schemaVersion": 2,
+            "mediaType": OCI_MANIFEST_MEDIA_TYPE,
+            "config": config.descriptor(),
+            "layers": layers.iter().map(Blob::descriptor).collect::<Vec<_>>(),
+        });
+        Image {
+            manifest: Blob::new(OCI_MANIFEST_MEDIA_TYPE, manifest.to_string().into_bytes()),
+            config,
+            layers,
+        }
+    }
+
+    pub fn digest(&self) -> &str {
+        &self.manifest.digest
+    }
+
+    fn layout_files(&self) -> Vec<(String, Vec<u8>)> {
+        let index = json!({
+            "schemaVersion": 2,
+            "manifests": [self.manifest.descriptor()],
+        });
+
+        let mut files = vec![
+            (
+                "oci-layout".to_string(),
+                json!({ "imageLayoutVersion": "1.0.0" })
+                    .to_string()
+                    .into_bytes(),
+            ),
+            ("index.json".to_string(), index.to_string().into_bytes()),
+        ];
+        for blob in [&self.manifest, &self.config]
+            .into_iter()
+            .chain(&self.layers)
+        {
+            files.push((blob.layout_path(), blob.data.clone()));
+        }
+        files
+    }
+
+    pub fn write_oci_layout(&self, dir: &Path) -> io::Result<()> {
+        for (path, data) in self.layout_files() {
+            let path = dir.join(path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, data)?;
+        }
+        Ok(())
+    }
+
+    pub fn to_oci_archive(&self) -> io::Result<Vec<u8>> {
+        let mut archive = tar::Builder::new(vec![]);
+        for (path, data) in self.layout_files() {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            archive.append_data(&mut header, path, data.as_slice())?;
+        }
+        archive.into_inner()
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RegistryCredentials {
+    pub username: String,
+    pub password: String,
+}
+
+impl RegistryCredentials {
+    fn basic_authorization(&self) -> String {
+        format!(
+            "Basic {}",
+            STANDARD.encode(format!("{}:{}", self.username, self.password))
+        )
+    }
+}
+
+pub fn push_image(
+    image: &Image,
+    reference: &ImageReference,
+    credentials: Option<&RegistryCredentials>,
+) -> Result<String, OciError> {
+    let authorization = request_push_authorization(reference, credentials)?;
+
+    for blob in image.layers.iter().chain([&image.config]) {
+        push_blob(reference, blob, authorization.as_deref())?;
+    }
+
+    let url = reference.manifest_url();
+    with_authorization(http::put(&url), authorization.as_deref())
+        .set("Content-Type", &image.manifest.media_type)
+        .send_bytes(&image.manifest.data)
+        .map_err(|e| OciError::Request(url, Box::new(e)))?;
+
+    Ok(image.digest().to_string())
+}
+
+fn request_push_authorization(
+    reference: &ImageReference,
+    credentials: Option<&RegistryCredentials>,
+) -> Result<Option<String>, OciError> {
+    let url = format!("https://{}/v2/", reference.api_host());
+    let challenge = match http::get(&url).call() {
+        Ok(_) => return Ok(None),
+        Err(ureq::Error::Status(401, response)) => response
+            .header("WWW-Authenticate")
+            .map(String::from)
+            .ok_or_else(|| OciError::MissingAuthChallenge(url.clone()))?,
+        Err(error) => return Err(OciError::Request(url, Box::new(error))),
+    };
+
+    let scope = format!("repository:{}:pull,push", reference.repository);
+    request_authorization(&url, &challenge, Some(&scope), credentials).map(Some)
+}
+
+fn push_blob(
+    reference: &ImageReference,
+    blob: &Blob,
+    authorization: Option<&str>,
+) -> Result<(), OciError> {
+    let blob_url = reference.blob_url(&blob.digest);
+    match with_authorization(http::head(&blob_url), authorization).call() {
+        Ok(_) => return Ok(()),
+        Err(ureq::Error::Status(404, _)) => {}
+        Err(error) => return Err(OciError::Request(blob_url, Box::new(error))),
+    }
+
+    let uploads_url = reference.blob_url("uploads/");
+    let response = with_authorization(http::post(&uploads_url), authorization)
+        .call()
+        .map_err(|e| OciError::Request(uploads_url.clone(), Box::new(e)))?;
+
+    let location = response
+        .header("Location")
+        .ok_or_else(|| OciError::MissingUploadLocation(uploads_url.clone()))?;
+    let location = if location.starts_with('/') {
+        format!("https://{}{location}", reference.api_host())
+    } else {
+        location.to_string()
+    };
+    let separator = if location.contains('?') { '&' } else { '?' };
+    let upload_url = format!("{location}{separator}digest={}", blob.digest);
+
+    with_authorization(http::put(&upload_url), authorization)
+        .set("Content-Type", "application/octet-stream")
+        .send_bytes(&blob.data)
+        .map_err(|e| OciError::Request(upload_url, Box::new(e)))?;
+
+    Ok(())
+}
+
+fn with_authorization(request: ureq::Request, authorization: Option<&str>) -> ureq::Request {
+    match authorization {
+        Some(authorization) => request.set("Authorization", authorization),
+        None => request,
+    }
+}
+
+#[derive(Debug)]
+pub enum OciError {
+    UnsupportedScheme(String),
+    InvalidImageReference(String),
+    Request(String, Box<ureq::Error>),
+    MissingAuthChallenge(String),
+    Unauthorized(String),
+    UnsupportedAuthChallenge(String),
+    ReadingTokenResponse(String, std::io::Error),
+    MissingToken(String),
+    MissingUploadLocation(String),
+    MissingDigest(String),
+    ManifestNotFound(String),
+    InvalidManifest(String),
+    ReadingResponse(String, io::Error),
+    ReadingDigestCache(PathBuf, io::Error),
+    ParsingDigestCache(PathBuf, serde_json::Error),
+    WritingDigestCache(PathBuf, io::Error),
+    Cancelled(CancellationError),
+}
+
+impl Display for OciError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OciError::UnsupportedScheme(value) => {
+                write!(f, "Only docker:// image references are supported - {value}")
+            }
+            OciError::InvalidImageReference(value) => {
+                write!(f, "Invalid image reference - {value}")
+            }
+            OciError::Request(url, error) => {
+                write!(f, "Registry request failed - {url}\nError: {error}")
+            }
+            OciError::MissingAuthChallenge(url) => {
+                write!(
+                    f,
+                    "Registry requires authentication but sent no challenge - {url}"
+                )
+            }
+            OciError::Unauthorized(url) => {
+                write!(f, "Registry rejected the provided credentials - {url}")
+            }
+            OciError::UnsupportedAuthChallenge(challenge) => {
+                write!(
+                    f,
+                    "Unsupported registry authentication challenge - {challenge}"
+                )
+            }
+            OciError::ReadingTokenResponse(url, error) => {
+                write!(
+                    f,
+                    "Could not read registry token response - {url}\nError: {error}"
+                )
+            }
+            OciError::MissingToken(url) => {
+                write!(f, "No token in registry token response - {url}")
+            }
+            OciError::MissingUploadLocation(url) => {
+                write!(f, "Registry did not return a blob upload location - {url}")
+            }
+            OciError::MissingDigest(url) => {
+                write!(f, "Registry did not return a manifest digest - {url}")
+            }
+            OciError::ManifestNotFound(url) => {
+                write!(f, "Image manifest does not exist in the registry - {url}")
+            }
+            OciError::InvalidManifest(url) => {
+                write!(f, "Image manifest has no usable config or platform - {url}")
+            }
+            OciError::ReadingResponse(url, error) => {
+                write!(
+                    f,
+                    "Could not read registry response - {url}\nError: {error}"
+                )
+            }
+            OciError::ReadingDigestCache(path, error) => {
+                write!(
+                    f,
+                    "Could not read digest cache\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+            OciError::ParsingDigestCache(path, error) => {
+                write!(
+                    f,
+                    "Could not parse digest cache\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+            OciError::WritingDigestCache(path, error) => {
+                write!(
+                    f,
+                    "Could not write digest cache\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+            OciError::Cancelled(error) => {
+                write!(f, "{error}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::oci::{
+        parse_bearer_challenge, resolve_digests, select_platform_manifest, Blob, CachedDigest,
+        DigestCache, Image, ImageReference, OCI_CONFIG_MEDIA_TYPE, OCI_LAYER_MEDIA_TYPE,
+        OCI_MANIFEST_MEDIA_TYPE,
+    };
+    use serde_json::{json, Value};
+
+    #[test]
+    fn test_parse_image_reference_with_digest() {
+        let image = ImageReference::try_from(
+            "docker://docker.io/heroku/buildpack-java@sha256:c6dd500be06a2a1e764c30359c5dd4f4955a98b572ef3095b2f6115cd8a87c99",
+        )
+        .unwrap();
+        assert_eq!(image.registry, "docker.io");
+        assert_eq!(image.repository, "heroku/buildpack-java");
+        assert_eq!(
+            image.reference,
+            "sha256:c6dd500be06a2a1e764c30359c5dd4f4955a98b572ef3095b2f6115cd8a87c99"
+        );
+        assert_eq!(
+            image.manifest_url(),
+            "https://registry-1.docker.io/v2/heroku/buildpack-java/manifests/sha256:c6dd500be06a2a1e764c30359c5dd4f4955a98b572ef3095b2f6115cd8a87c99"
+        );
+    }
+
+    #[test]
+    fn test_parse_image_reference_with_tag_and_custom_registry() {
+        let image = ImageReference::try_from("docker://localhost:5000/heroku/buildpack-java:1.2.3")
+            .unwrap();
+        assert_eq!(image.registry, "localhost:5000");
+        assert_eq!(image.repository, "heroku/buildpack-java");
+        assert_eq!(image.reference, "1.2.3");
+        assert_eq!(
+            image.to_string(),
+            "localhost:5000/heroku/buildpack-java:1.2.3"
+        );
+    }
+
+    #[test]
+    fn test_parse_image_reference_defaults() {
+        let image = ImageReference::try_from("docker://ubuntu").unwrap();
+        assert_eq!(image.registry, "docker.io");
+        assert_eq!(image.repository, "library/ubuntu");
+        assert_eq!(image.reference, "latest");
+    }
+
+    #[test]
+    fn test_parse_image_reference_requires_docker_scheme() {
+        assert!(ImageReference::try_from("https://example.com/buildpack.cnb").is_err());
+    }
+
+    #[test]
+    fn test_image_reference_with_digest() {
+        let image = ImageReference::try_from("docker://docker.io/heroku/buildpack-java:1.2.3")
+            .unwrap()
+            .with_digest("sha256:c6dd500be06a2a1e764c30359c5dd4f4955a98b572ef3095b2f6115cd8a87c99");
+        assert!(image.is_digest());
+        assert_eq!(
+            image.to_string(),
+            "docker.io/heroku/buildpack-java@sha256:c6dd500be06a2a1e764c30359c5dd4f4955a98b572ef3095b2f6115cd8a87c99"
+        );
+    }
+
+    #[test]
+    fn test_resolve_digests_of_digest_references() {
+        let images = [
+            "docker://docker.io/heroku/buildpack-java@sha256:c6dd500be06a2a1e764c30359c5dd4f4955a98b572ef3095b2f6115cd8a87c99",
+            "docker://docker.io/heroku/buildpack-go@sha256:22ec91eebee2271b99368844f193c4bb3c6084201062f89b3e45179b938c3241",
+            "docker://docker.io/heroku/buildpack-php@sha256:21990393c93927b16f76c303ae007ea7e95502d52b0317ca773d4cd51e7a5682",
+        ]
+        .into_iter()
+        .map(|image| ImageReference::try_from(image).unwrap())
+        .collect::<Vec<_>>();
+
+        let digests = resolve_digests(&images, 2, &DigestCache::default())
+            .into_iter()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            digests,
+            images
+                .iter()
+                .map(|image| image.reference.clone())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_digest_cache_round_trip() {
+        let path = std::env::temp_dir()
+            .join(format!("digest-cache-{}", std::process::id()))
+            .join("digests.json");
+        let image =
+            ImageReference::try_from("docker://docker.io/heroku/buildpack-java:1.2.3").unwrap();
+        let entry = CachedDigest {
+            digest: "sha256:c6dd500be06a2a1e764c30359c5dd4f4955a98b572ef3095b2f6115cd8a87c99"
+                .to_string(),
+            etag: Some("\"abc\"".to_string()),
+        };
+
+        let cache = DigestCache::load(path.clone()).unwrap();
+        assert_eq!(cache.get(&image), None);
+        cache.insert(&image, entry.clone());
+        cache.save().unwrap();
+
+        let cache = DigestCache::load(path.clone()).unwrap();
+        assert_eq!(cache.get(&image), Some(entry));
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_parse_bearer_challenge() {
+        let params = parse_bearer_challenge(
+            r#"Bearer realm="https://auth.docker.io/token",service="registry.docker.io",scope="repository:heroku/buildpack-java:pull""#,
+        )
+        .unwrap();
+        assert_eq!(params["realm"], "https://auth.docker.io/token");
+        assert_eq!(params["service"], "registry.docker.io");
+        assert_eq!(params["scope"], "repository:heroku/buildpack-java:pull");
+        assert_eq!(parse_bearer_challenge(r#"Basic realm="x""#), None);
+    }
+
+    #[test]
+    fn test_blob_digest() {
+        let blob = Blob::new(OCI_LAYER_MEDIA_TYPE, b"hello".to_vec());
+        assert_eq!(
+            blob.digest,
+            "sha256:2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+        assert_eq!(
+            blob.layout_path(),
+            "blobs/sha256/2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+    }
+
+    #[test]
+    fn test_image_manifest() {
+        let layer = Blob::new(OCI_LAYER_MEDIA_TYPE, b"hello".to_vec());
+        let layer_digest = layer.digest.clone();
+        let image = Image::new(&json!({ "os": "linux" }), vec![layer]);
+
+        let manifest: Value = serde_json::from_slice(&image.manifest.data).unwrap();
+        assert_eq!(image.manifest.media_type, OCI_MANIFEST_MEDIA_TYPE);
+        assert_eq!(image.digest(), image.manifest.digest);
+        assert_eq!(
+            manifest,
+            json!({
+                "schemaVersion": 2,
+                "mediaType": OCI_MANIFEST_MEDIA_TYPE,
+                "config": {
+                    "mediaType": OCI_CONFIG_MEDIA_TYPE,
+                    "digest": image.config.digest,
+                    "size": 14,
+                },
+                "layers": [{
+                    "mediaType": OCI_LAYER_MEDIA_TYPE,
+                    "digest": layer_digest,
+                    "size": 5,
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn test_select_platform_manifest() {
+        let index = json!({
+            "manifests": [
+                { "digest": "sha256:arm", "platform": { "os": "linux", "architecture": "arm64" } },
+                { "digest": "sha256:amd", "platform": { "os": "linux", "architecture": "amd64" } },
+            ]
+        });
+        assert_eq!(
+            select_platform_manifest(&index),
+            Some("sha256:amd".to_string())
+        );
+        assert_eq!(
+            select_platform_manifest(&json!({ "manifests": [{ "digest": "sha256:only" }] })),
+            Some("sha256:only".to_string())
+        );
+        assert_eq!(select_platform_manifest(&json!({ "manifests": [] })), None);
+    }
+}
@@ -0,0 +1,415 @@
+use crate::progress::{track, ProgressStep};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use std::str::FromStr;
+use toml_edit::Value;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Patch {
+    pub span: Range<usize>,
+    pub replacement: String,
+}
+
+impl Patch {
+    pub fn replace<S: Into<String>>(span: Range<usize>, replacement: S) -> Patch {
+        Patch {
+            span,
+            replacement: replacement.into(),
+        }
+    }
+
+    pub fn insert<S: Into<String>>(offset: usize, text: S) -> Patch {
+        Patch::replace(offset..offset, text)
+    }
+}
+
+pub fn apply_patches(contents: &str, patches: Vec<Patch>) -> Result<String, PatchError> {
+    let subject = match patches.len() {
+        1 => "1 patch".to_string(),
+        count => format!("{count} patches"),
+    };
+    track(ProgressStep::Edit, subject, || {
+        apply_sorted_patches(contents, patches)
+    })
+}
+
+fn apply_sorted_patches(contents: &str, mut patches: Vec<Patch>) -> Result<String, PatchError> {
+    // stable sort so insertions at the same offset are applied in the order given
+    patches.sort_by_key(|patch| patch.span.start);
+
+    let mut previous: Option<&Patch> = None;
+    for patch in &patches {
+        let span = &patch.span;
+        if span.start > span.end || span.end > contents.len() {
+            Err(PatchError::OutOfBounds(span.clone(), contents.len()))?;
+        }
+        for offset in [span.start, span.end] {
+            if !contents.is_char_boundary(offset) {
+                Err(PatchError::NotCharBoundary(offset))?;
+            }
+        }
+        if let Some(previous) = previous {
+            if previous.span.end > span.start {
+                Err(PatchError::Overlapping(previous.span.clone(), span.clone()))?;
+            }
+        }
+        previous = Some(patch);
+    }
+
+    let mut patched = String::with_capacity(contents.len());
+    let mut offset = 0;
+    for patch in &patches {
+        patched.push_str(&contents[offset..patch.span.start]);
+        patched.push_str(&patch.replacement);
+        offset = patch.span.end;
+    }
+    patched.push_str(&contents[offset..]);
+
+    Ok(patched)
+}
+
+// a table found by scanning TOML text, with the spans of its single-line string values since
+// toml_edit doesn't expose the spans of parsed items, keys before the first table header are skipped,
+// the table's span runs from its header up to the next header
+#[derive(Debug)]
+pub struct ScannedTable {
+    pub name: String,
+    pub is_array: bool,
+    pub span: Range<usize>,
+    pub values: HashMap<String, Range<usize>>,
+}
+
+impl ScannedTable {
+    pub fn get_str(&self, contents: &str, key: &str) -> Option<String> {
+        self.values
+            .get(key)
+            .and_then(|span| Value::from_str(&contents[span.clone()]).ok())
+            .and_then(|value| value.as_str().map(String::from))
+    }
+
+    // the end of the table's last line that isn't blank or a comment, trailing comments usually
+    // describe the table that follows
+    pub fn content_end(&self, contents: &str) -> usize {
+        let mut end = self.span.start;
+        let mut offset = self.span.start;
+        for line in contents[self.span.clone()].split_inclusive('\n') {
+            let trimmed = line.trim();
+            if !trimmed.is_empty() && !trimmed.starts_with('#') {
+                end = offset + line.trim_end().len();
+            }
+            offset += line.len();
+        }
+        end
+    }
+}
+
+pub fn scan_toml_tables(contents: &str) -> Vec<ScannedTable> {
+    lazy_static! {
+        static ref TABLE_HEADER: Regex =
+            Regex::new(r"^\s*\[(\[?)\s*([^\]]+?)\s*\]").expect("Should be a valid regex");
+        static ref STRING_VALUE: Regex =
+            Regex::new(r#"^\s*([A-Za-z0-9_-]+)\s*=\s*("(?:[^"\\\n]|\\.)*"|'[^'\n]*')"#)
+                .expect("Should be a valid regex");
+    }
+
+    let mut tables = vec![];
+    let mut current: Option<ScannedTable> = None;
+    let mut open_value = OpenValue::default();
+    let mut offset = 0;
+
+    for line in contents.split_inclusive('\n') {
+        if open_value.is_open() {
+            open_value.scan(line);
+        } else if let Some(captures) = TABLE_HEADER.captures(line) {
+            tables.extend(current.take().map(|mut table| {
+                table.span.end = offset;
+                table
+            }));
+            current = Some(ScannedTable {
+                name: captures[2].to_string(),
+                is_array: &captures[1] == "[",
+                span: offset..contents.len(),
+                values: HashMap::new(),
+            });
+        } else {
+            if let (Some(table), Some(captures)) = (&mut current, STRING_VALUE.captures(line)) {
+                let raw_value = captures.get(2).expect("Value group should be present");
+                table.values.insert(
+                    captures[1].to_string(),
+                    offset + raw_value.start()..offset + raw_value.end(),
+                );
+            }
+            open_value.scan(line);
+        }
+        offset += line.len();
+    }
+    tables.extend(current);
+
+    tables
+}
+
+// a value that continues past the end of its line, a multi-line array or string, whose lines can
+// look like table headers or keys (e.g.; `[foo]` in an array of arrays) but belong to the value
+#[derive(Default)]
+struct OpenValue {
+    brackets: usize,
+    multiline_string: Option<&'static str>,
+}
+
+impl OpenValue {
+    fn is_open(&self) -> bool {
+        self.brackets > 0 || self.multiline_string.is_some()
+    }
+
+    // only ascii delimiters are compared so walking the bytes never splits a character that matters,
+    // brackets only count once a `=` shows the line holds a value rather than a table header
+    fn scan(&mut self, line: &str) {
+        let bytes = line.as_bytes();
+        let mut in_value = self.is_open();
+        let mut index = 0;
+        while index < bytes.len() {
+            let rest = &bytes[index..];
+            if let Some(delimiter) = self.multiline_string {
+                if rest.starts_with(delimiter.as_bytes()) {
+                    self.multiline_string = None;
+                    index += delimiter.len();
+                } else if delimiter == "\"\"\"" && rest[0] == b'\\' {
+                    index += 2;
+                } else {
+                    index += 1;
+                }
+                continue;
+            }
+
+            match rest[0] {
+                b'#' => return,
+                b'"' if rest.starts_with(b"\"\"\"") => {
+                    self.multiline_string = Some("\"\"\"");
+                    index += 3;
+                    continue;
+                }
+                b'\'' if rest.starts_with(b"'''") => {
+                    self.multiline_string = Some("'''");
+                    index += 3;
+                    continue;
+                }
+                b'"' => {
+                    index += 1;
+                    while index < bytes.len() && bytes[index] != b'"' {
+                        index += if bytes[index] == b'\\' { 2 } else { 1 };
+                    }
+                }
+                b'\'' => {
+                    index += 1;
+                    while index < bytes.len() && bytes[index] != b'\'' {
+                        index += 1;
+                    }
+                }
+                b'=' => in_value = true,
+                b'[' | b'{' if in_value => self.brackets += 1,
+                b']' | b'}' if in_value => self.brackets = self.brackets.saturating_sub(1),
+                _ => {}
+            }
+            index += 1;
+        }
+    }
+}
+
+// a new string value keeps the quote style of the one it replaces when it can be written that way
+pub fn quote_like(existing: &str, new_value: &str) -> String {
+    if existing.starts_with('\'') && !new_value.contains(['\'', '\n']) {
+        format!("'{new_value}'")
+    } else {
+        Value::from(new_value).to_string()
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum PatchError {
+    OutOfBounds(Range<usize>, usize),
+    NotCharBoundary(usize),
+    Overlapping(Range<usize>, Range<usize>),
+}
+
+impl Display for PatchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PatchError::OutOfBounds(span, length) => {
+                write!(
+                    f,
+                    "Patch span {}..{} is outside of the document (length {length})",
+                    span.start, span.end
+                )
+            }
+            PatchError::NotCharBoundary(offset) => {
+                write!(f, "Patch offset {offset} is not on a character boundary")
+            }
+            PatchError::Overlapping(first, second) => {
+                write!(
+                    f,
+                    "Patch spans {}..{} and {}..{} overlap",
+                    first.start, first.end, second.start, second.end
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::patch::{apply_patches, scan_toml_tables, Patch, PatchError};
+    use quickcheck::{quickcheck, TestResult};
+    use std::str::FromStr;
+    use toml_edit::Document;
+
+    #[test]
+    fn test_apply_patches() {
+        let contents = "version = \"1.0.0\"\nid = \"heroku/nodejs\"\n";
+        assert_eq!(
+            apply_patches(
+                contents,
+                vec![
+                    Patch::replace(31..37, "java"),
+                    Patch::replace(11..16, "1.10.0"),
+                    Patch::insert(contents.len(), "# end\n"),
+                    Patch::insert(0, "# start\n"),
+                ]
+            )
+            .unwrap(),
+            "# start\nversion = \"1.10.0\"\nid = \"heroku/java\"\n# end\n"
+        );
+    }
+
+    #[test]
+    fn test_apply_patches_keeps_order_of_insertions_at_the_same_offset() {
+        assert_eq!(
+            apply_patches(
+                "ac",
+                vec![
+                    Patch::insert(1, "b"),
+                    Patch::insert(1, "B"),
+                    Patch::replace(1..2, "C")
+                ]
+            )
+            .unwrap(),
+            "abBC"
+        );
+    }
+
+    #[test]
+    fn test_apply_patches_rejects_invalid_spans() {
+        assert_eq!(
+            apply_patches(
+                "abcdef",
+                vec![Patch::replace(1..4, "x"), Patch::replace(3..5, "y")]
+            ),
+            Err(PatchError::Overlapping(1..4, 3..5))
+        );
+        assert_eq!(
+            apply_patches("abc", vec![Patch::replace(2..4, "x")]),
+            Err(PatchError::OutOfBounds(2..4, 3))
+        );
+        assert_eq!(
+            apply_patches("é", vec![Patch::insert(1, "x")]),
+            Err(PatchError::NotCharBoundary(1))
+        );
+    }
+
+    #[test]
+    fn test_scan_toml_tables_skips_multi_line_values() {
+        let contents = r#"[buildpack]
+id = "heroku/nodejs"
+keywords = [
+  "node",
+  # [not-a-table]
+  "npm", # ]
+]
+matrix = [
+["one"],
+  [true], [
+    "[three]"
+  ]
+]
+description = '''
+[not-a-table]
+name = "not-a-key"
+'''
+summary = """
+\"""
+[[not-a-table]]
+"""
+name = "Node.js"
+
+[[targets]]
+os = "linux"
+"#;
+        assert!(Document::from_str(contents).is_ok());
+
+        let tables = scan_toml_tables(contents);
+        assert_eq!(
+            tables
+                .iter()
+                .map(|table| (table.name.as_str(), table.is_array))
+                .collect::<Vec<_>>(),
+            vec![("buildpack", false), ("targets", true)]
+        );
+        assert_eq!(
+            tables[0].get_str(contents, "name"),
+            Some("Node.js".to_string())
+        );
+        assert_eq!(tables[0].span.end, contents.find("[[targets]]").unwrap());
+        assert_eq!(tables[1].get_str(contents, "os"), Some("linux".to_string()));
+    }
+
+    quickcheck! {
+        fn prop_patches_match_sequential_application(contents: String, cuts: Vec<(usize, usize, String)>) -> TestResult {
+            let boundaries = contents
+                .char_indices()
+                .map(|(index, _)| index)
+                .chain([contents.len()])
+                .collect::<Vec<_>>();
+
+            // build non-overlapping patches from arbitrary input by walking forward through the document
+            let mut patches = vec![];
+            let mut position = 0;
+            for (skip, length, replacement) in cuts {
+                let start = (position + skip % 4).min(boundaries.len() - 1);
+                let end = (start + length % 4).min(boundaries.len() - 1);
+                patches.push(Patch::replace(boundaries[start]..boundaries[end], replacement));
+                position = end;
+            }
+
+            let mut expected = contents.clone();
+            for patch in patches.iter().rev() {
+                expected.replace_range(patch.span.clone(), &patch.replacement);
+            }
+
+            TestResult::from_bool(apply_patches(&contents, patches) == Ok(expected))
+        }
+
+        fn prop_patched_toml_values_reparse(values: Vec<String>) -> TestResult {
+            if values.is_empty() {
+                return TestResult::discard();
+            }
+
+            let mut contents = String::new();
+            let mut patches = vec![];
+            for (index, value) in values.iter().enumerate() {
+                let prefix = format!("key{index} = ");
+                let start = contents.len() + prefix.len();
+                contents.push_str(&format!("{prefix}\"value\" # comment\n"));
+                patches.push(Patch::replace(
+                    start..start + "\"value\"".len(),
+                    toml_edit::Value::from(value.as_str()).to_string(),
+                ));
+            }
+            let patched = Document::from_str(&apply_patches(&contents, patches).unwrap()).unwrap();
+            TestResult::from_bool(values.iter().enumerate().all(|(index, value)| {
+                patched[&format!("key{index}")].as_str() == Some(value.as_str())
+            }))
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use clap::ValueEnum;
+use serde_json::{json, Value};
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+static JSONL_EVENTS: AtomicBool = AtomicBool::new(false);
+
+#[derive(ValueEnum, Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum ProgressFormat {
+    #[default]
+    Text,
+    Jsonl,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ProgressStep {
+    Discovery,
+    Parse,
+    Edit,
+    Write,
+    Output,
+}
+
+impl ProgressStep {
+    fn name(self) -> &'static str {
+        match self {
+            ProgressStep::Discovery => "discovery",
+            ProgressStep::Parse => "parse",
+            ProgressStep::Edit => "edit",
+            ProgressStep::Write => "write",
+            ProgressStep::Output => "output",
+        }
+    }
+}
+
+// with `jsonl`, every tracked step writes one JSON object to stderr next to the regular log lines so
+// an action wrapping a command can show its progress and timings
+pub fn set_progress_format(format: ProgressFormat) {
+    JSONL_EVENTS.store(format == ProgressFormat::Jsonl, Ordering::Relaxed);
+}
+
+// runs a step and, when events are enabled, reports it once it finishes along with what it worked on
+// (e.g.; a path or an output name) and whether it succeeded
+pub fn track<T, E>(
+    step: ProgressStep,
+    subject: impl Display,
+    run: impl FnOnce() -> Result<T, E>,
+) -> Result<T, E> {
+    if !JSONL_EVENTS.load(Ordering::Relaxed) {
+        return run();
+    }
+
+    let started = Instant::now();
+    let result = run();
+    eprintln!(
+        "{}",
+        progress_event(
+            step,
+            &subject.to_string(),
+            result.is_ok(),
+            started.elapsed()
+        )
+    );
+    result
+}
+
+fn progress_event(step: ProgressStep, subject: &str, succeeded: bool, elapsed: Duration) -> Value {
+    json!({
+        "step": step.name(),
+        "subject": subject,
+        "status": if succeeded { "ok" } else { "error" },
+        "elapsed_ms": u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::progress::{progress_event, ProgressStep};
+    use std::time::Duration;
+
+    #[test]
+    fn test_progress_event() {
+        assert_eq!(
+            progress_event(
+                ProgressStep::Parse,
+                "buildpacks/nodejs/buildpack.toml",
+                true,
+                Duration::from_micros(12_500)
+            )
+            .to_string(),
+            r#"{"elapsed_ms":12,"status":"ok","step":"parse","subject":"buildpacks/nodejs/buildpack.toml"}"#
+        );
+        assert_eq!(
+            progress_event(ProgressStep::Write, "CHANGELOG.md", false, Duration::ZERO).to_string(),
+            r#"{"elapsed_ms":0,"status":"error","step":"write","subject":"CHANGELOG.md"}"#
+        );
+    }
+}
@@ -0,0 +1,401 @@
+use crate::cancellation::remaining_time;
+use crate::log;
+use crate::oci::RegistryCredentials;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+// `anonymous` skips every credential lookup, anything else (or unset) looks credentials up
+pub const REGISTRY_AUTH_ENV: &str = "ACTIONS_REGISTRY_AUTH";
+
+// the same variable docker reads to find the directory holding `config.json`
+pub const DOCKER_CONFIG_ENV: &str = "DOCKER_CONFIG";
+
+const DOCKER_HUB_REGISTRY: &str = "docker.io";
+const DOCKER_HUB_SERVER_URL: &str = "https://index.docker.io/v1/";
+
+// ACR refresh tokens (`identitytoken` after `az acr login`) are exchanged with this username
+const IDENTITY_TOKEN_USERNAME: &str = "00000000-0000-0000-0000-000000000000";
+
+const PROCESS_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+lazy_static! {
+    // credentials are looked up once per registry since a helper runs a process each time
+    static ref CREDENTIALS: Mutex<HashMap<String, Option<RegistryCredentials>>> =
+        Mutex::new(HashMap::new());
+}
+
+// where the credentials for a registry come from, in the order docker checks them
+#[derive(Debug, Eq, PartialEq)]
+enum CredentialSource {
+    Stored(RegistryCredentials),
+    Helper(String),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct HelperCredentials {
+    username: String,
+    secret: String,
+}
+
+// looks up credentials from docker's `config.json` (stored logins and credential helpers like
+// `gcloud` or `ecr-login`) and, for ECR registries without one, exchanges the AWS credentials in the
+// environment for a registry token, registries without credentials are accessed anonymously
+pub fn find_registry_credentials(registry: &str) -> Option<RegistryCredentials> {
+    if std::env::var(REGISTRY_AUTH_ENV).map_or(false, |value| value == "anonymous") {
+        return None;
+    }
+
+    let cached = CREDENTIALS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .get(registry)
+        .cloned();
+    if let Some(credentials) = cached {
+        return credentials;
+    }
+
+    // the lock isn't held during the lookup so registries are looked up concurrently, a registry
+    // looked up by two requests at once runs its helper twice and the first result is kept
+    let credentials = lookup_registry_credentials(registry);
+    CREDENTIALS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .entry(registry.to_string())
+        .or_insert(credentials)
+        .clone()
+}
+
+fn lookup_registry_credentials(registry: &str) -> Option<RegistryCredentials> {
+    let credentials = read_docker_config()
+        .and_then(|config| find_credential_source(&config, registry))
+        .and_then(|source| match source {
+            CredentialSource::Stored(credentials) => Some(credentials),
+            CredentialSource::Helper(helper) => run_credential_helper(&helper, registry),
+        });
+    credentials.or_else(|| get_ecr_region(registry).and_then(request_ecr_credentials))
+}
+
+fn read_docker_config() -> Option<Value> {
+    let dir = std::env::var_os(DOCKER_CONFIG_ENV)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".docker")))?;
+    let path = dir.join("config.json");
+    let contents = std::fs::read_to_string(&path).ok()?;
+    match serde_json::from_str(&contents) {
+        Ok(config) => Some(config),
+        Err(error) => {
+            log!(
+                "⚠️ Could not parse docker config, continuing without its credentials\nPath: {}\nError: {error}",
+                path.display()
+            );
+            None
+        }
+    }
+}
+
+// `credHelpers` for the registry wins over a stored login which wins over the default `credsStore`,
+// stored logins are keyed by host but older configs use URLs (e.g.; `https://index.docker.io/v1/`)
+fn find_credential_source(config: &Value, registry: &str) -> Option<CredentialSource> {
+    if let Some(helper) = config["credHelpers"][registry].as_str() {
+        return Some(CredentialSource::Helper(helper.to_string()));
+    }
+
+    let stored = config["auths"].as_object().and_then(|auths| {
+        auths
+            .iter()
+            .find(|(server, _)| normalize_registry(server) == registry)
+            .and_then(|(_, entry)| parse_stored_credentials(entry))
+    });
+    if let Some(credentials) = stored {
+        return Some(CredentialSource::Stored(credentials));
+    }
+
+    config["credsStore"]
+        .as_str()
+        .filter(|helper| !helper.is_empty())
+        .map(|helper| CredentialSource::Helper(helper.to_string()))
+}
+
+fn parse_stored_credentials(entry: &Value) -> Option<RegistryCredentials> {
+    if let Some(identity_token) = entry["identitytoken"].as_str() {
+        return Some(RegistryCredentials {
+            username: entry["username"]
+                .as_str()
+                .unwrap_or(IDENTITY_TOKEN_USERNAME)
+                .to_string(),
+            password: identity_token.to_string(),
+        });
+    }
+
+    if let Some(auth) = entry["auth"].as_str().filter(|auth| !auth.is_empty()) {
+        let decoded = String::from_utf8(STANDARD.decode(auth).ok()?).ok()?;
+        let (username, password) = decoded.split_once(':')?;
+        return Some(RegistryCredentials {
+            username: username.to_string(),
+            password: password.to_string(),
+        });
+    }
+
+    match (entry["username"].as_str(), entry["password"].as_str()) {
+        (Some(username), Some(password)) => Some(RegistryCredentials {
+            username: username.to_string(),
+            password: password.to_string(),
+        }),
+        _ => None,
+    }
+}
+
+fn normalize_registry(server: &str) -> &str {
+    let host = server
+        .strip_prefix("https://")
+        .or_else(|| server.strip_prefix("http://"))
+        .unwrap_or(server);
+    let host = host.split_once('/').map_or(host, |(host, _)| host);
+    match host {
+        "index.docker.io" | "registry-1.docker.io" => DOCKER_HUB_REGISTRY,
+        host => host,
+    }
+}
+
+// helpers follow docker's protocol, the server is written to `docker-credential-<name> get` and the
+// credentials are read back as JSON, a helper that has nothing for the server exits with an error
+fn run_credential_helper(helper: &str, registry: &str) -> Option<RegistryCredentials> {
+    let program = format!("docker-credential-{helper}");
+    let server = if registry == DOCKER_HUB_REGISTRY {
+        DOCKER_HUB_SERVER_URL
+    } else {
+        registry
+    };
+
+    let mut command = Command::new(&program);
+    command.arg("get");
+    let output = run_with_timeout(command, server, remaining_time());
+
+    match output {
+        Ok(output) if output.status.success() => {
+            match serde_json::from_slice::<HelperCredentials>(&output.stdout) {
+                Ok(credentials) => Some(RegistryCredentials {
+                    username: credentials.username,
+                    password: credentials.secret,
+                }),
+                Err(error) => {
+                    log!("⚠️ {program} returned invalid credentials for {registry}, continuing anonymously\nError: {error}");
+                    None
+                }
+            }
+        }
+        Ok(_) => None,
+        Err(error) => {
+            log!(
+                "⚠️ Could not run {program} for {registry}, continuing anonymously\nError: {error}"
+            );
+            None
+        }
+    }
+}
+
+fn get_ecr_region(registry: &str) -> Option<&str> {
+    lazy_static! {
+        static ref ECR_REGISTRY: Regex =
+            Regex::new(r"^\d{12}\.dkr\.ecr(?:-fips)?\.([a-z0-9-]+)\.amazonaws\.com(?:\.cn)?$")
+                .expect("Should be a valid regex");
+    }
+
+    ECR_REGISTRY
+        .captures(registry)
+        .and_then(|captures| captures.get(1))
+        .map(|region| region.as_str())
+}
+
+// ECR only accepts short-lived tokens so the AWS credentials configured for the job (e.g.; by
+// `aws-actions/configure-aws-credentials`) are exchanged for one with the AWS CLI
+fn request_ecr_credentials(region: &str) -> Option<RegistryCredentials> {
+    let mut command = Command::new("aws");
+    command.args(["ecr", "get-login-password", "--region", region]);
+    let output = run_with_timeout(command, "", remaining_time());
+
+    match output {
+        Ok(output) if output.status.success() => Some(RegistryCredentials {
+            username: "AWS".to_string(),
+            password: String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        }),
+        Ok(output) => {
+            log!(
+                "⚠️ Could not get an ECR token for {region}, continuing anonymously\nError: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            None
+        }
+        Err(error) => {
+            log!(
+                "⚠️ Could not run the AWS CLI for an ECR token, continuing anonymously\nError: {error}"
+            );
+            None
+        }
+    }
+}
+
+// with `--timeout` set, a process is given the time left and killed once it runs out, so a helper
+// that hangs (e.g.; waiting on a keychain prompt) can't keep the command running past the deadline
+fn run_with_timeout(
+    mut command: Command,
+    input: &str,
+    timeout: Option<Duration>,
+) -> io::Result<Output> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes())?;
+    }
+
+    let deadline = match timeout {
+        Some(timeout) => Instant::now() + timeout,
+        None => return child.wait_with_output(),
+    };
+
+    // the output is read on other threads so a process that fills a pipe doesn't block while it's
+    // waited on
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "Timed out before the process finished",
+            ));
+        }
+        std::thread::sleep(PROCESS_POLL_INTERVAL);
+    };
+
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+fn read_in_background(pipe: Option<impl Read + Send + 'static>) -> JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut contents = vec![];
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut contents);
+        }
+        contents
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::oci::RegistryCredentials;
+    use crate::registry_auth::{
+        find_credential_source, get_ecr_region, run_with_timeout, CredentialSource,
+    };
+    use serde_json::json;
+    use std::process::Command;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_find_credential_source() {
+        let config = json!({
+            "auths": {
+                "https://index.docker.io/v1/": { "auth": "aGVyb2t1OnNlY3JldA==" },
+                "heroku.azurecr.io": { "identitytoken": "refresh-token" },
+                "registry.example.com": { "username": "heroku", "password": "secret" },
+                "quay.io": {}
+            },
+            "credHelpers": { "us-docker.pkg.dev": "gcloud" },
+            "credsStore": "desktop"
+        });
+
+        assert_eq!(
+            find_credential_source(&config, "docker.io"),
+            Some(CredentialSource::Stored(RegistryCredentials {
+                username: "heroku".to_string(),
+                password: "secret".to_string(),
+            }))
+        );
+        assert_eq!(
+            find_credential_source(&config, "heroku.azurecr.io"),
+            Some(CredentialSource::Stored(RegistryCredentials {
+                username: "00000000-0000-0000-0000-000000000000".to_string(),
+                password: "refresh-token".to_string(),
+            }))
+        );
+        assert_eq!(
+            find_credential_source(&config, "registry.example.com"),
+            Some(CredentialSource::Stored(RegistryCredentials {
+                username: "heroku".to_string(),
+                password: "secret".to_string(),
+            }))
+        );
+        assert_eq!(
+            find_credential_source(&config, "us-docker.pkg.dev"),
+            Some(CredentialSource::Helper("gcloud".to_string()))
+        );
+        assert_eq!(
+            find_credential_source(&config, "quay.io"),
+            Some(CredentialSource::Helper("desktop".to_string()))
+        );
+        assert_eq!(find_credential_source(&json!({}), "docker.io"), None);
+    }
+
+    #[test]
+    fn test_get_ecr_region() {
+        assert_eq!(
+            get_ecr_region("123456789012.dkr.ecr.us-east-1.amazonaws.com"),
+            Some("us-east-1")
+        );
+        assert_eq!(
+            get_ecr_region("123456789012.dkr.ecr-fips.us-gov-west-1.amazonaws.com"),
+            Some("us-gov-west-1")
+        );
+        assert_eq!(
+            get_ecr_region("123456789012.dkr.ecr.cn-north-1.amazonaws.com.cn"),
+            Some("cn-north-1")
+        );
+        assert_eq!(get_ecr_region("public.ecr.aws"), None);
+        assert_eq!(get_ecr_region("docker.io"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_with_timeout() {
+        let output = run_with_timeout(
+            Command::new("cat"),
+            "registry.example.com",
+            Some(Duration::from_secs(10)),
+        )
+        .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"registry.example.com");
+
+        let mut command = Command::new("sleep");
+        command.arg("10");
+        let started = Instant::now();
+        let error = run_with_timeout(command, "", Some(Duration::from_millis(100))).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::TimedOut);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,120 @@
+use libcnb_data::buildpack::BuildpackId;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+type Result<T> = std::result::Result<T, ReleaseManifestError>;
+
+// the buildpacks bumped by a release, printed as a single line by `prepare-release --print-manifest`
+// so it can be piped straight to `update-builder --manifest -`
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ReleaseManifest {
+    pub from_version: String,
+    pub to_version: String,
+    pub buildpacks: Vec<ReleasedBuildpack>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ReleasedBuildpack {
+    pub id: BuildpackId,
+    pub version: String,
+}
+
+impl ReleaseManifest {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Release manifest should serialize to JSON")
+    }
+}
+
+// `-` reads the manifest from stdin
+pub fn read_release_manifest(path: &Path) -> Result<ReleaseManifest> {
+    let contents = if path == Path::new("-") {
+        let mut contents = String::new();
+        io::stdin()
+            .read_to_string(&mut contents)
+            .map_err(|e| ReleaseManifestError::Reading(path.to_path_buf(), e))?;
+        contents
+    } else {
+        std::fs::read_to_string(path)
+            .map_err(|e| ReleaseManifestError::Reading(path.to_path_buf(), e))?
+    };
+    parse_release_manifest(&contents)
+        .map_err(|e| ReleaseManifestError::Parsing(path.to_path_buf(), e))
+}
+
+// the manifest shares stdout with workflow commands and, when `GITHUB_OUTPUT` isn't set, the printed
+// outputs so only the last line holding a JSON object is read
+pub fn parse_release_manifest(contents: &str) -> std::result::Result<ReleaseManifest, String> {
+    match contents
+        .lines()
+        .rev()
+        .map(str::trim)
+        .find(|line| line.starts_with('{'))
+    {
+        Some(line) => serde_json::from_str(line).map_err(|e| e.to_string()),
+        None => Err("No JSON object found".to_string()),
+    }
+}
+
+#[derive(Debug)]
+pub enum ReleaseManifestError {
+    Reading(PathBuf, io::Error),
+    Parsing(PathBuf, String),
+}
+
+impl Display for ReleaseManifestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReleaseManifestError::Reading(path, error) => {
+                write!(
+                    f,
+                    "Could not read release manifest\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            ReleaseManifestError::Parsing(path, error) => {
+                write!(
+                    f,
+                    "Could not parse release manifest\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::release_manifest::{parse_release_manifest, ReleaseManifest, ReleasedBuildpack};
+    use libcnb_data::buildpack_id;
+
+    #[test]
+    fn test_parse_release_manifest() {
+        let manifest = ReleaseManifest {
+            from_version: "1.0.0".to_string(),
+            to_version: "1.1.0".to_string(),
+            buildpacks: vec![ReleasedBuildpack {
+                id: buildpack_id!("heroku/nodejs"),
+                version: "1.1.0".to_string(),
+            }],
+        };
+
+        let contents = format!(
+            "::notice title=heroku/nodejs::1.0.0 → 1.1.0\n{}\nchanged=\"true\"\n",
+            manifest.to_json()
+        );
+        assert_eq!(parse_release_manifest(&contents).unwrap(), manifest);
+
+        assert_eq!(
+            parse_release_manifest("changed=\"true\"\n").unwrap_err(),
+            "No JSON object found"
+        );
+        assert!(parse_release_manifest(
+            r#"{"from_version":"1.0.0","to_version":"1.1.0","buildpacks":[{"id":"Not Valid","version":"1.1.0"}]}"#
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,337 @@
+use chrono::NaiveDate;
+use libcnb_data::buildpack::BuildpackId;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::str::FromStr;
+use toml_edit::{table, value, Document, Item, TomlError};
+
+type Result<T> = std::result::Result<T, ReleaseStateError>;
+
+pub const RELEASE_STATE_FILE: &str = ".release-state.toml";
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+// the last release of each buildpack, kept under `[buildpacks."<buildpack_id>"]` so the file can be
+// reviewed and edited by hand, comments and formatting survive updates
+#[derive(Debug, Clone)]
+pub struct ReleaseStateFile {
+    pub path: PathBuf,
+    pub document: Document,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ReleaseRecord {
+    pub version: String,
+    pub date: NaiveDate,
+    pub sha: Option<String>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ReleaseStateDrift {
+    VersionMismatch {
+        buildpack_id: String,
+        recorded: String,
+        actual: String,
+    },
+    NotRecorded(String),
+    UnknownBuildpack(String),
+}
+
+impl ReleaseStateFile {
+    pub fn new(path: impl Into<PathBuf>) -> ReleaseStateFile {
+        ReleaseStateFile {
+            path: path.into(),
+            document: Document::new(),
+        }
+    }
+}
+
+// the state file is optional so a missing file is `None` rather than an error
+pub fn read_release_state_file(path: impl Into<PathBuf>) -> Result<Option<ReleaseStateFile>> {
+    let path = path.into();
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(error) => Err(ReleaseStateError::Reading(path.clone(), error))?,
+    };
+    let document =
+        Document::from_str(&contents).map_err(|e| ReleaseStateError::Parsing(path.clone(), e))?;
+    Ok(Some(ReleaseStateFile { path, document }))
+}
+
+pub fn get_release_records(
+    release_state_file: &ReleaseStateFile,
+) -> Result<BTreeMap<String, ReleaseRecord>> {
+    let invalid = |message: String| {
+        ReleaseStateError::InvalidRecord(release_state_file.path.clone(), message)
+    };
+
+    let buildpacks = match release_state_file.document.get("buildpacks") {
+        Some(item) => item
+            .as_table_like()
+            .ok_or_else(|| invalid("`buildpacks` must be a table".to_string()))?,
+        None => return Ok(BTreeMap::new()),
+    };
+
+    buildpacks
+        .iter()
+        .map(|(buildpack_id, item)| {
+            let record = item.as_table_like().ok_or_else(|| {
+                invalid(format!("`buildpacks.\"{buildpack_id}\"` must be a table"))
+            })?;
+            let get_string = |key: &str| record.get(key).and_then(Item::as_str);
+
+            let version = get_string("version").ok_or_else(|| {
+                invalid(format!(
+                    "`buildpacks.\"{buildpack_id}\".version` must be a string"
+                ))
+            })?;
+            let date = get_string("date")
+                .and_then(|date| NaiveDate::parse_from_str(date, DATE_FORMAT).ok())
+                .ok_or_else(|| {
+                    invalid(format!(
+                        "`buildpacks.\"{buildpack_id}\".date` must be a date (yyyy-mm-dd)"
+                    ))
+                })?;
+
+            Ok((
+                buildpack_id.to_string(),
+                ReleaseRecord {
+                    version: version.to_string(),
+                    date,
+                    sha: get_string("sha").map(String::from),
+                },
+            ))
+        })
+        .collect()
+}
+
+pub fn set_release_record(
+    release_state_file: &mut ReleaseStateFile,
+    buildpack_id: &BuildpackId,
+    record: &ReleaseRecord,
+) {
+    let buildpacks = release_state_file
+        .document
+        .entry("buildpacks")
+        .or_insert_with(|| {
+            let mut buildpacks = table();
+            if let Some(buildpacks) = buildpacks.as_table_mut() {
+                buildpacks.set_implicit(true);
+            }
+            buildpacks
+        });
+
+    let entry = &mut buildpacks[buildpack_id.as_str()];
+    if !entry.is_table_like() {
+        *entry = table();
+    }
+    entry["version"] = value(&record.version);
+    entry["date"] = value(record.date.format(DATE_FORMAT).to_string());
+    match &record.sha {
+        Some(sha) => entry["sha"] = value(sha),
+        None => {
+            if let Some(entry) = entry.as_table_like_mut() {
+                entry.remove("sha");
+            }
+        }
+    }
+}
+
+pub fn remove_release_record(release_state_file: &mut ReleaseStateFile, buildpack_id: &str) {
+    if let Some(buildpacks) = release_state_file
+        .document
+        .get_mut("buildpacks")
+        .and_then(Item::as_table_like_mut)
+    {
+        buildpacks.remove(buildpack_id);
+    }
+}
+
+// compares the recorded versions with the versions in each buildpack's `buildpack.toml`, drift means
+// a release was prepared without updating the state or the state was edited by hand
+pub fn find_release_state_drift(
+    records: &BTreeMap<String, ReleaseRecord>,
+    buildpack_versions: &BTreeMap<String, String>,
+) -> Vec<ReleaseStateDrift> {
+    let mut drift = buildpack_versions
+        .iter()
+        .filter_map(|(buildpack_id, actual)| match records.get(buildpack_id) {
+            Some(record) if &record.version != actual => Some(ReleaseStateDrift::VersionMismatch {
+                buildpack_id: buildpack_id.clone(),
+                recorded: record.version.clone(),
+                actual: actual.clone(),
+            }),
+            Some(_) => None,
+            None => Some(ReleaseStateDrift::NotRecorded(buildpack_id.clone())),
+        })
+        .collect::<Vec<_>>();
+
+    drift.extend(
+        records
+            .keys()
+            .filter(|buildpack_id| !buildpack_versions.contains_key(*buildpack_id))
+            .map(|buildpack_id| ReleaseStateDrift::UnknownBuildpack(buildpack_id.clone())),
+    );
+
+    drift
+}
+
+impl Display for ReleaseStateDrift {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReleaseStateDrift::VersionMismatch {
+                buildpack_id,
+                recorded,
+                actual,
+            } => write!(
+                f,
+                "{buildpack_id} is recorded at {recorded} but buildpack.toml has {actual}"
+            ),
+            ReleaseStateDrift::NotRecorded(buildpack_id) => {
+                write!(f, "{buildpack_id} has no recorded release")
+            }
+            ReleaseStateDrift::UnknownBuildpack(buildpack_id) => {
+                write!(
+                    f,
+                    "{buildpack_id} is recorded but was not found in the project"
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ReleaseStateError {
+    Reading(PathBuf, io::Error),
+    Parsing(PathBuf, TomlError),
+    InvalidRecord(PathBuf, String),
+}
+
+impl Display for ReleaseStateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReleaseStateError::Reading(path, error) => {
+                write!(
+                    f,
+                    "Could not read release state\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            ReleaseStateError::Parsing(path, error) => {
+                write!(
+                    f,
+                    "Could not parse release state\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            ReleaseStateError::InvalidRecord(path, message) => {
+                write!(
+                    f,
+                    "Invalid release state\nPath: {}\nError: {message}",
+                    path.display()
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::release_state::{
+        find_release_state_drift, get_release_records, set_release_record, ReleaseRecord,
+        ReleaseStateDrift, ReleaseStateFile,
+    };
+    use chrono::NaiveDate;
+    use libcnb_data::buildpack_id;
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+    use toml_edit::Document;
+
+    #[test]
+    fn test_set_release_record() {
+        let mut release_state_file = ReleaseStateFile::new(".release-state.toml");
+        let record = ReleaseRecord {
+            version: "1.0.0".to_string(),
+            date: NaiveDate::from_ymd_opt(2023, 5, 10).unwrap(),
+            sha: Some("1b2c3d".to_string()),
+        };
+        set_release_record(
+            &mut release_state_file,
+            &buildpack_id!("heroku/nodejs"),
+            &record,
+        );
+        set_release_record(
+            &mut release_state_file,
+            &buildpack_id!("heroku/procfile"),
+            &ReleaseRecord {
+                sha: None,
+                ..record.clone()
+            },
+        );
+        assert_eq!(
+            release_state_file.document.to_string(),
+            r#"[buildpacks."heroku/nodejs"]
+version = "1.0.0"
+date = "2023-05-10"
+sha = "1b2c3d"
+
+[buildpacks."heroku/procfile"]
+version = "1.0.0"
+date = "2023-05-10"
+"#
+        );
+        assert_eq!(
+            get_release_records(&release_state_file)
+                .unwrap()
+                .get("heroku/nodejs"),
+            Some(&record)
+        );
+
+        let release_state_file = ReleaseStateFile {
+            path: PathBuf::from(".release-state.toml"),
+            document: Document::from_str(
+                "[buildpacks.\"heroku/nodejs\"]\nversion = \"1.0.0\"\ndate = \"May 10\"\n",
+            )
+            .unwrap(),
+        };
+        assert!(get_release_records(&release_state_file).is_err());
+    }
+
+    #[test]
+    fn test_find_release_state_drift() {
+        let record = |version: &str| ReleaseRecord {
+            version: version.to_string(),
+            date: NaiveDate::from_ymd_opt(2023, 5, 10).unwrap(),
+            sha: None,
+        };
+        let records = BTreeMap::from([
+            ("heroku/nodejs".to_string(), record("1.0.0")),
+            ("heroku/npm".to_string(), record("1.0.0")),
+            ("heroku/yarn".to_string(), record("1.0.0")),
+        ]);
+        let buildpack_versions = BTreeMap::from([
+            ("heroku/nodejs".to_string(), "1.0.0".to_string()),
+            ("heroku/npm".to_string(), "1.1.0".to_string()),
+            ("heroku/procfile".to_string(), "1.0.0".to_string()),
+        ]);
+        assert_eq!(
+            find_release_state_drift(&records, &buildpack_versions),
+            vec![
+                ReleaseStateDrift::VersionMismatch {
+                    buildpack_id: "heroku/npm".to_string(),
+                    recorded: "1.0.0".to_string(),
+                    actual: "1.1.0".to_string()
+                },
+                ReleaseStateDrift::NotRecorded("heroku/procfile".to_string()),
+                ReleaseStateDrift::UnknownBuildpack("heroku/yarn".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,250 @@
+use libcnb_data::buildpack::BuildpackId;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
+
+type Result<T> = std::result::Result<T, SbomError>;
+
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct Sbom {
+    pub components: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct CycloneDxDocument {
+    #[serde(default)]
+    components: Vec<CycloneDxComponent>,
+}
+
+#[derive(Deserialize)]
+struct CycloneDxComponent {
+    name: String,
+    group: Option<String>,
+    version: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SpdxDocument {
+    #[serde(default)]
+    packages: Vec<SpdxPackage>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SpdxPackage {
+    name: String,
+    version_info: Option<String>,
+}
+
+pub fn find_buildpack_sbom(dir: &Path, buildpack_id: &BuildpackId) -> Option<PathBuf> {
+    let name = buildpack_id.replace('/', "_");
+    ["cdx.json", "spdx.json"]
+        .iter()
+        .map(|extension| dir.join(format!("{name}.{extension}")))
+        .find(|path| path.exists())
+}
+
+pub fn read_sbom(path: &Path) -> Result<Sbom> {
+    let contents =
+        std::fs::read_to_string(path).map_err(|e| SbomError::Reading(path.to_path_buf(), e))?;
+    parse_sbom(&contents).map_err(|e| SbomError::Parsing(path.to_path_buf(), e))
+}
+
+fn parse_sbom(contents: &str) -> std::result::Result<Sbom, serde_json::Error> {
+    let value: serde_json::Value = serde_json::from_str(contents)?;
+
+    if value.get("spdxVersion").is_some() {
+        let document: SpdxDocument = serde_json::from_value(value)?;
+        let components = document
+            .packages
+            .into_iter()
+            .map(|package| (package.name, package.version_info.unwrap_or_default()))
+            .collect();
+        return Ok(Sbom { components });
+    }
+
+    let document: CycloneDxDocument = serde_json::from_value(value)?;
+    let components = document
+        .components
+        .into_iter()
+        .map(|component| {
+            let name = match component.group {
+                Some(group) if !group.is_empty() => format!("{group}/{}", component.name),
+                _ => component.name,
+            };
+            (name, component.version.unwrap_or_default())
+        })
+        .collect();
+    Ok(Sbom { components })
+}
+
+#[derive(Serialize, Debug, Default, Eq, PartialEq)]
+pub struct SbomDiff {
+    pub added: Vec<ComponentVersion>,
+    pub removed: Vec<ComponentVersion>,
+    pub changed: Vec<ComponentChange>,
+}
+
+#[derive(Serialize, Debug, Eq, PartialEq)]
+pub struct ComponentVersion {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Serialize, Debug, Eq, PartialEq)]
+pub struct ComponentChange {
+    pub name: String,
+    pub from: String,
+    pub to: String,
+}
+
+impl SbomDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+pub fn diff_sboms(previous: &Sbom, current: &Sbom) -> SbomDiff {
+    let mut diff = SbomDiff::default();
+
+    for (name, version) in &current.components {
+        match previous.components.get(name) {
+            None => diff.added.push(ComponentVersion {
+                name: name.clone(),
+                version: version.clone(),
+            }),
+            Some(previous_version) if previous_version != version => {
+                diff.changed.push(ComponentChange {
+                    name: name.clone(),
+                    from: previous_version.clone(),
+                    to: version.clone(),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+
+    for (name, version) in &previous.components {
+        if !current.components.contains_key(name) {
+            diff.removed.push(ComponentVersion {
+                name: name.clone(),
+                version: version.clone(),
+            });
+        }
+    }
+
+    diff
+}
+
+pub fn format_sbom_diff(diff: &SbomDiff) -> String {
+    diff.added
+        .iter()
+        .map(|added| format!("- Added `{}` {}", added.name, added.version))
+        .chain(diff.changed.iter().map(|changed| {
+            format!(
+                "- Updated `{}` from {} to {}",
+                changed.name, changed.from, changed.to
+            )
+        }))
+        .chain(
+            diff.removed
+                .iter()
+                .map(|removed| format!("- Removed `{}` {}", removed.name, removed.version)),
+        )
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn format_dependencies_section(diff: &SbomDiff) -> String {
+    format!("### Dependencies\n\n{}", format_sbom_diff(diff))
+}
+
+#[derive(Debug)]
+pub enum SbomError {
+    Reading(PathBuf, io::Error),
+    Parsing(PathBuf, serde_json::Error),
+}
+
+impl Display for SbomError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SbomError::Reading(path, error) => {
+                write!(
+                    f,
+                    "Could not read SBOM\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            SbomError::Parsing(path, error) => {
+                write!(
+                    f,
+                    "Could not parse SBOM as CycloneDX or SPDX JSON\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sbom::{diff_sboms, format_dependencies_section, parse_sbom};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_diff_sboms() {
+        let previous = parse_sbom(
+            r#"{
+  "bomFormat": "CycloneDX",
+  "components": [
+    { "type": "application", "name": "node", "version": "18.16.0" },
+    { "type": "library", "group": "org.example", "name": "util", "version": "1.0.0" },
+    { "type": "application", "name": "yarn", "version": "1.22.19" }
+  ]
+}"#,
+        )
+        .unwrap();
+        let current = parse_sbom(
+            r#"{
+  "bomFormat": "CycloneDX",
+  "components": [
+    { "type": "application", "name": "node", "version": "18.17.0" },
+    { "type": "library", "group": "org.example", "name": "util", "version": "1.0.0" },
+    { "type": "application", "name": "corepack", "version": "0.19.0" }
+  ]
+}"#,
+        )
+        .unwrap();
+
+        let diff = diff_sboms(&previous, &current);
+        assert_eq!(
+            format_dependencies_section(&diff),
+            "### Dependencies\n\n- Added `corepack` 0.19.0\n- Updated `node` from 18.16.0 to 18.17.0\n- Removed `yarn` 1.22.19"
+        );
+        assert!(diff_sboms(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn test_parse_spdx_sbom() {
+        let sbom = parse_sbom(
+            r#"{
+  "spdxVersion": "SPDX-2.3",
+  "packages": [
+    { "SPDXID": "SPDXRef-node", "name": "node", "versionInfo": "18.17.0" },
+    { "SPDXID": "SPDXRef-yarn", "name": "yarn" }
+  ]
+}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            sbom.components,
+            BTreeMap::from([
+                ("node".to_string(), "18.17.0".to_string()),
+                ("yarn".to_string(), String::new())
+            ])
+        );
+    }
+}
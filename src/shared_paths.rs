@@ -0,0 +1,214 @@
+use clap::Args;
+use libcnb_data::buildpack::BuildpackId;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use toml_edit::{Document, TomlError};
+
+type Result<T> = std::result::Result<T, SharedPathsError>;
+
+pub const SHARED_PATHS_FILE: &str = ".shared-paths.toml";
+
+#[derive(Args, Debug)]
+pub struct SharedPathsArgs {
+    #[arg(long, default_value = SHARED_PATHS_FILE)]
+    pub shared_paths_file: PathBuf,
+}
+
+impl SharedPathsArgs {
+    pub fn read(&self, dir: &Path) -> Result<SharedPaths> {
+        read_shared_paths(dir.join(&self.shared_paths_file))
+    }
+}
+
+// directories outside of any buildpack (e.g.; `common/`) mapped to the buildpacks built from them,
+// declared under `[shared_paths]` with paths relative to the repository root
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct SharedPaths(BTreeMap<String, Vec<BuildpackId>>);
+
+impl SharedPaths {
+    pub fn paths_for(&self, buildpack_id: &BuildpackId) -> Vec<&str> {
+        self.0
+            .iter()
+            .filter(|(_, buildpack_ids)| buildpack_ids.contains(buildpack_id))
+            .map(|(path, _)| path.as_str())
+            .collect()
+    }
+
+    // the shared paths containing any of the changed files, which are relative to the same root
+    pub fn changed(&self, changed_files: &[PathBuf]) -> Vec<(&str, &[BuildpackId])> {
+        self.0
+            .iter()
+            .filter(|(path, _)| {
+                changed_files
+                    .iter()
+                    .any(|file| file.starts_with(path.as_str()))
+            })
+            .map(|(path, buildpack_ids)| (path.as_str(), buildpack_ids.as_slice()))
+            .collect()
+    }
+
+    pub fn buildpack_ids(&self) -> Vec<&BuildpackId> {
+        let mut buildpack_ids = self.0.values().flatten().collect::<Vec<_>>();
+        buildpack_ids.sort_by_key(|buildpack_id| buildpack_id.as_str());
+        buildpack_ids.dedup();
+        buildpack_ids
+    }
+}
+
+// the config is optional so a missing file is an empty mapping
+pub fn read_shared_paths(path: impl Into<PathBuf>) -> Result<SharedPaths> {
+    let path = path.into();
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(SharedPaths::default()),
+        Err(error) => Err(SharedPathsError::Reading(path.clone(), error))?,
+    };
+    let document =
+        Document::from_str(&contents).map_err(|e| SharedPathsError::Parsing(path.clone(), e))?;
+    parse_shared_paths(&document).map_err(|message| SharedPathsError::Invalid(path, message))
+}
+
+fn parse_shared_paths(document: &Document) -> std::result::Result<SharedPaths, String> {
+    let shared_paths = match document.get("shared_paths") {
+        Some(item) => item
+            .as_table_like()
+            .ok_or_else(|| "`shared_paths` must be a table".to_string())?,
+        None => return Ok(SharedPaths::default()),
+    };
+
+    shared_paths
+        .iter()
+        .map(|(path, item)| {
+            let buildpack_ids = item
+                .as_array()
+                .and_then(|values| {
+                    values
+                        .iter()
+                        .map(|value| value.as_str())
+                        .collect::<Option<Vec<_>>>()
+                })
+                .ok_or_else(|| {
+                    format!("`shared_paths.\"{path}\"` must be a list of buildpack ids")
+                })?
+                .into_iter()
+                .map(|buildpack_id| {
+                    buildpack_id.parse::<BuildpackId>().map_err(|_| {
+                        format!("`shared_paths.\"{path}\"` has an invalid buildpack id `{buildpack_id}`")
+                    })
+                })
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            let path = path.trim_end_matches('/');
+            if path.is_empty() || Path::new(path).is_absolute() {
+                Err(format!(
+                    "Shared path `{path}` must be relative to the repository root"
+                ))?;
+            }
+            Ok((path.to_string(), buildpack_ids))
+        })
+        .collect::<std::result::Result<_, _>>()
+        .map(SharedPaths)
+}
+
+#[derive(Debug)]
+pub enum SharedPathsError {
+    Reading(PathBuf, io::Error),
+    Parsing(PathBuf, TomlError),
+    Invalid(PathBuf, String),
+}
+
+impl Display for SharedPathsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SharedPathsError::Reading(path, error) => {
+                write!(
+                    f,
+                    "Could not read shared paths\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            SharedPathsError::Parsing(path, error) => {
+                write!(
+                    f,
+                    "Could not parse shared paths\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            SharedPathsError::Invalid(path, message) => {
+                write!(
+                    f,
+                    "Invalid shared paths\nPath: {}\nError: {message}",
+                    path.display()
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::shared_paths::parse_shared_paths;
+    use libcnb_data::buildpack_id;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+    use toml_edit::Document;
+
+    #[test]
+    fn test_parse_shared_paths() {
+        let shared_paths = parse_shared_paths(
+            &Document::from_str(
+                r#"
+[shared_paths]
+"common/" = ["heroku/nodejs-engine", "heroku/nodejs-npm"]
+"shared/scripts" = ["heroku/nodejs-npm"]
+"#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            shared_paths.paths_for(&buildpack_id!("heroku/nodejs-npm")),
+            vec!["common", "shared/scripts"]
+        );
+        assert_eq!(
+            shared_paths.changed(&[
+                PathBuf::from("common/lib.sh"),
+                PathBuf::from("shared/scripts-old/run.sh")
+            ]),
+            vec![(
+                "common",
+                [
+                    buildpack_id!("heroku/nodejs-engine"),
+                    buildpack_id!("heroku/nodejs-npm")
+                ]
+                .as_slice()
+            )]
+        );
+        assert_eq!(
+            shared_paths.buildpack_ids(),
+            vec![
+                &buildpack_id!("heroku/nodejs-engine"),
+                &buildpack_id!("heroku/nodejs-npm")
+            ]
+        );
+
+        assert!(
+            parse_shared_paths(&Document::from_str("[shared_paths]\ncommon = \"a\"").unwrap())
+                .is_err()
+        );
+        assert!(parse_shared_paths(
+            &Document::from_str("[shared_paths]\ncommon = [\"Not Valid\"]").unwrap()
+        )
+        .is_err());
+        assert!(parse_shared_paths(
+            &Document::from_str("[shared_paths]\n\"/\" = [\"a\"]").unwrap()
+        )
+        .is_err());
+    }
+}
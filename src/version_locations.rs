@@ -0,0 +1,353 @@
+use crate::buildpack::BuildpackFile;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
+use toml_edit::{Document, Formatted, Item, TableLike, Value};
+
+type Result<T> = std::result::Result<T, VersionLocationError>;
+
+#[derive(Debug)]
+pub enum VersionLocation {
+    Pattern { path: PathBuf, pattern: Regex },
+    Key { path: PathBuf, key: String },
+}
+
+impl VersionLocation {
+    pub fn path(&self) -> &Path {
+        match self {
+            VersionLocation::Pattern { path, .. } | VersionLocation::Key { path, .. } => path,
+        }
+    }
+}
+
+// rules are declared as `[[metadata.release.version_locations]]` in buildpack.toml with a `file` relative
+// to the buildpack directory and either a `pattern` (whose `version` or first capture group is replaced)
+// or a dotted TOML `key`
+pub fn get_version_locations(buildpack_file: &BuildpackFile) -> Result<Vec<VersionLocation>> {
+    let rules = match buildpack_file
+        .document
+        .get("metadata")
+        .and_then(|value| value.as_table_like())
+        .and_then(|metadata| metadata.get("release"))
+        .and_then(|value| value.as_table_like())
+        .and_then(|release| release.get("version_locations"))
+    {
+        Some(Item::ArrayOfTables(rules)) => rules,
+        Some(_) => {
+            return Err(VersionLocationError::InvalidRule(
+                buildpack_file.path.clone(),
+                0,
+                "`version_locations` must be an array of tables".to_string(),
+            ))
+        }
+        None => return Ok(vec![]),
+    };
+
+    let buildpack_dir = buildpack_file.path.parent().unwrap_or(Path::new(""));
+
+    rules
+        .iter()
+        .enumerate()
+        .map(|(index, rule)| {
+            let invalid_rule = |reason: &str| {
+                VersionLocationError::InvalidRule(
+                    buildpack_file.path.clone(),
+                    index,
+                    reason.to_string(),
+                )
+            };
+
+            let path = get_string(rule, "file")
+                .map(|file| normalize_path(&buildpack_dir.join(file)))
+                .ok_or_else(|| invalid_rule("`file` must be set to a string"))?;
+
+            match (get_string(rule, "pattern"), get_string(rule, "key")) {
+                (Some(pattern), None) => {
+                    let pattern = Regex::new(pattern).map_err(|e| {
+                        VersionLocationError::InvalidPattern(
+                            buildpack_file.path.clone(),
+                            pattern.to_string(),
+                            Box::new(e),
+                        )
+                    })?;
+                    if pattern.captures_len() < 2 {
+                        Err(invalid_rule(
+                            "`pattern` must contain a capture group for the version",
+                        ))?;
+                    }
+                    Ok(VersionLocation::Pattern { path, pattern })
+                }
+                (None, Some(key)) => Ok(VersionLocation::Key {
+                    path,
+                    key: key.to_string(),
+                }),
+                _ => Err(invalid_rule(
+                    "exactly one of `pattern` or `key` must be set to a string",
+                )),
+            }
+        })
+        .collect()
+}
+
+// files are read once and every rule targeting them is applied in order so nothing is written until
+// all locations have been updated successfully, only files whose contents changed are returned
+pub fn update_version_locations(
+    locations: &[VersionLocation],
+    version: &str,
+) -> Result<BTreeMap<PathBuf, String>> {
+    let mut files: BTreeMap<PathBuf, (String, String)> = BTreeMap::new();
+
+    for location in locations {
+        let path = location.path();
+        let (original, contents) = match files.remove(path) {
+            Some(file) => file,
+            None => {
+                let contents = std::fs::read_to_string(path)
+                    .map_err(|e| VersionLocationError::Reading(path.to_path_buf(), e))?;
+                (contents.clone(), contents)
+            }
+        };
+        let contents = update_version_location(location, &contents, version)?;
+        files.insert(path.to_path_buf(), (original, contents));
+    }
+
+    Ok(files
+        .into_iter()
+        .filter(|(_, (original, contents))| original != contents)
+        .map(|(path, (_, contents))| (path, contents))
+        .collect())
+}
+
+pub fn update_version_location(
+    location: &VersionLocation,
+    contents: &str,
+    version: &str,
+) -> Result<String> {
+    match location {
+        VersionLocation::Pattern { path, pattern } => {
+            let mut updated = String::new();
+            let mut last_end = 0;
+            let mut matched = false;
+            for captures in pattern.captures_iter(contents) {
+                if let Some(group) = captures.name("version").or_else(|| captures.get(1)) {
+                    updated.push_str(&contents[last_end..group.start()]);
+                    updated.push_str(version);
+                    last_end = group.end();
+                    matched = true;
+                }
+            }
+            if !matched {
+                Err(VersionLocationError::NoMatch(
+                    path.clone(),
+                    pattern.to_string(),
+                ))?;
+            }
+            updated.push_str(&contents[last_end..]);
+            Ok(updated)
+        }
+
+        VersionLocation::Key { path, key } => {
+            let mut document = Document::from_str(contents)
+                .map_err(|e| VersionLocationError::ParsingToml(path.clone(), e))?;
+
+            let mut item = document.as_item_mut();
+            for segment in key.split('.') {
+                match item
+                    .as_table_like_mut()
+                    .and_then(|table| table.get_mut(segment))
+                {
+                    Some(child) => item = child,
+                    None => return Err(VersionLocationError::NoMatch(path.clone(), key.clone())),
+                }
+            }
+
+            match item.as_value_mut() {
+                Some(Value::String(current_value)) => {
+                    let decor = current_value.decor().clone();
+                    let mut replacement = Formatted::new(version.to_string());
+                    *replacement.decor_mut() = decor;
+                    *current_value = replacement;
+                    Ok(document.to_string())
+                }
+                _ => Err(VersionLocationError::InvalidKey(path.clone(), key.clone())),
+            }
+        }
+    }
+}
+
+fn get_string<'a>(table: &'a dyn TableLike, key: &str) -> Option<&'a str> {
+    table.get(key).and_then(|value| value.as_str())
+}
+
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+#[derive(Debug)]
+pub enum VersionLocationError {
+    InvalidRule(PathBuf, usize, String),
+    InvalidPattern(PathBuf, String, Box<regex::Error>),
+    Reading(PathBuf, io::Error),
+    ParsingToml(PathBuf, toml_edit::TomlError),
+    NoMatch(PathBuf, String),
+    InvalidKey(PathBuf, String),
+}
+
+impl Display for VersionLocationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VersionLocationError::InvalidRule(path, index, reason) => {
+                write!(
+                    f,
+                    "Invalid version location rule #{index}: {reason}\nPath: {}",
+                    path.display()
+                )
+            }
+
+            VersionLocationError::InvalidPattern(path, pattern, error) => {
+                write!(
+                    f,
+                    "Invalid version location pattern `{pattern}`\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            VersionLocationError::Reading(path, error) => {
+                write!(
+                    f,
+                    "Could not read version location\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            VersionLocationError::ParsingToml(path, error) => {
+                write!(
+                    f,
+                    "Could not parse version location as TOML\nPath: {}\nError: {error}",
+                    path.display()
+                )
+            }
+
+            VersionLocationError::NoMatch(path, rule) => {
+                write!(
+                    f,
+                    "No version found matching `{rule}`\nPath: {}",
+                    path.display()
+                )
+            }
+
+            VersionLocationError::InvalidKey(path, key) => {
+                write!(
+                    f,
+                    "Expected `{key}` to be a string value\nPath: {}",
+                    path.display()
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::buildpack::BuildpackFile;
+    use crate::version_locations::{
+        get_version_locations, update_version_location, VersionLocation,
+    };
+    use std::path::PathBuf;
+    use std::str::FromStr;
+    use toml_edit::Document;
+
+    #[test]
+    fn test_get_version_locations() {
+        let buildpack_file = BuildpackFile {
+            path: PathBuf::from("/repo/buildpacks/nodejs/buildpack.toml"),
+            document: Document::from_str(
+                r#"[buildpack]
+id = "heroku/nodejs"
+version = "1.0.0"
+
+[[metadata.release.version_locations]]
+file = "../../.github/actions/setup/action.yml"
+pattern = 'heroku/nodejs@v(\d+\.\d+\.\d+)'
+
+[[metadata.release.version_locations]]
+file = "docs/constants.toml"
+key = "nodejs.version"
+"#,
+            )
+            .unwrap(),
+        };
+
+        let locations = get_version_locations(&buildpack_file).unwrap();
+        assert_eq!(
+            locations
+                .iter()
+                .map(VersionLocation::path)
+                .collect::<Vec<_>>(),
+            vec![
+                PathBuf::from("/repo/.github/actions/setup/action.yml"),
+                PathBuf::from("/repo/buildpacks/nodejs/docs/constants.toml")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_version_locations_without_capture_group() {
+        let buildpack_file = BuildpackFile {
+            path: PathBuf::from("/repo/buildpack.toml"),
+            document: Document::from_str(
+                "[[metadata.release.version_locations]]\nfile = \"README.md\"\npattern = 'v\\d+'\n",
+            )
+            .unwrap(),
+        };
+        assert!(get_version_locations(&buildpack_file).is_err());
+    }
+
+    #[test]
+    fn test_update_version_location_with_pattern() {
+        let location = VersionLocation::Pattern {
+            path: PathBuf::from("action.yml"),
+            pattern: regex::Regex::new(r"heroku/nodejs@v(?P<version>[\d.]+)").unwrap(),
+        };
+        assert_eq!(
+            update_version_location(
+                &location,
+                "uses: heroku/nodejs@v1.0.0\n# heroku/nodejs@v1.0.0\n",
+                "1.1.0"
+            )
+            .unwrap(),
+            "uses: heroku/nodejs@v1.1.0\n# heroku/nodejs@v1.1.0\n"
+        );
+        assert!(update_version_location(&location, "uses: heroku/go@v1.0.0\n", "1.1.0").is_err());
+    }
+
+    #[test]
+    fn test_update_version_location_with_key() {
+        let location = VersionLocation::Key {
+            path: PathBuf::from("constants.toml"),
+            key: "nodejs.version".to_string(),
+        };
+        assert_eq!(
+            update_version_location(
+                &location,
+                "[nodejs]\nversion = \"1.0.0\" # pinned\n",
+                "1.1.0"
+            )
+            .unwrap(),
+            "[nodejs]\nversion = \"1.1.0\" # pinned\n"
+        );
+    }
+}
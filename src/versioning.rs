@@ -0,0 +1,276 @@
+use chrono::{DateTime, Datelike, Utc};
+use clap::ValueEnum;
+use libcnb_data::buildpack::BuildpackVersion;
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+
+#[derive(ValueEnum, Debug, Clone)]
+pub enum BumpCoordinate {
+    Major,
+    Minor,
+    Patch,
+}
+
+#[derive(ValueEnum, Debug, Clone, Default)]
+pub enum Scheme {
+    #[default]
+    Semver,
+    Calver,
+    Monotonic,
+}
+
+impl Scheme {
+    pub fn version_scheme(&self) -> Box<dyn VersionScheme> {
+        match self {
+            Scheme::Semver => Box::new(SemverScheme),
+            Scheme::Calver => Box::new(CalverScheme),
+            Scheme::Monotonic => Box::new(MonotonicScheme),
+        }
+    }
+}
+
+// `BuildpackVersion` doesn't implement `Clone` in the libcnb-data version we depend on
+pub fn copy_version(version: &BuildpackVersion) -> BuildpackVersion {
+    BuildpackVersion {
+        major: version.major,
+        minor: version.minor,
+        patch: version.patch,
+    }
+}
+
+pub fn compare_versions(a: &BuildpackVersion, b: &BuildpackVersion) -> Ordering {
+    (a.major, a.minor, a.patch).cmp(&(b.major, b.minor, b.patch))
+}
+
+pub trait VersionScheme {
+    fn next_version(
+        &self,
+        current_version: &BuildpackVersion,
+        bump: &BumpCoordinate,
+        today: &DateTime<Utc>,
+    ) -> Result<BuildpackVersion, VersionSchemeError>;
+
+    fn format_version(&self, version: &BuildpackVersion) -> String {
+        version.to_string()
+    }
+}
+
+pub struct SemverScheme;
+
+impl VersionScheme for SemverScheme {
+    fn next_version(
+        &self,
+        current_version: &BuildpackVersion,
+        bump: &BumpCoordinate,
+        _today: &DateTime<Utc>,
+    ) -> Result<BuildpackVersion, VersionSchemeError> {
+        let BuildpackVersion {
+            major,
+            minor,
+            patch,
+        } = current_version;
+
+        Ok(match bump {
+            BumpCoordinate::Major => BuildpackVersion {
+                major: major + 1,
+                minor: 0,
+                patch: 0,
+            },
+            BumpCoordinate::Minor => BuildpackVersion {
+                major: *major,
+                minor: minor + 1,
+                patch: 0,
+            },
+            BumpCoordinate::Patch => BuildpackVersion {
+                major: *major,
+                minor: *minor,
+                patch: patch + 1,
+            },
+        })
+    }
+}
+
+pub struct CalverScheme;
+
+impl VersionScheme for CalverScheme {
+    fn next_version(
+        &self,
+        current_version: &BuildpackVersion,
+        _bump: &BumpCoordinate,
+        today: &DateTime<Utc>,
+    ) -> Result<BuildpackVersion, VersionSchemeError> {
+        let year = u64::try_from(today.year())
+            .map_err(|_| VersionSchemeError::InvalidDate(today.to_rfc3339()))?;
+        let month = u64::from(today.month());
+
+        let current = (current_version.major, current_version.minor);
+        if current > (year, month) {
+            return Err(VersionSchemeError::CalverAheadOfToday(copy_version(
+                current_version,
+            )));
+        }
+
+        Ok(if current == (year, month) {
+            BuildpackVersion {
+                major: year,
+                minor: month,
+                patch: current_version.patch + 1,
+            }
+        } else {
+            BuildpackVersion {
+                major: year,
+                minor: month,
+                patch: 0,
+            }
+        })
+    }
+}
+
+pub struct MonotonicScheme;
+
+impl VersionScheme for MonotonicScheme {
+    fn next_version(
+        &self,
+        current_version: &BuildpackVersion,
+        _bump: &BumpCoordinate,
+        _today: &DateTime<Utc>,
+    ) -> Result<BuildpackVersion, VersionSchemeError> {
+        if current_version.minor != 0 || current_version.patch != 0 {
+            return Err(VersionSchemeError::NotMonotonic(copy_version(
+                current_version,
+            )));
+        }
+
+        Ok(BuildpackVersion {
+            major: current_version.major + 1,
+            minor: 0,
+            patch: 0,
+        })
+    }
+
+    fn format_version(&self, version: &BuildpackVersion) -> String {
+        version.major.to_string()
+    }
+}
+
+#[derive(Debug)]
+pub enum VersionSchemeError {
+    InvalidDate(String),
+    CalverAheadOfToday(BuildpackVersion),
+    NotMonotonic(BuildpackVersion),
+}
+
+impl Display for VersionSchemeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VersionSchemeError::InvalidDate(date) => {
+                write!(f, "Invalid date for calendar version - {date}")
+            }
+            VersionSchemeError::CalverAheadOfToday(version) => {
+                write!(
+                    f,
+                    "Calendar version is ahead of the current date - {version}"
+                )
+            }
+            VersionSchemeError::NotMonotonic(version) => {
+                write!(
+                    f,
+                    "Monotonic versions must only use the major coordinate - {version}"
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::versioning::{
+        compare_versions, BumpCoordinate, CalverScheme, MonotonicScheme, SemverScheme,
+        VersionScheme,
+    };
+    use chrono::{TimeZone, Utc};
+    use libcnb_data::buildpack::BuildpackVersion;
+    use std::cmp::Ordering;
+
+    fn version(major: u64, minor: u64, patch: u64) -> BuildpackVersion {
+        BuildpackVersion {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    #[test]
+    fn test_semver_next_version() {
+        let today = Utc.with_ymd_and_hms(2023, 6, 16, 0, 0, 0).unwrap();
+        let current = version(1, 2, 3);
+        assert_eq!(
+            SemverScheme
+                .next_version(&current, &BumpCoordinate::Major, &today)
+                .unwrap(),
+            version(2, 0, 0)
+        );
+        assert_eq!(
+            SemverScheme
+                .next_version(&current, &BumpCoordinate::Minor, &today)
+                .unwrap(),
+            version(1, 3, 0)
+        );
+        assert_eq!(
+            SemverScheme
+                .next_version(&current, &BumpCoordinate::Patch, &today)
+                .unwrap(),
+            version(1, 2, 4)
+        );
+        assert_eq!(SemverScheme.format_version(&current), "1.2.3");
+    }
+
+    #[test]
+    fn test_calver_next_version() {
+        let today = Utc.with_ymd_and_hms(2023, 6, 16, 0, 0, 0).unwrap();
+        assert_eq!(
+            CalverScheme
+                .next_version(&version(2023, 6, 2), &BumpCoordinate::Patch, &today)
+                .unwrap(),
+            version(2023, 6, 3)
+        );
+        assert_eq!(
+            CalverScheme
+                .next_version(&version(2023, 5, 7), &BumpCoordinate::Patch, &today)
+                .unwrap(),
+            version(2023, 6, 0)
+        );
+        assert!(CalverScheme
+            .next_version(&version(2023, 7, 0), &BumpCoordinate::Patch, &today)
+            .is_err());
+    }
+
+    #[test]
+    fn test_monotonic_next_version() {
+        let today = Utc.with_ymd_and_hms(2023, 6, 16, 0, 0, 0).unwrap();
+        let next = MonotonicScheme
+            .next_version(&version(41, 0, 0), &BumpCoordinate::Patch, &today)
+            .unwrap();
+        assert_eq!(next, version(42, 0, 0));
+        assert_eq!(MonotonicScheme.format_version(&next), "42");
+        assert!(MonotonicScheme
+            .next_version(&version(1, 2, 0), &BumpCoordinate::Patch, &today)
+            .is_err());
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(
+            compare_versions(&version(0, 6, 10), &version(0, 6, 9)),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_versions(&version(0, 6, 9), &version(1, 0, 0)),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_versions(&version(1, 0, 0), &version(1, 0, 0)),
+            Ordering::Equal
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use crate::log;
+use crate::progress::{track, ProgressStep};
+use std::io;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+// writes a file a command produced, reported as a `write` step
+pub fn write_file(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let path = path.as_ref();
+    track(ProgressStep::Write, path.display(), || {
+        write_atomically(path, contents.as_ref())
+    })
+}
+
+// writes edits a command planned up front, so one that couldn't be made has already stopped the
+// command before anything was written, returns the written paths relative to `dir`
+pub fn write_planned_edits<E>(
+    edits: &[(PathBuf, String)],
+    dir: &Path,
+    writing_error: impl Fn(PathBuf, io::Error) -> E,
+) -> Result<Vec<String>, E> {
+    with_rollback(|written_files| {
+        let mut modified_files = vec![];
+        for (path, contents) in edits {
+            if written_files
+                .write_if_changed(path, contents)
+                .map_err(|e| writing_error(path.clone(), e))?
+            {
+                let relative_path = path.strip_prefix(dir).unwrap_or(path);
+                log!("✅️ Updated {}", relative_path.display());
+                modified_files.push(relative_path.display().to_string());
+            }
+        }
+        Ok(modified_files)
+    })
+}
+
+// runs the writes of a command that stops partway, because it was cancelled or a file couldn't be
+// written, and puts back the files it already wrote so the repository isn't left half updated
+pub fn with_rollback<T, E>(write: impl FnOnce(&mut WrittenFiles) -> Result<T, E>) -> Result<T, E> {
+    let mut written_files = WrittenFiles::default();
+    let result = write(&mut written_files);
+    if result.is_err() {
+        written_files.restore();
+    }
+    result
+}
+
+// the files a command wrote along with what they contained before, `None` for files it created
+#[derive(Default)]
+pub struct WrittenFiles(Vec<(PathBuf, Option<String>)>);
+
+impl WrittenFiles {
+    pub fn write_if_changed(&mut self, path: &Path, contents: &str) -> io::Result<bool> {
+        let previous_contents = match std::fs::read_to_string(path) {
+            Ok(existing) if existing == contents => return Ok(false),
+            Ok(existing) => Some(existing),
+            Err(error) if error.kind() == ErrorKind::NotFound => None,
+            Err(error) => return Err(error),
+        };
+        write_file(path, contents)?;
+        self.0.push((path.to_path_buf(), previous_contents));
+        Ok(true)
+    }
+
+    fn restore(self) {
+        for (path, previous_contents) in self.0.into_iter().rev() {
+            let result = match previous_contents {
+                Some(contents) => write_file(&path, contents),
+                None => std::fs::remove_file(&path),
+            };
+            match result {
+                Ok(()) => log!(
+                    path = path;
+                    "⚠️ Restored {} since the command stopped before writing every file",
+                    path.display()
+                ),
+                Err(error) => log!(
+                    "⚠️ Could not restore {} after the command stopped before writing every file\nError: {error}",
+                    path.display()
+                ),
+            }
+        }
+    }
+}
+
+// the contents go to a temporary file next to the target that's then renamed over it, so a process
+// killed partway through leaves the previous file instead of a truncated one, symlinks are written
+// through and an existing file keeps its permissions
+fn write_atomically(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Path has no file name"))?;
+    let temp_path = path.with_file_name(format!(".{file_name}.{}.tmp", std::process::id()));
+
+    let result = std::fs::write(&temp_path, contents)
+        .and_then(|_| match std::fs::metadata(&path) {
+            Ok(metadata) => std::fs::set_permissions(&temp_path, metadata.permissions()),
+            Err(_) => Ok(()),
+        })
+        .and_then(|_| std::fs::rename(&temp_path, &path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use crate::write::{with_rollback, write_atomically, WrittenFiles};
+    use std::io;
+
+    #[test]
+    fn test_write_atomically() {
+        let dir = std::env::temp_dir().join(format!("write-atomically-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("CHANGELOG.md");
+
+        write_atomically(&path, b"# Changelog\n").unwrap();
+        write_atomically(&path, b"# Changelog\n\n## [Unreleased]\n").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "# Changelog\n\n## [Unreleased]\n"
+        );
+        assert!(write_atomically(&dir.join("missing").join("CHANGELOG.md"), b"").is_err());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_if_changed() {
+        let path = std::env::temp_dir().join(format!("write-if-changed-{}.md", std::process::id()));
+        let mut written_files = WrittenFiles::default();
+
+        assert!(written_files
+            .write_if_changed(&path, "# Changelog\n")
+            .unwrap());
+        assert!(!written_files
+            .write_if_changed(&path, "# Changelog\n")
+            .unwrap());
+        assert!(written_files
+            .write_if_changed(&path, "# Changelog\n\n## [Unreleased]\n")
+            .unwrap());
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "# Changelog\n\n## [Unreleased]\n"
+        );
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_with_rollback_restores_written_files() {
+        let dir = std::env::temp_dir().join(format!("with-rollback-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let existing = dir.join("builder.toml");
+        let created = dir.join("CHANGELOG.md");
+        std::fs::write(&existing, "[[order]]\n").unwrap();
+
+        let result = with_rollback(|written_files| {
+            written_files.write_if_changed(&existing, "[[order]]\n[[order.group]]\n")?;
+            written_files.write_if_changed(&created, "# Changelog\n")?;
+            Err::<(), _>(io::Error::new(io::ErrorKind::Other, "disk full"))
+        });
+        assert!(result.is_err());
+        assert_eq!(std::fs::read_to_string(&existing).unwrap(), "[[order]]\n");
+        assert!(!created.exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}